@@ -1,11 +1,11 @@
 use crate::api::{AnthropicClient, ApiError, ContentBlock, ImageSource, Message, StreamEvent, ToolResultContent};
 use crate::storage::{self, Conversation};
 use crate::bash::BashExecutor;
-use crate::browser::{BrowserClient, SharedBrowserClient};
-use crate::computer::{ComputerAction, ComputerControl, ComputerError};
+use crate::browser::{BrowserClient, BrowserError, DownloadOutcome, ElementAction, SharedBrowserClient};
+use crate::computer::{ActionHistory, ComputerAction, ComputerControl, ComputerError};
 use crate::voice::{create_tts_client, TtsClient};
 use crate::cognitive::CognitiveEngine;
-use crate::cognitive::agent_swarm::{AgentSwarm, SwarmEvent};
+use crate::cognitive::agent_swarm::{AgentSwarm, SubTaskStatus, SwarmEvent};
 use crate::cognitive::skill_executor::SkillExecutor;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -13,6 +13,7 @@ use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use thiserror::Error;
 use tokio::sync::{Mutex, mpsc};
+use tracing::{debug, error, info, warn};
 
 
 #[derive(Error, Debug)]
@@ -22,7 +23,7 @@ pub enum AgentError {
     #[error("Computer error: {0}")]
     Computer(#[from] ComputerError),
     #[error("Browser error: {0}")]
-    Browser(#[from] anyhow::Error),
+    Browser(#[from] BrowserError),
     #[error("No API key set")]
     NoApiKey,
 }
@@ -44,6 +45,7 @@ impl Default for AgentMode {
 enum BrowserToolResult {
     Image(String),
     Text(String),
+    Download(crate::files::CreatedFile),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,7 +77,10 @@ pub struct HistoryMessage {
 pub struct Agent {
     api_key: Option<String>,
     running: Arc<AtomicBool>,
+    // soft stop: let the tool that's executing finish, then end the run with every call answered
+    soft_stop: Arc<AtomicBool>,
     computer: Mutex<Option<ComputerControl>>,
+    action_history: Mutex<ActionHistory>,
     bash: Mutex<BashExecutor>,
     browser_client: SharedBrowserClient,
     pub cognitive: Arc<Mutex<CognitiveEngine>>,
@@ -87,7 +92,9 @@ impl Agent {
         Self {
             api_key: None,
             running,
+            soft_stop: Arc::new(AtomicBool::new(false)),
             computer: Mutex::new(None),
+            action_history: Mutex::new(ActionHistory::default()),
             bash: Mutex::new(BashExecutor::new()),
             browser_client: crate::browser::create_shared_browser_client(),
             cognitive: Arc::new(Mutex::new(CognitiveEngine::new())),
@@ -122,12 +129,17 @@ impl Agent {
         self.api_key = Some(key);
     }
 
-    pub fn has_api_key(&self) -> bool {
-        self.api_key.is_some()
+    /// the browser connection, shared so callers can use it without the agent lock
+    pub fn browser_client(&self) -> SharedBrowserClient {
+        self.browser_client.clone()
+    }
+
+    pub fn soft_stop_flag(&self) -> Arc<AtomicBool> {
+        self.soft_stop.clone()
     }
 
-    pub fn is_running(&self) -> bool {
-        self.running.load(Ordering::SeqCst)
+    pub fn has_api_key(&self) -> bool {
+        self.api_key.is_some()
     }
 
     pub async fn run(
@@ -138,16 +150,89 @@ impl Agent {
         voice_mode: bool,
         history: Vec<HistoryMessage>,
         context_screenshot: Option<String>,
+        context_snapshot: Option<String>,
         conversation_id: Option<String>,
+        dry_run: bool,
+        chat_only: bool,
         app_handle: AppHandle,
     ) -> Result<(), AgentError> {
         let run_start = std::time::Instant::now();
-        println!("[agent] run() starting with: {} (model: {}, mode: {:?}, history: {} msgs, screenshot: {}, conv: {:?})",
-            instructions, model, mode, history.len(), context_screenshot.is_some(), conversation_id);
+        let chosen_model = model.clone();
+        // the conversation the run is working on, so an error exit can still save it
+        let mut live_conversation: Option<Conversation> = None;
+        let result = self
+            .run_conversation(
+                instructions, model, mode, voice_mode, history, context_screenshot, context_snapshot,
+                conversation_id, dry_run, chat_only, app_handle, run_start, &mut live_conversation,
+            )
+            .await;
+
+        // the loop only saves every few rounds, so an error mid-run saves what it got to here.
+        // the run is over either way, so it isn't offered for resume
+        if let (Err(e), Some(conversation)) = (&result, live_conversation.as_mut()) {
+            error!(conversation_id = %conversation.id, "run failed: {}", e);
+            conversation.switch_model(&chosen_model);
+            conversation.run_in_progress = false;
+            conversation.record_run(run_start.elapsed());
+            if let Err(e) = storage::save_conversation(conversation) {
+                error!(conversation_id = %conversation.id, "failed to save conversation after error: {}", e);
+            }
+        }
+        // an ephemeral browser session ends with the run however it ended, taking its cookies
+        // and logins along
+        crate::browser::end_ephemeral_session(&self.browser_client).await;
+        result
+    }
+
+    async fn run_conversation(
+        &self,
+        instructions: String,
+        model: String,
+        mode: AgentMode,
+        voice_mode: bool,
+        history: Vec<HistoryMessage>,
+        context_screenshot: Option<String>,
+        context_snapshot: Option<String>,
+        conversation_id: Option<String>,
+        dry_run: bool,
+        chat_only: bool,
+        app_handle: AppHandle,
+        run_start: std::time::Instant,
+        live_conversation: &mut Option<Conversation>,
+    ) -> Result<(), AgentError> {
+        // the prompt itself only goes to the debug level - the info log is kept for days
+        info!(
+            conversation_id = ?conversation_id, model = %model, mode = ?mode, history = history.len(),
+            screenshot = context_screenshot.is_some(), dry_run, chat_only, prompt_chars = instructions.chars().count(),
+            "agent run starting"
+        );
+        debug!(conversation_id = ?conversation_id, "prompt: {}", instructions);
 
         let api_key = self.api_key.clone().ok_or(AgentError::NoApiKey)?;
         println!("[agent] API key present");
 
+        // undo only ever reverses this run's actions, not whatever an earlier run left behind
+        self.action_history.lock().await.clear();
+
+        // no instruction on an existing conversation = pick an interrupted run up where it stopped
+        let resuming = instructions.trim().is_empty() && conversation_id.is_some();
+
+        // dry run: propose a plan and stop before any skill, tool, computer, bash or browser use
+        if dry_run {
+            return self.preview_plan(&instructions, &model, mode, &api_key, &app_handle).await;
+        }
+
+        // quick answer: one tool-less streaming turn, no computer or browser init.
+        // a context screenshot means the question is about the screen, so it always gets the agent
+        let wants_quick_answer = !resuming && (chat_only || (!voice_mode && is_conversational(&instructions)));
+        if wants_quick_answer && context_screenshot.is_none() && context_snapshot.is_none() {
+            if self.quick_answer(&instructions, &model, mode, &history, conversation_id.as_deref(), &api_key, &app_handle).await {
+                info!(conversation_id = ?conversation_id, elapsed = ?run_start.elapsed(), "quick answer finished");
+                return Ok(());
+            }
+            println!("[agent] Quick answer not possible, continuing with the full agent");
+        }
+
         // Check permissions before starting
         #[cfg(target_os = "macos")]
         {
@@ -183,7 +268,7 @@ impl Agent {
         }
 
         // STEP 1: Try to execute a matching skill for simple tasks
-        if is_simple_quick_task(&instructions) {
+        if !resuming && is_simple_quick_task(&instructions) {
             println!("[agent] Simple task detected, trying skill execution...");
             
             let skill_result = {
@@ -195,15 +280,17 @@ impl Agent {
                 println!("[agent] ✓ Skill '{}' executed successfully", skill.name);
                 self.emit(&app_handle, "status", &format!("✓ Used skill: {}", skill.name), None, None);
                 
-                // Emit skill execution result
+                // Emit skill execution result - the chat offers thumbs up/down on it (rate_skill_execution)
                 let _ = app_handle.emit("agent-update", AgentUpdate {
-                    update_type: if result.success { "success" } else { "error" }.to_string(),
+                    update_type: "skill".to_string(),
                     message: result.output.clone(),
                     tool_name: Some(skill.name.clone()),
                     tool_input: None,
                     action: Some(serde_json::json!({
+                        "skill_id": skill.id,
                         "skill": skill.name,
-                        "description": skill.description
+                        "description": skill.description,
+                        "success": result.success
                     })),
                     screenshot: result.screenshot.clone(),
                     bash_command: None,
@@ -226,13 +313,14 @@ impl Agent {
         self.running.store(true, Ordering::SeqCst);
 
         // STEP 2: For complex tasks, use cognitive analysis
-        let is_complex = is_complex_task(&instructions);
+        let is_complex = !resuming && is_complex_task(&instructions);
         
         if is_complex {
             // Use cognitive engine to analyze the request
             let cognitive_start = std::time::Instant::now();
             let cognitive_analysis = {
                 let mut cognitive = self.cognitive.lock().await;
+                cognitive.correction.set_confirmer(app_handle.clone(), self.running.clone());
                 cognitive.process_request(&instructions).await
             };
             match &cognitive_analysis {
@@ -250,7 +338,7 @@ impl Agent {
             if !swarm_initialized {
                 if let Some(api_key) = &self.api_key {
                     println!("[agent] Auto-initializing Agent Swarm for complex task");
-                    self.init_agent_swarm(api_key.clone(), "claude-opus-4-6".to_string(), app_handle.clone()).await;
+                    self.init_agent_swarm(api_key.clone(), model.clone(), app_handle.clone()).await;
                 }
             }
             
@@ -258,6 +346,18 @@ impl Agent {
             if let Some(ref swarm) = *swarm_guard {
                 println!("[agent] Complex task detected, delegating to Agent Swarm");
                 self.emit(&app_handle, "status", "🤖 Agent Swarm activated for complex task", None, None);
+
+                // the tool audit is keyed by conversation, so a swarm run in a new chat needs one too
+                let mode_str = match mode {
+                    AgentMode::Computer => "computer",
+                    AgentMode::Browser => "browser",
+                };
+                let conversation = live_conversation.insert(load_or_create_conversation(conversation_id.as_deref(), &model, mode_str));
+                let _ = app_handle.emit("agent:conversation_id", &conversation.id);
+                conversation.add_message(Message {
+                    role: "user".to_string(),
+                    content: vec![ContentBlock::Text { text: instructions.clone() }],
+                });
                 
                 let task_id = swarm.submit_task(instructions.clone()).await;
                 println!("[agent] Submitted to swarm as task {}", task_id);
@@ -270,47 +370,120 @@ impl Agent {
                 
                 // IMPORTANT: Wait for swarm to finish, then return.
                 // Do NOT fall through to normal agent loop - that causes double execution.
+                let updates = swarm.updates();
                 drop(swarm_guard);
                 
-                // Poll swarm task status until complete
+                // Wait on swarm status changes until complete. The swarm lock is only taken when the
+                // swarm signals a change or the fallback poll comes due; stop and timeout are
+                // checked on a short tick without it
                 let swarm_timeout = std::time::Duration::from_secs(300); // 5 min max
                 let swarm_start = std::time::Instant::now();
+                let mut last_progress: Option<crate::cognitive::agent_swarm::TaskProgress> = None;
+                let mut last_progress_emit = std::time::Instant::now();
+                let mut subtask_statuses: std::collections::HashMap<String, SubTaskStatus> = std::collections::HashMap::new();
+                let mut changed = true;
+                let mut next_poll = std::time::Instant::now();
                 loop {
+                    // registered before the task is read so a change in between isn't missed
+                    let notified = updates.notified();
+                    tokio::pin!(notified);
+                    notified.as_mut().enable();
+
                     if !self.running.load(Ordering::SeqCst) {
+                        crate::cognitive::agent_swarm::cancel_swarm_task(&task_id);
                         self.emit(&app_handle, "status", "Agent stopped", None, None);
                         break;
                     }
                     if swarm_start.elapsed() > swarm_timeout {
+                        crate::cognitive::agent_swarm::cancel_swarm_task(&task_id);
                         self.emit(&app_handle, "error", "Swarm task timed out after 5 minutes", None, None);
                         break;
                     }
                     
-                    let guard = self.agent_swarm.lock().await;
-                    if let Some(ref swarm) = *guard {
-                        if let Some(details) = swarm.get_task_details(&task_id).await {
-                            let status = format!("{:?}", details.status);
-                            if status.contains("Completed") || status.contains("Failed") {
-                                // Gather results
-                                let mut result_text = String::new();
+                    if changed || std::time::Instant::now() >= next_poll {
+                        let guard = self.agent_swarm.lock().await;
+                        if let Some(ref swarm) = *guard {
+                            if let Some(details) = swarm.get_task_details(&task_id).await {
+                                // surface retries and verification as they happen
                                 for st in &details.subtasks {
-                                    if let Some(ref r) = st.result {
-                                        if !r.output.is_empty() {
-                                            result_text.push_str(&format!("**{}**: {}\n\n", st.description, r.output));
-                                        }
+                                    let previous = subtask_statuses.insert(st.id.clone(), st.status);
+                                    if previous != Some(st.status)
+                                        && matches!(st.status, SubTaskStatus::NeedsRetry | SubTaskStatus::Verifying)
+                                    {
+                                        let _ = app_handle.emit("swarm:subtask_status", serde_json::json!({
+                                            "task_id": task_id,
+                                            "subtask_id": st.id,
+                                            "description": st.description,
+                                            "status": st.status,
+                                            "retry_count": st.retry_count,
+                                        }));
                                     }
                                 }
-                                if result_text.is_empty() {
-                                    result_text = format!("Swarm task {} completed.", task_id);
+
+                                // emit on change, and every couple of seconds so the ETA keeps counting down
+                                let progress = details.progress(chrono::Utc::now());
+                                let counts_changed = last_progress.as_ref().map_or(true, |p| {
+                                    (p.completed, p.failed, p.total) != (progress.completed, progress.failed, progress.total)
+                                });
+                                if counts_changed || last_progress_emit.elapsed() >= std::time::Duration::from_millis(SWARM_PROGRESS_INTERVAL_MS) {
+                                    let _ = app_handle.emit("swarm:progress", serde_json::json!({
+                                        "task_id": task_id,
+                                        "completed": progress.completed,
+                                        "failed": progress.failed,
+                                        "total": progress.total,
+                                        "eta_ms": progress.eta_ms,
+                                    }));
+                                    last_progress_emit = std::time::Instant::now();
+                                    last_progress = Some(progress);
+                                }
+
+                                if details.metadata.contains_key("cancelled") {
+                                    self.emit(&app_handle, "status", "🛑 Swarm task cancelled", None, None);
+                                    break;
+                                }
+
+                                let status = format!("{:?}", details.status);
+                                if status.contains("Completed") || status.contains("Failed") {
+                                    // Gather results
+                                    if let Err(e) = storage::record_tool_calls(&conversation.id, &swarm_audit_entries(&details.subtasks)) {
+                                        warn!(conversation_id = %conversation.id, task_id = %task_id, "failed to write tool audit: {}", e);
+                                    }
+                                    let mut result_text = String::new();
+                                    for st in &details.subtasks {
+                                        if let Some(ref r) = st.result {
+                                            if !r.output.is_empty() {
+                                                result_text.push_str(&format!("**{}**: {}\n\n", st.description, r.output));
+                                            }
+                                        }
+                                    }
+                                    if result_text.is_empty() {
+                                        result_text = format!("Swarm task {} completed.", task_id);
+                                    }
+                                    self.emit(&app_handle, "response", &result_text, None, None);
+                                    conversation.add_message(Message {
+                                        role: "assistant".to_string(),
+                                        content: vec![ContentBlock::Text { text: result_text }],
+                                    });
+                                    break;
                                 }
-                                self.emit(&app_handle, "response", &result_text, None, None);
-                                break;
                             }
                         }
+                        drop(guard);
+                        next_poll = std::time::Instant::now() + swarm_fallback_poll();
                     }
-                    drop(guard);
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+                    changed = tokio::select! {
+                        _ = &mut notified => true,
+                        _ = tokio::time::sleep(std::time::Duration::from_millis(SWARM_STOP_CHECK_MS)) => false,
+                    };
                 }
                 
+                conversation.auto_title();
+                conversation.record_run(run_start.elapsed());
+                if let Err(e) = storage::save_conversation(conversation) {
+                    error!(conversation_id = %conversation.id, "failed to save conversation: {}", e);
+                }
+
                 self.running.store(false, Ordering::SeqCst);
                 self.emit(&app_handle, "finished", "Task completed", None, None);
                 let _ = app_handle.emit("agent:stopped", ());
@@ -344,34 +517,34 @@ impl Agent {
         if mode == AgentMode::Browser {
             let mut browser_guard = self.browser_client.lock().await;
             if browser_guard.is_none() {
-                println!("[agent] Connecting to browser...");
-                match BrowserClient::connect().await {
+                let kind = crate::browser::selected_browser_kind();
+                println!("[agent] Connecting to {}...", kind);
+                match BrowserClient::connect_with(kind).await {
                     Ok(client) => {
                         println!("[agent] Browser connected");
                         *browser_guard = Some(client);
                     }
                     Err(e) => {
-                        let err_str = e.to_string();
-                        if err_str.contains("CHROME_NEEDS_RESTART") {
-                            // emit event to ask user if they want to restart chrome
-                            println!("[agent] Chrome needs restart, asking user...");
-                            let _ = app_handle.emit("browser:needs-restart", ());
+                        if matches!(e, BrowserError::NeedsRestart(_)) {
+                            // emit event to ask user if they want to restart the browser
+                            println!("[agent] {} needs restart, asking user...", kind);
+                            let _ = app_handle.emit("browser:needs-restart", kind);
 
                             // wait for user response via a oneshot channel
                             // for now, just try to restart automatically
-                            match crate::browser::restart_chrome_with_debugging().await {
+                            match crate::browser::restart_browser_with_debugging(kind).await {
                                 Ok(client) => {
-                                    println!("[agent] Chrome restarted and connected");
+                                    println!("[agent] {} restarted and connected", kind);
                                     *browser_guard = Some(client);
                                 }
                                 Err(restart_err) => {
-                                    println!("[agent] Chrome restart failed: {}", restart_err);
-                                    let chrome_msg = if cfg!(target_os = "macos") {
-                                        "Chrome restart failed. Please manually quit Chrome and restart with: open -a 'Google Chrome' --args --remote-debugging-port=9222"
+                                    println!("[agent] {} restart failed: {}", kind, restart_err);
+                                    let browser_msg = if cfg!(target_os = "macos") {
+                                        format!("{kind} restart failed. Please manually quit {kind} and restart with: open -a '{kind}' --args --remote-debugging-port=9222")
                                     } else {
-                                        "Chrome restart failed. Please close all Chrome windows and restart Chrome with the --remote-debugging-port=9222 flag."
+                                        format!("{kind} restart failed. Please close all {kind} windows and restart {kind} with the --remote-debugging-port=9222 flag.")
                                     };
-                                    self.emit(&app_handle, "error", chrome_msg, None, None);
+                                    self.emit(&app_handle, "error", &browser_msg, None, None);
                                     self.running.store(false, Ordering::SeqCst);
                                     return Err(AgentError::Browser(restart_err));
                                 }
@@ -393,48 +566,34 @@ impl Agent {
             }
         }
 
-        let client = AnthropicClient::new(api_key.clone(), model.clone());
+        let mut client = AnthropicClient::new(api_key.clone(), model.clone());
+        // model actually answering - changes if we fall back after repeated failures
+        let mut active_model = model.clone();
+        // each fallback is tried at most once per run, so a full outage can't loop forever
+        let mut fallbacks: std::collections::VecDeque<String> = crate::api::fallback_models()
+            .into_iter()
+            .filter(|m| *m != model)
+            .collect();
         let mut messages: Vec<Message> = Vec::new();
+        let context_config = crate::context::ContextConfig::from_env();
 
         // load existing conversation or create new one
         let mode_str = match mode {
             AgentMode::Computer => "computer",
             AgentMode::Browser => "browser",
         };
-        let mut conversation = if let Some(ref conv_id) = conversation_id {
-            // try to load existing conversation
-            match storage::load_conversation(conv_id) {
-                Ok(Some(conv)) => {
-                    println!("[agent] Loaded existing conversation: {}", conv_id);
-                    conv
-                }
-                Ok(None) => {
-                    println!("[agent] Conversation {} not found, creating new", conv_id);
-                    Conversation::new(
-                        uuid::Uuid::new_v4().to_string(),
-                        "New Conversation".to_string(),
-                        model.clone(),
-                        mode_str.to_string(),
-                    )
-                }
-                Err(e) => {
-                    println!("[agent] Failed to load conversation {}: {}, creating new", conv_id, e);
-                    Conversation::new(
-                        uuid::Uuid::new_v4().to_string(),
-                        "New Conversation".to_string(),
-                        model.clone(),
-                        mode_str.to_string(),
-                    )
-                }
-            }
-        } else {
-            Conversation::new(
-                uuid::Uuid::new_v4().to_string(),
-                "New Conversation".to_string(),
-                model.clone(),
-                mode_str.to_string(),
-            )
-        };
+        let conversation = live_conversation.insert(load_or_create_conversation(conversation_id.as_deref(), &model, mode_str));
+        if resuming && !conversation.messages.last().is_some_and(|msg| msg.role == "user") {
+            self.running.store(false, Ordering::SeqCst);
+            self.emit(&app_handle, "error", "This conversation has nothing left to resume", None, None);
+            return Ok(());
+        }
+        // the user may pick a different model for the next step of an existing thread
+        let model_switched = conversation.switch_model(&model);
+        if model_switched {
+            let change = conversation.model_changes.last().unwrap();
+            println!("[agent] Conversation switched from {} to {} at message {}", change.from, change.to, change.message_index);
+        }
 
         // effective voice_mode: use frontend value OR persisted conversation value
         let effective_voice_mode = voice_mode || conversation.voice_mode;
@@ -443,6 +602,18 @@ impl Agent {
             conversation.voice_mode = effective_voice_mode;
         }
 
+        // wall-clock bound on the run, on top of the iteration cap
+        let time_limit = conversation.run_time_limit(resuming, storage::max_run_minutes());
+        let deadline = time_limit.map(|limit| run_start + limit);
+        let past_deadline = || deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline);
+        // set once the limit passes; like a soft stop, the running tool finishes first
+        let mut out_of_time = false;
+        // set when screen recording stayed off through the pause; the run ends like a soft stop
+        let mut screen_lost = false;
+
+        // pick the shell up where this conversation left it (fresh state for a new one)
+        self.bash.lock().await.restore(conversation.bash_state.clone());
+
         // emit conversation id and voice_mode to frontend
         let _ = app_handle.emit("agent:conversation_id", &conversation.id);
         let _ = app_handle.emit("agent:voice_mode", effective_voice_mode);
@@ -463,8 +634,14 @@ impl Agent {
             None
         };
 
+        // what the speak tool last said, so auto-speaking the final answer doesn't repeat it
+        let mut last_spoken: Option<String> = None;
+
         // Retrieve relevant memories to enhance context
-        let memory_context = {
+        // ids are kept so the task outcome can feed back into their success rates
+        let (memory_context, used_memory_ids) = if resuming {
+            (None, vec![])
+        } else {
             let cognitive = self.cognitive.lock().await;
             match cognitive.memory.search_relevant(&instructions).await {
                 Ok(memories) => {
@@ -474,17 +651,19 @@ impl Agent {
                             .collect::<Vec<_>>()
                             .join("\n");
                         println!("[agent] Retrieved {} relevant memories", memories.len());
-                        Some(context)
+                        (Some(context), memories.into_iter().map(|m| m.id).collect::<Vec<_>>())
                     } else {
-                        None
+                        (None, vec![])
                     }
                 }
                 Err(e) => {
                     println!("[agent] Memory search failed: {}", e);
-                    None
+                    (None, vec![])
                 }
             }
         };
+        // Some(true) when the model finishes on its own, Some(false) on a fatal error
+        let mut task_outcome: Option<bool> = None;
 
         // emit started to all windows with mode
         self.emit_full(&app_handle, "started", "Agent started", None, None, None, Some(mode_str.to_string()));
@@ -496,18 +675,20 @@ impl Agent {
         // small delay to ensure spotlight window event listeners are ready
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
-        // emit user message so all windows can display it
-        let _ = app_handle.emit("agent-update", AgentUpdate {
-            update_type: "user_message".to_string(),
-            message: instructions.clone(),
-            tool_name: None,
-            tool_input: None,
-            action: None,
-            screenshot: context_screenshot.clone(),
-            bash_command: None,
-            exit_code: None,
-            mode: None,
-        });
+        // emit user message so all windows can display it (a resumed run has no new one)
+        if !resuming {
+            let _ = app_handle.emit("agent-update", AgentUpdate {
+                update_type: "user_message".to_string(),
+                message: instructions.clone(),
+                tool_name: None,
+                tool_input: None,
+                action: None,
+                screenshot: context_screenshot.clone(),
+                bash_command: None,
+                exit_code: None,
+                mode: None,
+            });
+        }
         println!("[agent] Emitted started + user_message events");
 
         // load history: prefer DB conversation (has full tool_use/tool_result),
@@ -516,6 +697,14 @@ impl Agent {
             // resuming existing conversation - use DB messages which include tool blocks
             println!("[agent] Using {} messages from DB conversation", conversation.messages.len());
             messages = conversation.messages.clone();
+            // thinking signatures belong to the model that wrote them; earlier turns' thinking
+            // is optional, so drop it rather than hand it to a different model. tool schemas
+            // are built per request and identical for every model. a fallback that answered
+            // the end of the last run counts as a different model too
+            let last_writer = conversation.model_at(conversation.messages.len() - 1);
+            if model_switched || last_writer != model {
+                strip_thinking(&mut messages);
+            }
         } else {
             // new conversation - use frontend history (lossy but ok for first message)
             for msg in history {
@@ -526,57 +715,96 @@ impl Agent {
             }
         }
 
-        // build user message content - include screenshot if provided (computer mode only)
-        let mut user_content: Vec<ContentBlock> = Vec::new();
-
-        // add context screenshot first if provided (from hotkey help mode)
-        // skip in browser mode - a11y tree provides structure, screenshots are redundant
-        if let Some(screenshot_data) = context_screenshot {
-            if mode == AgentMode::Computer {
-                user_content.push(ContentBlock::Image {
-                    source: ImageSource {
-                        source_type: "base64".to_string(),
-                        media_type: "image/jpeg".to_string(),
-                        data: screenshot_data,
-                    },
+        // a resumed run answers the pending user turn (tool results or instruction) as it stands
+        if !resuming {
+            // build user message content - include screenshot if provided (computer mode only)
+            let mut user_content: Vec<ContentBlock> = Vec::new();
+
+            // add context screenshot first if provided (from hotkey help mode)
+            // in browser mode only the help hotkey's CDP page capture is attached (it comes with a
+            // snapshot) - a desktop screenshot is redundant next to the a11y tree
+            if let Some(screenshot_data) = context_screenshot {
+                if mode == AgentMode::Computer || context_snapshot.is_some() {
+                    user_content.push(ContentBlock::Image {
+                        source: ImageSource {
+                            source_type: "base64".to_string(),
+                            media_type: crate::computer::media_type_of(&screenshot_data).to_string(),
+                            data: screenshot_data,
+                        },
+                    });
+                }
+            }
+
+            if let Some(snapshot) = context_snapshot.filter(|_| mode == AgentMode::Browser) {
+                user_content.push(ContentBlock::Text {
+                    text: format!("<page_snapshot>\n{}\n</page_snapshot>", snapshot),
+                });
+            }
+
+            // Add memory context if available
+            if let Some(memories) = memory_context {
+                user_content.push(ContentBlock::Text {
+                    text: format!("<relevant_memories>\n{}\n</relevant_memories>", memories),
                 });
             }
-        }
 
-        // Add memory context if available
-        if let Some(memories) = memory_context {
+            // add text instructions - wrap in voice_input tags if voice mode
+            let text_content = if effective_voice_mode {
+                format!("<voice_input>{}</voice_input>", instructions)
+            } else {
+                instructions.clone()
+            };
             user_content.push(ContentBlock::Text {
-                text: format!("<relevant_memories>\n{}\n</relevant_memories>", memories),
+                text: text_content,
             });
-        }
 
-        // add text instructions - wrap in voice_input tags if voice mode
-        let text_content = if effective_voice_mode {
-            format!("<voice_input>{}</voice_input>", instructions)
-        } else {
-            instructions.clone()
-        };
-        user_content.push(ContentBlock::Text {
-            text: text_content,
-        });
+            let user_message = Message {
+                role: "user".to_string(),
+                content: user_content,
+            };
+            messages.push(user_message.clone());
+            conversation.add_message(user_message);
+        }
 
-        let user_message = Message {
-            role: "user".to_string(),
-            content: user_content,
-        };
-        messages.push(user_message.clone());
-        conversation.add_message(user_message);
+        // marks the run as live so a crash before the final save is offered for resume on relaunch
+        conversation.run_in_progress = true;
+        if let Err(e) = storage::save_conversation(conversation) {
+            warn!(conversation_id = %conversation.id, "failed to mark run in progress: {}", e);
+        }
 
         // agent loop - limit iterations to prevent runaway tasks.
         // 50 is enough for complex multi-step tasks while providing a safety bound
         const MAX_ITERATIONS: usize = 50;
         let mut iteration = 0;
-        println!("[agent] Starting agent loop");
+        let mut save_cadence = storage::SaveCadence::from_env();
+        info!(conversation_id = %conversation.id, "starting agent loop");
 
         'agent_loop: while self.running.load(Ordering::SeqCst) && iteration < MAX_ITERATIONS {
             iteration += 1;
-            if iteration <= 3 || iteration % 5 == 0 {
-                println!("[agent] Iteration {}", iteration);
+            debug!(conversation_id = %conversation.id, iteration, "agent iteration");
+
+            // the previous round's results are already in the history, so stopping here leaves it valid
+            if self.soft_stop.load(Ordering::SeqCst) {
+                info!(conversation_id = %conversation.id, iteration, "stopped after current tool");
+                self.emit(&app_handle, "finished", "Stopped", None, None);
+                break;
+            }
+            if screen_lost {
+                info!(conversation_id = %conversation.id, iteration, "stopped, screen recording is off");
+                self.emit(&app_handle, "error", "Paused task stopped - screen recording is still off", None, None);
+                break;
+            }
+            if out_of_time || past_deadline() {
+                let minutes = time_limit.map_or(0, |limit| limit.as_secs() / 60);
+                info!(conversation_id = %conversation.id, iteration, minutes, "run time limit reached");
+                self.emit(&app_handle, "time_limit", &format!("Stopped: the task ran past its {} minute time limit", minutes), None, None);
+                break;
+            }
+
+            // a hard-stopped run can leave tool calls without results, which the api refuses
+            let repair = crate::context::repair_message_sequence(&mut messages);
+            if !repair.is_empty() {
+                warn!(conversation_id = %conversation.id, filled = repair.filled, dropped = repair.dropped, "repaired tool call pairing");
             }
 
             // call API with streaming
@@ -607,46 +835,96 @@ impl Agent {
                                 "name": name
                             }));
                         }
+                        // forwarded as-is; the full input is parsed once the block closes
+                        StreamEvent::ToolInputDelta { partial_json } => {
+                            let _ = app_handle_clone.emit("agent-stream", serde_json::json!({
+                                "type": "tool_input_delta",
+                                "text": partial_json
+                            }));
+                        }
                         StreamEvent::MessageStop => {}
                     }
                 }
             });
 
-            // Try API call with auto-retry on rate limits
+            // files made earlier in the conversation, so "the PDF you made" can be found again
+            let artifacts_note = storage::list_artifacts(&conversation.id)
+                .ok()
+                .and_then(|artifacts| storage::artifacts_prompt(&artifacts));
+
+            // Try API call with auto-retry on rate limits / overload
             let mut retry_attempt = 0;
             const MAX_RETRIES: u32 = 5;
             
             let api_result = loop {
-                match client.send_message_streaming(messages.clone(), event_tx.clone(), mode, effective_voice_mode).await {
+                match client.send_message_streaming(messages.clone(), event_tx.clone(), mode, effective_voice_mode, artifacts_note.as_deref()).await {
                     Ok(result) => {
-                        println!("[agent] API streaming response complete, {} blocks, usage: {:?}", result.content.len(), result.usage);
+                        debug!(conversation_id = %conversation.id, iteration, blocks = result.content.len(), usage = ?result.usage, "api streaming response complete");
                         break result;
                     }
                     Err(e) => {
-                        let error_str = e.to_string();
-                        let is_rate_limit = error_str.contains("rate limit") 
-                            || error_str.contains("429") 
-                            || error_str.contains("tokens per minute");
+                        // 429s carry the server's retry hint; mid-stream rate limit errors only have text
+                        let (is_rate_limit, retry_after) = match &e {
+                            crate::api::ApiError::RateLimited { retry_after, .. } => (true, *retry_after),
+                            other => {
+                                let error_str = other.to_string();
+                                (error_str.contains("rate_limit_error") || error_str.contains("tokens per minute"), None)
+                            }
+                        };
                         
+                        let is_overloaded = matches!(e, crate::api::ApiError::Overloaded(_));
+
+                        if is_overloaded && retry_attempt < MAX_RETRIES {
+                            retry_attempt += 1;
+                            let delay = crate::rate_limiter::retry_delay(retry_attempt, None);
+                            warn!(conversation_id = %conversation.id, iteration, model = %active_model, attempt = retry_attempt,
+                                max_retries = MAX_RETRIES, delay = ?delay, "model overloaded, retrying");
+                            self.emit(&app_handle, "status",
+                                &format!("Model overloaded. Retrying in {}s... (attempt {}/{})",
+                                    delay.as_secs_f32().ceil() as u64, retry_attempt, MAX_RETRIES), None, None);
+                            tokio::time::sleep(delay).await;
+                            continue;
+                        }
+
                         if is_rate_limit && retry_attempt < MAX_RETRIES {
                             retry_attempt += 1;
-                            let delay_secs = 2_u64.pow(retry_attempt.min(4)); // 2, 4, 8, 16, 16 seconds
+                            let delay = crate::rate_limiter::retry_delay(retry_attempt, retry_after);
                             
-                            println!("[agent] Rate limit hit (attempt {}/{}). Retrying in {} seconds...", 
-                                retry_attempt, MAX_RETRIES, delay_secs);
+                            warn!(conversation_id = %conversation.id, iteration, attempt = retry_attempt, max_retries = MAX_RETRIES,
+                                delay = ?delay, server_hint = ?retry_after, "rate limit hit, retrying");
                             
                             self.emit(&app_handle, "status", 
                                 &format!("Rate limited. Retrying in {}s... (attempt {}/{})", 
-                                    delay_secs, retry_attempt, MAX_RETRIES), None, None);
+                                    delay.as_secs_f32().ceil() as u64, retry_attempt, MAX_RETRIES), None, None);
                             
-                            // Wait with exponential backoff (keeps context/messages intact)
-                            tokio::time::sleep(tokio::time::Duration::from_secs(delay_secs)).await;
+                            // Wait (keeps context/messages intact)
+                            tokio::time::sleep(delay).await;
                             
                             continue; // Retry the API call with same context
                         }
                         
-                        println!("[agent] API error: {:?}", e);
+                        // retry budget spent on this model - switch to the next fallback and keep the same
+                        // messages; tool definitions are built per request and don't depend on the model
+                        if is_overloaded || is_rate_limit {
+                            if let Some(next_model) = fallbacks.pop_front() {
+                                warn!(conversation_id = %conversation.id, iteration, from = %active_model, to = %next_model,
+                                    "model kept failing ({}), switching", e);
+                                self.emit(&app_handle, "model_switched",
+                                    &format!("{} is unavailable, switched to {}", active_model, next_model),
+                                    Some(serde_json::json!({ "from": active_model, "to": next_model })), None);
+                                client = AnthropicClient::new(api_key.clone(), next_model.clone());
+                                // the fallback can't take the failing model's thinking signatures
+                                conversation.switch_model(&next_model);
+                                strip_thinking(&mut messages);
+                                active_model = next_model;
+                                retry_attempt = 0;
+                                continue;
+                            }
+                        }
+
+                        error!(conversation_id = %conversation.id, iteration, "api error: {:?}", e);
                         self.emit(&app_handle, "error", &e.to_string(), None, None);
+                        task_outcome = Some(false);
                         break 'agent_loop;
                     }
                 }
@@ -666,9 +944,15 @@ impl Agent {
             };
             messages.push(assistant_message.clone());
             conversation.add_message(assistant_message);
-            conversation.add_usage(api_result.usage.clone(), &model);
+            conversation.add_usage(api_result.usage.clone(), &active_model);
+            // running totals for the cost meter, including what prompt caching saved
+            let summary = conversation.usage_summary();
+            self.emit(&app_handle, "usage", &format!("${:.4}", summary.cost_usd),
+                serde_json::to_value(&summary).ok(), None);
 
             let mut tool_results: Vec<ContentBlock> = Vec::new();
+            // every tool call this turn, timed for the audit log
+            let mut tool_timings: Vec<ToolTiming> = Vec::new();
 
             // debug: print all block types received
             let block_types: Vec<&str> = response_content.iter().map(|b| match b {
@@ -685,9 +969,33 @@ impl Agent {
             println!("[agent] Response blocks: {:?}", block_types);
 
             for block in &response_content {
+                // branches can `continue`, so a tool's clock stops when the next block starts
+                finish_last_timing(&mut tool_timings);
                 if !self.running.load(Ordering::SeqCst) {
                     break;
                 }
+                if !out_of_time && past_deadline() {
+                    println!("[agent] Run time limit reached, finishing after the current tool");
+                    out_of_time = true;
+                }
+                // after a soft stop the remaining calls aren't run, but each still gets a result
+                if self.soft_stop.load(Ordering::SeqCst) || out_of_time || screen_lost {
+                    if let ContentBlock::ToolUse { id, name, .. } = block {
+                        println!("[agent] Soft stop, skipping {}", name);
+                        let text = if out_of_time {
+                            "Not run: the task hit its time limit before this tool call."
+                        } else if screen_lost {
+                            "Not run: the task stopped because screen recording is off and the screen can't be seen."
+                        } else {
+                            "Not run: the user stopped the task before this tool call."
+                        };
+                        tool_results.push(ContentBlock::ToolResult {
+                            tool_use_id: id.clone(),
+                            content: vec![ToolResultContent::Text { text: text.to_string() }],
+                        });
+                    }
+                    continue;
+                }
 
                 println!("[agent] Processing block: {:?}", block);
 
@@ -703,10 +1011,23 @@ impl Agent {
 
                     ContentBlock::Text { text } => {
                         println!("[agent] Text: {}", text);
-                        self.emit(&app_handle, "response", text, None, None);
+                        self.emit(&app_handle, "response", text,
+                            Some(serde_json::json!({ "model": active_model })), None);
                     }
 
                     ContentBlock::ToolUse { id, name, input } => {
+                        tool_timings.push(ToolTiming::start(id, name, input));
+                        // switched off after the request went out, or called without being offered
+                        if !crate::tool_policy::ToolPolicy::current().allows(name) {
+                            println!("[agent] Refused disabled tool: {}", name);
+                            tool_results.push(ContentBlock::ToolResult {
+                                tool_use_id: id.clone(),
+                                content: vec![ToolResultContent::Text {
+                                    text: crate::tool_policy::DISABLED_TOOL_MESSAGE.to_string(),
+                                }],
+                            });
+                            continue;
+                        }
                         if name == "computer" {
                             // parse action
                             let action: ComputerAction = match serde_json::from_value(input.clone())
@@ -724,6 +1045,47 @@ impl Agent {
                                 }
                             };
 
+                            if let Some(reason) = crate::confirm::risky_computer_reason(&action) {
+                                if crate::confirm::ConfirmSettings::current().computer {
+                                    let summary = match action.coordinate {
+                                        Some([x, y]) => format!("{} at ({}, {})", action.action, x, y),
+                                        None => format!("{} {}", action.action, action.text.as_deref().unwrap_or("")).trim().to_string(),
+                                    };
+                                    let approved = crate::confirm::request_confirmation(
+                                        &app_handle,
+                                        &self.running,
+                                        crate::confirm::ConfirmationRequest {
+                                            tool: "computer".to_string(),
+                                            summary: summary.clone(),
+                                            reason: reason.to_string(),
+                                            screenshot: None,
+                                        },
+                                    ).await;
+                                    if !approved {
+                                        self.emit(&app_handle, "status", &format!("Declined: {}", summary), None, None);
+                                        tool_results.push(ContentBlock::ToolResult {
+                                            tool_use_id: id.clone(),
+                                            content: vec![ToolResultContent::Text {
+                                                text: format!("The user declined this action ({}). Do not retry it; choose another approach or ask the user.", summary),
+                                            }],
+                                        });
+                                        continue;
+                                    }
+                                }
+                            }
+                            if matches!(action.action.as_str(), "left_click" | "double_click" | "triple_click")
+                                && !self.approve_risky_click(&app_handle, "computer", action.coordinate).await
+                            {
+                                self.emit(&app_handle, "status", &format!("Declined: {}", action.action), None, None);
+                                tool_results.push(ContentBlock::ToolResult {
+                                    tool_use_id: id.clone(),
+                                    content: vec![ToolResultContent::Text {
+                                        text: "The user declined this click - the button looks like it deletes, confirms or pays for something. Do not retry it; choose another approach or ask the user.".to_string(),
+                                    }],
+                                });
+                                continue;
+                            }
+
                             // emit tool for TS-side formatting
                             self.emit_tool(&app_handle, "computer", input.clone());
                             // emit globally for mini
@@ -742,13 +1104,15 @@ impl Agent {
                                 let computer = computer_guard.as_ref().unwrap();
                                 (computer.screen_width, computer.screen_height)
                             };
-                            let result = tokio::task::spawn_blocking(move || {
+                            let (cursor_before, result) = tokio::task::spawn_blocking(move || {
                                 let computer = ComputerControl::with_dimensions(screen_w, screen_h);
-                                computer.perform_action(&action_clone)
+                                (computer.cursor_position(), computer.perform_action(&action_clone))
                             }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?;
 
                             match result {
                                 Ok(action_result) => {
+                                    self.action_history.lock().await.record(action.clone(), cursor_before);
+
                                     // zoom action returns screenshot directly, others need post-screenshot
                                     let screenshot = if action.action == "zoom" {
                                         // zoom returns the region screenshot, use panel exclusion
@@ -780,18 +1144,32 @@ impl Agent {
                                             }
                                         }
                                     } else {
-                                        // take screenshot excluding app windows
-                                        // must run on main thread for Panel access on macOS
-                                        #[cfg(target_os = "macos")]
-                                        {
-                                            crate::panels::take_screenshot_excluding_app()
-                                                .map_err(|e| AgentError::Computer(ComputerError::Screenshot(e)))?
-                                        }
-                                        #[cfg(not(target_os = "macos"))]
-                                        {
-                                            let computer_guard = self.computer.lock().await;
-                                            let computer = computer_guard.as_ref().unwrap();
-                                            computer.take_screenshot()?
+                                        // only a blank frame means screen recording is off; a failed capture is
+                                        // an ordinary error the model can react to
+                                        let capture = match self.capture_screen().await {
+                                            Ok(capture) => capture,
+                                            Err(e) => {
+                                                tool_results.push(ContentBlock::ToolResult {
+                                                    tool_use_id: id.clone(),
+                                                    content: vec![ToolResultContent::Text {
+                                                        text: format!("The action ran, but the screenshot after it failed: {}", e),
+                                                    }],
+                                                });
+                                                continue;
+                                            }
+                                        };
+                                        match self.await_screen_access(&app_handle, capture).await {
+                                            Some(capture) => capture,
+                                            None => {
+                                                screen_lost = true;
+                                                tool_results.push(ContentBlock::ToolResult {
+                                                    tool_use_id: id.clone(),
+                                                    content: vec![ToolResultContent::Text {
+                                                        text: "The action ran, but the screen can't be captured: screen recording permission was revoked and wasn't granted again, so the task stops here.".to_string(),
+                                                    }],
+                                                });
+                                                continue;
+                                            }
                                         }
                                     };
 
@@ -823,88 +1201,481 @@ impl Agent {
                                     });
                                 }
                             }
-                        } else if name == "bash" {
-                            let command = input.get("command").and_then(|v| v.as_str());
-                            let restart = input.get("restart").and_then(|v| v.as_bool()).unwrap_or(false);
-
-                            if restart {
-                                let mut bash = self.bash.lock().await;
-                                bash.restart();
-                                self.emit_tool(&app_handle, "bash", serde_json::json!({"restart": true}));
-                                tool_results.push(ContentBlock::ToolResult {
-                                    tool_use_id: id.clone(),
-                                    content: vec![ToolResultContent::Text {
-                                        text: "Bash session restarted".to_string(),
-                                    }],
-                                });
-                            } else if let Some(cmd) = command {
-                                // emit tool for TS-side formatting
-                                self.emit_tool(&app_handle, "bash", input.clone());
-                                // emit globally for mini
-                                let _ = app_handle.emit("agent:bash", serde_json::json!({ "command": cmd }));
-
-                                // execute
-                                let bash = self.bash.lock().await;
-                                let result = bash.execute(cmd);
+                        } else if name == "zoom_to_text" {
+                            let query = input.get("query").and_then(|q| q.as_str()).unwrap_or("").to_string();
+                            self.emit_tool(&app_handle, "zoom_to_text", input.clone());
+
+                            // full-resolution capture with our panels hidden so we don't OCR our own chat
+                            #[cfg(target_os = "macos")]
+                            let capture = crate::panels::take_screenshot_region_excluding_app(crate::computer::FULL_SCREEN_REGION)
+                                .map_err(ComputerError::Screenshot);
+                            #[cfg(not(target_os = "macos"))]
+                            let capture = {
+                                let computer_guard = self.computer.lock().await;
+                                computer_guard.as_ref().unwrap().take_screenshot_region(crate::computer::FULL_SCREEN_REGION)
+                            };
 
-                                let output = match result {
-                                    Ok(out) => {
-                                        let code = out.exit_code;
-                                        let text = out.to_string();
-                                        self.emit_with_exit_code(&app_handle, "bash_result", &text, None, None, Some(code));
-                                        text
-                                    }
-                                    Err(e) => {
-                                        let err_msg = format!("Error: {}", e);
-                                        self.emit_with_exit_code(&app_handle, "bash_result", &err_msg, None, None, Some(-1));
-                                        err_msg
-                                    }
-                                };
+                            let (screen_w, screen_h) = {
+                                let computer_guard = self.computer.lock().await;
+                                let computer = computer_guard.as_ref().unwrap();
+                                (computer.screen_width, computer.screen_height)
+                            };
+                            // OCR shells out, keep it off the async runtime
+                            let result = match capture {
+                                Ok(capture) => tokio::task::spawn_blocking(move || {
+                                    ComputerControl::with_dimensions(screen_w, screen_h).zoom_to_text(&capture, &query)
+                                }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?,
+                                Err(e) => Err(e),
+                            };
 
-                                tool_results.push(ContentBlock::ToolResult {
-                                    tool_use_id: id.clone(),
-                                    content: vec![ToolResultContent::Text { text: output }],
-                                });
-                            }
-                        } else if is_browser_tool(name) && mode == AgentMode::Browser {
-                            // check if stopped before starting browser tool
-                            if !self.running.load(Ordering::SeqCst) {
-                                println!("[agent] Stopped before browser tool");
-                                break;
+                            match result {
+                                Ok((matched, zoomed)) => {
+                                    self.emit(&app_handle, "screenshot", "Screenshot", None, Some(zoomed.clone()));
+                                    tool_results.push(ContentBlock::ToolResult {
+                                        tool_use_id: id.clone(),
+                                        content: vec![
+                                            ToolResultContent::Text { text: format!("Zoomed to \"{}\"", matched) },
+                                            ToolResultContent::Image {
+                                                source: ImageSource {
+                                                    source_type: "base64".to_string(),
+                                                    media_type: "image/jpeg".to_string(),
+                                                    data: zoomed,
+                                                },
+                                            },
+                                        ],
+                                    });
+                                }
+                                Err(e) => {
+                                    println!("[agent] zoom_to_text failed: {}", e);
+                                    tool_results.push(ContentBlock::ToolResult {
+                                        tool_use_id: id.clone(),
+                                        content: vec![ToolResultContent::Text { text: format!("Error: {}", e) }],
+                                    });
+                                }
                             }
+                        } else if name == "click_text" {
+                            let query = input.get("query").and_then(|q| q.as_str()).unwrap_or("").to_string();
+                            self.emit_tool(&app_handle, "click_text", input.clone());
+
+                            // same full-resolution, panels-hidden capture as zoom_to_text
+                            #[cfg(target_os = "macos")]
+                            let capture = crate::panels::take_screenshot_region_excluding_app(crate::computer::FULL_SCREEN_REGION)
+                                .map_err(ComputerError::Screenshot);
+                            #[cfg(not(target_os = "macos"))]
+                            let capture = {
+                                let computer_guard = self.computer.lock().await;
+                                computer_guard.as_ref().unwrap().take_screenshot_region(crate::computer::FULL_SCREEN_REGION)
+                            };
 
-                            // handle browser tools
-                            println!("[agent] Calling browser tool: {}", name);
-                            // emit tool for TS-side formatting
-                            self.emit_tool(&app_handle, name, input.clone());
-                            let _ = app_handle.emit("agent:browser_tool", serde_json::json!({ "name": name }));
+                            let (screen_w, screen_h) = {
+                                let computer_guard = self.computer.lock().await;
+                                let computer = computer_guard.as_ref().unwrap();
+                                (computer.screen_width, computer.screen_height)
+                            };
+                            let located = match capture {
+                                Ok(capture) => {
+                                    let query = query.clone();
+                                    tokio::task::spawn_blocking(move || {
+                                        ComputerControl::with_dimensions(screen_w, screen_h).locate_text(&capture, &query)
+                                    }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?
+                                }
+                                Err(e) => Err(e),
+                            };
 
-                            let mut browser_guard = self.browser_client.lock().await;
-                            if let Some(ref mut browser) = *browser_guard {
-                                // wrap browser operations with a cancellation check
-                                // use tokio::select! to race against stop signal
-                                let running_flag = self.running.clone();
-                                // check if this is a screenshot request (see_page with screenshot=true)
-                                let is_screenshot = name == "see_page" &&
-                                    input.get("screenshot").and_then(|v| v.as_bool()).unwrap_or(false);
-                                let browser_result: Result<BrowserToolResult, String> = {
-                                    let tool_future = async {
-                                        if is_screenshot {
-                                            match browser.screenshot().await {
-                                                Ok(data) => Ok(BrowserToolResult::Image(data)),
-                                                Err(e) => Err(format!("Screenshot error: {}", e)),
-                                            }
-                                        } else {
-                                            match execute_browser_tool(browser, name, input).await {
-                                                Ok(text) => Ok(BrowserToolResult::Text(text)),
-                                                Err(e) => Err(format!("Browser error: {}", e)),
+                            let text = match located {
+                                Ok(crate::ocr::ClickTarget::Found(found)) => {
+                                    let (x, y) = found.text_box.center();
+                                    let action = ComputerAction {
+                                        action: "left_click".to_string(),
+                                        coordinate: Some([x as i32, y as i32]),
+                                        start_coordinate: None,
+                                        text: None,
+                                        scroll_direction: None,
+                                        scroll_amount: None,
+                                        key: None,
+                                        region: None,
+                                    };
+                                    let summary = format!("click \"{}\" at ({}, {})", found.text_box.text, x, y);
+                                    let approved = (!crate::confirm::ConfirmSettings::current().computer
+                                        || crate::confirm::request_confirmation(
+                                            &app_handle,
+                                            &self.running,
+                                            crate::confirm::ConfirmationRequest {
+                                                tool: "click_text".to_string(),
+                                                summary: summary.clone(),
+                                                reason: "clicks on screen".to_string(),
+                                                screenshot: None,
+                                            },
+                                        ).await)
+                                        && self.approve_risky_click(&app_handle, "click_text", Some([x as i32, y as i32])).await;
+                                    if approved {
+                                        let action_clone = action.clone();
+                                        let (cursor_before, result) = tokio::task::spawn_blocking(move || {
+                                            let computer = ComputerControl::with_dimensions(screen_w, screen_h);
+                                            (computer.cursor_position(), computer.perform_action(&action_clone))
+                                        }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?;
+                                        match result {
+                                            Ok(_) => {
+                                                self.action_history.lock().await.record(action, cursor_before);
+                                                format!(
+                                                    "Clicked \"{}\" at ({}, {}), confidence {:.2}. Take a screenshot to see the result.",
+                                                    found.text_box.text, x, y, found.confidence
+                                                )
                                             }
+                                            Err(e) => format!("Error: {}", e),
                                         }
-                                    };
-
-                                    // poll for cancellation every 100ms
-                                    let cancel_check = async {
-                                        loop {
+                                    } else {
+                                        self.emit(&app_handle, "status", &format!("Declined: {}", summary), None, None);
+                                        format!("The user declined this action ({}). Do not retry it; choose another approach or ask the user.", summary)
+                                    }
+                                }
+                                Ok(crate::ocr::ClickTarget::Ambiguous(candidates)) => {
+                                    let lines: Vec<String> = candidates
+                                        .iter()
+                                        .map(|c| {
+                                            let (x, y) = c.text_box.center();
+                                            format!("- \"{}\" at ({}, {}), confidence {:.2}", c.text_box.text, x, y, c.confidence)
+                                        })
+                                        .collect();
+                                    format!(
+                                        "Nothing clicked - \"{}\" matches several places about equally well:\n{}\nCall click_text with more of the text, or click one of these coordinates with the computer tool.",
+                                        query,
+                                        lines.join("\n")
+                                    )
+                                }
+                                Ok(crate::ocr::ClickTarget::NotFound) => format!(
+                                    "Error: no text matching \"{}\" found on screen. Take a screenshot and click by coordinates instead.",
+                                    query
+                                ),
+                                Err(e) => {
+                                    println!("[agent] click_text failed: {}", e);
+                                    format!("Error: {}", e)
+                                }
+                            };
+                            tool_results.push(ContentBlock::ToolResult {
+                                tool_use_id: id.clone(),
+                                content: vec![ToolResultContent::Text { text }],
+                            });
+                        } else if name == "wait_for_change" {
+                            use crate::computer::{WAIT_DEFAULT_STABILITY_MS, WAIT_DEFAULT_TIMEOUT_MS};
+                            let region = input
+                                .get("region")
+                                .and_then(|r| serde_json::from_value::<[i32; 4]>(r.clone()).ok())
+                                .unwrap_or(crate::computer::FULL_SCREEN_REGION);
+                            let timeout_ms = input.get("timeout_ms").and_then(|v| v.as_u64()).unwrap_or(WAIT_DEFAULT_TIMEOUT_MS);
+                            let stability_ms = input.get("stability_ms").and_then(|v| v.as_u64()).unwrap_or(WAIT_DEFAULT_STABILITY_MS);
+                            self.emit_tool(&app_handle, "wait_for_change", input.clone());
+
+                            // our own panels are excluded on macOS so the border glow doesn't read as a change
+                            #[cfg(target_os = "macos")]
+                            let capture = move || crate::panels::take_screenshot_region_excluding_app(region)
+                                .map_err(ComputerError::Screenshot);
+                            #[cfg(not(target_os = "macos"))]
+                            let capture = {
+                                let computer_guard = self.computer.lock().await;
+                                let computer = computer_guard.as_ref().unwrap();
+                                let control = ComputerControl::with_dimensions(computer.screen_width, computer.screen_height);
+                                move || control.take_screenshot_region(region)
+                            };
+                            let running = self.running.clone();
+                            // polls for up to a minute, keep it off the async runtime
+                            let result = tokio::task::spawn_blocking(move || {
+                                ComputerControl::wait_for_change(capture, timeout_ms, stability_ms, &running)
+                            }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?;
+
+                            let text = match result {
+                                Ok(outcome) if outcome.stabilized => format!(
+                                    "Region stable after {}ms ({})",
+                                    outcome.elapsed_ms,
+                                    if outcome.changed { "it changed, then settled" } else { "no change while waiting" }
+                                ),
+                                Ok(outcome) => format!(
+                                    "Region still changing after {}ms (timed out); it may still be loading",
+                                    outcome.elapsed_ms
+                                ),
+                                Err(e) => {
+                                    println!("[agent] wait_for_change failed: {}", e);
+                                    format!("Error: {}", e)
+                                }
+                            };
+                            tool_results.push(ContentBlock::ToolResult {
+                                tool_use_id: id.clone(),
+                                content: vec![ToolResultContent::Text { text }],
+                            });
+                        } else if matches!(
+                            name.as_str(),
+                            "list_windows" | "focus_window" | "get_active_app" | "set_window_bounds" | "snap_window"
+                        ) {
+                            self.emit_tool(&app_handle, &name, input.clone());
+                            let query = input.get("title_or_id").and_then(|q| q.as_str()).unwrap_or("").to_string();
+                            let (screen_w, screen_h) = {
+                                let computer_guard = self.computer.lock().await;
+                                let computer = computer_guard.as_ref().unwrap();
+                                (computer.screen_width, computer.screen_height)
+                            };
+                            let tool = name.clone();
+                            let tool_input = input.clone();
+                            // window enumeration and osascript both block
+                            let result = tokio::task::spawn_blocking(move || {
+                                let control = ComputerControl::with_dimensions(screen_w, screen_h);
+                                match tool.as_str() {
+                                    "list_windows" => control.list_windows().map(|windows| {
+                                        if windows.is_empty() {
+                                            "No other windows are open".to_string()
+                                        } else {
+                                            serde_json::to_string_pretty(&windows).unwrap_or_default()
+                                        }
+                                    }),
+                                    "get_active_app" => control
+                                        .active_app()
+                                        .map(|app| serde_json::to_string_pretty(&app).unwrap_or_default()),
+                                    "set_window_bounds" | "snap_window" => {
+                                        let bounds = if tool == "snap_window" {
+                                            let position = tool_input.get("position").and_then(|p| p.as_str()).unwrap_or("");
+                                            crate::computer::SnapPosition::parse(position).map(|p| p.bounds()).ok_or_else(|| {
+                                                ComputerError::Input(format!("Unknown position \"{}\" - use left, right or maximize", position))
+                                            })
+                                        } else {
+                                            let number = |key: &str| tool_input.get(key).and_then(|v| v.as_i64()).map(|v| v as i32);
+                                            match (number("x"), number("y"), number("width"), number("height")) {
+                                                (Some(x), Some(y), Some(width), Some(height)) => Ok([x, y, x + width, y + height]),
+                                                _ => Err(ComputerError::Input("set_window_bounds needs x, y, width and height".to_string())),
+                                            }
+                                        };
+                                        bounds.and_then(|bounds| control.set_window_bounds(&query, bounds)).map(|window| {
+                                            format!("Moved \"{}\" ({}), bounds now {:?}", window.title, window.app, window.bounds)
+                                        })
+                                    }
+                                    _ => control.focus_window(&query).map(|window| {
+                                        format!("Focused \"{}\" ({}), bounds {:?}", window.title, window.app, window.bounds)
+                                    }),
+                                }
+                            }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?;
+
+                            let text = result.unwrap_or_else(|e| {
+                                println!("[agent] {} failed: {}", name, e);
+                                format!("Error: {}", e)
+                            });
+                            tool_results.push(ContentBlock::ToolResult {
+                                tool_use_id: id.clone(),
+                                content: vec![ToolResultContent::Text { text }],
+                            });
+                        } else if name == "observe_ui" {
+                            self.emit_tool(&app_handle, &name, input.clone());
+                            let (screen_w, screen_h) = {
+                                let computer_guard = self.computer.lock().await;
+                                let computer = computer_guard.as_ref().unwrap();
+                                (computer.screen_width, computer.screen_height)
+                            };
+                            // walking the tree is one osascript / powershell call, and it blocks
+                            let result = tokio::task::spawn_blocking(move || {
+                                ComputerControl::with_dimensions(screen_w, screen_h).observe_ui()
+                            }).await.map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?;
+
+                            let content = match result {
+                                Ok(Some(tree)) => vec![ToolResultContent::Text { text: tree }],
+                                // nothing to read structurally (a canvas app) - look at it instead
+                                Ok(None) => match self.capture_screen().await {
+                                    Ok(screenshot) => {
+                                        self.emit(&app_handle, "screenshot", "Screenshot", None, Some(screenshot.clone()));
+                                        vec![
+                                            ToolResultContent::Text {
+                                                text: "The focused window exposes no accessibility tree, so here is a screenshot instead.".to_string(),
+                                            },
+                                            ToolResultContent::Image {
+                                                source: ImageSource {
+                                                    source_type: "base64".to_string(),
+                                                    media_type: crate::computer::media_type_of(&screenshot).to_string(),
+                                                    data: screenshot,
+                                                },
+                                            },
+                                        ]
+                                    }
+                                    Err(e) => vec![ToolResultContent::Text {
+                                        text: format!("Error: the focused window exposes no accessibility tree and the screenshot failed: {}", e),
+                                    }],
+                                },
+                                Err(e) => {
+                                    println!("[agent] observe_ui failed: {}", e);
+                                    vec![ToolResultContent::Text { text: format!("Error: {}", e) }]
+                                }
+                            };
+                            tool_results.push(ContentBlock::ToolResult { tool_use_id: id.clone(), content });
+                        } else if name == "launch" {
+                            self.emit_tool(&app_handle, &name, input.clone());
+                            let targets: Vec<String> = input
+                                .get("targets")
+                                .and_then(|t| t.as_array())
+                                .map(|items| items.iter().filter_map(|t| t.as_str()).map(str::to_string).collect())
+                                .unwrap_or_default();
+                            let text = if targets.is_empty() {
+                                "Error: launch needs at least one app or URL in targets".to_string()
+                            } else if targets.len() > crate::launcher::MAX_LAUNCH_TARGETS {
+                                format!("Error: launch opens at most {} targets per call", crate::launcher::MAX_LAUNCH_TARGETS)
+                            } else {
+                                // scanning installed apps and the openers both block
+                                let outcomes = tokio::task::spawn_blocking(move || crate::launcher::launch_all(&targets))
+                                    .await
+                                    .map_err(|e| AgentError::Computer(ComputerError::Input(e.to_string())))?;
+                                crate::launcher::format_outcomes(&outcomes)
+                            };
+                            tool_results.push(ContentBlock::ToolResult {
+                                tool_use_id: id.clone(),
+                                content: vec![ToolResultContent::Text { text }],
+                            });
+                        } else if name == "bash" {
+                            let command = input.get("command").and_then(|v| v.as_str());
+                            let restart = input.get("restart").and_then(|v| v.as_bool()).unwrap_or(false);
+                            let keep_cwd = input.get("keep_cwd").and_then(|v| v.as_bool()).unwrap_or(false);
+
+                            if restart {
+                                let mut bash = self.bash.lock().await;
+                                bash.restart(keep_cwd);
+                                conversation.bash_state = bash.state().clone();
+                                self.emit_tool(&app_handle, "bash", serde_json::json!({"restart": true}));
+                                let text = match bash.cwd() {
+                                    Some(dir) if keep_cwd => format!("Bash session restarted (still in {})", dir),
+                                    _ => "Bash session restarted".to_string(),
+                                };
+                                tool_results.push(ContentBlock::ToolResult {
+                                    tool_use_id: id.clone(),
+                                    content: vec![ToolResultContent::Text { text }],
+                                });
+                            } else if let Some(cmd) = command {
+                                if let Some(reason) = crate::confirm::destructive_bash_reason(cmd) {
+                                    if crate::confirm::ConfirmSettings::current().bash {
+                                        let approved = crate::confirm::request_confirmation(
+                                            &app_handle,
+                                            &self.running,
+                                            crate::confirm::ConfirmationRequest {
+                                                tool: "bash".to_string(),
+                                                summary: cmd.to_string(),
+                                                reason: reason.to_string(),
+                                                screenshot: None,
+                                            },
+                                        ).await;
+                                        if !approved {
+                                            self.emit(&app_handle, "status", &format!("Declined: `{}`", cmd), None, None);
+                                            tool_results.push(ContentBlock::ToolResult {
+                                                tool_use_id: id.clone(),
+                                                content: vec![ToolResultContent::Text {
+                                                    text: format!("The user declined to run this command ({}). Do not retry it; choose another approach or ask the user.", reason),
+                                                }],
+                                            });
+                                            continue;
+                                        }
+                                    }
+                                }
+
+                                // emit tool for TS-side formatting
+                                self.emit_tool(&app_handle, "bash", input.clone());
+                                // emit globally for mini
+                                let _ = app_handle.emit("agent:bash", serde_json::json!({ "command": cmd }));
+
+                                // execute
+                                let mut bash = self.bash.lock().await;
+                                let result = bash.execute(cmd);
+                                conversation.bash_state = bash.state().clone();
+                                drop(bash);
+                                let cwd_action = Some(serde_json::json!({ "cwd": conversation.bash_state.cwd }));
+
+                                let output = match result {
+                                    Ok(out) => {
+                                        let code = out.exit_code;
+                                        let text = out.to_string();
+                                        self.emit_with_exit_code(&app_handle, "bash_result", &text, cwd_action, None, Some(code));
+                                        text
+                                    }
+                                    Err(e) => {
+                                        let err_msg = format!("Error: {}", e);
+                                        self.emit_with_exit_code(&app_handle, "bash_result", &err_msg, cwd_action, None, Some(-1));
+                                        err_msg
+                                    }
+                                };
+
+                                tool_results.push(ContentBlock::ToolResult {
+                                    tool_use_id: id.clone(),
+                                    content: vec![ToolResultContent::Text { text: output }],
+                                });
+                            }
+                        } else if is_browser_tool(name) && mode == AgentMode::Browser {
+                            // check if stopped before starting browser tool
+                            if !self.running.load(Ordering::SeqCst) {
+                                println!("[agent] Stopped before browser tool");
+                                break;
+                            }
+
+                            // a click on an element named like a delete / pay / confirm button waits for the user
+                            let clicked_uid = input.get("click").or_else(|| input.get("double_click")).and_then(|v| v.as_str());
+                            if let Some(uid) = clicked_uid.filter(|_| name == "page_action") {
+                                let element = self.browser_client.lock().await.as_ref().and_then(|b| b.element_label(uid));
+                                if let Some((_, element_name)) = element {
+                                    if !self.approve_risky_element_click(&app_handle, &element_name).await {
+                                        self.emit(&app_handle, "status", &format!("Declined: click \"{}\"", element_name), None, None);
+                                        tool_results.push(ContentBlock::ToolResult {
+                                            tool_use_id: id.clone(),
+                                            content: vec![ToolResultContent::Text {
+                                                text: "The user declined this click - the element looks like it deletes, confirms or pays for something. Do not retry it; choose another approach or ask the user.".to_string(),
+                                            }],
+                                        });
+                                        continue;
+                                    }
+                                }
+                            }
+
+                            // handle browser tools
+                            info!(conversation_id = %conversation.id, iteration, tool = %name, "calling browser tool");
+                            // emit tool for TS-side formatting
+                            self.emit_tool(&app_handle, name, input.clone());
+                            let _ = app_handle.emit("agent:browser_tool", serde_json::json!({ "name": name }));
+
+                            let mut browser_guard = self.browser_client.lock().await;
+                            if let Some(ref mut browser) = *browser_guard {
+                                // wrap browser operations with a cancellation check
+                                // use tokio::select! to race against stop signal
+                                let running_flag = self.running.clone();
+                                // check if this is a screenshot request (see_page with screenshot=true)
+                                let is_screenshot = name == "see_page" &&
+                                    input.get("screenshot").and_then(|v| v.as_bool()).unwrap_or(false);
+                                let browser_result: Result<BrowserToolResult, String> = {
+                                    let tool_future = async {
+                                        if is_screenshot {
+                                            match browser.screenshot().await {
+                                                Ok(data) => Ok(BrowserToolResult::Image(data)),
+                                                Err(e) => Err(format!("Screenshot error: {}", e)),
+                                            }
+                                        } else if name == "browser_download" {
+                                            let uid = input.get("click").and_then(|v| v.as_str());
+                                            let url = input.get("url").and_then(|v| v.as_str());
+                                            let timeout_ms = input.get("timeout_ms").and_then(|v| v.as_u64()).unwrap_or(30000);
+                                            match browser.download(uid, url, timeout_ms).await {
+                                                Ok(DownloadOutcome::Completed(file)) => Ok(BrowserToolResult::Download(file)),
+                                                Ok(DownloadOutcome::Navigated(url)) => Ok(BrowserToolResult::Text(format!(
+                                                    "No download started - the page navigated to {}. Use see_page to look for the actual download link.",
+                                                    url
+                                                ))),
+                                                Err(e) => Err(format!("Download error: {}", e)),
+                                            }
+                                        } else {
+                                            match execute_browser_tool(browser, name, input).await {
+                                                Ok(text) => Ok(BrowserToolResult::Text(text)),
+                                                Err(e) if e.is_element_lookup() => {
+                                                    // the page re-rendered since the snapshot - hand back fresh uids
+                                                    println!("[agent] {}, re-taking snapshot", e);
+                                                    tokio::time::sleep(tokio::time::Duration::from_millis(ELEMENT_RETRY_DELAY_MS)).await;
+                                                    match browser.take_snapshot(false).await {
+                                                        Ok(snapshot) => Err(format!("Browser error: {}\n\nFresh snapshot:\n{}", e, snapshot)),
+                                                        Err(_) => Err(format!("Browser error: {}", e)),
+                                                    }
+                                                }
+                                                Err(e) => Err(format!("Browser error: {}", e)),
+                                            }
+                                        }
+                                    };
+
+                                    // poll for cancellation every 100ms
+                                    let cancel_check = async {
+                                        loop {
                                             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                                             if !running_flag.load(Ordering::SeqCst) {
                                                 return;
@@ -941,8 +1712,26 @@ impl Agent {
                                             content: vec![ToolResultContent::Text { text: output }],
                                         });
                                     }
+                                    Ok(BrowserToolResult::Download(file)) => {
+                                        let output = format!(
+                                            "Downloaded {} ({}) to {}",
+                                            file.name,
+                                            file.size_display(),
+                                            file.path
+                                        );
+                                        println!("[agent] {}", output);
+                                        self.emit(&app_handle, "browser_result", &output, None, None);
+                                        let _ = app_handle.emit("agent:file_created", &file);
+                                        if let Err(e) = storage::record_artifact(&conversation.id, &file, &name, conversation.messages.len().saturating_sub(1)) {
+                                            warn!(conversation_id = %conversation.id, "failed to record artifact: {}", e);
+                                        }
+                                        tool_results.push(ContentBlock::ToolResult {
+                                            tool_use_id: id.clone(),
+                                            content: vec![ToolResultContent::Text { text: output }],
+                                        });
+                                    }
                                     Err(err_msg) => {
-                                        println!("[agent] Browser tool failed: {}", err_msg);
+                                        warn!(conversation_id = %conversation.id, iteration, tool = %name, "browser tool failed: {}", err_msg);
                                         if err_msg == "Stopped by user" {
                                             // don't add result, just break
                                             break;
@@ -955,7 +1744,7 @@ impl Agent {
                                     }
                                 }
                             } else {
-                                let err_msg = "Browser not connected".to_string();
+                                let err_msg = BrowserError::NotConnected.to_string();
                                 tool_results.push(ContentBlock::ToolResult {
                                     tool_use_id: id.clone(),
                                     content: vec![ToolResultContent::Text { text: err_msg }],
@@ -964,7 +1753,14 @@ impl Agent {
                         } else if name == "speak" {
                             // handle speak tool for voice mode
                             if let Some(text) = input.get("text").and_then(|t| t.as_str()) {
-                                if let Some(ref tts) = tts_client {
+                                if crate::quiet::status().speech_muted() {
+                                    tool_results.push(ContentBlock::ToolResult {
+                                        tool_use_id: id.clone(),
+                                        content: vec![ToolResultContent::Text {
+                                            text: "Speech is muted - do not disturb is on. Reply in text instead.".to_string(),
+                                        }],
+                                    });
+                                } else if let Some(ref tts) = tts_client {
                                     match tts.synthesize(text).await {
                                         Ok(audio_base64) => {
                                             println!("[agent] TTS synthesized {} bytes", audio_base64.len());
@@ -973,6 +1769,7 @@ impl Agent {
                                                 "audio": audio_base64,
                                                 "text": text,
                                             }));
+                                            last_spoken = Some(text.to_string());
 
                                             tool_results.push(ContentBlock::ToolResult {
                                                 tool_use_id: id.clone(),
@@ -1003,19 +1800,36 @@ impl Agent {
                             // handle deep research tool - opens Chrome for visual Google searches!
                             if let Some(query) = input.get("query").and_then(|q| q.as_str()) {
                                 let depth = input.get("depth").and_then(|d| d.as_str()).unwrap_or("standard");
+                                let config = crate::deep_research::ResearchConfig::from_tool_input(depth, input);
                                 
                                 self.emit_tool(&app_handle, "deep_research", input.clone());
-                                self.emit(&app_handle, "status", &format!("🔬 Deep researching: {} (depth: {}) - watch Chrome!", query, depth), None, None);
+                                self.emit(&app_handle, "status", &format!(
+                                    "🔬 Deep researching: {} (depth: {} — {}) - watch Chrome!",
+                                    query, crate::deep_research::normalize_depth(depth), config.summary()
+                                ), None, None);
                                 
                                 let api_key_clone = api_key.clone();
-                                let model_clone = model.clone();
+                                let model_clone = active_model.clone();
                                 
                                 match crate::deep_research::perform_deep_research(
-                                    query, depth, &api_key_clone, &model_clone, &self.browser_client
+                                    query, depth, &config, &api_key_clone, &model_clone, &self.browser_client, &self.running
                                 ).await {
                                     Ok(report) => {
-                                        let formatted = crate::deep_research::format_research_report(&report);
+                                        let mut formatted = crate::deep_research::format_research_report(&report);
                                         println!("[agent] Deep research complete: {} sources found", report.sources.len());
+                                        if report.cancelled {
+                                            self.emit(&app_handle, "research_cancelled", &format!(
+                                                "🛑 Research cancelled — kept {} source{} gathered so far",
+                                                report.sources.len(),
+                                                if report.sources.len() == 1 { "" } else { "s" }
+                                            ), Some(serde_json::json!({ "sources": report.sources.len() })), None);
+                                        }
+                                        match crate::deep_research::save_report(&report) {
+                                            Ok(report_id) => {
+                                                formatted.push_str(&format!("\n_Saved as research report `{}`_\n", report_id));
+                                            }
+                                            Err(e) => println!("[agent] Failed to save research report: {}", e),
+                                        }
                                         
                                         self.emit(&app_handle, "research_result", &formatted, None, None);
                                         tool_results.push(ContentBlock::ToolResult {
@@ -1066,11 +1880,22 @@ impl Agent {
                                         if !result.files_created.is_empty() {
                                             output.push_str("\n\n📁 Files created:\n");
                                             for file in &result.files_created {
-                                                output.push_str(&format!("\n• {}", file));
+                                                output.push_str(&format!(
+                                                    "\n• {} ({}, {})",
+                                                    file.path, file.size_display(), file.mime_type
+                                                ));
+                                                // ui shows an "Open" affordance per file
+                                                let _ = app_handle.emit("agent:file_created", file);
+                                                if let Err(e) = storage::record_artifact(&conversation.id, file, &name, conversation.messages.len().saturating_sub(1)) {
+                                                    warn!(conversation_id = %conversation.id, "failed to record artifact: {}", e);
+                                                }
                                             }
                                         }
                                         
                                         self.emit(&app_handle, "python_result", &output, None, None);
+                                        for image in &result.images {
+                                            self.emit(&app_handle, "screenshot", "Chart", None, Some(image.clone()));
+                                        }
                                         tool_results.push(ContentBlock::ToolResult {
                                             tool_use_id: id.clone(),
                                             content: vec![ToolResultContent::Text { text: output }],
@@ -1124,34 +1949,41 @@ impl Agent {
                     _ => {}
                 }
             }
+            finish_last_timing(&mut tool_timings);
+            if let Err(e) = storage::record_tool_calls(&conversation.id, &audit_entries(tool_timings, &tool_results)) {
+                warn!(conversation_id = %conversation.id, iteration, "failed to write tool audit: {}", e);
+            }
 
             // clear streaming text in mini on each message complete
             let _ = app_handle.emit("agent:message", ());
 
             // check if stopped during tool execution
             if !self.running.load(Ordering::SeqCst) {
-                println!("[agent] Stopped by user");
+                info!(conversation_id = %conversation.id, iteration, "stopped by user");
                 self.emit(&app_handle, "finished", "Stopped", None, None);
                 break;
             }
 
             // Check if the assistant actually requested any tools in this turn
             // We need to check the response_content, not just tool_results
-            let has_tool_calls = response_content.iter().any(|b| matches!(b, 
-                ContentBlock::ToolUse { .. } | 
-                ContentBlock::ServerToolUse { .. }
-            ));
+            let has_tool_calls = requests_local_tools(&response_content);
 
             // if no tools were requested, the task is complete
             if !has_tool_calls {
-                println!("[agent] No tool calls requested by assistant, task complete");
+                if let Some(ref tts) = tts_client {
+                    if crate::voice::auto_speak_final_enabled() {
+                        self.speak_final_response(tts, &response_content, last_spoken.as_deref(), &app_handle).await;
+                    }
+                }
+                info!(conversation_id = %conversation.id, iteration, "no tool calls requested, task complete");
                 self.emit(&app_handle, "finished", "Task completed", None, None);
+                task_outcome = Some(true);
                 break;
             }
 
             // If we have tool calls but no results, something went wrong during execution
             if tool_results.is_empty() {
-                println!("[agent] Warning: Tools were called but no results captured");
+                warn!(conversation_id = %conversation.id, iteration, "tools were called but no results captured");
                 // Don't break - let the loop continue and inform the model
                 let tool_result_message = Message {
                     role: "user".to_string(),
@@ -1164,25 +1996,6 @@ impl Agent {
                 continue;
             }
 
-            // check if we're adding a new snapshot - if so, summarize old ones
-            let has_new_snapshot = tool_results.iter().any(|r| {
-                if let ContentBlock::ToolResult { content, .. } = r {
-                    content.iter().any(|c| {
-                        if let ToolResultContent::Text { text } = c {
-                            text.starts_with("uid=")
-                        } else {
-                            false
-                        }
-                    })
-                } else {
-                    false
-                }
-            });
-
-            if has_new_snapshot {
-                summarize_old_snapshots(&mut messages);
-            }
-
             let tool_result_message = Message {
                 role: "user".to_string(),
                 content: tool_results,
@@ -1190,37 +2003,623 @@ impl Agent {
             messages.push(tool_result_message.clone());
             conversation.add_message(tool_result_message);
 
-            // save after each round so we don't lose progress on crash/stop
+            // only summarizes old snapshots / outputs once the context nears the budget;
+            // the saved conversation keeps the full results
+            if let Some(report) = crate::context::trim_context(&mut messages, &context_config) {
+                info!(
+                    conversation_id = %conversation.id, iteration, tokens_before = report.tokens_before,
+                    tokens_after = report.tokens_after, budget = context_config.budget_tokens,
+                    snapshots = report.snapshots, outputs = report.outputs,
+                    "context trimmed, reclaimed ~{} tokens", report.reclaimed()
+                );
+            }
+
+            // save every few rounds so a crash loses little, without rewriting the whole
+            // conversation each round; stopping still reaches the final save below
             conversation.auto_title();
-            if let Err(e) = storage::save_conversation(&conversation) {
-                println!("[agent] Failed to save conversation: {}", e);
+            if save_cadence.round_finished() {
+                if let Err(e) = storage::save_conversation(conversation) {
+                    error!(conversation_id = %conversation.id, iteration, "failed to save conversation: {}", e);
+                }
             }
         }
 
         self.running.store(false, Ordering::SeqCst);
 
+        // feed the outcome back into the memories that were used as context
+        if let Some(success) = task_outcome {
+            if !used_memory_ids.is_empty() {
+                let mut cognitive = self.cognitive.lock().await;
+                for memory_id in &used_memory_ids {
+                    if let Err(e) = cognitive.memory.update_memory_success(memory_id, success).await {
+                        println!("[agent] Failed to update memory {}: {}", memory_id, e);
+                    }
+                }
+            }
+        }
+
+        // a fallback only stands in for this run; the next message goes back to the chosen model
+        conversation.switch_model(&model);
+
         // final save
+        conversation.run_in_progress = false;
+        conversation.record_run(run_start.elapsed());
         if !conversation.messages.is_empty() {
-            if let Err(e) = storage::save_conversation(&conversation) {
-                println!("[agent] Failed to save conversation: {}", e);
+            if let Err(e) = storage::save_conversation(conversation) {
+                error!(conversation_id = %conversation.id, "failed to save conversation: {}", e);
             } else {
-                println!("[agent] Saved conversation {} ({} msgs, {} input, {} output tokens)",
-                    conversation.id,
-                    conversation.messages.len(),
-                    conversation.total_input_tokens,
-                    conversation.total_output_tokens
+                info!(
+                    conversation_id = %conversation.id,
+                    messages = conversation.messages.len(),
+                    input_tokens = conversation.total_input_tokens,
+                    output_tokens = conversation.total_output_tokens,
+                    "saved conversation"
                 );
             }
+            if let Err(e) = storage::checkpoint() {
+                warn!("failed to checkpoint db: {}", e);
+            }
         }
         let _ = app_handle.emit("agent:stopped", ());
 
         // emit border hide for frontend to call IPC command
         let _ = app_handle.emit("border:hide", ());
 
-        println!("[agent] Task completed in {:?}", run_start.elapsed());
+        spawn_auto_title(conversation, &api_key, &app_handle);
+
+        info!(conversation_id = %conversation.id, iterations = iteration, elapsed = ?run_start.elapsed(), "task completed");
+        Ok(())
+    }
+
+    /// Say the final answer out loud when the model ended a voice run without speaking it
+    async fn speak_final_response(
+        &self,
+        tts: &TtsClient,
+        response_content: &[ContentBlock],
+        last_spoken: Option<&str>,
+        app_handle: &AppHandle,
+    ) {
+        let Some(text) = response_content.iter().rev().find_map(|block| match block {
+            ContentBlock::Text { text } if !text.trim().is_empty() => Some(text.trim()),
+            _ => None,
+        }) else {
+            return;
+        };
+        let already_spoken = last_spoken
+            .is_some_and(|spoken| crate::voice::normalize_utterance(spoken) == crate::voice::normalize_utterance(text));
+        if already_spoken || crate::quiet::status().speech_muted() {
+            return;
+        }
+        match tts.synthesize(text).await {
+            Ok(audio_base64) => {
+                let _ = app_handle.emit("agent:speak", serde_json::json!({
+                    "audio": audio_base64,
+                    "text": text,
+                }));
+            }
+            Err(e) => println!("[agent] TTS failed for final response: {}", e),
+        }
+    }
+
+    /// Answer with a single tool-less streaming turn. Returns false, before anything has been
+    /// shown, when the request should go through the full agent instead: the model replied
+    /// with NEEDS_TOOLS_MARKER, the conversation already has tool history, or the API failed.
+    async fn quick_answer(
+        &self,
+        instructions: &str,
+        model: &str,
+        mode: AgentMode,
+        history: &[HistoryMessage],
+        conversation_id: Option<&str>,
+        api_key: &str,
+        app_handle: &AppHandle,
+    ) -> bool {
+        let started = std::time::Instant::now();
+        let mode_str = match mode {
+            AgentMode::Computer => "computer",
+            AgentMode::Browser => "browser",
+        };
+        let mut conversation = load_or_create_conversation(conversation_id, model, mode_str);
+        conversation.switch_model(model);
+
+        // tool_use / tool_result blocks can't be sent without tool definitions
+        let has_tool_history = conversation.messages.iter().any(|m| {
+            m.content.iter().any(|b| !matches!(b, ContentBlock::Text { .. }))
+        });
+        if has_tool_history {
+            return false;
+        }
+
+        let mut messages: Vec<Message> = if conversation.messages.is_empty() {
+            history
+                .iter()
+                .map(|msg| Message {
+                    role: msg.role.clone(),
+                    content: vec![ContentBlock::Text { text: msg.content.clone() }],
+                })
+                .collect()
+        } else {
+            conversation.messages.clone()
+        };
+        let user_message = Message {
+            role: "user".to_string(),
+            content: vec![ContentBlock::Text { text: instructions.to_string() }],
+        };
+        messages.push(user_message.clone());
+
+        println!("[agent] Trying quick answer ({} messages)", messages.len());
+        self.running.store(true, Ordering::SeqCst);
+        let client = AnthropicClient::new(api_key.to_string(), model.to_string());
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<StreamEvent>();
+
+        // nothing is shown until the reply can no longer be the needs-tools marker, so a
+        // bounced request looks exactly like a normal run to the frontend
+        let mut announced = false;
+        let mut pending = String::new();
+        let consume = async {
+            while let Some(event) = event_rx.recv().await {
+                let StreamEvent::TextDelta { text } = event else { continue };
+                if !announced {
+                    pending.push_str(&text);
+                    if could_be_tools_marker(&pending) {
+                        continue;
+                    }
+                    self.announce_quick_answer(app_handle, instructions, &conversation.id, mode_str);
+                    announced = true;
+                }
+                let delta = if pending.is_empty() { text } else { std::mem::take(&mut pending) };
+                let _ = app_handle.emit("agent-stream", serde_json::json!({
+                    "type": "text_delta",
+                    "text": delta
+                }));
+            }
+        };
+        let (result, ()) = tokio::join!(client.send_chat_streaming(messages, event_tx), consume);
+
+        let result = match result {
+            Ok(result) => result,
+            Err(e) if !announced => {
+                println!("[agent] Quick answer failed ({}), falling back", e);
+                self.running.store(false, Ordering::SeqCst);
+                return false;
+            }
+            Err(e) => {
+                println!("[agent] Quick answer failed mid-stream: {}", e);
+                self.emit(app_handle, "error", &e.to_string(), None, None);
+                self.finish_quick_answer(app_handle);
+                return true;
+            }
+        };
+
+        let text = result
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !announced && text.trim_start().starts_with(crate::api::NEEDS_TOOLS_MARKER) {
+            println!("[agent] Model asked for tools");
+            self.running.store(false, Ordering::SeqCst);
+            return false;
+        }
+        if !announced {
+            // reply was too short to ever leave the marker check (e.g. empty)
+            self.announce_quick_answer(app_handle, instructions, &conversation.id, mode_str);
+        }
+
+        self.emit(app_handle, "response", &text, Some(serde_json::json!({ "model": model })), None);
+        let _ = app_handle.emit("agent:message", ());
+
+        conversation.add_message(user_message);
+        conversation.add_message(Message {
+            role: "assistant".to_string(),
+            content: result.content,
+        });
+        conversation.add_usage(result.usage, model);
+        let summary = conversation.usage_summary();
+        self.emit(app_handle, "usage", &format!("${:.4}", summary.cost_usd),
+            serde_json::to_value(&summary).ok(), None);
+        conversation.auto_title();
+        conversation.record_run(started.elapsed());
+        if let Err(e) = storage::save_conversation(&conversation) {
+            println!("[agent] Failed to save conversation: {}", e);
+        }
+
+        self.emit(app_handle, "finished", "Task completed", None, None);
+        self.finish_quick_answer(app_handle);
+        spawn_auto_title(&conversation, api_key, app_handle);
+        true
+    }
+
+    // same start-of-run events as the full agent, minus the border overlay
+    fn announce_quick_answer(&self, app_handle: &AppHandle, instructions: &str, conversation_id: &str, mode_str: &str) {
+        let _ = app_handle.emit("agent:conversation_id", conversation_id);
+        self.emit_full(app_handle, "started", "Agent started", None, None, None, Some(mode_str.to_string()));
+        let _ = app_handle.emit("agent:started", ());
+        let _ = app_handle.emit("agent-update", AgentUpdate {
+            update_type: "user_message".to_string(),
+            message: instructions.to_string(),
+            tool_name: None,
+            tool_input: None,
+            action: None,
+            screenshot: None,
+            bash_command: None,
+            exit_code: None,
+            mode: None,
+        });
+    }
+
+    fn finish_quick_answer(&self, app_handle: &AppHandle) {
+        self.running.store(false, Ordering::SeqCst);
+        let _ = app_handle.emit("agent:stopped", ());
+    }
+
+    /// Build a plan for the request (one tool-less API turn, falling back to the cognitive
+    /// planner) and emit it as a "plan_preview" update. Never executes anything.
+    async fn preview_plan(
+        &self,
+        instructions: &str,
+        model: &str,
+        mode: AgentMode,
+        api_key: &str,
+        app_handle: &AppHandle,
+    ) -> Result<(), AgentError> {
+        let mode_str = match mode {
+            AgentMode::Computer => "computer",
+            AgentMode::Browser => "browser",
+        };
+        let system = format!(
+            "You are planning a task that an agent will later carry out in {} mode. \
+             Do not perform the task. Reply with only a numbered list of the concrete steps \
+             you would take (one short line each, at most 12 steps), noting any step that \
+             sends, deletes, purchases or otherwise can't be undone.",
+            mode_str
+        );
+        // the plan should respect the user's standing instructions, and the preview shows them
+        let preamble = crate::api::system_preamble();
+        let system = match &preamble {
+            Some(preamble) => format!("{}\n\n{}", preamble, system),
+            None => system,
+        };
+
+        let client = AnthropicClient::new(api_key.to_string(), model.to_string());
+        let request = vec![Message {
+            role: "user".to_string(),
+            content: vec![ContentBlock::Text { text: instructions.to_string() }],
+        }];
+
+        let mut steps = match client.complete(Some(system), request, None).await {
+            Ok(result) => {
+                let text: String = result
+                    .content
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                parse_plan_steps(&text)
+            }
+            Err(e) => {
+                println!("[agent] Plan preview API turn failed: {}", e);
+                Vec::new()
+            }
+        };
+
+        if steps.is_empty() {
+            let mut cognitive = self.cognitive.lock().await;
+            match cognitive.plan_request(instructions).await {
+                Ok(task) => steps = task.subtasks.iter().map(|s| s.description.clone()).collect(),
+                Err(e) => println!("[agent] Cognitive plan preview failed: {}", e),
+            }
+        }
+
+        if steps.is_empty() {
+            self.emit(app_handle, "error", "Couldn't build a plan for this request", None, None);
+            return Ok(());
+        }
+
+        println!("[agent] Plan preview with {} steps", steps.len());
+        let message = steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| format!("{}. {}", i + 1, step))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.emit_full(
+            app_handle,
+            "plan_preview",
+            &message,
+            Some(serde_json::json!({
+                "instructions": instructions,
+                "steps": steps,
+                "preamble": preamble,
+            })),
+            None,
+            None,
+            Some(mode_str.to_string()),
+        );
+        Ok(())
+    }
+
+    /// reverse the most recent computer action when it's a simple one (move, scroll, typing)
+    pub async fn undo_last_action(&self, app_handle: &AppHandle) -> Result<String, String> {
+        let mut history = self.action_history.lock().await;
+        let recorded = history.last().cloned().ok_or("Nothing to undo")?;
+        let inverse = crate::computer::inverse_actions(&recorded)
+            .map_err(|reason| format!("Can't undo the last action: {}", reason))?;
+
+        let (screen_w, screen_h) = {
+            let computer_guard = self.computer.lock().await;
+            let computer = computer_guard.as_ref().ok_or("Nothing to undo")?;
+            (computer.screen_width, computer.screen_height)
+        };
+        tokio::task::spawn_blocking(move || {
+            let computer = ComputerControl::with_dimensions(screen_w, screen_h);
+            inverse.iter().try_for_each(|action| computer.perform_action(action).map(|_| ()))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Undo failed: {}", e))?;
+        history.pop();
+
+        let message = format!("Undid {}", recorded.action.action.replace('_', " "));
+        println!("[agent] {}", message);
+        self.emit(
+            app_handle,
+            "undo",
+            &message,
+            Some(serde_json::json!({ "action": recorded.action.action })),
+            None,
+        );
+        Ok(message)
+    }
+
+    // full screenshot with the app's own windows hidden
+    async fn capture_screen(&self) -> Result<String, String> {
+        // must run on main thread for Panel access on macOS
+        #[cfg(target_os = "macos")]
+        {
+            crate::panels::take_screenshot_excluding_app()
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let computer_guard = self.computer.lock().await;
+            let computer = computer_guard.as_ref().ok_or("computer control not initialized")?;
+            computer.take_screenshot().map_err(|e| e.to_string())
+        }
+    }
+
+    /// a blank capture means screen recording was revoked mid-run. rather than act on a black
+    /// screen, hold the run until a capture shows the screen again; None when the user stops
+    /// or it never comes back, and the caller then ends the run after this step
+    async fn await_screen_access(&self, app_handle: &AppHandle, capture: String) -> Option<String> {
+        if !crate::computer::is_blank_frame(&capture) {
+            return Some(capture);
+        }
+
+        warn!("screenshot came back blank, pausing until screen recording is available");
+        self.emit(
+            app_handle,
+            "permission_revoked",
+            "Screen recording was turned off, so Hey work can't see the screen. Allow it again in System Settings > Privacy & Security > Screen Recording and the task picks up where it left off.",
+            Some(serde_json::json!({ "permission": "screenRecording" })),
+            None,
+        );
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(SCREEN_ACCESS_WAIT_SECS);
+        while self.running.load(Ordering::SeqCst) && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(tokio::time::Duration::from_millis(SCREEN_ACCESS_POLL_MS)).await;
+            if let Ok(capture) = self.capture_screen().await {
+                if !crate::computer::is_blank_frame(&capture) {
+                    info!("screen capture is back, resuming");
+                    self.emit(app_handle, "status", "Screen recording is back - resuming", None, None);
+                    return Some(capture);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// perform a stored conversation's replayable steps again, in order and without the model,
+    /// after one confirmation for the whole list. stops at the first step that fails
+    pub async fn replay(&self, title: &str, steps: Vec<crate::replay::ReplayStep>, app_handle: AppHandle) -> Result<usize, String> {
+        let steps: Vec<_> = steps.into_iter().filter(|step| step.replayed).collect();
+        if steps.is_empty() {
+            return Err("Nothing to replay - the conversation has no actions".to_string());
+        }
+
+        self.running.store(true, Ordering::SeqCst);
+        let approved = crate::confirm::request_confirmation(
+            &app_handle,
+            &self.running,
+            crate::confirm::ConfirmationRequest {
+                tool: "replay".to_string(),
+                summary: format!("Replay {} actions from \"{}\"", steps.len(), title),
+                reason: "repeats the recorded clicks, typing, commands and page actions for real".to_string(),
+                screenshot: None,
+            },
+        )
+        .await;
+        if !approved {
+            self.running.store(false, Ordering::SeqCst);
+            return Err("Replay declined".to_string());
+        }
+
+        self.action_history.lock().await.clear();
+        self.emit(&app_handle, "started", &format!("Replaying {} actions", steps.len()), None, None);
+        let _ = app_handle.emit("agent:started", ());
+        let _ = app_handle.emit("border:show", ());
+
+        let mut done = 0;
+        let mut failure: Option<String> = None;
+        for step in &steps {
+            if !self.running.load(Ordering::SeqCst) {
+                failure = Some("Stopped".to_string());
+                break;
+            }
+            if !crate::tool_policy::ToolPolicy::current().allows(&step.tool) {
+                failure = Some(format!("Step {} uses {}, which is disabled in Settings", step.index, step.tool));
+                break;
+            }
+            println!("[agent] Replay step {}: {}", step.index, step.summary);
+            self.emit_tool(&app_handle, &step.tool, step.input.clone());
+            let result = match step.tool.as_str() {
+                "computer" => self.replay_computer_action(&step.input).await,
+                "bash" => self.replay_bash(&step.input, &app_handle).await,
+                _ => self.replay_browser_tool(&step.tool, &step.input).await,
+            };
+            if let Err(e) = result {
+                failure = Some(format!("Step {} ({}) failed: {}", step.index, step.summary, e));
+                break;
+            }
+            done += 1;
+            tokio::time::sleep(tokio::time::Duration::from_millis(REPLAY_STEP_DELAY_MS)).await;
+        }
+
+        match &failure {
+            Some(message) => self.emit(&app_handle, "error", message, None, None),
+            None => self.emit(&app_handle, "finished", &format!("Replayed {} actions", done), None, None),
+        }
+        self.running.store(false, Ordering::SeqCst);
+        let _ = app_handle.emit("agent:stopped", ());
+        let _ = app_handle.emit("border:hide", ());
+        match failure {
+            Some(message) => Err(message),
+            None => Ok(done),
+        }
+    }
+
+    async fn replay_computer_action(&self, input: &serde_json::Value) -> Result<(), String> {
+        let action: ComputerAction = serde_json::from_value(input.clone()).map_err(|e| format!("bad action: {}", e))?;
+        let (screen_w, screen_h) = {
+            let mut computer_guard = self.computer.lock().await;
+            if computer_guard.is_none() {
+                *computer_guard = Some(ComputerControl::new().map_err(|e| format!("Computer init failed: {}", e))?);
+            }
+            let computer = computer_guard.as_ref().unwrap();
+            (computer.screen_width, computer.screen_height)
+        };
+        let action_clone = action.clone();
+        let (cursor_before, result) = tokio::task::spawn_blocking(move || {
+            let computer = ComputerControl::with_dimensions(screen_w, screen_h);
+            (computer.cursor_position(), computer.perform_action(&action_clone))
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        result.map_err(|e| e.to_string())?;
+        // replayed actions undo like any other
+        self.action_history.lock().await.record(action, cursor_before);
         Ok(())
     }
 
+    async fn replay_bash(&self, input: &serde_json::Value, app_handle: &AppHandle) -> Result<(), String> {
+        let command = input.get("command").and_then(|v| v.as_str()).ok_or("no command")?;
+        let _ = app_handle.emit("agent:bash", serde_json::json!({ "command": command }));
+        let mut bash = self.bash.lock().await;
+        let result = bash.execute(command);
+        let cwd_action = Some(serde_json::json!({ "cwd": bash.cwd() }));
+        drop(bash);
+        match result {
+            Ok(out) => {
+                let code = out.exit_code;
+                self.emit_with_exit_code(app_handle, "bash_result", &out.to_string(), cwd_action, None, Some(code));
+                Ok(())
+            }
+            Err(e) => {
+                let err_msg = format!("Error: {}", e);
+                self.emit_with_exit_code(app_handle, "bash_result", &err_msg, cwd_action, None, Some(-1));
+                Err(e.to_string())
+            }
+        }
+    }
+
+    async fn replay_browser_tool(&self, name: &str, input: &serde_json::Value) -> Result<(), String> {
+        let mut browser_guard = self.browser_client.lock().await;
+        if browser_guard.is_none() {
+            let client = BrowserClient::connect_with(crate::browser::selected_browser_kind())
+                .await
+                .map_err(|e| format!("Browser connection failed: {}", e))?;
+            *browser_guard = Some(client);
+        }
+        let browser = browser_guard.as_mut().unwrap();
+        execute_browser_tool(browser, name, input).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    // with risky-click confirmation on, read the text around a click and hold it for the user's
+    // ok when it matches a risk keyword. true when the click may go ahead - including when OCR
+    // isn't available, so a missing tesseract doesn't block every click
+    async fn approve_risky_click(&self, app_handle: &AppHandle, tool: &str, at: Option<[i32; 2]>) -> bool {
+        let settings = crate::confirm::ConfirmSettings::current();
+        // with every click confirmed already there's nothing to add
+        if !settings.risky_clicks || settings.computer || settings.risk_keywords.is_empty() {
+            return true;
+        }
+        let (screen_w, screen_h) = {
+            let computer_guard = self.computer.lock().await;
+            match computer_guard.as_ref() {
+                Some(computer) => (computer.screen_width, computer.screen_height),
+                None => return true,
+            }
+        };
+        let keywords = settings.risk_keywords;
+        let target = tokio::task::spawn_blocking(move || {
+            ComputerControl::with_dimensions(screen_w, screen_h).risky_click_target(at, &keywords)
+        })
+        .await;
+        let (label, crop) = match target {
+            Ok(Ok(Some(target))) => target,
+            Ok(Ok(None)) => return true,
+            Ok(Err(e)) => {
+                warn!("risky click check failed: {}", e);
+                return true;
+            }
+            Err(e) => {
+                warn!("risky click check panicked: {}", e);
+                return true;
+            }
+        };
+        let summary = match at {
+            Some([x, y]) => format!("click \"{}\" at ({}, {})", label, x, y),
+            None => format!("click \"{}\"", label),
+        };
+        crate::confirm::request_confirmation(
+            app_handle,
+            &self.running,
+            crate::confirm::ConfirmationRequest {
+                tool: tool.to_string(),
+                summary,
+                reason: "clicks a button that may not be undoable".to_string(),
+                screenshot: Some(crop),
+            },
+        )
+        .await
+    }
+
+    // the browser counterpart of approve_risky_click: the element's accessible name stands in
+    // for the OCR'd text. true when the click may go ahead
+    async fn approve_risky_element_click(&self, app_handle: &AppHandle, element_name: &str) -> bool {
+        let settings = crate::confirm::ConfirmSettings::current();
+        if !settings.risky_clicks || !crate::confirm::is_risky_text(element_name, &settings.risk_keywords) {
+            return true;
+        }
+        crate::confirm::request_confirmation(
+            app_handle,
+            &self.running,
+            crate::confirm::ConfirmationRequest {
+                tool: "page_action".to_string(),
+                summary: format!("click \"{}\"", element_name.trim()),
+                reason: "clicks a button that may not be undoable".to_string(),
+                screenshot: None,
+            },
+        )
+        .await
+    }
+
     fn emit(
         &self,
         app_handle: &AppHandle,
@@ -1297,10 +2696,41 @@ impl Agent {
     }
 }
 
+// how often the swarm poller re-sends progress when counts haven't changed (keeps the ETA live)
+const SWARM_PROGRESS_INTERVAL_MS: u64 = 2000;
+// how often the swarm wait looks at the stop flag and timeout, neither of which needs the lock
+const SWARM_STOP_CHECK_MS: u64 = 250;
+// share of the fallback poll added as random jitter, so it doesn't line up with executor work
+const SWARM_POLL_JITTER: f64 = 0.2;
+
+// the safety-net re-read of the swarm task when no change was signalled. it doesn't go below the
+// progress interval, so every fallback read sends the ETA
+fn swarm_fallback_poll() -> std::time::Duration {
+    let jitter = rand::random::<f64>() * SWARM_POLL_JITTER;
+    std::time::Duration::from_millis((SWARM_PROGRESS_INTERVAL_MS as f64 * (1.0 + jitter)) as u64)
+}
+
+// pause before re-snapshotting after a uid lookup fails, so a re-render can settle
+const ELEMENT_RETRY_DELAY_MS: u64 = 500;
+
+// how long a run waits for screen recording to be re-granted, and how often it looks
+const SCREEN_ACCESS_WAIT_SECS: u64 = 600;
+const SCREEN_ACCESS_POLL_MS: u64 = 2000;
+
+// pause between replayed steps, so each one's effect lands before the next acts on it
+const REPLAY_STEP_DELAY_MS: u64 = 400;
+
+// asked once a conversation is a few messages in; the heuristic title is just the first message
+const TITLE_PROMPT: &str = "Write a title of 4 to 6 words for the task in this conversation - what the user actually wanted done, not how the conversation opened. Reply with only the title, no quotes or punctuation at the end.";
+// past this the title request is dropped and tried again after the next run
+const TITLE_TIMEOUT_SECS: u64 = 10;
+
 const BROWSER_TOOLS: &[&str] = &[
     "see_page",
     "page_action",
     "browser_navigate",
+    "browser_download",
+    "autofill_login",
 ];
 
 fn is_browser_tool(name: &str) -> bool {
@@ -1311,15 +2741,18 @@ async fn execute_browser_tool(
     browser: &mut BrowserClient,
     name: &str,
     input: &serde_json::Value,
-) -> anyhow::Result<String> {
+) -> Result<String, BrowserError> {
     match name {
-        // see_page: observe the page (elements, screenshot, or tabs)
+        // see_page: observe the page (elements, screenshot, readable text, or tabs)
         "see_page" => {
             if input.get("screenshot").and_then(|v| v.as_bool()).unwrap_or(false) {
                 // screenshot handled separately in agent loop (returns image)
-                Err(anyhow::anyhow!("screenshot"))
+                Err(BrowserError::Cdp("screenshot".into()))
             } else if input.get("list_tabs").and_then(|v| v.as_bool()).unwrap_or(false) {
                 browser.list_pages().await
+            } else if input.get("read_page").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let max_chars = input.get("max_chars").and_then(|v| v.as_u64()).unwrap_or(12000);
+                browser.read_page(max_chars as usize).await
             } else {
                 // default: get elements
                 let verbose = input.get("verbose").and_then(|v| v.as_bool()).unwrap_or(false);
@@ -1330,21 +2763,23 @@ async fn execute_browser_tool(
         // page_action: interact with elements
         "page_action" => {
             if let Some(uid) = input.get("click").and_then(|v| v.as_str()) {
-                browser.click(uid, false).await
+                browser.element_action(uid, ElementAction::Click).await
             } else if let Some(uid) = input.get("double_click").and_then(|v| v.as_str()) {
-                browser.click(uid, true).await
+                browser.element_action(uid, ElementAction::DoubleClick).await
             } else if let Some(uid) = input.get("type_into").and_then(|v| v.as_str()) {
                 let text = input.get("text").and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow::anyhow!("text required with type_into"))?;
-                browser.fill(uid, text).await
+                    .ok_or_else(|| BrowserError::Cdp("text required with type_into".into()))?;
+                browser.element_action(uid, ElementAction::Fill(text)).await
             } else if let Some(uid) = input.get("hover").and_then(|v| v.as_str()) {
-                browser.hover(uid).await
+                browser.element_action(uid, ElementAction::Hover).await
+            } else if let Some(uid) = input.get("scroll_to").and_then(|v| v.as_str()) {
+                browser.element_action(uid, ElementAction::ScrollTo).await
             } else if let Some(arr) = input.get("drag_from_to").and_then(|v| v.as_array()) {
                 if arr.len() != 2 {
-                    return Err(anyhow::anyhow!("drag_from_to requires exactly 2 elements: [from, to]"));
+                    return Err(BrowserError::Cdp("drag_from_to requires exactly 2 elements: [from, to]".into()));
                 }
-                let from = arr[0].as_str().ok_or_else(|| anyhow::anyhow!("from element must be string"))?;
-                let to = arr[1].as_str().ok_or_else(|| anyhow::anyhow!("to element must be string"))?;
+                let from = arr[0].as_str().ok_or_else(|| BrowserError::Cdp("from element must be string".into()))?;
+                let to = arr[1].as_str().ok_or_else(|| BrowserError::Cdp("to element must be string".into()))?;
                 browser.drag(from, to).await
             } else if let Some(key) = input.get("press_key").and_then(|v| v.as_str()) {
                 browser.press_key(key).await
@@ -1358,12 +2793,18 @@ async fn execute_browser_tool(
                     Some((element.to_string(), text.to_string()))
                 }).collect();
                 browser.fill_form(&pairs).await
+            } else if let Some(upload) = input.get("upload_file") {
+                let uid = upload.get("element").and_then(|v| v.as_str())
+                    .ok_or_else(|| BrowserError::Cdp("upload_file requires element".into()))?;
+                let path = upload.get("path").and_then(|v| v.as_str())
+                    .ok_or_else(|| BrowserError::Cdp("upload_file requires path".into()))?;
+                browser.upload_file(uid, path).await
             } else if let Some(action) = input.get("dialog").and_then(|v| v.as_str()) {
                 let accept = action == "accept";
                 let dialog_text = input.get("dialog_text").and_then(|v| v.as_str());
                 browser.handle_dialog(accept, dialog_text).await
             } else {
-                Err(anyhow::anyhow!("page_action requires one of: click, double_click, type_into, hover, drag_from_to, press_key, scroll, fill_form, dialog"))
+                Err(BrowserError::Cdp("page_action requires one of: click, double_click, type_into, hover, scroll_to, drag_from_to, press_key, scroll, fill_form, upload_file, dialog".into()))
             }
         }
 
@@ -1390,11 +2831,14 @@ async fn execute_browser_tool(
                 let timeout = input.get("wait_timeout_ms").and_then(|v| v.as_u64()).unwrap_or(5000);
                 browser.wait_for(text, timeout).await
             } else {
-                Err(anyhow::anyhow!("browser_navigate requires one of: go_to_url, go_back, go_forward, reload, reload_skip_cache, open_new_tab, switch_to_tab, close_tab, wait_for_text"))
+                Err(BrowserError::Cdp("browser_navigate requires one of: go_to_url, go_back, go_forward, reload, reload_skip_cache, open_new_tab, switch_to_tab, close_tab, wait_for_text".into()))
             }
         }
 
-        _ => Err(anyhow::anyhow!("unknown browser tool: {}", name)),
+        // autofill_login: saved credentials go into the page without passing through the model
+        "autofill_login" => browser.autofill_login().await,
+
+        _ => Err(BrowserError::Cdp(format!("unknown browser tool: {}", name))),
     }
 }
 
@@ -1402,14 +2846,14 @@ async fn execute_browser_tool(
 fn handle_swarm_event(event: SwarmEvent, app_handle: &AppHandle) {
     match event {
         SwarmEvent::TaskStarted { task_id, description } => {
-            println!("[swarm] Task {} started: {}", task_id, description);
+            info!(task_id = %task_id, "swarm task started: {}", description);
             let _ = app_handle.emit("swarm:task_started", serde_json::json!({
                 "task_id": task_id,
                 "description": description
             }));
         }
         SwarmEvent::SubTaskStarted { task_id, subtask_id, agent } => {
-            println!("[swarm] Subtask {} started with {:?}", subtask_id, agent);
+            info!(task_id = %task_id, subtask_id = %subtask_id, agent = ?agent, "swarm subtask started");
             let _ = app_handle.emit("swarm:subtask_started", serde_json::json!({
                 "task_id": task_id,
                 "subtask_id": subtask_id,
@@ -1417,7 +2861,7 @@ fn handle_swarm_event(event: SwarmEvent, app_handle: &AppHandle) {
             }));
         }
         SwarmEvent::SubTaskCompleted { task_id, subtask_id, result } => {
-            println!("[swarm] Subtask {} completed: {}", subtask_id, result.output);
+            info!(task_id = %task_id, subtask_id = %subtask_id, "swarm subtask completed: {}", result.output);
             let _ = app_handle.emit("swarm:subtask_completed", serde_json::json!({
                 "task_id": task_id,
                 "subtask_id": subtask_id,
@@ -1425,24 +2869,27 @@ fn handle_swarm_event(event: SwarmEvent, app_handle: &AppHandle) {
             }));
         }
         SwarmEvent::SubTaskFailed { task_id, subtask_id, error } => {
-            println!("[swarm] Subtask {} failed: {}", subtask_id, error);
+            warn!(task_id = %task_id, subtask_id = %subtask_id, "swarm subtask failed: {}", error);
             let _ = app_handle.emit("swarm:subtask_failed", serde_json::json!({
                 "task_id": task_id,
                 "subtask_id": subtask_id,
                 "error": error
             }));
         }
-        SwarmEvent::VerificationCompleted { task_id, subtask_id, passed, score } => {
-            println!("[swarm] Verification {}: passed={}, score={}", subtask_id, passed, score);
+        SwarmEvent::VerificationCompleted { task_id, subtask_id, result } => {
+            info!(task_id = %task_id, subtask_id = %subtask_id, passed = result.passed, score = result.score, pixel_diff = ?result.pixel_diff, "swarm verification");
             let _ = app_handle.emit("swarm:verification", serde_json::json!({
                 "task_id": task_id,
                 "subtask_id": subtask_id,
-                "passed": passed,
-                "score": score
+                "passed": result.passed,
+                "score": result.score,
+                "pixel_diff": result.pixel_diff,
+                "before_screenshot": result.before_screenshot,
+                "after_screenshot": result.after_screenshot,
             }));
         }
         SwarmEvent::RecoveryAttempt { task_id, subtask_id, strategy } => {
-            println!("[swarm] Recovery for {}: {}", subtask_id, strategy);
+            info!(task_id = %task_id, subtask_id = %subtask_id, "swarm recovery: {}", strategy);
             let _ = app_handle.emit("swarm:recovery", serde_json::json!({
                 "task_id": task_id,
                 "subtask_id": subtask_id,
@@ -1450,7 +2897,7 @@ fn handle_swarm_event(event: SwarmEvent, app_handle: &AppHandle) {
             }));
         }
         SwarmEvent::TaskCompleted { task_id, success } => {
-            println!("[swarm] Task {} completed: success={}", task_id, success);
+            info!(task_id = %task_id, success, "swarm task completed");
             let _ = app_handle.emit("swarm:task_completed", serde_json::json!({
                 "task_id": task_id,
                 "success": success
@@ -1460,6 +2907,148 @@ fn handle_swarm_event(event: SwarmEvent, app_handle: &AppHandle) {
     }
 }
 
+/// drop thinking blocks from earlier assistant turns (kept when they're all a message has)
+fn strip_thinking(messages: &mut [Message]) {
+    let is_thinking = |b: &ContentBlock| matches!(b, ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. });
+    for message in messages.iter_mut().filter(|m| m.role == "assistant") {
+        if message.content.iter().any(|b| !is_thinking(b)) {
+            message.content.retain(|b| !is_thinking(b));
+        }
+    }
+}
+
+// once a conversation is a few messages in, swap the first-message title for one the light
+// model writes about the actual task. runs after the conversation is saved and the run has
+// ended, so nothing waits on it; a failed or unusable reply keeps the heuristic title until
+// the next run, and a title the user set is never touched
+fn spawn_auto_title(conversation: &Conversation, api_key: &str, app_handle: &AppHandle) {
+    if !conversation.wants_generated_title() {
+        return;
+    }
+    let id = conversation.id.clone();
+    let model = crate::cognitive::agent_swarm::DEFAULT_LIGHT_MODEL;
+    let client = AnthropicClient::new(api_key.to_string(), model.to_string());
+    let request = vec![Message {
+        role: "user".to_string(),
+        content: vec![ContentBlock::Text {
+            text: format!("{}\n\n<conversation>\n{}</conversation>", TITLE_PROMPT, conversation.title_excerpt()),
+        }],
+    }];
+    let app_handle = app_handle.clone();
+    tokio::spawn(async move {
+        let timeout = std::time::Duration::from_secs(TITLE_TIMEOUT_SECS);
+        let result = match tokio::time::timeout(timeout, client.complete(None, request, None)).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => {
+                println!("[agent] Title generation failed: {}", e);
+                return;
+            }
+            Err(_) => {
+                println!("[agent] Title generation timed out");
+                return;
+            }
+        };
+        let reply: String = result
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let title = storage::parse_generated_title(&reply);
+        if title.is_none() {
+            println!("[agent] Unusable title reply: {}", reply.trim());
+        }
+        match storage::record_generated_title(&id, title, result.usage, model) {
+            Ok(Some(title)) => {
+                println!("[agent] Titled conversation {}: {}", id, title);
+                let _ = app_handle.emit("conversation:titled", serde_json::json!({ "id": id, "title": title }));
+            }
+            Ok(None) => {}
+            Err(e) => println!("[agent] Failed to save generated title: {}", e),
+        }
+    });
+}
+
+/// load the conversation being continued, or start a new one
+fn load_or_create_conversation(conversation_id: Option<&str>, model: &str, mode_str: &str) -> Conversation {
+    if let Some(conv_id) = conversation_id {
+        match storage::load_conversation(conv_id) {
+            Ok(Some(conv)) => {
+                println!("[agent] Loaded existing conversation: {}", conv_id);
+                return conv;
+            }
+            Ok(None) => println!("[agent] Conversation {} not found, creating new", conv_id),
+            Err(e) => println!("[agent] Failed to load conversation {}: {}, creating new", conv_id, e),
+        }
+    }
+    Conversation::new(
+        uuid::Uuid::new_v4().to_string(),
+        "New Conversation".to_string(),
+        model.to_string(),
+        mode_str.to_string(),
+    )
+}
+
+/// Whether the turn asked for tools we have to run. Server tools (web search / fetch)
+/// already came back with their results inside the same response.
+fn requests_local_tools(content: &[ContentBlock]) -> bool {
+    content.iter().any(|b| matches!(b, ContentBlock::ToolUse { .. }))
+}
+
+/// Obvious Q&A ("what's the capital of France") that needs no computer control.
+/// Anything mentioning the screen, files, apps, the web or current events stays a task;
+/// the model can still bounce a misclassified request back with NEEDS_TOOLS_MARKER.
+fn is_conversational(instructions: &str) -> bool {
+    let lower = instructions.trim().to_lowercase();
+    if lower.is_empty() || lower.len() > 200 || lower.lines().count() > 2 {
+        return false;
+    }
+
+    // matched against the whole first word, so "hide", "highlight" or "whatsapp" don't count
+    let question_words = [
+        "what", "what's", "whats", "who", "who's", "why", "how", "how's", "when", "where", "where's",
+        "which", "is", "are", "does", "do", "explain", "define", "translate", "hi", "hello", "hey",
+        "thanks",
+    ];
+    let question_phrases = [
+        "can you explain", "tell me about", "tell me a", "summarize the concept", "give me an example",
+        "thank you",
+    ];
+    let first_word = lower
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .trim_end_matches(|c: char| !c.is_alphanumeric());
+    let is_question = lower.ends_with('?')
+        || question_words.contains(&first_word)
+        || question_phrases.iter().any(|q| lower.starts_with(q));
+    if !is_question {
+        return false;
+    }
+
+    // words that mean the answer lives on this machine, the web, or right now
+    let needs_tools = [
+        "screen", "click", "open", "close", "window", "tab", "app", "file", "files", "folder", "desktop",
+        "download", "install", "run", "type", "email", "mail", "send", "search", "browse", "website",
+        "browser", "my", "this", "these", "here", "current", "today", "tonight", "tomorrow",
+        "yesterday", "latest", "news", "weather", "price", "stock", "score", "now", "recent",
+        "calendar", "remind", "schedule", "spotify", "chrome", "safari",
+    ];
+    let mentions_link = lower.contains("http") || lower.contains("www.") || lower.contains(".com");
+    !mentions_link
+        && !lower
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| needs_tools.contains(&word))
+}
+
+/// Could `text` still turn out to be the needs-tools marker? Holds back streaming until it can't.
+fn could_be_tools_marker(text: &str) -> bool {
+    let text = text.trim_start();
+    crate::api::NEEDS_TOOLS_MARKER.starts_with(text) || text.starts_with(crate::api::NEEDS_TOOLS_MARKER)
+}
+
 /// Check if a task is a simple quick task that doesn't need cognitive analysis
 fn is_simple_quick_task(instructions: &str) -> bool {
     let lower = instructions.to_lowercase().trim().to_string();
@@ -1496,6 +3085,23 @@ fn is_simple_quick_task(instructions: &str) -> bool {
     is_simple_bash
 }
 
+/// Pull plan steps out of a numbered or bulleted list
+fn parse_plan_steps(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+            let step = if rest.len() < line.len() {
+                rest.strip_prefix('.').or_else(|| rest.strip_prefix(')'))?
+            } else {
+                line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))?
+            };
+            let step = step.trim();
+            (!step.is_empty()).then(|| step.to_string())
+        })
+        .collect()
+}
+
 /// Check if a task is complex and should use Agent Swarm
 fn is_complex_task(instructions: &str) -> bool {
     // Only delegate to swarm for truly multi-agent parallel tasks.
@@ -1514,67 +3120,6 @@ fn is_complex_task(instructions: &str) -> bool {
     swarm_keywords.iter().any(|&kw| lower.contains(kw))
 }
 
-// summarize old snapshots to reduce context size
-// keeps only interactive elements (links, buttons, inputs, headings)
-fn summarize_old_snapshots(messages: &mut Vec<Message>) {
-    for message in messages.iter_mut() {
-        if message.role != "user" {
-            continue;
-        }
-
-        for block in message.content.iter_mut() {
-            if let ContentBlock::ToolResult { content, .. } = block {
-                for item in content.iter_mut() {
-                    if let ToolResultContent::Text { text } = item {
-                        // check if it's a snapshot (starts with uid=)
-                        if text.starts_with("uid=") && text.len() > 5000 {
-                            *text = summarize_snapshot(text);
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
-fn summarize_snapshot(snapshot: &str) -> String {
-    // keep only lines with interactive roles
-    let interactive_roles = [
-        "link", "button", "textbox", "checkbox", "radio", "combobox",
-        "searchbox", "slider", "switch", "menuitem", "tab", "heading",
-        "WebArea", // keep the root
-    ];
-
-    let mut summary_lines: Vec<&str> = Vec::new();
-    let mut kept_count = 0;
-    let mut total_count = 0;
-
-    for line in snapshot.lines() {
-        total_count += 1;
-        let trimmed = line.trim();
-
-        // keep line if it contains any interactive role
-        let should_keep = interactive_roles.iter().any(|role| {
-            // match "uid=X_Y role" pattern
-            trimmed.contains(&format!(" {} ", role)) ||
-            trimmed.contains(&format!(" {} \"", role)) ||
-            trimmed.ends_with(&format!(" {}", role))
-        });
-
-        if should_keep {
-            summary_lines.push(line);
-            kept_count += 1;
-        }
-    }
-
-    let header = format!(
-        "[snapshot summarized: {} interactive elements from {} total]\n",
-        kept_count, total_count
-    );
-
-    header + &summary_lines.join("\n")
-}
-
 /// Execute Python code for document generation and data processing
 async fn execute_python_code(code: &str, save_to: Option<&str>) -> Result<String, String> {
     use tokio::process::Command;
@@ -1768,3 +3313,130 @@ print(json.dumps(result))
         Ok(stdout.to_string())
     }
 }
+
+/// when a tool call started and, once the next block begins, how long it took
+struct ToolTiming {
+    id: String,
+    name: String,
+    input: serde_json::Value,
+    started_at_ms: i64,
+    started: std::time::Instant,
+    elapsed: Option<std::time::Duration>,
+}
+
+impl ToolTiming {
+    fn start(id: &str, name: &str, input: &serde_json::Value) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            input: input.clone(),
+            started_at_ms: chrono::Utc::now().timestamp_millis(),
+            started: std::time::Instant::now(),
+            elapsed: None,
+        }
+    }
+}
+
+fn finish_last_timing(timings: &mut [ToolTiming]) {
+    if let Some(last) = timings.last_mut() {
+        if last.elapsed.is_none() {
+            last.elapsed = Some(last.started.elapsed());
+        }
+    }
+}
+
+/// pair each timed call with its result; a call that produced no result is recorded as failed
+fn audit_entries(timings: Vec<ToolTiming>, tool_results: &[ContentBlock]) -> Vec<storage::ToolCallEntry> {
+    timings
+        .into_iter()
+        .map(|timing| {
+            let content = tool_results.iter().find_map(|block| match block {
+                ContentBlock::ToolResult { tool_use_id, content } if *tool_use_id == timing.id => Some(content),
+                _ => None,
+            });
+            let output_summary = content.map_or_else(
+                || "(no result)".to_string(),
+                |content| {
+                    content
+                        .iter()
+                        .map(|part| match part {
+                            ToolResultContent::Text { text } => text.as_str(),
+                            ToolResultContent::Image { .. } => "[image]",
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                },
+            );
+            // tool branches report failures as "Error: ..." text
+            let success = content.is_some() && !output_summary.trim_start().starts_with("Error");
+            storage::ToolCallEntry {
+                tool_name: timing.name,
+                input: timing.input,
+                output_summary: storage::truncate_audit_output(&output_summary),
+                duration_ms: timing.elapsed.map(|d| d.as_millis() as u64),
+                success,
+                started_at_ms: timing.started_at_ms,
+                subtask_id: None,
+            }
+        })
+        .collect()
+}
+
+/// the swarm only records what each call returned, so calls are timed per subtask, not per call
+fn swarm_audit_entries(subtasks: &[crate::cognitive::agent_swarm::SubTask]) -> Vec<storage::ToolCallEntry> {
+    subtasks
+        .iter()
+        .filter_map(|st| st.result.as_ref().map(|result| (st, result)))
+        .flat_map(|(st, result)| {
+            result.tool_calls.iter().map(move |call| storage::ToolCallEntry {
+                tool_name: call.tool_name.clone(),
+                input: call.input.clone(),
+                output_summary: storage::truncate_audit_output(&call.output),
+                duration_ms: None,
+                success: result.success,
+                started_at_ms: call.timestamp.timestamp_millis(),
+                subtask_id: Some(st.id.clone()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversational_matches_whole_first_words() {
+        assert!(is_conversational("what is a monad"));
+        assert!(is_conversational("hey, how are you"));
+        assert!(is_conversational("Hi!"));
+        assert!(!is_conversational("hide the dock"));
+        assert!(!is_conversational("highlight the second paragraph"));
+        assert!(!is_conversational("whatsapp Sam that I'm late"));
+    }
+
+    #[test]
+    fn search_only_turn_needs_no_tool_results() {
+        let content = vec![
+            ContentBlock::ServerToolUse {
+                id: "srvtoolu_1".to_string(),
+                name: "web_search".to_string(),
+                input: serde_json::json!({ "query": "weather in Lisbon" }),
+            },
+            ContentBlock::WebSearchToolResult {
+                tool_use_id: "srvtoolu_1".to_string(),
+                content: serde_json::json!([]),
+            },
+            ContentBlock::Text { text: "It's sunny.".to_string() },
+        ];
+        assert!(!requests_local_tools(&content));
+
+        let mut with_local = content;
+        with_local.push(ContentBlock::ToolUse {
+            id: "toolu_1".to_string(),
+            name: "bash".to_string(),
+            input: serde_json::json!({ "command": "ls" }),
+        });
+        assert!(requests_local_tools(&with_local));
+    }
+}
@@ -0,0 +1,177 @@
+// opt-in ambient help: while the agent is idle, a low-res look at the screen every so often.
+// when the screen sits unchanged on what reads like an error, the app offers help. nothing is
+// captured while it's off, and captures are only compared and OCR'd locally - a screenshot
+// goes to the model only if the user takes the offer and asks
+
+use crate::computer::ComputerControl;
+use crate::run_guard::RunGuard;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use tauri::{AppHandle, Emitter};
+
+/// env vars (saved in .env): whether ambient help runs, and how often it looks
+pub const AMBIENT_HELP_ENV: &str = "HEYWORK_AMBIENT_HELP";
+pub const AMBIENT_INTERVAL_ENV: &str = "HEYWORK_AMBIENT_INTERVAL_SECS";
+const DEFAULT_INTERVAL_SECS: u64 = 30;
+const MIN_INTERVAL_SECS: u64 = 10;
+
+// captures in a row with the screen unchanged before it counts as stuck (a minute and a half
+// at the default interval)
+const STUCK_CAPTURES: u32 = 3;
+// changed fraction of the screen below which two captures count as the same screen - a
+// blinking caret or ticking clock stays under it
+const UNCHANGED_RATIO: f64 = 0.01;
+// phrases that mark a dialog or page as an error, matched case-insensitively per OCR line
+const ERROR_PHRASES: &[&str] = &[
+    "error",
+    "failed",
+    "failure",
+    "couldn't",
+    "could not",
+    "can't be",
+    "cannot be",
+    "unable to",
+    "not responding",
+    "exception",
+    "denied",
+    "something went wrong",
+    "try again",
+];
+// longest line passed on to the prompt
+const MAX_LINE_CHARS: usize = 120;
+// width captures are scaled down to before comparing and OCR - still enough for dialog text
+const CAPTURE_WIDTH: u32 = 1280;
+
+pub fn enabled() -> bool {
+    std::env::var(AMBIENT_HELP_ENV)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+pub fn interval() -> std::time::Duration {
+    let secs = std::env::var(AMBIENT_INTERVAL_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    std::time::Duration::from_secs(secs.max(MIN_INTERVAL_SECS))
+}
+
+/// the first recognized line that reads like an error message
+pub fn error_line(lines: &[String]) -> Option<String> {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| {
+            let lower = line.to_lowercase();
+            ERROR_PHRASES.iter().any(|phrase| lower.contains(phrase))
+        })
+        .map(|line| line.chars().take(MAX_LINE_CHARS).collect())
+}
+
+/// when successive captures of an idle screen are worth an OCR pass - once per stuck screen
+#[derive(Debug, Default)]
+pub struct StuckScreen {
+    unchanged: u32,
+    checked: bool,
+}
+
+impl StuckScreen {
+    /// feed whether the screen changed since the previous capture
+    pub fn observe(&mut self, changed: bool) -> bool {
+        if changed {
+            *self = Self::default();
+            return false;
+        }
+        self.unchanged += 1;
+        // the first capture of the run counts too, hence the + 1
+        if self.checked || self.unchanged + 1 < STUCK_CAPTURES {
+            return false;
+        }
+        self.checked = true;
+        true
+    }
+}
+
+fn capture() -> Option<String> {
+    let control = ComputerControl::new().ok()?;
+    control.take_screenshot_scaled(CAPTURE_WIDTH).ok()
+}
+
+fn recognize(capture: &str) -> Vec<String> {
+    let image = BASE64
+        .decode(capture)
+        .ok()
+        .and_then(|bytes| image::load_from_memory(&bytes).ok());
+    let Some(image) = image else {
+        return Vec::new();
+    };
+    crate::ocr::recognize_text(&image)
+        .map(|boxes| boxes.into_iter().map(|b| b.text).collect())
+        .unwrap_or_default()
+}
+
+/// the background loop; it checks the setting every tick, so toggling needs no restart
+pub fn spawn(app_handle: AppHandle, run_guard: RunGuard) {
+    tauri::async_runtime::spawn(async move {
+        let mut previous: Option<String> = None;
+        let mut stuck = StuckScreen::default();
+        loop {
+            tokio::time::sleep(interval()).await;
+            // nothing is captured while it's off, and the agent's own work isn't "stuck"
+            if !enabled() || run_guard.is_busy() {
+                previous = None;
+                stuck = StuckScreen::default();
+                continue;
+            }
+
+            let Ok(Some(current)) = tokio::task::spawn_blocking(capture).await else {
+                continue;
+            };
+            let worth_checking = match &previous {
+                Some(before) => {
+                    let changed = crate::computer::screenshot_change_ratio(before, &current)
+                        .map(|ratio| ratio > UNCHANGED_RATIO)
+                        .unwrap_or(true);
+                    stuck.observe(changed)
+                }
+                None => false,
+            };
+            if !worth_checking {
+                previous = Some(current);
+                continue;
+            }
+
+            let ocr_input = current.clone();
+            previous = Some(current);
+            let lines = tokio::task::spawn_blocking(move || recognize(&ocr_input)).await.unwrap_or_default();
+            if let Some(line) = error_line(&lines) {
+                println!("[ambient] Screen stuck on \"{}\", offering help", line);
+                let _ = app_handle.emit("ambient-help", serde_json::json!({ "text": line }));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_lines_are_picked_out() {
+        let lines = ["File  Edit  View", "  The operation couldn't be completed. (OSStatus -43) "]
+            .map(String::from);
+        assert_eq!(error_line(&lines).as_deref(), Some("The operation couldn't be completed. (OSStatus -43)"));
+        assert_eq!(error_line(&["Inbox (3)".to_string(), "Compose".to_string()]), None);
+    }
+
+    #[test]
+    fn a_still_screen_is_checked_once() {
+        let mut stuck = StuckScreen::default();
+        assert!(!stuck.observe(false));
+        assert!(stuck.observe(false));
+        // still the same screen - already looked at
+        assert!(!stuck.observe(false));
+        assert!(!stuck.observe(true));
+        assert!(!stuck.observe(false));
+        assert!(stuck.observe(false));
+    }
+}
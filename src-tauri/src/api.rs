@@ -1,5 +1,5 @@
 use crate::agent::AgentMode;
-use crate::rate_limiter::{RateLimiter, RateLimiterStats};
+use crate::rate_limiter::{ModelRateLimiter, RateLimiter, RateLimiterStats};
 use crate::storage::Usage;
 use futures_util::StreamExt;
 use reqwest::Client;
@@ -8,12 +8,12 @@ use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::mpsc;
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
 // computer-use-2025-01-24: enables computer_20250124 and bash_20250124 tools
 // interleaved-thinking-2025-05-14: enables extended thinking with tool use for Claude 4 models
 // context-management-2025-06-27: enables context editing (clear_tool_uses, clear_thinking)
 const BETA_HEADER: &str = "computer-use-2025-01-24,interleaved-thinking-2025-05-14,context-management-2025-06-27";
-const API_VERSION: &str = "2023-06-01";
+pub const API_VERSION: &str = "2023-06-01";
 
 /// display dimensions sent to claude for coordinate mapping.
 /// matches the resolution we resize screenshots to in computer.rs
@@ -24,16 +24,83 @@ const DISPLAY_HEIGHT: u32 = 800;
 /// 8k is sufficient for most tasks while being more efficient
 const MAX_TOKENS: u32 = 8000;
 
+/// max output tokens for quick answers - short by design, long output means it was a task
+const CHAT_MAX_TOKENS: u32 = 2048;
+
 /// thinking budget - reduced for rate limit efficiency
 /// 2k still provides good reasoning without excessive tokens
 const THINKING_BUDGET: u32 = 2000;
 
+/// the message cache breakpoint only advances every this many messages, so most turns
+/// re-read the same cached prefix instead of writing a new one
+const CACHE_BREAKPOINT_STRIDE: usize = 8;
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("HTTP request failed: {0}")]
     Request(#[from] reqwest::Error),
     #[error("API error: {0}")]
     Api(String),
+    /// HTTP 429 - `retry_after` comes from the Retry-After / anthropic-ratelimit-*-reset headers
+    #[error("Rate limit: {message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<std::time::Duration>,
+    },
+    /// HTTP 5xx / 529 or a mid-stream overloaded_error - worth retrying, then falling back to another model
+    #[error("Model overloaded: {0}")]
+    Overloaded(String),
+}
+
+/// Comma-separated models tried in order once the primary model keeps failing
+pub const FALLBACK_MODELS_ENV: &str = "HEYWORK_FALLBACK_MODELS";
+
+/// Configured fallback models (blank entries dropped, duplicates kept in first position only)
+pub fn fallback_models() -> Vec<String> {
+    let mut models: Vec<String> = Vec::new();
+    for model in std::env::var(FALLBACK_MODELS_ENV).unwrap_or_default().split(',') {
+        let model = model.trim();
+        if !model.is_empty() && !models.iter().any(|m| m == model) {
+            models.push(model.to_string());
+        }
+    }
+    models
+}
+
+/// Rate-limit headers whose `-reset` timestamp we honor when the matching `-remaining` is exhausted
+const RATE_LIMIT_HEADERS: &[&str] = &[
+    "anthropic-ratelimit-requests",
+    "anthropic-ratelimit-tokens",
+    "anthropic-ratelimit-input-tokens",
+    "anthropic-ratelimit-output-tokens",
+];
+
+/// How long the server asked us to wait. Prefers Retry-After (seconds or HTTP date),
+/// then the latest reset time of any exhausted anthropic-ratelimit-* bucket.
+fn retry_after_from_headers(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+    let until = |at: chrono::DateTime<chrono::Utc>| {
+        (at - chrono::Utc::now()).to_std().unwrap_or(std::time::Duration::ZERO)
+    };
+
+    if let Some(value) = header("retry-after") {
+        if let Ok(secs) = value.parse::<f64>() {
+            if secs.is_finite() && secs >= 0.0 {
+                return Some(std::time::Duration::from_secs_f64(secs));
+            }
+        }
+        if let Ok(at) = chrono::DateTime::parse_from_rfc2822(value) {
+            return Some(until(at.with_timezone(&chrono::Utc)));
+        }
+    }
+
+    RATE_LIMIT_HEADERS
+        .iter()
+        .filter(|prefix| header(&format!("{}-remaining", prefix)) == Some("0"))
+        .filter_map(|prefix| header(&format!("{}-reset", prefix)))
+        .filter_map(|reset| chrono::DateTime::parse_from_rfc3339(reset).ok())
+        .map(|at| until(at.with_timezone(&chrono::Utc)))
+        .max()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,12 +207,71 @@ struct ApiRequest {
     max_tokens: u32,
     system: Vec<SystemBlock>,
     tools: Vec<serde_json::Value>,
-    messages: Vec<Message>,
+    messages: RequestMessages,
     stream: bool,
-    thinking: ThinkingConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
     context_management: ContextManagement,
 }
 
+/// Conversation messages plus an optional cache_control breakpoint on one of them.
+/// The breakpoint is injected at serialization so ContentBlock stays cache-agnostic.
+#[derive(Debug)]
+struct RequestMessages {
+    messages: Vec<Message>,
+    cache_breakpoint: Option<usize>,
+}
+
+impl RequestMessages {
+    /// Mark the oldest stable prefix of the conversation as cacheable
+    fn cached(messages: Vec<Message>) -> Self {
+        let cache_breakpoint = cache_breakpoint(&messages);
+        Self { messages, cache_breakpoint }
+    }
+}
+
+impl From<Vec<Message>> for RequestMessages {
+    fn from(messages: Vec<Message>) -> Self {
+        Self { messages, cache_breakpoint: None }
+    }
+}
+
+impl Serialize for RequestMessages {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeSeq};
+        let mut seq = serializer.serialize_seq(Some(self.messages.len()))?;
+        for (i, message) in self.messages.iter().enumerate() {
+            if Some(i) != self.cache_breakpoint {
+                seq.serialize_element(message)?;
+                continue;
+            }
+            let mut value = serde_json::to_value(message).map_err(S::Error::custom)?;
+            if let Some(last) = value
+                .get_mut("content")
+                .and_then(|c| c.as_array_mut())
+                .and_then(|blocks| blocks.last_mut())
+                .and_then(|block| block.as_object_mut())
+            {
+                last.insert("cache_control".to_string(), serde_json::json!({"type": "ephemeral"}));
+            }
+            seq.serialize_element(&value)?;
+        }
+        seq.end()
+    }
+}
+
+/// Pick a user message to carry the conversation cache breakpoint. It sits at a fixed
+/// stride boundary (not the latest turn) so it stays put while new messages are appended,
+/// and on a user message because thinking blocks can't take cache_control.
+fn cache_breakpoint(messages: &[Message]) -> Option<usize> {
+    // the newest message changes every turn, never cache it
+    let stable = messages.len().saturating_sub(1);
+    let boundary = stable / CACHE_BREAKPOINT_STRIDE * CACHE_BREAKPOINT_STRIDE;
+    messages[..boundary]
+        .iter()
+        .rposition(|m| m.role == "user" && !m.content.is_empty())
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiErrorResponse {
     error: ApiErrorDetail,
@@ -171,9 +297,54 @@ pub enum StreamEvent {
     TextDelta { text: String },
     ThinkingDelta { thinking: String },
     ToolUseStart { name: String },
+    /// raw fragment of a tool's input JSON - not valid JSON on its own until the block closes
+    ToolInputDelta { partial_json: String },
     MessageStop,
 }
 
+/// rough per-image token cost (1280x800 screenshots land around 1.3-1.6k)
+const IMAGE_TOKEN_ESTIMATE: u32 = 1600;
+
+/// Estimate input tokens for a request (~4 chars per token, flat cost per image)
+/// so the rate limiter can reserve budget before we know the real count
+fn estimate_input_tokens(request: &ApiRequest) -> u32 {
+    let mut chars: usize = request.system.iter().map(|b| b.text.len()).sum();
+    chars += request.tools.iter().map(|t| t.to_string().len()).sum::<usize>();
+    (chars / 4) as u32 + estimate_message_tokens(&request.messages.messages)
+}
+
+/// Estimate tokens for conversation messages on the same ~4 chars/token scale
+pub fn estimate_message_tokens(messages: &[Message]) -> u32 {
+    let mut chars: usize = 0;
+    let mut images: u32 = 0;
+
+    for message in messages {
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => chars += text.len(),
+                ContentBlock::Image { .. } => images += 1,
+                ContentBlock::ToolUse { input, .. } | ContentBlock::ServerToolUse { input, .. } => {
+                    chars += input.to_string().len()
+                }
+                ContentBlock::ToolResult { content, .. } => {
+                    for item in content {
+                        match item {
+                            ToolResultContent::Text { text } => chars += text.len(),
+                            ToolResultContent::Image { .. } => images += 1,
+                        }
+                    }
+                }
+                ContentBlock::Thinking { thinking, .. } => chars += thinking.len(),
+                ContentBlock::RedactedThinking { .. } => {}
+                ContentBlock::WebSearchToolResult { content, .. }
+                | ContentBlock::WebFetchToolResult { content, .. } => chars += content.to_string().len(),
+            }
+        }
+    }
+
+    (chars / 4) as u32 + images * IMAGE_TOKEN_ESTIMATE
+}
+
 // api call result with content and usage
 #[derive(Debug)]
 pub struct ApiResult {
@@ -186,18 +357,38 @@ pub struct AnthropicClient {
     api_key: String,
     model: String,
     rate_limiter: Arc<RateLimiter>,
+    /// process-wide request/token budget for this model, shared with every other client
+    model_limiter: Arc<ModelRateLimiter>,
 }
 
 impl AnthropicClient {
     pub fn new(api_key: String, model: String) -> Self {
+        let model_limiter = crate::rate_limiter::limiter_for(&model);
         Self {
             client: Client::new(),
             api_key,
             model,
             rate_limiter: Arc::new(RateLimiter::new()),
+            model_limiter,
         }
     }
 
+    /// Take a request + estimated input tokens from the model's budget before sending
+    async fn acquire_permit(&self, request: &ApiRequest) -> u32 {
+        let estimated = estimate_input_tokens(request);
+        let waited = self.model_limiter.acquire(estimated).await;
+        if waited > std::time::Duration::from_millis(100) {
+            println!("[api] Waited {:?} for {} rate budget (~{} tokens)", waited, self.model, estimated);
+        }
+        estimated
+    }
+
+    /// Replace the estimate with what the API actually counted
+    fn settle_permit(&self, estimated: u32, usage: &Usage) {
+        self.model_limiter
+            .settle(estimated, usage.input_tokens + usage.cache_creation_input_tokens);
+    }
+
     /// Get rate limiter stats
     pub async fn get_rate_limit_stats(&self) -> RateLimiterStats {
         self.rate_limiter.get_stats().await
@@ -213,7 +404,7 @@ impl AnthropicClient {
         self.rate_limiter.record_usage(usage).await;
     }
 
-    fn build_tools(&self, mode: AgentMode) -> Vec<serde_json::Value> {
+    fn build_tools(&self, mode: AgentMode, policy: &crate::tool_policy::ToolPolicy) -> Vec<serde_json::Value> {
         let mut tools = Vec::new();
 
         match mode {
@@ -226,6 +417,156 @@ impl AnthropicClient {
                     "display_height_px": DISPLAY_HEIGHT,
                     "display_number": 1
                 }));
+                // OCR-backed zoom so small labels can be read without guessing a region
+                tools.push(serde_json::json!({
+                    "name": "zoom_to_text",
+                    "description": "Find text on screen and return a sharp, enlarged crop around it. Use this to read small UI labels, menu items or values you are unsure about instead of guessing zoom coordinates. Returns an error if the text is not visible; take a full screenshot then.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "query": {
+                                "type": "string",
+                                "description": "Text to look for (case-insensitive, partial matches allowed)"
+                            }
+                        },
+                        "required": ["query"]
+                    }
+                }));
+                // click by visible text instead of estimating coordinates from a screenshot
+                tools.push(serde_json::json!({
+                    "name": "click_text",
+                    "description": "Find text on screen with OCR and click the middle of it - more reliable than clicking coordinates read off a screenshot, especially for small buttons, menu items and links. Returns the text it clicked and a confidence score. If several places match about equally well nothing is clicked; you get the candidates with their coordinates instead, so call again with more of the text or click one with the computer tool.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "query": {
+                                "type": "string",
+                                "description": "Visible text of the thing to click (case-insensitive, partial matches allowed)"
+                            }
+                        },
+                        "required": ["query"]
+                    }
+                }));
+                // the computer-mode counterpart of page_action's wait_for_text
+                tools.push(serde_json::json!({
+                    "name": "wait_for_change",
+                    "description": "Wait until a screen region stops changing (page loads, spinners, progress bars, animations) instead of guessing a wait duration. Returns once the region has looked the same for stability_ms, or when timeout_ms passes, and says whether it settled and whether it changed at all. Take a screenshot afterwards to see the result.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "region": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "minItems": 4,
+                                "maxItems": 4,
+                                "description": "[x1, y1, x2, y2] to watch, in screenshot coordinates. Defaults to the whole screen."
+                            },
+                            "timeout_ms": {
+                                "type": "integer",
+                                "description": "Give up after this long (default 10000, max 60000)"
+                            },
+                            "stability_ms": {
+                                "type": "integer",
+                                "description": "How long the region must stay unchanged (default 1000)"
+                            }
+                        }
+                    }
+                }));
+                // switching apps by window instead of hunting for it in the dock or cmd+tab
+                tools.push(serde_json::json!({
+                    "name": "list_windows",
+                    "description": "List the open windows of other apps, frontmost first, with their id, title, owning app and bounds [x1, y1, x2, y2] in screenshot coordinates. Use it to find a window that is hidden behind others or on another part of the screen.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                }));
+                tools.push(serde_json::json!({
+                    "name": "get_active_app",
+                    "description": "Get the frontmost app's name, bundle id and focused window title as text. Much cheaper than a screenshot for checking you are in the right app, e.g. after switching apps and before typing.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                }));
+                // structured look at the focused window, far cheaper than a screenshot
+                tools.push(serde_json::json!({
+                    "name": "observe_ui",
+                    "description": "Read the focused window's accessibility tree as text: one line per element with its role, label, value and bounds [x1, y1, x2, y2] in screenshot coordinates, indented by nesting. Much cheaper than a screenshot and exact about labels and text field contents - use it to find buttons, fields and menu items, then click the middle of their bounds. Apps that draw their own UI (games, canvases, some editors) expose no tree; you get a screenshot instead.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                }));
+                tools.push(serde_json::json!({
+                    "name": "focus_window",
+                    "description": "Bring a window to the front and focus it. Pass an id from list_windows, or part of a window title or app name. Take a screenshot afterwards before interacting with it.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "title_or_id": {
+                                "type": "string",
+                                "description": "Window id from list_windows, or text from its title or app name (case-insensitive)"
+                            }
+                        },
+                        "required": ["title_or_id"]
+                    }
+                }));
+                // arranging windows - accessibility moves are exact where dragging title bars isn't
+                tools.push(serde_json::json!({
+                    "name": "set_window_bounds",
+                    "description": "Move and resize another app's window. x, y, width and height are in screenshot coordinates. Returns the bounds the window actually ended up with - apps can refuse sizes below their minimum.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "title_or_id": {
+                                "type": "string",
+                                "description": "Window id from list_windows, or text from its title or app name (case-insensitive)"
+                            },
+                            "x": { "type": "integer" },
+                            "y": { "type": "integer" },
+                            "width": { "type": "integer" },
+                            "height": { "type": "integer" }
+                        },
+                        "required": ["title_or_id", "x", "y", "width", "height"]
+                    }
+                }));
+                tools.push(serde_json::json!({
+                    "name": "snap_window",
+                    "description": "Snap another app's window to the left or right half of the screen, or make it fill the screen. Use two calls for side-by-side layouts.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "title_or_id": {
+                                "type": "string",
+                                "description": "Window id from list_windows, or text from its title or app name (case-insensitive)"
+                            },
+                            "position": {
+                                "type": "string",
+                                "enum": ["left", "right", "maximize"]
+                            }
+                        },
+                        "required": ["title_or_id", "position"]
+                    }
+                }));
+                // several apps and sites in one call rather than a turn per launch
+                tools.push(serde_json::json!({
+                    "name": "launch",
+                    "description": "Open several apps and/or URLs at once. App names are matched case-insensitively against installed apps (\"slack\" opens Slack); URLs and bare domains like gmail.com open in the default browser. Returns whether each item opened; a missing app only fails that item. Put every app and site the user asked for in one call.",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "targets": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "minItems": 1,
+                                "maxItems": crate::launcher::MAX_LAUNCH_TARGETS,
+                                "description": "App names and/or URLs, opened in order"
+                            }
+                        },
+                        "required": ["targets"]
+                    }
+                }));
             }
             AgentMode::Browser => {
                 // browser tools via chromiumoxide CDP
@@ -239,7 +580,8 @@ impl AnthropicClient {
             "name": "bash"
         }));
 
-        // web search tool - server-side, anthropic executes
+        // web search tool - server-side, anthropic executes. the light path for quick facts,
+        // deep_research is the heavy one
         tools.push(serde_json::json!({
             "type": "web_search_20250305",
             "name": "web_search",
@@ -266,7 +608,7 @@ impl AnthropicClient {
         // python tool for document generation and data processing
         tools.push(serde_json::json!({
             "name": "python",
-            "description": "Execute Python code for professional document generation, data analysis, and automation. All libraries are AUTO-INSTALLED (no pip needed). Creates publication-quality output.\n\nALWAYS USE THESE BUILT-IN HELPERS (they produce professional output):\n\n1. create_professional_report(title, sections, output_path, style)\n   - sections: dict of section_name -> content (str, list, or dict)\n   - output_path: .html, .docx, .pdf, .md, .pptx, .txt\n   - style: 'modern'(default), 'dark', 'executive', 'classic', 'minimal'\n   - Example: create_professional_report('Q4 Report', {'Summary': 'Revenue up 25%', 'Details': ['Point 1', 'Point 2']}, '~/Desktop/report.html', 'modern')\n\n2. create_presentation(title, slides, output_path, theme)\n   - slides: list of dicts with 'title', 'content' (str/list/dict), optional 'notes', 'image_path'\n   - theme: 'modern', 'dark', 'minimal', 'corporate', 'creative'\n   - Auto-generates title + end slides with professional design\n   - Example: create_presentation('AI Strategy', [{'title': 'Overview', 'content': ['Point 1', 'Point 2']}, {'title': 'Data', 'content': {'Metric': 'Value'}}], '~/Desktop/deck.pptx', 'dark')\n\n3. create_advanced_chart(data, chart_type, title, save_path)\n   - chart_type: 'bar', 'line', 'pie', 'donut', 'scatter', 'area', 'histogram'\n   - .html saves as interactive Plotly chart, .png/.svg/.pdf as matplotlib\n   - Example: create_advanced_chart({'Q1': 100, 'Q2': 150}, 'bar', 'Revenue', '~/Desktop/chart.html')\n\n4. create_spreadsheet(data, output_path)\n   - data: dict of sheet_name -> list of dicts (rows)\n   - Professional formatting with styled headers\n   - Example: create_spreadsheet({'Sales': [{'Month': 'Jan', 'Revenue': 100}]}, '~/Desktop/data.xlsx')\n\n5. create_dashboard(title, charts, output_path, layout)\n   - charts: list of dicts with 'title', 'data', 'chart_type'\n   - layout: 'grid' (2-col) or 'stack' (1-col)\n\n6. quick_analyze(data) - Statistical summary of data\n\nWHEN USER ASKS FOR PPTX: Always use create_presentation() with a good theme.\nWHEN USER ASKS FOR REPORT: Always use create_professional_report() with appropriate format.\nWHEN USER ASKS FOR CHART: Always use create_advanced_chart().\nDefault save location: ~/Desktop/ unless user specifies otherwise.",
+            "description": "Execute Python code for professional document generation, data analysis, and automation. All libraries are AUTO-INSTALLED (no pip needed). Creates publication-quality output.\n\nALWAYS USE THESE BUILT-IN HELPERS (they produce professional output):\n\n1. create_professional_report(title, sections, output_path, style)\n   - sections: dict of section_name -> content (str, list, or dict)\n   - output_path: .html, .docx, .pdf, .md, .pptx, .txt\n   - style: 'modern'(default), 'dark', 'executive', 'classic', 'minimal' (anything else falls back to 'modern' with a note)\n   - Example: create_professional_report('Q4 Report', {'Summary': 'Revenue up 25%', 'Details': ['Point 1', 'Point 2']}, '~/Desktop/report.html', 'modern')\n   - Long document? Build it across calls with append_to_report(output_path, sections) - adds sections to the end of an existing .docx/.md/.html (creates it if missing)\n\n2. create_presentation(title, slides, output_path, theme)\n   - slides: list of dicts with 'title', 'content' (str/list/dict), optional 'notes', 'image_path'\n   - theme: 'modern', 'dark', 'minimal', 'corporate', 'creative'\n   - Auto-generates title + end slides with professional design\n   - Example: create_presentation('AI Strategy', [{'title': 'Overview', 'content': ['Point 1', 'Point 2']}, {'title': 'Data', 'content': {'Metric': 'Value'}}], '~/Desktop/deck.pptx', 'dark')\n   - add_slides_to(path, slides, theme) adds slides to an existing deck, before its end slide (creates the deck if missing)\n\n3. create_advanced_chart(data, chart_type, title, save_path)\n   - chart_type: 'bar', 'line', 'pie', 'donut', 'scatter', 'area', 'histogram'\n   - .html saves as interactive Plotly chart, .png/.svg/.pdf as matplotlib\n   - save_path None or 'inline' shows the chart as an image right in the chat (no file)\n   - Example: create_advanced_chart({'Q1': 100, 'Q2': 150}, 'bar', 'Revenue', '~/Desktop/chart.html')\n\n4. create_spreadsheet(data, output_path)\n   - data: dict of sheet_name -> list of dicts (rows)\n   - Professional formatting with styled headers\n   - Example: create_spreadsheet({'Sales': [{'Month': 'Jan', 'Revenue': 100}]}, '~/Desktop/data.xlsx')\n\n5. create_dashboard(title, charts, output_path, layout)\n   - charts: list of dicts with 'title', 'data', 'chart_type'\n   - layout: 'grid' (2-col) or 'stack' (1-col)\n\n6. quick_analyze(data) - Statistical summary of data\n\nWHEN USER ASKS FOR PPTX: Always use create_presentation() with a good theme.\nWHEN USER ASKS FOR REPORT: Always use create_professional_report() with appropriate format.\nWHEN USER ASKS FOR CHART: Always use create_advanced_chart().\nDefault save location: ~/Desktop/ unless user specifies otherwise.",
             "input_schema": {
                 "type": "object",
                 "properties": {
@@ -291,7 +633,7 @@ impl AnthropicClient {
         // deep research tool - Chrome search + full content extraction + LLM synthesis
         tools.push(serde_json::json!({
             "name": "deep_research",
-            "description": "Perform deep, Perplexity-like research. Opens Chrome for real Google searches, extracts FULL page content, then uses AI to synthesize a high-quality, professionally formatted report with source citations.\n\nPipeline:\n1. AI generates smart search queries for the topic\n2. Chrome opens Google and searches each query (user sees the magic)\n3. Chrome visits top result pages and extracts full article content\n4. ALL extracted content is sent to AI for intelligent synthesis\n5. AI produces a polished report formatted for the user's specific request\n\nFalls back to Claude's built-in web search if Chrome is unavailable.\n\nUse when user asks to research, investigate, analyze, compare, or needs comprehensive information.\n\nDepth levels:\n- 'quick': 3 searches, reads 3 pages (~20 sec)\n- 'standard': 5 searches, reads 5 pages (~40 sec)\n- 'deep': 8 searches, reads 8 pages (~75 sec)\n\nExample: deep_research({\"query\": \"latest AI agent frameworks 2026\", \"depth\": \"standard\"})",
+            "description": "Perform deep, Perplexity-like research. Opens Chrome for real Google searches, extracts FULL page content, then uses AI to synthesize a high-quality, professionally formatted report with source citations.\n\nPipeline:\n1. AI generates smart search queries for the topic\n2. Chrome opens Google and searches each query (user sees the magic)\n3. Chrome visits top result pages and extracts full article content\n4. ALL extracted content is sent to AI for intelligent synthesis\n5. AI produces a polished report formatted for the user's specific request\n\nFalls back to Claude's built-in web search if Chrome is unavailable.\n\nUse when user asks to research, investigate, analyze, compare, or needs comprehensive information. Not for a single quick fact (weather, a score, a price) - web_search answers those without opening Chrome.\n\nDepth presets:\n- 'quick': 3 searches, reads 3 pages (~20 sec)\n- 'standard': 5 searches, reads 5 pages (~40 sec)\n- 'exhaustive': 8 searches, reads 10 pages (~2 min)\n\nmax_sources, max_queries, per_source_timeout_ms and follow_links override the preset.\n\nExample: deep_research({\"query\": \"latest AI agent frameworks 2026\", \"depth\": \"standard\"})",
             "input_schema": {
                 "type": "object",
                 "properties": {
@@ -301,16 +643,34 @@ impl AnthropicClient {
                     },
                     "depth": {
                         "type": "string",
-                        "description": "Research depth: 'quick', 'standard', or 'deep'",
-                        "enum": ["quick", "standard", "deep"]
+                        "description": "Research depth preset: 'quick', 'standard', or 'exhaustive'",
+                        "enum": ["quick", "standard", "exhaustive"]
+                    },
+                    "max_sources": {
+                        "type": "integer",
+                        "description": "Override: max sources to keep in the report"
+                    },
+                    "max_queries": {
+                        "type": "integer",
+                        "description": "Override: number of search queries to run"
+                    },
+                    "per_source_timeout_ms": {
+                        "type": "integer",
+                        "description": "Override: time budget for reading each source page"
+                    },
+                    "follow_links": {
+                        "type": "boolean",
+                        "description": "Override: visit result pages for full content (false = snippets only)"
                     }
                 },
                 "required": ["query"]
             }
         }));
 
+        tools.retain(|tool| tool.get("name").and_then(|v| v.as_str()).is_none_or(|name| policy.allows(name)));
+
         // add cache_control to last tool to cache all tool definitions
-        // tools are stable per mode, maximizing cache hits across requests
+        // tools are stable per mode and policy, maximizing cache hits across requests
         if let Some(last_tool) = tools.last_mut() {
             if let Some(obj) = last_tool.as_object_mut() {
                 obj.insert(
@@ -329,6 +689,7 @@ impl AnthropicClient {
         event_tx: mpsc::UnboundedSender<StreamEvent>,
         mode: AgentMode,
         voice_mode: bool,
+        context_note: Option<&str>,
     ) -> Result<ApiResult, ApiError> {
         // Show rate limit status (no pre-throttling — we rely on 429 retry instead)
         let stats: crate::rate_limiter::RateLimiterStats = self.rate_limiter.get_stats().await;
@@ -336,16 +697,15 @@ impl AnthropicClient {
 
         // build system prompt as array of blocks for caching
         // base prompt is stable across all requests with same mode
-        let base_prompt = match mode {
-            AgentMode::Computer => SYSTEM_PROMPT,
-            AgentMode::Browser => BROWSER_SYSTEM_PROMPT,
-        };
+        // only the tools the user left on are described and offered
+        let tool_policy = crate::tool_policy::ToolPolicy::current();
+        let base_prompt = system_prompt(mode, &tool_policy);
 
         // voice instructions vary by model, so they go in a separate block
         // this way base prompt can still be cached even if voice config differs
         let mut system_blocks = vec![SystemBlock {
             block_type: "text".to_string(),
-            text: base_prompt.to_string(),
+            text: base_prompt,
             cache_control: if voice_mode {
                 None // don't cache here, cache after voice block
             } else {
@@ -355,6 +715,16 @@ impl AnthropicClient {
             },
         }];
 
+        // user's standing instructions go first, in their own uncached block, so the
+        // built-in prompt after them has the final word on tool use
+        if let Some(preamble) = system_preamble() {
+            system_blocks.insert(0, SystemBlock {
+                block_type: "text".to_string(),
+                text: preamble,
+                cache_control: None,
+            });
+        }
+
         if voice_mode {
             let voice_prompt = if self.model.contains("haiku") {
                 VOICE_PROMPT_HAIKU
@@ -370,7 +740,17 @@ impl AnthropicClient {
             });
         }
 
-        let tools = self.build_tools(mode);
+        // conversation state (the files made so far) goes last and uncached - it only changes
+        // when a new file shows up, so the prompt cache still hits between those turns
+        if let Some(note) = context_note {
+            system_blocks.push(SystemBlock {
+                block_type: "text".to_string(),
+                text: note.to_string(),
+                cache_control: None,
+            });
+        }
+
+        let tools = self.build_tools(mode, &tool_policy);
         println!("[api] Sending {} tools, voice_mode={}", tools.len(), voice_mode);
 
         let request = ApiRequest {
@@ -378,12 +758,12 @@ impl AnthropicClient {
             max_tokens: MAX_TOKENS,
             system: system_blocks,
             tools,
-            messages,
+            messages: RequestMessages::cached(messages),
             stream: true,
-            thinking: ThinkingConfig {
+            thinking: Some(ThinkingConfig {
                 config_type: "enabled".to_string(),
                 budget_tokens: THINKING_BUDGET,
-            },
+            }),
             context_management: ContextManagement {
                 edits: vec![
                     // clear thinking blocks from older turns, keep only last 1
@@ -403,6 +783,55 @@ impl AnthropicClient {
             },
         };
 
+        self.stream_request(request, event_tx).await
+    }
+
+    /// Text-only streaming turn with no tools and no extended thinking, for the
+    /// quick-answer path. The model replies with NEEDS_TOOLS_MARKER when the request
+    /// actually needs the agent, so the caller can fall back to a full run.
+    pub async fn send_chat_streaming(
+        &self,
+        messages: Vec<Message>,
+        event_tx: mpsc::UnboundedSender<StreamEvent>,
+    ) -> Result<ApiResult, ApiError> {
+        let request = ApiRequest {
+            model: self.model.clone(),
+            max_tokens: CHAT_MAX_TOKENS,
+            system: system_preamble()
+                .map(|preamble| SystemBlock {
+                    block_type: "text".to_string(),
+                    text: preamble,
+                    cache_control: None,
+                })
+                .into_iter()
+                .chain(std::iter::once(SystemBlock {
+                    block_type: "text".to_string(),
+                    text: CHAT_SYSTEM_PROMPT.to_string(),
+                    cache_control: Some(CacheControl {
+                        cache_type: "ephemeral".to_string(),
+                    }),
+                }))
+                .collect(),
+            tools: vec![],
+            messages: RequestMessages::cached(messages),
+            stream: true,
+            thinking: None,
+            context_management: ContextManagement {
+                edits: vec![],
+            },
+        };
+
+        self.stream_request(request, event_tx).await
+    }
+
+    /// Send a streaming request and assemble the SSE events into content blocks
+    async fn stream_request(
+        &self,
+        request: ApiRequest,
+        event_tx: mpsc::UnboundedSender<StreamEvent>,
+    ) -> Result<ApiResult, ApiError> {
+        let estimated_tokens = self.acquire_permit(&request).await;
+
         let response = self
             .client
             .post(ANTHROPIC_API_URL)
@@ -416,6 +845,7 @@ impl AnthropicClient {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = retry_after_from_headers(response.headers());
             let body = response.text().await?;
             
             // Check for rate limit error (429 or specific error message)
@@ -433,16 +863,20 @@ impl AnthropicClient {
                     cache_read_input_tokens: 0,
                 }).await;
                 
-                if let Ok(err) = serde_json::from_str::<ApiErrorResponse>(&body) {
-                    return Err(ApiError::Api(format!("Rate limit: {}", err.error.message)));
-                }
-                return Err(ApiError::Api(format!("Rate limit hit (HTTP 429). Will retry automatically.")));
+                let message = serde_json::from_str::<ApiErrorResponse>(&body)
+                    .map(|err| err.error.message)
+                    .unwrap_or_else(|_| "HTTP 429. Will retry automatically.".to_string());
+                return Err(ApiError::RateLimited { message, retry_after });
             }
             
-            if let Ok(err) = serde_json::from_str::<ApiErrorResponse>(&body) {
-                return Err(ApiError::Api(err.error.message));
+            let message = serde_json::from_str::<ApiErrorResponse>(&body)
+                .map(|err| err.error.message)
+                .unwrap_or_else(|_| format!("HTTP {}: {}", status, body));
+            // includes 529, anthropic's "overloaded"
+            if status.is_server_error() {
+                return Err(ApiError::Overloaded(message));
             }
-            return Err(ApiError::Api(format!("HTTP {}: {}", status, body)));
+            return Err(ApiError::Api(message));
         }
 
         // parse SSE stream incrementally
@@ -607,6 +1041,9 @@ impl AnthropicClient {
                                                 current_tool_json.push(String::new());
                                             }
                                             current_tool_json[index].push_str(json);
+                                            let _ = event_tx.send(StreamEvent::ToolInputDelta {
+                                                partial_json: json.to_string(),
+                                            });
                                         }
                                     }
                                     _ => {}
@@ -722,6 +1159,23 @@ impl AnthropicClient {
                             let _ = event_tx.send(StreamEvent::MessageStop);
                         }
 
+                        // the API can fail after a 200 - surface it instead of returning a truncated turn
+                        "error" => {
+                            let error = event.get("error");
+                            let kind = error.and_then(|e| e.get("type")).and_then(|t| t.as_str()).unwrap_or("");
+                            let message = error
+                                .and_then(|e| e.get("message"))
+                                .and_then(|m| m.as_str())
+                                .unwrap_or("stream error")
+                                .to_string();
+                            self.settle_permit(estimated_tokens, &usage);
+                            return Err(match kind {
+                                "overloaded_error" | "api_error" => ApiError::Overloaded(message),
+                                "rate_limit_error" => ApiError::RateLimited { message, retry_after: None },
+                                _ => ApiError::Api(message),
+                            });
+                        }
+
                         _ => {}
                     }
                 }
@@ -730,6 +1184,7 @@ impl AnthropicClient {
 
         // Record usage for rate limiting
         self.record_usage(&usage).await;
+        self.settle_permit(estimated_tokens, &usage);
 
         Ok(ApiResult {
             content: content_blocks,
@@ -758,17 +1213,19 @@ impl AnthropicClient {
             max_tokens: MAX_TOKENS,
             system: system_blocks.unwrap_or_default(),
             tools: tools.unwrap_or_default(),
-            messages,
+            messages: messages.into(),
             stream: false,
-            thinking: ThinkingConfig {
+            thinking: Some(ThinkingConfig {
                 config_type: "enabled".to_string(),
                 budget_tokens: THINKING_BUDGET,
-            },
+            }),
             context_management: ContextManagement {
                 edits: vec![],
             },
         };
 
+        let estimated_tokens = self.acquire_permit(&request_body).await;
+
         let response = self
             .client
             .post(ANTHROPIC_API_URL)
@@ -781,7 +1238,12 @@ impl AnthropicClient {
             .await?;
 
         if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = retry_after_from_headers(response.headers());
             let error_text = response.text().await.unwrap_or_default();
+            if status.as_u16() == 429 {
+                return Err(ApiError::RateLimited { message: error_text, retry_after });
+            }
             return Err(ApiError::Api(error_text));
         }
 
@@ -789,6 +1251,7 @@ impl AnthropicClient {
         
         let usage = api_response.usage.unwrap_or_default();
         self.record_usage(&usage).await;
+        self.settle_permit(estimated_tokens, &usage);
 
         Ok(ApiResult {
             content: api_response.content,
@@ -796,6 +1259,24 @@ impl AnthropicClient {
         })
     }
 
+    /// `complete` for auxiliary calls whose answer only depends on the prompt (decomposition,
+    /// query planning): a repeat within the cache ttl is answered from disk at no token cost.
+    /// never use it for the agent loop - its requests carry screenshots of a changing screen
+    pub async fn complete_cached(
+        &self,
+        system: Option<String>,
+        messages: Vec<Message>,
+        tools: Option<Vec<serde_json::Value>>,
+    ) -> Result<ApiResult, ApiError> {
+        let key = crate::api_cache::cache_key(&self.model, system.as_deref(), &messages, tools.as_deref());
+        if let Some(content) = crate::api_cache::lookup(&key) {
+            return Ok(ApiResult { content, usage: Usage::default() });
+        }
+        let result = self.complete(system, messages, tools).await?;
+        crate::api_cache::store(&key, &result.content);
+        Ok(result)
+    }
+
     /// Perform a multi-turn API call with web search tool enabled.
     /// Claude will autonomously search the web, read results, and produce
     /// a cited response. Handles `pause_turn` automatically (re-sends to continue).
@@ -838,12 +1319,12 @@ impl AnthropicClient {
                 max_tokens: 16000, // larger for research output
                 system: system_blocks.unwrap_or_default(),
                 tools: vec![web_search_tool.clone()],
-                messages: messages.clone(),
+                messages: messages.clone().into(),
                 stream: false,
-                thinking: ThinkingConfig {
+                thinking: Some(ThinkingConfig {
                     config_type: "enabled".to_string(),
                     budget_tokens: 4000, // more thinking for research
-                },
+                }),
                 context_management: ContextManagement {
                     edits: vec![],
                 },
@@ -851,6 +1332,8 @@ impl AnthropicClient {
 
             println!("[api] web_search iteration {}: sending request ({} messages)", iterations, messages.len());
 
+            let estimated_tokens = self.acquire_permit(&request_body).await;
+
             let response = self
                 .client
                 .post(ANTHROPIC_API_URL)
@@ -864,13 +1347,15 @@ impl AnthropicClient {
 
             if !response.status().is_success() {
                 let status = response.status();
+                let retry_after = retry_after_from_headers(response.headers());
                 let error_text = response.text().await.unwrap_or_default();
                 println!("[api] web_search error {}: {}", status, &error_text[..error_text.len().min(200)]);
 
                 // Retry on 429 (rate limited)
                 if status.as_u16() == 429 {
-                    println!("[api] Rate limited during web search, waiting 10s...");
-                    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                    let delay = crate::rate_limiter::retry_delay(iterations, retry_after);
+                    println!("[api] Rate limited during web search, waiting {:?}...", delay);
+                    tokio::time::sleep(delay).await;
                     continue;
                 }
                 return Err(ApiError::Api(error_text));
@@ -880,6 +1365,7 @@ impl AnthropicClient {
             
             let usage = api_response.usage.unwrap_or_default();
             self.record_usage(&usage).await;
+            self.settle_permit(estimated_tokens, &usage);
             total_usage.input_tokens += usage.input_tokens;
             total_usage.output_tokens += usage.output_tokens;
 
@@ -985,47 +1471,151 @@ Output only the rewritten text. No explanations, no quotes, no prefixes.
     Ok(raw_text.to_string())
 }
 
-const SYSTEM_PROMPT: &str = r#"You are Hey work, a desktop computer control agent. You see the screen, control mouse/keyboard, run bash, and execute Python.
-
-Keep text responses very concise. Focus on doing, not explaining. Use tools on every turn.
-
-Click to focus before typing. Screenshot after actions to verify. If something fails, try another approach.
-
-Prefer bash for speed: open -a "App", open https://url, pbcopy/pbpaste, mdfind. Use `sleep N` when waiting.
-
-For web research, use the deep_research tool. It opens Chrome for real Google searches, extracts full page content, then synthesizes a polished report using AI. The web_search tool is also available for quick inline lookups.
-
-Use computer tool for visual tasks: clicking UI, reading screen content, filling forms.
-
-**Python Tool** (all libraries AUTO-INSTALLED): Use for ALL document/data tasks:
+// the computer-mode system prompt, paragraph by paragraph. each part names the tool toggle
+// it describes (None = always included), so a disabled tool is never mentioned
+const SYSTEM_PROMPT_PARAGRAPHS: &[&[PromptPart]] = &[
+    &[(None, "Keep text responses very concise. Focus on doing, not explaining. Use tools on every turn.")],
+    &[(Some("computer"), "Click to focus before typing. Screenshot after actions to verify. If something fails, try another approach.")],
+    &[
+        (Some("computer"), "To open apps or websites, use the launch tool with every target in one call (e.g. {\"targets\": [\"Slack\", \"Notion\", \"gmail.com\"]}) rather than opening them one by one.\n"),
+        (Some("bash"), "Prefer bash for speed: pbcopy/pbpaste, mdfind. "),
+        (Some("computer"), "For loads and spinners, use wait_for_change instead of guessing with `sleep N`.\n"),
+        (Some("bash"), "The bash working directory and exported variables persist between commands. To reset the session but stay in the current directory, pass {\"restart\": true, \"keep_cwd\": true}."),
+    ],
+    &[
+        (Some("web_search"), "For quick facts (weather, a score, a price, a date, who or what something is), use web_search: it answers inline in seconds without opening a browser. "),
+        (Some("deep_research"), "Save deep_research for genuine investigations that need several sources read and compared. It opens Chrome for real Google searches, extracts full page content, then synthesizes a polished report using AI."),
+    ],
+    &[(Some("computer"), "Use computer tool for visual tasks: clicking UI, reading screen content, filling forms.")],
+    &[(Some("python"), r#"**Python Tool** (all libraries AUTO-INSTALLED): Use for ALL document/data tasks:
 - ALWAYS use built-in helpers: create_professional_report(), create_presentation(), create_advanced_chart(), create_spreadsheet(), create_dashboard()
 - Reports: create_professional_report(title, sections_dict, path, style='modern'|'dark'|'executive'|'classic'|'minimal')
 - Presentations: create_presentation(title, slides_list, path, theme='modern'|'dark'|'minimal'|'corporate'|'creative')
 - Charts: create_advanced_chart(data_dict, type='bar'|'line'|'pie'|'donut', title, save_path) - use .html for interactive
 - Excel: create_spreadsheet(data_dict, path) with auto-formatting
 - Dashboard: create_dashboard(title, charts_list, path, layout='grid')
-- Default save to ~/Desktop/ unless user specifies
+- Default save to ~/Desktop/ unless user specifies"#)],
+    &[(Some("deep_research"), "**Deep Research Tool**: Use for in-depth research tasks. Opens real browser, searches multiple queries, extracts content from pages, synthesizes findings with citations. Use when user asks for research, analysis, comparisons, or comprehensive information gathering.")],
+];
+
+const BROWSER_SYSTEM_PROMPT_PARAGRAPHS: &[&[PromptPart]] = &[
+    &[(None, "You are Hey work in browser mode. You control Chrome via CDP.")],
+    &[(None, "Keep text responses very concise. Focus on doing, not explaining. Use tools on every turn.")],
+    &[(Some("browser"), "Use uids from the latest snapshot only—stale uids fail. Take a new snapshot after any action that changes the page.")],
+    &[(Some("browser"), r#"Use screenshot when:
+- You're stuck or something isn't working as expected
+- You need to verify a visual result after an action
+- Dealing with CAPTCHAs, images, or visual elements not in the a11y tree
+- Confirming the page looks correct before reporting success"#)],
+    &[(Some("web_search"), "For a quick fact that doesn't need the current page, use web_search instead of opening a search engine in a tab.")],
+    &[(Some("bash"), "Use bash for file operations. The bash working directory and exported variables persist between commands.")],
+    &[(Some("bash"), r#"If browser tools fail with connection errors, Chrome may have been closed. Run this bash command to relaunch it with debugging enabled:
+macOS: open -a "Google Chrome" --args --remote-debugging-port=9222 --user-data-dir="$HOME/.heywork-chrome" --profile-directory=Default --no-first-run
+Windows: "C:\Program Files\Google\Chrome\Application\chrome.exe" --remote-debugging-port=9222 --user-data-dir="%LOCALAPPDATA%\hey-work\heywork-chrome" --profile-directory=Default --no-first-run
+Then wait a few seconds and retry the browser tool."#)],
+];
+
+// a piece of prompt text and the tool toggle it's about
+type PromptPart = (Option<&'static str>, &'static str);
+
+// "a", "a and b", "a, b, and c"
+fn join_clauses(clauses: &[&str]) -> String {
+    match clauses {
+        [] => String::new(),
+        [only] => only.to_string(),
+        [first, second] => format!("{} and {}", first, second),
+        [rest @ .., last] => format!("{}, and {}", rest.join(", "), last),
+    }
+}
+
+/// the base system prompt for a mode, describing only the tools the policy allows
+pub fn system_prompt(mode: AgentMode, policy: &crate::tool_policy::ToolPolicy) -> String {
+    let on = |part: &&PromptPart| part.0.is_none_or(|toggle| policy.allows(toggle));
+    let mut paragraphs = Vec::new();
+    let body = match mode {
+        AgentMode::Computer => {
+            // the opening line lists what the agent can do
+            let abilities: Vec<&str> = [
+                ("computer", "see the screen, control mouse/keyboard"),
+                ("bash", "run bash"),
+                ("python", "execute Python"),
+            ]
+            .iter()
+            .filter(|(toggle, _)| policy.allows(toggle))
+            .map(|(_, ability)| *ability)
+            .collect();
+            paragraphs.push(if abilities.is_empty() {
+                "You are Hey work, a desktop computer control agent.".to_string()
+            } else {
+                format!("You are Hey work, a desktop computer control agent. You {}.", join_clauses(&abilities))
+            });
+            SYSTEM_PROMPT_PARAGRAPHS
+        }
+        AgentMode::Browser => BROWSER_SYSTEM_PROMPT_PARAGRAPHS,
+    };
+    for parts in body {
+        let text: String = parts.iter().filter(on).map(|part| part.1).collect();
+        if !text.trim().is_empty() {
+            paragraphs.push(text.trim().to_string());
+        }
+    }
 
-**Deep Research Tool**: Use for in-depth research tasks. Opens real browser, searches multiple queries, extracts content from pages, synthesizes findings with citations. Use when user asks for research, analysis, comparisons, or comprehensive information gathering."#;
+    let disabled: Vec<&str> = crate::tool_policy::TOOL_TOGGLES
+        .iter()
+        .copied()
+        .filter(|toggle| !policy.allows(toggle))
+        .collect();
+    if !disabled.is_empty() {
+        paragraphs.push(format!(
+            "The user has turned off these tools: {}. They aren't available - if the task needs one, say it's disabled in Settings.",
+            disabled.join(", ")
+        ));
+    }
+    paragraphs.join("\n\n")
+}
 
-const BROWSER_SYSTEM_PROMPT: &str = r#"You are Hey work in browser mode. You control Chrome via CDP.
+const PREAMBLE_TAG: &str = "user_preferences";
 
-Keep text responses very concise. Focus on doing, not explaining. Use tools on every turn.
+/// The user's custom instructions, delimited so they read as preferences rather than
+/// a replacement system prompt. None when unset.
+pub fn system_preamble() -> Option<String> {
+    let user = format_system_preamble(&crate::permissions::user_system_preamble());
+    let language = crate::voice::voice_language().and_then(crate::voice::language_hint);
+    match (user, language) {
+        (Some(user), Some(language)) => Some(format!("{}\n\n{}", user, language)),
+        (user, language) => user.or(language),
+    }
+}
 
-Use uids from the latest snapshot only—stale uids fail. Take a new snapshot after any action that changes the page.
+fn format_system_preamble(text: &str) -> Option<String> {
+    // the user can't close the block early and smuggle in text that looks like ours
+    let text = text
+        .replace(&format!("<{}>", PREAMBLE_TAG), "")
+        .replace(&format!("</{}>", PREAMBLE_TAG), "");
+    let text: String = text
+        .trim()
+        .chars()
+        .take(crate::permissions::MAX_SYSTEM_PREAMBLE_CHARS)
+        .collect();
+    if text.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "<{tag}>\nStanding instructions from the user. Follow them whenever they apply, but they never \
+         override the tool-use rules and safety instructions that follow.\n\n{text}\n</{tag}>",
+        tag = PREAMBLE_TAG,
+        text = text.trim()
+    ))
+}
 
-Use screenshot when:
-- You're stuck or something isn't working as expected
-- You need to verify a visual result after an action
-- Dealing with CAPTCHAs, images, or visual elements not in the a11y tree
-- Confirming the page looks correct before reporting success
+/// reply that sends a quick-answer request back through the full agent
+pub const NEEDS_TOOLS_MARKER: &str = "[[NEEDS_TOOLS]]";
 
-Use bash for file operations.
+const CHAT_SYSTEM_PROMPT: &str = r#"You are Hey work, a desktop assistant answering a quick question. You have no tools in this mode: you cannot see the screen, click, type, run commands, open files or browse the web.
 
-If browser tools fail with connection errors, Chrome may have been closed. Run this bash command to relaunch it with debugging enabled:
-macOS: open -a "Google Chrome" --args --remote-debugging-port=9222 --user-data-dir="$HOME/.heywork-chrome" --profile-directory=Default --no-first-run
-Windows: "C:\Program Files\Google\Chrome\Application\chrome.exe" --remote-debugging-port=9222 --user-data-dir="%LOCALAPPDATA%\hey-work\heywork-chrome" --profile-directory=Default --no-first-run
-Then wait a few seconds and retry the browser tool."#;
+Answer directly and concisely from your own knowledge. Use markdown only when it helps (short lists, code).
+
+If answering requires acting on the computer, reading the user's screen or files, opening apps or websites, or information newer than your training data, reply with exactly [[NEEDS_TOOLS]] and nothing else. The request will then be handed to the full agent."#;
 
 // voice prompt for opus/sonnet - lighter touch, they follow instructions well
 const VOICE_PROMPT_OPUS: &str = r#"
@@ -1075,7 +1665,7 @@ fn build_browser_tools() -> Vec<serde_json::Value> {
         // TOOL 1: see_page - observe the current page
         serde_json::json!({
             "name": "see_page",
-            "description": "See what's on the page. By default returns all interactive elements (buttons, links, inputs) with element IDs like '3_42'. You MUST call this first before using page_action. Set screenshot=true to get a visual image instead, or list_tabs=true to see open browser tabs.",
+            "description": "See what's on the page. By default returns all interactive elements (buttons, links, inputs) with element IDs like '3_42'. You MUST call this first before using page_action. Set screenshot=true to get a visual image instead, read_page=true to get the main article text as markdown, or list_tabs=true to see open browser tabs.",
             "input_schema": {
                 "type": "object",
                 "properties": {
@@ -1087,6 +1677,14 @@ fn build_browser_tools() -> Vec<serde_json::Value> {
                         "type": "boolean",
                         "description": "Return a list of all open browser tabs with their URLs and tab numbers."
                     },
+                    "read_page": {
+                        "type": "boolean",
+                        "description": "Return the page's main content (title + article text) as markdown. Best for reading or summarizing articles - much smaller than the element list."
+                    },
+                    "max_chars": {
+                        "type": "integer",
+                        "description": "Max characters of markdown to return with read_page (default 12000)"
+                    },
                     "verbose": {
                         "type": "boolean",
                         "description": "Include all elements, not just interactive ones. Default false."
@@ -1140,6 +1738,10 @@ fn build_browser_tools() -> Vec<serde_json::Value> {
                         "type": "integer",
                         "description": "Pixels to scroll (default 500). Use with scroll."
                     },
+                    "scroll_to": {
+                        "type": "string",
+                        "description": "Scroll until this element is in view - better than scroll on long pages. Returns the element's new ID and any elements that appeared. Example: \"3_42\""
+                    },
                     "fill_form": {
                         "type": "array",
                         "items": {
@@ -1152,6 +1754,15 @@ fn build_browser_tools() -> Vec<serde_json::Value> {
                         },
                         "description": "Fill multiple fields at once. Example: [{\"element\": \"3_10\", \"text\": \"John\"}]"
                     },
+                    "upload_file": {
+                        "type": "object",
+                        "properties": {
+                            "element": { "type": "string" },
+                            "path": { "type": "string" }
+                        },
+                        "required": ["element", "path"],
+                        "description": "Attach a local file to a file input (no file dialog needed). Path must be in Desktop, Documents, Downloads or temp. Example: {\"element\": \"3_12\", \"path\": \"~/Documents/resume.pdf\"}"
+                    },
                     "dialog": {
                         "type": "string",
                         "enum": ["accept", "dismiss"],
@@ -1220,5 +1831,38 @@ fn build_browser_tools() -> Vec<serde_json::Value> {
                 "required": []
             }
         }),
+        // TOOL 4: browser_download - save a file and wait for it to finish
+        serde_json::json!({
+            "name": "browser_download",
+            "description": "Download a file. Click a download link/button by element ID, or fetch a direct URL. Waits for the download to finish and returns the saved path and size. If the click just opens a page instead, you'll be told to use see_page.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "click": {
+                        "type": "string",
+                        "description": "Click this element to start the download. Example: \"3_42\""
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "Download this URL directly. Example: \"https://example.com/report.pdf\""
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "Max time to wait for the download in milliseconds (default 30000)"
+                    }
+                },
+                "required": []
+            }
+        }),
+        // TOOL 5: autofill_login - sign in with a saved login the model never sees
+        serde_json::json!({
+            "name": "autofill_login",
+            "description": "On a login page, fill the username and password fields with the login the user saved for this site. You never see the values - just which fields were filled. Submit the form afterwards. Use this instead of asking the user for a password; if nothing is saved for the site, ask the user to sign in themselves.",
+            "input_schema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        }),
     ]
 }
@@ -0,0 +1,176 @@
+// on-disk cache for auxiliary model calls (task decomposition, search query planning) whose
+// answer only depends on the prompt. entries are keyed by a hash of the model and the full
+// request, expire after a day, and the oldest-used go first once the folder passes its cap.
+// the agent loop streams through a different path and is never cached
+
+use crate::api::{ContentBlock, Message};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// how long a cached answer is reused
+pub const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// size of the cache folder past which the least recently used entries are evicted
+pub const MAX_CACHE_BYTES: u64 = 20 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// unix seconds when the answer was fetched - the ttl counts from here, not from the last hit
+    created: u64,
+    content: Vec<ContentBlock>,
+}
+
+// a file in the cache folder, for eviction
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// file mtime, bumped on every hit
+    pub last_used: SystemTime,
+}
+
+pub fn cache_dir() -> PathBuf {
+    crate::storage::data_dir().join("api_cache")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// content address of a request: same model, system prompt, messages and tools give the same key
+pub fn cache_key(model: &str, system: Option<&str>, messages: &[Message], tools: Option<&[serde_json::Value]>) -> String {
+    let request = serde_json::json!([model, system, messages, tools]);
+    let digest = Sha1::digest(request.to_string().as_bytes());
+    digest.iter().fold(String::with_capacity(40), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
+
+pub fn is_fresh(created: u64, now: u64, ttl: Duration) -> bool {
+    now.saturating_sub(created) < ttl.as_secs()
+}
+
+/// the files to delete so the rest fit in `cap`, least recently used first
+pub fn evictions(mut files: Vec<CacheFile>, cap: u64) -> Vec<PathBuf> {
+    let mut total: u64 = files.iter().map(|file| file.size).sum();
+    files.sort_by_key(|file| file.last_used);
+    files
+        .into_iter()
+        .take_while(|file| {
+            let over = total > cap;
+            total = total.saturating_sub(file.size);
+            over
+        })
+        .map(|file| file.path)
+        .collect()
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.json", key))
+}
+
+fn list(dir: &Path) -> Vec<CacheFile> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            meta.is_file().then(|| CacheFile {
+                path: entry.path(),
+                size: meta.len(),
+                last_used: meta.modified().unwrap_or(UNIX_EPOCH),
+            })
+        })
+        .collect()
+}
+
+/// the cached answer for `key` if there's a fresh one; a stale or unreadable entry is deleted
+pub fn lookup(key: &str) -> Option<Vec<ContentBlock>> {
+    let path = entry_path(&cache_dir(), key);
+    let text = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Entry>(&text) {
+        Ok(entry) if is_fresh(entry.created, now_secs(), CACHE_TTL) => {
+            // mtime doubles as the lru clock
+            if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+            Some(entry.content)
+        }
+        _ => {
+            let _ = std::fs::remove_file(&path);
+            None
+        }
+    }
+}
+
+/// save an answer and trim the folder back under the cap. failures only cost a future hit
+pub fn store(key: &str, content: &[ContentBlock]) {
+    if content.is_empty() {
+        return;
+    }
+    let dir = cache_dir();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        println!("[api_cache] Couldn't create {}: {}", dir.display(), e);
+        return;
+    }
+    let entry = Entry { created: now_secs(), content: content.to_vec() };
+    let Ok(json) = serde_json::to_string(&entry) else {
+        return;
+    };
+    if let Err(e) = std::fs::write(entry_path(&dir, key), json) {
+        println!("[api_cache] Couldn't write entry: {}", e);
+        return;
+    }
+    for path in evictions(list(&dir), MAX_CACHE_BYTES) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// drop every cached answer; returns how many were removed
+#[tauri::command]
+pub fn clear_api_cache() -> Result<usize, String> {
+    let files = list(&cache_dir());
+    for file in &files {
+        std::fs::remove_file(&file.path).map_err(|e| format!("failed to remove {}: {}", file.path.display(), e))?;
+    }
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(text: &str) -> Vec<Message> {
+        vec![Message { role: "user".to_string(), content: vec![ContentBlock::Text { text: text.to_string() }] }]
+    }
+
+    #[test]
+    fn key_covers_model_and_request() {
+        let key = cache_key("model-a", None, &user("split this"), None);
+        assert_eq!(key.len(), 40);
+        assert_eq!(key, cache_key("model-a", None, &user("split this"), None));
+        assert_ne!(key, cache_key("model-b", None, &user("split this"), None));
+        assert_ne!(key, cache_key("model-a", Some("be brief"), &user("split this"), None));
+        assert_ne!(key, cache_key("model-a", None, &user("split that"), None));
+    }
+
+    #[test]
+    fn least_recently_used_go_first_until_under_the_cap() {
+        let file = |name: &str, size: u64, secs: u64| CacheFile {
+            path: PathBuf::from(name),
+            size,
+            last_used: UNIX_EPOCH + Duration::from_secs(secs),
+        };
+        let files = vec![file("new", 40, 300), file("old", 40, 100), file("mid", 40, 200)];
+        assert_eq!(evictions(files.clone(), 120), Vec::<PathBuf>::new());
+        assert_eq!(evictions(files.clone(), 100), vec![PathBuf::from("old")]);
+        assert_eq!(evictions(files, 40), vec![PathBuf::from("old"), PathBuf::from("mid")]);
+        assert!(is_fresh(1000, 1000 + 59, Duration::from_secs(60)));
+        assert!(!is_fresh(1000, 1000 + 60, Duration::from_secs(60)));
+    }
+}
@@ -2,30 +2,34 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chromiumoxide::browser::{Browser, BrowserConfig};
 use chromiumoxide::cdp::browser_protocol::accessibility::{
     AxNode, AxPropertyName, GetFullAxTreeParams,
 };
 use chromiumoxide::cdp::browser_protocol::dom::{
-    BackendNodeId, GetBoxModelParams, ResolveNodeParams,
+    BackendNodeId, GetBoxModelParams, ScrollIntoViewIfNeededParams, SetFileInputFilesParams,
 };
 use chromiumoxide::cdp::browser_protocol::input::{
     DispatchKeyEventParams, DispatchKeyEventType, DispatchMouseEventParams,
     DispatchMouseEventType, MouseButton,
 };
 use chromiumoxide::cdp::browser_protocol::network::SetCookieParams;
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
 use chromiumoxide::cdp::browser_protocol::page::{
     AddScriptToEvaluateOnNewDocumentParams,
-    CaptureScreenshotFormat, CloseParams, HandleJavaScriptDialogParams, NavigateParams,
-    ReloadParams,
+    CaptureScreenshotFormat, CloseParams, EventFrameNavigated, EventNavigatedWithinDocument,
+    HandleJavaScriptDialogParams, NavigateParams, ReloadParams, SetDownloadBehaviorBehavior, SetDownloadBehaviorParams,
 };
 use chromiumoxide::page::ScreenshotParams;
 use chromiumoxide::handler::Handler;
 use chromiumoxide::Page;
 use futures::StreamExt;
+use thiserror::Error;
 use tokio::sync::Mutex;
+use tracing::{error, info, warn};
 
 // paths to check for DevToolsActivePort (for connecting to existing chrome)
 #[cfg(target_os = "macos")]
@@ -45,6 +49,453 @@ const CHROME_PROFILES: &[&str] = &[
 #[cfg(not(any(target_os = "macos", target_os = "windows")))]
 const CHROME_PROFILES: &[&str] = &[];
 
+#[cfg(target_os = "macos")]
+const EDGE_PROFILES: &[&str] = &["Library/Application Support/Microsoft Edge"];
+
+#[cfg(target_os = "windows")]
+const EDGE_PROFILES: &[&str] = &["AppData/Local/Microsoft/Edge/User Data"];
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const EDGE_PROFILES: &[&str] = &[];
+
+// debugging port we launch browsers with
+const DEBUG_PORT: u16 = 9222;
+
+// env var holding the user's browser choice (persisted via the app .env)
+pub const BROWSER_KIND_ENV: &str = "HEYWORK_BROWSER";
+
+// env var for running the automation browser without a window (for long batch jobs)
+pub const BROWSER_HEADLESS_ENV: &str = "HEYWORK_BROWSER_HEADLESS";
+
+// env var for whether the automation profile is kept between runs or thrown away after each
+pub const BROWSER_SESSION_MODE_ENV: &str = "HEYWORK_BROWSER_SESSION_MODE";
+
+// prefix of the throwaway profile dirs ephemeral sessions make in the temp folder
+const EPHEMERAL_PROFILE_PREFIX: &str = "heywork-ephemeral-";
+
+/// whether the automation profile persists (cookies, logins) or each run starts fresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserSessionMode {
+    /// the dedicated automation profile, kept between runs
+    #[default]
+    Persistent,
+    /// a new profile in the temp folder per run, deleted when the browser disconnects
+    Ephemeral,
+}
+
+impl BrowserSessionMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "persistent" => Some(Self::Persistent),
+            "ephemeral" => Some(Self::Ephemeral),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Persistent => "persistent",
+            Self::Ephemeral => "ephemeral",
+        }
+    }
+}
+
+/// session mode chosen in settings (defaults to persistent)
+pub fn selected_session_mode() -> BrowserSessionMode {
+    std::env::var(BROWSER_SESSION_MODE_ENV)
+        .ok()
+        .and_then(|v| BrowserSessionMode::parse(&v))
+        .unwrap_or_default()
+}
+
+/// which browser the automation backend drives - all of them speak CDP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserKind {
+    #[default]
+    Chrome,
+    Edge,
+    Firefox,
+}
+
+impl BrowserKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "chrome" | "google chrome" => Some(Self::Chrome),
+            "edge" | "msedge" | "microsoft edge" => Some(Self::Edge),
+            "firefox" => Some(Self::Firefox),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chrome => "chrome",
+            Self::Edge => "edge",
+            Self::Firefox => "firefox",
+        }
+    }
+
+    /// application name as shown by the OS (used for quitting on macOS)
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Chrome => "Google Chrome",
+            Self::Edge => "Microsoft Edge",
+            Self::Firefox => "Firefox",
+        }
+    }
+
+    /// error returned by connect() when the browser is running without a debug port
+    pub fn needs_restart_error(&self) -> String {
+        format!("{}_NEEDS_RESTART", self.as_str().to_uppercase())
+    }
+
+    fn is_chromium(&self) -> bool {
+        !matches!(self, Self::Firefox)
+    }
+
+    // whether the `Browser` field of /json/version ("Chrome/124.0...", "Edg/124.0...") is this browser
+    fn reports_as(&self, product: &str) -> bool {
+        match self {
+            Self::Chrome => product.contains("Chrome/"),
+            Self::Edge => product.contains("Edg/"),
+            Self::Firefox => product.contains("Firefox/"),
+        }
+    }
+
+    // profile dirs that can contain a DevToolsActivePort file
+    fn devtools_profiles(&self) -> &'static [&'static str] {
+        match self {
+            Self::Chrome => CHROME_PROFILES,
+            Self::Edge => EDGE_PROFILES,
+            Self::Firefox => &[],
+        }
+    }
+
+    // process name for pgrep -x / tasklist
+    fn process_name(&self) -> &'static str {
+        #[cfg(target_os = "windows")]
+        {
+            match self {
+                Self::Chrome => "chrome.exe",
+                Self::Edge => "msedge.exe",
+                Self::Firefox => "firefox.exe",
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            match self {
+                Self::Chrome => "Google Chrome",
+                Self::Edge => "Microsoft Edge",
+                Self::Firefox => "firefox",
+            }
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            match self {
+                Self::Chrome => "chrome",
+                Self::Edge => "msedge",
+                Self::Firefox => "firefox",
+            }
+        }
+    }
+
+    // dedicated automation profile, separate from the user's main profile
+    fn debug_profile_dir(&self) -> PathBuf {
+        let name = match self {
+            Self::Chrome => "heywork-chrome",
+            Self::Edge => "heywork-edge",
+            Self::Firefox => "heywork-firefox",
+        };
+
+        #[cfg(target_os = "windows")]
+        {
+            if let Some(base) = dirs::data_local_dir() {
+                return base.join("hey-work").join(name);
+            }
+        }
+        profile_base_dir().join(format!(".{}", name))
+    }
+
+    // profile a launch uses: the automation profile, or a fresh throwaway one for an ephemeral session
+    fn launch_profile_dir(&self, mode: BrowserSessionMode) -> PathBuf {
+        match mode {
+            BrowserSessionMode::Persistent => self.debug_profile_dir(),
+            BrowserSessionMode::Ephemeral => std::env::temp_dir()
+                .join(format!("{}{}-{}", EPHEMERAL_PROFILE_PREFIX, self.as_str(), uuid::Uuid::new_v4())),
+        }
+    }
+
+    // command-line flags to start the browser with remote debugging enabled
+    fn debug_args(&self, user_data_dir: &std::path::Path) -> Vec<String> {
+        let dir = user_data_dir.to_string_lossy();
+        let headless = headless_enabled();
+        if !self.is_chromium() {
+            // firefox: CDP is enabled via remote.active-protocols in user.js (see prepare_firefox_profile)
+            let mut args = vec![
+                format!("--remote-debugging-port={}", DEBUG_PORT),
+                "--profile".to_string(),
+                dir.to_string(),
+                "--no-remote".to_string(),
+                "--new-instance".to_string(),
+            ];
+            if headless {
+                args.push("--headless".to_string());
+            }
+            return args;
+        }
+
+        let mut args = vec![
+            format!("--remote-debugging-port={}", DEBUG_PORT),
+            format!("--user-data-dir={}", dir),
+            "--profile-directory=Default".to_string(),
+        ];
+        args.extend(chromium_launch_args(headless).into_iter().map(|a| a.to_string()));
+        args
+    }
+}
+
+impl std::fmt::Display for BrowserKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+/// whether the automation browser runs without a window (defaults to off)
+pub fn headless_enabled() -> bool {
+    std::env::var(BROWSER_HEADLESS_ENV)
+        .map(|v| matches!(v.trim(), "1" | "true"))
+        .unwrap_or(false)
+}
+
+/// browser chosen in settings (defaults to Chrome)
+pub fn selected_browser_kind() -> BrowserKind {
+    std::env::var(BROWSER_KIND_ENV)
+        .ok()
+        .and_then(|v| BrowserKind::parse(&v))
+        .unwrap_or_default()
+}
+
+/// errors from the browser client - messages are what the model sees as "Browser error: ..."
+#[derive(Error, Debug)]
+pub enum BrowserError {
+    #[error("Browser not connected")]
+    NotConnected,
+    /// browser is running without a debug port and has to be relaunched
+    #[error("{}", .0.needs_restart_error())]
+    NeedsRestart(BrowserKind),
+    #[error("uid not found: {uid}")]
+    ElementNotFound { uid: String },
+    #[error("stale uid from snapshot {snapshot_id}, current is {current}. take a new snapshot first.")]
+    StaleUid { snapshot_id: u64, current: u64 },
+    /// the uid mapped to a DOM node that has since been removed (the page re-rendered)
+    #[error("failed to get box model for element")]
+    ElementDetached { uid: String },
+    /// re-snapshotting after a failed lookup didn't find the same element again
+    #[error("{}", unresolved_message(.uid, .candidates))]
+    Unresolved { uid: String, candidates: Vec<String> },
+    /// the tab went to another page since the snapshot its uids came from
+    #[error("the page navigated to {url} since the last snapshot, so its uids are gone. take a new snapshot first.")]
+    Navigated { url: String },
+    #[error("{0}")]
+    NavigationTimeout(String),
+    /// the installed browser can't be driven over CDP at all
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("{0}")]
+    Cdp(String),
+}
+
+impl BrowserError {
+    /// the uid no longer points at an element - a fresh snapshot usually fixes it
+    pub fn is_element_lookup(&self) -> bool {
+        matches!(
+            self,
+            Self::ElementNotFound { .. } | Self::StaleUid { .. } | Self::ElementDetached { .. }
+        )
+    }
+}
+
+fn unresolved_message(uid: &str, candidates: &[String]) -> String {
+    if candidates.is_empty() {
+        return format!("uid {uid} no longer matches anything on the page. take a new snapshot first.");
+    }
+    format!(
+        "uid {uid} no longer matches anything on the page. closest elements in the new snapshot:\n{}",
+        candidates.join("\n")
+    )
+}
+
+impl From<chromiumoxide::error::CdpError> for BrowserError {
+    fn from(e: chromiumoxide::error::CdpError) -> Self {
+        Self::Cdp(e.to_string())
+    }
+}
+
+// launch helpers still use anyhow internally; their context message is kept as-is
+impl From<anyhow::Error> for BrowserError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Cdp(e.to_string())
+    }
+}
+
+pub type Result<T, E = BrowserError> = std::result::Result<T, E>;
+
+// anti-detection flags shared by every chromium launch path
+const CHROMIUM_ANTI_DETECTION_ARGS: &[&str] = &[
+    "--no-first-run",
+    "--no-default-browser-check",
+    "--disable-blink-features=AutomationControlled",
+    "--disable-features=AutomationControlled",
+    "--disable-infobars",
+    "--disable-background-timer-throttling",
+    "--disable-backgrounding-occluded-windows",
+    "--disable-renderer-backgrounding",
+    "--disable-ipc-flooding-protection",
+    "--password-store=basic",
+    "--use-mock-keychain",
+    "--lang=en-US,en",
+];
+
+// flags that only matter when there's a real window to hide or occlude
+const VISIBLE_WINDOW_ARGS: &[&str] = &[
+    "--disable-infobars",
+    "--disable-backgrounding-occluded-windows",
+];
+
+// new headless mode renders like the real browser, so sites and screenshots behave the same
+const HEADLESS_ARGS: &[&str] = &["--headless=new", "--window-size=1920,1080"];
+
+fn chromium_launch_args(headless: bool) -> Vec<&'static str> {
+    if !headless {
+        return CHROMIUM_ANTI_DETECTION_ARGS.to_vec();
+    }
+    CHROMIUM_ANTI_DETECTION_ARGS
+        .iter()
+        .copied()
+        .filter(|arg| !VISIBLE_WINDOW_ARGS.contains(arg))
+        .chain(HEADLESS_ARGS.iter().copied())
+        .collect()
+}
+
+// suffixes browsers use for downloads that are still in flight
+const PARTIAL_DOWNLOAD_SUFFIXES: &[&str] = &[".crdownload", ".part", ".download", ".tmp"];
+
+// pause after scroll_to so lazy-loaded content can render before the re-snapshot
+const SCROLL_SETTLE_MS: u64 = 400;
+
+// most newly appeared elements scroll_to lists
+const MAX_SCROLL_DELTA: usize = 30;
+
+// how long to wait for a download to start before assuming the click navigated instead
+const DOWNLOAD_START_GRACE_MS: u64 = 3000;
+
+/// what a snapshot uid pointed at, kept so a stale uid can be found again after a re-render
+struct SnapshotElement {
+    backend_id: BackendNodeId,
+    role: String,
+    name: String,
+}
+
+/// page_action operations that target a single element by uid
+#[derive(Debug, Clone, Copy)]
+pub enum ElementAction<'a> {
+    Click,
+    DoubleClick,
+    Fill(&'a str),
+    Hover,
+    ScrollTo,
+}
+
+/// result of browser_download - either a finished file or a normal navigation
+pub enum DownloadOutcome {
+    Completed(crate::files::CreatedFile),
+    Navigated(String),
+}
+
+// downloads land in ~/Downloads/hey-work so the file can be opened from the chat
+fn download_dir() -> PathBuf {
+    dirs::download_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("hey-work")
+}
+
+fn list_dir_names(dir: &std::path::Path) -> std::collections::HashSet<String> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_partial_download(name: &str) -> bool {
+    PARTIAL_DOWNLOAD_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+// main-frame navigations on one tab, recorded by a listener task so the agent notices page
+// changes it didn't make (redirects, form submits, the user clicking around)
+struct NavigationWatch {
+    target: TargetId,
+    // url of the latest navigation since the watch was last cleared
+    navigated_to: Arc<std::sync::Mutex<Option<String>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl NavigationWatch {
+    async fn start(page: &Page) -> Result<Self> {
+        let mut navigated = page.event_listener::<EventFrameNavigated>().await?;
+        // history.pushState route changes in single-page apps
+        let mut within_document = page.event_listener::<EventNavigatedWithinDocument>().await?;
+        let main_frame = page.mainframe().await?;
+
+        let navigated_to = Arc::new(std::sync::Mutex::new(None));
+        let latest = navigated_to.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let url = tokio::select! {
+                    Some(event) = navigated.next() => {
+                        // iframes loading ads or widgets aren't a page change
+                        if event.frame.parent_id.is_some() {
+                            continue;
+                        }
+                        event.frame.url.clone()
+                    }
+                    Some(event) = within_document.next() => {
+                        if main_frame.as_ref() != Some(&event.frame_id) {
+                            continue;
+                        }
+                        event.url.clone()
+                    }
+                    else => break,
+                };
+                *latest.lock().unwrap() = Some(url);
+            }
+        });
+
+        Ok(Self { target: page.target_id().clone(), navigated_to, task })
+    }
+
+    fn navigated_to(&self) -> Option<String> {
+        self.navigated_to.lock().unwrap().clone()
+    }
+
+    fn clear(&self) {
+        *self.navigated_to.lock().unwrap() = None;
+    }
+}
+
+impl Drop for NavigationWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
 pub struct BrowserClient {
     browser: Browser,
     _handler_task: tokio::task::JoinHandle<()>,
@@ -52,74 +503,168 @@ pub struct BrowserClient {
     selected_page_idx: usize,
     // snapshot state
     snapshot_id: u64,
-    uid_to_backend_node: HashMap<String, BackendNodeId>,
+    snapshot_elements: HashMap<String, SnapshotElement>,
+    // elements of the snapshot before, so uids the model kept can still be re-resolved
+    previous_elements: HashMap<String, SnapshotElement>,
+    // last read_page extraction as (url, markdown)
+    read_cache: Option<(String, String)>,
+    // navigations on the snapshotted tab since the snapshot was taken
+    navigation: Option<NavigationWatch>,
+    // throwaway profile of an ephemeral session, deleted on disconnect
+    ephemeral_profile: Option<PathBuf>,
+}
+
+// a client dropped without disconnect() - replaced by a restart, or after a panic - can't ask
+// its browser to quit politely from here, so it kills the process it launched before taking the
+// throwaway profile with it. otherwise the browser keeps running with the session's cookies
+impl Drop for BrowserClient {
+    fn drop(&mut self) {
+        let Some(profile) = self.ephemeral_profile.take() else {
+            return;
+        };
+        if let Some(child) = self.browser.get_mut_child() {
+            let child = child.as_mut_inner();
+            match child.start_kill() {
+                Ok(()) => {
+                    // give it a moment to let go of the profile
+                    for _ in 0..20 {
+                        if matches!(child.try_wait(), Ok(Some(_))) {
+                            break;
+                        }
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                    }
+                }
+                Err(e) => warn!("couldn't kill the ephemeral browser: {}", e),
+            }
+        }
+        match std::fs::remove_dir_all(&profile) {
+            Ok(()) => info!(profile = %profile.display(), "deleted ephemeral profile"),
+            Err(e) => error!(profile = %profile.display(), "couldn't delete ephemeral profile: {}", e),
+        }
+    }
 }
 
 impl BrowserClient {
     pub async fn connect() -> Result<Self> {
-        // try to connect to existing chrome first
-        if let Some(ws_url) = try_find_existing_chrome().await {
-            println!("[browser] Connecting to existing Chrome at {}", ws_url);
+        Self::connect_with(selected_browser_kind()).await
+    }
+
+    pub async fn connect_with(kind: BrowserKind) -> Result<Self> {
+        ensure_supported(kind)?;
+        if selected_session_mode() == BrowserSessionMode::Ephemeral {
+            return Self::launch_ephemeral(kind).await;
+        }
+
+        // try to connect to an existing debuggable instance first
+        if let Some(ws_url) = try_find_existing_browser(kind).await {
+            println!("[browser] Connecting to existing {} at {}", kind, ws_url);
             match Browser::connect(&ws_url).await {
-                Ok((mut browser, handler)) => {
-                    let handler_task = tokio::spawn(async move {
-                        handler_loop(handler).await;
-                    });
-
-                    // fetch existing targets so we can see tabs that were already open
-                    let _ = browser.fetch_targets().await;
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                    let pages = browser.pages().await.unwrap_or_default();
-                    println!("[browser] Found {} existing pages", pages.len());
-
-                    return Ok(Self {
-                        browser,
-                        _handler_task: handler_task,
-                        pages,
-                        selected_page_idx: 0,
-                        snapshot_id: 0,
-                        uid_to_backend_node: HashMap::new(),
-                    });
-                }
+                Ok((browser, handler)) => return Ok(Self::from_connection(browser, handler).await),
                 Err(e) => {
-                    println!("[browser] Failed to connect to existing Chrome: {}", e);
+                    println!("[browser] Failed to connect to existing {}: {}", kind, e);
+                }
+            }
+        }
+
+        // no existing instance with debugging, try to launch a new one
+        // on macOS, this only works if the browser isn't already running
+        println!("[browser] Launching {} with automation profile...", kind);
+        let user_data_dir = kind.debug_profile_dir();
+        let launched = if kind.is_chromium() {
+            launch_chromium_with_profile(kind, &user_data_dir).await
+        } else {
+            launch_firefox_with_profile(&user_data_dir).await
+        };
+
+        match launched {
+            Ok((browser, handler)) => Ok(Self::from_connection(browser, handler).await),
+            Err(e) => {
+                // check if the browser is already running without debugging
+                if is_browser_running(kind) {
+                    return Err(BrowserError::NeedsRestart(kind));
                 }
+                Err(e.into())
             }
         }
+    }
 
-        // no existing chrome with debugging, try to launch a new one
-        // on macOS, this only works if Chrome isn't already running
-        println!("[browser] Launching Chrome with user profile...");
-        let (browser, handler) = match launch_chrome_with_profile().await {
-            Ok(b) => b,
+    // a browser of its own on a fresh profile - a running instance would be on the kept one
+    async fn launch_ephemeral(kind: BrowserKind) -> Result<Self> {
+        remove_stale_ephemeral_profiles();
+        let user_data_dir = kind.launch_profile_dir(BrowserSessionMode::Ephemeral);
+        println!("[browser] Launching {} with a throwaway profile at {:?}", kind, user_data_dir);
+        let launched = if kind.is_chromium() {
+            launch_chromium_with_profile(kind, &user_data_dir).await
+        } else {
+            launch_firefox_with_profile(&user_data_dir).await
+        };
+        match launched {
+            Ok((browser, handler)) => {
+                let mut client = Self::from_connection(browser, handler).await;
+                client.ephemeral_profile = Some(user_data_dir);
+                Ok(client)
+            }
             Err(e) => {
-                // check if chrome is already running without debugging
-                if is_chrome_running() {
-                    return Err(anyhow!("CHROME_NEEDS_RESTART"));
+                let _ = std::fs::remove_dir_all(&user_data_dir);
+                if is_browser_running(kind) {
+                    return Err(BrowserError::NeedsRestart(kind));
                 }
-                return Err(e);
+                Err(e.into())
             }
+        }
+    }
+
+    /// whether this connection runs on a throwaway profile that goes away on disconnect
+    pub fn is_ephemeral(&self) -> bool {
+        self.ephemeral_profile.is_some()
+    }
+
+    /// close the connection. an ephemeral session's browser is quit and its profile deleted,
+    /// so no cookies or logins outlive the run
+    pub async fn disconnect(mut self) {
+        self.navigation = None;
+        let Some(profile) = self.ephemeral_profile.take() else {
+            return;
         };
+        if let Err(e) = self.browser.close().await {
+            warn!("couldn't close the ephemeral browser: {}", e);
+        }
+        let _ = self.browser.wait().await;
+        match std::fs::remove_dir_all(&profile) {
+            Ok(()) => info!(profile = %profile.display(), "deleted ephemeral profile"),
+            Err(e) => error!(profile = %profile.display(), "couldn't delete ephemeral profile: {}", e),
+        }
+    }
 
+    // wrap a fresh CDP connection, picking up tabs that were already open
+    async fn from_connection(mut browser: Browser, handler: Handler) -> Self {
         let handler_task = tokio::spawn(async move {
             handler_loop(handler).await;
         });
 
+        let _ = browser.fetch_targets().await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         let pages = browser.pages().await.unwrap_or_default();
-        Ok(Self {
+        println!("[browser] Found {} existing pages", pages.len());
+
+        Self {
             browser,
             _handler_task: handler_task,
             pages,
             selected_page_idx: 0,
             snapshot_id: 0,
-            uid_to_backend_node: HashMap::new(),
-        })
+            snapshot_elements: HashMap::new(),
+            previous_elements: HashMap::new(),
+            read_cache: None,
+            navigation: None,
+            ephemeral_profile: None,
+        }
     }
 
     fn selected_page(&self) -> Result<&Page> {
         self.pages
             .get(self.selected_page_idx)
-            .ok_or_else(|| anyhow!("no page selected"))
+            .ok_or_else(|| BrowserError::Cdp("no page selected".into()))
     }
 
     // refresh page list from browser
@@ -136,6 +681,8 @@ impl BrowserClient {
         println!("[browser] take_snapshot: starting");
         let start = std::time::Instant::now();
 
+        // cleared before reading the tree, so a navigation during the read still counts
+        self.reset_navigation_watch().await;
         let page = self.selected_page()?;
         println!("[browser] take_snapshot: got page, calling GetFullAxTree...");
 
@@ -146,18 +693,90 @@ impl BrowserClient {
         println!("[browser] take_snapshot: GetFullAxTree returned in {:?}", start.elapsed());
 
         self.snapshot_id += 1;
-        self.uid_to_backend_node.clear();
+        self.previous_elements = std::mem::take(&mut self.snapshot_elements);
 
         let nodes = resp.result.nodes;
         println!("[browser] take_snapshot: formatting {} nodes", nodes.len());
-        let snapshot_text = format_ax_tree(&nodes, self.snapshot_id, verbose, &mut self.uid_to_backend_node);
+        let snapshot_text = format_ax_tree(&nodes, self.snapshot_id, verbose, &mut self.snapshot_elements);
         println!("[browser] take_snapshot: done in {:?}, {} chars", start.elapsed(), snapshot_text.len());
 
         Ok(snapshot_text)
     }
 
+    // point the navigation watch at the selected tab and forget what it has seen so far.
+    // switching tabs starts a new watch, so there's only ever one listener
+    async fn reset_navigation_watch(&mut self) {
+        let Ok(page) = self.selected_page().cloned() else {
+            return;
+        };
+        let watching = self.navigation.as_ref().is_some_and(|watch| watch.target == *page.target_id());
+        if !watching {
+            self.navigation = match NavigationWatch::start(&page).await {
+                Ok(watch) => Some(watch),
+                Err(e) => {
+                    println!("[browser] Couldn't watch navigations: {}", e);
+                    None
+                }
+            };
+        }
+        if let Some(watch) = &self.navigation {
+            watch.clear();
+        }
+    }
+
+    /// where the selected tab went since the last snapshot, if it navigated at all
+    pub fn navigated_since_snapshot(&self) -> Option<String> {
+        let watch = self.navigation.as_ref()?;
+        let page = self.selected_page().ok()?;
+        if watch.target != *page.target_id() {
+            return None;
+        }
+        watch.navigated_to()
+    }
+
+    // anything that can change the page's text (clicks, typing, navigation, tab switches)
+    // makes the last read_page extraction stale, even when the url stays the same
+    fn invalidate_read_cache(&mut self) {
+        self.read_cache = None;
+    }
+
+    // tool: read_page - article text as markdown, much cheaper than the a11y tree
+    pub async fn read_page(&mut self, max_chars: usize) -> Result<String> {
+        let page = self.selected_page()?;
+        let url = page.url().await.ok().flatten().unwrap_or_default();
+
+        if let Some((cached_url, markdown)) = &self.read_cache {
+            if *cached_url == url {
+                println!("[browser] read_page: cache hit for {}", url);
+                return Ok(truncate_chars(markdown, max_chars));
+            }
+        }
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            page.evaluate(Self::read_page_script()),
+        )
+        .await
+        .map_err(|_| BrowserError::NavigationTimeout("read_page timed out".into()))?
+        .context("failed to extract page content")?;
+
+        let extracted: serde_json::Value = result.into_value().context("unexpected read_page result")?;
+        let title = extracted.get("title").and_then(|v| v.as_str()).unwrap_or("");
+        let body = extracted.get("markdown").and_then(|v| v.as_str()).unwrap_or("");
+        if body.trim().is_empty() {
+            return Err(BrowserError::Cdp("no readable content found - try see_page or a screenshot".into()));
+        }
+
+        let markdown = format!("# {title}\n{url}\n\n{body}");
+        println!("[browser] read_page: extracted {} chars from {}", markdown.len(), url);
+        self.read_cache = Some((url, markdown.clone()));
+
+        Ok(truncate_chars(&markdown, max_chars))
+    }
+
     // tool: click
     pub async fn click(&mut self, uid: &str, dbl_click: bool) -> Result<String> {
+        self.invalidate_read_cache();
         println!("[browser] click: resolving uid {}", uid);
         let start = std::time::Instant::now();
         let (x, y) = self.resolve_uid_to_point(uid).await?;
@@ -209,6 +828,7 @@ impl BrowserClient {
 
     // tool: hover
     pub async fn hover(&mut self, uid: &str) -> Result<String> {
+        self.invalidate_read_cache();
         let (x, y) = self.resolve_uid_to_point(uid).await?;
         let page = self.selected_page()?;
 
@@ -225,8 +845,66 @@ impl BrowserClient {
         Ok("Successfully hovered over element".to_string())
     }
 
+    // tool: scroll_to - bring one element into view instead of guessing a scroll distance,
+    // then re-snapshot and report the element's new uid plus whatever appeared
+    pub async fn scroll_to(&mut self, uid: &str) -> Result<String> {
+        self.invalidate_read_cache();
+        let backend_node_id = self.get_backend_node_id(uid)?;
+        let page = self.selected_page()?;
+        page.execute(
+            ScrollIntoViewIfNeededParams::builder()
+                .backend_node_id(backend_node_id)
+                .build(),
+        )
+        .await
+        .map_err(|_| BrowserError::ElementDetached { uid: uid.to_string() })?;
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(SCROLL_SETTLE_MS)).await;
+        let seen: std::collections::HashSet<(String, String)> = self
+            .snapshot_elements
+            .values()
+            .map(|el| (el.role.clone(), el.name.clone()))
+            .collect();
+        self.take_snapshot(false).await?;
+
+        // backend ids survive a re-snapshot, so the element keeps its identity under a new uid
+        let new_uid = self
+            .snapshot_elements
+            .iter()
+            .find(|(_, el)| el.backend_id == backend_node_id)
+            .map(|(new_uid, _)| new_uid.clone());
+        let mut appeared: Vec<(u64, String)> = self
+            .snapshot_elements
+            .iter()
+            .filter(|(_, el)| !el.name.is_empty() && !seen.contains(&(el.role.clone(), el.name.clone())))
+            .filter_map(|(uid, el)| {
+                let index = uid.rsplit('_').next()?.parse().ok()?;
+                Some((index, format!("uid={uid} {} \"{}\"", el.role, el.name)))
+            })
+            .collect();
+        appeared.sort_by_key(|(index, _)| *index);
+
+        let mut output = match new_uid {
+            Some(new_uid) => format!("Scrolled {uid} into view - it is now {new_uid} (snapshot refreshed)"),
+            None => format!("Scrolled {uid} into view (snapshot refreshed)"),
+        };
+        if appeared.is_empty() {
+            output.push_str("\nNo new elements appeared");
+        } else {
+            output.push_str(&format!("\n{} new elements:", appeared.len()));
+            for (_, line) in appeared.iter().take(MAX_SCROLL_DELTA) {
+                output.push_str(&format!("\n{line}"));
+            }
+            if appeared.len() > MAX_SCROLL_DELTA {
+                output.push_str(&format!("\n... {} more - use see_page for the full list", appeared.len() - MAX_SCROLL_DELTA));
+            }
+        }
+        Ok(output)
+    }
+
     // tool: fill
     pub async fn fill(&mut self, uid: &str, value: &str) -> Result<String> {
+        self.invalidate_read_cache();
         // click first to focus
         self.click(uid, false).await?;
 
@@ -268,6 +946,7 @@ impl BrowserClient {
 
     // tool: press_key
     pub async fn press_key(&mut self, key: &str) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.selected_page()?;
 
         // parse modifiers from key string like "Control+A" or "Enter"
@@ -317,6 +996,7 @@ impl BrowserClient {
 
     // tool: scroll - uses JS for reliability (CDP Input.dispatchMouseEvent can timeout)
     pub async fn scroll(&mut self, direction: &str, amount: Option<i64>) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.selected_page()?;
         let pixels = amount.unwrap_or(500);
 
@@ -325,7 +1005,7 @@ impl BrowserClient {
             "down" => (0, pixels),
             "left" => (-pixels, 0),
             "right" => (pixels, 0),
-            _ => return Err(anyhow!("Invalid scroll direction: {}", direction)),
+            _ => return Err(BrowserError::Cdp(format!("Invalid scroll direction: {}", direction))),
         };
 
         // use JS scrollBy - faster and more reliable than CDP mouse wheel events
@@ -336,8 +1016,8 @@ impl BrowserClient {
             page.evaluate(js)
         ).await {
             Ok(Ok(_)) => Ok(format!("Scrolled {} by {} pixels", direction, pixels)),
-            Ok(Err(e)) => Err(anyhow!("Scroll failed: {e}")),
-            Err(_) => Err(anyhow!("Scroll timed out")),
+            Ok(Err(e)) => Err(BrowserError::Cdp(format!("Scroll failed: {e}"))),
+            Err(_) => Err(BrowserError::Cdp("Scroll timed out".into())),
         }
     }
 
@@ -348,17 +1028,18 @@ impl BrowserClient {
         url: Option<&str>,
         ignore_cache: bool,
     ) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.selected_page()?;
 
         match nav_type {
             "url" => {
-                let url = url.ok_or_else(|| anyhow!("url required for type=url"))?;
+                let url = url.ok_or_else(|| BrowserError::Cdp("url required for type=url".into()))?;
                 // don't wait for full page load - heavy sites timeout
                 // agent can take_snapshot to verify when ready
                 let nav_future = page.execute(NavigateParams::builder().url(url).build().unwrap());
                 match tokio::time::timeout(std::time::Duration::from_secs(5), nav_future).await {
                     Ok(Ok(_)) => Ok(format!("Navigated to {url}")),
-                    Ok(Err(e)) => Err(anyhow!("Navigation failed: {e}")),
+                    Ok(Err(e)) => Err(BrowserError::Cdp(format!("Navigation failed: {e}"))),
                     Err(_) => Ok(format!("Navigating to {url} (page still loading, use take_snapshot to check)")),
                 }
             }
@@ -380,13 +1061,14 @@ impl BrowserClient {
                 .await?;
                 Ok("Successfully reloaded page".to_string())
             }
-            _ => Err(anyhow!("unknown navigation type: {nav_type}")),
+            _ => Err(BrowserError::Cdp(format!("unknown navigation type: {nav_type}"))),
         }
     }
 
     // tool: wait_for
     // uses fast JS evaluation instead of heavy a11y tree polling
     pub async fn wait_for(&mut self, text: &str, timeout_ms: u64) -> Result<String> {
+        self.invalidate_read_cache();
         let start = std::time::Instant::now();
         let timeout = std::time::Duration::from_millis(timeout_ms);
         let page = self.selected_page()?;
@@ -400,7 +1082,7 @@ impl BrowserClient {
         loop {
             // check timeout FIRST
             if start.elapsed() > timeout {
-                return Err(anyhow!("timeout waiting for text: {text}"));
+                return Err(BrowserError::NavigationTimeout(format!("timeout waiting for text: {text}")));
             }
 
             // use JS evaluation - much faster than GetFullAXTree
@@ -429,52 +1111,131 @@ impl BrowserClient {
         }
     }
 
-    // tool: upload_file
-    pub async fn upload_file(&mut self, uid: &str, file_path: &str) -> Result<String> {
-        let backend_node_id = self.get_backend_node_id(uid)?;
+    // tool: browser_download
+    // triggers a download (click on uid, or direct url) and waits for the file to finish
+    pub async fn download(
+        &mut self,
+        uid: Option<&str>,
+        url: Option<&str>,
+        timeout_ms: u64,
+    ) -> Result<DownloadOutcome> {
+        let dir = download_dir();
+        std::fs::create_dir_all(&dir).context("failed to create download folder")?;
+
         let page = self.selected_page()?;
+        page.execute(
+            SetDownloadBehaviorParams::builder()
+                .behavior(SetDownloadBehaviorBehavior::Allow)
+                .download_path(dir.to_string_lossy().to_string())
+                .build()
+                .map_err(BrowserError::Cdp)?,
+        )
+        .await
+        .context("failed to set download behavior")?;
 
-        // resolve node to get remote object
-        let resolve_resp = page
-            .execute(
-                ResolveNodeParams::builder()
-                    .backend_node_id(backend_node_id)
-                    .build(),
-            )
-            .await?;
+        let before = list_dir_names(&dir);
+        let start_url = page.url().await.ok().flatten().unwrap_or_default();
+
+        match (uid, url) {
+            (Some(uid), _) => {
+                self.click(uid, false).await?;
+            }
+            (None, Some(url)) => {
+                // anchor with download attr so the browser saves instead of rendering
+                let js = format!(
+                    r#"(() => {{
+                        const a = document.createElement('a');
+                        a.href = {};
+                        a.download = '';
+                        document.body.appendChild(a);
+                        a.click();
+                        a.remove();
+                    }})()"#,
+                    serde_json::Value::from(url).to_string()
+                );
+                self.selected_page()?.evaluate(js).await?;
+            }
+            (None, None) => return Err(BrowserError::Cdp("browser_download needs either click or url".into())),
+        }
 
-        let object_id = resolve_resp
-            .result
-            .object
-            .object_id
-            .ok_or_else(|| anyhow!("could not resolve element"))?;
+        let start = std::time::Instant::now();
+        let grace = std::time::Duration::from_millis(DOWNLOAD_START_GRACE_MS.min(timeout_ms));
+        let timeout = std::time::Duration::from_millis(timeout_ms);
 
-        // set file via js
-        let js = format!(
-            r#"
-            (function(files) {{
-                const input = this;
-                const dt = new DataTransfer();
-                for (const f of files) {{
-                    dt.items.add(new File([''], f));
-                }}
-                input.files = dt.files;
-                input.dispatchEvent(new Event('change', {{ bubbles: true }}));
-            }})(["{file_path}"])
-            "#
-        );
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+
+            let new_names: Vec<String> = list_dir_names(&dir)
+                .into_iter()
+                .filter(|n| !before.contains(n))
+                .collect();
+
+            let in_progress = new_names.iter().any(|n| is_partial_download(n));
+            if !in_progress {
+                if let Some(name) = new_names.iter().find(|n| !n.starts_with('.')) {
+                    let path = dir.join(name);
+                    if let Some(file) = crate::files::CreatedFile::from_path(&path.to_string_lossy()) {
+                        println!("[browser] download finished: {} ({} bytes)", file.path, file.size_bytes);
+                        return Ok(DownloadOutcome::Completed(file));
+                    }
+                }
+            }
 
-        page.evaluate(format!(
-            "((obj) => {{ const el = obj; {js} }})(document.querySelector('[data-object-id=\"{}\"]'))",
-            object_id.inner()
-        ))
-        .await?;
+            // nothing started - if the page moved on, it was a regular link
+            if new_names.is_empty() && start.elapsed() > grace {
+                let current_url = self
+                    .selected_page()?
+                    .url()
+                    .await
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                if current_url != start_url {
+                    return Ok(DownloadOutcome::Navigated(current_url));
+                }
+            }
+
+            if start.elapsed() > timeout {
+                if in_progress {
+                    return Err(BrowserError::Cdp(format!("download still in progress after {timeout_ms}ms")));
+                }
+                return Err(BrowserError::Cdp(format!("no download started within {timeout_ms}ms")));
+            }
+        }
+    }
+
+    // tool: upload_file
+    // sets the file on an <input type=file> directly, no native file dialog involved
+    pub async fn upload_file(&mut self, uid: &str, file_path: &str) -> Result<String> {
+        self.invalidate_read_cache();
+        let path = crate::files::validate_path(file_path).map_err(BrowserError::Cdp)?;
+        if !path.is_file() {
+            return Err(BrowserError::Cdp(format!("not a file: {file_path}")));
+        }
+
+        let backend_node_id = self.get_backend_node_id(uid)?;
+        let page = self.selected_page()?;
+
+        page.execute(
+            SetFileInputFilesParams::builder()
+                .file(path.to_string_lossy().to_string())
+                .backend_node_id(backend_node_id)
+                .build()
+                .map_err(BrowserError::Cdp)?,
+        )
+        .await
+        .context("failed to set file on element (is it an <input type=file>?)")?;
 
-        Ok(format!("File uploaded: {file_path}"))
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+        Ok(format!("Uploaded file: {name}"))
     }
 
     // tool: new_page
     pub async fn new_page(&mut self, url: &str) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.browser.new_page(url).await?;
         
         // Inject stealth scripts into the new page/tab so that
@@ -499,6 +1260,7 @@ impl BrowserClient {
     /// scripts and cookies, THEN navigates to the target URL.
     /// This ensures navigator.webdriver is hidden BEFORE Google's scripts run.
     pub async fn new_page_stealth(&mut self, url: &str) -> Result<String> {
+        self.invalidate_read_cache();
         println!("[browser] new_page_stealth: opening about:blank first");
         
         // Step 1: Create a blank page — no target site scripts run yet
@@ -536,6 +1298,32 @@ impl BrowserClient {
         Ok(format!("Created stealth page and navigated to {url}"))
     }
 
+    /// Inject cookies into the automation profile via CDP Network.setCookie.
+    /// Returns how many the browser accepted.
+    pub async fn set_cookies(&mut self, cookies: Vec<SetCookieParams>) -> Result<usize> {
+        self.invalidate_read_cache();
+        if self.pages.is_empty() {
+            self.refresh_pages().await?;
+        }
+        if self.pages.is_empty() {
+            let page = self.browser.new_page("about:blank").await?;
+            self.pages.push(page);
+            self.selected_page_idx = 0;
+        }
+        let page = self.selected_page()?;
+
+        let mut accepted = 0;
+        for cookie in cookies {
+            let name = cookie.name.clone();
+            match page.execute(cookie).await {
+                Ok(resp) if resp.result.success => accepted += 1,
+                Ok(_) => println!("[browser] set_cookies: browser rejected {}", name),
+                Err(e) => println!("[browser] set_cookies: {} failed: {}", name, e),
+            }
+        }
+        Ok(accepted)
+    }
+
     /// Set Google consent and preference cookies on a page via CDP
     async fn set_google_cookies_on_page(page: &Page) {
         // SOCS cookie: Google's consent acceptance cookie (GDPR/CCPA)
@@ -587,6 +1375,7 @@ impl BrowserClient {
 
     /// Try to dismiss any cookie consent overlay on the current page
     pub async fn dismiss_cookie_consent(&mut self) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.selected_page()?;
         let dismiss_js = r#"
         (function() {
@@ -659,13 +1448,14 @@ impl BrowserClient {
 
     // tool: select_page
     pub async fn select_page(&mut self, page_idx: usize, bring_to_front: bool) -> Result<String> {
+        self.invalidate_read_cache();
         self.refresh_pages().await?;
 
         if page_idx >= self.pages.len() {
-            return Err(anyhow!(
+            return Err(BrowserError::Cdp(format!(
                 "page index {page_idx} out of range (0..{})",
                 self.pages.len()
-            ));
+            )));
         }
 
         self.selected_page_idx = page_idx;
@@ -680,17 +1470,18 @@ impl BrowserClient {
 
     // tool: close_page
     pub async fn close_page(&mut self, page_idx: usize) -> Result<String> {
+        self.invalidate_read_cache();
         self.refresh_pages().await?;
 
         if self.pages.len() <= 1 {
-            return Err(anyhow!("cannot close the last open page"));
+            return Err(BrowserError::Cdp("cannot close the last open page".into()));
         }
 
         if page_idx >= self.pages.len() {
-            return Err(anyhow!(
+            return Err(BrowserError::Cdp(format!(
                 "page index {page_idx} out of range (0..{})",
                 self.pages.len()
-            ));
+            )));
         }
 
         let page = &self.pages[page_idx];
@@ -709,6 +1500,7 @@ impl BrowserClient {
 
     // tool: drag (drag element from one uid to another)
     pub async fn drag(&mut self, from_uid: &str, to_uid: &str) -> Result<String> {
+        self.invalidate_read_cache();
         let (from_x, from_y) = self.resolve_uid_to_point(from_uid).await?;
         let (to_x, to_y) = self.resolve_uid_to_point(to_uid).await?;
         let page = self.selected_page()?;
@@ -764,8 +1556,63 @@ impl BrowserClient {
         Ok(format!("Filled {filled} form elements"))
     }
 
+    // tool: autofill_login - type the saved login for this page's domain into its login form.
+    // the values go straight from the keychain into the page, so the result never includes them
+    pub async fn autofill_login(&mut self) -> Result<String> {
+        let url = self.current_url().await?;
+        if !crate::credentials::is_secure_url(&url) {
+            return Err(BrowserError::Cdp(format!(
+                "refusing to fill a saved login on {} - logins are only filled on https pages",
+                url
+            )));
+        }
+        let Some((domain, credential)) = crate::credentials::lookup(&url) else {
+            return Err(BrowserError::Cdp(format!(
+                "no saved login for {} - the user can add one under Settings > Saved logins",
+                crate::credentials::normalize_domain(&url).unwrap_or(url)
+            )));
+        };
+
+        self.take_snapshot(false).await?;
+        // uids are "<snapshot>_<index>", so the index puts elements back in page order
+        let mut elements: Vec<(u64, (String, String, String))> = self
+            .snapshot_elements
+            .iter()
+            .map(|(uid, el)| {
+                let index = uid.rsplit('_').next().and_then(|n| n.parse().ok()).unwrap_or(u64::MAX);
+                (index, (uid.clone(), el.role.clone(), el.name.clone()))
+            })
+            .collect();
+        elements.sort_by_key(|(index, _)| *index);
+        let elements: Vec<_> = elements.into_iter().map(|(_, element)| element).collect();
+
+        let fields = crate::credentials::pick_login_fields(&elements);
+        let mut filled = Vec::new();
+        if let Some(uid) = &fields.username {
+            if !credential.username.is_empty() {
+                self.fill(uid, &credential.username).await?;
+                filled.push(format!("username ({uid})"));
+            }
+        }
+        if let Some(uid) = &fields.password {
+            self.fill(uid, &credential.password).await?;
+            filled.push(format!("password ({uid})"));
+        }
+
+        match (filled.is_empty(), fields.password.is_some()) {
+            (true, _) => Err(BrowserError::Cdp(format!(
+                "found no login fields on this page for {domain} - open the sign-in form first"
+            ))),
+            (false, true) => Ok(format!("Filled credentials for {domain}: {}. Submit the form to sign in.", filled.join(", "))),
+            (false, false) => Ok(format!(
+                "Filled the username for {domain} - no password field yet. Continue to the next step, then call autofill_login again."
+            )),
+        }
+    }
+
     // tool: handle_dialog (accept/dismiss browser dialogs)
     pub async fn handle_dialog(&mut self, accept: bool, prompt_text: Option<&str>) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.selected_page()?;
 
         let params = if let Some(text) = prompt_text {
@@ -802,27 +1649,117 @@ impl BrowserClient {
 
     // helper: get backend node id from uid
     fn get_backend_node_id(&self, uid: &str) -> Result<BackendNodeId> {
+        // backend ids from the old document would point at nothing, or at something else
+        if let Some(url) = self.navigated_since_snapshot() {
+            return Err(BrowserError::Navigated { url });
+        }
+
         // validate snapshot id
         let parts: Vec<&str> = uid.split('_').collect();
         if parts.len() != 2 {
-            return Err(anyhow!("invalid uid format: {uid}"));
+            return Err(BrowserError::Cdp(format!("invalid uid format: {uid}")));
         }
 
         let snapshot_id: u64 = parts[0]
             .parse()
-            .map_err(|_| anyhow!("invalid snapshot id in uid"))?;
+            .map_err(|_| BrowserError::Cdp("invalid snapshot id in uid".into()))?;
 
         if snapshot_id != self.snapshot_id {
-            return Err(anyhow!(
-                "stale uid from snapshot {snapshot_id}, current is {}. take a new snapshot first.",
-                self.snapshot_id
-            ));
+            return Err(BrowserError::StaleUid {
+                snapshot_id,
+                current: self.snapshot_id,
+            });
         }
 
-        self.uid_to_backend_node
+        self.snapshot_elements
+            .get(uid)
+            .map(|el| el.backend_id)
+            .ok_or_else(|| BrowserError::ElementNotFound { uid: uid.to_string() })
+    }
+
+    /// role + name the uid had when it was handed out (current snapshot, then the one before)
+    pub fn element_label(&self, uid: &str) -> Option<(String, String)> {
+        self.snapshot_elements
             .get(uid)
-            .copied()
-            .ok_or_else(|| anyhow!("uid not found: {uid}"))
+            .or_else(|| self.previous_elements.get(uid))
+            .map(|el| (el.role.clone(), el.name.clone()))
+    }
+
+    // uid of the only element in the current snapshot with this role + name
+    fn find_element(&self, role: &str, name: &str) -> Option<String> {
+        if name.is_empty() {
+            return None;
+        }
+        let mut matches = self
+            .snapshot_elements
+            .iter()
+            .filter(|(_, el)| el.role == role && el.name == name);
+        let (uid, _) = matches.next()?;
+        // several identical elements (e.g. a list of "Add to cart") - don't guess
+        if matches.next().is_some() {
+            return None;
+        }
+        Some(uid.clone())
+    }
+
+    // snapshot lines whose text is closest to `name`, best first
+    fn closest_elements(&self, name: &str, limit: usize) -> Vec<String> {
+        let mut scored: Vec<(f64, u64, String)> = self
+            .snapshot_elements
+            .iter()
+            .filter(|(_, el)| !el.name.is_empty())
+            .filter_map(|(uid, el)| {
+                let score = text_similarity(name, &el.name);
+                let index = uid.rsplit('_').next()?.parse().ok()?;
+                (score > 0.0).then(|| (score, index, format!("uid={uid} {} \"{}\"", el.role, el.name)))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().take(limit).map(|(_, _, line)| line).collect()
+    }
+
+    async fn run_element_action(&mut self, uid: &str, action: ElementAction<'_>) -> Result<String> {
+        match action {
+            ElementAction::Click => self.click(uid, false).await,
+            ElementAction::DoubleClick => self.click(uid, true).await,
+            ElementAction::Fill(value) => self.fill(uid, value).await,
+            ElementAction::Hover => self.hover(uid).await,
+            ElementAction::ScrollTo => self.scroll_to(uid).await,
+        }
+    }
+
+    // tool: page_action on one element - if the uid went stale, re-snapshot,
+    // find the element again by its role + text and retry once
+    pub async fn element_action(&mut self, uid: &str, action: ElementAction<'_>) -> Result<String> {
+        let err = match self.run_element_action(uid, action).await {
+            Ok(output) => return Ok(self.with_navigation_note(output)),
+            Err(e) if e.is_element_lookup() => e,
+            Err(e) => return Err(e),
+        };
+        let Some((role, name)) = self.element_label(uid) else {
+            return Err(err);
+        };
+
+        println!("[browser] {}: re-resolving {} \"{}\"", err, role, name);
+        self.take_snapshot(false).await?;
+        match self.find_element(&role, &name) {
+            Some(new_uid) => {
+                let output = self.run_element_action(&new_uid, action).await?;
+                Ok(self.with_navigation_note(format!("{output} (page changed, {uid} re-resolved to {new_uid})")))
+            }
+            None => Err(BrowserError::Unresolved {
+                uid: uid.to_string(),
+                candidates: self.closest_elements(&name, 5),
+            }),
+        }
+    }
+
+    // tell the model when its action took the tab somewhere else, so it re-snapshots
+    fn with_navigation_note(&self, output: String) -> String {
+        match self.navigated_since_snapshot() {
+            Some(url) => format!("{output}\n(the page navigated to {url} - take a new snapshot before using its uids)"),
+            None => output,
+        }
     }
 
     // helper: resolve uid to center point
@@ -837,7 +1774,7 @@ impl BrowserClient {
                     .build(),
             )
             .await
-            .context("failed to get box model for element")?;
+            .map_err(|_| BrowserError::ElementDetached { uid: uid.to_string() })?;
 
         let model = box_resp.result.model;
         // content quad: 4 points (x1,y1,x2,y2,x3,y3,x4,y4)
@@ -848,6 +1785,110 @@ impl BrowserClient {
         Ok((x, y))
     }
 
+    // === Page Reading ===
+
+    /// Readability-style extractor: picks the densest content container and
+    /// converts it to markdown. Returns { title, markdown }.
+    fn read_page_script() -> &'static str {
+        r#"
+        (() => {
+            const SKIP = new Set(['SCRIPT', 'STYLE', 'NOSCRIPT', 'NAV', 'FOOTER', 'HEADER', 'ASIDE', 'FORM', 'IFRAME', 'SVG', 'BUTTON']);
+            const NOISE = /comment|sidebar|footer|menu|nav|share|social|promo|advert|banner|cookie|related|subscribe/i;
+
+            // score candidates by paragraph text, penalize link-heavy blocks
+            const score = (el) => {
+                let text = 0;
+                el.querySelectorAll('p').forEach(p => { text += p.innerText.length; });
+                let links = 0;
+                el.querySelectorAll('a').forEach(a => { links += a.innerText.length; });
+                const total = el.innerText.length || 1;
+                return text * (1 - Math.min(links / total, 0.9));
+            };
+
+            let root = document.querySelector('article') || document.querySelector('main') || document.querySelector('[role="main"]');
+            if (!root) {
+                let best = document.body, bestScore = 0;
+                document.querySelectorAll('div, section').forEach(el => {
+                    if (NOISE.test(el.className + ' ' + el.id)) return;
+                    const s = score(el);
+                    if (s > bestScore) { best = el; bestScore = s; }
+                });
+                root = best;
+            }
+
+            const inline = (node) => {
+                let out = '';
+                node.childNodes.forEach(child => {
+                    if (child.nodeType === Node.TEXT_NODE) {
+                        out += child.textContent.replace(/\s+/g, ' ');
+                        return;
+                    }
+                    if (child.nodeType !== Node.ELEMENT_NODE || SKIP.has(child.tagName)) return;
+                    const inner = inline(child);
+                    switch (child.tagName) {
+                        case 'A': {
+                            const href = child.getAttribute('href') || '';
+                            out += href && !href.startsWith('javascript:') && inner.trim()
+                                ? `[${inner.trim()}](${child.href})` : inner;
+                            break;
+                        }
+                        case 'STRONG': case 'B': out += inner.trim() ? `**${inner.trim()}**` : ''; break;
+                        case 'EM': case 'I': out += inner.trim() ? `_${inner.trim()}_` : ''; break;
+                        case 'CODE': out += `\`${child.textContent}\``; break;
+                        case 'BR': out += '\n'; break;
+                        case 'IMG': out += child.alt ? `![${child.alt}]` : ''; break;
+                        default: out += inner;
+                    }
+                });
+                return out;
+            };
+
+            const blocks = [];
+            const walk = (node) => {
+                node.childNodes.forEach(child => {
+                    if (child.nodeType !== Node.ELEMENT_NODE || SKIP.has(child.tagName)) return;
+                    if (child !== root && NOISE.test((child.className || '') + ' ' + (child.id || ''))) return;
+                    const tag = child.tagName;
+                    if (/^H[1-6]$/.test(tag)) {
+                        const text = inline(child).trim();
+                        if (text) blocks.push('#'.repeat(Number(tag[1])) + ' ' + text);
+                    } else if (tag === 'P') {
+                        const text = inline(child).trim();
+                        if (text) blocks.push(text);
+                    } else if (tag === 'UL' || tag === 'OL') {
+                        const items = [];
+                        child.querySelectorAll(':scope > li').forEach((li, i) => {
+                            const text = inline(li).trim();
+                            if (text) items.push((tag === 'OL' ? `${i + 1}.` : '-') + ' ' + text);
+                        });
+                        if (items.length) blocks.push(items.join('\n'));
+                    } else if (tag === 'PRE') {
+                        blocks.push('```\n' + child.textContent.trim() + '\n```');
+                    } else if (tag === 'BLOCKQUOTE') {
+                        const text = inline(child).trim();
+                        if (text) blocks.push('> ' + text);
+                    } else if (tag === 'TABLE') {
+                        const rows = [];
+                        child.querySelectorAll('tr').forEach(tr => {
+                            const cells = [...tr.children].map(c => c.innerText.trim().replace(/\|/g, '/'));
+                            rows.push('| ' + cells.join(' | ') + ' |');
+                        });
+                        if (rows.length) blocks.push(rows.join('\n'));
+                    } else {
+                        walk(child);
+                    }
+                });
+            };
+            walk(root);
+
+            return {
+                title: document.title || '',
+                markdown: blocks.join('\n\n'),
+            };
+        })()
+        "#
+    }
+
     // === Stealth & Anti-Detection ===
 
     /// Returns the stealth JavaScript that overrides automation detection signals.
@@ -959,6 +2000,7 @@ impl BrowserClient {
 
     /// Evaluate JavaScript on the current page and return result as string
     pub async fn evaluate_js(&mut self, js: &str) -> Result<String> {
+        self.invalidate_read_cache();
         let page = self.selected_page()?;
         let eval_result = tokio::time::timeout(
             std::time::Duration::from_secs(10),
@@ -968,8 +2010,8 @@ impl BrowserClient {
 
         match eval_result {
             Ok(Ok(result)) => Ok(result.into_value::<String>().unwrap_or_default()),
-            Ok(Err(e)) => Err(anyhow!("JS evaluation failed: {}", e)),
-            Err(_) => Err(anyhow!("JS evaluation timed out")),
+            Ok(Err(e)) => Err(BrowserError::Cdp(format!("JS evaluation failed: {}", e))),
+            Err(_) => Err(BrowserError::Cdp("JS evaluation timed out".into())),
         }
     }
 
@@ -991,6 +2033,7 @@ impl BrowserClient {
 
     /// Close all pages/tabs (used for cleanup after research)
     pub async fn close_all_pages(&mut self) -> Result<()> {
+        self.invalidate_read_cache();
         self.refresh_pages().await?;
         // Close pages from last to first, keeping at least one (Chrome needs it)
         while self.pages.len() > 1 {
@@ -1028,20 +2071,23 @@ fn profile_base_dir() -> PathBuf {
     PathBuf::from(std::env::var("HOME").unwrap_or_default())
 }
 
-fn chrome_debug_profile_dir() -> PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-        if let Some(base) = dirs::data_local_dir() {
-            return base.join("hey-work").join("heywork-chrome");
-        }
-    }
-    profile_base_dir().join(".heywork-chrome")
+// look for an executable on PATH (linux installs don't live at a fixed location)
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn find_in_path(names: &[&str]) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |n| dir.join(n)))
+        .find(|p| p.is_file())
 }
 
-fn find_chrome_binary() -> Option<PathBuf> {
+fn find_browser_binary(kind: BrowserKind) -> Option<PathBuf> {
     #[cfg(target_os = "macos")]
     {
-        let p = PathBuf::from("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome");
+        let p = PathBuf::from(match kind {
+            BrowserKind::Chrome => "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+            BrowserKind::Edge => "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+            BrowserKind::Firefox => "/Applications/Firefox.app/Contents/MacOS/firefox",
+        });
         return p.exists().then_some(p);
     }
 
@@ -1050,167 +2096,253 @@ fn find_chrome_binary() -> Option<PathBuf> {
         let local = std::env::var("LOCALAPPDATA").ok();
         let pf = std::env::var("ProgramFiles").ok();
         let pf86 = std::env::var("ProgramFiles(x86)").ok();
+        let rel = match kind {
+            BrowserKind::Chrome => "Google/Chrome/Application/chrome.exe",
+            BrowserKind::Edge => "Microsoft/Edge/Application/msedge.exe",
+            BrowserKind::Firefox => "Mozilla Firefox/firefox.exe",
+        };
         let candidates = [
-            local.map(|p| PathBuf::from(p).join("Google/Chrome/Application/chrome.exe")),
-            pf.map(|p| PathBuf::from(p).join("Google/Chrome/Application/chrome.exe")),
-            pf86.map(|p| PathBuf::from(p).join("Google/Chrome/Application/chrome.exe")),
+            local.map(|p| PathBuf::from(p).join(rel)),
+            pf.map(|p| PathBuf::from(p).join(rel)),
+            pf86.map(|p| PathBuf::from(p).join(rel)),
         ];
         return candidates.into_iter().flatten().find(|p| p.exists());
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     {
-        None
+        match kind {
+            BrowserKind::Chrome => find_in_path(&["google-chrome", "google-chrome-stable", "chromium", "chromium-browser"]),
+            BrowserKind::Edge => find_in_path(&["microsoft-edge", "microsoft-edge-stable"]),
+            BrowserKind::Firefox => find_in_path(&["firefox"]),
+        }
     }
 }
 
-// check if chrome is already running
-fn is_chrome_running() -> bool {
+/// whether a binary for this browser can be found on the system
+pub fn is_browser_installed(kind: BrowserKind) -> bool {
+    find_browser_binary(kind).is_some()
+}
+
+// check if the browser is already running
+fn is_browser_running(kind: BrowserKind) -> bool {
     #[cfg(target_os = "windows")]
     {
+        let image = kind.process_name();
         return std::process::Command::new("tasklist")
-            .args(["/FI", "IMAGENAME eq chrome.exe"])
+            .args(["/FI", &format!("IMAGENAME eq {}", image)])
             .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).contains("chrome.exe"))
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(image))
             .unwrap_or(false);
     }
 
     #[cfg(not(target_os = "windows"))]
     {
         std::process::Command::new("pgrep")
-            .args(["-x", "Google Chrome"])
+            .args(["-x", kind.process_name()])
             .output()
             .map(|o| o.status.success())
             .unwrap_or(false)
     }
 }
 
-// restart chrome with debugging enabled (macOS)
-// returns a connected BrowserClient if successful
-pub async fn restart_chrome_with_debugging() -> Result<BrowserClient> {
-    // try graceful quit first
-    println!("[browser] Quitting Chrome...");
+/// ask the browser to quit (graceful), or kill it outright when `force` is set
+pub fn quit_browser(kind: BrowserKind, force: bool) {
     #[cfg(target_os = "macos")]
     {
-        std::process::Command::new("osascript")
-            .args(["-e", "tell application \"Google Chrome\" to quit"])
-            .output()
-            .context("failed to quit Chrome")?;
+        if force {
+            let _ = std::process::Command::new("pkill")
+                .args(["-9", kind.process_name()])
+                .output();
+        } else {
+            let _ = std::process::Command::new("osascript")
+                .args(["-e", &format!("tell application \"{}\" to quit", kind.display_name())])
+                .output();
+        }
     }
+
     #[cfg(target_os = "windows")]
     {
-        let _ = std::process::Command::new("taskkill")
-            .args(["/IM", "chrome.exe", "/T"])
+        let mut args = vec!["/IM", kind.process_name(), "/T"];
+        if force {
+            args.insert(0, "/F");
+        }
+        let _ = std::process::Command::new("taskkill").args(args).output();
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let signal = if force { "-9" } else { "-15" };
+        let _ = std::process::Command::new("pkill")
+            .args([signal, "-x", kind.process_name()])
             .output();
     }
+}
 
-    // wait for chrome to quit gracefully
+// restart chrome with debugging enabled
+// returns a connected BrowserClient if successful
+pub async fn restart_chrome_with_debugging() -> Result<BrowserClient> {
+    restart_browser_with_debugging(selected_browser_kind()).await
+}
+
+// restart the given browser with debugging enabled
+pub async fn restart_browser_with_debugging(kind: BrowserKind) -> Result<BrowserClient> {
+    ensure_supported(kind)?;
+    // try graceful quit first
+    println!("[browser] Quitting {}...", kind);
+    quit_browser(kind, false);
+
+    // wait for the browser to quit gracefully
     for _ in 0..6 {
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        if !is_chrome_running() {
+        if !is_browser_running(kind) {
             break;
         }
     }
 
     // if still running, force kill
-    if is_chrome_running() {
-        println!("[browser] Chrome didn't quit gracefully, force killing...");
-        #[cfg(target_os = "windows")]
-        let _ = std::process::Command::new("taskkill")
-            .args(["/F", "/IM", "chrome.exe", "/T"])
-            .output();
-        #[cfg(not(target_os = "windows"))]
-        let _ = std::process::Command::new("pkill")
-            .args(["-9", "Google Chrome"])
-            .output();
+    if is_browser_running(kind) {
+        println!("[browser] {} didn't quit gracefully, force killing...", kind);
+        quit_browser(kind, true);
 
         // wait for force kill to take effect
         for _ in 0..10 {
             tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
-            if !is_chrome_running() {
+            if !is_browser_running(kind) {
                 break;
             }
         }
     }
 
-    if is_chrome_running() {
-        return Err(anyhow!("Chrome didn't quit in time"));
+    if is_browser_running(kind) {
+        return Err(BrowserError::Cdp(format!("{} didn't quit in time", kind)));
     }
 
     // launch with dedicated debug profile (not user's main profile)
     // using the main profile causes issues with "confirm before quit" dialogs
     // and bot detection on login pages
-    println!("[browser] Launching Chrome with debug profile...");
-    let user_data_dir = chrome_debug_profile_dir();
-    // Launch Chrome binary DIRECTLY instead of via `open -a`
-    // `open -a` ignores --args if Chrome was recently running, causing
-    // anti-detection flags to not be applied
-    let chrome_binary = find_chrome_binary()
-        .ok_or_else(|| anyhow!("failed to locate Google Chrome binary"))?;
-    std::process::Command::new(chrome_binary)
-        .args([
-            "--remote-debugging-port=9222",
-            &format!("--user-data-dir={}", user_data_dir.to_string_lossy()),
-            "--profile-directory=Default",
-            "--no-first-run",
-            "--no-default-browser-check",
-            "--disable-blink-features=AutomationControlled",
-            "--disable-features=AutomationControlled",
-            "--disable-infobars",
-            "--disable-background-timer-throttling",
-            "--disable-backgrounding-occluded-windows",
-            "--disable-renderer-backgrounding",
-            "--disable-ipc-flooding-protection",
-            "--password-store=basic",
-            "--use-mock-keychain",
-            "--lang=en-US,en",
-        ])
+    println!("[browser] Launching {} with debug profile (headless: {})...", kind, headless_enabled());
+    let mode = selected_session_mode();
+    let user_data_dir = kind.launch_profile_dir(mode);
+    spawn_with_debugging(kind, &user_data_dir)?;
+
+    let connected = connect_debug_port().await.context("failed to connect after restart");
+    let (browser, handler) = match connected {
+        Ok(connection) => connection,
+        Err(e) => {
+            if mode == BrowserSessionMode::Ephemeral {
+                let _ = std::fs::remove_dir_all(&user_data_dir);
+            }
+            return Err(e.into());
+        }
+    };
+
+    println!("[browser] Connected to {} with debugging", kind);
+    let mut client = BrowserClient::from_connection(browser, handler).await;
+    if mode == BrowserSessionMode::Ephemeral {
+        client.ephemeral_profile = Some(user_data_dir);
+    }
+    Ok(client)
+}
+
+// launch the browser binary DIRECTLY instead of via `open -a`
+// `open -a` ignores --args if the browser was recently running, causing
+// anti-detection flags to not be applied
+fn spawn_with_debugging(kind: BrowserKind, user_data_dir: &std::path::Path) -> anyhow::Result<()> {
+    if !kind.is_chromium() {
+        prepare_firefox_profile(user_data_dir)?;
+    }
+
+    let binary = find_browser_binary(kind)
+        .ok_or_else(|| anyhow!("failed to locate {} binary", kind))?;
+    std::process::Command::new(binary)
+        .args(kind.debug_args(user_data_dir))
         .spawn()
-        .context("failed to launch Chrome")?;
+        .with_context(|| format!("failed to launch {}", kind))?;
+    Ok(())
+}
 
-    // wait for debug port to be ready
+// wait for the debug port to come up, then attach over CDP
+async fn connect_debug_port() -> anyhow::Result<(Browser, Handler)> {
+    let base = format!("http://127.0.0.1:{}", DEBUG_PORT);
     for _ in 0..20 {
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        if let Ok(resp) = reqwest::get("http://127.0.0.1:9222/json/version").await {
+        if let Ok(resp) = reqwest::get(format!("{}/json/version", base)).await {
             if resp.status().is_success() {
                 break;
             }
         }
     }
 
-    // try to connect
-    let (mut browser, handler) = Browser::connect("http://127.0.0.1:9222")
+    Browser::connect(&base)
+        .await
+        .map_err(|e| anyhow!("failed to connect to debug port: {}", e))
+}
+
+// whether anything answers CDP on the debug port right now - no connection is kept
+pub async fn debug_port_reachable() -> bool {
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(2))
+        .build()
+    {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    client
+        .get(format!("http://127.0.0.1:{}/json/version", DEBUG_PORT))
+        .send()
         .await
-        .context("failed to connect after restart")?;
-
-    println!("[browser] Connected to Chrome with debugging");
-
-    let handler_task = tokio::spawn(async move {
-        handler_loop(handler).await;
-    });
-
-    // fetch existing targets
-    let _ = browser.fetch_targets().await;
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    let pages = browser.pages().await.unwrap_or_default();
-    println!("[browser] Found {} pages after restart", pages.len());
-
-    Ok(BrowserClient {
-        browser,
-        _handler_task: handler_task,
-        pages,
-        selected_page_idx: 0,
-        snapshot_id: 0,
-        uid_to_backend_node: HashMap::new(),
-    })
+        .map(|resp| resp.status().is_success())
+        .unwrap_or(false)
+}
+
+// the last firefox release that still serves CDP; later ones are WebDriver BiDi only
+const LAST_CDP_FIREFOX: u32 = 128;
+
+// refuse a browser that can't serve CDP before quitting or launching anything for it
+fn ensure_supported(kind: BrowserKind) -> Result<()> {
+    if kind != BrowserKind::Firefox {
+        return Ok(());
+    }
+    match find_browser_binary(kind).and_then(|binary| firefox_major_version(&binary)) {
+        Some(version) if version > LAST_CDP_FIREFOX => Err(BrowserError::Unsupported(format!(
+            "Firefox {} can't be automated - Firefox dropped CDP support after version {}. Choose Chrome or Edge in Settings.",
+            version, LAST_CDP_FIREFOX
+        ))),
+        _ => Ok(()),
+    }
 }
 
-// try to find existing chrome with debugging enabled
-async fn try_find_existing_chrome() -> Option<String> {
+// major version from `firefox --version` ("Mozilla Firefox 128.0.3"); None when it can't be read,
+// e.g. on windows where the gui binary prints nothing
+fn firefox_major_version(binary: &std::path::Path) -> Option<u32> {
+    let output = std::process::Command::new(binary).arg("--version").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.split_whitespace()
+        .find_map(|word| word.split('.').next()?.parse().ok())
+}
+
+// firefox only serves CDP when remote.active-protocols includes it (firefox 86 through
+// LAST_CDP_FIREFOX; ensure_supported turns newer releases away)
+fn prepare_firefox_profile(profile_dir: &std::path::Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(profile_dir).context("failed to create firefox profile")?;
+    let prefs = [
+        r#"user_pref("remote.active-protocols", 3);"#,
+        r#"user_pref("remote.enabled", true);"#,
+        r#"user_pref("browser.shell.checkDefaultBrowser", false);"#,
+        r#"user_pref("datareporting.policy.dataSubmissionEnabled", false);"#,
+    ];
+    std::fs::write(profile_dir.join("user.js"), prefs.join("\n"))
+        .context("failed to write firefox prefs")?;
+    Ok(())
+}
+
+// try to find an existing browser instance with debugging enabled
+async fn try_find_existing_browser(kind: BrowserKind) -> Option<String> {
     let home = profile_base_dir();
 
     // check DevToolsActivePort files in known profile locations
-    for profile in CHROME_PROFILES {
+    for profile in kind.devtools_profiles() {
         let port_file = home.join(profile).join("Default/DevToolsActivePort");
 
         if let Ok(content) = tokio::fs::read_to_string(&port_file).await {
@@ -1224,53 +2356,108 @@ async fn try_find_existing_chrome() -> Option<String> {
         }
     }
 
-    // fallback: try localhost:9222
-    if reqwest::get("http://127.0.0.1:9222/json/version")
+    // fallback: the default debug port, as long as the browser listening there is the one asked for
+    let base = format!("http://127.0.0.1:{}", DEBUG_PORT);
+    let version: serde_json::Value = reqwest::get(format!("{}/json/version", base))
         .await
-        .is_ok()
-    {
-        return Some("http://127.0.0.1:9222".to_string());
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    let product = version.get("Browser").and_then(|v| v.as_str()).unwrap_or_default();
+    if kind.reports_as(product) {
+        return Some(base);
     }
-
+    println!("[browser] Debug port {} belongs to {:?}, not {} - not attaching", DEBUG_PORT, product, kind);
     None
 }
 
-// launch chrome using chromiumoxide with dedicated debug profile
-async fn launch_chrome_with_profile() -> Result<(Browser, Handler)> {
-    // chrome requires a NON-DEFAULT user data dir for remote debugging
-    // using the default chrome profile path doesn't work - chrome treats it specially
+// launch a chromium browser using chromiumoxide with dedicated debug profile
+async fn launch_chromium_with_profile(kind: BrowserKind, user_data_dir: &std::path::Path) -> anyhow::Result<(Browser, Handler)> {
+    // chromium requires a NON-DEFAULT user data dir for remote debugging
+    // using the default profile path doesn't work - chrome treats it specially
     // so we create a dedicated debug profile that's separate from the user's main profile
-    let user_data_dir = chrome_debug_profile_dir();
-
     println!("[browser] Using debug profile: {:?}", user_data_dir);
 
     // disable_default_args() skips puppeteer automation flags that break normal browser usage
     // (like --disable-extensions, --disable-sync, --enable-automation, etc.)
     // Anti-detection flags prevent Google from identifying automated Chrome
-    let config = BrowserConfig::builder()
+    // with_head() keeps chromiumoxide from adding legacy --headless; the headless
+    // setting passes --headless=new through chromium_launch_args instead
+    let mut builder = BrowserConfig::builder()
         .disable_default_args()
         .with_head()
-        .user_data_dir(&user_data_dir)
+        .user_data_dir(user_data_dir)
         .viewport(None)
-        // === Anti-Detection Chrome flags ===
-        .arg("--disable-blink-features=AutomationControlled")
-        .arg("--disable-features=AutomationControlled")
-        .arg("--disable-infobars")
-        .arg("--disable-background-timer-throttling")
-        .arg("--disable-backgrounding-occluded-windows")
-        .arg("--disable-renderer-backgrounding")
-        .arg("--disable-ipc-flooding-protection")
-        .arg("--no-first-run")
-        .arg("--no-default-browser-check")
-        .arg("--password-store=basic")
-        .arg("--use-mock-keychain")
-        .arg("--lang=en-US,en")
+        .args(chromium_launch_args(headless_enabled()));
+
+    // chromiumoxide auto-detects chrome; other chromium browsers need an explicit binary
+    if kind != BrowserKind::Chrome {
+        let binary = find_browser_binary(kind)
+            .ok_or_else(|| anyhow!("failed to locate {} binary", kind))?;
+        builder = builder.chrome_executable(binary);
+    }
+
+    let config = builder
         .build()
         .map_err(|e| anyhow!("failed to build browser config: {}", e))?;
 
     Browser::launch(config)
         .await
-        .context("failed to launch chrome")
+        .with_context(|| format!("failed to launch {}", kind))
+}
+
+// firefox can't be launched through chromiumoxide (it passes chrome-only flags),
+// so spawn it ourselves and attach to its debug port
+async fn launch_firefox_with_profile(user_data_dir: &std::path::Path) -> anyhow::Result<(Browser, Handler)> {
+    if is_browser_running(BrowserKind::Firefox) {
+        return Err(anyhow!("firefox already running without remote debugging"));
+    }
+    spawn_with_debugging(BrowserKind::Firefox, user_data_dir)?;
+    connect_debug_port().await
+}
+
+// throwaway profiles a crash or force quit left behind. only one browser connection exists at
+// a time, so any found while a new one launches are no longer in use
+fn remove_stale_ephemeral_profiles() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(EPHEMERAL_PROFILE_PREFIX) {
+            if let Err(e) = std::fs::remove_dir_all(entry.path()) {
+                warn!(profile = %entry.path().display(), "couldn't delete stale ephemeral profile: {}", e);
+            }
+        }
+    }
+}
+
+// word overlap between two element labels (0.0 - 1.0), with a bump for substring matches
+fn text_similarity(a: &str, b: &str) -> f64 {
+    let a = a.to_lowercase();
+    let b = b.to_lowercase();
+    let words_a: std::collections::HashSet<&str> = a.split_whitespace().collect();
+    let words_b: std::collections::HashSet<&str> = b.split_whitespace().collect();
+    if words_a.is_empty() || words_b.is_empty() {
+        return 0.0;
+    }
+    let shared = words_a.intersection(&words_b).count() as f64;
+    let overlap = shared / words_a.union(&words_b).count() as f64;
+    if a.contains(b.as_str()) || b.contains(a.as_str()) {
+        (overlap + 0.5).min(1.0)
+    } else {
+        overlap
+    }
+}
+
+// cut to max_chars on a char boundary, noting how much was dropped
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    format!("{cut}\n\n[truncated - showing {max_chars} of {total} chars]")
 }
 
 // format a11y tree to text snapshot
@@ -1278,7 +2465,7 @@ fn format_ax_tree(
     nodes: &[AxNode],
     snapshot_id: u64,
     verbose: bool,
-    uid_map: &mut HashMap<String, BackendNodeId>,
+    uid_map: &mut HashMap<String, SnapshotElement>,
 ) -> String {
     // build parent->children map
     let mut children_map: HashMap<String, Vec<&AxNode>> = HashMap::new();
@@ -1368,7 +2555,7 @@ fn format_node(
     depth: usize,
     snapshot_id: u64,
     node_index: &mut u64,
-    uid_map: &mut HashMap<String, BackendNodeId>,
+    uid_map: &mut HashMap<String, SnapshotElement>,
     verbose: bool,
     parent_name: Option<&str>,
     output: &mut String,
@@ -1419,7 +2606,11 @@ fn format_node(
 
     // store backend node id mapping
     if let Some(backend_id) = node.backend_dom_node_id {
-        uid_map.insert(uid.clone(), backend_id);
+        uid_map.insert(uid.clone(), SnapshotElement {
+            backend_id,
+            role: role.unwrap_or_default().to_string(),
+            name: name.unwrap_or_default().to_string(),
+        });
     }
 
     // build attributes
@@ -1498,7 +2689,7 @@ fn process_children(
     depth: usize,
     snapshot_id: u64,
     node_index: &mut u64,
-    uid_map: &mut HashMap<String, BackendNodeId>,
+    uid_map: &mut HashMap<String, SnapshotElement>,
     verbose: bool,
     parent_name: Option<&str>,
     output: &mut String,
@@ -1526,6 +2717,15 @@ fn process_children(
 // thread-safe wrapper
 pub type SharedBrowserClient = Arc<Mutex<Option<BrowserClient>>>;
 
+/// end the shared connection if it's an ephemeral session, quitting its browser and deleting
+/// its profile. a kept session stays connected
+pub async fn end_ephemeral_session(shared: &SharedBrowserClient) {
+    let ephemeral = shared.lock().await.take_if(|client| client.is_ephemeral());
+    if let Some(client) = ephemeral {
+        client.disconnect().await;
+    }
+}
+
 pub fn create_shared_browser_client() -> SharedBrowserClient {
     Arc::new(Mutex::new(None))
 }
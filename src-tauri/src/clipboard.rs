@@ -0,0 +1,223 @@
+// one-click copy of the agent's answer: the last reply, the fenced code blocks in it,
+// and system clipboard reads/writes that work whichever window has focus
+
+use crate::api::{ContentBlock, Message};
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeBlock {
+    /// info string after the opening fence, e.g. "rust"
+    pub language: Option<String>,
+    pub code: String,
+}
+
+/// the final assistant text of a conversation - empty when there's no reply yet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastResponse {
+    pub text: String,
+    pub code_blocks: Vec<CodeBlock>,
+}
+
+/// text of the newest assistant message that has any, tool-only turns skipped
+pub fn last_response(messages: &[Message]) -> LastResponse {
+    let text = messages
+        .iter()
+        .rev()
+        .filter(|message| message.role == "assistant")
+        .map(|message| {
+            message
+                .content
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::Text { text } if !text.trim().is_empty() => Some(text.trim()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+        .find(|text| !text.is_empty())
+        .unwrap_or_default();
+    let code_blocks = extract_code_blocks(&text);
+    LastResponse { text, code_blocks }
+}
+
+/// fenced (``` or ~~~) code blocks in markdown, in order. an unclosed fence runs to the end
+pub fn extract_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    // (fence, language, lines so far) of the block being read
+    let mut open: Option<(String, Option<String>, Vec<&str>)> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        match open.as_mut() {
+            Some((fence, _, lines)) => {
+                // closed by a bare run of the same character, at least as long as the opener
+                let candidate = trimmed.trim_end();
+                let closes = candidate.len() >= fence.len() && candidate.chars().all(|c| fence.starts_with(c));
+                if !closes {
+                    lines.push(line);
+                } else if let Some((_, language, lines)) = open.take() {
+                    blocks.push(CodeBlock { language, code: lines.join("\n") });
+                }
+            }
+            None => {
+                let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+                if let Some(marker) = marker {
+                    let fence_len = trimmed.chars().take_while(|c| *c == marker).count();
+                    if fence_len >= 3 {
+                        let info = trimmed[fence_len..].trim();
+                        let language = info.split_whitespace().next().map(str::to_string);
+                        open = Some((marker.to_string().repeat(fence_len), language, Vec::new()));
+                    }
+                }
+            }
+        }
+    }
+    if let Some((_, language, lines)) = open {
+        blocks.push(CodeBlock { language, code: lines.join("\n") });
+    }
+    blocks
+}
+
+// the platform's clipboard tool, fed through stdin
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("{}: {}", program, e))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| format!("{}: no stdin", program))?
+        .write_all(text.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+// stdout of a clipboard reader, None when it isn't installed or the clipboard holds no text
+fn read_from(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).stderr(Stdio::null()).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(target_os = "macos")]
+pub fn read_clipboard() -> Option<String> {
+    read_from("pbpaste", &[])
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_clipboard() -> Option<String> {
+    read_from(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8; [Console]::Out.Write((Get-Clipboard -Raw))",
+        ],
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn read_clipboard() -> Option<String> {
+    read_from("wl-paste", &["--no-newline"])
+        .or_else(|| read_from("xclip", &["-selection", "clipboard", "-o"]))
+        .or_else(|| read_from("xsel", &["--clipboard", "--output"]))
+}
+
+#[cfg(target_os = "macos")]
+pub fn write_clipboard(text: &str) -> Result<(), String> {
+    pipe_to("pbcopy", &[], text)
+}
+
+#[cfg(target_os = "windows")]
+pub fn write_clipboard(text: &str) -> Result<(), String> {
+    // clip.exe mangles non-ascii text, so read stdin as utf-8 from powershell instead
+    pipe_to(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+        ],
+        text,
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn write_clipboard(text: &str) -> Result<(), String> {
+    pipe_to("wl-copy", &[], text)
+        .or_else(|_| pipe_to("xclip", &["-selection", "clipboard"], text))
+        .or_else(|_| pipe_to("xsel", &["--clipboard", "--input"], text))
+        .map_err(|_| "Copying needs wl-copy, xclip or xsel".to_string())
+}
+
+// put text on the system clipboard
+#[tauri::command]
+pub async fn copy_to_clipboard(text: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || write_clipboard(&text))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, texts: &[&str]) -> Message {
+        Message {
+            role: role.to_string(),
+            content: texts.iter().map(|text| ContentBlock::Text { text: text.to_string() }).collect(),
+        }
+    }
+
+    #[test]
+    fn extracts_fenced_blocks_with_languages() {
+        let markdown = "Run this:\n```bash\nls -la\n```\nthen\n~~~\nplain\ntext\n~~~\n```py\nunclosed";
+        assert_eq!(
+            extract_code_blocks(markdown),
+            vec![
+                CodeBlock { language: Some("bash".to_string()), code: "ls -la".to_string() },
+                CodeBlock { language: None, code: "plain\ntext".to_string() },
+                CodeBlock { language: Some("py".to_string()), code: "unclosed".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn longer_fences_can_contain_shorter_ones() {
+        let markdown = "````md\n```\ninner\n```\n````";
+        assert_eq!(extract_code_blocks(markdown)[0].code, "```\ninner\n```");
+    }
+
+    #[test]
+    fn last_response_skips_tool_only_turns() {
+        let messages = vec![
+            message("user", &["hi"]),
+            message("assistant", &["Here:", "```\nx\n```"]),
+            message("assistant", &[" "]),
+            message("user", &["thanks"]),
+        ];
+        let last = last_response(&messages);
+        assert_eq!(last.text, "Here:\n\n```\nx\n```");
+        assert_eq!(last.code_blocks.len(), 1);
+    }
+
+    #[test]
+    fn no_reply_yet_is_empty() {
+        assert_eq!(last_response(&[message("user", &["hi"])]), LastResponse::default());
+    }
+}
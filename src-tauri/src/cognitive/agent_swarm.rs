@@ -16,10 +16,46 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{mpsc, Mutex, Notify, RwLock};
 use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// Cancellation tokens for running swarm tasks, keyed by task id. Global rather than on
+/// the swarm so `cancel_swarm_task` works while the agent lock is held by the run.
+static TASK_CANCELLATIONS: std::sync::OnceLock<std::sync::Mutex<HashMap<String, CancellationToken>>> =
+    std::sync::OnceLock::new();
+
+fn task_cancellations() -> &'static std::sync::Mutex<HashMap<String, CancellationToken>> {
+    TASK_CANCELLATIONS.get_or_init(|| std::sync::Mutex::new(HashMap::new()))
+}
+
+/// The token for a task - a fresh, never-cancelled one if the task isn't registered
+fn task_token(task_id: &str) -> CancellationToken {
+    task_cancellations()
+        .lock()
+        .ok()
+        .and_then(|tokens| tokens.get(task_id).cloned())
+        .unwrap_or_default()
+}
+
+/// Cancel one running swarm task; other tasks keep their own tokens. Returns false if
+/// the task isn't running.
+pub fn cancel_swarm_task(task_id: &str) -> bool {
+    let token = task_cancellations().lock().ok().and_then(|tokens| tokens.get(task_id).cloned());
+    match token {
+        Some(token) => {
+            info!(task_id = %task_id, "cancelling swarm task");
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+const CANCELLED_MESSAGE: &str = "cancelled by user";
+
 /// Types of specialized agents in the swarm
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AgentType {
@@ -67,6 +103,45 @@ pub struct ComplexTask {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// Completed/total counts and a rough time-to-finish, polled while a swarm task runs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskProgress {
+    pub completed: usize,
+    pub failed: usize,
+    pub total: usize,
+    /// None when the planner gave no duration estimates
+    pub eta_ms: Option<u64>,
+}
+
+impl ComplexTask {
+    /// Progress snapshot - the ETA is each unfinished subtask's estimate (minus time
+    /// already spent on running ones), spread across the parallel slots
+    pub fn progress(&self, now: DateTime<Utc>) -> TaskProgress {
+        let completed = self.subtasks.iter().filter(|st| st.status == SubTaskStatus::Completed).count();
+        let failed = self.subtasks.iter().filter(|st| st.status == SubTaskStatus::Failed).count();
+
+        let remaining: Vec<u64> = self
+            .subtasks
+            .iter()
+            .filter(|st| !matches!(st.status, SubTaskStatus::Completed | SubTaskStatus::Failed))
+            .map(|st| {
+                let spent = st
+                    .started_at
+                    .map(|started| (now - started).num_milliseconds().max(0) as u64)
+                    .unwrap_or(0);
+                st.estimated_duration_ms.saturating_sub(spent)
+            })
+            .collect();
+        let has_estimates = self.subtasks.iter().any(|st| st.estimated_duration_ms > 0);
+        let eta_ms = has_estimates.then(|| {
+            let slots = self.max_parallel.clamp(1, remaining.len().max(1)) as u64;
+            remaining.iter().sum::<u64>() / slots
+        });
+
+        TaskProgress { completed, failed, total: self.subtasks.len(), eta_ms }
+    }
+}
+
 /// Individual subtask with dependencies
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubTask {
@@ -84,6 +159,9 @@ pub struct SubTask {
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub estimated_duration_ms: u64,
+    /// What the planner expects to see once the step is done - given to the verifier
+    #[serde(default)]
+    pub expected_outcome: Option<String>,
 }
 
 /// Result of executing a subtask
@@ -111,12 +189,20 @@ pub struct ToolCallRecord {
 }
 
 /// Verification result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct VerificationResult {
     pub passed: bool,
     pub score: f32, // 0.0 to 1.0
     pub issues: Vec<String>,
     pub suggestions: Vec<String>,
+    /// Screen before/after the subtask (base64 JPEG), for steps that act on the screen
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub before_screenshot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub after_screenshot: Option<String>,
+    /// Fraction of the screen that visibly changed between the two (0.0-1.0)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pixel_diff: Option<f64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -160,6 +246,8 @@ pub struct AgentSwarm {
     /// Real execution tools
     computer: Arc<Mutex<Option<ComputerControl>>>,
     bash: Arc<Mutex<BashExecutor>>,
+    /// Woken whenever a task or subtask changes status, so pollers don't have to spin
+    updates: Arc<Notify>,
 }
 
 /// Configuration for the swarm
@@ -181,6 +269,53 @@ pub struct SwarmConfig {
     pub parallel_execution: bool,
     /// Require human confirmation for destructive actions
     pub confirm_destructive: bool,
+    /// Model for roles that act (Executor, Specialist, ...) - the user's selected model
+    pub main_model: String,
+    /// Cheaper model for the reasoning-only roles (Planner, Verifier, Critic)
+    pub light_model: String,
+    /// Per-role models that win over both defaults
+    pub model_overrides: HashMap<AgentType, String>,
+}
+
+/// Per-role swarm models, e.g. "Planner=claude-sonnet-4-5,Verifier=claude-haiku-4-5-20251001"
+pub const SWARM_MODELS_ENV: &str = "HEYWORK_SWARM_MODELS";
+
+const DEFAULT_SWARM_MODEL: &str = "claude-opus-4-6";
+/// Small model for one-line answers (verification, replacement commands, titles)
+pub const DEFAULT_LIGHT_MODEL: &str = "claude-haiku-4-5-20251001";
+
+impl SwarmConfig {
+    /// The model a role's executor should call
+    pub fn model_for(&self, agent_type: AgentType) -> String {
+        if let Some(model) = self.model_overrides.get(&agent_type) {
+            return model.clone();
+        }
+        match agent_type {
+            AgentType::Planner | AgentType::Verifier | AgentType::Critic => self.light_model.clone(),
+            _ => self.main_model.clone(),
+        }
+    }
+}
+
+/// Parse "Role=model" pairs; unknown roles and blank models are skipped
+fn parse_model_overrides(raw: &str) -> HashMap<AgentType, String> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (role, model) = pair.split_once('=')?;
+            let model = model.trim();
+            let agent_type = match role.trim().to_lowercase().as_str() {
+                "planner" => AgentType::Planner,
+                "executor" => AgentType::Executor,
+                "verifier" => AgentType::Verifier,
+                "critic" => AgentType::Critic,
+                "recovery" => AgentType::Recovery,
+                "coordinator" => AgentType::Coordinator,
+                "specialist" => AgentType::Specialist,
+                _ => return None,
+            };
+            (!model.is_empty()).then(|| (agent_type, model.to_string()))
+        })
+        .collect()
 }
 
 impl Default for SwarmConfig {
@@ -194,6 +329,9 @@ impl Default for SwarmConfig {
             subtask_timeout_secs: 120,
             parallel_execution: true,
             confirm_destructive: true,
+            main_model: DEFAULT_SWARM_MODEL.to_string(),
+            light_model: DEFAULT_LIGHT_MODEL.to_string(),
+            model_overrides: parse_model_overrides(&std::env::var(SWARM_MODELS_ENV).unwrap_or_default()),
         }
     }
 }
@@ -218,7 +356,7 @@ pub enum SwarmEvent {
     SubTaskStarted { task_id: String, subtask_id: String, agent: AgentType },
     SubTaskCompleted { task_id: String, subtask_id: String, result: TaskResult },
     SubTaskFailed { task_id: String, subtask_id: String, error: String },
-    VerificationCompleted { task_id: String, subtask_id: String, passed: bool, score: f32 },
+    VerificationCompleted { task_id: String, subtask_id: String, result: VerificationResult },
     CriticReview { task_id: String, issues: Vec<String>, suggestions: Vec<String> },
     TaskCompleted { task_id: String, success: bool },
     NeedsUserInput { task_id: String, question: String },
@@ -234,6 +372,10 @@ pub struct AgentExecutor {
 
 impl AgentSwarm {
     pub fn new(api_key: String, model: String, event_tx: mpsc::UnboundedSender<SwarmEvent>) -> Self {
+        let config = SwarmConfig {
+            main_model: model,
+            ..SwarmConfig::default()
+        };
         let mut executors = HashMap::new();
         
         for agent_type in [
@@ -245,10 +387,12 @@ impl AgentSwarm {
             AgentType::Coordinator,
             AgentType::Specialist,
         ] {
+            let model = config.model_for(agent_type);
+            debug!(agent = ?agent_type, model = %model, "swarm agent model");
             executors.insert(agent_type, AgentExecutor {
                 agent_type,
                 api_key: api_key.clone(),
-                model: model.clone(),
+                model,
             });
         }
         
@@ -257,10 +401,11 @@ impl AgentSwarm {
             task_queue: Arc::new(Mutex::new(VecDeque::new())),
             executors,
             event_tx,
-            config: SwarmConfig::default(),
+            config,
             stats: Arc::new(RwLock::new(SwarmStats::default())),
             computer: Arc::new(Mutex::new(None)),
             bash: Arc::new(Mutex::new(BashExecutor::new())),
+            updates: Arc::new(Notify::new()),
         }
     }
 
@@ -271,10 +416,10 @@ impl AgentSwarm {
             match ComputerControl::new() {
                 Ok(computer) => {
                     *computer_guard = Some(computer);
-                    println!("[swarm] Computer control initialized");
+                    info!("swarm computer control initialized");
                 }
                 Err(e) => {
-                    println!("[swarm] Failed to initialize computer control: {}", e);
+                    warn!("swarm failed to initialize computer control: {}", e);
                 }
             }
         }
@@ -312,11 +457,23 @@ impl AgentSwarm {
             description,
         });
         
-        // Start processing
+        let token = CancellationToken::new();
+        if let Ok(mut tokens) = task_cancellations().lock() {
+            tokens.insert(task_id.clone(), token.clone());
+        }
+
+        // Start processing - cancelling drops the whole pipeline, including in-flight API and tool calls
         let swarm = Arc::new(self.clone_swarm());
         let task_id_clone = task_id.clone();
         tokio::spawn(async move {
-            swarm.process_task(task_id_clone).await;
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => swarm.mark_cancelled(&task_id_clone).await,
+                _ = swarm.process_task(task_id_clone.clone()) => {}
+            }
+            if let Ok(mut tokens) = task_cancellations().lock() {
+                tokens.remove(&task_id_clone);
+            }
         });
         
         task_id
@@ -352,6 +509,7 @@ impl AgentSwarm {
                 };
             }
         }
+        self.task_changed();
         
         let _ = self.event_tx.send(SwarmEvent::TaskCompleted {
             task_id,
@@ -359,6 +517,33 @@ impl AgentSwarm {
         });
     }
 
+    /// Fail a cancelled task and every subtask that hadn't finished
+    async fn mark_cancelled(&self, task_id: &str) {
+        {
+            let mut tasks = self.tasks.write().await;
+            if let Some(task) = tasks.get_mut(task_id) {
+                task.status = TaskStatus::Failed;
+                task.metadata.insert("cancelled".to_string(), serde_json::Value::Bool(true));
+                for st in task.subtasks.iter_mut() {
+                    if !matches!(st.status, SubTaskStatus::Completed | SubTaskStatus::Failed) {
+                        st.status = SubTaskStatus::Failed;
+                        st.completed_at = Some(chrono::Utc::now());
+                        st.result = Some(TaskResult {
+                            error: Some(CANCELLED_MESSAGE.to_string()),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+        self.task_changed();
+        info!(task_id = %task_id, "swarm task {}", CANCELLED_MESSAGE);
+        let _ = self.event_tx.send(SwarmEvent::TaskCompleted {
+            task_id: task_id.to_string(),
+            success: false,
+        });
+    }
+
     /// Phase 1: Decompose task into subtasks using Planner agent
     async fn plan_task(&self, task_id: String) {
         let _ = self.event_tx.send(SwarmEvent::TaskPlanning {
@@ -380,6 +565,8 @@ impl AgentSwarm {
                 task.status = TaskStatus::Executing;
                 task.subtasks = plan;
             }
+            drop(tasks);
+            self.task_changed();
         }
     }
 
@@ -391,20 +578,24 @@ impl AgentSwarm {
         let analysis = self.analyze_task_complexity(description).await;
         
         let mut subtasks = Vec::new();
+        let plan_id = Uuid::new_v4();
+        let ids: Vec<String> = (0..analysis.steps.len())
+            .map(|idx| format!("{}_step_{}", plan_id, idx))
+            .collect();
         
         // Create subtasks based on analysis
         for (idx, step) in analysis.steps.iter().enumerate() {
             let subtask = SubTask {
-                id: format!("{}_step_{}", Uuid::new_v4(), idx),
+                id: ids[idx].clone(),
                 parent_id: None,
                 description: step.description.clone(),
                 agent_type: step.agent_type,
-                dependencies: step.dependencies.clone(),
-                status: if step.dependencies.is_empty() {
-                    SubTaskStatus::Ready
-                } else {
-                    SubTaskStatus::Blocked
-                },
+                dependencies: step
+                    .dependencies
+                    .iter()
+                    .filter_map(|dep| resolve_step_reference(dep, &ids))
+                    .collect(),
+                status: SubTaskStatus::Blocked,
                 result: None,
                 verification_result: None,
                 retry_count: 0,
@@ -413,10 +604,19 @@ impl AgentSwarm {
                 started_at: None,
                 completed_at: None,
                 estimated_duration_ms: step.estimated_duration_ms,
+                expected_outcome: step.expected_outcome.clone(),
             };
             subtasks.push(subtask);
         }
         
+        // Cycles would leave every subtask Blocked forever
+        break_dependency_cycles(&mut subtasks);
+        for st in subtasks.iter_mut() {
+            if st.dependencies.is_empty() {
+                st.status = SubTaskStatus::Ready;
+            }
+        }
+        
         subtasks
     }
 
@@ -443,11 +643,14 @@ Return a JSON object:
       "description": "Specific action description",
       "agent_type": "Planner" | "Executor" | "Specialist" | "Verifier",
       "depends_on": [],
-      "estimated_ms": 5000
+      "estimated_ms": 5000,
+      "expected_outcome": "What should be true or visible once this step is done"
     }}
   ]
 }}
 
+depends_on lists the 1-based numbers of earlier steps this step must wait for (e.g. [1, 2]).
+
 Agent types:
 - Planner: Analysis, planning, research
 - Executor: Computer actions (click, type, screenshot), bash commands, app launching
@@ -469,102 +672,32 @@ Return ONLY JSON."#,
                 content: vec![crate::api::ContentBlock::Text { text: prompt }],
             }];
             
-            if let Ok(result) = client.complete(None, messages, None).await {
-                let text = result.content.iter()
-                    .filter_map(|b| if let crate::api::ContentBlock::Text { text } = b { Some(text.as_str()) } else { None })
-                    .collect::<String>();
-                
-                // Parse JSON response
-                if let Some(start) = text.find('{') {
-                    if let Some(end) = text.rfind('}') {
-                        let json_str = &text[start..=end];
-                        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(json_str) {
-                            let complexity = match parsed.get("complexity").and_then(|v| v.as_str()).unwrap_or("moderate") {
-                                "simple" => TaskComplexity::Simple,
-                                "complex" => TaskComplexity::Complex,
-                                _ => TaskComplexity::Moderate,
-                            };
-                            
-                            let parallelizable = parsed.get("parallelizable").and_then(|v| v.as_bool()).unwrap_or(false);
-                            
-                            if let Some(steps_arr) = parsed.get("steps").and_then(|v| v.as_array()) {
-                                let mut steps = Vec::new();
-                                let mut total_ms = 0u64;
-                                
-                                for step in steps_arr {
-                                    let desc = step.get("description").and_then(|v| v.as_str()).unwrap_or("Execute task");
-                                    let agent = match step.get("agent_type").and_then(|v| v.as_str()).unwrap_or("Executor") {
-                                        "Planner" => AgentType::Planner,
-                                        "Specialist" => AgentType::Specialist,
-                                        "Verifier" => AgentType::Verifier,
-                                        "Critic" => AgentType::Critic,
-                                        _ => AgentType::Executor,
-                                    };
-                                    let est_ms = step.get("estimated_ms").and_then(|v| v.as_u64()).unwrap_or(5000);
-                                    let deps: Vec<String> = step.get("depends_on")
-                                        .and_then(|v| v.as_array())
-                                        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                                        .unwrap_or_default();
-                                    
-                                    total_ms += est_ms;
-                                    steps.push(AnalysisStep {
-                                        description: desc.to_string(),
-                                        agent_type: agent,
-                                        dependencies: deps,
-                                        estimated_duration_ms: est_ms,
-                                    });
-                                }
-                                
-                                if !steps.is_empty() {
-                                    println!("[swarm] LLM decomposed task into {} steps", steps.len());
-                                    return TaskAnalysis {
-                                        complexity,
-                                        steps,
-                                        parallelizable,
-                                        requires_verification: true,
-                                        estimated_total_duration_ms: total_ms,
-                                    };
-                                }
-                            }
-                        }
+            match client.complete_cached(None, messages, None).await {
+                Ok(result) => {
+                    let text = result.content.iter()
+                        .filter_map(|b| if let crate::api::ContentBlock::Text { text } = b { Some(text.as_str()) } else { None })
+                        .collect::<String>();
+                    if let Some(analysis) = parse_task_analysis(&text) {
+                        info!(steps = analysis.steps.len(), "swarm llm decomposed task");
+                        return analysis;
                     }
+                    warn!(raw = %text, "swarm planner output didn't parse, falling back to splitting the instruction");
                 }
+                Err(e) => warn!("swarm planner call failed ({}), falling back to splitting the instruction", e),
             }
         }
-        
-        // Fallback: simple sequential plan
-        println!("[swarm] Using fallback task decomposition");
-        TaskAnalysis {
-            complexity: TaskComplexity::Moderate,
-            steps: vec![
-                AnalysisStep {
-                    description: format!("Take screenshot to observe current state"),
-                    agent_type: AgentType::Executor,
-                    dependencies: vec![],
-                    estimated_duration_ms: 3000,
-                },
-                AnalysisStep {
-                    description: format!("Execute main task: {}", description),
-                    agent_type: AgentType::Executor,
-                    dependencies: vec![],
-                    estimated_duration_ms: 10000,
-                },
-                AnalysisStep {
-                    description: "Verify task completion by taking screenshot".to_string(),
-                    agent_type: AgentType::Verifier,
-                    dependencies: vec![],
-                    estimated_duration_ms: 3000,
-                },
-            ],
-            parallelizable: false,
-            requires_verification: true,
-            estimated_total_duration_ms: 16000,
-        }
+
+        fallback_analysis(description)
     }
 
     /// Phase 2: Execute subtasks
     async fn execute_task(&self, task_id: String) {
+        let token = task_token(&task_id);
         loop {
+            if token.is_cancelled() {
+                break;
+            }
+
             // Get ready subtasks
             let ready_subtasks = {
                 let tasks = self.tasks.read().await;
@@ -623,6 +756,9 @@ Return ONLY JSON."#,
             } else {
                 // Sequential execution
                 for subtask_id in ready_subtasks {
+                    if token.is_cancelled() {
+                        break;
+                    }
                     self.execute_subtask(task_id.clone(), subtask_id).await;
                 }
             }
@@ -652,31 +788,44 @@ Return ONLY JSON."#,
                     }
                 }
             }
+            self.task_changed();
             
             let _ = self.event_tx.send(SwarmEvent::SubTaskStarted {
                 task_id: task_id.clone(),
                 subtask_id: subtask_id.clone(),
                 agent: subtask.agent_type,
             });
+
+            // baseline for the verifier's before/after comparison
+            let before_screenshot = if self.config.verification_enabled && acts_on_screen(subtask.agent_type) {
+                self.capture_screen().await
+            } else {
+                None
+            };
             
-            // Execute with timeout
+            // Execute with timeout; a cancel drops the executor (and its API/tool call) mid-flight
             let timeout = Duration::from_secs(self.config.subtask_timeout_secs);
-            let result = tokio::time::timeout(
-                timeout,
-                self.run_agent_executor(&subtask)
-            ).await;
+            let token = task_token(&task_id);
+            let result = tokio::select! {
+                biased;
+                _ = token.cancelled() => return,
+                result = tokio::time::timeout(timeout, self.run_agent_executor(&subtask)) => result,
+            };
             
             match result {
                 Ok(Ok(task_result)) => {
-                    // Success
-                    let mut tasks = self.tasks.write().await;
-                    if let Some(task) = tasks.get_mut(&task_id) {
-                        if let Some(st) = task.subtasks.iter_mut().find(|s| s.id == subtask_id) {
-                            st.status = SubTaskStatus::Completed;
-                            st.completed_at = Some(chrono::Utc::now());
-                            st.result = Some(task_result.clone());
+                    // Success - release the write lock before verification reads the task
+                    {
+                        let mut tasks = self.tasks.write().await;
+                        if let Some(task) = tasks.get_mut(&task_id) {
+                            if let Some(st) = task.subtasks.iter_mut().find(|s| s.id == subtask_id) {
+                                st.status = SubTaskStatus::Completed;
+                                st.completed_at = Some(chrono::Utc::now());
+                                st.result = Some(task_result.clone());
+                            }
                         }
                     }
+                    self.task_changed();
                     
                     let _ = self.event_tx.send(SwarmEvent::SubTaskCompleted {
                         task_id: task_id.clone(),
@@ -685,8 +834,8 @@ Return ONLY JSON."#,
                     });
                     
                     // Trigger verification if enabled
-                    if self.config.verification_enabled {
-                        self.verify_subtask(task_id.clone(), subtask_id.clone()).await;
+                    if self.config.verification_enabled && !token.is_cancelled() {
+                        self.verify_subtask(task_id.clone(), subtask_id.clone(), before_screenshot).await;
                     }
                 }
                 Ok(Err(e)) => {
@@ -747,12 +896,18 @@ Return ONLY JSON."#,
         }
         
         // Default: Try to interpret and execute using LLM
-        println!("[swarm] Using LLM to interpret task: {}", subtask.description);
+        info!(subtask_id = %subtask.id, "swarm using llm to interpret task: {}", subtask.description);
         return self.execute_llm_task(executor, subtask).await
     }
 
+    /// Current screen as base64 JPEG, or None when computer control isn't available
+    async fn capture_screen(&self) -> Option<String> {
+        self.execute_screenshot().await.ok()?.screenshots.into_iter().next()
+    }
+
     /// Execute screenshot tool
     async fn execute_screenshot(&self) -> Result<TaskResult, String> {
+        crate::tool_policy::ensure_allowed("computer")?;
         let computer_guard = self.computer.lock().await;
         let computer = match computer_guard.as_ref() {
             Some(c) => c,
@@ -787,6 +942,7 @@ Return ONLY JSON."#,
 
     /// Execute click action - uses LLM to determine WHERE to click via screenshot analysis
     async fn execute_click(&self, description: &str) -> Result<TaskResult, String> {
+        crate::tool_policy::ensure_allowed("computer")?;
         // Step 1: Take a screenshot so the LLM can see what's on screen
         let screenshot_result = self.execute_screenshot().await?;
         let screenshot_b64 = screenshot_result.screenshots.first()
@@ -800,7 +956,7 @@ Return ONLY JSON."#,
             (px, py)
         } else {
             // Step 3: Ask LLM to identify click target from screenshot
-            let executor = self.executors.values().next()
+            let executor = self.executors.get(&AgentType::Executor)
                 .ok_or("No executor available")?;
             let client = crate::api::AnthropicClient::new(
                 executor.api_key.clone(), executor.model.clone(),
@@ -853,13 +1009,13 @@ Return ONLY JSON."#,
                     }
                 }
                 Err(e) => {
-                    println!("[swarm] LLM click analysis failed: {}, using center", e);
+                    warn!("swarm llm click analysis failed: {}, using center", e);
                     (500, 500)
                 }
             }
         };
         
-        println!("[swarm] Clicking at [{}, {}] for: {}", x, y, description);
+        debug!(x, y, "swarm clicking for: {}", description);
         
         let computer_guard = self.computer.lock().await;
         let computer = match computer_guard.as_ref() {
@@ -906,6 +1062,7 @@ Return ONLY JSON."#,
 
     /// Execute type action
     async fn execute_type(&self, description: &str) -> Result<TaskResult, String> {
+        crate::tool_policy::ensure_allowed("computer")?;
         // Extract text to type - simple heuristic
         let text = if let Some(pos) = description.find('"') {
             if let Some(end) = description[pos+1..].find('"') {
@@ -962,7 +1119,8 @@ Return ONLY JSON."#,
 
     /// Execute bash command
     async fn execute_bash(&self, command: &str) -> Result<TaskResult, String> {
-        let bash = self.bash.lock().await;
+        crate::tool_policy::ensure_allowed("bash")?;
+        let mut bash = self.bash.lock().await;
         
         match bash.execute(command) {
             Ok(output) => Ok(TaskResult {
@@ -1090,6 +1248,8 @@ Return ONLY JSON."#,
                     st.status = SubTaskStatus::Ready; // Retry
                 }
             }
+            drop(tasks);
+            self.task_changed();
         } else {
             // Mark as failed
             let mut tasks = self.tasks.write().await;
@@ -1107,6 +1267,8 @@ Return ONLY JSON."#,
                     });
                 }
             }
+            drop(tasks);
+            self.task_changed();
             
             let _ = self.event_tx.send(SwarmEvent::SubTaskFailed {
                 task_id,
@@ -1117,22 +1279,38 @@ Return ONLY JSON."#,
     }
 
     /// Verify subtask result using LLM
-    async fn verify_subtask(&self, task_id: String, subtask_id: String) {
+    async fn verify_subtask(&self, task_id: String, subtask_id: String, before_screenshot: Option<String>) {
         // Get the subtask result to verify
-        let (subtask_desc, subtask_result) = {
+        let (subtask_desc, subtask_result, expected_outcome) = {
             let tasks = self.tasks.read().await;
             if let Some(task) = tasks.get(&task_id) {
                 if let Some(st) = task.subtasks.iter().find(|s| s.id == subtask_id) {
-                    (st.description.clone(), st.result.clone())
+                    (st.description.clone(), st.result.clone(), st.expected_outcome.clone())
                 } else {
-                    (String::new(), None)
+                    (String::new(), None, None)
                 }
             } else {
-                (String::new(), None)
+                (String::new(), None, None)
+            }
+        };
+
+        // Baseline comparison: what the step actually changed on screen
+        let after_screenshot = match before_screenshot {
+            Some(_) => self.capture_screen().await,
+            None => None,
+        };
+        let pixel_diff = match (&before_screenshot, &after_screenshot) {
+            (Some(before), Some(after)) => {
+                let (before, after) = (before.clone(), after.clone());
+                tokio::task::spawn_blocking(move || crate::computer::screenshot_change_ratio(&before, &after))
+                    .await
+                    .ok()
+                    .and_then(|r| r.ok())
             }
+            _ => None,
         };
         
-        let verification = if let Some(ref result) = subtask_result {
+        let mut verification = if let Some(ref result) = subtask_result {
             // Try LLM-based verification
             if let Some(verifier) = self.executors.get(&AgentType::Verifier) {
                 let client = crate::api::AnthropicClient::new(
@@ -1140,24 +1318,48 @@ Return ONLY JSON."#,
                     verifier.model.clone(),
                 );
                 
+                let screen_signal = match pixel_diff {
+                    Some(diff) => format!(
+                        "\nScreen change: {:.1}% of pixels changed between the before and after screenshots attached below.",
+                        diff * 100.0
+                    ),
+                    None => String::new(),
+                };
                 let prompt = format!(
                     r#"Verify this task execution result. Return JSON only.
 
 Task: "{}"
+Expected outcome: "{}"
 Result success: {}
 Output: "{}"
-Error: {:?}
+Error: {:?}{}
 
+Judge by what actually changed, not by what the output claims.
 Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggestions": ["suggestion1"]}}"#,
                     subtask_desc,
+                    expected_outcome.as_deref().unwrap_or("not specified - infer it from the task"),
                     result.success,
-                    &result.output[..result.output.len().min(500)],
-                    result.error
+                    result.output.chars().take(500).collect::<String>(),
+                    result.error,
+                    screen_signal
                 );
-                
+
+                let mut content = vec![crate::api::ContentBlock::Text { text: prompt }];
+                if let (Some(before), Some(after)) = (&before_screenshot, &after_screenshot) {
+                    for (label, screenshot) in [("Before:", before), ("After:", after)] {
+                        content.push(crate::api::ContentBlock::Text { text: label.to_string() });
+                        content.push(crate::api::ContentBlock::Image {
+                            source: crate::api::ImageSource {
+                                source_type: "base64".to_string(),
+                                media_type: "image/jpeg".to_string(),
+                                data: screenshot.clone(),
+                            },
+                        });
+                    }
+                }
                 let messages = vec![crate::api::Message {
                     role: "user".to_string(),
-                    content: vec![crate::api::ContentBlock::Text { text: prompt }],
+                    content,
                 }];
                 
                 match client.complete(Some(VERIFIER_PROMPT.to_string()), messages, None).await {
@@ -1182,13 +1384,14 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
                                         .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
                                         .unwrap_or_default();
                                     
-                                    VerificationResult { passed, score, issues, suggestions }
+                                    VerificationResult { passed, score, issues, suggestions, ..Default::default() }
                                 } else {
                                     VerificationResult {
                                         passed: result.success,
                                         score: if result.success { 0.8 } else { 0.3 },
                                         issues: vec![],
                                         suggestions: vec![],
+                                        ..Default::default(),
                                     }
                                 }
                             } else {
@@ -1197,6 +1400,7 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
                                     score: if result.success { 0.8 } else { 0.3 },
                                     issues: vec![],
                                     suggestions: vec![],
+                                    ..Default::default(),
                                 }
                             }
                         } else {
@@ -1205,6 +1409,7 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
                                 score: if result.success { 0.8 } else { 0.3 },
                                 issues: vec![],
                                 suggestions: vec![],
+                                ..Default::default(),
                             }
                         }
                     }
@@ -1215,6 +1420,7 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
                             score: if result.success { 0.75 } else { 0.2 },
                             issues: if result.success { vec![] } else { vec!["Task reported failure".to_string()] },
                             suggestions: vec![],
+                            ..Default::default(),
                         }
                     }
                 }
@@ -1225,6 +1431,7 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
                     score: if result.success { 0.75 } else { 0.2 },
                     issues: vec![],
                     suggestions: vec![],
+                    ..Default::default(),
                 }
             }
         } else {
@@ -1233,9 +1440,14 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
                 score: 0.0,
                 issues: vec!["No result to verify".to_string()],
                 suggestions: vec!["Re-execute the task".to_string()],
+                ..Default::default(),
             }
         };
         
+        verification.before_screenshot = before_screenshot;
+        verification.after_screenshot = after_screenshot;
+        verification.pixel_diff = pixel_diff;
+
         let mut tasks = self.tasks.write().await;
         if let Some(task) = tasks.get_mut(&task_id) {
             if let Some(st) = task.subtasks.iter_mut().find(|s| s.id == subtask_id) {
@@ -1246,8 +1458,7 @@ Return: {{"passed": true/false, "score": 0.0-1.0, "issues": ["issue1"], "suggest
         let _ = self.event_tx.send(SwarmEvent::VerificationCompleted {
             task_id,
             subtask_id,
-            passed: verification.passed,
-            score: verification.score,
+            result: verification,
         });
     }
 
@@ -1359,6 +1570,17 @@ Return: {{"issues": ["issue1", "issue2"], "suggestions": ["suggestion1", "sugges
         }
     }
 
+    /// Wake everyone waiting on `updates`
+    fn task_changed(&self) {
+        self.updates.notify_waiters();
+    }
+
+    /// Signalled on every task or subtask status change. Only waiters registered at the time
+    /// are woken, so enable the `notified()` future before reading the task
+    pub fn updates(&self) -> Arc<Notify> {
+        self.updates.clone()
+    }
+
     /// Get task status
     pub async fn get_task_status(&self, task_id: &str) -> Option<TaskStatus> {
         let tasks = self.tasks.read().await;
@@ -1407,6 +1629,7 @@ Return: {{"issues": ["issue1", "issue2"], "suggestions": ["suggestion1", "sugges
             stats: self.stats.clone(),
             computer: self.computer.clone(),
             bash: self.bash.clone(),
+            updates: self.updates.clone(),
         }
     }
 }
@@ -1442,6 +1665,249 @@ fn parse_coordinates_from_text(text: &str) -> Option<(i32, i32)> {
     None
 }
 
+/// Whether a subtask's agent touches the screen - only those get before/after screenshots
+fn acts_on_screen(agent_type: AgentType) -> bool {
+    !matches!(agent_type, AgentType::Planner | AgentType::Critic | AgentType::Verifier)
+}
+
+/// Map a planner dependency ("2", "step_2", or a subtask id) to a subtask id.
+/// Step numbers are 1-based, matching the numbering in the decomposition prompt.
+fn resolve_step_reference(dep: &str, ids: &[String]) -> Option<String> {
+    if ids.iter().any(|id| id == dep) {
+        return Some(dep.to_string());
+    }
+    let digits: String = dep
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let number: usize = digits.parse().ok()?;
+    ids.get(number.saturating_sub(1)).cloned()
+}
+
+/// Drop edges until the dependency graph is acyclic. In each cycle the weakest edge is the
+/// one pointing furthest forward (a step waiting on a later step), which plans rarely mean.
+fn break_dependency_cycles(subtasks: &mut [SubTask]) {
+    let index: HashMap<String, usize> = subtasks
+        .iter()
+        .enumerate()
+        .map(|(i, st)| (st.id.clone(), i))
+        .collect();
+    for st in subtasks.iter_mut() {
+        st.dependencies.retain(|dep| index.contains_key(dep));
+    }
+
+    loop {
+        let deps: Vec<Vec<usize>> = subtasks
+            .iter()
+            .map(|st| st.dependencies.iter().map(|d| index[d]).collect())
+            .collect();
+        let Some(cycle) = super::planner::find_dependency_cycle(&deps) else {
+            break;
+        };
+
+        let (dependent, dependency) = (0..cycle.len())
+            .map(|k| (cycle[k], cycle[(k + 1) % cycle.len()]))
+            .max_by_key(|&(from, to)| to as isize - from as isize)
+            .unwrap_or((cycle[0], cycle[0]));
+
+        warn!(
+            "swarm circular dependency between \"{}\" and \"{}\" - dropping the edge",
+            subtasks[dependent].description, subtasks[dependency].description
+        );
+        let dependency_id = subtasks[dependency].id.clone();
+        subtasks[dependent].dependencies.retain(|d| *d != dependency_id);
+    }
+}
+
+/// Drop commas that directly precede a closing brace or bracket (outside strings) - the
+/// most common way an otherwise fine model JSON fails to parse.
+fn repair_json(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut repaired = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        repaired.push(c);
+    }
+    repaired
+}
+
+/// Parse the planner's decomposition, trimmed to the outermost braces, with one repair pass
+/// if it doesn't parse as is. None when there's no usable step list.
+fn parse_task_analysis(text: &str) -> Option<TaskAnalysis> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    let json_str = text.get(start..=end)?;
+    let parsed: serde_json::Value = serde_json::from_str(json_str)
+        .or_else(|_| serde_json::from_str(&repair_json(json_str)))
+        .ok()?;
+
+    let complexity = match parsed.get("complexity").and_then(|v| v.as_str()).unwrap_or("moderate") {
+        "simple" => TaskComplexity::Simple,
+        "complex" => TaskComplexity::Complex,
+        _ => TaskComplexity::Moderate,
+    };
+    let parallelizable = parsed.get("parallelizable").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let mut steps = Vec::new();
+    let mut total_ms = 0u64;
+    for step in parsed.get("steps").and_then(|v| v.as_array())? {
+        let desc = step.get("description").and_then(|v| v.as_str()).unwrap_or("Execute task");
+        let agent = match step.get("agent_type").and_then(|v| v.as_str()).unwrap_or("Executor") {
+            "Planner" => AgentType::Planner,
+            "Specialist" => AgentType::Specialist,
+            "Verifier" => AgentType::Verifier,
+            "Critic" => AgentType::Critic,
+            _ => AgentType::Executor,
+        };
+        let est_ms = step.get("estimated_ms").and_then(|v| v.as_u64()).unwrap_or(5000);
+        let deps: Vec<String> = step.get("depends_on")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| match v {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }).collect())
+            .unwrap_or_default();
+        let expected = step.get("expected_outcome")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.to_string());
+
+        total_ms += est_ms;
+        steps.push(AnalysisStep {
+            description: desc.to_string(),
+            agent_type: agent,
+            dependencies: deps,
+            estimated_duration_ms: est_ms,
+            expected_outcome: expected,
+        });
+    }
+    if steps.is_empty() {
+        return None;
+    }
+
+    Some(TaskAnalysis {
+        complexity,
+        steps,
+        parallelizable,
+        requires_verification: true,
+        estimated_total_duration_ms: total_ms,
+    })
+}
+
+// Phrases that mark the next part of an instruction, matched case-insensitively
+const SEQUENCE_SEPARATORS: &[&str] = &[
+    ", and then ",
+    " and then ",
+    ", then ",
+    "; then ",
+    ". then ",
+    ", after that ",
+    " after that ",
+    ". after that ",
+    " and afterwards ",
+    ", finally ",
+    " and finally ",
+];
+
+/// Strip a list marker ("1.", "2)", "-", "*") from the start of a line, if it has one.
+fn strip_list_marker(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(rest);
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = line[digits..].strip_prefix('.').or_else(|| line[digits..].strip_prefix(')'))?;
+    (digits > 0 && rest.starts_with(char::is_whitespace)).then_some(rest)
+}
+
+/// Split an instruction into its sequential parts: the items of a numbered or bulleted list,
+/// otherwise the clauses between "and then", "after that" and the like.
+fn split_instruction(description: &str) -> Vec<String> {
+    let items: Vec<&str> = description.lines().filter_map(strip_list_marker).collect();
+    let parts: Vec<String> = if items.len() >= 2 {
+        items.iter().map(|item| item.to_string()).collect()
+    } else {
+        // same byte offsets as the original, so the cut points carry over
+        let lower = description.to_ascii_lowercase();
+        let mut parts = Vec::new();
+        let mut from = 0;
+        while let Some((at, separator)) = SEQUENCE_SEPARATORS
+            .iter()
+            .filter_map(|separator| lower[from..].find(separator).map(|at| (from + at, separator)))
+            .min_by_key(|&(at, separator)| (at, std::cmp::Reverse(separator.len())))
+        {
+            parts.push(description[from..at].to_string());
+            from = at + separator.len();
+        }
+        parts.push(description[from..].to_string());
+        parts
+    };
+
+    parts
+        .iter()
+        .map(|part| part.trim().trim_matches(|c: char| matches!(c, ',' | ';' | '.')).trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Plan used when the planner gives nothing usable: one Executor step per part of the
+/// instruction, each waiting on the one before, then a Verifier step.
+fn fallback_analysis(description: &str) -> TaskAnalysis {
+    let mut parts = split_instruction(description);
+    if parts.is_empty() {
+        parts.push(description.trim().to_string());
+    }
+    let mut steps: Vec<AnalysisStep> = parts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, part)| AnalysisStep {
+            description: part,
+            agent_type: AgentType::Executor,
+            // step numbers are 1-based, so this is the previous step
+            dependencies: if idx == 0 { vec![] } else { vec![idx.to_string()] },
+            estimated_duration_ms: 10000,
+            expected_outcome: None,
+        })
+        .collect();
+    steps.push(AnalysisStep {
+        description: format!("Verify by taking a screenshot that the task is done: {}", description.trim()),
+        agent_type: AgentType::Verifier,
+        dependencies: vec![steps.len().to_string()],
+        estimated_duration_ms: 3000,
+        expected_outcome: None,
+    });
+
+    let total_ms = steps.iter().map(|step| step.estimated_duration_ms).sum();
+    TaskAnalysis {
+        complexity: TaskComplexity::Moderate,
+        steps,
+        parallelizable: false,
+        requires_verification: true,
+        estimated_total_duration_ms: total_ms,
+    }
+}
+
 // Supporting structs
 #[derive(Debug, Clone)]
 struct TaskAnalysis {
@@ -1458,6 +1924,7 @@ struct AnalysisStep {
     agent_type: AgentType,
     dependencies: Vec<String>,
     estimated_duration_ms: u64,
+    expected_outcome: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -1573,3 +2040,139 @@ Expertise:
 - Code generation
 
 Use Python with appropriate libraries for efficient document processing."#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subtask(id: &str, deps: &[&str]) -> SubTask {
+        SubTask {
+            id: id.to_string(),
+            parent_id: None,
+            description: format!("step {}", id),
+            agent_type: AgentType::Executor,
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            status: SubTaskStatus::Blocked,
+            result: None,
+            verification_result: None,
+            retry_count: 0,
+            max_retries: 1,
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            completed_at: None,
+            estimated_duration_ms: 0,
+            expected_outcome: None,
+        }
+    }
+
+    #[test]
+    fn cycle_is_broken_at_forward_edge() {
+        // a waits on b (forward), b waits on a - dropping a -> b keeps the natural order
+        let mut plan = vec![subtask("a", &["b"]), subtask("b", &["a"])];
+        break_dependency_cycles(&mut plan);
+        assert!(plan[0].dependencies.is_empty());
+        assert_eq!(plan[1].dependencies, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn progress_counts_and_eta() {
+        let now = chrono::Utc::now();
+        let mut done = subtask("a", &[]);
+        done.status = SubTaskStatus::Completed;
+        done.estimated_duration_ms = 4_000;
+        let mut running = subtask("b", &[]);
+        running.status = SubTaskStatus::Executing;
+        running.estimated_duration_ms = 10_000;
+        running.started_at = Some(now - chrono::Duration::seconds(4));
+        let mut waiting = subtask("c", &["b"]);
+        waiting.estimated_duration_ms = 6_000;
+
+        let mut task = ComplexTask {
+            id: "t".to_string(),
+            description: String::new(),
+            goal: String::new(),
+            subtasks: vec![done, running, waiting],
+            status: TaskStatus::Executing,
+            created_at: now,
+            max_parallel: 1,
+            require_verification: false,
+            metadata: HashMap::new(),
+        };
+        let progress = task.progress(now);
+        assert_eq!((progress.completed, progress.failed, progress.total), (1, 0, 3));
+        assert_eq!(progress.eta_ms, Some(12_000));
+
+        task.max_parallel = 3;
+        assert_eq!(task.progress(now).eta_ms, Some(6_000));
+
+        task.subtasks.iter_mut().for_each(|st| st.estimated_duration_ms = 0);
+        assert_eq!(task.progress(now).eta_ms, None);
+    }
+
+    #[test]
+    fn models_follow_role_defaults_and_overrides() {
+        let mut config = SwarmConfig {
+            main_model: "main".to_string(),
+            light_model: "light".to_string(),
+            model_overrides: HashMap::new(),
+            ..SwarmConfig::default()
+        };
+        assert_eq!(config.model_for(AgentType::Planner), "light");
+        assert_eq!(config.model_for(AgentType::Verifier), "light");
+        assert_eq!(config.model_for(AgentType::Executor), "main");
+        assert_eq!(config.model_for(AgentType::Specialist), "main");
+
+        config.model_overrides = parse_model_overrides(" planner = custom ,Bogus=x,Verifier=,Executor=fast");
+        assert_eq!(config.model_overrides.len(), 2);
+        assert_eq!(config.model_for(AgentType::Planner), "custom");
+        assert_eq!(config.model_for(AgentType::Verifier), "light");
+        assert_eq!(config.model_for(AgentType::Executor), "fast");
+    }
+
+    #[test]
+    fn step_numbers_resolve_to_ids() {
+        let ids = vec!["x_step_0".to_string(), "x_step_1".to_string()];
+        assert_eq!(resolve_step_reference("1", &ids).as_deref(), Some("x_step_0"));
+        assert_eq!(resolve_step_reference("step_2", &ids).as_deref(), Some("x_step_1"));
+        assert_eq!(resolve_step_reference("x_step_1", &ids).as_deref(), Some("x_step_1"));
+        assert_eq!(resolve_step_reference("7", &ids), None);
+        assert_eq!(resolve_step_reference("later", &ids), None);
+    }
+
+    #[test]
+    fn planner_json_gets_one_repair_pass() {
+        let text = "Here you go:\n```json\n{\"complexity\": \"simple\", \"steps\": [{\"description\": \"Open Notes, then wait\", \"depends_on\": [],},],}\n```";
+        let analysis = parse_task_analysis(text).unwrap();
+        assert_eq!(analysis.steps.len(), 1);
+        assert_eq!(analysis.steps[0].description, "Open Notes, then wait");
+        assert_eq!(repair_json("{\"a\": \"x,}\", \"b\": [1, 2 ,] }"), "{\"a\": \"x,}\", \"b\": [1, 2 ] }");
+        assert!(parse_task_analysis("no json here").is_none());
+        assert!(parse_task_analysis("{\"steps\": []}").is_none());
+    }
+
+    #[test]
+    fn instructions_split_on_sequence_words_and_lists() {
+        assert_eq!(
+            split_instruction("Open Safari and then go to github.com, after that star the repo"),
+            vec!["Open Safari", "go to github.com", "star the repo"]
+        );
+        assert_eq!(
+            split_instruction("Do these:\n1. Open Mail\n2) Reply to Sam\n- Archive it"),
+            vec!["Open Mail", "Reply to Sam", "Archive it"]
+        );
+        assert_eq!(split_instruction("Book a table for 2 at 7. Thanks"), vec!["Book a table for 2 at 7. Thanks"]);
+    }
+
+    #[test]
+    fn fallback_plan_runs_the_parts_in_order() {
+        let analysis = fallback_analysis("open Finder and then make a folder");
+        let plan: Vec<(&str, AgentType, Vec<String>)> = analysis
+            .steps
+            .iter()
+            .map(|step| (step.description.as_str(), step.agent_type, step.dependencies.clone()))
+            .collect();
+        assert_eq!(plan[0], ("open Finder", AgentType::Executor, vec![]));
+        assert_eq!(plan[1], ("make a folder", AgentType::Executor, vec!["1".to_string()]));
+        assert_eq!((plan[2].1, plan[2].2.clone()), (AgentType::Verifier, vec!["2".to_string()]));
+    }
+}
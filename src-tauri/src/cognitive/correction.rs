@@ -3,9 +3,11 @@
 //! Detects failures, analyzes root causes, and automatically retries
 //! with alternative approaches until success or max retries exceeded.
 
-use super::{Subtask, TaskResult};
+use super::{ActionType, Subtask, TaskResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Self-correction engine with retry logic
@@ -16,6 +18,15 @@ pub struct SelfCorrection {
     max_retries: u32,
     /// Base delay between retries (exponential backoff)
     base_delay_ms: u64,
+    /// Where model-written commands are put to the user; without it they're refused
+    confirmer: Option<CommandConfirmer>,
+}
+
+/// The app and run flag a confirmation prompt needs
+#[derive(Clone)]
+pub struct CommandConfirmer {
+    pub app_handle: tauri::AppHandle,
+    pub running: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -72,6 +83,65 @@ pub enum CorrectionAction {
     AskUser,
 }
 
+/// How an attempt runs the subtask's action. Each action type has a ladder of these,
+/// climbed one rung per retry, so a retry changes the approach instead of repeating it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptStrategy {
+    /// Run the action as planned
+    AsPlanned,
+    /// Computer clicks: find the target's label on screen with OCR and click that
+    ClickByText,
+    /// Bash: ask the model for a different command with the same goal
+    AlternativeCommand,
+}
+
+impl AttemptStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AsPlanned => "as_planned",
+            Self::ClickByText => "click_by_text",
+            Self::AlternativeCommand => "alternative_command",
+        }
+    }
+}
+
+/// The strategy ladder for an action type, in the order the rungs are tried
+pub fn strategy_ladder(action_type: &ActionType) -> &'static [AttemptStrategy] {
+    use AttemptStrategy::*;
+    match action_type {
+        ActionType::Computer { action, .. } if action.contains("click") => &[AsPlanned, ClickByText],
+        ActionType::Bash { .. } => &[AsPlanned, AlternativeCommand],
+        _ => &[AsPlanned],
+    }
+}
+
+/// Strategy for the 1-based `attempt`; past the top of the ladder the last rung is repeated
+pub fn strategy_for(action_type: &ActionType, attempt: u32) -> AttemptStrategy {
+    let ladder = strategy_ladder(action_type);
+    let rung = (attempt.max(1) as usize - 1).min(ladder.len() - 1);
+    ladder[rung]
+}
+
+/// The visible label a computer click was aiming at, if the plan named one
+pub fn click_label(params: &serde_json::Value) -> Option<String> {
+    ["text", "label", "target"]
+        .iter()
+        .find_map(|key| params.get(key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(str::to_string)
+}
+
+/// The command in a model reply, without code fences, a leading `$` or surrounding backticks
+pub fn parse_command(reply: &str) -> Option<String> {
+    reply
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("```"))
+        .map(|line| line.trim_start_matches("$ ").trim_matches('`').trim().to_string())
+        .filter(|command| !command.is_empty())
+}
+
 /// Result of a correction attempt
 #[derive(Debug, Clone)]
 pub struct CorrectionResult {
@@ -231,9 +301,15 @@ impl SelfCorrection {
             strategies,
             max_retries: 3,
             base_delay_ms: 500,
+            confirmer: None,
         }
     }
 
+    /// Let alternative commands be confirmed with the user like the agent loop's bash calls
+    pub fn set_confirmer(&mut self, app_handle: tauri::AppHandle, running: Arc<AtomicBool>) {
+        self.confirmer = Some(CommandConfirmer { app_handle, running });
+    }
+
     /// Execute a subtask with automatic retry and correction
     pub async fn execute_with_retry(&self, subtask: &mut Subtask) -> anyhow::Result<TaskResult> {
         let start_time = Instant::now();
@@ -254,11 +330,21 @@ impl SelfCorrection {
                 subtask.max_retries
             );
             
-            // Try to execute the action
-            match self.try_execute(subtask).await {
-                Ok(result) => {
+            // Try to execute the action, with this attempt's rung of the ladder
+            let strategy = strategy_for(&subtask.action_type, retry_state.attempt);
+            let last_error = retry_state.failures.last().map(|f| f.message.clone());
+            match self.try_execute(subtask, strategy, last_error.as_deref()).await {
+                Ok(mut result) => {
                     if result.success {
-                        println!("[correction] Success on attempt {}", retry_state.attempt);
+                        println!("[correction] Success on attempt {} ({})", retry_state.attempt, strategy.name());
+                        if retry_state.attempt > 1 {
+                            result.learnings.push(format!(
+                                "'{}' succeeded with strategy '{}' after {} failed attempt(s)",
+                                subtask.description,
+                                strategy.name(),
+                                retry_state.attempt - 1
+                            ));
+                        }
                         return Ok(result);
                     } else {
                         // Execution returned but marked as failed
@@ -367,7 +453,12 @@ impl SelfCorrection {
     }
 
     /// Try to execute the action using the skill executor
-    async fn try_execute(&self, subtask: &Subtask) -> anyhow::Result<TaskResult> {
+    async fn try_execute(
+        &self,
+        subtask: &Subtask,
+        strategy: AttemptStrategy,
+        last_error: Option<&str>,
+    ) -> anyhow::Result<TaskResult> {
         use super::skill_executor::SkillExecutor;
         
         let executor = SkillExecutor::new();
@@ -376,7 +467,35 @@ impl SelfCorrection {
         // Convert subtask action_type to skill execution
         let skill_result = match &subtask.action_type {
             super::ActionType::Computer { action, params } => {
-                executor.execute_computer_action(action, params).await
+                match click_label(params).filter(|_| strategy == AttemptStrategy::ClickByText) {
+                    Some(label) => {
+                        println!("[correction] Clicking '{}' found with OCR instead of the planned coordinate", label);
+                        executor.click_text(&label).await
+                    }
+                    None => executor.execute_computer_action(action, params).await,
+                }
+            }
+            super::ActionType::Bash { command } if strategy == AttemptStrategy::AlternativeCommand => {
+                match self.alternative_command(&subtask.description, command, last_error).await {
+                    Ok(alternative) => {
+                        println!("[correction] Trying alternative command: {}", alternative);
+                        if let Err(reason) = self.confirm_alternative(&alternative).await {
+                            return Ok(super::TaskResult {
+                                success: false,
+                                output: String::new(),
+                                screenshot: None,
+                                error: Some(format!("Alternative command `{}` not run: {}", alternative, reason)),
+                                duration_ms: 0,
+                                learnings: vec![],
+                            });
+                        }
+                        executor.execute_bash(&alternative).await
+                    }
+                    Err(e) => {
+                        println!("[correction] No alternative command ({}), re-running the original", e);
+                        executor.execute_bash(command).await
+                    }
+                }
             }
             super::ActionType::Bash { command } => {
                 executor.execute_bash(command).await
@@ -448,6 +567,70 @@ impl SelfCorrection {
         }
     }
 
+    /// Hold a model-written command for the user the same way the agent loop holds its bash
+    /// calls; with no way to ask, a command that needs confirmation is refused
+    async fn confirm_alternative(&self, command: &str) -> Result<(), String> {
+        let Some(reason) = crate::confirm::destructive_bash_reason(command) else {
+            return Ok(());
+        };
+        if !crate::confirm::ConfirmSettings::current().bash {
+            return Ok(());
+        }
+        let Some(confirmer) = &self.confirmer else {
+            return Err(format!("it {} and there's no one to confirm it", reason));
+        };
+        let approved = crate::confirm::request_confirmation(
+            &confirmer.app_handle,
+            &confirmer.running,
+            crate::confirm::ConfirmationRequest {
+                tool: "bash".to_string(),
+                summary: command.to_string(),
+                reason: reason.to_string(),
+                screenshot: None,
+            },
+        )
+        .await;
+        if approved {
+            Ok(())
+        } else {
+            Err(format!("the user declined it ({})", reason))
+        }
+    }
+
+    /// Ask the model for a different shell command that reaches the same goal
+    async fn alternative_command(&self, goal: &str, failed: &str, error: Option<&str>) -> anyhow::Result<String> {
+        use crate::api::{AnthropicClient, ContentBlock, Message};
+
+        let api_key = crate::permissions::load_api_key_for_service("anthropic")
+            .ok_or_else(|| anyhow::anyhow!("no API key set"))?;
+        let client = AnthropicClient::new(api_key, super::agent_swarm::DEFAULT_LIGHT_MODEL.to_string());
+        let prompt = format!(
+            "Goal: {}\n\nThis shell command failed:\n{}\n\nError:\n{}\n\nReply with one different shell command that reaches the goal another way. Only the command, no explanation.",
+            goal,
+            failed,
+            error.unwrap_or("(no error output)")
+        );
+        let message = Message {
+            role: "user".to_string(),
+            content: vec![ContentBlock::Text { text: prompt }],
+        };
+        let result = client.complete(None, vec![message], None).await?;
+        let reply: String = result
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let command = parse_command(&reply).ok_or_else(|| anyhow::anyhow!("model gave no command"))?;
+        if command == failed.trim() {
+            anyhow::bail!("model suggested the same command");
+        }
+        Ok(command)
+    }
+
     /// Attempt to correct a failure
     async fn attempt_correction(
         &self,
@@ -639,4 +822,43 @@ impl Default for SelfCorrection {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer(action: &str) -> ActionType {
+        ActionType::Computer { action: action.to_string(), params: serde_json::json!({}) }
+    }
+
+    #[test]
+    fn retries_climb_the_ladder_then_stay_on_top() {
+        let bash = ActionType::Bash { command: "ls".to_string() };
+        assert_eq!(strategy_for(&bash, 1), AttemptStrategy::AsPlanned);
+        assert_eq!(strategy_for(&bash, 2), AttemptStrategy::AlternativeCommand);
+        assert_eq!(strategy_for(&bash, 5), AttemptStrategy::AlternativeCommand);
+
+        assert_eq!(strategy_for(&computer("left_click"), 2), AttemptStrategy::ClickByText);
+        // only clicks have something to fall back to
+        assert_eq!(strategy_for(&computer("type"), 3), AttemptStrategy::AsPlanned);
+        assert_eq!(strategy_for(&ActionType::Wait { duration_ms: 10 }, 2), AttemptStrategy::AsPlanned);
+        let browser = ActionType::Browser { tool: "page_action".to_string(), params: serde_json::json!({}) };
+        assert_eq!(strategy_for(&browser, 2), AttemptStrategy::AsPlanned);
+    }
+
+    #[test]
+    fn click_label_comes_from_the_plan() {
+        assert_eq!(click_label(&serde_json::json!({ "coordinate": [1, 2], "text": " Submit " })).as_deref(), Some("Submit"));
+        assert_eq!(click_label(&serde_json::json!({ "target": "Save" })).as_deref(), Some("Save"));
+        assert_eq!(click_label(&serde_json::json!({ "coordinate": [1, 2] })), None);
+    }
+
+    #[test]
+    fn command_is_taken_from_the_reply() {
+        assert_eq!(parse_command("```bash\nls -la ~/Downloads\n```").as_deref(), Some("ls -la ~/Downloads"));
+        assert_eq!(parse_command("$ open -a Safari").as_deref(), Some("open -a Safari"));
+        assert_eq!(parse_command("`pwd`").as_deref(), Some("pwd"));
+        assert_eq!(parse_command("  \n"), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! Embeddings - remote vector embeddings for semantic memory search
+//!
+//! The embedding backend is chosen separately from the chat model, so a user
+//! on a local LLM can still use cloud embeddings and the other way round.
+//! Every backend speaks the OpenAI-compatible `/v1/embeddings` API; Anthropic
+//! has no embeddings endpoint, so its recommended provider (Voyage) stands in.
+//! With the hashed backend, or no key, `MemorySystem` uses local trigram hashing.
+
+use serde::{Deserialize, Serialize};
+
+/// Model tag for the local hashed embedding (not a real model)
+pub const LOCAL_EMBEDDING_MODEL: &str = "trigram-hash-256";
+
+/// env vars (saved in .env): the backend, and optional model / endpoint overrides for it
+pub const EMBEDDING_BACKEND_ENV: &str = "HEYWORK_EMBEDDING_BACKEND";
+pub const EMBEDDING_MODEL_ENV: &str = "HEYWORK_EMBEDDING_MODEL";
+pub const EMBEDDING_URL_ENV: &str = "HEYWORK_EMBEDDING_URL";
+
+/// Where memory embeddings come from, independent of the chat provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingBackend {
+    /// Local trigram hashing - offline, keyword-level only
+    Hashed,
+    OpenAi,
+    /// Voyage AI, the embeddings provider Anthropic recommends
+    Voyage,
+    /// An OpenAI-compatible server on this machine, e.g. llama.cpp's
+    /// `llama-server --embedding` with a gguf model, or Ollama
+    Local,
+}
+
+impl EmbeddingBackend {
+    pub const ALL: [EmbeddingBackend; 4] = [Self::Hashed, Self::OpenAi, Self::Voyage, Self::Local];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "hashed" => Some(Self::Hashed),
+            "openai" => Some(Self::OpenAi),
+            "voyage" | "anthropic" => Some(Self::Voyage),
+            "local" | "gguf" | "local-gguf" => Some(Self::Local),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hashed => "hashed",
+            Self::OpenAi => "openai",
+            Self::Voyage => "voyage",
+            Self::Local => "local",
+        }
+    }
+
+    /// The saved backend. Before the setting existed an OpenAI key alone switched
+    /// embeddings on, so that's still the default when nothing is saved
+    pub fn current() -> Self {
+        std::env::var(EMBEDDING_BACKEND_ENV)
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_else(|| if api_key("OPENAI_API_KEY").is_some() { Self::OpenAi } else { Self::Hashed })
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Self::Hashed => LOCAL_EMBEDDING_MODEL,
+            Self::OpenAi => "text-embedding-3-small",
+            Self::Voyage => "voyage-3-lite",
+            Self::Local => "nomic-embed-text",
+        }
+    }
+
+    fn default_url(&self) -> &'static str {
+        match self {
+            Self::Hashed => "",
+            Self::OpenAi => "https://api.openai.com/v1/embeddings",
+            Self::Voyage => "https://api.voyageai.com/v1/embeddings",
+            Self::Local => "http://127.0.0.1:8080/v1/embeddings",
+        }
+    }
+
+    /// env var holding the backend's API key; a local server needs none
+    pub fn key_env(&self) -> Option<&'static str> {
+        match self {
+            Self::OpenAi => Some("OPENAI_API_KEY"),
+            Self::Voyage => Some("VOYAGE_API_KEY"),
+            Self::Hashed | Self::Local => None,
+        }
+    }
+}
+
+/// The tag stored with every embedding. Vectors are only compared when their tags
+/// match, so switching backend or model never mixes vector spaces
+pub fn embedding_tag(backend: EmbeddingBackend, model: &str) -> String {
+    match backend {
+        EmbeddingBackend::Hashed => LOCAL_EMBEDDING_MODEL.to_string(),
+        _ => format!("{}/{}", backend.as_str(), model),
+    }
+}
+
+/// Tag for an embedding stored before tags named the backend - those could only
+/// come from OpenAI or the local hashing
+pub fn normalize_tag(stored: &str) -> String {
+    if stored == LOCAL_EMBEDDING_MODEL || stored.contains('/') {
+        stored.to_string()
+    } else {
+        embedding_tag(EmbeddingBackend::OpenAi, stored)
+    }
+}
+
+/// What the settings panel shows and saves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingSettings {
+    pub backend: EmbeddingBackend,
+    pub model: String,
+    pub url: String,
+    /// whether the backend has the key it needs
+    pub has_key: bool,
+}
+
+impl EmbeddingSettings {
+    pub fn current() -> Self {
+        let backend = EmbeddingBackend::current();
+        Self {
+            backend,
+            model: api_key(EMBEDDING_MODEL_ENV).unwrap_or_else(|| backend.default_model().to_string()),
+            url: api_key(EMBEDDING_URL_ENV).unwrap_or_else(|| backend.default_url().to_string()),
+            has_key: backend.key_env().is_none_or(|name| api_key(name).is_some()),
+        }
+    }
+
+    /// Tag new embeddings get; the hashed tag when the backend can't be used
+    pub fn active_tag(&self) -> String {
+        match (self.backend, self.has_key) {
+            (EmbeddingBackend::Hashed, _) | (_, false) => LOCAL_EMBEDDING_MODEL.to_string(),
+            (backend, true) => embedding_tag(backend, &self.model),
+        }
+    }
+}
+
+// a set, non-blank env var - a blank model or url override means "use the backend's default"
+fn api_key(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|k| !k.trim().is_empty())
+}
+
+/// Thin client for an OpenAI-compatible embeddings endpoint
+pub struct EmbeddingClient {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    model: String,
+    url: String,
+    tag: String,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingClient {
+    /// Build for the configured backend; None for hashed or when its key is missing
+    pub fn from_env() -> Option<Self> {
+        let settings = EmbeddingSettings::current();
+        if settings.backend == EmbeddingBackend::Hashed || !settings.has_key {
+            return None;
+        }
+        let tag = settings.active_tag();
+
+        Some(Self {
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            api_key: settings.backend.key_env().and_then(api_key),
+            model: settings.model,
+            url: settings.url,
+            tag,
+        })
+    }
+
+    /// Backend and model, as stored alongside each embedding
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Embed a single text
+    pub async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        let mut request = self.client.post(&self.url);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .json(&serde_json::json!({
+                "model": self.model,
+                "input": text,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Embedding API error {}: {}", status, body));
+        }
+
+        let parsed: EmbeddingResponse = response.json().await?;
+        parsed
+            .data
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .ok_or_else(|| anyhow::anyhow!("Embedding API returned no vectors"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_separate_backends_and_read_old_rows() {
+        assert_eq!(EmbeddingBackend::parse("Anthropic"), Some(EmbeddingBackend::Voyage));
+        assert_eq!(EmbeddingBackend::parse("local-gguf"), Some(EmbeddingBackend::Local));
+        assert_eq!(EmbeddingBackend::parse("cohere"), None);
+        for backend in EmbeddingBackend::ALL {
+            assert_eq!(EmbeddingBackend::parse(backend.as_str()), Some(backend));
+        }
+
+        let openai = embedding_tag(EmbeddingBackend::OpenAi, "text-embedding-3-small");
+        assert_ne!(openai, embedding_tag(EmbeddingBackend::Local, "text-embedding-3-small"));
+        assert_eq!(embedding_tag(EmbeddingBackend::Hashed, "anything"), LOCAL_EMBEDDING_MODEL);
+        // rows written before tags named the backend
+        assert_eq!(normalize_tag("text-embedding-3-small"), openai);
+        assert_eq!(normalize_tag(LOCAL_EMBEDDING_MODEL), LOCAL_EMBEDDING_MODEL);
+        assert_eq!(normalize_tag(&openai), openai);
+    }
+}
@@ -315,7 +315,7 @@ impl CognitiveAgent {
 
     /// Execute a bash command
     async fn execute_bash_command(&self, command: &str) -> anyhow::Result<TaskResult> {
-        let bash = self.bash.lock().await;
+        let mut bash = self.bash.lock().await;
         let result = bash.execute(command);
         
         match result {
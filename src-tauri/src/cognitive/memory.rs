@@ -4,6 +4,7 @@
 //! relevant memories using vector embeddings for semantic search.
 //! Persisted to SQLite for durability across sessions.
 
+use super::embeddings::{normalize_tag, EmbeddingClient, EmbeddingSettings, LOCAL_EMBEDDING_MODEL};
 use super::Memory;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -13,6 +14,24 @@ use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
 use std::sync::Mutex;
 
+/// Half-life for the recency decay on memory confidence
+const DECAY_HALF_LIFE_DAYS: f32 = 45.0;
+/// Learning rate for the success-rate exponential moving average
+const SUCCESS_EMA_ALPHA: f32 = 0.3;
+/// Default pruning policy: drop memories below this confidence...
+pub const PRUNE_MIN_CONFIDENCE: f32 = 0.25;
+/// ...that haven't been used in this many days
+pub const PRUNE_UNUSED_DAYS: i64 = 60;
+
+/// Recency-decayed confidence in a memory (0-1).
+/// Success rate dominates, usage adds a little trust, and everything
+/// decays exponentially with age so stale patterns fade out.
+pub fn memory_confidence(success_rate: f32, usage_count: u32, age_days: f32) -> f32 {
+    let recency = 0.5f32.powf(age_days.max(0.0) / DECAY_HALF_LIFE_DAYS);
+    let usage = ((1.0 + usage_count as f32).ln() / 51f32.ln()).min(1.0);
+    (0.7 * success_rate + 0.3 * usage) * (0.25 + 0.75 * recency)
+}
+
 /// Vector embedding for semantic search
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Embedding {
@@ -31,6 +50,8 @@ pub struct MemorySystem {
     task_patterns: HashMap<String, Vec<String>>, // pattern -> memory_ids
     /// Simple embedding cache (in production, use a proper vector DB)
     embedding_cache: HashMap<String, Embedding>,
+    /// When each memory was last retrieved and used (memory_id -> time)
+    last_used: HashMap<String, DateTime<Utc>>,
     /// Database connection
     db: Option<Mutex<Connection>>,
 }
@@ -53,6 +74,7 @@ impl MemorySystem {
             user_preferences: HashMap::new(),
             task_patterns: HashMap::new(),
             embedding_cache: HashMap::new(),
+            last_used: HashMap::new(),
             db: None,
         }
     }
@@ -100,14 +122,30 @@ impl MemorySystem {
                 context_json TEXT NOT NULL,
                 updated_at TEXT NOT NULL
             );
+
+            CREATE TABLE IF NOT EXISTS embedding_cache (
+                model TEXT NOT NULL,
+                text TEXT NOT NULL,
+                vector_json TEXT NOT NULL,
+                PRIMARY KEY (model, text)
+            );
             "
         )?;
 
+        // migration: record which model produced each stored embedding
+        conn.execute("ALTER TABLE memories ADD COLUMN embedding_model TEXT", []).ok();
+        conn.execute("ALTER TABLE memories ADD COLUMN last_used_at TEXT", []).ok();
+
         self.db = Some(Mutex::new(conn));
         
         // Load existing memories
         self.load_memories()?;
         self.load_preferences()?;
+
+        // drop stale, low-confidence memories so they stop polluting retrieval
+        if let Err(e) = self.prune_memories(PRUNE_MIN_CONFIDENCE, PRUNE_UNUSED_DAYS) {
+            println!("[memory] Prune failed: {}", e);
+        }
         
         println!("[memory] Initialized with {} memories and {} preferences", 
             self.memories.len(), self.user_preferences.len());
@@ -142,11 +180,11 @@ impl MemorySystem {
     /// Load memories from database
     fn load_memories(&mut self) -> anyhow::Result<()> {
         // Collect memories first to avoid borrow issues
-        let mut loaded_memories: Vec<Memory> = Vec::new();
+        let mut loaded_memories: Vec<(Memory, Option<String>, Option<String>)> = Vec::new();
         
         self.with_db(|conn| {
             let mut stmt = conn.prepare(
-                "SELECT id, task_pattern, actions_json, success_rate, usage_count, created_at, embedding_json FROM memories"
+                "SELECT id, task_pattern, actions_json, success_rate, usage_count, created_at, embedding_json, embedding_model, last_used_at FROM memories"
             )?;
 
             let rows = stmt.query_map([], |row| {
@@ -157,12 +195,14 @@ impl MemorySystem {
                 let usage_count: i64 = row.get(4)?;
                 let created_at: String = row.get(5)?;
                 let embedding_json: Option<String> = row.get(6)?;
+                let embedding_model: Option<String> = row.get(7)?;
+                let last_used_at: Option<String> = row.get(8)?;
 
                 let actions: Vec<String> = serde_json::from_str(&actions_json).unwrap_or_default();
                 let embedding: Option<Vec<f32>> = embedding_json
                     .and_then(|s| serde_json::from_str(&s).ok());
 
-                Ok(Memory {
+                Ok((Memory {
                     id,
                     task_pattern,
                     actions,
@@ -170,12 +210,12 @@ impl MemorySystem {
                     usage_count: usage_count as u32,
                     created_at: created_at.parse().unwrap_or_else(|_| Utc::now()),
                     embedding,
-                })
+                }, embedding_model, last_used_at))
             })?;
 
             for row in rows {
-                if let Ok(memory) = row {
-                    loaded_memories.push(memory);
+                if let Ok(loaded) = row {
+                    loaded_memories.push(loaded);
                 }
             }
 
@@ -183,7 +223,11 @@ impl MemorySystem {
         })?;
         
         // Now rebuild indexes and embedding cache after with_db returns
-        for memory in loaded_memories {
+        for (memory, embedding_model, last_used_at) in loaded_memories {
+            let last_used = last_used_at
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(memory.created_at);
+            self.last_used.insert(memory.id.clone(), last_used);
             let keywords = self.extract_keywords(&memory.task_pattern);
             for keyword in keywords {
                 self.task_patterns
@@ -195,7 +239,7 @@ impl MemorySystem {
             let embedding = if let Some(ref vec) = memory.embedding {
                 Embedding {
                     vector: vec.clone(),
-                    model: "trigram-hash-256".to_string(),
+                    model: embedding_model.map_or_else(|| LOCAL_EMBEDDING_MODEL.to_string(), |m| normalize_tag(&m)),
                     dimensions: vec.len(),
                 }
             } else {
@@ -241,8 +285,8 @@ impl MemorySystem {
     pub async fn store_execution(&mut self, record: ExecutionRecord) -> anyhow::Result<Memory> {
         let memory_id = Uuid::new_v4().to_string();
         
-        // Generate simple keyword-based "embedding" (in production, use OpenAI/Claude embeddings)
-        let embedding = self.generate_simple_embedding(&record.task_description);
+        // Remote embedding when configured, local trigram hashing otherwise
+        let embedding = self.embed_text(&record.task_description).await;
         
         let memory = Memory {
             id: memory_id.clone(),
@@ -256,6 +300,7 @@ impl MemorySystem {
         
         // Store embedding
         self.embedding_cache.insert(memory_id.clone(), embedding);
+        self.last_used.insert(memory_id.clone(), memory.created_at);
         
         // Index by keywords
         let keywords = self.extract_keywords(&record.task_description);
@@ -274,14 +319,146 @@ impl MemorySystem {
         Ok(memory)
     }
 
-    /// Search for relevant memories using hybrid keyword + embedding similarity
+    /// Embed text with the configured backend, using the on-disk cache.
+    /// Falls back to the local hashed embedding if no key is set or the call fails.
+    async fn embed_text(&self, text: &str) -> Embedding {
+        let Some(client) = EmbeddingClient::from_env() else {
+            return self.generate_simple_embedding(text);
+        };
+        let model = client.tag().to_string();
+
+        if let Some(vector) = self.cached_embedding(&model, text) {
+            return Embedding { dimensions: vector.len(), vector, model };
+        }
+
+        match client.embed(text).await {
+            Ok(vector) => {
+                if let Err(e) = self.cache_embedding_on_disk(&model, text, &vector) {
+                    println!("[memory] Failed to cache embedding: {}", e);
+                }
+                Embedding { dimensions: vector.len(), vector, model }
+            }
+            Err(e) => {
+                println!("[memory] Embedding call failed, using local fallback: {}", e);
+                self.generate_simple_embedding(text)
+            }
+        }
+    }
+
+    /// First half of re-embedding every memory whose embedding came from another backend or
+    /// model, so retrieval compares like with like after the setting changes. Moves the ones
+    /// the local backend or the on-disk cache can answer and returns how many, along with the
+    /// (id, task pattern) pairs still needing a remote call - make those with
+    /// `embed_stale_remotely` without holding the memory system, then `store_reembedded`
+    pub async fn prepare_reembed(&mut self) -> anyhow::Result<(usize, Vec<(String, String)>)> {
+        let target = EmbeddingSettings::current().active_tag();
+        let stale: Vec<(String, String)> = self
+            .memories
+            .iter()
+            .filter(|m| self.embedding_cache.get(&m.id).is_none_or(|e| e.model != target))
+            .map(|m| (m.id.clone(), m.task_pattern.clone()))
+            .collect();
+
+        let mut ready = Vec::new();
+        let mut pending = Vec::new();
+        for (id, pattern) in stale {
+            if target == LOCAL_EMBEDDING_MODEL {
+                ready.push((id, self.generate_simple_embedding(&pattern)));
+            } else if let Some(vector) = self.cached_embedding(&target, &pattern) {
+                ready.push((id, Embedding { dimensions: vector.len(), vector, model: target.clone() }));
+            } else {
+                pending.push((id, pattern));
+            }
+        }
+        let moved = self.store_reembedded(ready).await?;
+        Ok((moved, pending))
+    }
+
+    /// Second half of re-embedding: store the new embeddings, skipping memories deleted since
+    /// and vectors from a backend that is no longer the configured one. Returns how many moved
+    pub async fn store_reembedded(&mut self, embedded: Vec<(String, Embedding)>) -> anyhow::Result<usize> {
+        let target = EmbeddingSettings::current().active_tag();
+        let mut moved = 0;
+        for (id, embedding) in embedded {
+            if embedding.model != target {
+                continue;
+            }
+            let Some(memory) = self.memories.iter_mut().find(|m| m.id == id) else {
+                continue;
+            };
+            memory.embedding = Some(embedding.vector.clone());
+            let memory = memory.clone();
+            if embedding.model != LOCAL_EMBEDDING_MODEL {
+                if let Err(e) = self.cache_embedding_on_disk(&embedding.model, &memory.task_pattern, &embedding.vector) {
+                    println!("[memory] Failed to cache embedding: {}", e);
+                }
+            }
+            self.embedding_cache.insert(id, embedding);
+            self.persist_memory(&memory).await?;
+            moved += 1;
+        }
+        if moved > 0 {
+            println!("[memory] Re-embedded {} memories with {}", moved, target);
+        }
+        Ok(moved)
+    }
+
+    fn cached_embedding(&self, model: &str, text: &str) -> Option<Vec<f32>> {
+        self.with_db(|conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT vector_json FROM embedding_cache WHERE model = ?1 AND text = ?2",
+                    params![model, text],
+                    |row| row.get(0),
+                )
+                .ok();
+            Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+        })
+        .ok()
+        .flatten()
+    }
+
+    fn cache_embedding_on_disk(&self, model: &str, text: &str, vector: &[f32]) -> anyhow::Result<()> {
+        self.with_db(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO embedding_cache (model, text, vector_json) VALUES (?1, ?2, ?3)",
+                params![model, text, serde_json::to_string(vector)?],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Search for relevant memories.
+    /// Ranks by cosine similarity when the memory and query share a remote
+    /// embedding model; otherwise uses hybrid keyword + hashed-embedding scoring.
     pub async fn search_relevant(&self, query: &str) -> anyhow::Result<Vec<Memory>> {
         let query_keywords = self.extract_keywords(query);
         let query_embedding = self.generate_simple_embedding(query);
         let query_lower = query.to_lowercase();
         let mut scored_memories: Vec<(Memory, f32)> = Vec::new();
+
+        // only pay for a remote query embedding if some memory can be compared against it
+        let has_remote = self.embedding_cache.values().any(|e| e.model != LOCAL_EMBEDDING_MODEL);
+        let remote_query = if has_remote {
+            Some(self.embed_text(query).await).filter(|e| e.model != LOCAL_EMBEDDING_MODEL)
+        } else {
+            None
+        };
         
         for memory in &self.memories {
+            // semantic path: same vector space, rank mostly by meaning
+            if let (Some(q), Some(m)) = (&remote_query, self.embedding_cache.get(&memory.id)) {
+                if q.model == m.model {
+                    let similarity = self.cosine_similarity(&q.vector, &m.vector);
+                    if similarity < 0.3 {
+                        continue;
+                    }
+                    let score = similarity * 0.75 + self.confidence(memory) * 0.25;
+                    scored_memories.push((memory.clone(), score));
+                    continue;
+                }
+            }
+
             let mut score = 0.0;
             
             // 1. Keyword overlap (0-0.3)
@@ -295,8 +472,9 @@ impl MemorySystem {
                 score += (overlap / query_keywords.len() as f32) * 0.3;
             }
             
-            // 2. Embedding cosine similarity (0-0.3)
+            // 2. Embedding cosine similarity (0-0.3), in the local hashed space
             let memory_embedding = self.embedding_cache.get(&memory.id)
+                .filter(|e| e.model == LOCAL_EMBEDDING_MODEL)
                 .cloned()
                 .unwrap_or_else(|| self.generate_simple_embedding(&memory.task_pattern));
             let cosine_sim = self.cosine_similarity(&query_embedding.vector, &memory_embedding.vector);
@@ -316,17 +494,8 @@ impl MemorySystem {
                 }
             }
             
-            // 4. Success rate weighting (0-0.15)
-            score += memory.success_rate * 0.15;
-            
-            // 5. Recency bonus (0-0.05)
-            let age_days = (Utc::now() - memory.created_at).num_days() as f32;
-            let recency_score = (1.0 / (1.0 + age_days / 30.0)) * 0.05;
-            score += recency_score;
-            
-            // 6. Usage frequency bonus (0-0.05)
-            let usage_score = (memory.usage_count as f32 / 50.0).min(0.05);
-            score += usage_score;
+            // 4. Recency-decayed confidence: success, usage and age (0-0.25)
+            score += self.confidence(memory) * 0.25;
             
             if score > 0.15 { // Lower threshold to catch more potential matches
                 scored_memories.push((memory.clone(), score));
@@ -385,23 +554,71 @@ impl MemorySystem {
             .collect()
     }
 
-    /// Update memory success rate after reuse
+    /// Update memory success rate after a task that used it completes
     pub async fn update_memory_success(&mut self, memory_id: &str, success: bool) -> anyhow::Result<()> {
-        if let Some(memory) = self.memories.iter_mut().find(|m| m.id == memory_id) {
-            memory.usage_count += 1;
-            
-            // Update success rate with exponential moving average
-            let alpha = 0.3; // Learning rate
-            let new_success = if success { 1.0 } else { 0.0 };
-            memory.success_rate = memory.success_rate * (1.0 - alpha) + new_success * alpha;
-            
-            println!(
-                "[memory] Updated memory {}: success_rate={:.2}, uses={}",
-                memory_id, memory.success_rate, memory.usage_count
-            );
+        let Some(memory) = self.memories.iter_mut().find(|m| m.id == memory_id) else {
+            return Ok(());
+        };
+        memory.usage_count += 1;
+
+        // exponential moving average so recent outcomes outweigh old ones
+        let new_success = if success { 1.0 } else { 0.0 };
+        memory.success_rate = memory.success_rate * (1.0 - SUCCESS_EMA_ALPHA) + new_success * SUCCESS_EMA_ALPHA;
+
+        println!(
+            "[memory] Updated memory {}: success_rate={:.2}, uses={}",
+            memory_id, memory.success_rate, memory.usage_count
+        );
+
+        let memory = memory.clone();
+        self.last_used.insert(memory.id.clone(), Utc::now());
+        self.persist_memory(&memory).await
+    }
+
+    /// Current recency-decayed confidence for a memory
+    fn confidence(&self, memory: &Memory) -> f32 {
+        let age_days = (Utc::now() - memory.created_at).num_hours() as f32 / 24.0;
+        memory_confidence(memory.success_rate, memory.usage_count, age_days)
+    }
+
+    /// Drop memories whose confidence fell below `min_confidence` and that
+    /// haven't been used in `unused_days`. Returns how many were removed.
+    pub fn prune_memories(&mut self, min_confidence: f32, unused_days: i64) -> anyhow::Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(unused_days);
+        let doomed: Vec<String> = self
+            .memories
+            .iter()
+            .filter(|m| {
+                let last_used = self.last_used.get(&m.id).copied().unwrap_or(m.created_at);
+                last_used < cutoff && self.confidence(m) < min_confidence
+            })
+            .map(|m| m.id.clone())
+            .collect();
+
+        if doomed.is_empty() {
+            return Ok(0);
         }
-        
-        Ok(())
+
+        self.with_db(|conn| {
+            for id in &doomed {
+                conn.execute("DELETE FROM task_patterns WHERE memory_id = ?1", params![id])?;
+                conn.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+            }
+            Ok(())
+        })?;
+
+        self.memories.retain(|m| !doomed.contains(&m.id));
+        for id in &doomed {
+            self.embedding_cache.remove(id);
+            self.last_used.remove(id);
+        }
+        for ids in self.task_patterns.values_mut() {
+            ids.retain(|id| !doomed.contains(id));
+        }
+        self.task_patterns.retain(|_, ids| !ids.is_empty());
+
+        println!("[memory] Pruned {} stale memories", doomed.len());
+        Ok(doomed.len())
     }
 
     /// Extract keywords from text for indexing
@@ -484,7 +701,7 @@ impl MemorySystem {
         
         Embedding {
             vector,
-            model: "trigram-hash-256".to_string(),
+            model: LOCAL_EMBEDDING_MODEL.to_string(),
             dimensions: dim,
         }
     }
@@ -522,10 +739,14 @@ impl MemorySystem {
             let actions_json = serde_json::to_string(&memory.actions)?;
             let embedding_json = memory.embedding.as_ref()
                 .map(|e| serde_json::to_string(e).unwrap_or_default());
+            let embedding_model = self.embedding_cache.get(&memory.id)
+                .map(|e| e.model.clone());
+            let last_used_at = self.last_used.get(&memory.id)
+                .map(|t| t.to_rfc3339());
 
             conn.execute(
-                "INSERT OR REPLACE INTO memories (id, task_pattern, actions_json, success_rate, usage_count, created_at, embedding_json)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT OR REPLACE INTO memories (id, task_pattern, actions_json, success_rate, usage_count, created_at, embedding_json, embedding_model, last_used_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                 params![
                     memory.id,
                     memory.task_pattern,
@@ -534,6 +755,8 @@ impl MemorySystem {
                     memory.usage_count as i64,
                     memory.created_at.to_rfc3339(),
                     embedding_json,
+                    embedding_model,
+                    last_used_at,
                 ],
             )?;
             Ok(())
@@ -621,27 +844,87 @@ impl MemorySystem {
 
     /// Get statistics about the memory system
     pub fn get_stats(&self) -> MemoryStats {
+        let count = self.memories.len().max(1) as f32;
+        let cutoff = Utc::now() - chrono::Duration::days(PRUNE_UNUSED_DAYS);
         MemoryStats {
             total_memories: self.memories.len(),
             total_preferences: self.user_preferences.len(),
-            avg_success_rate: if self.memories.is_empty() {
-                0.0
-            } else {
-                self.memories.iter().map(|m| m.success_rate).sum::<f32>() / self.memories.len() as f32
+            avg_success_rate: self.memories.iter().map(|m| m.success_rate).sum::<f32>() / count,
+            avg_confidence: self.memories.iter().map(|m| self.confidence(m)).sum::<f32>() / count,
+            prunable_memories: self
+                .memories
+                .iter()
+                .filter(|m| {
+                    let last_used = self.last_used.get(&m.id).copied().unwrap_or(m.created_at);
+                    last_used < cutoff && self.confidence(m) < PRUNE_MIN_CONFIDENCE
+                })
+                .count(),
+            embedded_memories: self
+                .embedding_cache
+                .values()
+                .filter(|e| e.model != LOCAL_EMBEDDING_MODEL)
+                .count(),
+            stale_embeddings: {
+                let target = EmbeddingSettings::current().active_tag();
+                self.embedding_cache.values().filter(|e| e.model != target).count()
             },
+            oldest_memory: self.memories.iter().map(|m| m.created_at).min(),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryStats {
     pub total_memories: usize,
     pub total_preferences: usize,
     pub avg_success_rate: f32,
+    pub avg_confidence: f32,
+    /// memories that the next prune would drop
+    pub prunable_memories: usize,
+    /// memories with a remote (semantic) embedding
+    pub embedded_memories: usize,
+    /// memories embedded by a backend other than the configured one - re-embedding moves them
+    pub stale_embeddings: usize,
+    pub oldest_memory: Option<DateTime<Utc>>,
+}
+
+/// Embed the patterns `prepare_reembed` left pending with the configured remote backend.
+/// Touches nothing in the memory system, so it runs without its lock; memories whose call
+/// fails are left out and tried again next time
+pub async fn embed_stale_remotely(pending: &[(String, String)]) -> Vec<(String, Embedding)> {
+    let Some(client) = EmbeddingClient::from_env() else {
+        return Vec::new();
+    };
+    let mut embedded = Vec::new();
+    for (id, pattern) in pending {
+        match client.embed(pattern).await {
+            Ok(vector) => embedded.push((
+                id.clone(),
+                Embedding { dimensions: vector.len(), vector, model: client.tag().to_string() },
+            )),
+            Err(e) => println!("[memory] Embedding call failed for memory {}: {}", id, e),
+        }
+    }
+    embedded
 }
 
 impl Default for MemorySystem {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_confidence_decays_with_age() {
+        let fresh = memory_confidence(0.9, 5, 0.0);
+        let half = memory_confidence(0.9, 5, DECAY_HALF_LIFE_DAYS);
+        let ancient = memory_confidence(0.9, 5, 365.0);
+        assert!(fresh > half && half > ancient);
+        // stale memories fade but never vanish entirely
+        assert!(ancient > 0.0);
+        assert!(memory_confidence(0.0, 0, 0.0) < memory_confidence(1.0, 0, 0.0));
+    }
+}
@@ -9,6 +9,7 @@
 
 pub mod planner;
 pub mod memory;
+pub mod embeddings;
 pub mod skills;
 pub mod reasoner;
 pub mod context;
@@ -169,6 +170,9 @@ impl CognitiveEngine {
         if let Err(e) = engine.memory.init() {
             println!("[cognitive] Warning: Failed to initialize memory: {}", e);
         }
+        if let Err(e) = engine.skills.load_ratings() {
+            println!("[cognitive] Warning: Failed to load skill ratings: {}", e);
+        }
         
         engine
     }
@@ -182,6 +186,16 @@ impl CognitiveEngine {
 
     /// Process a high-level user request
     pub async fn process_request(&mut self, request: &str) -> anyhow::Result<Task> {
+        let task = self.plan_request(request).await?;
+        
+        // 6. Store in memory
+        self.memory.store_task_intent(request, &task).await?;
+        
+        Ok(task)
+    }
+
+    /// Analyze and plan a request without recording it (used for dry-run previews)
+    pub async fn plan_request(&mut self, request: &str) -> anyhow::Result<Task> {
         // 1. Analyze the request with reasoning
         let analysis = self.reasoner.analyze_request(request).await?;
         
@@ -200,12 +214,7 @@ impl CognitiveEngine {
         
         // 5. Plan the task
         let request_analysis = planner::RequestAnalysis::from_task_analysis(&analysis);
-        let task = self.planner.create_plan(request, &request_analysis, &context).await?;
-        
-        // 6. Store in memory
-        self.memory.store_task_intent(request, &task).await?;
-        
-        Ok(task)
+        self.planner.create_plan(request, &request_analysis, &context).await
     }
 
     /// Execute the next ready subtask
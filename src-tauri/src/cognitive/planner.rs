@@ -201,13 +201,16 @@ impl Planner {
         let task_id = Uuid::new_v4().to_string();
         
         // Try to match a template first
-        let subtasks = if let Some(template) = self.match_template(request) {
+        let mut subtasks = if let Some(template) = self.match_template(request) {
             self.generate_from_template(&template, request, analysis)
         } else {
             // Use AI-powered planning for novel tasks
             self.ai_powered_planning(request, analysis, context).await?
         };
 
+        // A cyclic plan would never have a ready subtask - fail loudly instead of deadlocking
+        validate_dependencies(&mut subtasks)?;
+
         let task = Task {
             id: task_id,
             description: request.to_string(),
@@ -267,15 +270,21 @@ impl Planner {
             param_map.insert("app", app);
         }
         
+        // Ids up front so template dependency indices resolve to real subtasks
+        let plan_id = Uuid::new_v4();
+        let ids: Vec<String> = (0..template.subtask_generators.len())
+            .map(|idx| format!("{}-{}", plan_id, idx))
+            .collect();
+        
         for (idx, generator) in template.subtask_generators.iter().enumerate() {
             let description = self.fill_template(&generator.description_template, &param_map);
             let action = self.action_from_template(&generator.action_type, &param_map);
             
             let subtask = Subtask {
-                id: format!("{}-{}", Uuid::new_v4(), idx),
+                id: ids[idx].clone(),
                 description,
                 action_type: action,
-                dependencies: generator.dependencies.iter().map(|i| format!("{}-{}", Uuid::new_v4(), i)).collect(),
+                dependencies: generator.dependencies.iter().filter_map(|&i| ids.get(i).cloned()).collect(),
                 status: SubtaskStatus::Pending,
                 retry_count: 0,
                 max_retries: 3,
@@ -631,6 +640,81 @@ impl Default for Planner {
     }
 }
 
+/// Find a dependency cycle in a graph given as `deps[i]` = indices node `i` depends on.
+/// Returns the cycle as a path where each node depends on the next and the last depends on the first.
+pub(crate) fn find_dependency_cycle(deps: &[Vec<usize>]) -> Option<Vec<usize>> {
+    fn visit(node: usize, deps: &[Vec<usize>], state: &mut [u8], stack: &mut Vec<usize>) -> Option<Vec<usize>> {
+        // 0 = unvisited, 1 = on the current path, 2 = done
+        state[node] = 1;
+        stack.push(node);
+        for &dep in &deps[node] {
+            match state[dep] {
+                1 => {
+                    let start = stack.iter().position(|&n| n == dep).unwrap_or(0);
+                    return Some(stack[start..].to_vec());
+                }
+                0 => {
+                    if let Some(cycle) = visit(dep, deps, state, stack) {
+                        return Some(cycle);
+                    }
+                }
+                _ => {}
+            }
+        }
+        stack.pop();
+        state[node] = 2;
+        None
+    }
+
+    let mut state = vec![0u8; deps.len()];
+    let mut stack = Vec::new();
+    for node in 0..deps.len() {
+        if state[node] == 0 {
+            if let Some(cycle) = visit(node, deps, &mut state, &mut stack) {
+                return Some(cycle);
+            }
+        }
+    }
+    None
+}
+
+/// Drop dependencies on unknown subtasks and reject plans with circular dependencies
+fn validate_dependencies(subtasks: &mut [Subtask]) -> anyhow::Result<()> {
+    let index: HashMap<String, usize> = subtasks
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.id.clone(), i))
+        .collect();
+    
+    for subtask in subtasks.iter_mut() {
+        let before = subtask.dependencies.len();
+        subtask.dependencies.retain(|dep| index.contains_key(dep));
+        if subtask.dependencies.len() < before {
+            println!(
+                "[planner] Dropped {} unknown dependencies from \"{}\"",
+                before - subtask.dependencies.len(),
+                subtask.description
+            );
+        }
+    }
+    
+    let deps: Vec<Vec<usize>> = subtasks
+        .iter()
+        .map(|s| s.dependencies.iter().map(|d| index[d]).collect())
+        .collect();
+    
+    if let Some(cycle) = find_dependency_cycle(&deps) {
+        let first = &subtasks[cycle[0]].description;
+        let second = &subtasks[cycle[1 % cycle.len()]].description;
+        return Err(anyhow::anyhow!(
+            "Plan has a circular dependency between \"{}\" and \"{}\"",
+            first, second
+        ));
+    }
+    
+    Ok(())
+}
+
 /// Extract app name from a request string like "open chrome" or "launch Safari"
 fn extract_app_name(request: &str) -> String {
     let known_apps = [
@@ -692,4 +776,55 @@ fn extract_app_name(request: &str) -> String {
     }
     
     "Finder".to_string() // Default
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subtask(id: &str, deps: &[&str]) -> Subtask {
+        Subtask {
+            id: id.to_string(),
+            description: format!("step {}", id),
+            action_type: ActionType::Think { reasoning: String::new() },
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            status: SubtaskStatus::Pending,
+            retry_count: 0,
+            max_retries: 1,
+            result: None,
+        }
+    }
+
+    #[test]
+    fn cyclic_plan_is_rejected() {
+        let mut plan = vec![subtask("a", &["b"]), subtask("b", &["a"]), subtask("c", &[])];
+        let err = validate_dependencies(&mut plan).unwrap_err().to_string();
+        assert!(err.contains("circular dependency"));
+        assert!(err.contains("step a") && err.contains("step b"));
+    }
+
+    #[test]
+    fn self_dependency_is_a_cycle() {
+        let mut plan = vec![subtask("a", &["a"])];
+        assert!(validate_dependencies(&mut plan).is_err());
+    }
+
+    #[test]
+    fn acyclic_plan_keeps_known_dependencies() {
+        let mut plan = vec![
+            subtask("a", &[]),
+            subtask("b", &["a", "missing"]),
+            subtask("c", &["a", "b"]),
+        ];
+        validate_dependencies(&mut plan).unwrap();
+        assert_eq!(plan[1].dependencies, vec!["a".to_string()]);
+        assert_eq!(plan[2].dependencies.len(), 2);
+    }
+
+    #[test]
+    fn cycle_path_follows_dependency_edges() {
+        // 0 -> 1 -> 2 -> 1
+        let cycle = find_dependency_cycle(&[vec![1], vec![2], vec![1]]).unwrap();
+        assert_eq!(cycle, vec![1, 2]);
+        assert!(find_dependency_cycle(&[vec![], vec![0], vec![0, 1]]).is_none());
+    }
+}
@@ -5,6 +5,7 @@
 
 use super::{ActionTemplate, ActionType, Skill};
 use crate::computer::{ComputerAction, ComputerControl};
+use crate::ocr::ClickTarget;
 use crate::bash::BashExecutor;
 use crate::browser::BrowserClient;
 use std::collections::HashMap;
@@ -166,12 +167,25 @@ impl SkillExecutor {
         }
     }
 
+    /// Failed result for a tool the user switched off
+    fn refusal(tool: &str) -> Option<SkillExecutionResult> {
+        crate::tool_policy::ensure_allowed(tool).err().map(|error| SkillExecutionResult {
+            success: false,
+            output: String::new(),
+            screenshot: None,
+            error: Some(error),
+        })
+    }
+
     /// Execute computer control action
     pub async fn execute_computer_action(
         &self,
         action: &str,
         params: &serde_json::Value,
     ) -> anyhow::Result<SkillExecutionResult> {
+        if let Some(refused) = Self::refusal("computer") {
+            return Ok(refused);
+        }
         let computer_guard = self.computer.lock().await;
         let computer = match computer_guard.as_ref() {
             Some(c) => c,
@@ -239,7 +253,10 @@ impl SkillExecutor {
 
     /// Execute bash command
     pub async fn execute_bash(&self, command: &str) -> anyhow::Result<SkillExecutionResult> {
-        let bash = self.bash.lock().await;
+        if let Some(refused) = Self::refusal("bash") {
+            return Ok(refused);
+        }
+        let mut bash = self.bash.lock().await;
         
         match bash.execute(command) {
             Ok(output) => Ok(SkillExecutionResult {
@@ -257,8 +274,59 @@ impl SkillExecutor {
         }
     }
 
+    /// Click on-screen text found with OCR - the fallback when a coordinate click missed
+    pub async fn click_text(&self, query: &str) -> anyhow::Result<SkillExecutionResult> {
+        if let Some(refused) = Self::refusal("computer") {
+            return Ok(refused);
+        }
+        let computer_guard = self.computer.lock().await;
+        let computer = match computer_guard.as_ref() {
+            Some(c) => c,
+            None => {
+                drop(computer_guard);
+                self.init_computer().await?;
+                return Box::pin(self.click_text(query)).await;
+            }
+        };
+
+        let screen_w = computer.screen_width;
+        let screen_h = computer.screen_height;
+        drop(computer_guard);
+
+        let owned_query = query.to_string();
+        let located = tokio::task::spawn_blocking(move || {
+            let computer = ComputerControl::with_dimensions(screen_w, screen_h);
+            let capture = computer.take_screenshot_region(crate::computer::FULL_SCREEN_REGION)?;
+            computer.locate_text(&capture, &owned_query)
+        }).await
+        .map_err(|e| anyhow::anyhow!("OCR task failed: {}", e))?;
+
+        let failure = |error: String| SkillExecutionResult {
+            success: false,
+            output: String::new(),
+            screenshot: None,
+            error: Some(error),
+        };
+        match located {
+            Ok(ClickTarget::Found(found)) => {
+                let (x, y) = found.text_box.center();
+                self.execute_computer_action("left_click", &serde_json::json!({ "coordinate": [x, y] })).await
+            }
+            Ok(ClickTarget::Ambiguous(matches)) => {
+                let places: Vec<String> = matches
+                    .iter()
+                    .map(|m| format!("\"{}\" at {:?}", m.text_box.text, m.text_box.center()))
+                    .collect();
+                Ok(failure(format!("\"{}\" matches several places: {}", query, places.join(", "))))
+            }
+            Ok(ClickTarget::NotFound) => Ok(failure(format!("Text \"{}\" not found on screen", query))),
+            Err(e) => Ok(failure(format!("OCR failed: {}", e))),
+        }
+    }
+
     /// Take screenshot
     pub async fn take_screenshot(&self) -> anyhow::Result<String> {
+        crate::tool_policy::ensure_allowed("computer").map_err(anyhow::Error::msg)?;
         let computer_guard = self.computer.lock().await;
         let computer = match computer_guard.as_ref() {
             Some(c) => c,
@@ -20,6 +20,35 @@ pub struct SkillLibrary {
     app_index: HashMap<String, Vec<String>>, // app -> skill_ids
     /// Predefined skills loaded at startup
     predefined_skills: Vec<Skill>,
+    /// Prior versions of learned skills, oldest first (capped at MAX_SKILL_VERSIONS)
+    versions: HashMap<String, Vec<SkillVersion>>,
+    /// Current version number per learned skill (absent = 1)
+    current_versions: HashMap<String, u32>,
+    /// Skills on probation after an action change: skill_id -> (previous success rate, uses since change)
+    regression_watch: HashMap<String, (f32, u32)>,
+    /// Consecutive thumbs-down ratings per skill; at DEMOTE_AFTER_THUMBS_DOWN it stops matching
+    thumbs_down_streak: HashMap<String, u32>,
+}
+
+/// How many prior versions of each skill are kept
+const MAX_SKILL_VERSIONS: usize = 5;
+/// Uses after an action change before a regression can trigger auto-rollback
+const ROLLBACK_MIN_USES: u32 = 5;
+/// How far the success rate must fall below the previous version's to roll back
+const ROLLBACK_MARGIN: f32 = 0.15;
+/// Weight of the newest outcome in the success rate moving average
+const SUCCESS_RATE_ALPHA: f32 = 0.2;
+/// Thumbs-down ratings in a row before a skill is no longer auto-selected
+const DEMOTE_AFTER_THUMBS_DOWN: u32 = 3;
+
+/// Snapshot of a skill at a given version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillVersion {
+    pub version: u32,
+    pub created_at: String,
+    /// Why the snapshot was taken (e.g. "learned", "import", "rollback to v2")
+    pub reason: String,
+    pub skill: Skill,
 }
 
 /// Skill creation from successful execution
@@ -57,6 +86,10 @@ impl SkillLibrary {
             intent_index: HashMap::new(),
             app_index: HashMap::new(),
             predefined_skills: Vec::new(),
+            versions: HashMap::new(),
+            current_versions: HashMap::new(),
+            regression_watch: HashMap::new(),
+            thumbs_down_streak: HashMap::new(),
         };
         
         library.load_predefined_skills();
@@ -334,6 +367,11 @@ impl SkillLibrary {
 
     /// Calculate how well a skill matches the intent
     fn calculate_match_score(&self, skill: &Skill, keywords: &[&str], intent: &str) -> f32 {
+        // Demoted skills score below every matching threshold
+        if self.is_demoted(&skill.id) {
+            return 0.0;
+        }
+
         let mut score = 0.0;
         
         // Keyword matching
@@ -405,11 +443,6 @@ impl SkillLibrary {
         subtask: &Subtask,
         result: &TaskResult,
     ) -> anyhow::Result<()> {
-        // Check if this execution is worth learning from
-        if !result.success {
-            return Ok(());
-        }
-        
         // Check if similar skill already exists
         let exists = self.skills.iter().any(|s| {
             s.pattern.intent_keywords.iter().any(|k| {
@@ -417,9 +450,17 @@ impl SkillLibrary {
             })
         });
         
+        // Failures only count against an existing skill's success rate
+        if !result.success {
+            if exists {
+                self.update_existing_skill(subtask, result).await?;
+            }
+            return Ok(());
+        }
+        
         if exists {
             // Update existing skill
-            self.update_existing_skill(&subtask.description, result).await?;
+            self.update_existing_skill(subtask, result).await?;
         } else {
             // Create new skill candidate
             let candidate = self.create_skill_candidate(task, subtask, result).await?;
@@ -434,31 +475,211 @@ impl SkillLibrary {
         Ok(())
     }
 
-    async fn update_existing_skill(&mut self, description: &str, result: &TaskResult) -> anyhow::Result<()> {
-        for skill in &mut self.skills {
-            if skill.pattern.intent_keywords.iter().any(|k| description.to_lowercase().contains(&k.to_lowercase())) {
-                skill.total_uses += 1;
-                
-                // Update success rate
-                let alpha = 0.2;
-                let new_success = if result.success { 1.0 } else { 0.0 };
-                skill.success_rate = skill.success_rate * (1.0 - alpha) + new_success * alpha;
-                
-                // Update avg execution time
-                skill.avg_execution_time_ms = 
-                    (skill.avg_execution_time_ms * (skill.total_uses as u64 - 1) + result.duration_ms) 
-                    / skill.total_uses as u64;
-                
-                println!(
-                    "[skills] Updated skill {}: uses={}, success_rate={:.2}",
-                    skill.name, skill.total_uses, skill.success_rate
-                );
-                break;
-            }
+    async fn update_existing_skill(&mut self, subtask: &Subtask, result: &TaskResult) -> anyhow::Result<()> {
+        let description = subtask.description.to_lowercase();
+        let Some(idx) = self.skills.iter().position(|s| {
+            s.pattern.intent_keywords.iter().any(|k| description.contains(&k.to_lowercase()))
+        }) else {
+            return Ok(());
+        };
+
+        // A successful run that used an action the skill doesn't know yet refines the skill.
+        // Snapshot first so a bad refinement can be undone.
+        let new_action = serde_json::to_value(&subtask.action_type).ok();
+        let knows_action = self.skills[idx]
+            .actions
+            .iter()
+            .any(|a| serde_json::to_value(&a.action_type).ok() == new_action);
+        if result.success && !knows_action {
+            let previous = self.skills[idx].clone();
+            let version = self.snapshot_skill(&previous, "learned");
+            self.regression_watch
+                .insert(previous.id.clone(), (previous.success_rate, 0));
+            self.skills[idx].actions.push(ActionTemplate {
+                action_type: subtask.action_type.clone(),
+                condition: None,
+                fallback: None,
+            });
+            println!("[skills] Refined skill {} -> v{}", previous.name, version);
+        }
+
+        let skill = &mut self.skills[idx];
+        skill.total_uses += 1;
+
+        // Update success rate
+        skill.success_rate = blend_success_rate(skill.success_rate, result.success);
+
+        // Update avg execution time
+        skill.avg_execution_time_ms =
+            (skill.avg_execution_time_ms * (skill.total_uses as u64 - 1) + result.duration_ms)
+            / skill.total_uses as u64;
+
+        println!(
+            "[skills] Updated skill {}: uses={}, success_rate={:.2}",
+            skill.name, skill.total_uses, skill.success_rate
+        );
+
+        let skill_id = skill.id.clone();
+        let success_rate = skill.success_rate;
+        if let Some(previous_version) = self.check_regression(&skill_id, success_rate) {
+            println!(
+                "[skills] Skill {} regressed (success_rate={:.2}), rolling back to v{}",
+                skill_id, success_rate, previous_version
+            );
+            self.rollback_skill(&skill_id, previous_version).await?;
+        } else {
+            self.persist_skill(&self.skills[idx]).await?;
         }
         Ok(())
     }
 
+    /// Record the user's verdict on a skill run (thumbs up/down) in its success rate and use count.
+    /// Repeated thumbs-down demotes the skill so it stops being auto-selected; a thumbs-up restores it
+    pub async fn rate_skill_execution(&mut self, skill_id: &str, succeeded: bool) -> anyhow::Result<Skill> {
+        let skill = self
+            .apply_rating(skill_id, succeeded)
+            .ok_or_else(|| anyhow::anyhow!("Unknown skill: {}", skill_id))?;
+        println!(
+            "[skills] Rated skill {} {}: uses={}, success_rate={:.2}{}",
+            skill.name,
+            if succeeded { "up" } else { "down" },
+            skill.total_uses,
+            skill.success_rate,
+            if self.is_demoted(skill_id) { " (demoted)" } else { "" }
+        );
+
+        // the rating and demotion streak are kept for every skill; predefined skills have
+        // nothing else stored, learned ones are saved in full below
+        if let Err(e) = self.persist_rating(&skill) {
+            println!("[skills] Failed to save rating for {}: {}", skill_id, e);
+        }
+        if !self.skills.iter().any(|s| s.id == skill_id) {
+            return Ok(skill);
+        }
+        if let Some(previous_version) = self.check_regression(skill_id, skill.success_rate) {
+            println!(
+                "[skills] Skill {} regressed (success_rate={:.2}), rolling back to v{}",
+                skill_id, skill.success_rate, previous_version
+            );
+            return self.rollback_skill(skill_id, previous_version).await;
+        }
+        self.persist_skill(&skill).await?;
+        Ok(skill)
+    }
+
+    fn apply_rating(&mut self, skill_id: &str, succeeded: bool) -> Option<Skill> {
+        let skill = self
+            .skills
+            .iter_mut()
+            .chain(self.predefined_skills.iter_mut())
+            .find(|s| s.id == skill_id)?;
+        skill.total_uses += 1;
+        skill.success_rate = blend_success_rate(skill.success_rate, succeeded);
+        let skill = skill.clone();
+
+        if succeeded {
+            self.thumbs_down_streak.remove(skill_id);
+        } else {
+            *self.thumbs_down_streak.entry(skill_id.to_string()).or_default() += 1;
+        }
+        Some(skill)
+    }
+
+    fn is_demoted(&self, skill_id: &str) -> bool {
+        self.thumbs_down_streak
+            .get(skill_id)
+            .is_some_and(|streak| *streak >= DEMOTE_AFTER_THUMBS_DOWN)
+    }
+
+    /// Count a use against a skill on probation; returns the version to roll back to
+    /// once it has clearly underperformed the version it replaced
+    fn check_regression(&mut self, skill_id: &str, success_rate: f32) -> Option<u32> {
+        let (baseline, uses) = self.regression_watch.get_mut(skill_id)?;
+        *uses += 1;
+        if *uses < ROLLBACK_MIN_USES {
+            return None;
+        }
+        let regressed = success_rate < *baseline - ROLLBACK_MARGIN;
+        self.regression_watch.remove(skill_id);
+        if !regressed {
+            return None;
+        }
+        self.versions
+            .get(skill_id)
+            .and_then(|v| v.last())
+            .map(|v| v.version)
+    }
+
+    /// Store `skill` as its current version and bump the counter; returns the new version number
+    fn snapshot_skill(&mut self, skill: &Skill, reason: &str) -> u32 {
+        let version = self.current_version(&skill.id);
+        let history = self.versions.entry(skill.id.clone()).or_default();
+        history.push(SkillVersion {
+            version,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            reason: reason.to_string(),
+            skill: skill.clone(),
+        });
+        if history.len() > MAX_SKILL_VERSIONS {
+            let excess = history.len() - MAX_SKILL_VERSIONS;
+            history.drain(..excess);
+        }
+        self.current_versions.insert(skill.id.clone(), version + 1);
+        version + 1
+    }
+
+    fn current_version(&self, skill_id: &str) -> u32 {
+        self.current_versions.get(skill_id).copied().unwrap_or(1)
+    }
+
+    /// Version history for a learned skill, oldest first; the last entry is the live version
+    pub fn list_skill_versions(&self, skill_id: &str) -> Option<Vec<SkillVersion>> {
+        let current = self.skills.iter().find(|s| s.id == skill_id)?;
+        let mut versions = self.versions.get(skill_id).cloned().unwrap_or_default();
+        versions.push(SkillVersion {
+            version: self.current_version(skill_id),
+            created_at: String::new(),
+            reason: "current".to_string(),
+            skill: current.clone(),
+        });
+        Some(versions)
+    }
+
+    /// Restore a learned skill to a previous version (the live version is snapshotted first)
+    pub async fn rollback_skill(&mut self, skill_id: &str, version: u32) -> anyhow::Result<Skill> {
+        let idx = self
+            .skills
+            .iter()
+            .position(|s| s.id == skill_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown learned skill: {}", skill_id))?;
+        let target = self
+            .versions
+            .get(skill_id)
+            .and_then(|history| history.iter().find(|v| v.version == version))
+            .map(|v| v.skill.clone())
+            .ok_or_else(|| anyhow::anyhow!("Skill {} has no version {}", skill_id, version))?;
+
+        let live = self.skills[idx].clone();
+        let new_version = self.snapshot_skill(&live, &format!("rollback to v{}", version));
+        self.regression_watch.remove(skill_id);
+        self.skills[idx] = target.clone();
+        self.rebuild_indexes();
+        self.persist_skill(&target).await?;
+
+        println!("[skills] Rolled back {} to v{} (now v{})", skill_id, version, new_version);
+        Ok(target)
+    }
+
+    fn rebuild_indexes(&mut self) {
+        self.intent_index.clear();
+        self.app_index.clear();
+        let skills = std::mem::take(&mut self.skills);
+        for skill in &skills {
+            self.index_skill(skill);
+        }
+        self.skills = skills;
+    }
+
     async fn create_skill_candidate(
         &self,
         task: &Task,
@@ -560,18 +781,42 @@ impl SkillLibrary {
             .collect()
     }
 
+    /// Restore ratings and demotion streaks saved by earlier sessions
+    pub fn load_ratings(&mut self) -> anyhow::Result<()> {
+        let conn = open_ratings_db()?;
+        let mut stmt = conn.prepare("SELECT skill_id, success_rate, total_uses, thumbs_down_streak FROM skill_ratings")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (skill_id, success_rate, total_uses, streak) in &rows {
+            if let Some(skill) = self.skills.iter_mut().chain(self.predefined_skills.iter_mut()).find(|s| &s.id == skill_id) {
+                skill.success_rate = *success_rate as f32;
+                skill.total_uses = *total_uses as u32;
+            }
+            if *streak > 0 {
+                self.thumbs_down_streak.insert(skill_id.clone(), *streak as u32);
+            }
+        }
+        println!("[skills] Loaded {} skill ratings", rows.len());
+        Ok(())
+    }
+
+    fn persist_rating(&self, skill: &Skill) -> anyhow::Result<()> {
+        let conn = open_ratings_db()?;
+        let streak = self.thumbs_down_streak.get(&skill.id).copied().unwrap_or(0);
+        conn.execute(
+            "INSERT OR REPLACE INTO skill_ratings (skill_id, success_rate, total_uses, thumbs_down_streak) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![skill.id, skill.success_rate as f64, skill.total_uses as i64, streak as i64],
+        )?;
+        Ok(())
+    }
+
     async fn persist_skill(&self, skill: &Skill) -> anyhow::Result<()> {
         // Save to SQLite skills database
-        let db_path = {
-            let app_dir = dirs::data_local_dir()
-                .or_else(dirs::data_dir)
-                .unwrap_or_else(std::env::temp_dir)
-                .join("hey-work");
-            let _ = std::fs::create_dir_all(&app_dir);
-            app_dir.join("skills.db")
-        };
-        
-        match rusqlite::Connection::open(&db_path) {
+        match rusqlite::Connection::open(skills_db_path()) {
             Ok(conn) => {
                 conn.execute(
                     "CREATE TABLE IF NOT EXISTS learned_skills (
@@ -646,17 +891,27 @@ impl SkillLibrary {
         let export_data: SkillExport = serde_json::from_str(json)?;
         
         let mut imported = 0;
+        let mut replaced = false;
         for skill in export_data.skills {
-            // Skip if skill with same ID already exists
-            if self.skills.iter().any(|s| s.id == skill.id) {
-                continue;
+            if let Some(idx) = self.skills.iter().position(|s| s.id == skill.id) {
+                // Replace the existing skill, keeping it as a version so the import can be rolled back
+                if serde_json::to_value(&self.skills[idx]).ok() == serde_json::to_value(&skill).ok() {
+                    continue;
+                }
+                let previous = self.skills[idx].clone();
+                self.snapshot_skill(&previous, "import");
+                self.regression_watch.remove(&skill.id);
+                self.skills[idx] = skill;
+                replaced = true;
+            } else {
+                self.index_skill(&skill);
+                self.skills.push(skill);
             }
-            
-            // Add skill
-            self.index_skill(&skill);
-            self.skills.push(skill);
             imported += 1;
         }
+        if replaced {
+            self.rebuild_indexes();
+        }
         
         println!("[skills] Imported {} skills", imported);
         Ok(imported)
@@ -679,6 +934,10 @@ impl SkillLibrary {
     pub fn delete_skill(&mut self, skill_id: &str) -> bool {
         let before = self.skills.len();
         self.skills.retain(|s| s.id != skill_id);
+        self.versions.remove(skill_id);
+        self.current_versions.remove(skill_id);
+        self.regression_watch.remove(skill_id);
+        self.thumbs_down_streak.remove(skill_id);
         self.skills.len() < before
     }
 
@@ -787,8 +1046,119 @@ pub struct SkillStats {
     pub avg_success_rate: f32,
 }
 
+/// Exponential moving average of outcomes, so recent runs count most
+fn blend_success_rate(current: f32, succeeded: bool) -> f32 {
+    let outcome = if succeeded { 1.0 } else { 0.0 };
+    current * (1.0 - SUCCESS_RATE_ALPHA) + outcome * SUCCESS_RATE_ALPHA
+}
+
+fn skills_db_path() -> std::path::PathBuf {
+    let app_dir = crate::storage::data_dir();
+    let _ = std::fs::create_dir_all(&app_dir);
+    app_dir.join("skills.db")
+}
+
+// user ratings of every skill, predefined ones included, next to the learned skills
+fn open_ratings_db() -> anyhow::Result<rusqlite::Connection> {
+    let conn = rusqlite::Connection::open(skills_db_path())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS skill_ratings (
+            skill_id TEXT PRIMARY KEY,
+            success_rate REAL NOT NULL,
+            total_uses INTEGER NOT NULL,
+            thumbs_down_streak INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    Ok(conn)
+}
+
 impl Default for SkillLibrary {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn learned_skill(id: &str) -> Skill {
+        Skill {
+            id: id.to_string(),
+            name: "test skill".to_string(),
+            description: String::new(),
+            pattern: TaskPattern {
+                intent_keywords: vec!["test".to_string()],
+                app_context: None,
+                required_elements: vec![],
+            },
+            actions: vec![],
+            success_rate: 0.9,
+            total_uses: 10,
+            avg_execution_time_ms: 0,
+        }
+    }
+
+    #[test]
+    fn snapshots_are_capped_and_numbered() {
+        let mut library = SkillLibrary::new();
+        let skill = learned_skill("skill_learned_test");
+        for _ in 0..(MAX_SKILL_VERSIONS + 2) {
+            library.snapshot_skill(&skill, "learned");
+        }
+        let history = &library.versions["skill_learned_test"];
+        assert_eq!(history.len(), MAX_SKILL_VERSIONS);
+        assert_eq!(history.last().unwrap().version, MAX_SKILL_VERSIONS as u32 + 2);
+        assert_eq!(library.current_version("skill_learned_test"), MAX_SKILL_VERSIONS as u32 + 3);
+    }
+
+    #[test]
+    fn regression_waits_for_enough_uses() {
+        let mut library = SkillLibrary::new();
+        let skill = learned_skill("skill_learned_test");
+        library.snapshot_skill(&skill, "learned");
+        library.regression_watch.insert(skill.id.clone(), (0.9, 0));
+
+        for _ in 0..(ROLLBACK_MIN_USES - 1) {
+            assert_eq!(library.check_regression(&skill.id, 0.5), None);
+        }
+        assert_eq!(library.check_regression(&skill.id, 0.5), Some(1));
+        assert!(!library.regression_watch.contains_key(&skill.id));
+    }
+
+    #[test]
+    fn small_dips_do_not_roll_back() {
+        let mut library = SkillLibrary::new();
+        let skill = learned_skill("skill_learned_test");
+        library.snapshot_skill(&skill, "learned");
+        library.regression_watch.insert(skill.id.clone(), (0.9, ROLLBACK_MIN_USES - 1));
+        assert_eq!(library.check_regression(&skill.id, 0.8), None);
+    }
+
+    #[test]
+    fn ratings_update_the_moving_average() {
+        let mut library = SkillLibrary::new();
+        let rated = library.apply_rating("skill_open_chrome", false).unwrap();
+        assert_eq!(rated.total_uses, 1);
+        assert!((rated.success_rate - 0.8).abs() < 1e-6);
+        assert!(library.apply_rating("skill_missing", true).is_none());
+    }
+
+    #[test]
+    fn repeated_thumbs_down_demotes_until_a_thumbs_up() {
+        let mut library = SkillLibrary::new();
+        let skill = library.predefined_skills[0].clone();
+        let keywords: Vec<&str> = skill.pattern.intent_keywords.iter().map(String::as_str).collect();
+        let intent = skill.name.to_lowercase();
+        assert!(library.calculate_match_score(&skill, &keywords, &intent) > 0.7);
+
+        for _ in 0..DEMOTE_AFTER_THUMBS_DOWN {
+            library.apply_rating(&skill.id, false);
+        }
+        assert!(library.is_demoted(&skill.id));
+        assert_eq!(library.calculate_match_score(&skill, &keywords, &intent), 0.0);
+
+        library.apply_rating(&skill.id, true);
+        assert!(!library.is_demoted(&skill.id));
+    }
+}
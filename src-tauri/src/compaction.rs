@@ -0,0 +1,215 @@
+// compact_conversation: fold the oldest part of a marathon conversation into one summary so it
+// can keep going past the context window. the replaced messages are archived to a sidecar file
+
+use crate::api::{estimate_message_tokens, AnthropicClient, ContentBlock, Message, ToolResultContent};
+use crate::storage::{self, Conversation};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// most recent user turns kept verbatim when the caller doesn't say
+pub const DEFAULT_KEEP_TURNS: usize = 4;
+
+// longest tool input or output quoted into the transcript that gets summarized
+const MAX_QUOTED_CHARS: usize = 1500;
+// the summary call has to fit the window too - past this only the newest part of the transcript is sent
+const MAX_TRANSCRIPT_CHARS: usize = 400_000;
+
+const SUMMARY_PROMPT: &str = "You are compacting the early part of a long conversation between a user and a computer-use agent so the conversation can continue. Write a synopsis the agent can rely on instead of the original messages. Cover: what the user asked for, decisions and preferences they stated, what the agent did and found (files created or changed with their paths, commands run, pages visited, values looked up), what succeeded, what failed and why, and anything left unfinished. Keep exact names, paths, urls and numbers. Write plain prose and short lists; no preamble.";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactionReport {
+    pub compacted_messages: usize,
+    pub kept_messages: usize,
+    /// estimated context size of the conversation, before and after
+    pub tokens_before: u32,
+    pub tokens_after: u32,
+    /// sidecar holding the replaced messages
+    pub archive_path: String,
+}
+
+// a user message the user typed, as opposed to one carrying tool results
+fn is_instruction(message: &Message) -> bool {
+    message.role == "user" && !message.content.iter().any(|b| matches!(b, ContentBlock::ToolResult { .. }))
+}
+
+/// where the verbatim tail starts: the `keep_turns`-th last user instruction, so a tool call is
+/// never separated from its result. None when there's nothing older than that to compact
+pub fn split_point(messages: &[Message], keep_turns: usize) -> Option<usize> {
+    let turn_starts: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, message)| is_instruction(message))
+        .map(|(index, _)| index)
+        .collect();
+    let keep_turns = keep_turns.max(1);
+    if turn_starts.len() < keep_turns {
+        return None;
+    }
+    let split = turn_starts[turn_starts.len() - keep_turns];
+    (split > 0).then_some(split)
+}
+
+fn quote(text: &str) -> String {
+    match text.char_indices().nth(MAX_QUOTED_CHARS) {
+        Some((end, _)) => format!("{}… ({} chars)", &text[..end], text.chars().count()),
+        None => text.to_string(),
+    }
+}
+
+/// the messages as plain text for the summarizer - screenshots and thinking left out,
+/// tool traffic quoted briefly
+pub fn transcript(messages: &[Message]) -> String {
+    let mut lines = Vec::new();
+    for message in messages {
+        let speaker = if message.role == "assistant" { "Assistant" } else { "User" };
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } if !text.trim().is_empty() => {
+                    lines.push(format!("{}: {}", speaker, text.trim()));
+                }
+                ContentBlock::Image { .. } => lines.push(format!("{}: [image]", speaker)),
+                ContentBlock::ToolUse { name, input, .. } | ContentBlock::ServerToolUse { name, input, .. } => {
+                    lines.push(format!("Assistant used {}: {}", name, quote(&input.to_string())));
+                }
+                ContentBlock::ToolResult { content, .. } => {
+                    let parts: Vec<String> = content
+                        .iter()
+                        .map(|part| match part {
+                            ToolResultContent::Text { text } => quote(text.trim()),
+                            ToolResultContent::Image { .. } => "[screenshot]".to_string(),
+                        })
+                        .collect();
+                    lines.push(format!("Tool result: {}", parts.join(" ")));
+                }
+                ContentBlock::WebSearchToolResult { .. } | ContentBlock::WebFetchToolResult { .. } => {
+                    lines.push("Tool result: [web results]".to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let text = lines.join("\n\n");
+    let total = text.chars().count();
+    if total <= MAX_TRANSCRIPT_CHARS {
+        return text;
+    }
+    let tail: String = text.chars().skip(total - MAX_TRANSCRIPT_CHARS).collect();
+    format!("[earliest part of the transcript omitted]\n\n{}", tail)
+}
+
+// sidecar under the app data dir: <id>-<timestamp>.json with the replaced messages
+fn archive(conversation_id: &str, messages: &[Message]) -> Result<PathBuf, String> {
+    let dir = storage::data_dir().join("compacted");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create archive dir: {}", e))?;
+    let path = dir.join(format!("{}-{}.json", conversation_id, chrono::Utc::now().timestamp()));
+    let json = serde_json::to_string(messages).map_err(|e| format!("serialize error: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("failed to write archive: {}", e))?;
+    Ok(path)
+}
+
+/// summarize everything before the last `keep_turns` user turns, archive it, and save the
+/// conversation with the summary in its place
+pub async fn compact(conversation: &mut Conversation, keep_turns: usize, api_key: String) -> Result<CompactionReport, String> {
+    let split = split_point(&conversation.messages, keep_turns).ok_or_else(|| {
+        format!("Nothing to compact - the conversation has {} or fewer turns", keep_turns.max(1))
+    })?;
+    let tokens_before = estimate_message_tokens(&conversation.messages);
+
+    let request = Message {
+        role: "user".to_string(),
+        content: vec![ContentBlock::Text {
+            text: format!("<transcript>\n{}\n</transcript>", transcript(&conversation.messages[..split])),
+        }],
+    };
+    let client = AnthropicClient::new(api_key, conversation.model.clone());
+    let result = client
+        .complete(Some(SUMMARY_PROMPT.to_string()), vec![request], None)
+        .await
+        .map_err(|e| format!("Summarizing failed: {}", e))?;
+    let summary: String = result
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text.trim()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if summary.is_empty() {
+        return Err("Summarizing failed: the model returned no text".to_string());
+    }
+
+    // archived before anything is replaced, so a failure here leaves the conversation untouched
+    let archive_path = archive(&conversation.id, &conversation.messages[..split])?;
+    let model = conversation.model.clone();
+    conversation.replace_with_summary(split, &summary);
+    conversation.add_usage(result.usage, &model);
+    storage::save_conversation(conversation)?;
+
+    Ok(CompactionReport {
+        compacted_messages: split,
+        kept_messages: conversation.messages.len() - 2,
+        tokens_before,
+        tokens_after: estimate_message_tokens(&conversation.messages),
+        archive_path: archive_path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(role: &str, text: &str) -> Message {
+        Message { role: role.to_string(), content: vec![ContentBlock::Text { text: text.to_string() }] }
+    }
+
+    fn tool_round() -> [Message; 2] {
+        [
+            Message {
+                role: "assistant".to_string(),
+                content: vec![ContentBlock::ToolUse {
+                    id: "t1".to_string(),
+                    name: "bash".to_string(),
+                    input: serde_json::json!({ "command": "ls" }),
+                }],
+            },
+            Message {
+                role: "user".to_string(),
+                content: vec![ContentBlock::ToolResult {
+                    tool_use_id: "t1".to_string(),
+                    content: vec![ToolResultContent::Text { text: "notes.txt".to_string() }],
+                }],
+            },
+        ]
+    }
+
+    fn conversation_of(turns: usize) -> Vec<Message> {
+        let mut messages = Vec::new();
+        for turn in 0..turns {
+            messages.push(text("user", &format!("task {}", turn)));
+            messages.extend(tool_round());
+            messages.push(text("assistant", "done"));
+        }
+        messages
+    }
+
+    #[test]
+    fn tail_starts_at_a_user_instruction() {
+        let messages = conversation_of(5);
+        // 4 messages per turn, tool results don't count as turns
+        assert_eq!(split_point(&messages, 2), Some(12));
+        assert_eq!(split_point(&messages, 5), None);
+        assert_eq!(split_point(&messages, 9), None);
+        assert_eq!(split_point(&messages, 0), Some(16));
+    }
+
+    #[test]
+    fn transcript_quotes_tool_calls_and_results() {
+        assert_eq!(
+            transcript(&conversation_of(1)),
+            "User: task 0\n\nAssistant used bash: {\"command\":\"ls\"}\n\nTool result: notes.txt\n\nAssistant: done"
+        );
+    }
+}
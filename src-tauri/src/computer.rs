@@ -27,6 +27,94 @@ const JPEG_QUALITY: u8 = 60;
 const AI_WIDTH: u32 = 1280;
 const AI_HEIGHT: u32 = 800;
 
+/// the whole screen as a zoom region
+pub const FULL_SCREEN_REGION: [i32; 4] = [0, 0, AI_WIDTH as i32, AI_HEIGHT as i32];
+
+// zoom_to_text crops get sharper encoding and upscaling so small labels stay legible
+const ZOOM_JPEG_QUALITY: u8 = 85;
+const ZOOM_MIN_WIDTH: u32 = 480;
+const ZOOM_PADDING: u32 = 40;
+
+// wait_for_change defaults and bounds
+pub const WAIT_DEFAULT_TIMEOUT_MS: u64 = 10_000;
+pub const WAIT_MAX_TIMEOUT_MS: u64 = 60_000;
+pub const WAIT_DEFAULT_STABILITY_MS: u64 = 1_000;
+const WAIT_POLL_MS: u64 = 250;
+// frames are compared as small grayscale thumbnails - cheap, and immune to jpeg noise
+const WAIT_THUMB_WIDTH: u32 = 160;
+// mean per-pixel difference (0-255) that counts as a change; cursor blinks stay under it
+const WAIT_CHANGE_THRESHOLD: f64 = 1.5;
+// time for a moved window to settle before its new bounds are read back
+const WINDOW_SETTLE_MS: u64 = 150;
+// observe_ui stops walking the focused window's accessibility tree past these, so a huge
+// list or table can't flood the context
+const MAX_UI_ELEMENTS: usize = 400;
+const MAX_UI_DEPTH: usize = 12;
+// longest name or value kept per element
+const MAX_UI_TEXT_CHARS: usize = 200;
+// containers only worth a line when they carry a label; their children move up a level
+const UNLABELED_CONTAINER_ROLES: &[&str] = &["group", "split group", "scroll area", "layout area", "pane", "custom", "unknown"];
+// per-pixel difference (0-255) that counts a pixel as changed in screenshot_change_ratio
+const PIXEL_CHANGE_THRESHOLD: u8 = 24;
+// brightness spread (0-255) at or below which a downscaled capture counts as blank
+const BLANK_FRAME_RANGE: u8 = 3;
+// how many executed actions undo can walk back through
+const UNDO_HISTORY_LIMIT: usize = 20;
+
+/// env var (saved in .env): glide the cursor along a curved path instead of teleporting it
+pub const HUMANIZE_MOUSE_ENV: &str = "HEYWORK_HUMANIZE_MOUSE";
+// humanized moves take longer the farther they go, but never more than this
+const HUMANIZE_MAX_DURATION_MS: u64 = 400;
+const HUMANIZE_MIN_DURATION_MS: u64 = 80;
+const HUMANIZE_STEP_MS: u64 = 8;
+// random offset (screen px) added to the landing point - small enough to stay on any button
+const HUMANIZE_JITTER_PX: i32 = 2;
+
+/// env var (saved in .env): put the user's clipboard back after text is typed by pasting
+pub const PASTE_RESTORE_CLIPBOARD_ENV: &str = "HEYWORK_PASTE_RESTORE_CLIPBOARD";
+// typed text longer than this goes in by paste - key events are slow and drop characters on long runs
+const PASTE_MIN_CHARS: usize = 40;
+// time for the focused app to read the clipboard before the old contents go back
+const PASTE_RESTORE_DELAY_MS: u64 = 300;
+
+/// key synthesis mangles emoji, accents and non-latin scripts and is slow for long text,
+/// so those go in through the clipboard; short ascii is still typed key by key
+pub fn should_paste(text: &str) -> bool {
+    !text.is_ascii() || text.chars().count() > PASTE_MIN_CHARS
+}
+
+/// the text to paste and whether it ended in a newline - pasting that newline wouldn't
+/// submit the way a typed one does, so it's pressed as return afterwards
+pub fn split_submit(text: &str) -> (&str, bool) {
+    let body = text.trim_end_matches(['\n', '\r']);
+    (body, body.len() < text.len())
+}
+
+fn paste_restore_enabled() -> bool {
+    std::env::var(PASTE_RESTORE_CLIPBOARD_ENV)
+        .map(|v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "no" | "off"))
+        .unwrap_or(true)
+}
+
+pub fn humanize_enabled() -> bool {
+    std::env::var(HUMANIZE_MOUSE_ENV)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// env vars (saved in .env) for the help hotkey's context screenshot
+pub const HELP_SCREENSHOT_FORMAT_ENV: &str = "HEYWORK_HELP_SCREENSHOT_FORMAT";
+pub const HELP_SCREENSHOT_AREA_ENV: &str = "HEYWORK_HELP_SCREENSHOT_AREA";
+// cursor-centered help capture, in AI space - a quarter of the screen
+const HELP_CURSOR_REGION: [i32; 2] = [640, 400];
+// the API downsamples anything with a longer edge than this, so don't send more
+const HELP_MAX_EDGE: u32 = 1568;
+// area read around a click when checking it for risky text, in AI space - about a button and
+// its label
+const RISK_CHECK_REGION: [i32; 2] = [320, 120];
+// actions that only look at the screen - never recorded, so undo skips past them
+const OBSERVE_ONLY_ACTIONS: &[&str] = &["screenshot", "zoom", "zoom_to_text", "wait"];
+
 #[derive(Error, Debug)]
 pub enum ComputerError {
     #[error("Input error: {0}")]
@@ -37,6 +125,212 @@ pub enum ComputerError {
     UnknownAction(String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenshotFormat {
+    #[default]
+    Jpeg,
+    /// lossless - keeps small fonts readable at the cost of a bigger upload
+    Png,
+}
+
+impl ScreenshotFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "jpeg" | "jpg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        std::env::var(HELP_SCREENSHOT_FORMAT_ENV)
+            .ok()
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpeg",
+            Self::Png => "png",
+        }
+    }
+
+    pub fn media_type(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+        }
+    }
+}
+
+/// how much of the screen the help hotkey captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HelpCaptureArea {
+    #[default]
+    FullScreen,
+    /// the frontmost window of another app
+    ActiveWindow,
+    /// a quarter-screen box around the mouse
+    Cursor,
+}
+
+impl HelpCaptureArea {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "full" => Some(Self::FullScreen),
+            "window" => Some(Self::ActiveWindow),
+            "cursor" => Some(Self::Cursor),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FullScreen => "full",
+            Self::ActiveWindow => "window",
+            Self::Cursor => "cursor",
+        }
+    }
+
+    pub fn from_env() -> Self {
+        std::env::var(HELP_SCREENSHOT_AREA_ENV)
+            .ok()
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+}
+
+/// the help screenshot settings as the settings screen shows them
+#[derive(Debug, Clone, Serialize)]
+pub struct HelpScreenshotSettings {
+    pub format: String,
+    pub area: String,
+}
+
+impl HelpScreenshotSettings {
+    pub fn current() -> Self {
+        Self {
+            format: ScreenshotFormat::from_env().as_str().to_string(),
+            area: HelpCaptureArea::from_env().as_str().to_string(),
+        }
+    }
+}
+
+/// how a wait_for_change call ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WaitOutcome {
+    /// the region stayed still for the full stability window
+    pub stabilized: bool,
+    /// the region changed at least once while waiting
+    pub changed: bool,
+    pub elapsed_ms: u64,
+}
+
+/// an on-screen window from list_windows, bounds in AI space like every other coordinate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowInfo {
+    pub id: u32,
+    pub title: String,
+    pub app: String,
+    #[serde(skip)]
+    pub pid: u32,
+    /// [x1, y1, x2, y2]
+    pub bounds: [i32; 4],
+}
+
+/// where snap_window puts a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapPosition {
+    Left,
+    Right,
+    Maximize,
+}
+
+impl SnapPosition {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "maximize" | "max" | "full" => Some(Self::Maximize),
+            _ => None,
+        }
+    }
+
+    /// the [x1, y1, x2, y2] it fills in AI space
+    pub fn bounds(self) -> [i32; 4] {
+        let (width, height) = (AI_WIDTH as i32, AI_HEIGHT as i32);
+        match self {
+            Self::Left => [0, 0, width / 2, height],
+            Self::Right => [width / 2, 0, width, height],
+            Self::Maximize => FULL_SCREEN_REGION,
+        }
+    }
+}
+
+/// the frontmost app and its focused window, from get_active_app
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActiveApp {
+    pub name: String,
+    /// bundle id on macOS, executable path on Windows, window class on Linux
+    pub bundle_id: Option<String>,
+    pub window_title: Option<String>,
+}
+
+/// one element of the focused window's accessibility tree, as observe_ui reads it
+#[derive(Debug, Clone, PartialEq)]
+pub struct UiElement {
+    pub depth: usize,
+    /// e.g. "button", "text field", "static text"
+    pub role: String,
+    pub name: String,
+    pub value: String,
+    /// [x, y, width, height] in screen pixels, when the element has a frame
+    pub frame: Option<[i32; 4]>,
+}
+
+// "AXTextField" and "ControlType.TextField" both become "text field"
+fn normalize_role(raw: &str) -> String {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix("ControlType.").or_else(|| raw.strip_prefix("AX")).unwrap_or(raw);
+    let mut role = String::with_capacity(raw.len() + 4);
+    for (i, c) in raw.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            role.push(' ');
+        }
+        role.extend(c.to_lowercase());
+    }
+    role
+}
+
+/// the tab-separated lines the platform scripts print: depth, role, name, value, then
+/// x, y, width and height when the element has a frame. lines that don't parse are skipped
+pub fn parse_ui_tree(output: &str) -> Vec<UiElement> {
+    let text = |field: Option<&str>| {
+        let field = field.unwrap_or("").trim();
+        if field == "missing value" {
+            return String::new();
+        }
+        field.chars().take(MAX_UI_TEXT_CHARS).collect::<String>()
+    };
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let depth = fields.next()?.trim().parse().ok()?;
+            let role = normalize_role(fields.next()?);
+            let name = text(fields.next());
+            let value = text(fields.next());
+            let numbers: Vec<i32> = fields.filter_map(|f| f.trim().parse::<f64>().ok()).map(|n| n as i32).collect();
+            let frame = match numbers[..] {
+                [x, y, width, height] => Some([x, y, width, height]),
+                _ => None,
+            };
+            Some(UiElement { depth, role, name, value, frame })
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComputerAction {
     pub action: String,
@@ -56,9 +350,50 @@ pub struct ComputerAction {
     pub region: Option<[i32; 4]>,  // for zoom action [x1, y1, x2, y2]
 }
 
+/// an executed action plus the cursor position before it - enough to invert the simple ones
+#[derive(Debug, Clone)]
+pub struct RecordedAction {
+    pub action: ComputerAction,
+    /// in AI space, like the action's own coordinates
+    pub cursor_before: Option<[i32; 2]>,
+}
+
+/// bounded history of executed computer actions, newest last
+#[derive(Debug, Default)]
+pub struct ActionHistory {
+    entries: std::collections::VecDeque<RecordedAction>,
+}
+
+impl ActionHistory {
+    pub fn record(&mut self, action: ComputerAction, cursor_before: Option<[i32; 2]>) {
+        if OBSERVE_ONLY_ACTIONS.contains(&action.action.as_str()) {
+            return;
+        }
+        self.entries.push_back(RecordedAction { action, cursor_before });
+        while self.entries.len() > UNDO_HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn last(&self) -> Option<&RecordedAction> {
+        self.entries.back()
+    }
+
+    pub fn pop(&mut self) -> Option<RecordedAction> {
+        self.entries.pop_back()
+    }
+
+    /// forget everything - a new run's undo must never reach into an older run's actions
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 pub struct ComputerControl {
     pub screen_width: u32,
     pub screen_height: u32,
+    /// move the cursor like a person would before clicks, moves and drags
+    pub humanize: bool,
 }
 
 impl ComputerControl {
@@ -72,11 +407,12 @@ impl ComputerControl {
         Ok(Self {
             screen_width: monitor.width().map_err(|e| ComputerError::Screenshot(e.to_string()))?,
             screen_height: monitor.height().map_err(|e| ComputerError::Screenshot(e.to_string()))?,
+            humanize: humanize_enabled(),
         })
     }
 
     pub fn with_dimensions(screen_width: u32, screen_height: u32) -> Self {
-        Self { screen_width, screen_height }
+        Self { screen_width, screen_height, humanize: humanize_enabled() }
     }
 
     pub fn take_screenshot(&self) -> Result<String, ComputerError> {
@@ -167,6 +503,48 @@ impl ComputerControl {
     }
 
     pub fn perform_action(&self, action: &ComputerAction) -> Result<Option<String>, ComputerError> {
+        // our panels are left out of screenshots but still on screen - never let a click land on them
+        #[cfg(target_os = "macos")]
+        let shielded = self.shield_own_panels(action);
+
+        let result = self.dispatch_action(action);
+
+        #[cfg(target_os = "macos")]
+        crate::panels::restore_panel_clicks(&shielded);
+
+        result
+    }
+
+    // click-through any Hey-work panel under the points this action presses the mouse at
+    #[cfg(target_os = "macos")]
+    fn shield_own_panels(&self, action: &ComputerAction) -> Vec<&'static str> {
+        let presses = matches!(
+            action.action.as_str(),
+            "left_click" | "right_click" | "middle_click" | "double_click" | "triple_click"
+                | "left_click_drag" | "left_mouse_down" | "left_mouse_up"
+        );
+        if !presses {
+            return Vec::new();
+        }
+
+        // no coordinate means "click where the cursor is"
+        let points: Vec<(f64, f64)> = [action.start_coordinate, action.coordinate.or_else(|| self.cursor_position())]
+            .into_iter()
+            .flatten()
+            .map(|[x, y]| {
+                let (x, y) = self.map_from_ai_space(x, y);
+                (f64::from(x), f64::from(y))
+            })
+            .collect();
+
+        let shielded = crate::panels::pass_clicks_through_panels_at(&points);
+        if !shielded.is_empty() {
+            println!("[computer] {} target overlaps our {} panel(s), passing the click through", action.action, shielded.join(", "));
+        }
+        shielded
+    }
+
+    fn dispatch_action(&self, action: &ComputerAction) -> Result<Option<String>, ComputerError> {
         let mut enigo = Enigo::new(&Settings::default())
             .map_err(|e| ComputerError::Input(e.to_string()))?;
 
@@ -179,8 +557,7 @@ impl ComputerControl {
             "mouse_move" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 Ok(None)
             }
@@ -188,8 +565,7 @@ impl ComputerControl {
             "left_click" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 enigo.button(Button::Left, Direction::Click)
                     .map_err(|e| ComputerError::Input(e.to_string()))?;
@@ -199,8 +575,7 @@ impl ComputerControl {
             "right_click" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 enigo.button(Button::Right, Direction::Click)
                     .map_err(|e| ComputerError::Input(e.to_string()))?;
@@ -210,8 +585,7 @@ impl ComputerControl {
             "middle_click" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 enigo.button(Button::Middle, Direction::Click)
                     .map_err(|e| ComputerError::Input(e.to_string()))?;
@@ -221,8 +595,7 @@ impl ComputerControl {
             "double_click" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 enigo.button(Button::Left, Direction::Click)
                     .map_err(|e| ComputerError::Input(e.to_string()))?;
@@ -234,8 +607,7 @@ impl ComputerControl {
             "triple_click" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 for _ in 0..3 {
                     enigo.button(Button::Left, Direction::Click)
@@ -249,12 +621,10 @@ impl ComputerControl {
                     let (sx, sy) = self.map_from_ai_space(start[0], start[1]);
                     let (ex, ey) = self.map_from_ai_space(end[0], end[1]);
 
-                    enigo.move_mouse(sx, sy, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, sx, sy)?;
                     enigo.button(Button::Left, Direction::Press)
                         .map_err(|e| ComputerError::Input(e.to_string()))?;
-                    enigo.move_mouse(ex, ey, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, ex, ey)?;
                     enigo.button(Button::Left, Direction::Release)
                         .map_err(|e| ComputerError::Input(e.to_string()))?;
                 }
@@ -262,7 +632,9 @@ impl ComputerControl {
             }
 
             "type" => {
-                if let Some(text) = &action.text {
+                if let Some(text) = action.text.as_deref().filter(|text| should_paste(text)) {
+                    self.paste_text(&mut enigo, text)?;
+                } else if let Some(text) = &action.text {
                     #[cfg(target_os = "macos")]
                     {
                         self.type_text_applescript(text)?;
@@ -328,8 +700,7 @@ impl ComputerControl {
             "left_mouse_down" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 enigo.button(Button::Left, Direction::Press)
                     .map_err(|e| ComputerError::Input(e.to_string()))?;
@@ -339,8 +710,7 @@ impl ComputerControl {
             "left_mouse_up" => {
                 if let Some(coord) = action.coordinate {
                     let (x, y) = self.map_from_ai_space(coord[0], coord[1]);
-                    enigo.move_mouse(x, y, Coordinate::Abs)
-                        .map_err(|e| ComputerError::Input(e.to_string()))?;
+                    self.move_cursor(&mut enigo, x, y)?;
                 }
                 enigo.button(Button::Left, Direction::Release)
                     .map_err(|e| ComputerError::Input(e.to_string()))?;
@@ -376,16 +746,329 @@ impl ComputerControl {
                 }
             }
 
+            "zoom_to_text" => {
+                // text holds the query; capture full resolution, OCR it, crop around the match
+                let query = action.text.as_deref().unwrap_or("");
+                let capture = self.take_screenshot_region(FULL_SCREEN_REGION)?;
+                let (_, zoomed) = self.zoom_to_text(&capture, query)?;
+                Ok(Some(zoomed))
+            }
+
             _ => Err(ComputerError::UnknownAction(action.action.clone())),
         }
     }
 
+    /// find `query` in a full-resolution capture (base64 jpeg) and return the matched text
+    /// and a padded, upscaled crop around it. Errors if nothing matches.
+    pub fn zoom_to_text(&self, capture: &str, query: &str) -> Result<(String, String), ComputerError> {
+        let bytes = BASE64.decode(capture).map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+        let image = image::load_from_memory(&bytes).map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+
+        let boxes = crate::ocr::recognize_text(&image).map_err(ComputerError::Screenshot)?;
+        let found = crate::ocr::find_text(&boxes, query).ok_or_else(|| {
+            ComputerError::Input(format!(
+                "No text matching \"{}\" found on screen ({} text regions read). Take a full screenshot instead.",
+                query,
+                boxes.len()
+            ))
+        })?;
+
+        let x1 = found.x.saturating_sub(ZOOM_PADDING);
+        let y1 = found.y.saturating_sub(ZOOM_PADDING);
+        let x2 = (found.x + found.width + ZOOM_PADDING).min(image.width());
+        let y2 = (found.y + found.height + ZOOM_PADDING).min(image.height());
+        let mut crop = image.crop_imm(x1, y1, x2.saturating_sub(x1).max(1), y2.saturating_sub(y1).max(1));
+
+        if crop.width() < ZOOM_MIN_WIDTH {
+            let scale = f64::from(ZOOM_MIN_WIDTH) / f64::from(crop.width());
+            let height = (f64::from(crop.height()) * scale) as u32;
+            crop = crop.resize_exact(ZOOM_MIN_WIDTH, height.max(1), FilterType::Lanczos3);
+        }
+
+        let rgb = crop.to_rgb8();
+        let mut buffer = Vec::with_capacity(100_000);
+        let mut encoder = JpegEncoder::new_with_quality(&mut buffer, ZOOM_JPEG_QUALITY);
+        encoder.encode_image(&rgb)
+            .map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+
+        Ok((found.text.clone(), BASE64.encode(&buffer)))
+    }
+
+    /// OCR a full-resolution capture (base64 jpeg) and pick where click_text should click.
+    /// boxes come back in AI space, so they can be clicked or reported as they are
+    pub fn locate_text(&self, capture: &str, query: &str) -> Result<crate::ocr::ClickTarget, ComputerError> {
+        let bytes = BASE64.decode(capture).map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+        let image = image::load_from_memory(&bytes).map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+
+        // the capture is at native resolution (2x or more on high-DPI screens), AI space isn't
+        let scale_x = f64::from(AI_WIDTH) / f64::from(image.width().max(1));
+        let scale_y = f64::from(AI_HEIGHT) / f64::from(image.height().max(1));
+        let boxes: Vec<crate::ocr::TextBox> = crate::ocr::recognize_text(&image)
+            .map_err(ComputerError::Screenshot)?
+            .into_iter()
+            .map(|b| crate::ocr::TextBox {
+                x: (f64::from(b.x) * scale_x) as u32,
+                y: (f64::from(b.y) * scale_y) as u32,
+                width: (f64::from(b.width) * scale_x).max(1.0) as u32,
+                height: (f64::from(b.height) * scale_y).max(1.0) as u32,
+                text: b.text,
+            })
+            .collect();
+        Ok(crate::ocr::click_target(&boxes, query))
+    }
+
+    /// Poll `capture` (base64 jpeg of the watched region) until it has looked the same for
+    /// `stability_ms`, or `timeout_ms` passes. Returns early, unstabilized, once `running` drops.
+    pub fn wait_for_change<F>(
+        mut capture: F,
+        timeout_ms: u64,
+        stability_ms: u64,
+        running: &std::sync::atomic::AtomicBool,
+    ) -> Result<WaitOutcome, ComputerError>
+    where
+        F: FnMut() -> Result<String, ComputerError>,
+    {
+        let start = std::time::Instant::now();
+        let timeout = std::time::Duration::from_millis(timeout_ms.min(WAIT_MAX_TIMEOUT_MS));
+        let stability = std::time::Duration::from_millis(stability_ms);
+
+        let mut previous = frame_thumbnail(&capture()?)?;
+        let mut still_since = std::time::Instant::now();
+        let mut changed = false;
+
+        loop {
+            if still_since.elapsed() >= stability {
+                return Ok(WaitOutcome { stabilized: true, changed, elapsed_ms: start.elapsed().as_millis() as u64 });
+            }
+            if start.elapsed() >= timeout || !running.load(std::sync::atomic::Ordering::SeqCst) {
+                return Ok(WaitOutcome { stabilized: false, changed, elapsed_ms: start.elapsed().as_millis() as u64 });
+            }
+            std::thread::sleep(std::time::Duration::from_millis(WAIT_POLL_MS));
+
+            let frame = frame_thumbnail(&capture()?)?;
+            if frame_difference(&previous, &frame) > WAIT_CHANGE_THRESHOLD {
+                changed = true;
+                still_since = std::time::Instant::now();
+            }
+            previous = frame;
+        }
+    }
+
+    /// current mouse position in AI space, if the platform reports it
+    pub fn cursor_position(&self) -> Option<[i32; 2]> {
+        let enigo = Enigo::new(&Settings::default()).ok()?;
+        let (x, y) = enigo.location().ok()?;
+        let ai_x = (x as f64 * AI_WIDTH as f64 / self.screen_width.max(1) as f64) as i32;
+        let ai_y = (y as f64 * AI_HEIGHT as f64 / self.screen_height.max(1) as f64) as i32;
+        Some([ai_x, ai_y])
+    }
+
+    /// visible top-level windows of other apps, frontmost first. our own panels are left out
+    /// so the model doesn't try to focus the chat it's running in
+    pub fn list_windows(&self) -> Result<Vec<WindowInfo>, ComputerError> {
+        let own_pid = std::process::id();
+        let windows = xcap::Window::all().map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+        let mut listed = Vec::new();
+        for window in windows {
+            let (Ok(pid), Ok(width), Ok(height)) = (window.pid(), window.width(), window.height()) else {
+                continue;
+            };
+            if pid == own_pid || width == 0 || height == 0 || window.is_minimized().unwrap_or(false) {
+                continue;
+            }
+            let (x, y) = (window.x().unwrap_or(0), window.y().unwrap_or(0));
+            let (x1, y1) = self.map_to_ai_space(x, y);
+            let (x2, y2) = self.map_to_ai_space(x + width as i32, y + height as i32);
+            listed.push(WindowInfo {
+                id: window.id().unwrap_or(0),
+                title: window.title().unwrap_or_default(),
+                app: window.app_name().unwrap_or_default(),
+                pid,
+                bounds: [x1, y1, x2, y2],
+            });
+        }
+        Ok(listed)
+    }
+
+    /// the frontmost app and its focused window title, without a screenshot
+    pub fn active_app(&self) -> Result<ActiveApp, ComputerError> {
+        #[cfg(target_os = "macos")]
+        if !crate::permissions::has_accessibility() {
+            return Err(ComputerError::Input(
+                "Reading the active app needs Accessibility permission - grant it in System Settings > Privacy & Security > Accessibility, or take a screenshot instead".to_string(),
+            ));
+        }
+        query_active_app()
+    }
+
+    /// the focused window's accessibility tree as indented text - roles, labels, values and
+    /// bounds, no screenshot. None when the window exposes nothing below itself (canvas and
+    /// custom-drawn apps), so the caller can fall back to a screenshot
+    pub fn observe_ui(&self) -> Result<Option<String>, ComputerError> {
+        #[cfg(target_os = "macos")]
+        if !crate::permissions::has_accessibility() {
+            return Err(ComputerError::Input(
+                "Reading the window's UI needs Accessibility permission - grant it in System Settings > Privacy & Security > Accessibility, or take a screenshot instead".to_string(),
+            ));
+        }
+        let elements = query_ui_tree()?;
+        if elements.len() <= 1 {
+            return Ok(None);
+        }
+        let mut text = self.format_ui_tree(&elements);
+        if elements.len() >= MAX_UI_ELEMENTS {
+            text.push_str(&format!("\n(stopped after {} elements - zoom or take a screenshot for the rest)", MAX_UI_ELEMENTS));
+        }
+        Ok(Some(text))
+    }
+
+    /// one line per element, indented by depth, bounds as [x1, y1, x2, y2] in AI space.
+    /// unlabeled containers are left out and their children move up, like the browser
+    /// snapshot does with generic divs
+    pub fn format_ui_tree(&self, elements: &[UiElement]) -> String {
+        let mut output = String::new();
+        // depths of the skipped containers above the current element
+        let mut skipped: Vec<usize> = Vec::new();
+        for element in elements {
+            while skipped.last().is_some_and(|&depth| depth >= element.depth) {
+                skipped.pop();
+            }
+            let unlabeled = element.name.is_empty() && element.value.is_empty();
+            if unlabeled && UNLABELED_CONTAINER_ROLES.contains(&element.role.as_str()) {
+                skipped.push(element.depth);
+                continue;
+            }
+
+            let indent = element.depth.saturating_sub(skipped.len());
+            output.push_str(&"  ".repeat(indent));
+            output.push_str(&element.role);
+            if !element.name.is_empty() {
+                output.push_str(&format!(" \"{}\"", element.name));
+            }
+            if !element.value.is_empty() && element.value != element.name {
+                output.push_str(&format!(" value=\"{}\"", element.value));
+            }
+            if let Some([x, y, width, height]) = element.frame {
+                let (x1, y1) = self.map_to_ai_space(x, y);
+                let (x2, y2) = self.map_to_ai_space(x + width, y + height);
+                output.push_str(&format!(" [{}, {}, {}, {}]", x1, y1, x2, y2));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// raise and focus the window matching `query` (an id from list_windows, or part of a title or app name)
+    pub fn focus_window(&self, query: &str) -> Result<WindowInfo, ComputerError> {
+        let windows = self.list_windows()?;
+        let window = find_window(&windows, query)
+            .cloned()
+            .ok_or_else(|| ComputerError::Input(format!("No open window matches \"{}\"", query)))?;
+        raise_window(&window)?;
+        Ok(window)
+    }
+
+    /// move and resize the window matching `query` to `bounds` ([x1, y1, x2, y2] in AI space).
+    /// returns where it ended up - apps can refuse sizes below their minimum or past the screen
+    pub fn set_window_bounds(&self, query: &str, bounds: [i32; 4]) -> Result<WindowInfo, ComputerError> {
+        let [x1, y1, x2, y2] = bounds;
+        if x2 <= x1 || y2 <= y1 {
+            return Err(ComputerError::Input("Window width and height must be positive".to_string()));
+        }
+        let windows = self.list_windows()?;
+        let window = find_window(&windows, query)
+            .cloned()
+            .ok_or_else(|| ComputerError::Input(format!("No open window matches \"{}\"", query)))?;
+        let (x, y) = self.map_from_ai_space(x1, y1);
+        let (right, bottom) = self.map_from_ai_space(x2, y2);
+        move_window(&window, x, y, right - x, bottom - y)?;
+
+        std::thread::sleep(std::time::Duration::from_millis(WINDOW_SETTLE_MS));
+        let moved = self.list_windows()?.into_iter().find(|w| w.id == window.id);
+        Ok(moved.unwrap_or(WindowInfo { bounds, ..window }))
+    }
+
+    // jump straight to (x, y) in screen px, or glide there when humanize is on
+    fn move_cursor(&self, enigo: &mut Enigo, x: i32, y: i32) -> Result<(), ComputerError> {
+        let to_input = |e: enigo::InputError| ComputerError::Input(e.to_string());
+        let from = if self.humanize { enigo.location().ok() } else { None };
+        let Some(from) = from else {
+            return enigo.move_mouse(x, y, Coordinate::Abs).map_err(to_input);
+        };
+
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let to = (
+            x + rng.gen_range(-HUMANIZE_JITTER_PX..=HUMANIZE_JITTER_PX),
+            y + rng.gen_range(-HUMANIZE_JITTER_PX..=HUMANIZE_JITTER_PX),
+        );
+        let distance = f64::from(to.0 - from.0).hypot(f64::from(to.1 - from.1));
+        let duration_ms = humanized_duration_ms(distance);
+        // bow the path to one side by up to a fifth of its length
+        let bow = (rng.gen_range(-0.2..=0.2), rng.gen_range(-0.2..=0.2));
+        let steps = (duration_ms / HUMANIZE_STEP_MS).max(1) as usize;
+
+        for point in bezier_path(from, to, bow, steps) {
+            enigo.move_mouse(point.0, point.1, Coordinate::Abs).map_err(to_input)?;
+            std::thread::sleep(std::time::Duration::from_millis(HUMANIZE_STEP_MS));
+        }
+        Ok(())
+    }
+
+    fn map_to_ai_space(&self, x: i32, y: i32) -> (i32, i32) {
+        let ai_x = (x as f64 * AI_WIDTH as f64 / self.screen_width.max(1) as f64) as i32;
+        let ai_y = (y as f64 * AI_HEIGHT as f64 / self.screen_height.max(1) as f64) as i32;
+        (ai_x, ai_y)
+    }
+
     fn map_from_ai_space(&self, x: i32, y: i32) -> (i32, i32) {
         let scaled_x = (x as f64 * self.screen_width as f64 / AI_WIDTH as f64) as i32;
         let scaled_y = (y as f64 * self.screen_height as f64 / AI_HEIGHT as f64) as i32;
         (scaled_x, scaled_y)
     }
 
+    // the platform paste shortcut - no enigo key events on macos, like the "key" action
+    fn press_combo(&self, enigo: &mut Enigo, combo: &str) -> Result<(), ComputerError> {
+        #[cfg(target_os = "macos")]
+        {
+            let _ = enigo;
+            self.press_key_cgevent(combo)
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            self.press_key(enigo, combo)
+        }
+    }
+
+    // insert text through the clipboard, then put back what the user had copied
+    fn paste_text(&self, enigo: &mut Enigo, text: &str) -> Result<(), ComputerError> {
+        let (body, submit) = split_submit(text);
+        // only text can be put back - an image or file clipboard reads as empty text, which
+        // would restore to a blank clipboard
+        let previous = if paste_restore_enabled() {
+            crate::clipboard::read_clipboard().filter(|text| !text.is_empty())
+        } else {
+            None
+        };
+
+        crate::clipboard::write_clipboard(body).map_err(ComputerError::Input)?;
+        let paste = if cfg!(target_os = "macos") { "cmd+v" } else { "ctrl+v" };
+        let pasted = self.press_combo(enigo, paste);
+
+        // restored whether or not the paste went through
+        if let Some(previous) = previous {
+            std::thread::sleep(std::time::Duration::from_millis(PASTE_RESTORE_DELAY_MS));
+            if let Err(e) = crate::clipboard::write_clipboard(&previous) {
+                println!("[computer] Failed to restore clipboard: {}", e);
+            }
+        }
+        pasted?;
+        if submit {
+            self.press_combo(enigo, "return")?;
+        }
+        Ok(())
+    }
+
     #[cfg(target_os = "macos")]
     fn type_text_applescript(&self, text: &str) -> Result<(), ComputerError> {
         use std::process::Command;
@@ -490,6 +1173,11 @@ impl ComputerControl {
     /// uses same exclusion logic as take_screenshot_excluding
     #[cfg(target_os = "macos")]
     pub fn take_screenshot_region_excluding(&self, region: [i32; 4], window_id: u32) -> Result<String, ComputerError> {
+        encode_capture(&self.capture_region_excluding(region, window_id)?, ScreenshotFormat::Jpeg)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn capture_region_excluding(&self, region: [i32; 4], window_id: u32) -> Result<image::RgbImage, ComputerError> {
         use core_graphics::window::{
             kCGWindowListOptionOnScreenBelowWindow, kCGWindowListExcludeDesktopElements,
             CGWindowListCreateImage,
@@ -541,20 +1229,29 @@ impl ComputerControl {
             }
         }
 
-        let img = image::RgbImage::from_raw(img_width as u32, img_height as u32, rgb_data)
-            .ok_or_else(|| ComputerError::Screenshot("failed to create image".to_string()))?;
-
         // zoom returns full resolution, no resize
-        let mut buffer = Vec::with_capacity(200_000);
-        let mut encoder = JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY);
-        encoder.encode_image(&img)
-            .map_err(|e| ComputerError::Screenshot(e.to_string()))?;
-
-        Ok(BASE64.encode(&buffer))
+        image::RgbImage::from_raw(img_width as u32, img_height as u32, rgb_data)
+            .ok_or_else(|| ComputerError::Screenshot("failed to create image".to_string()))
     }
 
     /// fallback zoom without exclusion (for non-macos or when window_id unavailable)
     pub fn take_screenshot_region(&self, region: [i32; 4]) -> Result<String, ComputerError> {
+        encode_capture(&self.capture_region(region)?, ScreenshotFormat::Jpeg)
+    }
+
+    /// the whole screen scaled down to at most `max_width` pixels wide, for cheap background looks
+    pub fn take_screenshot_scaled(&self, max_width: u32) -> Result<String, ComputerError> {
+        let capture = self.capture_region(FULL_SCREEN_REGION)?;
+        let scaled = if capture.width() > max_width {
+            let height = (capture.height() as u64 * max_width as u64 / capture.width() as u64).max(1) as u32;
+            image::imageops::resize(&capture, max_width, height, FilterType::Triangle)
+        } else {
+            capture
+        };
+        encode_capture(&scaled, ScreenshotFormat::Jpeg)
+    }
+
+    fn capture_region(&self, region: [i32; 4]) -> Result<image::RgbImage, ComputerError> {
         // region is [x1, y1, x2, y2] in AI space, convert to screen space
         let (x1, y1) = self.map_from_ai_space(region[0], region[1]);
         let (x2, y2) = self.map_from_ai_space(region[2], region[3]);
@@ -575,13 +1272,79 @@ impl ComputerControl {
         let cropped = DynamicImage::ImageRgba8(image)
             .crop_imm(x1 as u32, y1 as u32, width, height);
 
-        let rgb = cropped.to_rgb8();
-        let mut buffer = Vec::with_capacity(200_000);
-        let mut encoder = JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY);
-        encoder.encode_image(&rgb)
-            .map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+        Ok(cropped.to_rgb8())
+    }
 
-        Ok(BASE64.encode(&buffer))
+    /// the help hotkey's context screenshot, in the configured format and area.
+    /// full-screen JPEG (the default) is the same capture the agent loop uses
+    pub fn take_help_screenshot(&self, exclude_below: Option<u32>) -> Result<String, ComputerError> {
+        let format = ScreenshotFormat::from_env();
+        let area = HelpCaptureArea::from_env();
+
+        if area == HelpCaptureArea::FullScreen && format == ScreenshotFormat::Jpeg {
+            #[cfg(target_os = "macos")]
+            if let Some(window_id) = exclude_below {
+                return self.take_screenshot_excluding(window_id);
+            }
+            return self.take_screenshot();
+        }
+
+        let region = self.help_capture_region(area);
+        #[cfg(target_os = "macos")]
+        let capture = match exclude_below {
+            Some(window_id) => self.capture_region_excluding(region, window_id)?,
+            None => self.capture_region(region)?,
+        };
+        #[cfg(not(target_os = "macos"))]
+        let capture = {
+            let _ = exclude_below;
+            self.capture_region(region)?
+        };
+
+        encode_capture(&fit_within(capture, HELP_MAX_EDGE), format)
+    }
+
+    /// when the text under a click at `at` (AI space, or the cursor if None) matches one of
+    /// `keywords`: that text and a jpeg crop of the area around the click
+    pub fn risky_click_target(&self, at: Option<[i32; 2]>, keywords: &[String]) -> Result<Option<(String, String)>, ComputerError> {
+        let Some([x, y]) = at.or_else(|| self.cursor_position()) else {
+            return Ok(None);
+        };
+        let (half_w, half_h) = (RISK_CHECK_REGION[0] / 2, RISK_CHECK_REGION[1] / 2);
+        let region = clamp_region([x - half_w, y - half_h, x + half_w, y + half_h]);
+        let capture = self.capture_region(region)?;
+
+        // OCR boxes are in capture pixels, which are screen pixels rather than AI space
+        let scale = f64::from(capture.width()) / f64::from((region[2] - region[0]).max(1));
+        let point = (
+            (f64::from((x - region[0]).max(0)) * scale) as u32,
+            (f64::from((y - region[1]).max(0)) * scale) as u32,
+        );
+        let boxes = crate::ocr::recognize_text(&DynamicImage::ImageRgb8(capture.clone())).map_err(ComputerError::Screenshot)?;
+        match crate::confirm::risky_label(&boxes, point, keywords) {
+            Some(label) => Ok(Some((label, encode_capture(&capture, ScreenshotFormat::Jpeg)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// [x1, y1, x2, y2] in AI space for a help capture area, falling back to the whole
+    /// screen when there's no window or cursor to center on
+    pub fn help_capture_region(&self, area: HelpCaptureArea) -> [i32; 4] {
+        let region = match area {
+            HelpCaptureArea::FullScreen => None,
+            HelpCaptureArea::ActiveWindow => self
+                .list_windows()
+                .ok()
+                .and_then(|windows| windows.into_iter().find(|w| !w.title.is_empty()))
+                .map(|window| window.bounds),
+            HelpCaptureArea::Cursor => self.cursor_position().map(|[x, y]| {
+                let (half_w, half_h) = (HELP_CURSOR_REGION[0] / 2, HELP_CURSOR_REGION[1] / 2);
+                let x1 = (x - half_w).clamp(0, AI_WIDTH as i32 - HELP_CURSOR_REGION[0]);
+                let y1 = (y - half_h).clamp(0, AI_HEIGHT as i32 - HELP_CURSOR_REGION[1]);
+                [x1, y1, x1 + HELP_CURSOR_REGION[0], y1 + HELP_CURSOR_REGION[1]]
+            }),
+        };
+        region.map_or(FULL_SCREEN_REGION, clamp_region)
     }
 
     #[cfg(target_os = "macos")]
@@ -760,3 +1523,756 @@ impl ComputerControl {
         }
     }
 }
+
+/// mime type of a base64 capture, from its magic bytes - context screenshots may be PNG now
+pub fn media_type_of(data: &str) -> &'static str {
+    if data.starts_with("iVBORw0KGgo") {
+        ScreenshotFormat::Png.media_type()
+    } else {
+        ScreenshotFormat::Jpeg.media_type()
+    }
+}
+
+fn encode_capture(img: &image::RgbImage, format: ScreenshotFormat) -> Result<String, ComputerError> {
+    let mut buffer = Vec::with_capacity(200_000);
+    match format {
+        ScreenshotFormat::Jpeg => JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY)
+            .encode_image(img)
+            .map_err(|e| ComputerError::Screenshot(e.to_string()))?,
+        ScreenshotFormat::Png => img
+            .write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Png)
+            .map_err(|e| ComputerError::Screenshot(e.to_string()))?,
+    }
+    Ok(BASE64.encode(&buffer))
+}
+
+// shrink (never enlarge) so the longer edge is at most max_edge
+fn fit_within(img: image::RgbImage, max_edge: u32) -> image::RgbImage {
+    if img.width().max(img.height()) <= max_edge {
+        return img;
+    }
+    DynamicImage::ImageRgb8(img)
+        .resize(max_edge, max_edge, FilterType::Triangle)
+        .to_rgb8()
+}
+
+// keep a region on screen and at least a pixel wide, falling back to the whole screen
+fn clamp_region(region: [i32; 4]) -> [i32; 4] {
+    let x1 = region[0].min(region[2]).clamp(0, AI_WIDTH as i32);
+    let x2 = region[0].max(region[2]).clamp(0, AI_WIDTH as i32);
+    let y1 = region[1].min(region[3]).clamp(0, AI_HEIGHT as i32);
+    let y2 = region[1].max(region[3]).clamp(0, AI_HEIGHT as i32);
+    if x2 <= x1 || y2 <= y1 {
+        return FULL_SCREEN_REGION;
+    }
+    [x1, y1, x2, y2]
+}
+
+/// an exact id wins, then an exact title, then the first title or app name containing the query.
+/// windows come frontmost first, so ties go to the one the user saw last
+pub fn find_window<'a>(windows: &'a [WindowInfo], query: &str) -> Option<&'a WindowInfo> {
+    let query = query.trim();
+    if let Ok(id) = query.parse::<u32>() {
+        if let Some(window) = windows.iter().find(|w| w.id == id) {
+            return Some(window);
+        }
+    }
+    let needle = query.to_lowercase();
+    if needle.is_empty() {
+        return None;
+    }
+    windows
+        .iter()
+        .find(|w| w.title.to_lowercase() == needle)
+        .or_else(|| windows.iter().find(|w| w.title.to_lowercase().contains(&needle)))
+        .or_else(|| windows.iter().find(|w| w.app.to_lowercase().contains(&needle)))
+}
+
+#[cfg(target_os = "macos")]
+fn raise_window(window: &WindowInfo) -> Result<(), ComputerError> {
+    let escaped = window.title.replace('\\', "\\\\").replace('"', "\\\"");
+    // frontmost brings the app forward, AXRaise picks the window when it has several
+    let script = format!(
+        r#"tell application "System Events"
+    set proc to first process whose unix id is {}
+    set frontmost of proc to true
+    try
+        perform action "AXRaise" of (first window of proc whose name is "{}")
+    end try
+end tell"#,
+        window.pid, escaped
+    );
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .map_err(|e| ComputerError::Input(e.to_string()))?;
+    if !output.status.success() {
+        return Err(ComputerError::Input(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn raise_window(window: &WindowInfo) -> Result<(), ComputerError> {
+    let script = format!("(New-Object -ComObject WScript.Shell).AppActivate({})", window.pid);
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .map_err(|e| ComputerError::Input(e.to_string()))?;
+    if String::from_utf8_lossy(&output.stdout).trim() != "True" {
+        return Err(ComputerError::Input(format!("Couldn't focus \"{}\"", window.title)));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn raise_window(window: &WindowInfo) -> Result<(), ComputerError> {
+    // xdotool first, wmctrl as the fallback - X11 only either way
+    let activated = std::process::Command::new("xdotool")
+        .args(["windowactivate", &window.id.to_string()])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+        || std::process::Command::new("wmctrl")
+            .args(["-i", "-a", &format!("0x{:x}", window.id)])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+    if !activated {
+        return Err(ComputerError::Input("Focusing windows needs xdotool or wmctrl".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn move_window(window: &WindowInfo, x: i32, y: i32, width: i32, height: i32) -> Result<(), ComputerError> {
+    let target = if window.title.is_empty() {
+        "first window of proc".to_string()
+    } else {
+        let escaped = window.title.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("first window of proc whose name is \"{}\"", escaped)
+    };
+    // position before size, so a window growing past the screen edge isn't clamped first
+    let script = format!(
+        r#"tell application "System Events"
+    set proc to first process whose unix id is {}
+    set win to {}
+    set position of win to {{{}, {}}}
+    set size of win to {{{}, {}}}
+end tell"#,
+        window.pid, target, x, y, width, height
+    );
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .map_err(|e| ComputerError::Input(e.to_string()))?;
+    if !output.status.success() {
+        return Err(ComputerError::Input(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn move_window(window: &WindowInfo, x: i32, y: i32, width: i32, height: i32) -> Result<(), ComputerError> {
+    // restore first - a maximized window ignores MoveWindow
+    let script = format!(
+        "Add-Type -Namespace HeyWork -Name Win -MemberDefinition '[DllImport(\"user32.dll\")] public static extern bool MoveWindow(IntPtr h, int x, int y, int w, int ht, bool r); [DllImport(\"user32.dll\")] public static extern bool ShowWindow(IntPtr h, int c);'; [void][HeyWork.Win]::ShowWindow([IntPtr]{id}, 9); [HeyWork.Win]::MoveWindow([IntPtr]{id}, {x}, {y}, {width}, {height}, $true)",
+        id = window.id
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .map_err(|e| ComputerError::Input(e.to_string()))?;
+    if String::from_utf8_lossy(&output.stdout).trim() != "True" {
+        return Err(ComputerError::Input(format!("Couldn't move \"{}\"", window.title)));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn move_window(window: &WindowInfo, x: i32, y: i32, width: i32, height: i32) -> Result<(), ComputerError> {
+    let id = window.id.to_string();
+    let (x, y, width, height) = (x.to_string(), y.to_string(), width.to_string(), height.to_string());
+    let moved = std::process::Command::new("xdotool")
+        .args(["windowsize", &id, &width, &height, "windowmove", &id, &x, &y])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+        || std::process::Command::new("wmctrl")
+            .args(["-i", "-r", &format!("0x{:x}", window.id), "-e", &format!("0,{},{},{},{}", x, y, width, height)])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+    if !moved {
+        return Err(ComputerError::Input("Moving windows needs xdotool or wmctrl".to_string()));
+    }
+    Ok(())
+}
+
+/// name, id and window title, one per line, as the platform scripts below print them
+fn parse_active_app(output: &str) -> Option<ActiveApp> {
+    let mut lines = output.lines().map(str::trim);
+    let name = lines.next().filter(|name| !name.is_empty())?.to_string();
+    let non_empty = |line: Option<&str>| line.filter(|l| !l.is_empty() && *l != "missing value").map(str::to_string);
+    Some(ActiveApp { name, bundle_id: non_empty(lines.next()), window_title: non_empty(lines.next()) })
+}
+
+fn run_active_app_query(program: &str, args: &[&str]) -> Result<ActiveApp, ComputerError> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| ComputerError::Input(format!("{}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(ComputerError::Input(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    parse_active_app(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| ComputerError::Input("No app is in the foreground".to_string()))
+}
+
+#[cfg(target_os = "macos")]
+fn query_active_app() -> Result<ActiveApp, ComputerError> {
+    let script = r#"tell application "System Events"
+    set proc to first application process whose frontmost is true
+    set appName to name of proc
+    set bundleId to bundle identifier of proc
+    set winTitle to ""
+    try
+        set winTitle to name of front window of proc
+    end try
+end tell
+return appName & linefeed & bundleId & linefeed & winTitle"#;
+    run_active_app_query("osascript", &["-e", script])
+}
+
+// run a tree dump script with the element and depth caps filled in
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn run_ui_tree_query(program: &str, args: &[&str], script: &str) -> Result<Vec<UiElement>, ComputerError> {
+    let script = script
+        .replace("__MAX_ELEMENTS__", &MAX_UI_ELEMENTS.to_string())
+        .replace("__MAX_DEPTH__", &MAX_UI_DEPTH.to_string());
+    let output = std::process::Command::new(program)
+        .args(args)
+        .arg(&script)
+        .output()
+        .map_err(|e| ComputerError::Input(format!("{}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(ComputerError::Input(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(parse_ui_tree(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(target_os = "macos")]
+fn query_ui_tree() -> Result<Vec<UiElement>, ComputerError> {
+    let script = r#"property maxElements : __MAX_ELEMENTS__
+property maxDepth : __MAX_DEPTH__
+property counted : 0
+property out : ""
+
+on clean(t)
+    if t is missing value then return ""
+    try
+        set t to t as text
+    on error
+        return ""
+    end try
+    set AppleScript's text item delimiters to {tab, return, linefeed}
+    set parts to text items of t
+    set AppleScript's text item delimiters to " "
+    set t to parts as text
+    set AppleScript's text item delimiters to ""
+    return t
+end clean
+
+on walk(el, depth)
+    if counted is greater than or equal to maxElements then return
+    set counted to counted + 1
+    set elRole to ""
+    set elName to ""
+    set elValue to ""
+    set box to ""
+    set kids to {}
+    tell application "System Events"
+        try
+            set elRole to role of el
+        end try
+        try
+            set elName to name of el
+        end try
+        if elName is missing value or elName is "" then
+            try
+                set elName to description of el
+            end try
+        end if
+        try
+            set elValue to value of el
+        end try
+        try
+            set p to position of el
+            set s to size of el
+            set box to ((item 1 of p) as text) & tab & ((item 2 of p) as text) & tab & ((item 1 of s) as text) & tab & ((item 2 of s) as text)
+        end try
+        if depth < maxDepth then
+            try
+                set kids to UI elements of el
+            end try
+        end if
+    end tell
+    set out to out & (depth as text) & tab & my clean(elRole) & tab & my clean(elName) & tab & my clean(elValue) & tab & box & linefeed
+    repeat with kid in kids
+        my walk(kid, depth + 1)
+    end repeat
+end walk
+
+tell application "System Events"
+    set proc to first application process whose frontmost is true
+    set win to front window of proc
+end tell
+walk(win, 0)
+return out"#;
+    run_ui_tree_query("osascript", &["-e"], script)
+}
+
+#[cfg(target_os = "windows")]
+fn query_ui_tree() -> Result<Vec<UiElement>, ComputerError> {
+    // ui automation's control view skips the raw tree's layout-only nodes
+    let script = r#"Add-Type -AssemblyName UIAutomationClient
+Add-Type -AssemblyName UIAutomationTypes
+Add-Type @"
+using System; using System.Runtime.InteropServices;
+public class Fg { [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow(); }
+"@
+$max = __MAX_ELEMENTS__
+$maxDepth = __MAX_DEPTH__
+$script:count = 0
+$walker = [Windows.Automation.TreeWalker]::ControlViewWalker
+$out = New-Object Text.StringBuilder
+function Clean($t) { if ($null -eq $t) { return "" }; return ("$t" -replace "[\t\r\n]", " ") }
+function Walk($el, $depth) {
+    if ($script:count -ge $max) { return }
+    $script:count++
+    $c = $el.Current
+    $value = $el.GetCurrentPropertyValue([Windows.Automation.ValuePattern]::ValueProperty, $true)
+    if ($value -eq [Windows.Automation.AutomationElement]::NotSupported) { $value = "" }
+    $r = $c.BoundingRectangle
+    $box = if ($r.IsEmpty) { "" } else { "$([int]$r.X)`t$([int]$r.Y)`t$([int]$r.Width)`t$([int]$r.Height)" }
+    [void]$out.AppendLine("$depth`t$(Clean $c.ControlType.ProgrammaticName)`t$(Clean $c.Name)`t$(Clean $value)`t$box")
+    if ($depth -ge $maxDepth) { return }
+    $child = $walker.GetFirstChild($el)
+    while ($null -ne $child) {
+        Walk $child ($depth + 1)
+        $child = $walker.GetNextSibling($child)
+    }
+}
+Walk ([Windows.Automation.AutomationElement]::FromHandle([Fg]::GetForegroundWindow())) 0
+$out.ToString()"#;
+    run_ui_tree_query("powershell", &["-NoProfile", "-Command"], script)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn query_ui_tree() -> Result<Vec<UiElement>, ComputerError> {
+    // no AT-SPI reader yet - an empty tree makes observe_ui fall back to a screenshot
+    Ok(Vec::new())
+}
+
+#[cfg(target_os = "windows")]
+fn query_active_app() -> Result<ActiveApp, ComputerError> {
+    let script = r#"Add-Type @"
+using System; using System.Text; using System.Runtime.InteropServices;
+public class Fg {
+    [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+    [DllImport("user32.dll")] public static extern int GetWindowText(IntPtr h, StringBuilder s, int n);
+    [DllImport("user32.dll")] public static extern uint GetWindowThreadProcessId(IntPtr h, out uint p);
+}
+"@
+$h = [Fg]::GetForegroundWindow()
+$title = New-Object Text.StringBuilder 512
+[void][Fg]::GetWindowText($h, $title, 512)
+$procId = 0
+[void][Fg]::GetWindowThreadProcessId($h, [ref]$procId)
+$proc = Get-Process -Id $procId
+"$($proc.ProcessName)`n$($proc.Path)`n$($title.ToString())""#;
+    run_active_app_query("powershell", &["-NoProfile", "-Command", script])
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn query_active_app() -> Result<ActiveApp, ComputerError> {
+    // X11 only; the class doubles as the app name since the title is all xdotool offers otherwise
+    let xdotool = |args: &[&str]| {
+        std::process::Command::new("xdotool")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let class = xdotool(&["getactivewindow", "getwindowclassname"])
+        .ok_or_else(|| ComputerError::Input("Reading the active app needs xdotool".to_string()))?;
+    let title = xdotool(&["getactivewindow", "getwindowname"]).unwrap_or_default();
+    parse_active_app(&format!("{}\n{}\n{}", class, class, title))
+        .ok_or_else(|| ComputerError::Input("No app is in the foreground".to_string()))
+}
+
+/// how long a humanized move of `distance` screen px takes
+fn humanized_duration_ms(distance: f64) -> u64 {
+    // roughly 1.5 screen widths per second
+    let ms = (distance / 2.5) as u64;
+    ms.clamp(HUMANIZE_MIN_DURATION_MS, HUMANIZE_MAX_DURATION_MS)
+}
+
+/// `steps` points along a cubic bezier from `from` to `to`, eased in and out and ending
+/// exactly on `to`. `bow` pushes the control points sideways, as fractions of the distance
+fn bezier_path(from: (i32, i32), to: (i32, i32), bow: (f64, f64), steps: usize) -> Vec<(i32, i32)> {
+    let (x0, y0) = (f64::from(from.0), f64::from(from.1));
+    let (x3, y3) = (f64::from(to.0), f64::from(to.1));
+    let (dx, dy) = (x3 - x0, y3 - y0);
+    // perpendicular to the straight line, so the bow curves the path rather than stretching it
+    let (px, py) = (-dy, dx);
+    let (x1, y1) = (x0 + dx / 3.0 + px * bow.0, y0 + dy / 3.0 + py * bow.0);
+    let (x2, y2) = (x0 + dx * 2.0 / 3.0 + px * bow.1, y0 + dy * 2.0 / 3.0 + py * bow.1);
+
+    (1..=steps.max(1))
+        .map(|i| {
+            let t = i as f64 / steps.max(1) as f64;
+            // ease in-out cubic: slow start, fast middle, slow landing
+            let t = if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 };
+            let u = 1.0 - t;
+            let x = u * u * u * x0 + 3.0 * u * u * t * x1 + 3.0 * u * t * t * x2 + t * t * t * x3;
+            let y = u * u * u * y0 + 3.0 * u * u * t * y1 + 3.0 * u * t * t * y2 + t * t * t * y3;
+            (x.round() as i32, y.round() as i32)
+        })
+        .collect()
+}
+
+fn bare_action(name: &str) -> ComputerAction {
+    ComputerAction {
+        action: name.to_string(),
+        coordinate: None,
+        start_coordinate: None,
+        text: None,
+        scroll_direction: None,
+        scroll_amount: None,
+        key: None,
+        region: None,
+    }
+}
+
+/// the actions that reverse a recorded one, or why it can't be reversed safely.
+/// only cursor moves, scrolls and plain typing are invertible - clicks, drags and
+/// key presses may have triggered something we can't see, so those are refused
+pub fn inverse_actions(recorded: &RecordedAction) -> Result<Vec<ComputerAction>, String> {
+    let action = &recorded.action;
+    match action.action.as_str() {
+        "mouse_move" => {
+            let before = recorded
+                .cursor_before
+                .ok_or("the cursor position before the move wasn't recorded")?;
+            Ok(vec![ComputerAction { coordinate: Some(before), ..bare_action("mouse_move") }])
+        }
+        "scroll" => {
+            let opposite = match action.scroll_direction.as_deref().unwrap_or("down") {
+                "up" => "down",
+                "left" => "right",
+                "right" => "left",
+                _ => "up",
+            };
+            Ok(vec![ComputerAction {
+                coordinate: action.coordinate,
+                scroll_direction: Some(opposite.to_string()),
+                scroll_amount: Some(action.scroll_amount.unwrap_or(3)),
+                ..bare_action("scroll")
+            }])
+        }
+        "type" => {
+            let text = action.text.as_deref().unwrap_or("");
+            // a newline or tab may have submitted a form or moved focus elsewhere
+            if text.contains(['\n', '\r', '\t']) {
+                return Err("the typed text contained a newline or tab, which may have submitted or moved focus".to_string());
+            }
+            Ok(text
+                .chars()
+                .map(|_| ComputerAction { text: Some("backspace".to_string()), ..bare_action("key") })
+                .collect())
+        }
+        "key" | "hold_key" => Err(format!(
+            "a key press ({}) can't be reversed",
+            action.text.as_deref().or(action.key.as_deref()).unwrap_or("unknown key")
+        )),
+        "left_click_drag" => Err("a drag may have moved or dropped something and can't be reversed".to_string()),
+        other => Err(format!("{} may have triggered something that can't be reversed", other.replace('_', " "))),
+    }
+}
+
+/// decode a base64 capture into the small grayscale frame wait_for_change compares
+fn frame_thumbnail(capture: &str) -> Result<image::GrayImage, ComputerError> {
+    let bytes = BASE64.decode(capture).map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+    let image = image::load_from_memory(&bytes).map_err(|e| ComputerError::Screenshot(e.to_string()))?;
+    let height = (u64::from(image.height()) * u64::from(WAIT_THUMB_WIDTH) / u64::from(image.width().max(1))).max(1);
+    Ok(image.resize_exact(WAIT_THUMB_WIDTH, height as u32, FilterType::Triangle).to_luma8())
+}
+
+/// a capture with nothing on it - empty, undecodable, or a single flat shade. macOS hands back
+/// frames like that once screen recording is revoked; a real screen always has some contrast
+pub fn is_blank_frame(capture: &str) -> bool {
+    match frame_thumbnail(capture) {
+        Ok(frame) => {
+            let (min, max) = frame
+                .as_raw()
+                .iter()
+                .fold((u8::MAX, u8::MIN), |(min, max), &luma| (min.min(luma), max.max(luma)));
+            max.saturating_sub(min) <= BLANK_FRAME_RANGE
+        }
+        Err(_) => true,
+    }
+}
+
+/// mean absolute per-pixel difference (0-255); frames of different sizes count as fully changed
+fn frame_difference(a: &image::GrayImage, b: &image::GrayImage) -> f64 {
+    if a.dimensions() != b.dimensions() || a.is_empty() {
+        return 255.0;
+    }
+    let total: u64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(x, y)| u64::from(x.abs_diff(*y)))
+        .sum();
+    total as f64 / a.as_raw().len() as f64
+}
+
+/// fraction (0.0-1.0) of pixels that visibly changed between two captures, compared
+/// on the same small grayscale thumbnails wait_for_change uses
+pub fn screenshot_change_ratio(before: &str, after: &str) -> Result<f64, ComputerError> {
+    let (a, b) = (frame_thumbnail(before)?, frame_thumbnail(after)?);
+    Ok(changed_pixel_ratio(&a, &b))
+}
+
+fn changed_pixel_ratio(a: &image::GrayImage, b: &image::GrayImage) -> f64 {
+    if a.dimensions() != b.dimensions() || a.is_empty() {
+        return 1.0;
+    }
+    let changed = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .filter(|(x, y)| x.abs_diff(**y) > PIXEL_CHANGE_THRESHOLD)
+        .count();
+    changed as f64 / a.as_raw().len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bezier_path_lands_on_target() {
+        let path = bezier_path((0, 0), (300, 120), (0.2, -0.15), 25);
+        assert_eq!(path.len(), 25);
+        assert_eq!(*path.last().unwrap(), (300, 120));
+        // eased: the first step covers far less ground than a straight-line share
+        assert!(path[0].0.abs() < 300 / 25);
+    }
+
+    #[test]
+    fn humanized_moves_are_capped() {
+        assert_eq!(humanized_duration_ms(0.0), HUMANIZE_MIN_DURATION_MS);
+        assert_eq!(humanized_duration_ms(50_000.0), HUMANIZE_MAX_DURATION_MS);
+    }
+
+    #[test]
+    fn changed_pixel_ratio_counts_visible_changes() {
+        let base = image::GrayImage::from_pixel(10, 10, image::Luma([100]));
+        let mut noisy = base.clone();
+        noisy.put_pixel(0, 0, image::Luma([105]));
+        assert_eq!(changed_pixel_ratio(&base, &noisy), 0.0);
+
+        let mut changed = base.clone();
+        for x in 0..10 {
+            changed.put_pixel(x, 0, image::Luma([250]));
+        }
+        assert!((changed_pixel_ratio(&base, &changed) - 0.1).abs() < f64::EPSILON);
+        assert_eq!(changed_pixel_ratio(&base, &image::GrayImage::new(5, 5)), 1.0);
+    }
+
+    #[test]
+    fn frame_difference_ignores_noise_but_sees_changes() {
+        let base = image::GrayImage::from_pixel(16, 10, image::Luma([120]));
+        let mut noisy = base.clone();
+        noisy.put_pixel(3, 4, image::Luma([160])); // a blinking caret
+        let mut loaded = base.clone();
+        for x in 0..16 {
+            for y in 0..5 {
+                loaded.put_pixel(x, y, image::Luma([250]));
+            }
+        }
+        assert_eq!(frame_difference(&base, &base), 0.0);
+        assert!(frame_difference(&base, &noisy) < WAIT_CHANGE_THRESHOLD);
+        assert!(frame_difference(&base, &loaded) > WAIT_CHANGE_THRESHOLD);
+        assert_eq!(frame_difference(&base, &image::GrayImage::new(8, 8)), 255.0);
+    }
+
+    #[test]
+    fn unicode_and_long_text_is_pasted() {
+        assert!(!should_paste("hello world"));
+        assert!(!should_paste("query\n"));
+        assert!(should_paste("café"));
+        assert!(should_paste("привет"));
+        assert!(should_paste("ship it 🚀"));
+        assert!(should_paste(&"a".repeat(PASTE_MIN_CHARS + 1)));
+
+        assert_eq!(split_submit("naïve\n"), ("naïve", true));
+        assert_eq!(split_submit("line one\nline two"), ("line one\nline two", false));
+    }
+
+    #[test]
+    fn only_simple_actions_are_invertible() {
+        let recorded = |action: ComputerAction, cursor_before| RecordedAction { action, cursor_before };
+
+        let scroll = ComputerAction { scroll_direction: Some("down".to_string()), scroll_amount: Some(5), ..bare_action("scroll") };
+        let undo = inverse_actions(&recorded(scroll, None)).unwrap();
+        assert_eq!(undo[0].scroll_direction.as_deref(), Some("up"));
+        assert_eq!(undo[0].scroll_amount, Some(5));
+
+        let typed = ComputerAction { text: Some("héllo".to_string()), ..bare_action("type") };
+        assert_eq!(inverse_actions(&recorded(typed, None)).unwrap().len(), 5);
+
+        let moved = ComputerAction { coordinate: Some([10, 10]), ..bare_action("mouse_move") };
+        assert_eq!(inverse_actions(&recorded(moved.clone(), Some([400, 300]))).unwrap()[0].coordinate, Some([400, 300]));
+        assert!(inverse_actions(&recorded(moved, None)).is_err());
+
+        let submitted = ComputerAction { text: Some("query\n".to_string()), ..bare_action("type") };
+        assert!(inverse_actions(&recorded(submitted, None)).is_err());
+        assert!(inverse_actions(&recorded(bare_action("left_click"), None)).is_err());
+    }
+
+    #[test]
+    fn history_skips_observations_and_stays_bounded() {
+        let mut history = ActionHistory::default();
+        history.record(bare_action("screenshot"), None);
+        assert!(history.last().is_none());
+        for _ in 0..UNDO_HISTORY_LIMIT + 5 {
+            history.record(bare_action("scroll"), None);
+        }
+        assert_eq!(history.entries.len(), UNDO_HISTORY_LIMIT);
+        history.clear();
+        assert!(history.last().is_none());
+    }
+
+    fn window(id: u32, title: &str, app: &str) -> WindowInfo {
+        WindowInfo { id, title: title.to_string(), app: app.to_string(), pid: 1, bounds: [0, 0, 10, 10] }
+    }
+
+    #[test]
+    fn parses_active_app_lines() {
+        assert_eq!(
+            parse_active_app("Safari\ncom.apple.Safari\nApple - Start Page\n"),
+            Some(ActiveApp {
+                name: "Safari".to_string(),
+                bundle_id: Some("com.apple.Safari".to_string()),
+                window_title: Some("Apple - Start Page".to_string()),
+            })
+        );
+        // apps without windows (Finder on an empty desktop) still report their name
+        let finder = parse_active_app("Finder\ncom.apple.finder\n").unwrap();
+        assert_eq!(finder.window_title, None);
+        assert_eq!(parse_active_app("Dock\nmissing value\n").unwrap().bundle_id, None);
+        assert_eq!(parse_active_app("\n"), None);
+    }
+
+    #[test]
+    fn ui_tree_dump_drops_unlabeled_containers() {
+        let output = "0\tAXWindow\tNotes\t\t0\t0\t2560\t1600\n\
+            1\tAXGroup\tmissing value\tmissing value\t0\t0\t2560\t1600\n\
+            2\tAXButton\tNew Note\t\t100\t40\t60\t40\n\
+            2\tAXTextField\t\tshopping list\t200\t40\t400\t40\n\
+            not a line\n\
+            1\tControlType.MenuItem\tFile\t\n";
+        let elements = parse_ui_tree(output);
+        assert_eq!(elements.len(), 5);
+        assert_eq!(elements[2].role, "button");
+        assert_eq!(elements[3].role, "text field");
+        assert_eq!(elements[4].frame, None);
+
+        // a 2x screen, so bounds halve into AI space
+        let control = ComputerControl::with_dimensions(AI_WIDTH * 2, AI_HEIGHT * 2);
+        assert_eq!(
+            control.format_ui_tree(&elements),
+            "window \"Notes\" [0, 0, 1280, 800]\n  button \"New Note\" [50, 20, 80, 40]\n  text field value=\"shopping list\" [100, 20, 300, 40]\n  menu item \"File\"\n"
+        );
+    }
+
+    #[test]
+    fn find_window_prefers_id_then_exact_title() {
+        let windows = vec![
+            window(7, "Inbox - Mail", "Mail"),
+            window(42, "Notes", "Notes"),
+            window(9, "Release notes.txt", "TextEdit"),
+        ];
+        assert_eq!(find_window(&windows, "42").map(|w| w.id), Some(42));
+        assert_eq!(find_window(&windows, "notes").map(|w| w.id), Some(42));
+        assert_eq!(find_window(&windows, "release").map(|w| w.id), Some(9));
+        assert_eq!(find_window(&windows, "textedit").map(|w| w.id), Some(9));
+        assert_eq!(find_window(&windows, "inbox").map(|w| w.id), Some(7));
+        assert!(find_window(&windows, "Safari").is_none());
+        assert!(find_window(&windows, "  ").is_none());
+    }
+
+    #[test]
+    fn snaps_fill_a_half_or_the_screen() {
+        assert_eq!(SnapPosition::parse(" Left "), Some(SnapPosition::Left));
+        assert_eq!(SnapPosition::parse("full"), Some(SnapPosition::Maximize));
+        assert_eq!(SnapPosition::parse("top"), None);
+        assert_eq!(SnapPosition::Left.bounds(), [0, 0, 640, 800]);
+        assert_eq!(SnapPosition::Right.bounds(), [640, 0, 1280, 800]);
+        assert_eq!(SnapPosition::Maximize.bounds(), FULL_SCREEN_REGION);
+    }
+
+    #[test]
+    fn help_capture_settings_parse() {
+        assert_eq!(ScreenshotFormat::parse("PNG"), Some(ScreenshotFormat::Png));
+        assert_eq!(ScreenshotFormat::parse("jpg"), Some(ScreenshotFormat::Jpeg));
+        assert_eq!(ScreenshotFormat::parse("webp"), None);
+        assert_eq!(HelpCaptureArea::parse(" window "), Some(HelpCaptureArea::ActiveWindow));
+        assert_eq!(HelpCaptureArea::parse("region"), None);
+    }
+
+    #[test]
+    fn flat_or_missing_captures_are_blank() {
+        let black = image::RgbImage::new(320, 200);
+        assert!(is_blank_frame(&encode_capture(&black, ScreenshotFormat::Png).unwrap()));
+        let mut screen = black.clone();
+        // a menu bar is enough to count as a real screen
+        for x in 0..320 {
+            for y in 0..12 {
+                screen.put_pixel(x, y, image::Rgb([230, 230, 230]));
+            }
+        }
+        assert!(!is_blank_frame(&encode_capture(&screen, ScreenshotFormat::Png).unwrap()));
+        assert!(is_blank_frame(""));
+    }
+
+    #[test]
+    fn media_type_follows_the_encoding() {
+        let img = image::RgbImage::from_pixel(4, 4, image::Rgb([200, 10, 10]));
+        let png = encode_capture(&img, ScreenshotFormat::Png).unwrap();
+        let jpeg = encode_capture(&img, ScreenshotFormat::Jpeg).unwrap();
+        assert_eq!(media_type_of(&png), "image/png");
+        assert_eq!(media_type_of(&jpeg), "image/jpeg");
+    }
+
+    #[test]
+    fn regions_are_clamped_to_the_screen() {
+        assert_eq!(clamp_region([-50, -10, 300, 200]), [0, 0, 300, 200]);
+        assert_eq!(clamp_region([1300, 0, 1200, 900]), [1200, 0, 1280, 800]);
+        assert_eq!(clamp_region([2000, 10, 2100, 20]), FULL_SCREEN_REGION);
+    }
+
+    #[test]
+    fn fit_within_only_shrinks() {
+        let small = image::RgbImage::new(800, 600);
+        assert_eq!(fit_within(small, HELP_MAX_EDGE).dimensions(), (800, 600));
+        let large = image::RgbImage::new(3136, 1960);
+        assert_eq!(fit_within(large, HELP_MAX_EDGE).dimensions(), (1568, 980));
+    }
+}
@@ -0,0 +1,275 @@
+// destructive-action confirmation for the main agent loop
+// bash commands / computer actions that match a risky pattern are held until the
+// user answers an "await_confirmation" event via the confirm_action command.
+// opt-in: a click that lands on text like "Delete account" or "Pay now" is held too, with a
+// crop of the button so the user can see what the model was about to press
+
+use crate::computer::ComputerAction;
+use crate::ocr::TextBox;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+pub const CONFIRM_BASH_ENV: &str = "HEYWORK_CONFIRM_BASH";
+pub const CONFIRM_COMPUTER_ENV: &str = "HEYWORK_CONFIRM_COMPUTER";
+pub const CONFIRM_RISKY_CLICKS_ENV: &str = "HEYWORK_CONFIRM_RISKY_CLICKS";
+/// comma-separated words that mark a button as risky to click
+pub const RISK_KEYWORDS_ENV: &str = "HEYWORK_RISK_KEYWORDS";
+
+pub const DEFAULT_RISK_KEYWORDS: &[&str] = &["delete", "remove", "confirm", "purchase", "pay"];
+
+// how long we wait for an answer before treating it as declined
+const CONFIRM_TIMEOUT_SECS: u64 = 300;
+
+// only one confirmation can be outstanding - the agent loop runs tools sequentially
+static PENDING: Mutex<Option<oneshot::Sender<bool>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmSettings {
+    pub bash: bool,
+    pub computer: bool,
+    /// hold clicks on text matching risk_keywords; redundant while `computer` is on
+    pub risky_clicks: bool,
+    pub risk_keywords: Vec<String>,
+}
+
+fn env_flag(name: &str, default: bool) -> bool {
+    match std::env::var(name) {
+        Ok(v) => matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+        Err(_) => default,
+    }
+}
+
+impl ConfirmSettings {
+    // on for bash, off for computer actions and risky clicks unless the user opts in
+    pub fn current() -> Self {
+        Self {
+            bash: env_flag(CONFIRM_BASH_ENV, true),
+            computer: env_flag(CONFIRM_COMPUTER_ENV, false),
+            risky_clicks: env_flag(CONFIRM_RISKY_CLICKS_ENV, false),
+            risk_keywords: std::env::var(RISK_KEYWORDS_ENV)
+                .map(|v| parse_keywords(&v))
+                .unwrap_or_else(|_| DEFAULT_RISK_KEYWORDS.iter().map(|k| k.to_string()).collect()),
+        }
+    }
+}
+
+/// lower-cased, de-duplicated keywords from a comma-separated list
+pub fn parse_keywords(value: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for keyword in value.split(',').map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty()) {
+        if !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
+// a word of the text starts with the keyword, so "pay" catches "Payment" but not "Display".
+// multi-word keywords ("sign out") match across the words' separators
+fn mentions(text: &str, keyword: &str) -> bool {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {}", words.join(" ")).contains(&format!(" {}", keyword))
+}
+
+// how far outside every text box a click can land and still count as on the nearest one
+const NEAR_CLICK_PX: i64 = 24;
+
+/// whether `text` (an OCR label or an element's accessible name) mentions one of `keywords`
+pub fn is_risky_text(text: &str, keywords: &[String]) -> bool {
+    keywords.iter().any(|k| mentions(text, k))
+}
+
+/// the risky text under `point` (pixels in the same image as the boxes), if any. a click
+/// inside a box is judged by that box alone; only a click between boxes falls back to
+/// risky text within NEAR_CLICK_PX
+pub fn risky_label(boxes: &[TextBox], point: (u32, u32), keywords: &[String]) -> Option<String> {
+    let is_risky = |b: &TextBox| is_risky_text(&b.text, keywords);
+    // squared distance from the point to the box's edge, 0 inside it
+    let distance = |b: &TextBox| {
+        let gap = |p: u32, start: u32, len: u32| {
+            let (p, start, end) = (i64::from(p), i64::from(start), i64::from(start + len));
+            (start - p).max(p - end).max(0)
+        };
+        gap(point.0, b.x, b.width).pow(2) + gap(point.1, b.y, b.height).pow(2)
+    };
+    if let Some(hit) = boxes.iter().find(|b| distance(b) == 0) {
+        return is_risky(hit).then(|| hit.text.trim().to_string());
+    }
+    boxes
+        .iter()
+        .filter(|b| is_risky(b) && distance(b) <= NEAR_CLICK_PX.pow(2))
+        .min_by_key(|b| distance(b))
+        .map(|b| b.text.trim().to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfirmationRequest {
+    pub tool: String,
+    pub summary: String,
+    pub reason: String,
+    /// base64 jpeg of what's being acted on, shown next to the question
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<String>,
+}
+
+/// why a bash command needs confirmation, if it does
+pub fn destructive_bash_reason(command: &str) -> Option<&'static str> {
+    let lower = command.to_lowercase();
+    // split on shell separators so "cd x && rm -rf y" is caught
+    for segment in lower.split(|c| c == ';' || c == '|' || c == '&' || c == '\n') {
+        let words: Vec<&str> = segment.split_whitespace().collect();
+        let words = match words.first() {
+            Some(&"sudo") => &words[1..],
+            _ => &words[..],
+        };
+        let Some(program) = words.first() else { continue };
+        let program = program.rsplit('/').next().unwrap_or(program);
+
+        let reason = match program {
+            "rm" | "rmdir" | "unlink" | "shred" | "srm" => Some("deletes files"),
+            "dd" => Some("writes raw data to a device or file"),
+            "killall" | "pkill" => Some("kills processes"),
+            "kill" if words.iter().any(|w| *w == "-9" || *w == "-kill") => Some("force-kills a process"),
+            p if p.starts_with("mkfs") => Some("formats a filesystem"),
+            "diskutil" if words.iter().any(|w| w.starts_with("erase") || *w == "partitiondisk") => {
+                Some("erases a disk")
+            }
+            // mv without -n/-i can silently overwrite the destination
+            "mv" if !words.iter().any(|w| w.starts_with('-') && (w.contains('n') || w.contains('i'))) => {
+                Some("moves files and may overwrite existing ones")
+            }
+            "git" if words.get(1).is_some_and(|w| *w == "clean" || *w == "reset") => {
+                Some("discards local changes")
+            }
+            "find" if words.iter().any(|w| *w == "-delete") => Some("deletes files"),
+            _ => None,
+        };
+        if reason.is_some() {
+            return reason;
+        }
+        // truncating redirect onto a file (e.g. "> ~/notes.txt")
+        let overwrites = segment
+            .split(" > ")
+            .skip(1)
+            .any(|target| !target.trim_start().starts_with("/dev/null"));
+        if overwrites {
+            return Some("overwrites a file");
+        }
+    }
+    None
+}
+
+/// why a computer action needs confirmation, if it does
+pub fn risky_computer_reason(action: &ComputerAction) -> Option<&'static str> {
+    match action.action.as_str() {
+        "left_click" | "right_click" | "double_click" | "triple_click" | "middle_click" | "left_click_drag" => {
+            Some("clicks on screen")
+        }
+        "key" => {
+            let combo = action.text.as_deref().unwrap_or("").to_lowercase().replace(' ', "");
+            let risky = ["cmd+q", "super+q", "alt+f4", "cmd+backspace", "cmd+delete", "shift+delete", "ctrl+alt+delete"];
+            risky
+                .iter()
+                .any(|r| combo.contains(r))
+                .then_some("quits an app or deletes items")
+        }
+        _ => None,
+    }
+}
+
+/// emit "await_confirmation" and wait for confirm_action; declines on timeout or stop
+pub async fn request_confirmation(
+    app_handle: &AppHandle,
+    running: &AtomicBool,
+    request: ConfirmationRequest,
+) -> bool {
+    let (tx, mut rx) = oneshot::channel();
+    // a stale sender (answer never came) is dropped here, which reads as declined
+    *PENDING.lock().unwrap() = Some(tx);
+
+    println!("[confirm] Waiting for approval: {} ({})", request.summary, request.reason);
+    let _ = app_handle.emit("await_confirmation", request);
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(CONFIRM_TIMEOUT_SECS);
+    let approved = loop {
+        tokio::select! {
+            answer = &mut rx => break answer.unwrap_or(false),
+            _ = tokio::time::sleep(std::time::Duration::from_millis(250)) => {
+                if !running.load(Ordering::SeqCst) || tokio::time::Instant::now() >= deadline {
+                    break false;
+                }
+            }
+        }
+    };
+
+    PENDING.lock().unwrap().take();
+    let _ = app_handle.emit("confirmation_resolved", approved);
+    println!("[confirm] {}", if approved { "Approved" } else { "Declined" });
+    approved
+}
+
+// answer the outstanding confirmation
+#[tauri::command]
+pub fn confirm_action(approved: bool) -> Result<(), String> {
+    let sender = PENDING
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| "No action is waiting for confirmation".to_string())?;
+    let _ = sender.send(approved);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_destructive_bash() {
+        assert!(destructive_bash_reason("rm -rf ~/Downloads/*").is_some());
+        assert!(destructive_bash_reason("cd /tmp && sudo rm file").is_some());
+        assert!(destructive_bash_reason("/bin/rm x").is_some());
+        assert!(destructive_bash_reason("mv a.txt b.txt").is_some());
+        assert!(destructive_bash_reason("killall Safari").is_some());
+        assert!(destructive_bash_reason("mkfs.ext4 /dev/sdb1").is_some());
+        assert!(destructive_bash_reason("find . -name '*.log' -delete").is_some());
+        assert!(destructive_bash_reason("echo hi > notes.txt").is_some());
+    }
+
+    #[test]
+    fn allows_safe_bash() {
+        assert!(destructive_bash_reason("ls -la ~/Downloads").is_none());
+        assert!(destructive_bash_reason("mv -n a.txt b.txt").is_none());
+        assert!(destructive_bash_reason("echo hi | grep h").is_none());
+        assert!(destructive_bash_reason("git status").is_none());
+        assert!(destructive_bash_reason("open -a Safari").is_none());
+        assert!(destructive_bash_reason("make build > /dev/null").is_none());
+    }
+
+    #[test]
+    fn risky_labels_prefer_the_box_under_the_click() {
+        let text_box = |text: &str, x: u32| TextBox { text: text.to_string(), x, y: 10, width: 80, height: 20 };
+        let keywords = parse_keywords("Delete, pay,, sign out, delete");
+        assert_eq!(keywords, vec!["delete", "pay", "sign out"]);
+
+        let boxes = [text_box("Display settings", 0), text_box("Cancel", 100), text_box("Delete account", 200)];
+        // on "Cancel" - the risky neighbour doesn't count
+        assert_eq!(risky_label(&boxes, (140, 20), &keywords), None);
+        assert_eq!(risky_label(&boxes, (220, 20), &keywords).as_deref(), Some("Delete account"));
+        // in the gap between two boxes, the nearby risky one counts
+        assert_eq!(risky_label(&boxes, (190, 20), &keywords).as_deref(), Some("Delete account"));
+        assert_eq!(risky_label(&boxes, (600, 20), &keywords), None);
+        let boxes = [text_box("Payment", 0), text_box("Deleted items", 300)];
+        assert_eq!(risky_label(&boxes, (320, 20), &keywords).as_deref(), Some("Deleted items"));
+        assert_eq!(risky_label(&[text_box("Sign out", 0)], (0, 0), &keywords).as_deref(), Some("Sign out"));
+        assert_eq!(risky_label(&[text_box("Display", 0), text_box("Sign in", 90)], (0, 0), &keywords), None);
+    }
+}
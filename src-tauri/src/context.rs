@@ -0,0 +1,466 @@
+// context budget management for the agent loop
+// estimates what the conversation costs in tokens and, only once it nears the model's
+// context window, summarizes the oldest large tool results first - browser snapshots
+// down to their interactive elements, bash/python output down to its head and tail.
+// also repairs tool call / result pairing a hard-stopped run can leave broken
+
+use crate::api::{estimate_message_tokens, ContentBlock, Message, ToolResultContent};
+
+pub const CONTEXT_BUDGET_ENV: &str = "HEYWORK_CONTEXT_BUDGET_TOKENS";
+pub const SUMMARIZE_THRESHOLD_ENV: &str = "HEYWORK_SUMMARIZE_THRESHOLD";
+pub const KEEP_FULL_RESULTS_ENV: &str = "HEYWORK_KEEP_FULL_RESULTS";
+
+// every current claude model has a 200k window; leave room for system prompt, tools and output
+const CONTEXT_WINDOW_TOKENS: u32 = 200_000;
+const DEFAULT_BUDGET_TOKENS: u32 = CONTEXT_WINDOW_TOKENS / 4 * 3;
+const DEFAULT_SUMMARIZE_THRESHOLD: usize = 5000;
+const DEFAULT_KEEP_FULL: usize = 1;
+
+// lines kept from the start / end of a summarized command output (errors tend to be at the end)
+const OUTPUT_HEAD_LINES: usize = 20;
+const OUTPUT_TAIL_LINES: usize = 40;
+
+/// result given to a tool call whose run was cut off before it returned
+pub const INTERRUPTED_TOOL_RESULT: &str = "Interrupted, no result captured.";
+
+const SNAPSHOT_SUMMARY_PREFIX: &str = "[snapshot summarized";
+const OUTPUT_SUMMARY_PREFIX: &str = "[output summarized";
+
+#[derive(Debug, Clone, Copy)]
+pub struct ContextConfig {
+    /// estimated conversation tokens above which old results get summarized
+    pub budget_tokens: u32,
+    /// tool results shorter than this (in chars) are never summarized
+    pub summarize_threshold: usize,
+    /// how many of the most recent snapshots / outputs stay in full
+    pub keep_full: usize,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            budget_tokens: DEFAULT_BUDGET_TOKENS,
+            summarize_threshold: DEFAULT_SUMMARIZE_THRESHOLD,
+            keep_full: DEFAULT_KEEP_FULL,
+        }
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+impl ContextConfig {
+    /// defaults, overridden by HEYWORK_CONTEXT_BUDGET_TOKENS / _SUMMARIZE_THRESHOLD / _KEEP_FULL_RESULTS
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            budget_tokens: env_number(CONTEXT_BUDGET_ENV).unwrap_or(defaults.budget_tokens),
+            summarize_threshold: env_number(SUMMARIZE_THRESHOLD_ENV).unwrap_or(defaults.summarize_threshold),
+            keep_full: env_number(KEEP_FULL_RESULTS_ENV).unwrap_or(defaults.keep_full),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultKind {
+    Snapshot,
+    Output,
+}
+
+/// what a trim pass did, for logging
+#[derive(Debug, Default)]
+pub struct TrimReport {
+    pub tokens_before: u32,
+    pub tokens_after: u32,
+    pub snapshots: usize,
+    pub outputs: usize,
+}
+
+impl TrimReport {
+    pub fn reclaimed(&self) -> u32 {
+        self.tokens_before.saturating_sub(self.tokens_after)
+    }
+}
+
+fn classify(text: &str) -> Option<ResultKind> {
+    if text.starts_with(SNAPSHOT_SUMMARY_PREFIX) || text.starts_with(OUTPUT_SUMMARY_PREFIX) {
+        None // already summarized
+    } else if text.starts_with("uid=") {
+        Some(ResultKind::Snapshot)
+    } else {
+        Some(ResultKind::Output)
+    }
+}
+
+/// Summarize old tool results, oldest first, until the conversation fits the budget.
+/// Returns None when nothing was summarized.
+pub fn trim_context(messages: &mut [Message], config: &ContextConfig) -> Option<TrimReport> {
+    let tokens_before = estimate_message_tokens(messages);
+    if tokens_before <= config.budget_tokens {
+        return None;
+    }
+
+    // (message, block, item, kind) of every large tool result, oldest first
+    let mut candidates: Vec<(usize, usize, usize, ResultKind)> = Vec::new();
+    for (m, message) in messages.iter().enumerate() {
+        if message.role != "user" {
+            continue;
+        }
+        for (b, block) in message.content.iter().enumerate() {
+            let ContentBlock::ToolResult { content, .. } = block else { continue };
+            for (i, item) in content.iter().enumerate() {
+                if let ToolResultContent::Text { text } = item {
+                    if text.len() > config.summarize_threshold {
+                        if let Some(kind) = classify(text) {
+                            candidates.push((m, b, i, kind));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // the newest `keep_full` results of each kind are what the model is working from
+    for kind in [ResultKind::Snapshot, ResultKind::Output] {
+        let total = candidates.iter().filter(|c| c.3 == kind).count();
+        let mut seen = 0;
+        candidates.retain(|c| {
+            if c.3 != kind {
+                return true;
+            }
+            seen += 1;
+            seen + config.keep_full <= total
+        });
+    }
+
+    let mut report = TrimReport { tokens_before, ..Default::default() };
+    let mut tokens = tokens_before;
+
+    for (m, b, i, kind) in candidates {
+        if tokens <= config.budget_tokens {
+            break;
+        }
+        let ContentBlock::ToolResult { content, .. } = &mut messages[m].content[b] else { continue };
+        let ToolResultContent::Text { text } = &mut content[i] else { continue };
+
+        let summary = match kind {
+            ResultKind::Snapshot => summarize_snapshot(text),
+            ResultKind::Output => summarize_output(text, config.summarize_threshold),
+        };
+        if summary.len() >= text.len() {
+            continue;
+        }
+        tokens = tokens.saturating_sub(((text.len() - summary.len()) / 4) as u32);
+        *text = summary;
+        match kind {
+            ResultKind::Snapshot => report.snapshots += 1,
+            ResultKind::Output => report.outputs += 1,
+        }
+    }
+
+    if report.snapshots + report.outputs == 0 {
+        return None;
+    }
+    report.tokens_after = estimate_message_tokens(messages);
+    Some(report)
+}
+
+/// what a repair pass changed, for logging
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SequenceRepair {
+    /// tool calls given an interrupted result
+    pub filled: usize,
+    /// tool results dropped for answering no call in the turn before
+    pub dropped: usize,
+}
+
+impl SequenceRepair {
+    pub fn is_empty(&self) -> bool {
+        self.filled == 0 && self.dropped == 0
+    }
+}
+
+fn tool_use_ids(message: &Message) -> Vec<String> {
+    if message.role != "assistant" {
+        return Vec::new();
+    }
+    message
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, .. } => Some(id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// make every tool call answered by a result in the next user turn, and every result answer a
+/// call, so the api accepts the history. a run stopped mid-tool leaves calls without results;
+/// those get INTERRUPTED_TOOL_RESULT, and results with no call are dropped
+pub fn repair_message_sequence(messages: &mut Vec<Message>) -> SequenceRepair {
+    let mut repair = SequenceRepair::default();
+    let mut i = 0;
+    while i < messages.len() {
+        if messages[i].role != "assistant" {
+            let calls = if i == 0 { Vec::new() } else { tool_use_ids(&messages[i - 1]) };
+            let before = messages[i].content.len();
+            messages[i].content.retain(|block| match block {
+                ContentBlock::ToolResult { tool_use_id, .. } => calls.contains(tool_use_id),
+                _ => true,
+            });
+            repair.dropped += before - messages[i].content.len();
+            if messages[i].content.is_empty() {
+                messages.remove(i);
+                continue;
+            }
+            i += 1;
+            continue;
+        }
+
+        let calls = tool_use_ids(&messages[i]);
+        if calls.is_empty() {
+            i += 1;
+            continue;
+        }
+        if messages.get(i + 1).is_none_or(|next| next.role != "user") {
+            messages.insert(i + 1, Message { role: "user".to_string(), content: Vec::new() });
+        }
+        let answers = &mut messages[i + 1].content;
+        let missing: Vec<ContentBlock> = calls
+            .into_iter()
+            .filter(|id| {
+                !answers
+                    .iter()
+                    .any(|block| matches!(block, ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == id))
+            })
+            .map(|id| ContentBlock::ToolResult {
+                tool_use_id: id,
+                content: vec![ToolResultContent::Text { text: INTERRUPTED_TOOL_RESULT.to_string() }],
+            })
+            .collect();
+        repair.filled += missing.len();
+        // results go ahead of anything else in the turn
+        let at = answers.iter().take_while(|block| matches!(block, ContentBlock::ToolResult { .. })).count();
+        answers.splice(at..at, missing);
+        i += 1;
+    }
+    repair
+}
+
+/// keep only interactive elements (links, buttons, inputs, headings) of a browser snapshot
+pub fn summarize_snapshot(snapshot: &str) -> String {
+    // keep only lines with interactive roles
+    let interactive_roles = [
+        "link", "button", "textbox", "checkbox", "radio", "combobox",
+        "searchbox", "slider", "switch", "menuitem", "tab", "heading",
+        "WebArea", // keep the root
+    ];
+
+    let mut summary_lines: Vec<&str> = Vec::new();
+    let mut kept_count = 0;
+    let mut total_count = 0;
+
+    for line in snapshot.lines() {
+        total_count += 1;
+        let trimmed = line.trim();
+
+        // keep line if it contains any interactive role
+        let should_keep = interactive_roles.iter().any(|role| {
+            // match "uid=X_Y role" pattern
+            trimmed.contains(&format!(" {} ", role)) ||
+            trimmed.contains(&format!(" {} \"", role)) ||
+            trimmed.ends_with(&format!(" {}", role))
+        });
+
+        if should_keep {
+            summary_lines.push(line);
+            kept_count += 1;
+        }
+    }
+
+    let header = format!(
+        "{}: {} interactive elements from {} total]\n",
+        SNAPSHOT_SUMMARY_PREFIX, kept_count, total_count
+    );
+
+    header + &summary_lines.join("\n")
+}
+
+/// keep the first / last lines of bash or python output, capped at `max_chars`
+fn summarize_output(output: &str, max_chars: usize) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    let body = if lines.len() > OUTPUT_HEAD_LINES + OUTPUT_TAIL_LINES {
+        format!(
+            "{}\n... ({} lines omitted) ...\n{}",
+            lines[..OUTPUT_HEAD_LINES].join("\n"),
+            lines.len() - OUTPUT_HEAD_LINES - OUTPUT_TAIL_LINES,
+            lines[lines.len() - OUTPUT_TAIL_LINES..].join("\n")
+        )
+    } else {
+        output.to_string()
+    };
+
+    // a few huge lines (minified json, base64) still need a hard cap
+    let body = if body.chars().count() > max_chars {
+        let half = max_chars / 2;
+        let head: String = body.chars().take(half).collect();
+        let tail: String = body.chars().rev().take(half).collect::<Vec<_>>().into_iter().rev().collect();
+        format!("{}\n... (truncated) ...\n{}", head, tail)
+    } else {
+        body
+    };
+
+    format!(
+        "{}: {} lines, {} chars originally]\n{}",
+        OUTPUT_SUMMARY_PREFIX,
+        lines.len(),
+        output.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_result(text: String) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: vec![ContentBlock::ToolResult {
+                tool_use_id: "t".to_string(),
+                content: vec![ToolResultContent::Text { text }],
+            }],
+        }
+    }
+
+    fn result_text(message: &Message) -> &str {
+        match &message.content[0] {
+            ContentBlock::ToolResult { content, .. } => match &content[0] {
+                ToolResultContent::Text { text } => text,
+                _ => "",
+            },
+            _ => "",
+        }
+    }
+
+    fn snapshot(lines: usize) -> String {
+        let mut s = String::from("uid=1_0 WebArea \"page\"\n");
+        for i in 0..lines {
+            s.push_str(&format!("  uid=1_{} StaticText \"filler text number {}\"\n", i + 1, i));
+        }
+        s.push_str("  uid=1_999 button \"Submit\"");
+        s
+    }
+
+    #[test]
+    fn leaves_context_alone_under_budget() {
+        let mut messages = vec![tool_result(snapshot(500)), tool_result(snapshot(500))];
+        let config = ContextConfig { budget_tokens: 1_000_000, ..Default::default() };
+        assert!(trim_context(&mut messages, &config).is_none());
+        assert!(result_text(&messages[0]).starts_with("uid="));
+    }
+
+    #[test]
+    fn reports_nothing_when_only_kept_results_are_large() {
+        let mut messages = vec![tool_result(snapshot(500))];
+        let config = ContextConfig { budget_tokens: 1, ..Default::default() };
+        assert!(trim_context(&mut messages, &config).is_none());
+        assert!(result_text(&messages[0]).starts_with("uid="));
+    }
+
+    #[test]
+    fn summarizes_oldest_and_keeps_latest_full() {
+        let mut messages = vec![
+            tool_result(snapshot(500)),
+            tool_result("x\n".repeat(5000)),
+            tool_result(snapshot(500)),
+        ];
+        let config = ContextConfig { budget_tokens: 1, ..Default::default() };
+        let report = trim_context(&mut messages, &config).unwrap();
+
+        assert_eq!(report.snapshots, 1);
+        // the only large output is also the latest one of its kind
+        assert_eq!(report.outputs, 0);
+        assert!(report.reclaimed() > 0);
+        assert!(result_text(&messages[0]).starts_with(SNAPSHOT_SUMMARY_PREFIX));
+        assert!(result_text(&messages[0]).contains("button \"Submit\""));
+        assert!(result_text(&messages[1]).starts_with("x\n"));
+        assert!(result_text(&messages[2]).starts_with("uid="));
+    }
+
+    #[test]
+    fn stops_once_back_under_budget() {
+        let mut messages = vec![
+            tool_result(snapshot(500)),
+            tool_result(snapshot(500)),
+            tool_result(snapshot(500)),
+        ];
+        let full = estimate_message_tokens(&messages);
+        // dropping one snapshot's filler is enough to fit
+        let config = ContextConfig { budget_tokens: full - 1000, ..Default::default() };
+        let report = trim_context(&mut messages, &config).unwrap();
+        assert_eq!(report.snapshots, 1);
+        assert!(result_text(&messages[1]).starts_with("uid="));
+    }
+
+    #[test]
+    fn dangling_tool_calls_get_interrupted_results() {
+        let message = |role: &str, content: Vec<ContentBlock>| Message { role: role.to_string(), content };
+        let call = |id: &str| ContentBlock::ToolUse { id: id.to_string(), name: "bash".to_string(), input: serde_json::json!({}) };
+        let result = |id: &str| ContentBlock::ToolResult {
+            tool_use_id: id.to_string(),
+            content: vec![ToolResultContent::Text { text: "ok".to_string() }],
+        };
+        let text = |t: &str| ContentBlock::Text { text: t.to_string() };
+        let mut messages = vec![
+            message("user", vec![text("tidy up")]),
+            // b's result never came back
+            message("assistant", vec![call("a"), call("b")]),
+            message("user", vec![result("a")]),
+            // stopped before any result, then the user typed again
+            message("assistant", vec![call("c")]),
+            message("user", vec![text("carry on"), result("stale")]),
+            // the hard stop itself
+            message("assistant", vec![text("checking"), call("d")]),
+        ];
+
+        let repair = repair_message_sequence(&mut messages);
+        assert_eq!(repair, SequenceRepair { filled: 3, dropped: 1 });
+        let answered: Vec<Vec<&str>> = messages
+            .iter()
+            .filter(|m| m.role == "user")
+            .map(|m| {
+                m.content
+                    .iter()
+                    .map(|block| match block {
+                        ContentBlock::ToolResult { tool_use_id, content } => match &content[0] {
+                            ToolResultContent::Text { text } if text == INTERRUPTED_TOOL_RESULT => "interrupted",
+                            _ => tool_use_id.as_str(),
+                        },
+                        ContentBlock::Text { text } => text.as_str(),
+                        _ => "?",
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            answered,
+            vec![vec!["tidy up"], vec!["a", "interrupted"], vec!["interrupted", "carry on"], vec!["interrupted"]]
+        );
+        assert_eq!(messages.len(), 7);
+        // an already valid history is left alone
+        assert!(repair_message_sequence(&mut messages).is_empty());
+    }
+
+    #[test]
+    fn output_summary_keeps_head_and_tail() {
+        let output: String = (0..500).map(|i| format!("line {}\n", i)).collect();
+        let summary = summarize_output(&output, 5000);
+        assert!(summary.starts_with(OUTPUT_SUMMARY_PREFIX));
+        assert!(summary.contains("line 0\n"));
+        assert!(summary.contains("line 499"));
+        assert!(!summary.contains("line 100\n"));
+        assert!(summary.len() < output.len());
+    }
+}
@@ -0,0 +1,275 @@
+// per-domain logins for the browser agent. secrets live in the OS keychain and only ever
+// travel from there into the page - the model sees which domain was filled, never the values
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// one keychain entry per domain under this service, holding the credential as json
+const KEYRING_SERVICE: &str = "com.heywork.app.credentials";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credential {
+    pub username: String,
+    pub password: String,
+    /// also fill on subdomains (accounts.example.com for an example.com login). off by
+    /// default - subdomains can host other people's pages, like sites.google.com
+    #[serde(default)]
+    pub include_subdomains: bool,
+}
+
+/// snapshot uids of the fields autofill_login will type into
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoginFields {
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+// the keychain can't list entries, so the saved domains (no secrets) are kept alongside
+fn index_path() -> PathBuf {
+    crate::storage::data_dir().join("credential_domains.json")
+}
+
+fn read_index() -> Vec<String> {
+    std::fs::read_to_string(index_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(domains: &[String]) -> Result<(), String> {
+    let path = index_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(domains).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+fn entry(domain: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, domain).map_err(|e| e.to_string())
+}
+
+/// "https://www.GitHub.com/login" -> "github.com". accepts bare domains and full urls
+pub fn normalize_domain(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    let without_scheme = trimmed.split_once("://").map(|(_, rest)| rest).unwrap_or(trimmed);
+    let authority = without_scheme.split(['/', '?', '#']).next().unwrap_or("");
+    // drop any user@ prefix and :port suffix
+    let host = authority.rsplit('@').next().unwrap_or("");
+    let host = host.split(':').next().unwrap_or("").trim_end_matches('.').to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+
+    let valid = host.contains('.')
+        && host
+            .split('.')
+            .all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    if valid {
+        Ok(host)
+    } else {
+        Err(format!("'{}' isn't a domain, e.g. github.com", input.trim()))
+    }
+}
+
+/// the host itself, then each parent domain - so accounts.google.com can find a google.com
+/// login that opted in to subdomains
+pub fn candidate_domains(host: &str) -> Vec<String> {
+    let labels: Vec<&str> = host.split('.').collect();
+    (0..labels.len().saturating_sub(1)).map(|start| labels[start..].join(".")).collect()
+}
+
+/// logins are only ever typed into pages served over https
+pub fn is_secure_url(url: &str) -> bool {
+    url.trim()
+        .split_once("://")
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case("https"))
+}
+
+/// whether a login saved under `domain` may be filled on `host`: the exact host, or a
+/// subdomain when the login opted in
+pub fn login_applies(domain: &str, host: &str, credential: &Credential) -> bool {
+    domain == host || (credential.include_subdomains && host.ends_with(&format!(".{}", domain)))
+}
+
+/// the saved login for a page's url, with the domain it was saved under
+pub fn lookup(url: &str) -> Option<(String, Credential)> {
+    if !is_secure_url(url) {
+        return None;
+    }
+    let host = normalize_domain(url).ok()?;
+    let saved = read_index();
+    candidate_domains(&host)
+        .into_iter()
+        .filter(|domain| saved.contains(domain))
+        .find_map(|domain| {
+            let secret = entry(&domain).ok()?.get_password().ok()?;
+            let credential: Credential = serde_json::from_str(&secret).ok()?;
+            login_applies(&domain, &host, &credential).then_some((domain, credential))
+        })
+}
+
+const PASSWORD_HINTS: &[&str] = &["password", "passwort", "contraseña", "mot de passe", "passcode"];
+const USERNAME_HINTS: &[&str] = &["email", "e-mail", "user", "login", "account", "phone", "identifier"];
+
+/// pick the username and password inputs from snapshot elements given as (uid, role, name),
+/// in page order. search boxes never count. a textbox named like a password is the password
+/// field; the username is the nearest textbox before it, or - on a username-only step - the
+/// first textbox named like one
+pub fn pick_login_fields(elements: &[(String, String, String)]) -> LoginFields {
+    let textboxes: Vec<(usize, &str, String)> = elements
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, role, _))| matches!(role.as_str(), "textbox" | "combobox"))
+        .map(|(index, (uid, _, name))| (index, uid.as_str(), name.to_lowercase()))
+        .filter(|(_, _, name)| !name.contains("search"))
+        .collect();
+    let named = |hints: &[&str], name: &str| hints.iter().any(|hint| name.contains(hint));
+
+    let password = textboxes.iter().find(|(_, _, name)| named(PASSWORD_HINTS, name));
+    let username = match password {
+        // the form's own field sits right before the password, whatever a header box is called
+        Some((password_index, _, _)) => textboxes
+            .iter()
+            .rev()
+            .find(|(index, _, name)| index < password_index && !named(PASSWORD_HINTS, name)),
+        None => textboxes.iter().find(|(_, _, name)| named(USERNAME_HINTS, name)),
+    };
+
+    LoginFields {
+        username: username.map(|(_, uid, _)| uid.to_string()),
+        password: password.map(|(_, uid, _)| uid.to_string()),
+    }
+}
+
+// store a login for a domain in the keychain, replacing any saved one
+#[tauri::command(rename_all = "camelCase")]
+pub fn save_credential(domain: String, username: String, password: String, include_subdomains: bool) -> Result<String, String> {
+    let domain = normalize_domain(&domain)?;
+    if password.is_empty() {
+        return Err("Password can't be empty".to_string());
+    }
+    let credential = Credential { username, password, include_subdomains };
+    let secret = serde_json::to_string(&credential).map_err(|e| e.to_string())?;
+    entry(&domain)?.set_password(&secret).map_err(|e| e.to_string())?;
+
+    let mut domains = read_index();
+    if !domains.contains(&domain) {
+        domains.push(domain.clone());
+        domains.sort();
+        write_index(&domains)?;
+    }
+    Ok(domain)
+}
+
+// domains with a saved login - never the usernames or passwords
+#[tauri::command]
+pub fn list_credential_domains() -> Vec<String> {
+    read_index()
+}
+
+#[tauri::command]
+pub fn delete_credential(domain: String) -> Result<(), String> {
+    let domain = normalize_domain(&domain)?;
+    match entry(&domain)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+    let mut domains = read_index();
+    domains.retain(|saved| saved != &domain);
+    write_index(&domains)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(uid: &str, role: &str, name: &str) -> (String, String, String) {
+        (uid.to_string(), role.to_string(), name.to_string())
+    }
+
+    #[test]
+    fn domains_normalize_from_urls() {
+        assert_eq!(normalize_domain("https://www.GitHub.com/login?next=/").unwrap(), "github.com");
+        assert_eq!(normalize_domain("accounts.google.com:443").unwrap(), "accounts.google.com");
+        assert_eq!(normalize_domain(" example.org ").unwrap(), "example.org");
+        assert!(normalize_domain("localhost").is_err());
+        assert!(normalize_domain("not a domain").is_err());
+    }
+
+    #[test]
+    fn parent_domains_are_candidates() {
+        assert_eq!(
+            candidate_domains("login.live.com"),
+            vec!["login.live.com".to_string(), "live.com".to_string()]
+        );
+        assert_eq!(candidate_domains("github.com"), vec!["github.com".to_string()]);
+    }
+
+    fn login(include_subdomains: bool) -> Credential {
+        Credential { username: "me".to_string(), password: "pw".to_string(), include_subdomains }
+    }
+
+    #[test]
+    fn logins_fill_only_on_https() {
+        assert!(is_secure_url("https://github.com/login"));
+        assert!(is_secure_url("HTTPS://github.com"));
+        assert!(!is_secure_url("http://github.com/login"));
+        assert!(!is_secure_url("github.com"));
+    }
+
+    #[test]
+    fn subdomains_need_an_opt_in() {
+        assert!(login_applies("google.com", "google.com", &login(false)));
+        assert!(!login_applies("google.com", "sites.google.com", &login(false)));
+        assert!(login_applies("google.com", "accounts.google.com", &login(true)));
+        assert!(!login_applies("google.com", "evilgoogle.com", &login(true)));
+    }
+
+    #[test]
+    fn finds_labelled_login_fields() {
+        let elements = [
+            element("1_2", "searchbox", "Search"),
+            element("1_5", "textbox", "Email address"),
+            element("1_6", "textbox", "Password"),
+            element("1_7", "button", "Sign in"),
+        ];
+        let fields = pick_login_fields(&elements);
+        assert_eq!(fields.username.as_deref(), Some("1_5"));
+        assert_eq!(fields.password.as_deref(), Some("1_6"));
+    }
+
+    #[test]
+    fn unlabelled_username_is_the_box_before_the_password() {
+        let elements = [
+            element("2_1", "textbox", ""),
+            element("2_3", "textbox", ""),
+            element("2_4", "textbox", "Password"),
+        ];
+        assert_eq!(pick_login_fields(&elements).username.as_deref(), Some("2_3"));
+    }
+
+    #[test]
+    fn header_search_fields_never_get_the_username() {
+        let elements = [
+            element("4_1", "searchbox", "Search accounts"),
+            element("4_2", "textbox", "Search accounts"),
+            element("4_3", "textbox", "Find a user"),
+            element("4_7", "textbox", ""),
+            element("4_8", "textbox", "Password"),
+        ];
+        assert_eq!(pick_login_fields(&elements).username.as_deref(), Some("4_7"));
+        assert_eq!(
+            pick_login_fields(&[element("4_1", "searchbox", "Search accounts"), element("4_2", "button", "Next")]),
+            LoginFields::default()
+        );
+    }
+
+    #[test]
+    fn username_step_without_a_password_field() {
+        let elements = [element("3_1", "textbox", "Email or phone"), element("3_2", "button", "Next")];
+        assert_eq!(
+            pick_login_fields(&elements),
+            LoginFields { username: Some("3_1".to_string()), password: None }
+        );
+        assert_eq!(pick_login_fields(&[element("3_9", "button", "Next")]), LoginFields::default());
+    }
+}
@@ -14,7 +14,10 @@
 use crate::api::{AnthropicClient, ContentBlock, Message};
 use crate::browser::{BrowserClient, SharedBrowserClient};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
 
 // ============================================================
 // Data Structures
@@ -45,6 +48,65 @@ pub struct ResearchSource {
     pub published_date: Option<String>,
 }
 
+/// Knobs for a research run. Named presets cover the common cases;
+/// the deep_research tool input can override any individual field.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ResearchConfig {
+    pub max_sources: usize,
+    pub max_queries: usize,
+    pub per_source_timeout_ms: u64,
+    pub follow_links: bool,
+}
+
+impl ResearchConfig {
+    /// "quick" / "standard" / "exhaustive" ("deep" is accepted as an alias)
+    pub fn preset(name: &str) -> Self {
+        match normalize_depth(name) {
+            "quick" => Self { max_sources: 3, max_queries: 3, per_source_timeout_ms: 8_000, follow_links: true },
+            "exhaustive" => Self { max_sources: 10, max_queries: 8, per_source_timeout_ms: 15_000, follow_links: true },
+            _ => Self { max_sources: 5, max_queries: 5, per_source_timeout_ms: 12_000, follow_links: true },
+        }
+    }
+
+    /// start from the preset named by `depth`, then apply any explicit overrides
+    pub fn from_tool_input(depth: &str, input: &serde_json::Value) -> Self {
+        let mut config = Self::preset(depth);
+        if let Some(v) = input.get("max_sources").and_then(|v| v.as_u64()) {
+            config.max_sources = (v as usize).clamp(1, 30);
+        }
+        if let Some(v) = input.get("max_queries").and_then(|v| v.as_u64()) {
+            config.max_queries = (v as usize).clamp(1, 15);
+        }
+        if let Some(v) = input.get("per_source_timeout_ms").and_then(|v| v.as_u64()) {
+            config.per_source_timeout_ms = v.clamp(2_000, 60_000);
+        }
+        if let Some(v) = input.get("follow_links").and_then(|v| v.as_bool()) {
+            config.follow_links = v;
+        }
+        config
+    }
+
+    /// one-line description for status updates
+    pub fn summary(&self) -> String {
+        format!(
+            "{} queries, up to {} sources, {}s per source{}",
+            self.max_queries,
+            self.max_sources,
+            self.per_source_timeout_ms / 1000,
+            if self.follow_links { "" } else { ", snippets only" }
+        )
+    }
+}
+
+/// map user-facing depth names onto the preset names
+pub fn normalize_depth(depth: &str) -> &'static str {
+    match depth {
+        "quick" => "quick",
+        "deep" | "exhaustive" => "exhaustive",
+        _ => "standard",
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeepResearchReport {
     pub original_query: String,
@@ -56,6 +118,10 @@ pub struct DeepResearchReport {
     pub follow_up_questions: Vec<String>,
     pub confidence_score: f32,
     pub research_depth: String,
+    pub config: ResearchConfig,
+    /// stopped by the user before synthesis; `sources` holds what was gathered so far
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 // ============================================================
@@ -127,15 +193,10 @@ const PAGE_CONTENT_EXTRACT_JS: &str = r#"
 async fn generate_search_queries(
     original_query: &str,
     depth: &str,
+    num_queries: usize,
     api_key: &str,
     model: &str,
 ) -> Vec<ResearchQuery> {
-    let num_queries = match depth {
-        "quick" => 3,
-        "standard" => 5,
-        "deep" => 8,
-        _ => 5,
-    };
 
     let client = AnthropicClient::new(api_key.to_string(), model.to_string());
 
@@ -153,7 +214,7 @@ Return ONLY the JSON array."#,
         content: vec![ContentBlock::Text { text: prompt }],
     }];
 
-    match client.complete(None, messages, None).await {
+    match client.complete_cached(None, messages, None).await {
         Ok(result) => {
             let text: String = result.content.iter()
                 .filter_map(|b| if let ContentBlock::Text { text } = b { Some(text.as_str()) } else { None })
@@ -166,28 +227,28 @@ Return ONLY the JSON array."#,
                             ResearchQuery {
                                 query: q.get("query").and_then(|v| v.as_str()).unwrap_or(original_query).to_string(),
                                 intent: q.get("intent").and_then(|v| v.as_str()).unwrap_or("related").to_string(),
-                                priority: (100 - (idx as u32 * 10)).max(10),
+                                priority: 100u32.saturating_sub(idx as u32 * 10).max(10),
                             }
-                        }).collect();
+                        }).take(num_queries).collect();
                     }
                 }
             }
-            fallback_queries(original_query, depth)
+            fallback_queries(original_query, depth, num_queries)
         }
         Err(e) => {
             println!("[deep_research] LLM query gen failed: {}, using fallback", e);
-            fallback_queries(original_query, depth)
+            fallback_queries(original_query, depth, num_queries)
         }
     }
 }
 
-fn fallback_queries(query: &str, depth: &str) -> Vec<ResearchQuery> {
+fn fallback_queries(query: &str, depth: &str, max_queries: usize) -> Vec<ResearchQuery> {
     let mut queries = vec![
         ResearchQuery { query: query.to_string(), intent: "primary".to_string(), priority: 100 },
     ];
-    let extras: Vec<String> = match depth {
+    let extras: Vec<String> = match normalize_depth(depth) {
         "quick" => vec![format!("{} 2025 2026", query)],
-        "deep" => vec![
+        "exhaustive" => vec![
             format!("{} comprehensive guide 2025 2026", query),
             format!("{} latest research findings", query),
             format!("{} expert analysis opinions", query),
@@ -206,6 +267,7 @@ fn fallback_queries(query: &str, depth: &str) -> Vec<ResearchQuery> {
             query: q, intent: "related".to_string(), priority: 80 - (i as u32 * 10),
         });
     }
+    queries.truncate(max_queries.max(1));
     queries
 }
 
@@ -346,9 +408,9 @@ async fn llm_synthesize(
         }
     }
 
-    let depth_instruction = match depth {
+    let depth_instruction = match normalize_depth(depth) {
         "quick" => "Provide a focused, concise answer.",
-        "deep" => "Provide an exhaustive, highly detailed analysis covering every angle.",
+        "exhaustive" => "Provide an exhaustive, highly detailed analysis covering every angle.",
         _ => "Provide a thorough, well-structured answer.",
     };
 
@@ -444,15 +506,12 @@ fn default_follow_ups(query: &str) -> Vec<String> {
 async fn research_with_claude_web_search(
     query: &str,
     depth: &str,
+    config: &ResearchConfig,
     api_key: &str,
     model: &str,
 ) -> (Vec<ResearchSource>, String, Vec<String>, Vec<String>) {
-    let max_searches = match depth {
-        "quick" => 5,
-        "standard" => 10,
-        "deep" => 20,
-        _ => 10,
-    };
+    // server-side search gets a couple of tries per planned query
+    let max_searches = (config.max_queries * 2) as u32;
 
     let client = AnthropicClient::new(api_key.to_string(), model.to_string());
 
@@ -532,28 +591,90 @@ fn extract_bullet_points(text: &str, section_keyword: &str) -> Vec<String> {
 // Main Orchestrator
 // ============================================================
 
+// how often the watcher re-checks the agent's running flag
+const CANCEL_POLL_MS: u64 = 200;
+
+/// cancel `token` as soon as the agent's running flag drops (user hit stop)
+fn watch_running(running: Arc<AtomicBool>, token: CancellationToken) {
+    tokio::spawn(async move {
+        loop {
+            if !running.load(Ordering::SeqCst) {
+                println!("[deep_research] 🛑 Stop requested — cancelling research");
+                token.cancel();
+                break;
+            }
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_millis(CANCEL_POLL_MS)) => {}
+            }
+        }
+    });
+}
+
+/// run `fut` unless the token fires first; on cancel the future is dropped,
+/// which aborts any HTTP / CDP request it has in flight
+async fn cancellable<F: std::future::Future>(token: &CancellationToken, fut: F) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => None,
+        out = fut => Some(out),
+    }
+}
+
+/// stand-in for the LLM synthesis when the run was stopped early
+fn partial_synthesis(sources: &[ResearchSource]) -> String {
+    if sources.is_empty() {
+        return "Research was cancelled before any sources were gathered.".to_string();
+    }
+    let mut text = format!(
+        "Research was cancelled after gathering {} source{}. No synthesis was run; here is what was found so far:\n\n",
+        sources.len(),
+        if sources.len() == 1 { "" } else { "s" }
+    );
+    for source in sources {
+        let snippet = if source.snippet.is_empty() { &source.content } else { &source.snippet };
+        let snippet: String = snippet.chars().take(200).collect();
+        text.push_str(&format!("- **{}** ({}): {}\n", source.title, source.url, snippet.trim()));
+    }
+    text
+}
+
 /// Perform deep research:
 ///   1. Try Chrome (Google search + full page reading)
 ///   2. Pass ALL content to Claude LLM for polished synthesis
 ///   3. Falls back to Claude's built-in web_search if Chrome unavailable
+///
+/// Stops promptly once `running` goes false, returning a partial report
+/// (`cancelled: true`) with the sources gathered up to that point.
 pub async fn perform_deep_research(
     query: &str,
     depth: &str,
+    config: &ResearchConfig,
     api_key: &str,
     model: &str,
     browser_client: &SharedBrowserClient,
+    running: &Arc<AtomicBool>,
 ) -> Result<DeepResearchReport, String> {
     let start_time = std::time::Instant::now();
     println!("[deep_research] ========================================");
-    println!("[deep_research] Starting research: \"{}\" (depth: {})", query, depth);
+    println!("[deep_research] Starting research: \"{}\" (depth: {}, {})", query, depth, config.summary());
     println!("[deep_research] ========================================");
 
+    let token = CancellationToken::new();
+    watch_running(running.clone(), token.clone());
+    // stop the watcher when we return, whichever way
+    let _watch_guard = token.clone().drop_guard();
+
     // Step 1: Generate smart search queries using LLM
-    let research_queries = generate_search_queries(query, depth, api_key, model).await;
+    let research_queries = cancellable(&token, generate_search_queries(query, depth, config.max_queries, api_key, model))
+        .await
+        .unwrap_or_default();
     println!("[deep_research] Generated {} search queries", research_queries.len());
 
     // Step 2: Try Chrome-based research first
-    let (all_sources, synthesized_answer, key_findings, follow_up_questions) = {
+    let (all_sources, synthesized_answer, key_findings, follow_up_questions) = if token.is_cancelled() {
+        (vec![], String::new(), vec![], vec![])
+    } else {
         let mut guard = browser_client.lock().await;
         let browser_was_open = guard.is_some();
 
@@ -584,10 +705,13 @@ pub async fn perform_deep_research(
             let mut results: Vec<ResearchResult> = Vec::new();
 
             for (idx, rq) in research_queries.iter().enumerate() {
+                if token.is_cancelled() {
+                    break;
+                }
                 println!("[deep_research] 🔍 [{}/{}] ({}): \"{}\"", idx + 1, research_queries.len(), rq.intent, rq.query);
 
-                match timeout(Duration::from_secs(20), chrome_search(&rq.query, browser, idx)).await {
-                    Ok(sources) => {
+                match cancellable(&token, timeout(Duration::from_secs(20), chrome_search(&rq.query, browser, idx))).await {
+                    Some(Ok(sources)) => {
                         results.push(ResearchResult {
                             query: rq.query.clone(),
                             sources: sources.clone(),
@@ -596,9 +720,10 @@ pub async fn perform_deep_research(
                         });
                         all_sources.extend(sources);
                     }
-                    Err(_) => {
+                    Some(Err(_)) => {
                         println!("[deep_research] ⏰ Search timed out: \"{}\"", rq.query);
                     }
+                    None => break,
                 }
             }
 
@@ -607,21 +732,21 @@ pub async fn perform_deep_research(
             all_sources.retain(|s| seen.insert(s.url.clone()));
             println!("[deep_research] 📊 {} unique sources found", all_sources.len());
 
-            // Phase 1b: Visit top pages and extract FULL content
-            let max_pages = match depth {
-                "quick" => 3,
-                "standard" => 5,
-                "deep" => 8,
-                _ => 5,
-            };
+            all_sources.truncate(config.max_sources);
 
-            for source in all_sources.iter_mut().take(max_pages) {
-                match timeout(Duration::from_secs(12), chrome_read_page(&source.url, browser)).await {
-                    Ok(Some(content)) => {
+            // Phase 1b: Visit top pages and extract FULL content
+            if config.follow_links {
+                let per_source = Duration::from_millis(config.per_source_timeout_ms);
+                for source in all_sources.iter_mut() {
+                    if token.is_cancelled() {
+                        break;
+                    }
+                    if let Some(Ok(Some(content))) =
+                        cancellable(&token, timeout(per_source, chrome_read_page(&source.url, browser))).await
+                    {
                         source.content = content;
                         source.credibility_score = 0.95; // higher for pages we actually read
                     }
-                    _ => {}
                 }
             }
 
@@ -633,34 +758,49 @@ pub async fn perform_deep_research(
                 let _ = browser.select_page(original_selected, false).await;
             } else {
                 let _ = browser.close_all_pages().await;
-                *guard = None;
-                #[cfg(target_os = "macos")]
-                let _ = std::process::Command::new("osascript")
-                    .args(["-e", "tell application \"Google Chrome\" to quit"])
-                    .output();
-                #[cfg(target_os = "windows")]
-                let _ = std::process::Command::new("taskkill")
-                    .args(["/IM", "chrome.exe", "/T"])
-                    .output();
-                println!("[deep_research] ✅ Chrome closed");
+                let kind = crate::browser::selected_browser_kind();
+                match guard.take() {
+                    // only its own browser, not every window of that kind
+                    Some(client) if client.is_ephemeral() => client.disconnect().await,
+                    _ => crate::browser::quit_browser(kind, false),
+                }
+                println!("[deep_research] ✅ {} closed", kind);
             }
 
-            // Phase 2: LLM Synthesis — the quality layer
-            println!("[deep_research] 🧠 Sending {} sources to Claude for synthesis...", all_sources.len());
-            let (synthesis, findings, follow_ups) =
-                llm_synthesize(query, &all_sources, depth, api_key, model).await;
-
-            (all_sources, synthesis, findings, follow_ups)
+            if token.is_cancelled() {
+                (all_sources, String::new(), vec![], vec![])
+            } else {
+                // Phase 2: LLM Synthesis — the quality layer
+                println!("[deep_research] 🧠 Sending {} sources to Claude for synthesis...", all_sources.len());
+                match cancellable(&token, llm_synthesize(query, &all_sources, depth, api_key, model)).await {
+                    Some((synthesis, findings, follow_ups)) => (all_sources, synthesis, findings, follow_ups),
+                    None => (all_sources, String::new(), vec![], vec![]),
+                }
+            }
         } else {
             // ====== FALLBACK: Claude's built-in web_search ======
             drop(guard);
             println!("[deep_research] 📡 Chrome unavailable — using Claude's built-in web search");
-            research_with_claude_web_search(query, depth, api_key, model).await
+            match cancellable(&token, research_with_claude_web_search(query, depth, config, api_key, model)).await {
+                Some((mut sources, synthesis, findings, follow_ups)) => {
+                    sources.truncate(config.max_sources);
+                    (sources, synthesis, findings, follow_ups)
+                }
+                // web search returns sources and synthesis in one response, so nothing is salvageable
+                None => (vec![], String::new(), vec![], vec![]),
+            }
         }
     };
 
+    let cancelled = token.is_cancelled();
+    let synthesized_answer = if cancelled { partial_synthesis(&all_sources) } else { synthesized_answer };
+
     let elapsed = start_time.elapsed();
-    println!("[deep_research] ✅ Research complete in {:.1}s ({} sources)", elapsed.as_secs_f64(), all_sources.len());
+    if cancelled {
+        println!("[deep_research] 🛑 Research cancelled after {:.1}s ({} sources)", elapsed.as_secs_f64(), all_sources.len());
+    } else {
+        println!("[deep_research] ✅ Research complete in {:.1}s ({} sources)", elapsed.as_secs_f64(), all_sources.len());
+    }
 
     let confidence = if all_sources.iter().any(|s| !s.content.is_empty()) && all_sources.len() >= 5 {
         0.95
@@ -686,10 +826,166 @@ pub async fn perform_deep_research(
         sources: all_sources,
         follow_up_questions,
         confidence_score: confidence,
-        research_depth: depth.to_string(),
+        research_depth: normalize_depth(depth).to_string(),
+        config: *config,
+        cancelled,
     })
 }
 
+// ============================================================
+// Report Persistence
+// ============================================================
+
+/// A research report saved to disk so its sources can be revisited later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedResearchReport {
+    pub id: String,
+    pub created_at: i64,
+    pub report: DeepResearchReport,
+}
+
+/// Lightweight listing entry (no sources or synthesis)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchReportMeta {
+    pub id: String,
+    pub query: String,
+    pub created_at: i64,
+    pub research_depth: String,
+    pub source_count: usize,
+}
+
+fn reports_dir() -> std::path::PathBuf {
+    crate::storage::data_dir().join("research")
+}
+
+/// filesystem-safe slug of the query for report ids and export names
+fn slugify(text: &str) -> String {
+    let slug: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-");
+    slug.chars().take(48).collect::<String>().trim_end_matches('-').to_string()
+}
+
+// ids come back from the frontend - refuse anything that could escape the reports dir
+fn report_path(id: &str) -> Result<std::path::PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("invalid report id: {}", id));
+    }
+    Ok(reports_dir().join(format!("{}.json", id)))
+}
+
+/// Save a finished report; returns its id (`<unix ts>-<query slug>`)
+pub fn save_report(report: &DeepResearchReport) -> Result<String, String> {
+    let created_at = chrono::Utc::now().timestamp();
+    let id = format!("{}-{}", created_at, slugify(&report.original_query));
+    let saved = SavedResearchReport { id: id.clone(), created_at, report: report.clone() };
+
+    let dir = reports_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create research dir: {e}"))?;
+    let json = serde_json::to_string_pretty(&saved).map_err(|e| e.to_string())?;
+    std::fs::write(report_path(&id)?, json).map_err(|e| format!("failed to save report: {e}"))?;
+
+    println!("[deep_research] Saved report {}", id);
+    Ok(id)
+}
+
+/// All saved reports, newest first
+pub fn list_reports() -> Result<Vec<ResearchReportMeta>, String> {
+    let entries = match std::fs::read_dir(reports_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(vec![]),
+    };
+
+    let mut metas: Vec<ResearchReportMeta> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|json| serde_json::from_str::<SavedResearchReport>(&json).ok())
+        .map(|saved| ResearchReportMeta {
+            id: saved.id,
+            query: saved.report.original_query,
+            created_at: saved.created_at,
+            research_depth: saved.report.research_depth,
+            source_count: saved.report.sources.len(),
+        })
+        .collect();
+
+    metas.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(metas)
+}
+
+pub fn load_report(id: &str) -> Result<SavedResearchReport, String> {
+    let json = std::fs::read_to_string(report_path(id)?)
+        .map_err(|_| format!("research report not found: {}", id))?;
+    serde_json::from_str(&json).map_err(|e| format!("corrupt research report: {e}"))
+}
+
+/// Export a saved report as Markdown or PDF into ~/Documents/hey-work/research.
+/// PDF goes through the python document helpers (create_professional_report).
+pub async fn export_report(id: &str, format: &str) -> Result<crate::files::CreatedFile, String> {
+    let saved = load_report(id)?;
+    let report = &saved.report;
+
+    let dir = dirs::document_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("hey-work")
+        .join("research");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create export dir: {e}"))?;
+
+    let stem = format!("research-{}", slugify(&report.original_query));
+    let path = match format {
+        "md" | "markdown" => {
+            let path = dir.join(format!("{}.md", stem));
+            std::fs::write(&path, format_research_report(report))
+                .map_err(|e| format!("failed to write markdown: {e}"))?;
+            path
+        }
+        "pdf" => {
+            let path = dir.join(format!("{}.pdf", stem));
+            let sources: Vec<String> = report
+                .sources
+                .iter()
+                .enumerate()
+                .map(|(i, s)| format!("{}. {} — {}", i + 1, s.title, s.url))
+                .collect();
+            let payload = serde_json::json!({
+                "title": format!("Deep Research: {}", report.original_query),
+                "sections": {
+                    "Report": report.synthesized_answer,
+                    "Key Findings": report.key_findings,
+                    "Sources": sources,
+                    "Follow-up Questions": report.follow_up_questions,
+                },
+                "output_path": path.to_string_lossy(),
+            });
+            // base64 so quotes/newlines in the report can't break the script
+            let encoded = base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                payload.to_string(),
+            );
+            let code = format!(
+                "import base64, json\n\
+                 data = json.loads(base64.b64decode('{}').decode('utf-8'))\n\
+                 create_professional_report(data['title'], data['sections'], data['output_path'], 'modern')\n",
+                encoded
+            );
+            let result = crate::python_tool::execute_python_enhanced(&code, None, Some("document")).await?;
+            if !result.success {
+                return Err(format!("PDF export failed: {}", result.errors.join("; ")));
+            }
+            path
+        }
+        other => return Err(format!("unsupported export format: {}", other)),
+    };
+
+    println!("[deep_research] Exported report {} to {}", id, path.display());
+    crate::files::CreatedFile::from_path(&path.to_string_lossy())
+        .ok_or_else(|| "export finished but the file is missing".to_string())
+}
+
 // ============================================================
 // Report Formatting & Detection
 // ============================================================
@@ -706,7 +1002,7 @@ pub fn format_research_report(report: &DeepResearchReport) -> String {
     format!(
         r#"# Deep Research: {}
 
-**Depth:** {} | **Confidence:** {:.0}% | **Sources:** {} | **Method:** {}
+**Depth:** {} ({}) | **Confidence:** {:.0}% | **Sources:** {} | **Method:** {}
 
 ---
 
@@ -726,6 +1022,7 @@ pub fn format_research_report(report: &DeepResearchReport) -> String {
 "#,
         report.original_query,
         report.research_depth,
+        report.config.summary(),
         report.confidence_score * 100.0,
         report.sources.len(),
         method,
@@ -791,6 +1088,24 @@ mod tests {
         assert_eq!(get_research_depth("tell me about AI"), "standard");
     }
 
+    #[test]
+    fn test_research_config_overrides() {
+        assert_eq!(ResearchConfig::preset("deep"), ResearchConfig::preset("exhaustive"));
+
+        let input = serde_json::json!({ "max_sources": 2, "follow_links": false });
+        let config = ResearchConfig::from_tool_input("standard", &input);
+        assert_eq!(config.max_sources, 2);
+        assert!(!config.follow_links);
+        assert_eq!(config.max_queries, ResearchConfig::preset("standard").max_queries);
+    }
+
+    #[test]
+    fn test_report_ids_are_safe() {
+        assert_eq!(slugify("What's new in Rust 2026?"), "what-s-new-in-rust-2026");
+        assert!(report_path("../etc/passwd").is_err());
+        assert!(report_path("1700000000-rust").is_ok());
+    }
+
     #[test]
     fn test_parse_search_results() {
         let json = r#"[{"title":"Test","url":"https://example.com","snippet":"A snippet"}]"#;
@@ -0,0 +1,324 @@
+// generated-file helpers - open / reveal artifacts produced by the python tool
+// only paths under the allowlisted output roots can be opened, so these commands
+// can't be used as an arbitrary launcher
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// extensions we never hand to the platform opener
+const BLOCKED_EXTENSIONS: &[&str] = &[
+    "app", "command", "sh", "bash", "zsh", "exe", "bat", "cmd", "ps1", "msi",
+    "scpt", "applescript", "pkg", "dmg", "jar", "workflow",
+    // windows script hosts, shortcuts and registry imports
+    "js", "vbs", "wsf", "hta", "lnk", "url", "scr", "com", "reg",
+    // macos terminal sessions and location files that open whatever they point at
+    "terminal", "webloc", "inetloc",
+];
+
+/// a generated file (python artifact, browser download) with enough metadata for the UI to open it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedFile {
+    pub path: String,
+    pub name: String,
+    pub size_bytes: u64,
+    pub mime_type: String,
+}
+
+impl CreatedFile {
+    /// stat a path on disk; returns None if the file is missing
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = Path::new(path);
+        let meta = std::fs::metadata(path).ok()?;
+        if !meta.is_file() {
+            return None;
+        }
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        Some(Self {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size_bytes: meta.len(),
+            mime_type: mime_type_for_path(&path).to_string(),
+            path: path.to_string_lossy().to_string(),
+        })
+    }
+
+    /// human-readable size (e.g. "12.4 KB")
+    pub fn size_display(&self) -> String {
+        let bytes = self.size_bytes as f64;
+        if bytes < 1024.0 {
+            format!("{} B", self.size_bytes)
+        } else if bytes < 1024.0 * 1024.0 {
+            format!("{:.1} KB", bytes / 1024.0)
+        } else {
+            format!("{:.1} MB", bytes / (1024.0 * 1024.0))
+        }
+    }
+}
+
+pub fn mime_type_for_path(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pdf" => "application/pdf",
+        "html" | "htm" => "text/html",
+        "md" => "text/markdown",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "svg" => "image/svg+xml",
+        "gif" => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+/// env var (saved in .env): the folder bash starts in and python's relative paths resolve against
+pub const WORKSPACE_DIR_ENV: &str = "HEYWORK_WORKSPACE_DIR";
+// under the home folder when the user hasn't picked one
+const DEFAULT_WORKSPACE_NAME: &str = "HeyWork";
+
+/// expand a user-entered folder ("~/Work", "/Users/me/out"); relative paths have nothing to
+/// be relative to, so they're refused
+pub fn expand_dir(input: &str) -> Result<PathBuf, String> {
+    let input = input.trim();
+    let path = match input.strip_prefix("~/").or((input == "~").then_some("")) {
+        Some(rest) => dirs::home_dir().ok_or("no home directory")?.join(rest),
+        None => PathBuf::from(input),
+    };
+    if path.is_absolute() {
+        Ok(path)
+    } else {
+        Err(format!("Use a full path for the workspace, e.g. ~/HeyWork (got \"{}\")", input))
+    }
+}
+
+// folders that belong to the OS or installed software - never a workspace, nor inside one
+#[cfg(not(target_os = "windows"))]
+const SYSTEM_DIRS: &[&str] = &[
+    "/System", "/Library", "/Applications", "/private", "/usr", "/bin", "/sbin", "/etc", "/var",
+    "/opt", "/dev", "/proc", "/sys", "/boot", "/lib", "/lib64", "/root", "/snap",
+];
+#[cfg(target_os = "windows")]
+const SYSTEM_DIRS: &[&str] = &["C:\\Windows", "C:\\Program Files", "C:\\Program Files (x86)", "C:\\ProgramData"];
+
+// drop `.` and resolve `..` without touching the disk, so "~/Work/../.." can't sneak past
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// a workspace the sandbox can be narrowed to: everything under it becomes writable, so the
+/// filesystem root, the home folder (or anything above it) and system folders are refused
+pub fn check_workspace(dir: &Path, home: Option<&Path>) -> Result<PathBuf, String> {
+    let dir = normalize_lexically(dir);
+    let shown = dir.display();
+    if dir.parent().is_none() {
+        return Err(format!("{} is the whole disk - pick a folder inside your home folder", shown));
+    }
+    if let Some(home) = home {
+        if home.starts_with(&dir) {
+            return Err(format!("{} contains your whole home folder - pick a folder inside it, e.g. ~/HeyWork", shown));
+        }
+    }
+    if let Some(system) = SYSTEM_DIRS.iter().find(|system| dir.starts_with(system)) {
+        return Err(format!("{} is inside the system folder {} - pick a folder of your own", shown, system));
+    }
+    Ok(dir)
+}
+
+/// expand and check a user-entered workspace folder
+pub fn parse_workspace(input: &str) -> Result<PathBuf, String> {
+    check_workspace(&expand_dir(input)?, dirs::home_dir().as_deref())
+}
+
+/// the workspace folder, whether or not it exists yet
+pub fn workspace_dir() -> PathBuf {
+    std::env::var(WORKSPACE_DIR_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .and_then(|value| parse_workspace(&value).ok())
+        .unwrap_or_else(|| {
+            dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(DEFAULT_WORKSPACE_NAME)
+        })
+}
+
+/// the workspace folder, created on first use
+pub fn ensure_workspace() -> Result<PathBuf, String> {
+    let dir = workspace_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create workspace {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// directories generated files are allowed to live in - the workspace first
+pub fn allowed_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = [
+        Some(workspace_dir()),
+        dirs::desktop_dir(),
+        dirs::document_dir(),
+        dirs::download_dir(),
+        Some(std::env::temp_dir()),
+        Some(crate::storage::data_dir()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // canonicalize so symlinked roots (e.g. /tmp -> /private/tmp on macOS) still match
+    for root in roots.iter_mut() {
+        if let Ok(canon) = root.canonicalize() {
+            *root = canon;
+        }
+    }
+    roots
+}
+
+/// resolve a user-supplied path and make sure it exists inside an allowed root
+pub fn validate_path(path: &str) -> Result<PathBuf, String> {
+    let expanded = if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir().ok_or("no home directory")?.join(rest)
+    } else {
+        PathBuf::from(path)
+    };
+
+    let canon = expanded
+        .canonicalize()
+        .map_err(|_| format!("File not found: {}", path))?;
+
+    if !allowed_roots().iter().any(|root| canon.starts_with(root)) {
+        return Err(format!("Path is outside the allowed output folders: {}", path));
+    }
+
+    Ok(canon)
+}
+
+fn is_blocked_extension(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| BLOCKED_EXTENSIONS.contains(&ext.as_str()))
+}
+
+// open a generated file in its default app
+#[tauri::command]
+pub fn open_file(path: String) -> Result<(), String> {
+    let path = validate_path(&path)?;
+    if !path.is_file() {
+        return Err("Only files can be opened".to_string());
+    }
+    if is_blocked_extension(&path) {
+        return Err("Refusing to open executable file".to_string());
+    }
+
+    println!("[files] Opening {}", path.display());
+
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("open");
+        c.arg(&path);
+        c
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("explorer");
+        c.arg(&path);
+        c
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = {
+        let mut c = std::process::Command::new("xdg-open");
+        c.arg(&path);
+        c
+    };
+
+    cmd.spawn().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// reveal a generated file in Finder / Explorer / the file manager
+#[tauri::command]
+pub fn open_file_location(path: String) -> Result<(), String> {
+    let path = validate_path(&path)?;
+
+    println!("[files] Revealing {}", path.display());
+
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("open");
+        c.arg("-R").arg(&path);
+        c
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("explorer");
+        c.arg(format!("/select,{}", path.display()));
+        c
+    };
+
+    // no portable "select" on linux - open the containing folder
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = {
+        let dir = if path.is_dir() {
+            path.clone()
+        } else {
+            path.parent().map(Path::to_path_buf).unwrap_or_else(|| path.clone())
+        };
+        let mut c = std::process::Command::new("xdg-open");
+        c.arg(dir);
+        c
+    };
+
+    cmd.spawn().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn workspace_cant_widen_the_sandbox() {
+        let home = Path::new("/Users/me");
+        let check = |dir: &str| check_workspace(Path::new(dir), Some(home));
+        assert!(check("/").is_err());
+        assert!(check("/Users/me").is_err());
+        assert!(check("/Users/me/").is_err());
+        assert!(check("/Users").is_err());
+        assert!(check("/Users/me/Work/../..").is_err());
+        assert!(check("/System/Library").is_err());
+        assert!(check("/etc").is_err());
+        assert!(check("/usr/local/share").is_err());
+        assert_eq!(check("/Users/me/HeyWork").unwrap(), PathBuf::from("/Users/me/HeyWork"));
+        assert_eq!(check("/Users/me/Work/./out").unwrap(), PathBuf::from("/Users/me/Work/out"));
+        assert!(check("/Volumes/Data/projects").is_ok());
+    }
+
+    #[test]
+    fn launchers_and_scripts_are_blocked() {
+        assert!(is_blocked_extension(Path::new("invoice.VBS")));
+        assert!(is_blocked_extension(Path::new("setup.hta")));
+        assert!(is_blocked_extension(Path::new("session.terminal")));
+        assert!(is_blocked_extension(Path::new("link.webloc")));
+        assert!(!is_blocked_extension(Path::new("report.pdf")));
+        assert!(!is_blocked_extension(Path::new("data.json")));
+    }
+}
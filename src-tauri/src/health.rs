@@ -0,0 +1,215 @@
+// system_healthcheck: every subsystem the agent leans on, checked side by side so the settings
+// page can show one checklist. a check that hangs or panics only fails its own row
+
+use crate::permissions::PermissionStatus;
+use serde::Serialize;
+use std::future::Future;
+use std::time::Duration;
+
+// no single check may hold up the report longer than this
+const CHECK_TIMEOUT_SECS: u64 = 8;
+
+// ordered from best to worst, so a report's status is the max of its checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Ok,
+    /// works without it, but a feature is off (an optional key, no debuggable browser)
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// the worst status among the checks
+    pub status: HealthStatus,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthCheck {
+    fn new(name: &str, status: HealthStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status, detail: detail.into() }
+    }
+}
+
+impl HealthReport {
+    pub fn new(checks: Vec<HealthCheck>) -> Self {
+        let status = checks.iter().map(|check| check.status).max().unwrap_or(HealthStatus::Ok);
+        Self { status, checks }
+    }
+}
+
+/// a macos privacy permission as a checklist row; `required` ones are errors when missing
+pub fn permission_check(name: &str, status: &PermissionStatus, required: bool) -> HealthCheck {
+    let missing = if required { HealthStatus::Error } else { HealthStatus::Warning };
+    match status {
+        PermissionStatus::Granted => HealthCheck::new(name, HealthStatus::Ok, "Granted"),
+        PermissionStatus::NotNeeded => HealthCheck::new(name, HealthStatus::Ok, "Not needed on this platform"),
+        PermissionStatus::NotAsked => HealthCheck::new(name, missing, "Not requested yet"),
+        PermissionStatus::Denied => HealthCheck::new(name, missing, "Denied - allow it in System Settings > Privacy & Security"),
+    }
+}
+
+/// run one check on its own task with a deadline, so a panic or hang is reported as that check failing
+pub async fn guarded<F>(name: &str, timeout: Duration, check: F) -> HealthCheck
+where
+    F: Future<Output = HealthCheck> + Send + 'static,
+{
+    match tokio::time::timeout(timeout, tokio::spawn(check)).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => HealthCheck::new(name, HealthStatus::Error, format!("Check crashed: {}", e)),
+        Err(_) => HealthCheck::new(name, HealthStatus::Error, format!("Check timed out after {}s", timeout.as_secs())),
+    }
+}
+
+async fn check_permissions() -> Vec<HealthCheck> {
+    let checked = tokio::task::spawn_blocking(crate::permissions::check_permissions).await;
+    match checked {
+        Ok(permissions) => vec![
+            permission_check("Accessibility", &permissions.accessibility, true),
+            permission_check("Screen recording", &permissions.screen_recording, true),
+            permission_check("Microphone permission", &permissions.microphone, false),
+        ],
+        Err(e) => vec![HealthCheck::new("Permissions", HealthStatus::Error, format!("Check crashed: {}", e))],
+    }
+}
+
+async fn check_api_key(service: &'static str, name: &'static str, required: bool) -> HealthCheck {
+    match crate::permissions::validate_api_key(service.to_string()).await {
+        Ok(validation) if validation.valid => {
+            let detail = match validation.tier {
+                Some(tier) => format!("{} ({})", validation.message, tier),
+                None => validation.message,
+            };
+            HealthCheck::new(name, HealthStatus::Ok, detail)
+        }
+        Ok(validation) => {
+            let status = if required { HealthStatus::Error } else { HealthStatus::Warning };
+            HealthCheck::new(name, status, validation.message)
+        }
+        // couldn't reach the service, so the key may be fine
+        Err(e) => HealthCheck::new(name, HealthStatus::Warning, e),
+    }
+}
+
+async fn check_python() -> HealthCheck {
+    const NAME: &str = "Python";
+    let version = tokio::process::Command::new("python3").arg("--version").output().await;
+    let version = match version {
+        Ok(out) if out.status.success() => {
+            // older pythons print the version on stderr
+            let text = if out.stdout.is_empty() { out.stderr } else { out.stdout };
+            String::from_utf8_lossy(&text).trim().to_string()
+        }
+        Ok(out) => return HealthCheck::new(NAME, HealthStatus::Error, format!("python3 exited with {}", out.status)),
+        Err(e) => return HealthCheck::new(NAME, HealthStatus::Error, format!("python3 not found: {}", e)),
+    };
+    match crate::python_tool::missing_packages().await {
+        Ok(missing) if missing.is_empty() => HealthCheck::new(NAME, HealthStatus::Ok, format!("{}, all packages installed", version)),
+        // they're installed on the first python call, so this only slows that call down
+        Ok(missing) => HealthCheck::new(
+            NAME,
+            HealthStatus::Warning,
+            format!("{}, missing {} (installed on first use)", version, missing.join(", ")),
+        ),
+        Err(e) => HealthCheck::new(NAME, HealthStatus::Warning, format!("{}, {}", version, e)),
+    }
+}
+
+async fn check_browser() -> HealthCheck {
+    if crate::browser::debug_port_reachable().await {
+        HealthCheck::new("Browser debugging", HealthStatus::Ok, "Chrome answers on the debug port")
+    } else {
+        HealthCheck::new(
+            "Browser debugging",
+            HealthStatus::Warning,
+            "Nothing on the debug port - browser mode launches Chrome, or use Restart with debugging",
+        )
+    }
+}
+
+async fn check_microphone_device() -> HealthCheck {
+    const NAME: &str = "Microphone";
+    let device = tokio::task::spawn_blocking(|| {
+        use cpal::traits::{DeviceTrait, HostTrait};
+        let device = cpal::default_host().default_input_device()?;
+        Some(device.name().unwrap_or_else(|_| "default input".to_string()))
+    })
+    .await;
+    match device {
+        Ok(Some(name)) => HealthCheck::new(NAME, HealthStatus::Ok, name),
+        Ok(None) => HealthCheck::new(NAME, HealthStatus::Warning, "No input device - voice mode won't hear you"),
+        Err(e) => HealthCheck::new(NAME, HealthStatus::Error, format!("Check crashed: {}", e)),
+    }
+}
+
+// readiness of permissions, api keys, python, the browser and the mic - one row per check
+#[tauri::command]
+pub async fn system_healthcheck() -> HealthReport {
+    let timeout = Duration::from_secs(CHECK_TIMEOUT_SECS);
+    let permissions = async {
+        match tokio::time::timeout(timeout, check_permissions()).await {
+            Ok(checks) => checks,
+            Err(_) => vec![HealthCheck::new("Permissions", HealthStatus::Error, "Check timed out")],
+        }
+    };
+    let (permissions, anthropic, deepgram, elevenlabs, python, browser, microphone) = tokio::join!(
+        permissions,
+        guarded("Anthropic API key", timeout, check_api_key("anthropic", "Anthropic API key", true)),
+        guarded("Deepgram API key", timeout, check_api_key("deepgram", "Deepgram API key", false)),
+        guarded("ElevenLabs API key", timeout, check_api_key("elevenlabs", "ElevenLabs API key", false)),
+        guarded("Python", timeout, check_python()),
+        guarded("Browser debugging", timeout, check_browser()),
+        guarded("Microphone", timeout, check_microphone_device()),
+    );
+
+    let mut checks = permissions;
+    checks.extend([anthropic, deepgram, elevenlabs, python, browser, microphone]);
+    HealthReport::new(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_permissions_fail_only_when_required() {
+        assert_eq!(permission_check("A", &PermissionStatus::Granted, true).status, HealthStatus::Ok);
+        assert_eq!(permission_check("A", &PermissionStatus::NotNeeded, true).status, HealthStatus::Ok);
+        assert_eq!(permission_check("A", &PermissionStatus::Denied, true).status, HealthStatus::Error);
+        assert_eq!(permission_check("M", &PermissionStatus::NotAsked, false).status, HealthStatus::Warning);
+    }
+
+    #[test]
+    fn report_status_is_the_worst_check() {
+        let ok = HealthCheck::new("a", HealthStatus::Ok, "");
+        let warning = HealthCheck::new("b", HealthStatus::Warning, "");
+        let error = HealthCheck::new("c", HealthStatus::Error, "");
+        assert_eq!(HealthReport::new(vec![ok.clone(), warning.clone()]).status, HealthStatus::Warning);
+        assert_eq!(HealthReport::new(vec![error, ok, warning]).status, HealthStatus::Error);
+        assert_eq!(HealthReport::new(Vec::new()).status, HealthStatus::Ok);
+    }
+
+    fn broken_check() -> HealthCheck {
+        panic!("boom")
+    }
+
+    #[tokio::test]
+    async fn a_hung_or_panicking_check_only_fails_itself() {
+        let hung = guarded("slow", Duration::from_millis(20), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            HealthCheck::new("slow", HealthStatus::Ok, "")
+        });
+        let panicked = guarded("broken", Duration::from_secs(1), async { broken_check() });
+        let (hung, panicked) = tokio::join!(hung, panicked);
+        assert_eq!((hung.name.as_str(), hung.status), ("slow", HealthStatus::Error));
+        assert_eq!((panicked.name.as_str(), panicked.status), ("broken", HealthStatus::Error));
+    }
+}
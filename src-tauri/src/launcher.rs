@@ -0,0 +1,288 @@
+// the launch tool: open several apps and urls in one call instead of one agent turn each.
+// app names resolve case-insensitively against what's installed, and a miss only fails that item
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// most targets one launch call will open
+pub const MAX_LAUNCH_TARGETS: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledApp {
+    pub name: String,
+    /// what the platform opener takes: an .app bundle, a start menu shortcut, or a desktop entry id
+    pub launch: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchOutcome {
+    pub target: String,
+    /// the app or url that was actually opened
+    pub opened: Option<String>,
+    pub error: Option<String>,
+}
+
+/// the only link schemes launch opens - `file://` or custom schemes could run a local script
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// the lowercased scheme of an explicit link ("https://…", "mailto:…", "file:///…")
+fn url_scheme(target: &str) -> Option<String> {
+    let (scheme, rest) = target.trim().split_once(':')?;
+    let is_scheme = scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    let scheme = scheme.to_lowercase();
+    (is_scheme && (rest.starts_with("//") || scheme == "mailto")).then_some(scheme)
+}
+
+/// why an explicit link won't be opened, if it uses a scheme outside the allowed ones
+pub fn refused_scheme(target: &str) -> Option<String> {
+    url_scheme(target)
+        .filter(|scheme| !ALLOWED_URL_SCHEMES.contains(&scheme.as_str()))
+        .map(|scheme| format!("refused: only http, https and mailto links can be opened, not {}:", scheme))
+}
+
+/// urls and bare domains ("gmail.com") open in the browser; anything else is an app name
+pub fn as_url(target: &str) -> Option<String> {
+    let target = target.trim();
+    if let Some(scheme) = url_scheme(target) {
+        return ALLOWED_URL_SCHEMES.contains(&scheme.as_str()).then(|| target.to_string());
+    }
+    let host = target.split('/').next().unwrap_or("");
+    let looks_like_domain = !target.contains(char::is_whitespace)
+        && host.contains('.')
+        && host
+            .rsplit('.')
+            .next()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    looks_like_domain.then(|| format!("https://{}", target))
+}
+
+/// exact name first, then a name starting with the query, then one containing it - all case-insensitive
+pub fn resolve_app<'a>(name: &str, apps: &'a [InstalledApp]) -> Option<&'a InstalledApp> {
+    let wanted = name.trim().to_lowercase();
+    if wanted.is_empty() {
+        return None;
+    }
+    let lower = |app: &InstalledApp| app.name.to_lowercase();
+    apps.iter()
+        .find(|app| lower(app) == wanted)
+        .or_else(|| apps.iter().find(|app| lower(app).starts_with(&wanted)))
+        .or_else(|| apps.iter().find(|app| lower(app).contains(&wanted)))
+}
+
+/// open each target in order, one outcome per target
+pub fn launch_all(targets: &[String]) -> Vec<LaunchOutcome> {
+    // only list installed apps when something actually needs resolving
+    let needs_apps = targets.iter().any(|target| as_url(target).is_none());
+    let apps = if needs_apps { installed_apps() } else { Vec::new() };
+
+    targets
+        .iter()
+        .map(|target| {
+            let result = match (refused_scheme(target), as_url(target)) {
+                (Some(refusal), _) => Err(refusal),
+                (None, Some(url)) => open_url(&url).map(|()| url),
+                (None, None) => match resolve_app(target, &apps) {
+                    Some(app) => launch_app(app).map(|()| app.name.clone()),
+                    None => Err(format!("app not found: no installed app matches \"{}\"", target.trim())),
+                },
+            };
+            match result {
+                Ok(opened) => LaunchOutcome { target: target.clone(), opened: Some(opened), error: None },
+                Err(e) => LaunchOutcome { target: target.clone(), opened: None, error: Some(e) },
+            }
+        })
+        .collect()
+}
+
+/// one line per target, for the tool result
+pub fn format_outcomes(outcomes: &[LaunchOutcome]) -> String {
+    let opened = outcomes.iter().filter(|o| o.error.is_none()).count();
+    let mut lines = vec![format!("Opened {} of {}", opened, outcomes.len())];
+    for outcome in outcomes {
+        lines.push(match (&outcome.opened, &outcome.error) {
+            (_, Some(error)) => format!("- {}: failed ({})", outcome.target, error),
+            (Some(opened), None) if opened != &outcome.target => format!("- {}: opened {}", outcome.target, opened),
+            _ => format!("- {}: opened", outcome.target),
+        });
+    }
+    lines.join("\n")
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let output = command.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() { format!("exited with {}", output.status) } else { stderr })
+    }
+}
+
+// files with `extension` under `dir`, `depth` directory levels down
+fn find_files(dir: &Path, extension: &str, depth: usize, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension)) {
+            found.push(path);
+        } else if depth > 0 && path.is_dir() {
+            find_files(&path, extension, depth - 1, found);
+        }
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn installed_apps() -> Vec<InstalledApp> {
+    let mut roots = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/System/Applications"),
+        PathBuf::from("/System/Applications/Utilities"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join("Applications"));
+    }
+    let mut bundles = Vec::new();
+    for dir in &roots {
+        // one level down catches suites like /Applications/Microsoft Office/*.app
+        find_files(dir, "app", 1, &mut bundles);
+    }
+    bundles
+        .into_iter()
+        .map(|path| InstalledApp { name: file_stem(&path), launch: path.to_string_lossy().to_string() })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn launch_app(app: &InstalledApp) -> Result<(), String> {
+    run(Command::new("open").args(["-a", &app.launch]))
+}
+
+#[cfg(target_os = "macos")]
+fn open_url(url: &str) -> Result<(), String> {
+    run(Command::new("open").arg(url))
+}
+
+#[cfg(target_os = "windows")]
+fn installed_apps() -> Vec<InstalledApp> {
+    let start_menus = [std::env::var("ProgramData").ok(), std::env::var("APPDATA").ok()];
+    let mut shortcuts = Vec::new();
+    for root in start_menus.into_iter().flatten() {
+        let dir = PathBuf::from(root).join("Microsoft\\Windows\\Start Menu\\Programs");
+        find_files(&dir, "lnk", 3, &mut shortcuts);
+    }
+    shortcuts
+        .into_iter()
+        .map(|path| InstalledApp { name: file_stem(&path), launch: path.to_string_lossy().to_string() })
+        .collect()
+}
+
+// hand the target straight to the shell's default handler - going through `cmd /C start`
+// would let cmd parse it, so a `&` in a url would start a second command
+#[cfg(target_os = "windows")]
+fn shell_open(target: &str) -> Result<(), String> {
+    run(Command::new("rundll32").args(["url.dll,FileProtocolHandler", target]))
+}
+
+#[cfg(target_os = "windows")]
+fn launch_app(app: &InstalledApp) -> Result<(), String> {
+    shell_open(&app.launch)
+}
+
+#[cfg(target_os = "windows")]
+fn open_url(url: &str) -> Result<(), String> {
+    shell_open(url)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn installed_apps() -> Vec<InstalledApp> {
+    let mut roots = vec![PathBuf::from("/usr/share/applications"), PathBuf::from("/usr/local/share/applications")];
+    if let Some(data) = dirs::data_dir() {
+        roots.push(data.join("applications"));
+    }
+    let mut entries = Vec::new();
+    for dir in &roots {
+        find_files(dir, "desktop", 0, &mut entries);
+    }
+    entries
+        .into_iter()
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(&path).ok()?;
+            if text.lines().any(|line| line.trim() == "NoDisplay=true") {
+                return None;
+            }
+            let name = text.lines().find_map(|line| line.strip_prefix("Name="))?.trim().to_string();
+            Some(InstalledApp { name, launch: file_stem(&path) })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn launch_app(app: &InstalledApp) -> Result<(), String> {
+    run(Command::new("gtk-launch").arg(&app.launch))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn open_url(url: &str) -> Result<(), String> {
+    run(Command::new("xdg-open").arg(url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str) -> InstalledApp {
+        InstalledApp { name: name.to_string(), launch: format!("/Applications/{}.app", name) }
+    }
+
+    #[test]
+    fn urls_and_domains_are_told_apart_from_app_names() {
+        assert_eq!(as_url("https://notion.so"), Some("https://notion.so".to_string()));
+        assert_eq!(as_url("gmail.com"), Some("https://gmail.com".to_string()));
+        assert_eq!(as_url("github.com/rust-lang"), Some("https://github.com/rust-lang".to_string()));
+        assert_eq!(as_url("Slack"), None);
+        assert_eq!(as_url("Visual Studio Code"), None);
+        assert_eq!(as_url("Node.js 20"), None);
+        assert_eq!(as_url("mailto:me@example.com"), Some("mailto:me@example.com".to_string()));
+    }
+
+    #[test]
+    fn only_web_and_mail_links_are_opened() {
+        assert!(refused_scheme("https://example.com/?a=1&b=2").is_none());
+        assert!(refused_scheme("HTTP://example.com").is_none());
+        assert!(refused_scheme("mailto:me@example.com").is_none());
+        assert!(refused_scheme("Slack").is_none());
+        assert!(refused_scheme("file:///Users/me/x.command").is_some());
+        assert!(refused_scheme("vscode://file/etc/passwd").is_some());
+        assert_eq!(as_url("file:///Users/me/x.command"), None);
+    }
+
+    #[test]
+    fn app_names_resolve_case_insensitively() {
+        let apps = [app("Slack"), app("Notion Calendar"), app("Notion"), app("Microsoft Word")];
+        assert_eq!(resolve_app("slack", &apps).unwrap().name, "Slack");
+        // an exact match beats an earlier prefix match
+        assert_eq!(resolve_app("NOTION", &apps).unwrap().name, "Notion");
+        assert_eq!(resolve_app("word", &apps).unwrap().name, "Microsoft Word");
+        assert!(resolve_app("Figma", &apps).is_none());
+        assert!(resolve_app("  ", &apps).is_none());
+    }
+
+    #[test]
+    fn outcomes_report_each_item() {
+        let outcomes = [
+            LaunchOutcome { target: "slack".to_string(), opened: Some("Slack".to_string()), error: None },
+            LaunchOutcome { target: "figma".to_string(), opened: None, error: Some("app not found".to_string()) },
+        ];
+        assert_eq!(
+            format_outcomes(&outcomes),
+            "Opened 1 of 2\n- slack: opened Slack\n- figma: failed (app not found)"
+        );
+    }
+}
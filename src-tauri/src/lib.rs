@@ -1,13 +1,29 @@
 pub mod agent;
+pub mod ambient;
 pub mod api;
+pub mod api_cache;
 pub mod bash;
 pub mod browser;
+pub mod clipboard;
 pub mod cognitive;
+pub mod compaction;
 pub mod computer;
+pub mod confirm;
+pub mod context;
+pub mod credentials;
 pub mod deep_research;
+pub mod files;
+pub mod health;
+pub mod launcher;
+pub mod logging;
+pub mod ocr;
 pub mod panels;
 pub mod permissions;
 pub mod python_tool;
+pub mod quiet;
 pub mod rate_limiter;
+pub mod replay;
+pub mod run_guard;
 pub mod storage;
+pub mod tool_policy;
 pub mod voice;
@@ -0,0 +1,143 @@
+// rolling debug log under the app data dir, so packaged builds (no visible stdout)
+// still leave something users can attach to a bug report
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+/// env var (saved in .env) holding the log level: error, warn, info or debug
+pub const LOG_LEVEL_ENV: &str = "HEYWORK_LOG_LEVEL";
+
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug"];
+
+const LOG_FILE_PREFIX: &str = "heywork";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// most lines get_recent_logs will hand back in one call
+pub const MAX_RECENT_LINES: usize = 5000;
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+// flushes the non-blocking writer on drop, so it has to live as long as the app
+static WRITER_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+pub fn log_dir() -> PathBuf {
+    crate::storage::data_dir().join("logs")
+}
+
+fn parse_level(level: &str) -> Option<LevelFilter> {
+    match level.trim().to_lowercase().as_str() {
+        "error" => Some(LevelFilter::ERROR),
+        "warn" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        _ => None,
+    }
+}
+
+/// the configured log level, falling back to info
+pub fn current_level() -> String {
+    std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .filter(|level| parse_level(level).is_some())
+        .map(|level| level.trim().to_lowercase())
+        .unwrap_or_else(|| "info".to_string())
+}
+
+/// install the global subscriber - call once at startup, after saved settings are loaded.
+/// debug builds also log to stdout
+pub fn init() {
+    let level = parse_level(&current_level()).unwrap_or(LevelFilter::INFO);
+    let (level_layer, handle) = reload::Layer::new(level);
+
+    let file_layer = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir())
+        .map_err(|e| eprintln!("[logging] file logging disabled: {}", e))
+        .ok()
+        .map(|appender| {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = WRITER_GUARD.set(guard);
+            fmt::layer().with_ansi(false).with_writer(writer)
+        });
+
+    let stdout_layer = cfg!(debug_assertions).then(fmt::layer);
+
+    if tracing_subscriber::registry()
+        .with(level_layer)
+        .with(file_layer)
+        .with(stdout_layer)
+        .try_init()
+        .is_ok()
+    {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+}
+
+/// change the level of the running subscriber
+pub fn set_level(level: &str) -> Result<(), String> {
+    let filter = parse_level(level)
+        .ok_or_else(|| format!("Unknown log level '{}', expected one of: {}", level, LOG_LEVELS.join(", ")))?;
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        handle.reload(filter).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// newest log file - daily files sort by date in their names
+fn latest_log_file() -> Option<PathBuf> {
+    std::fs::read_dir(log_dir())
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().starts_with(LOG_FILE_PREFIX))
+                .unwrap_or(false)
+        })
+        .max()
+}
+
+fn last_lines(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+// last `lines` lines of the current log file, for pasting into bug reports
+#[tauri::command]
+pub fn get_recent_logs(lines: usize) -> Result<String, String> {
+    let Some(path) = latest_log_file() else {
+        return Ok(String::new());
+    };
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    Ok(last_lines(&text, lines.min(MAX_RECENT_LINES)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_levels_only() {
+        assert_eq!(parse_level("DEBUG"), Some(LevelFilter::DEBUG));
+        assert_eq!(parse_level(" warn "), Some(LevelFilter::WARN));
+        assert_eq!(parse_level("trace"), None);
+        assert_eq!(parse_level(""), None);
+    }
+
+    #[test]
+    fn last_lines_keeps_the_tail() {
+        assert_eq!(last_lines("a\nb\nc", 2), "b\nc");
+        assert_eq!(last_lines("a\nb", 10), "a\nb");
+        assert_eq!(last_lines("a\nb", 0), "");
+    }
+}
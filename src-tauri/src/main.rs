@@ -6,24 +6,40 @@ use mimalloc::MiMalloc;
 static GLOBAL: MiMalloc = MiMalloc;
 
 mod agent;
+mod ambient;
 mod api;
+mod api_cache;
 mod bash;
 mod browser;
+mod clipboard;
 mod cognitive;
+mod compaction;
 mod computer;
+mod confirm;
+mod context;
+mod credentials;
 mod deep_research;
+mod files;
+mod health;
+mod launcher;
+mod logging;
+mod ocr;
 mod panels;
 mod permissions;
 mod python_tool;
+mod quiet;
 mod rate_limiter;
+mod replay;
+mod run_guard;
 mod storage;
+mod tool_policy;
 mod voice;
 
 use agent::{Agent, AgentMode, HistoryMessage};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem},
     tray::TrayIconBuilder,
     Emitter, Manager, PhysicalPosition, State,
 };
@@ -47,8 +63,34 @@ tauri_panel! {
 struct AppState {
     agent: Arc<Mutex<Agent>>,
     running: Arc<std::sync::atomic::AtomicBool>,
+    // set by stop_after_tool, cleared when a run starts
+    soft_stop: Arc<std::sync::atomic::AtomicBool>,
+    // the agent's browser connection, reachable without the agent lock (help hotkey)
+    browser: browser::SharedBrowserClient,
+    // claimed for the whole of a top-level run so a second one can't start alongside it
+    run_guard: run_guard::RunGuard,
+    // the conversation a previous launch quit in the middle of, found once at startup - asking
+    // later would find this launch's own live run
+    resumable: std::sync::Mutex<Option<storage::ConversationMeta>>,
 }
 
+/// CSS that forces every webview background layer transparent. Injected into the
+/// panels on macOS and into the border overlay on Windows/Linux, where the
+/// webview otherwise paints its default white/dark background.
+const FORCE_TRANSPARENT_JS: &str = r#"(function(){
+    var s = document.getElementById('heywork-force-transparent');
+    if (!s) {
+        s = document.createElement('style');
+        s.id = 'heywork-force-transparent';
+        s.textContent = 'html, body, #root { background: transparent !important; background-color: transparent !important; } html::before, html::after, body::before, body::after { display: none !important; }';
+        (document.head || document.documentElement).appendChild(s);
+    }
+    document.documentElement.style.setProperty('background', 'transparent', 'important');
+    document.body.style.setProperty('background', 'transparent', 'important');
+    var root = document.getElementById('root');
+    if (root) root.style.setProperty('background', 'transparent', 'important');
+})();"#;
+
 // cached screen info for fast window positioning
 #[cfg(target_os = "macos")]
 struct ScreenInfo {
@@ -305,6 +347,18 @@ async fn set_api_key(api_key: String, state: State<'_, AppState>) -> Result<(),
     Ok(())
 }
 
+// apply a settings profile; an imported anthropic key goes live without a restart
+#[tauri::command]
+async fn import_settings(json: String, state: State<'_, AppState>) -> Result<permissions::SettingsImportReport, String> {
+    let report = permissions::import_settings_profile(&json)?;
+    if report.secrets.iter().any(|service| service == "anthropic") {
+        if let Some(key) = permissions::load_api_key_for_service("anthropic") {
+            state.agent.lock().await.set_api_key(key);
+        }
+    }
+    Ok(report)
+}
+
 #[tauri::command]
 async fn check_api_key(state: State<'_, AppState>) -> Result<bool, String> {
     let agent = state.agent.lock().await;
@@ -319,35 +373,60 @@ async fn run_agent(
     voice_mode: Option<bool>,
     history: Vec<HistoryMessage>,
     context_screenshot: Option<String>,
+    context_snapshot: Option<String>,
     conversation_id: Option<String>,
+    dry_run: Option<bool>,
+    chat_only: Option<bool>,
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let voice = voice_mode.unwrap_or(false);
-    println!("[heywork] run_agent called with: {} (model: {}, mode: {:?}, voice: {}, history: {} msgs, screenshot: {}, conv: {:?})",
-        instructions, model, mode, voice, history.len(), context_screenshot.is_some(), conversation_id);
+    let dry_run = dry_run.unwrap_or(false);
+    let chat_only = chat_only.unwrap_or(false);
+    println!("[heywork] run_agent called with: {} (model: {}, mode: {:?}, voice: {}, history: {} msgs, screenshot: {}, conv: {:?}, dry_run: {}, chat_only: {})",
+        instructions, model, mode, voice, history.len(), context_screenshot.is_some(), conversation_id, dry_run, chat_only);
 
     let agent = state.agent.clone();
 
-    {
-        let agent_guard = agent.lock().await;
-        if agent_guard.is_running() {
-            return Err("Agent is already running".to_string());
+    // only checked once the guard is claimed, so two calls can't both get past it
+    let ready = async {
+        // a soft stop that arrived after the last run ended mustn't cut this one short
+        state.soft_stop.store(false, std::sync::atomic::Ordering::SeqCst);
+        // a run in progress holds the agent lock, so this only waits while a previous run tears down
+        if agent.lock().await.has_api_key() {
+            Ok(())
+        } else {
+            Err("No API key set. Please add your Anthropic API key in onboarding or Settings.".to_string())
         }
-        if !agent_guard.has_api_key() {
-            return Err("No API key set. Please add your Anthropic API key in onboarding or Settings.".to_string());
+    };
+    let run = {
+        let agent = agent.clone();
+        let app_handle = app_handle.clone();
+        async move {
+            let agent_guard = agent.lock().await;
+            match agent_guard.run(instructions, model, mode, voice, history, context_screenshot, context_snapshot, conversation_id, dry_run, chat_only, app_handle).await {
+                Ok(_) => println!("[heywork] Agent finished"),
+                Err(e) => println!("[heywork] Agent error: {:?}", e),
+            }
         }
-    }
+    };
+    start_run(&state.run_guard, ready, run, &app_handle).await
+}
 
-    tokio::spawn(async move {
-        let agent_guard = agent.lock().await;
-        match agent_guard.run(instructions, model, mode, voice, history, context_screenshot, conversation_id, app_handle).await {
-            Ok(_) => println!("[heywork] Agent finished"),
-            Err(e) => println!("[heywork] Agent error: {:?}", e),
+// claim the run guard and start `run`, telling the frontend when another run already holds it
+async fn start_run<R, F>(guard: &run_guard::RunGuard, ready: R, run: F, app_handle: &tauri::AppHandle) -> Result<(), String>
+where
+    R: std::future::Future<Output = Result<(), String>>,
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    match guard.start(ready, run).await {
+        Ok(_) => Ok(()),
+        Err(run_guard::StartError::Busy) => {
+            emit_busy(app_handle);
+            Err(run_guard::AGENT_BUSY_ERROR.to_string())
         }
-    });
-
-    Ok(())
+        Err(run_guard::StartError::NotReady(e)) => Err(e),
+    }
 }
 
 #[tauri::command]
@@ -357,6 +436,94 @@ fn stop_agent(state: State<'_, AppState>) -> Result<(), String> {
     Ok(())
 }
 
+// stop once the tool that's running finishes and its result is saved, so the conversation
+// never ends on a tool call without a result. stop_agent stays the immediate stop
+#[tauri::command]
+fn stop_after_tool(state: State<'_, AppState>) -> Result<(), String> {
+    if !state.run_guard.is_busy() {
+        return Ok(());
+    }
+    state.soft_stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    println!("[heywork] Stop after current tool requested");
+    Ok(())
+}
+
+#[tauri::command]
+async fn undo_last_action(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    // the running agent holds its lock for the whole run, so refuse instead of waiting
+    if state.running.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Stop the agent before undoing".to_string());
+    }
+    let agent = state.agent.lock().await;
+    agent.undo_last_action(&app_handle).await
+}
+
+// re-run a stored conversation's computer, bash and browser actions in order, without the
+// model. dry_run only lists the steps; a real replay asks for confirmation first
+#[tauri::command(rename_all = "camelCase")]
+async fn replay_conversation(
+    id: String,
+    dry_run: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<replay::ReplayStep>, String> {
+    let conv = storage::load_conversation(&id)?.ok_or_else(|| format!("conversation {} not found", id))?;
+    let steps = replay::steps(&conv.messages);
+    if dry_run.unwrap_or(false) {
+        return Ok(steps);
+    }
+
+    let agent = state.agent.clone();
+    let replayed = steps.clone();
+    let run = {
+        let app_handle = app_handle.clone();
+        async move {
+            let agent_guard = agent.lock().await;
+            match agent_guard.replay(&conv.title, replayed, app_handle).await {
+                Ok(done) => println!("[heywork] Replay finished ({} steps)", done),
+                Err(e) => println!("[heywork] Replay ended: {}", e),
+            }
+        }
+    };
+    start_run(&state.run_guard, async { Ok(()) }, run, &app_handle).await?;
+    Ok(steps)
+}
+
+// tell the frontend a new run was refused because one is already active
+fn emit_busy(app_handle: &tauri::AppHandle) {
+    let _ = app_handle.emit("agent-update", agent::AgentUpdate {
+        update_type: "busy".to_string(),
+        message: run_guard::AGENT_BUSY_ERROR.to_string(),
+        tool_name: None,
+        tool_input: None,
+        action: None,
+        screenshot: None,
+        bash_command: None,
+        exit_code: None,
+        mode: None,
+    });
+}
+
+// restart the selected browser with remote debugging ahead of a browser task, instead of
+// waiting for a run to fail to connect. the connection is kept for the next run
+#[tauri::command]
+async fn restart_browser_debugging(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    // restarting kills the browser a running task is driving
+    let Some(_slot) = state.run_guard.try_claim() else {
+        emit_busy(&app_handle);
+        return Err(run_guard::AGENT_BUSY_ERROR.to_string());
+    };
+    let client = browser::restart_chrome_with_debugging().await.map_err(|e| e.to_string())?;
+    *state.browser.lock().await = Some(client);
+    Ok(())
+}
+
+// whether a CDP endpoint answers on the debug port, i.e. a browser task can connect without a restart
+#[tauri::command]
+async fn is_browser_debuggable() -> Result<bool, String> {
+    Ok(browser::debug_port_reachable().await)
+}
+
 #[tauri::command]
 async fn init_agent_swarm(
     api_key: String,
@@ -364,6 +531,11 @@ async fn init_agent_swarm(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    // swapping the swarm out from under a run would orphan its subtasks
+    if state.run_guard.is_busy() {
+        emit_busy(&app_handle);
+        return Err(run_guard::AGENT_BUSY_ERROR.to_string());
+    }
     let mut agent = state.agent.lock().await;
     agent.init_agent_swarm(api_key, model, app_handle).await;
     println!("[heywork] Agent Swarm initialized");
@@ -409,6 +581,36 @@ async fn list_active_swarm_tasks(
     }
 }
 
+#[tauri::command]
+fn cancel_swarm_task(task_id: String) -> Result<(), String> {
+    // no agent lock here - the run holds it while polling the swarm
+    if cognitive::agent_swarm::cancel_swarm_task(&task_id) {
+        Ok(())
+    } else {
+        Err(format!("Swarm task {} is not running", task_id))
+    }
+}
+
+#[tauri::command]
+async fn get_memory_stats(state: State<'_, AppState>) -> Result<cognitive::memory::MemoryStats, String> {
+    let agent = state.agent.lock().await;
+    let cognitive = agent.cognitive.lock().await;
+    Ok(cognitive.memory.get_stats())
+}
+
+// re-embed memories stored by another embedding backend; returns how many moved
+#[tauri::command]
+async fn reembed_memories(state: State<'_, AppState>) -> Result<usize, String> {
+    let engine = state.agent.lock().await.cognitive.clone();
+    let (moved, pending) = engine.lock().await.memory.prepare_reembed().await
+        .map_err(|e| format!("Failed to re-embed memories: {}", e))?;
+    // one network call per memory - made with neither lock held, so runs and settings don't wait
+    let embedded = cognitive::memory::embed_stale_remotely(&pending).await;
+    let stored = engine.lock().await.memory.store_reembedded(embedded).await
+        .map_err(|e| format!("Failed to re-embed memories: {}", e))?;
+    Ok(moved + stored)
+}
+
 #[tauri::command]
 async fn export_skills(state: State<'_, AppState>) -> Result<String, String> {
     let agent = state.agent.lock().await;
@@ -425,6 +627,36 @@ async fn import_skills(json: String, state: State<'_, AppState>) -> Result<usize
         .map_err(|e| format!("Failed to import skills: {}", e))
 }
 
+#[tauri::command]
+async fn list_skill_versions(
+    skill_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<cognitive::skills::SkillVersion>, String> {
+    let agent = state.agent.lock().await;
+    let cognitive = agent.cognitive.lock().await;
+    cognitive.skills.list_skill_versions(&skill_id)
+        .ok_or_else(|| format!("Unknown learned skill: {}", skill_id))
+}
+
+#[tauri::command]
+async fn rollback_skill(skill_id: String, version: u32, state: State<'_, AppState>) -> Result<(), String> {
+    let agent = state.agent.lock().await;
+    let mut cognitive = agent.cognitive.lock().await;
+    cognitive.skills.rollback_skill(&skill_id, version).await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to roll back skill: {}", e))
+}
+
+// thumbs up/down on a skill result from the chat, feeds the skill's success rate
+#[tauri::command]
+async fn rate_skill_execution(skill_id: String, succeeded: bool, state: State<'_, AppState>) -> Result<(), String> {
+    let agent = state.agent.lock().await;
+    let mut cognitive = agent.cognitive.lock().await;
+    cognitive.skills.rate_skill_execution(&skill_id, succeeded).await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to rate skill: {}", e))
+}
+
 #[tauri::command]
 async fn list_skills(state: State<'_, AppState>) -> Result<Vec<serde_json::Value>, String> {
     let agent = state.agent.lock().await;
@@ -564,6 +796,50 @@ fn hide_main_window(_app_handle: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// emitted with whether the spotlight panel got keyboard focus, so its input can focus itself
+const SPOTLIGHT_FOCUS_EVENT: &str = "spotlight:focus-input";
+// wait before checking the panel is still key - activating the app can take a runloop turn
+const SPOTLIGHT_FOCUS_CHECK_MS: u64 = 80;
+
+// make the main panel key so the spotlight input receives keystrokes, checking it held and
+// trying once more if not, then tell the frontend to focus the input
+async fn focus_spotlight(app_handle: tauri::AppHandle) -> bool {
+    let mut focused = false;
+    for attempt in 1..=2 {
+        #[cfg(target_os = "macos")]
+        {
+            if MAIN_PANEL.get().is_some() {
+                // escalates step by step and checks after each one - nothing left to retry
+                focused = panels::make_main_panel_key(&app_handle).await;
+                break;
+            } else if let Some(window) = app_handle.get_webview_window("main") {
+                // the panel conversion failed at startup, this is a plain window
+                let _ = window.show();
+                focused = window.set_focus().is_ok();
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+            tokio::time::sleep(std::time::Duration::from_millis(SPOTLIGHT_FOCUS_CHECK_MS)).await;
+            focused = window.is_focused().unwrap_or(false);
+        }
+        if focused {
+            break;
+        }
+        println!("[spotlight] Input isn't focused after attempt {}", attempt);
+    }
+    let _ = app_handle.emit(SPOTLIGHT_FOCUS_EVENT, focused);
+    focused
+}
+
+// give the spotlight input keyboard focus; false when the panel never became key
+#[tauri::command]
+async fn focus_spotlight_input(app_handle: tauri::AppHandle) -> bool {
+    focus_spotlight(app_handle).await
+}
+
 // show main window in voice response mode and emit event
 #[tauri::command]
 fn show_main_voice_response(app_handle: tauri::AppHandle, text: String, screenshot: Option<String>, mode: String) -> Result<(), String> {
@@ -597,6 +873,28 @@ fn set_main_click_through(ignore: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Size the border overlay to the work area of the monitor under the cursor
+/// (falls back to the window's current monitor, then the primary one).
+#[cfg(not(target_os = "macos"))]
+fn fit_border_to_work_area(window: &tauri::WebviewWindow) {
+    let monitor = window
+        .cursor_position()
+        .ok()
+        .and_then(|pos| window.monitor_from_point(pos.x, pos.y).ok().flatten())
+        .or_else(|| window.current_monitor().ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten());
+
+    if let Some(monitor) = monitor {
+        let area = monitor.work_area();
+        let _ = window.set_position(area.position);
+        let _ = window.set_size(area.size);
+        println!(
+            "[heywork] Border overlay fit to work area {}x{} at ({}, {})",
+            area.size.width, area.size.height, area.position.x, area.position.y
+        );
+    }
+}
+
 #[tauri::command]
 fn show_border_overlay(app_handle: tauri::AppHandle) {
     #[cfg(target_os = "macos")]
@@ -605,7 +903,12 @@ fn show_border_overlay(app_handle: tauri::AppHandle) {
     }
     #[cfg(not(target_os = "macos"))]
     if let Some(window) = app_handle.get_webview_window("border") {
+        // re-fit on every show: the user may have moved to another monitor
+        fit_border_to_work_area(&window);
+        let _ = window.set_ignore_cursor_events(true);
+        let _ = window.set_always_on_top(true);
         let _ = window.show();
+        let _ = window.eval(FORCE_TRANSPARENT_JS);
     }
 }
 
@@ -636,28 +939,34 @@ fn take_screenshot_excluding_app() -> Result<String, String> {
     }
 }
 
-// trigger screen flash effect - plays sound as feedback
+// trigger screen flash effect - plays sound as feedback, unless do not disturb is on
 #[cfg(target_os = "macos")]
 fn trigger_screen_flash() {
-    std::process::Command::new("afplay")
-        .arg("/System/Library/Components/CoreAudio.component/Contents/SharedSupport/SystemSounds/system/Grab.aif")
-        .spawn()
-        .ok();
+    quiet::play_sound(quiet::CAPTURE_SOUND);
 }
 
 #[cfg(not(target_os = "macos"))]
 fn capture_screenshot_fallback() -> Option<String> {
     match computer::ComputerControl::new() {
-        Ok(control) => control.take_screenshot().ok(),
+        Ok(control) => control.take_help_screenshot(None).ok(),
         Err(_) => None,
     }
 }
 
+/// help context for a browser task: the selected page as CDP sees it
+async fn capture_browser_for_help(browser: &browser::SharedBrowserClient) -> Option<(String, String)> {
+    let mut guard = browser.lock().await;
+    let client = guard.as_mut()?;
+    let screenshot = client.screenshot().await.ok()?;
+    let snapshot = client.take_snapshot(false).await.ok()?;
+    Some((screenshot, snapshot))
+}
+
 // hotkey triggered - capture screenshot and return base64
 #[tauri::command]
 fn capture_screen_for_help() -> Result<String, String> {
     let control = computer::ComputerControl::new().map_err(|e| e.to_string())?;
-    let screenshot = control.take_screenshot().map_err(|e| e.to_string())?;
+    let screenshot = control.take_help_screenshot(None).map_err(|e| e.to_string())?;
 
     #[cfg(target_os = "macos")]
     trigger_screen_flash();
@@ -668,7 +977,7 @@ fn capture_screen_for_help() -> Result<String, String> {
 // --- storage IPC commands ---
 
 mod storage_cmd {
-    use crate::storage::{self, Conversation, ConversationMeta};
+    use crate::storage::{self, Conversation, ConversationMeta, StoragePaths, ToolCallEntry};
 
     #[tauri::command]
     pub fn list_conversations(limit: usize, offset: usize) -> Result<Vec<ConversationMeta>, String> {
@@ -695,15 +1004,155 @@ mod storage_cmd {
         storage::delete_conversation(&id)
     }
 
+    // files the agent made in a conversation that are still on disk, oldest first
+    #[tauri::command]
+    pub fn list_conversation_artifacts(id: String) -> Result<Vec<storage::ArtifactEntry>, String> {
+        storage::list_artifacts(&id)
+    }
+
+    // totals, per-model spend, run durations and tool-call counts for conversations active in `range`
+    #[tauri::command]
+    pub fn get_usage_stats(range: Option<storage::UsageRange>) -> Result<storage::UsageStats, String> {
+        storage::get_usage_stats(range.unwrap_or_default())
+    }
+
     #[tauri::command]
     pub fn search_conversations(query: String, limit: usize) -> Result<Vec<ConversationMeta>, String> {
         storage::search_conversations(&query, limit)
     }
 
+    // fold the oldest messages into a summary, keeping the last `keep_turns` user turns verbatim;
+    // the replaced messages are archived next to the db
+    #[tauri::command(rename_all = "camelCase")]
+    pub async fn compact_conversation(
+        id: String,
+        keep_turns: Option<usize>,
+        state: tauri::State<'_, super::AppState>,
+    ) -> Result<crate::compaction::CompactionReport, String> {
+        // a running agent holds its own copy and would save over the compacted one, so hold the
+        // guard until the summary is saved - a run can't start while the api call is in flight
+        let Some(_slot) = state.run_guard.try_claim() else {
+            return Err(crate::run_guard::AGENT_BUSY_ERROR.to_string());
+        };
+        let mut conv = storage::load_conversation(&id)?.ok_or_else(|| format!("conversation {} not found", id))?;
+        let api_key = crate::permissions::load_api_key_for_service("anthropic")
+            .ok_or_else(|| "No API key set. Please add your Anthropic API key in onboarding or Settings.".to_string())?;
+        let keep_turns = keep_turns.unwrap_or(crate::compaction::DEFAULT_KEEP_TURNS);
+        crate::compaction::compact(&mut conv, keep_turns, api_key).await
+    }
+
+    // the conversation's final assistant text and its code blocks, empty before the first reply
+    #[tauri::command(rename_all = "camelCase")]
+    pub fn get_last_response(conversation_id: String) -> Result<crate::clipboard::LastResponse, String> {
+        Ok(storage::load_conversation(&conversation_id)?
+            .map(|conv| crate::clipboard::last_response(&conv.messages))
+            .unwrap_or_default())
+    }
+
+    // every tool the agent ran in the conversation, oldest first, with inputs and trimmed outputs
+    #[tauri::command(rename_all = "camelCase")]
+    pub fn get_tool_audit(conversation_id: String) -> Result<Vec<ToolCallEntry>, String> {
+        storage::get_tool_audit(&conversation_id)
+    }
+
+    // where the conversation's bash session currently is (saved after every command)
+    #[tauri::command(rename_all = "camelCase")]
+    pub fn get_bash_cwd(conversation_id: String) -> Result<Option<String>, String> {
+        Ok(storage::load_conversation(&conversation_id)?.and_then(|conv| conv.bash_state.cwd))
+    }
+
     #[tauri::command(rename_all = "camelCase")]
     pub fn set_conversation_voice_mode(conversation_id: String, voice_mode: bool) -> Result<(), String> {
         storage::set_conversation_voice_mode(&conversation_id, voice_mode)
     }
+
+    #[tauri::command]
+    pub fn set_conversation_pinned(id: String, pinned: bool) -> Result<(), String> {
+        storage::set_conversation_pinned(&id, pinned)
+    }
+
+    // give a conversation the user's own title; generated titles never replace it
+    #[tauri::command]
+    pub fn rename_conversation(id: String, title: String) -> Result<String, String> {
+        storage::rename_conversation(&id, &title)
+    }
+
+    // copy the first `up_to_message_index` messages into a new conversation that can be continued on its own
+    #[tauri::command(rename_all = "camelCase")]
+    pub fn fork_conversation(id: String, up_to_message_index: usize) -> Result<String, String> {
+        storage::fork_conversation(&id, up_to_message_index)
+    }
+
+    // conversation the app quit in the middle of, offered for resume on launch
+    #[tauri::command]
+    pub fn get_resumable_conversation(state: tauri::State<'_, super::AppState>) -> Result<Option<ConversationMeta>, String> {
+        resumable_candidate(&state)
+    }
+
+    pub fn resumable_candidate(state: &super::AppState) -> Result<Option<ConversationMeta>, String> {
+        // while a run is live the offer could only point at work already under way
+        if state.run_guard.is_busy() {
+            return Ok(None);
+        }
+        let mut candidate = state.resumable.lock().unwrap();
+        // resumed and finished (or failed) since launch - nothing left to offer
+        if let Some(meta) = candidate.as_ref() {
+            if !storage::load_conversation(&meta.id)?.is_some_and(|conv| conv.is_interrupted()) {
+                *candidate = None;
+            }
+        }
+        Ok(candidate.clone())
+    }
+
+    // decline the resume offer so it isn't shown again
+    #[tauri::command]
+    pub fn dismiss_resumable_conversation(id: String, state: tauri::State<'_, super::AppState>) -> Result<(), String> {
+        let mut candidate = state.resumable.lock().unwrap();
+        if !candidate.as_ref().is_some_and(|meta| meta.id == id) {
+            return Ok(());
+        }
+        *candidate = None;
+        // a live run may be this very conversation, and it clears the marker itself when done
+        if state.run_guard.is_busy() {
+            return Ok(());
+        }
+        storage::set_run_in_progress(&id, false)
+    }
+
+    #[tauri::command]
+    pub fn get_storage_paths() -> StoragePaths {
+        storage::storage_paths()
+    }
+
+    // reveal the app data directory (db, settings, reports) in the file manager
+    #[tauri::command]
+    pub fn open_data_folder() -> Result<(), String> {
+        let dir = storage::data_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        crate::files::open_file_location(dir.to_string_lossy().to_string())
+    }
+}
+
+// --- research report IPC commands ---
+
+mod research_cmd {
+    use crate::deep_research::{self, ResearchReportMeta, SavedResearchReport};
+    use crate::files::CreatedFile;
+
+    #[tauri::command]
+    pub fn list_research_reports() -> Result<Vec<ResearchReportMeta>, String> {
+        deep_research::list_reports()
+    }
+
+    #[tauri::command]
+    pub fn load_research_report(id: String) -> Result<SavedResearchReport, String> {
+        deep_research::load_report(&id)
+    }
+
+    #[tauri::command]
+    pub async fn export_research_report(id: String, format: String) -> Result<CreatedFile, String> {
+        deep_research::export_report(&id, &format).await
+    }
 }
 
 // --- voice IPC commands ---
@@ -744,18 +1193,18 @@ mod voice_cmd {
         state: State<'_, VoiceState>,
     ) -> Result<(), String> {
         println!("[voice cmd] start_voice called");
-        let api_key = match std::env::var("DEEPGRAM_API_KEY") {
-            Ok(key) => {
+        let api_key = match crate::permissions::load_api_key_for_service("deepgram") {
+            Some(key) => {
                 println!("[voice cmd] got API key (len={})", key.len());
                 key
             }
-            Err(e) => {
-                println!("[voice cmd] DEEPGRAM_API_KEY not found: {:?}", e);
-                return Err("DEEPGRAM_API_KEY not set in .env".to_string());
+            None => {
+                println!("[voice cmd] DEEPGRAM_API_KEY not found");
+                return Err("Deepgram API key not set - add it in Settings".to_string());
             }
         };
         println!("[voice cmd] starting session...");
-        let result = state.session.start(api_key, app_handle).await;
+        let result = state.session.start(api_key, crate::voice::voice_language(), crate::voice::input_device_setting().as_deref(), app_handle).await;
         println!("[voice cmd] session.start returned: {:?}", result);
         result
     }
@@ -785,7 +1234,7 @@ mod voice_cmd {
         let screenshot = if mode_str == "computer" {
             #[cfg(target_os = "macos")]
             {
-                panels::take_screenshot_excluding_app_sync().ok()
+                panels::take_help_screenshot_excluding_app_sync().ok()
             }
             #[cfg(not(target_os = "macos"))]
             {
@@ -802,13 +1251,7 @@ mod voice_cmd {
         *state.mode.lock().unwrap() = Some(mode_str.clone());
 
         // play recording start sound
-        #[cfg(target_os = "macos")]
-        {
-            std::process::Command::new("afplay")
-                .arg("/System/Library/Sounds/Tink.aiff")
-                .spawn()
-                .ok();
-        }
+        crate::quiet::play_sound(crate::quiet::START_SOUND);
 
         // show voice window centered - must run on main thread
         #[cfg(target_os = "macos")]
@@ -838,10 +1281,10 @@ mod voice_cmd {
             "sessionId": 0
         }));
 
-        let api_key = std::env::var("DEEPGRAM_API_KEY")
-            .map_err(|_| "DEEPGRAM_API_KEY not set in .env".to_string())?;
+        let api_key = crate::permissions::load_api_key_for_service("deepgram")
+            .ok_or_else(|| "Deepgram API key not set - add it in Settings".to_string())?;
 
-        let session_id = state.session.start(api_key, app_handle).await?;
+        let session_id = state.session.start(api_key, crate::voice::voice_language(), crate::voice::input_device_setting().as_deref(), app_handle).await?;
         *state.current_session_id.lock().unwrap() = session_id;
         Ok(())
     }
@@ -854,13 +1297,7 @@ mod voice_cmd {
         println!("[ptt cmd] stop_ptt called");
 
         // play stop sound
-        #[cfg(target_os = "macos")]
-        {
-            std::process::Command::new("afplay")
-                .arg("/System/Library/Sounds/Pop.aiff")
-                .spawn()
-                .ok();
-        }
+        crate::quiet::play_sound(crate::quiet::STOP_SOUND);
 
         let expected_session_id = *state.current_session_id.lock().unwrap();
         let (raw_text, result_session_id) = state.session.stop().await;
@@ -895,6 +1332,28 @@ mod voice_cmd {
     pub fn is_ptt_running(state: State<'_, PttState>) -> Result<bool, String> {
         Ok(state.session.is_running())
     }
+
+    // the microphones voice capture can use
+    #[tauri::command]
+    pub fn list_audio_input_devices() -> Result<Vec<crate::voice::AudioInputDevice>, String> {
+        crate::voice::list_input_devices()
+    }
+
+    // listen to the selected input for a couple of seconds and report its level, device and
+    // permission - no transcription, so it works without a deepgram key
+    #[tauri::command]
+    pub async fn test_microphone(
+        app_handle: tauri::AppHandle,
+        voice: State<'_, VoiceState>,
+        ptt: State<'_, PttState>,
+    ) -> Result<crate::voice::MicTestReport, String> {
+        if voice.session.is_running() || ptt.session.is_running() {
+            return Err("Voice input is in use - stop it before testing the microphone".to_string());
+        }
+        tokio::task::spawn_blocking(move || crate::voice::test_microphone(&app_handle))
+            .await
+            .map_err(|e| e.to_string())
+    }
 }
 
 fn main() {
@@ -902,6 +1361,8 @@ fn main() {
     if dotenvy::dotenv().is_err() {
         let _ = dotenvy::from_filename("../.env");
     }
+    permissions::load_saved_settings();
+    logging::init();
 
     // init storage
     if let Err(e) = storage::init_db() {
@@ -919,6 +1380,8 @@ fn main() {
     }
 
     let running_for_shortcut = running.clone();
+    let browser_client = agent.browser_client();
+    let soft_stop = agent.soft_stop_flag();
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(
@@ -954,7 +1417,7 @@ fn main() {
                                 let screenshot = if mode == "computer" {
                                     #[cfg(target_os = "macos")]
                                     {
-                                        panels::take_screenshot_excluding_app_sync().ok()
+                                        panels::take_help_screenshot_excluding_app_sync().ok()
                                     }
                                     #[cfg(not(target_os = "macos"))]
                                     {
@@ -965,13 +1428,7 @@ fn main() {
                                 };
 
                                 // play recording start sound
-                                #[cfg(target_os = "macos")]
-                                {
-                                    std::process::Command::new("afplay")
-                                        .arg("/System/Library/Sounds/Tink.aiff")
-                                        .spawn()
-                                        .ok();
-                                }
+                                quiet::play_sound(quiet::START_SOUND);
 
                                 // show voice window centered at 300x300
                             #[cfg(target_os = "macos")]
@@ -1010,9 +1467,9 @@ fn main() {
                                 let mode_str = mode.to_string();
                                 tauri::async_runtime::spawn(async move {
                                     if let Some(ptt_state) = app_clone.try_state::<voice_cmd::PttState>() {
-                                        let api_key = match std::env::var("DEEPGRAM_API_KEY") {
-                                            Ok(k) => k,
-                                            Err(_) => {
+                                        let api_key = match permissions::load_api_key_for_service("deepgram") {
+                                            Some(k) => k,
+                                            None => {
                                                 let _ = app_clone.emit("ptt:error", "DEEPGRAM_API_KEY not set");
                                                 return;
                                             }
@@ -1024,7 +1481,7 @@ fn main() {
                                         }
                                         *ptt_state.mode.lock().unwrap() = Some(mode_str);
 
-                                        match ptt_state.session.start(api_key, app_clone.clone()).await {
+                                        match ptt_state.session.start(api_key, crate::voice::voice_language(), crate::voice::input_device_setting().as_deref(), app_clone.clone()).await {
                                             Ok(session_id) => {
                                                 *ptt_state.current_session_id.lock().unwrap() = session_id;
                                                 // session started - first ptt:recording already emitted with mode
@@ -1041,13 +1498,7 @@ fn main() {
                                 println!("[ptt] released - stopping recording");
 
                                 // play recording stop sound
-                                #[cfg(target_os = "macos")]
-                                {
-                                    std::process::Command::new("afplay")
-                                        .arg("/System/Library/Sounds/Pop.aiff")
-                                        .spawn()
-                                        .ok();
-                                }
+                                quiet::play_sound(quiet::STOP_SOUND);
 
                                 // stop recording and get result
                                 let app_clone = app.clone();
@@ -1090,10 +1541,38 @@ fn main() {
 
                     // Cmd+Shift+H - help mode (screenshot + prompt)
                     if shortcut.matches(Modifiers::SUPER | Modifiers::SHIFT, Code::KeyH) {
+                        // browser tasks get the page itself (CDP screenshot + a11y snapshot)
+                        let browser_task = storage::last_active_mode().ok().flatten().as_deref() == Some("browser");
+                        let browser = app.state::<AppState>().browser.clone();
+                        // a held lock means a tool is using the connection, so it's there
+                        let browser_connected = browser.try_lock().map(|guard| guard.is_some()).unwrap_or(true);
+                        if browser_task && browser_connected {
+                            let app = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let payload = match capture_browser_for_help(&browser).await {
+                                    Some((screenshot, snapshot)) => serde_json::json!({
+                                        "screenshot": screenshot,
+                                        "snapshot": snapshot,
+                                        "mode": "browser",
+                                    }),
+                                    None => {
+                                        println!("[heywork] Browser help capture failed, using desktop screenshot");
+                                        let screenshot = tauri::async_runtime::spawn_blocking(capture_screen_for_help)
+                                            .await
+                                            .ok()
+                                            .and_then(|r| r.ok());
+                                        serde_json::json!({ "screenshot": screenshot })
+                                    }
+                                };
+                                let _ = app.emit("hotkey-help", payload);
+                            });
+                            return;
+                        }
+
                         let screenshot = {
                             #[cfg(target_os = "macos")]
                             {
-                                panels::take_screenshot_excluding_app_sync().ok()
+                                panels::take_help_screenshot_excluding_app_sync().ok()
                             }
                             #[cfg(not(target_os = "macos"))]
                             {
@@ -1111,22 +1590,8 @@ fn main() {
                     if shortcut.matches(Modifiers::SUPER | Modifiers::SHIFT, Code::Space) {
                         println!("[heywork] Spotlight mode triggered");
                         let _ = app.emit("hotkey-spotlight", ());
-
-                        #[cfg(target_os = "macos")]
-                        if let Some(panel) = MAIN_PANEL.get() {
-                            panel.show();
-                            // make panel key window so input receives focus
-                            let ns_panel = panel.as_panel();
-                            unsafe {
-                                let _: () = objc2::msg_send![ns_panel, makeKeyAndOrderFront: std::ptr::null::<objc2::runtime::AnyObject>()];
-                            }
-                        }
-
-                        #[cfg(not(target_os = "macos"))]
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
+                        // make the panel key so the input receives keystrokes
+                        tauri::async_runtime::spawn(focus_spotlight(app.clone()));
                     }
 
                     // Cmd+Shift+S - stop agent
@@ -1155,6 +1620,13 @@ fn main() {
         .manage(AppState {
             agent: Arc::new(Mutex::new(agent)),
             running,
+            soft_stop,
+            browser: browser_client,
+            run_guard: run_guard::RunGuard::default(),
+            resumable: std::sync::Mutex::new(storage::find_interrupted_conversation().unwrap_or_else(|e| {
+                println!("[heywork] Couldn't check for an interrupted conversation: {}", e);
+                None
+            })),
         })
         .manage(voice_cmd::VoiceState {
             session: Arc::new(voice::VoiceSession::new()),
@@ -1290,24 +1762,10 @@ fn main() {
                 // Delayed re-apply transparency at multiple intervals.
                 // The WKWebView may reset drawsBackground after initial load,
                 // so we hit it multiple times to be sure.
-                let css_injection = r#"(function(){
-                    var s = document.getElementById('heywork-force-transparent');
-                    if (!s) {
-                        s = document.createElement('style');
-                        s.id = 'heywork-force-transparent';
-                        s.textContent = 'html, body, #root { background: transparent !important; background-color: transparent !important; } html::before, html::after, body::before, body::after { display: none !important; }';
-                        (document.head || document.documentElement).appendChild(s);
-                    }
-                    document.documentElement.style.setProperty('background', 'transparent', 'important');
-                    document.body.style.setProperty('background', 'transparent', 'important');
-                    var root = document.getElementById('root');
-                    if (root) root.style.setProperty('background', 'transparent', 'important');
-                })();"#;
 
                 // Apply at 500ms, 1500ms, and 3000ms
                 for delay_ms in [500u64, 1500, 3000] {
                     let app_handle = app.handle().clone();
-                    let css_js = css_injection.to_string();
                     std::thread::spawn(move || {
                         std::thread::sleep(std::time::Duration::from_millis(delay_ms));
                         // Re-nuke native backgrounds
@@ -1322,7 +1780,7 @@ fn main() {
                         // Inject aggressive CSS into all webviews
                         for label in &["main", "voice", "border"] {
                             if let Some(w) = app_handle.get_webview_window(label) {
-                                let _ = w.eval(&css_js);
+                                let _ = w.eval(FORCE_TRANSPARENT_JS);
                             }
                         }
                         println!("[heywork] Delayed transparency pass at {}ms complete", delay_ms);
@@ -1336,6 +1794,27 @@ fn main() {
             // This block ensures the window is centered and focused on startup.
             #[cfg(not(target_os = "macos"))]
             {
+                // border overlay: a click-through, frameless, shadowless layered
+                // window (tauri maps transparent:true to WS_EX_LAYERED/DWM on
+                // Windows), so only the glow drawn by React is visible
+                if let Some(window) = app.get_webview_window("border") {
+                    let _ = window.set_decorations(false);
+                    let _ = window.set_shadow(false);
+                    let _ = window.set_skip_taskbar(true);
+                    let _ = window.set_background_color(Some(tauri::window::Color(0, 0, 0, 0)));
+                    let _ = window.set_ignore_cursor_events(true);
+                    fit_border_to_work_area(&window);
+
+                    let app_handle = app.handle().clone();
+                    std::thread::spawn(move || {
+                        // wait for the webview to load before forcing its page background clear
+                        std::thread::sleep(std::time::Duration::from_millis(500));
+                        if let Some(w) = app_handle.get_webview_window("border") {
+                            let _ = w.eval(FORCE_TRANSPARENT_JS);
+                        }
+                    });
+                }
+
                 if let Some(window) = app.get_webview_window("main") {
                     println!("[heywork] Windows: Initializing main window");
                     let _ = window.set_skip_taskbar(false);
@@ -1348,10 +1827,14 @@ fn main() {
                 }
             }
 
-            // tray menu with show + quit options
+            // tray menu with show, open data folder, do not disturb + quit options
             let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+            let data_folder = MenuItem::with_id(app, "open_data_folder", "Open data folder", true, None::<&str>)?;
+            let do_not_disturb =
+                CheckMenuItem::with_id(app, "do_not_disturb", "Do not disturb", true, quiet::status().enabled, None::<&str>)?;
             let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let tray_menu = Menu::with_items(app, &[&show, &quit])?;
+            let tray_menu = Menu::with_items(app, &[&show, &data_folder, &do_not_disturb, &quit])?;
+            app.manage(quiet::TrayToggle(do_not_disturb));
 
             TrayIconBuilder::new()
                 .icon(app.default_window_icon().unwrap().clone())
@@ -1367,6 +1850,18 @@ fn main() {
                             }
                             let _ = app.emit("tray:show", ());
                         }
+                        "open_data_folder" => {
+                            if let Err(e) = storage_cmd::open_data_folder() {
+                                eprintln!("[heywork] failed to open data folder: {}", e);
+                            }
+                        }
+                        "do_not_disturb" => {
+                            // the check item already flipped itself; save what it shows now
+                            let enabled = app.try_state::<quiet::TrayToggle>().and_then(|toggle| toggle.0.is_checked().ok());
+                            if let Err(e) = permissions::set_do_not_disturb(app.clone(), enabled.unwrap_or(!quiet::status().enabled)) {
+                                eprintln!("[heywork] failed to save do not disturb: {}", e);
+                            }
+                        }
                         "quit" => {
                             app.exit(0);
                         }
@@ -1416,13 +1911,30 @@ fn main() {
                 })
                 .build(app)?;
 
+            // idles until the user opts in from settings
+            ambient::spawn(app.handle().clone(), app.state::<AppState>().run_guard.clone());
+            quiet::spawn_watcher(app.handle().clone());
+
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            // the main UI is up - offer to resume a run the last session quit in the middle of
+            if webview.label() == "main" && payload.event() == tauri::webview::PageLoadEvent::Finished {
+                match storage_cmd::resumable_candidate(&webview.state::<AppState>()) {
+                    Ok(Some(conv)) => {
+                        println!("[heywork] conversation {} was interrupted, offering resume", conv.id);
+                        let _ = webview.emit("resumable_conversation", conv);
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("[heywork] interrupted run check failed: {}", e),
+                }
+            }
+        })
         .on_window_event(|window, event| {
-            // emit focus lost event for main window (spotlight dismiss)
+            // emit focus events for main window (spotlight dismiss, idle timer reset)
             if window.label() == "main" {
-                if let tauri::WindowEvent::Focused(false) = event {
-                    let _ = window.emit("window:blur", ());
+                if let tauri::WindowEvent::Focused(focused) = event {
+                    let _ = window.emit(if *focused { "window:focus" } else { "window:blur" }, ());
                 }
             }
         })
@@ -1431,11 +1943,24 @@ fn main() {
             check_api_key,
             run_agent,
             stop_agent,
+            stop_after_tool,
+            undo_last_action,
+            replay_conversation,
+            restart_browser_debugging,
+            is_browser_debuggable,
+            health::system_healthcheck,
+            api_cache::clear_api_cache,
             init_agent_swarm,
             get_swarm_task_status,
             list_active_swarm_tasks,
+            cancel_swarm_task,
+            get_memory_stats,
+            reembed_memories,
             export_skills,
             import_skills,
+            list_skill_versions,
+            rollback_skill,
+            rate_skill_execution,
             list_skills,
             confirm_swarm_task,
             is_agent_running,
@@ -1444,6 +1969,7 @@ fn main() {
             show_voice_window,
             hide_voice_window,
             hide_main_window,
+            focus_spotlight_input,
             show_main_voice_response,
             move_panel_to,
             set_main_click_through,
@@ -1455,15 +1981,66 @@ fn main() {
             storage_cmd::load_conversation,
             storage_cmd::create_conversation,
             storage_cmd::save_conversation,
+            storage_cmd::compact_conversation,
             storage_cmd::delete_conversation,
             storage_cmd::search_conversations,
+            storage_cmd::get_usage_stats,
+            storage_cmd::list_conversation_artifacts,
             storage_cmd::set_conversation_voice_mode,
+            storage_cmd::set_conversation_pinned,
+            storage_cmd::rename_conversation,
+            storage_cmd::get_bash_cwd,
+            storage_cmd::get_tool_audit,
+            storage_cmd::get_last_response,
+            python_tool::reset_python_env,
+            python_tool::cancel_python_install,
+            python_tool::list_document_styles,
+            clipboard::copy_to_clipboard,
+            credentials::save_credential,
+            credentials::list_credential_domains,
+            credentials::delete_credential,
+            storage_cmd::fork_conversation,
+            storage_cmd::get_resumable_conversation,
+            storage_cmd::dismiss_resumable_conversation,
+            storage_cmd::get_storage_paths,
+            logging::get_recent_logs,
+            permissions::get_log_level,
+            permissions::get_idle_timeout,
+            permissions::export_settings,
+            import_settings,
+            permissions::save_idle_timeout,
+            permissions::get_help_screenshot_settings,
+            permissions::save_help_screenshot_settings,
+            permissions::get_humanize_mouse,
+            permissions::save_humanize_mouse,
+            permissions::get_ambient_help,
+            permissions::get_do_not_disturb,
+            permissions::set_do_not_disturb,
+            permissions::save_do_not_disturb_options,
+            permissions::save_ambient_help,
+            permissions::get_ambient_interval_secs,
+            permissions::get_max_run_minutes,
+            permissions::save_max_run_minutes,
+            permissions::get_python_skip_autoinstall,
+            permissions::save_python_skip_autoinstall,
+            permissions::get_enabled_tools,
+            permissions::set_enabled_tools,
+            permissions::get_workspace,
+            permissions::set_workspace,
+            permissions::save_log_level,
+            storage_cmd::open_data_folder,
+            research_cmd::list_research_reports,
+            research_cmd::load_research_report,
+            research_cmd::export_research_report,
             voice_cmd::start_voice,
             voice_cmd::stop_voice,
             voice_cmd::is_voice_running,
             voice_cmd::start_ptt,
             voice_cmd::stop_ptt,
             voice_cmd::is_ptt_running,
+            voice_cmd::test_microphone,
+            voice_cmd::list_audio_input_devices,
+            permissions::save_input_device,
             permissions::check_permissions,
             permissions::request_permission,
             permissions::open_permission_settings,
@@ -1471,12 +2048,43 @@ fn main() {
             permissions::open_browser_profile,
             permissions::open_browser_profile_url,
             permissions::clear_domain_cookies,
+            permissions::import_cookies,
             permissions::reset_browser_profile,
             permissions::get_api_key_status,
+            permissions::validate_api_key,
             permissions::save_api_key,
             permissions::get_voice_settings,
             permissions::save_voice_settings,
+            permissions::save_auto_speak_final,
+            permissions::save_voice_language,
+            permissions::get_browser_settings,
+            permissions::save_browser_settings,
+            permissions::save_browser_headless,
+            permissions::save_browser_session_mode,
+            permissions::get_confirm_settings,
+            permissions::save_confirm_settings,
+            permissions::get_embedding_settings,
+            permissions::save_embedding_settings,
+            permissions::get_fallback_models,
+            permissions::save_fallback_models,
+            permissions::get_system_preamble,
+            permissions::save_system_preamble,
+            confirm::confirm_action,
+            rate_limiter::get_rate_limits,
+            rate_limiter::set_rate_limits,
+            bash::get_command_policy,
+            bash::set_command_policy,
+            files::open_file,
+            files::open_file_location,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // an ephemeral session opened outside a run (a browser restart from settings)
+            // would otherwise leave its logged-in profile behind until the next launch
+            if let tauri::RunEvent::Exit = event {
+                let browser = app.state::<AppState>().browser.clone();
+                tauri::async_runtime::block_on(browser::end_ephemeral_session(&browser));
+            }
+        });
 }
@@ -0,0 +1,316 @@
+// on-screen text recognition for the zoom_to_text and click_text tools
+// macOS uses the Vision framework through JXA (no extra deps); other platforms use the
+// tesseract CLI when it's installed
+
+use image::DynamicImage;
+use serde::Deserialize;
+use std::path::Path;
+
+/// recognized text with its bounding box in image pixels (origin top-left)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TextBox {
+    pub text: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextBox {
+    pub fn center(&self) -> (u32, u32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    fn contains_center_of(&self, other: &TextBox) -> bool {
+        let (cx, cy) = other.center();
+        (self.x..=self.x + self.width).contains(&cx) && (self.y..=self.y + self.height).contains(&cy)
+    }
+}
+
+/// a click_text candidate and how sure we are it's what was asked for (0.0 - 1.0)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextMatch {
+    pub text_box: TextBox,
+    pub confidence: f64,
+}
+
+/// what click_text should do for a query
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClickTarget {
+    Found(TextMatch),
+    /// several places match about equally well - best first, nothing gets clicked
+    Ambiguous(Vec<TextMatch>),
+    NotFound,
+}
+
+// a runner-up this close to the best match makes clicking a coin toss
+const AMBIGUITY_MARGIN: f64 = 0.05;
+// most candidates listed back for an ambiguous query
+const MAX_CANDIDATES: usize = 5;
+
+/// OCR an image; boxes are line-level on macOS, words and lines with tesseract
+pub fn recognize_text(image: &DynamicImage) -> Result<Vec<TextBox>, String> {
+    let path = std::env::temp_dir().join(format!("heywork_ocr_{}.png", uuid::Uuid::new_v4()));
+    image.save(&path).map_err(|e| format!("failed to write OCR input: {}", e))?;
+    let result = recognize_file(&path, image.width(), image.height());
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+// Vision returns normalized boxes with a bottom-left origin; flipped to pixels in rust
+#[cfg(target_os = "macos")]
+const VISION_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('Vision');
+function run(argv) {
+    const url = $.NSURL.fileURLWithPath(argv[0]);
+    const request = $.VNRecognizeTextRequest.alloc.init;
+    request.recognitionLevel = 0;
+    request.usesLanguageCorrection = false;
+    const handler = $.VNImageRequestHandler.alloc.initWithURLOptions(url, $({}));
+    if (!handler.performRequestsError($([request]), null)) return '[]';
+    const out = [];
+    const results = request.results;
+    for (let i = 0; i < results.count; i++) {
+        const observation = results.objectAtIndex(i);
+        const candidates = observation.topCandidates(1);
+        if (candidates.count === 0) continue;
+        const box = observation.boundingBox;
+        out.push({
+            text: candidates.objectAtIndex(0).string.js,
+            x: box.origin.x, y: box.origin.y, w: box.size.width, h: box.size.height
+        });
+    }
+    return JSON.stringify(out);
+}
+"#;
+
+#[cfg(target_os = "macos")]
+fn recognize_file(path: &Path, width: u32, height: u32) -> Result<Vec<TextBox>, String> {
+    #[derive(Deserialize)]
+    struct VisionBox {
+        text: String,
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+    }
+
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", VISION_SCRIPT])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("failed to run Vision OCR: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Vision OCR failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let boxes: Vec<VisionBox> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("unexpected Vision OCR output: {}", e))?;
+    let (w, h) = (f64::from(width), f64::from(height));
+    Ok(boxes
+        .into_iter()
+        .map(|b| TextBox {
+            text: b.text,
+            x: (b.x * w).max(0.0) as u32,
+            y: ((1.0 - b.y - b.h) * h).max(0.0) as u32,
+            width: (b.w * w) as u32,
+            height: (b.h * h) as u32,
+        })
+        .collect())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn recognize_file(path: &Path, _width: u32, _height: u32) -> Result<Vec<TextBox>, String> {
+    let output = std::process::Command::new("tesseract")
+        .arg(path)
+        .args(["stdout", "tsv"])
+        .output()
+        .map_err(|_| "OCR needs tesseract installed and on PATH".to_string())?;
+    if !output.status.success() {
+        return Err(format!("tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// parse `tesseract ... tsv` into word boxes plus one merged box per line
+/// (so multi-word queries can match)
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn parse_tesseract_tsv(tsv: &str) -> Vec<TextBox> {
+    let mut words: Vec<TextBox> = Vec::new();
+    let mut lines: Vec<((u32, u32, u32), TextBox)> = Vec::new();
+
+    // level page block par line word left top width height conf text
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.split('\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let text = cols[11].trim();
+        if text.is_empty() {
+            continue;
+        }
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0);
+        let word = TextBox { text: text.to_string(), x: num(6), y: num(7), width: num(8), height: num(9) };
+        let key = (num(2), num(3), num(4));
+
+        match lines.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, line)) => {
+                let right = (line.x + line.width).max(word.x + word.width);
+                let bottom = (line.y + line.height).max(word.y + word.height);
+                line.x = line.x.min(word.x);
+                line.y = line.y.min(word.y);
+                line.width = right - line.x;
+                line.height = bottom - line.y;
+                line.text.push(' ');
+                line.text.push_str(&word.text);
+            }
+            None => lines.push((key, word.clone())),
+        }
+        words.push(word);
+    }
+
+    words.extend(lines.into_iter().map(|(_, line)| line));
+    words
+}
+
+/// best box for a query: exact match, then prefix, then substring; shorter text wins ties
+pub fn find_text<'a>(boxes: &'a [TextBox], query: &str) -> Option<&'a TextBox> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    boxes
+        .iter()
+        .filter_map(|b| {
+            let text = b.text.trim().to_lowercase();
+            let rank = if text == query {
+                0
+            } else if text.starts_with(&query) {
+                1
+            } else if text.contains(&query) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, text.len(), b))
+        })
+        .min_by_key(|(rank, len, _)| (*rank, *len))
+        .map(|(_, _, b)| b)
+}
+
+// exact 1.0; prefix and substring matches score lower, and lower still the less of the text they cover
+fn match_confidence(text: &str, query: &str) -> Option<f64> {
+    let text = text.trim().to_lowercase();
+    let coverage = query.chars().count() as f64 / text.chars().count().max(1) as f64;
+    if text == query {
+        Some(1.0)
+    } else if text.starts_with(query) {
+        Some(0.5 + 0.4 * coverage)
+    } else if text.contains(query) {
+        Some(0.3 + 0.4 * coverage)
+    } else {
+        None
+    }
+}
+
+/// every box containing the query, best first. a box overlapping a better one (a tesseract
+/// word inside its own line) is the same spot on screen and is dropped
+pub fn rank_text(boxes: &[TextBox], query: &str) -> Vec<TextMatch> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<TextMatch> = boxes
+        .iter()
+        .filter_map(|b| Some(TextMatch { text_box: b.clone(), confidence: match_confidence(&b.text, &query)? }))
+        .collect();
+    matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+    let mut distinct: Vec<TextMatch> = Vec::new();
+    for candidate in matches {
+        let repeats = distinct.iter().any(|kept| {
+            kept.text_box.contains_center_of(&candidate.text_box) || candidate.text_box.contains_center_of(&kept.text_box)
+        });
+        if !repeats {
+            distinct.push(candidate);
+        }
+    }
+    distinct
+}
+
+/// the one box click_text should click, or the close candidates when it can't tell
+pub fn click_target(boxes: &[TextBox], query: &str) -> ClickTarget {
+    let mut ranked = rank_text(boxes, query);
+    let ambiguous = ranked.len() > 1 && ranked[0].confidence - ranked[1].confidence < AMBIGUITY_MARGIN;
+    if ambiguous {
+        ranked.truncate(MAX_CANDIDATES);
+        ClickTarget::Ambiguous(ranked)
+    } else if ranked.is_empty() {
+        ClickTarget::NotFound
+    } else {
+        ClickTarget::Found(ranked.swap_remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_box(text: &str) -> TextBox {
+        TextBox { text: text.to_string(), x: 0, y: 0, width: 10, height: 10 }
+    }
+
+    fn placed(text: &str, x: u32, y: u32, width: u32) -> TextBox {
+        TextBox { text: text.to_string(), x, y, width, height: 20 }
+    }
+
+    #[test]
+    fn prefers_exact_then_shortest_match() {
+        let boxes = vec![text_box("Save as template"), text_box("Save"), text_box("Autosave")];
+        assert_eq!(find_text(&boxes, "save").unwrap().text, "Save");
+        assert_eq!(find_text(&boxes, "as temp").unwrap().text, "Save as template");
+        assert!(find_text(&boxes, "export").is_none());
+        assert!(find_text(&boxes, "  ").is_none());
+    }
+
+    #[test]
+    fn parses_tesseract_words_and_lines() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   4\t1\t1\t1\t1\t0\t10\t20\t200\t15\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t10\t20\t50\t15\t96\tSign\n\
+                   5\t1\t1\t1\t1\t2\t70\t22\t40\t13\t95\tin\n";
+        let boxes = parse_tesseract_tsv(tsv);
+        assert_eq!(boxes.len(), 3);
+        let line = find_text(&boxes, "sign in").unwrap();
+        assert_eq!((line.x, line.y, line.width, line.height), (10, 20, 100, 15));
+    }
+
+    #[test]
+    fn clicks_a_clear_best_match() {
+        // the word "Save" sits inside its own line box - one spot, not two candidates
+        let boxes = vec![placed("Save", 10, 10, 40), placed("Save as template", 10, 10, 160), placed("Autosave", 300, 10, 80)];
+        match click_target(&boxes, "save") {
+            ClickTarget::Found(found) => {
+                assert_eq!(found.text_box.text, "Save");
+                assert_eq!(found.confidence, 1.0);
+                assert_eq!(found.text_box.center(), (30, 20));
+            }
+            other => panic!("expected a match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn equal_matches_in_different_places_are_ambiguous() {
+        let boxes = vec![placed("Delete", 10, 10, 60), placed("Delete", 10, 400, 60), placed("Deleted items", 500, 10, 120)];
+        match click_target(&boxes, "delete") {
+            ClickTarget::Ambiguous(candidates) => {
+                assert_eq!(candidates.len(), 3);
+                assert_eq!(candidates[0].confidence, 1.0);
+                assert_eq!(candidates[2].text_box.text, "Deleted items");
+            }
+            other => panic!("expected ambiguity, got {:?}", other),
+        }
+        assert_eq!(click_target(&boxes, "archive"), ClickTarget::NotFound);
+    }
+}
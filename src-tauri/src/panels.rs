@@ -10,9 +10,174 @@ pub static VOICE_PANEL: std::sync::OnceLock<PanelHandle<tauri::Wry>> = std::sync
 #[cfg(target_os = "macos")]
 pub static BORDER_PANEL: std::sync::OnceLock<PanelHandle<tauri::Wry>> = std::sync::OnceLock::new();
 
-// core screenshot logic - must be called on main thread
+// run `f` on the main thread - directly when already on it (shortcut handlers), dispatched otherwise
 #[cfg(target_os = "macos")]
-fn take_screenshot_excluding_impl() -> Result<String, String> {
+fn on_main_thread<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    if objc2_foundation::MainThreadMarker::new().is_some() {
+        f()
+    } else {
+        dispatch::Queue::main().exec_sync(f)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn app_panels() -> impl Iterator<Item = (&'static str, &'static PanelHandle<tauri::Wry>)> {
+    [("main", &MAIN_PANEL), ("voice", &VOICE_PANEL), ("border", &BORDER_PANEL)]
+        .into_iter()
+        .filter_map(|(label, cell)| cell.get().map(|panel| (label, panel)))
+}
+
+/// make every visible panel covering one of `points` (screen points, top-left origin)
+/// click-through, so an agent click reaches the app underneath instead of our own UI.
+/// Returns the panels that were changed - pass them to `restore_panel_clicks` afterwards
+#[cfg(target_os = "macos")]
+pub fn pass_clicks_through_panels_at(points: &[(f64, f64)]) -> Vec<&'static str> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    on_main_thread(|| {
+        // cocoa frames are bottom-left based on the primary display, clicks are top-left based
+        let screen_height = core_graphics::display::CGDisplay::main().bounds().size.height;
+        app_panels()
+            .filter(|(_, panel)| panel.is_visible())
+            .filter_map(|(label, panel)| {
+                let ns_panel = panel.as_panel();
+                let (frame, ignoring): (objc2_foundation::NSRect, bool) = unsafe {
+                    (objc2::msg_send![ns_panel, frame], objc2::msg_send![ns_panel, ignoresMouseEvents])
+                };
+                let top = screen_height - frame.origin.y - frame.size.height;
+                let hit = points.iter().any(|&(x, y)| {
+                    x >= frame.origin.x
+                        && x < frame.origin.x + frame.size.width
+                        && y >= top
+                        && y < top + frame.size.height
+                });
+                (hit && !ignoring).then(|| {
+                    panel.set_ignores_mouse_events(true);
+                    label
+                })
+            })
+            .collect()
+    })
+}
+
+/// undo `pass_clicks_through_panels_at`
+#[cfg(target_os = "macos")]
+pub fn restore_panel_clicks(labels: &[&'static str]) {
+    if labels.is_empty() {
+        return;
+    }
+    on_main_thread(|| {
+        for (label, panel) in app_panels() {
+            if labels.contains(&label) {
+                panel.set_ignores_mouse_events(false);
+            }
+        }
+    });
+}
+
+// how long the app stays a regular (dock) app when that's what it took to get key focus
+#[cfg(target_os = "macos")]
+const REGULAR_POLICY_MS: u64 = 300;
+
+/// whether the main panel is the key window, i.e. gets the keystrokes
+#[cfg(target_os = "macos")]
+pub fn main_panel_is_key() -> bool {
+    let Some(panel) = MAIN_PANEL.get() else {
+        return false;
+    };
+    on_main_thread(|| unsafe { objc2::msg_send![panel.as_panel(), isKeyWindow] })
+}
+
+// wait before checking whether the panel became key - activation lands on a later runloop turn
+#[cfg(target_os = "macos")]
+const KEY_SETTLE_MS: u64 = 50;
+
+/// show the main panel and make it key. a nonactivating panel doesn't always become key while
+/// another app is frontmost (full-screen ones especially), so each step goes further: makeKey,
+/// then activating the app, then briefly switching to a regular app. returns whether it's key
+#[cfg(target_os = "macos")]
+pub async fn make_main_panel_key(app: &tauri::AppHandle) -> bool {
+    let Some(panel) = MAIN_PANEL.get() else {
+        return false;
+    };
+
+    on_main_thread(|| {
+        panel.show();
+        make_panel_key(panel);
+    });
+    if settled_is_key().await {
+        return true;
+    }
+    println!("[panels] Main panel didn't become key, activating the app");
+    on_main_thread(|| {
+        activate_app();
+        make_panel_key(panel);
+    });
+    if settled_is_key().await {
+        return true;
+    }
+    println!("[panels] Still not key, switching to a regular app for a moment");
+    on_main_thread(|| {
+        let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+        activate_app();
+        make_panel_key(panel);
+    });
+    let key = settled_is_key().await;
+    // back to menubar-only once the activation has gone through
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(REGULAR_POLICY_MS));
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || {
+            let _ = handle.set_activation_policy(tauri::ActivationPolicy::Accessory);
+        });
+    });
+    key
+}
+
+// give the main thread's runloop a turn before asking, so the last step has taken effect
+#[cfg(target_os = "macos")]
+async fn settled_is_key() -> bool {
+    tokio::time::sleep(std::time::Duration::from_millis(KEY_SETTLE_MS)).await;
+    main_panel_is_key()
+}
+
+#[cfg(target_os = "macos")]
+fn make_panel_key(panel: &PanelHandle<tauri::Wry>) {
+    unsafe {
+        let _: () = objc2::msg_send![panel.as_panel(), makeKeyAndOrderFront: std::ptr::null::<objc2::runtime::AnyObject>()];
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn activate_app() {
+    unsafe {
+        let ns_app: *mut objc2::runtime::AnyObject = objc2::msg_send![objc2::class!(NSApplication), sharedApplication];
+        let _: () = objc2::msg_send![ns_app, activateIgnoringOtherApps: true];
+    }
+}
+
+// env var for collapsing an idle expanded main panel after N seconds (0 = never)
+pub const IDLE_TIMEOUT_ENV: &str = "HEYWORK_IDLE_TIMEOUT_SECS";
+
+/// longest idle timeout accepted, in seconds
+pub const MAX_IDLE_TIMEOUT_SECS: u32 = 3600;
+
+/// seconds before an idle main panel collapses back to the orb (0 = disabled, the default)
+pub fn idle_timeout_secs() -> u32 {
+    std::env::var(IDLE_TIMEOUT_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .map_or(0, |secs: u32| secs.min(MAX_IDLE_TIMEOUT_SECS))
+}
+
+// hide the border and voice panels, run `capture` with the main panel's window id (for
+// BelowWindow exclusion, None when it's hidden), then restore them - must be called on main thread
+#[cfg(target_os = "macos")]
+fn capture_with_app_hidden<T>(
+    capture: impl FnOnce(&crate::computer::ComputerControl, Option<u32>) -> Result<T, String>,
+) -> Result<T, String> {
     use crate::computer::ComputerControl;
 
     let control = ComputerControl::new().map_err(|e| e.to_string())?;
@@ -53,12 +218,7 @@ fn take_screenshot_excluding_impl() -> Result<String, String> {
         std::thread::sleep(std::time::Duration::from_millis(10));
     }
 
-    // take screenshot
-    let screenshot = if let Some(wid) = main_id {
-        control.take_screenshot_excluding(wid).map_err(|e| e.to_string())?
-    } else {
-        control.take_screenshot().map_err(|e| e.to_string())?
-    };
+    let result = capture(&control, main_id);
 
     // restore panels
     if border_was_visible {
@@ -72,7 +232,19 @@ fn take_screenshot_excluding_impl() -> Result<String, String> {
         }
     }
 
-    Ok(screenshot)
+    result
+}
+
+// core screenshot logic - must be called on main thread
+#[cfg(target_os = "macos")]
+fn take_screenshot_excluding_impl() -> Result<String, String> {
+    capture_with_app_hidden(|control, main_id| {
+        match main_id {
+            Some(wid) => control.take_screenshot_excluding(wid),
+            None => control.take_screenshot(),
+        }
+        .map_err(|e| e.to_string())
+    })
 }
 
 // take screenshot excluding app windows - dispatches to main thread
@@ -83,11 +255,13 @@ pub fn take_screenshot_excluding_app() -> Result<String, String> {
     Queue::main().exec_sync(take_screenshot_excluding_impl)
 }
 
-// take screenshot excluding app windows - no dispatch, call when already on main thread
-// use from shortcut handlers
+// context screenshot for the help and push-to-talk hotkeys (configured format and area),
+// excluding app windows - no dispatch, call when already on main thread (shortcut handlers)
 #[cfg(target_os = "macos")]
-pub fn take_screenshot_excluding_app_sync() -> Result<String, String> {
-    take_screenshot_excluding_impl()
+pub fn take_help_screenshot_excluding_app_sync() -> Result<String, String> {
+    capture_with_app_hidden(|control, main_id| {
+        control.take_help_screenshot(main_id).map_err(|e| e.to_string())
+    })
 }
 
 // zoom screenshot of region excluding app windows - dispatches to main thread for Panel access
@@ -33,6 +33,15 @@ pub struct BrowserProfileStatus {
     pub exists: bool,
     pub path: String,
     pub sessions: Vec<String>, // domains with cookies
+    pub headless: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieImportReport {
+    pub imported: Vec<String>, // domains with at least one cookie injected
+    pub skipped: Vec<String>,  // domains whose cookies couldn't be decrypted
+    pub cookie_count: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,10 +52,32 @@ pub struct ApiKeyStatus {
     pub elevenlabs: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyValidation {
+    pub valid: bool,
+    pub message: String,
+    pub tier: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VoiceSettings {
     pub elevenlabs_voice_id: Option<String>,
+    pub auto_speak_final_response: bool,
+    /// BCP-47 tag voice mode listens and answers in, "multi" to auto-detect
+    pub language: String,
+    /// microphone to capture from, None for the system default
+    pub input_device: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserSettings {
+    pub browser: crate::browser::BrowserKind,
+    pub installed: Vec<crate::browser::BrowserKind>,
+    pub headless: bool,
+    pub session_mode: crate::browser::BrowserSessionMode,
 }
 
 const KEYRING_SERVICE: &str = "com.heywork.app";
@@ -60,18 +91,53 @@ fn api_env_var_for_service(service: &str) -> Option<&'static str> {
     }
 }
 
+// voice keys get their own keychain service; the anthropic key stays where it always was
+fn keyring_service_for(var_name: &str) -> String {
+    match var_name {
+        "DEEPGRAM_API_KEY" => format!("{}.deepgram", KEYRING_SERVICE),
+        "ELEVENLABS_API_KEY" => format!("{}.elevenlabs", KEYRING_SERVICE),
+        _ => KEYRING_SERVICE.to_string(),
+    }
+}
+
+// keys set in the settings UI win over a stale .env for the voice services
+fn prefers_keychain(var_name: &str) -> bool {
+    matches!(var_name, "DEEPGRAM_API_KEY" | "ELEVENLABS_API_KEY")
+}
+
+fn read_keychain(var_name: &str) -> Option<String> {
+    let service = keyring_service_for(var_name);
+    let read = |service: &str| {
+        keyring::Entry::new(service, var_name)
+            .ok()?
+            .get_password()
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+    };
+    // voice keys saved before they had their own service
+    read(&service).or_else(|| {
+        if service != KEYRING_SERVICE {
+            read(KEYRING_SERVICE)
+        } else {
+            None
+        }
+    })
+}
+
+fn read_env_key(var_name: &str) -> Option<String> {
+    std::env::var(var_name).ok().filter(|value| !value.trim().is_empty())
+}
+
 fn read_api_key_secure(var_name: &str) -> Option<String> {
-    if let Ok(value) = std::env::var(var_name) {
-        if !value.trim().is_empty() {
+    if !prefers_keychain(var_name) {
+        if let Some(value) = read_env_key(var_name) {
             return Some(value);
         }
     }
 
-    let entry = keyring::Entry::new(KEYRING_SERVICE, var_name).ok()?;
-    let key = entry.get_password().ok()?;
-    if key.trim().is_empty() {
-        return None;
-    }
+    let Some(key) = read_keychain(var_name) else {
+        return read_env_key(var_name);
+    };
     std::env::set_var(var_name, key.clone());
     Some(key)
 }
@@ -142,6 +208,24 @@ pub fn check_permissions() -> PermissionsCheck {
     }
 }
 
+/// microphone access on its own, without the slower screen checks
+pub fn microphone_permission() -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        check_microphone()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        PermissionStatus::NotNeeded
+    }
+}
+
+/// whether we may read other apps' windows and drive input
+#[cfg(target_os = "macos")]
+pub fn has_accessibility() -> bool {
+    matches!(check_accessibility(), PermissionStatus::Granted)
+}
+
 #[cfg(target_os = "macos")]
 fn check_accessibility() -> PermissionStatus {
     if unsafe { AXIsProcessTrusted() } {
@@ -353,6 +437,7 @@ pub fn get_browser_profile_status() -> BrowserProfileStatus {
             exists: false,
             path: profile_path,
             sessions: vec![],
+            headless: crate::browser::headless_enabled(),
         };
     }
 
@@ -383,6 +468,7 @@ pub fn get_browser_profile_status() -> BrowserProfileStatus {
         exists: true,
         path: profile_path,
         sessions,
+        headless: crate::browser::headless_enabled(),
     }
 }
 
@@ -450,6 +536,262 @@ pub fn clear_domain_cookies(domain: String) -> Result<(), String> {
     Ok(())
 }
 
+// seconds between 1601-01-01 (chrome's cookie epoch) and the unix epoch
+const CHROME_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
+
+// a cookie row from the user's own chrome profile
+struct SourceCookie {
+    host: String,
+    name: String,
+    value: Option<String>, // None if decryption failed
+    path: String,
+    expires_utc: i64,
+    secure: bool,
+    http_only: bool,
+    same_site: i64,
+}
+
+// the user's everyday chrome profile (not the automation one)
+fn default_chrome_cookies_db() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let base = dirs::home_dir().map(|h| h.join("Library/Application Support/Google/Chrome/Default"));
+    #[cfg(target_os = "windows")]
+    let base = dirs::data_local_dir().map(|d| d.join("Google/Chrome/User Data/Default"));
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let base = dirs::config_dir().map(|d| d.join("google-chrome/Default"));
+
+    let base = base?;
+    // newer chrome keeps cookies under Network/
+    [base.join("Network/Cookies"), base.join("Cookies")]
+        .into_iter()
+        .find(|p| p.exists())
+}
+
+// AES key chrome uses for v10/v11 cookie values
+// macOS: password lives in the keychain as "Chrome Safe Storage" (prompts the user once)
+#[cfg(target_os = "macos")]
+fn chrome_cookie_key(_version: &str) -> Option<[u8; 16]> {
+    let output = std::process::Command::new("security")
+        .args(["find-generic-password", "-w", "-s", "Chrome Safe Storage", "-a", "Chrome"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        println!("[permissions] Keychain access to Chrome Safe Storage denied");
+        return None;
+    }
+    let password = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(derive_cookie_key(password.as_bytes(), 1003))
+}
+
+// linux: v10 uses a fixed password, v11 uses the one stored in the secret service
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn chrome_cookie_key(version: &str) -> Option<[u8; 16]> {
+    if version == "v10" {
+        return Some(derive_cookie_key(b"peanuts", 1));
+    }
+    let output = std::process::Command::new("secret-tool")
+        .args(["lookup", "application", "chrome"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let password = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(derive_cookie_key(password.as_bytes(), 1))
+}
+
+// windows encrypts with DPAPI + AES-GCM, which we don't support yet
+#[cfg(target_os = "windows")]
+fn chrome_cookie_key(_version: &str) -> Option<[u8; 16]> {
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+fn derive_cookie_key(password: &[u8], iterations: u32) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, b"saltysalt", iterations, &mut key);
+    key
+}
+
+// decrypt a "v10"/"v11" AES-128-CBC cookie value
+// db_version >= 24 prepends a 32-byte sha256 of the host to the plaintext
+fn decrypt_cookie_value(encrypted: &[u8], key: &[u8; 16], db_version: i64) -> Option<String> {
+    use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+    let ciphertext = encrypted.get(3..)?;
+    let mut buf = ciphertext.to_vec();
+    let iv = [b' '; 16];
+    let plain = cbc::Decryptor::<aes::Aes128>::new(key.into(), &iv.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut buf)
+        .ok()?;
+    let plain = if db_version >= 24 { plain.get(32..)? } else { plain };
+    String::from_utf8(plain.to_vec()).ok()
+}
+
+fn read_source_cookies(db_path: &std::path::Path, domain: &str) -> Result<Vec<SourceCookie>, String> {
+    // copy db to a private temp dir (chrome locks it); the dir goes away on every return path
+    let temp_dir = tempfile::TempDir::new().map_err(|e| e.to_string())?;
+    let temp_path = temp_dir.path().join("Cookies");
+    std::fs::copy(db_path, &temp_path).map_err(|e| e.to_string())?;
+    // cookies chrome wrote recently can still be in the write-ahead log only
+    let mut wal_path = db_path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    let wal_path = PathBuf::from(wal_path);
+    if wal_path.exists() {
+        std::fs::copy(&wal_path, temp_dir.path().join("Cookies-wal")).map_err(|e| e.to_string())?;
+    }
+
+    let conn = rusqlite::Connection::open(&temp_path).map_err(|e| e.to_string())?;
+
+    let db_version: i64 = conn
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT host_key, name, value, encrypted_value, path, expires_utc, is_secure, is_httponly, samesite \
+             FROM cookies WHERE host_key = ?1 OR host_key = ?2 OR host_key LIKE ?3",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut keys: std::collections::HashMap<String, Option<[u8; 16]>> = std::collections::HashMap::new();
+    let rows = stmt
+        .query_map(
+            [domain.to_string(), format!(".{}", domain), format!("%.{}", domain)],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Vec<u8>>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, bool>(6)?,
+                    row.get::<_, bool>(7)?,
+                    row.get::<_, i64>(8)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut cookies = vec![];
+    for (host, name, plain, encrypted, path, expires_utc, secure, http_only, same_site) in rows.flatten() {
+        let value = if encrypted.is_empty() {
+            Some(plain)
+        } else {
+            let version = String::from_utf8_lossy(encrypted.get(..3).unwrap_or_default()).to_string();
+            let key = keys
+                .entry(version.clone())
+                .or_insert_with(|| chrome_cookie_key(&version));
+            key.as_ref()
+                .and_then(|k| decrypt_cookie_value(&encrypted, k, db_version))
+        };
+        cookies.push(SourceCookie { host, name, value, path, expires_utc, secure, http_only, same_site });
+    }
+
+    Ok(cookies)
+}
+
+fn to_set_cookie_params(
+    cookie: &SourceCookie,
+    value: &str,
+) -> Option<chromiumoxide::cdp::browser_protocol::network::SetCookieParams> {
+    use chromiumoxide::cdp::browser_protocol::network::{CookieSameSite, SetCookieParams, TimeSinceEpoch};
+
+    let mut builder = SetCookieParams::builder()
+        .name(cookie.name.clone())
+        .value(value)
+        .domain(cookie.host.clone())
+        .path(cookie.path.clone())
+        .secure(cookie.secure)
+        .http_only(cookie.http_only);
+    // 0 = session cookie
+    if cookie.expires_utc > 0 {
+        let unix_secs = cookie.expires_utc / 1_000_000 - CHROME_EPOCH_OFFSET_SECS;
+        builder = builder.expires(TimeSinceEpoch::new(unix_secs as f64));
+    }
+    builder = match cookie.same_site {
+        0 => builder.same_site(CookieSameSite::None),
+        1 => builder.same_site(CookieSameSite::Lax),
+        2 => builder.same_site(CookieSameSite::Strict),
+        _ => builder,
+    };
+    builder.build().ok()
+}
+
+// copy a domain's cookies from the user's everyday chrome into the automation profile
+// complements clear_domain_cookies - lets the agent reuse existing logins
+#[tauri::command]
+pub async fn import_cookies(domain: String) -> Result<CookieImportReport, String> {
+    let domain = domain.trim().trim_start_matches('.').to_lowercase();
+    if domain.is_empty() {
+        return Err("domain is required".to_string());
+    }
+    // an ephemeral session would launch a throwaway browser just to hold the cookies
+    if crate::browser::selected_session_mode() == crate::browser::BrowserSessionMode::Ephemeral {
+        return Err("Cookie import needs a persistent browser session - switch the session mode in Settings first".to_string());
+    }
+
+    let db_path = default_chrome_cookies_db().ok_or("Chrome profile not found")?;
+    println!("[permissions] Importing cookies for {} from {}", domain, db_path.display());
+
+    let source = {
+        let domain = domain.clone();
+        tokio::task::spawn_blocking(move || read_source_cookies(&db_path, &domain))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+    if source.is_empty() {
+        return Err(format!("No cookies for {} in your Chrome profile", domain));
+    }
+
+    let mut params = vec![];
+    let mut imported: Vec<String> = vec![];
+    let mut skipped: Vec<String> = vec![];
+    for cookie in &source {
+        let host = cookie.host.trim_start_matches('.').to_string();
+        match cookie.value.as_deref().and_then(|v| to_set_cookie_params(cookie, v)) {
+            Some(p) => {
+                params.push(p);
+                if !imported.contains(&host) {
+                    imported.push(host);
+                }
+            }
+            None => {
+                if !skipped.contains(&host) {
+                    skipped.push(host);
+                }
+            }
+        }
+    }
+
+    let cookie_count = if params.is_empty() {
+        0
+    } else {
+        let mut browser = crate::browser::BrowserClient::connect()
+            .await
+            .map_err(|e| e.to_string())?;
+        browser.set_cookies(params).await.map_err(|e| e.to_string())?
+    };
+
+    println!(
+        "[permissions] Imported {} cookies ({} domains), skipped {} domains",
+        cookie_count,
+        imported.len(),
+        skipped.len()
+    );
+
+    Ok(CookieImportReport {
+        imported,
+        skipped,
+        cookie_count,
+    })
+}
+
 // open browser profile in chrome for manual login
 #[tauri::command]
 pub fn open_browser_profile() -> Result<(), String> {
@@ -556,11 +898,127 @@ pub fn get_api_key_status() -> ApiKeyStatus {
     }
 }
 
+// account endpoints that answer cheaply for any valid key
+const DEEPGRAM_PROJECTS_URL: &str = "https://api.deepgram.com/v1/projects";
+const ELEVENLABS_SUBSCRIPTION_URL: &str = "https://api.elevenlabs.io/v1/user/subscription";
+const KEY_CHECK_TIMEOUT_SECS: u64 = 10;
+// how long a successful check is trusted before hitting the endpoint again
+const KEY_CHECK_CACHE_SECS: u64 = 300;
+
+// service -> (key that was checked, when, result); only positive results are cached
+type KeyCheckCache = std::collections::HashMap<String, (String, std::time::Instant, ApiKeyValidation)>;
+static KEY_CHECKS: std::sync::OnceLock<std::sync::Mutex<KeyCheckCache>> = std::sync::OnceLock::new();
+
+fn service_display_name(service: &str) -> &'static str {
+    match service {
+        "deepgram" => "Deepgram",
+        "elevenlabs" => "ElevenLabs",
+        _ => "Anthropic",
+    }
+}
+
+// one cheap authenticated request per service
+async fn probe_api_key(service: &str, key: &str) -> Result<reqwest::Response, reqwest::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(KEY_CHECK_TIMEOUT_SECS))
+        .build()?;
+    match service {
+        "deepgram" => {
+            client
+                .get(DEEPGRAM_PROJECTS_URL)
+                .header("Authorization", format!("Token {}", key))
+                .send()
+                .await
+        }
+        "elevenlabs" => client.get(ELEVENLABS_SUBSCRIPTION_URL).header("xi-api-key", key).send().await,
+        _ => {
+            // 1-token completion on the cheapest model
+            client
+                .post(crate::api::ANTHROPIC_API_URL)
+                .header("x-api-key", key)
+                .header("anthropic-version", crate::api::API_VERSION)
+                .json(&serde_json::json!({
+                    "model": "claude-haiku-4-5-20251001",
+                    "max_tokens": 1,
+                    "messages": [{ "role": "user", "content": "hi" }]
+                }))
+                .send()
+                .await
+        }
+    }
+}
+
+// check that the saved key for a service is accepted, not just present
+#[tauri::command]
+pub async fn validate_api_key(service: String) -> Result<ApiKeyValidation, String> {
+    let var_name = api_env_var_for_service(&service).ok_or_else(|| "Unknown service".to_string())?;
+    let name = service_display_name(&service);
+    let Some(key) = read_api_key_secure(var_name) else {
+        return Ok(ApiKeyValidation {
+            valid: false,
+            message: format!("No {} API key set", name),
+            tier: None,
+        });
+    };
+
+    let cache = KEY_CHECKS.get_or_init(Default::default);
+    if let Some((cached_key, checked_at, result)) = cache.lock().unwrap().get(&service) {
+        if *cached_key == key && checked_at.elapsed().as_secs() < KEY_CHECK_CACHE_SECS {
+            return Ok(result.clone());
+        }
+    }
+
+    // network trouble means "couldn't check", not "invalid"
+    let response = probe_api_key(&service, &key)
+        .await
+        .map_err(|e| format!("Couldn't reach {} to check the key: {}", name, e))?;
+    let status = response.status();
+    if status.as_u16() == 401 || status.as_u16() == 403 {
+        return Ok(ApiKeyValidation {
+            valid: false,
+            message: format!("{} rejected this API key - check it was copied in full", name),
+            tier: None,
+        });
+    }
+    if !status.is_success() && status.as_u16() != 429 {
+        return Err(format!("Couldn't check the {} key (HTTP {})", name, status));
+    }
+
+    let tier = match service.as_str() {
+        // the request limit header tracks the account's usage tier
+        "anthropic" => response
+            .headers()
+            .get("anthropic-ratelimit-requests-limit")
+            .and_then(|v| v.to_str().ok())
+            .map(|limit| format!("{} requests/min", limit)),
+        "elevenlabs" => response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("tier").and_then(|t| t.as_str()).map(String::from)),
+        _ => None,
+    };
+
+    let result = ApiKeyValidation {
+        valid: true,
+        message: format!("{} API key is valid", name),
+        tier,
+    };
+    cache
+        .lock()
+        .unwrap()
+        .insert(service, (key, std::time::Instant::now(), result.clone()));
+    Ok(result)
+}
+
 // get voice settings
 #[tauri::command]
 pub fn get_voice_settings() -> VoiceSettings {
     VoiceSettings {
         elevenlabs_voice_id: std::env::var("ELEVENLABS_VOICE_ID").ok(),
+        auto_speak_final_response: crate::voice::auto_speak_final_enabled(),
+        language: crate::voice::voice_language().unwrap_or(crate::voice::AUTO_LANGUAGE).to_string(),
+        input_device: crate::voice::input_device_setting(),
     }
 }
 
@@ -570,6 +1028,294 @@ pub fn save_voice_settings(voice_id: String) -> Result<(), String> {
     save_env_var("ELEVENLABS_VOICE_ID", &voice_id)
 }
 
+// speak the final answer of voice-mode runs even when the model didn't call speak
+#[tauri::command]
+pub fn save_auto_speak_final(enabled: bool) -> Result<(), String> {
+    save_env_var(crate::voice::AUTO_SPEAK_FINAL_ENV, if enabled { "true" } else { "false" })
+}
+
+// microphone for voice capture by name; None goes back to the system default. a saved
+// device that's unplugged falls back to the default until it's back
+#[tauri::command]
+pub fn save_input_device(device: Option<String>) -> Result<(), String> {
+    save_env_var(crate::voice::INPUT_DEVICE_ENV, device.as_deref().map(str::trim).unwrap_or(""))
+}
+
+// language for transcription and replies - rejects tags deepgram can't transcribe
+#[tauri::command]
+pub fn save_voice_language(language: String) -> Result<(), String> {
+    let language = crate::voice::validate_language(&language)?;
+    save_env_var(crate::voice::VOICE_LANGUAGE_ENV, language)
+}
+
+// get browser automation settings
+#[tauri::command]
+pub fn get_browser_settings() -> BrowserSettings {
+    use crate::browser::BrowserKind;
+    BrowserSettings {
+        browser: crate::browser::selected_browser_kind(),
+        installed: [BrowserKind::Chrome, BrowserKind::Edge, BrowserKind::Firefox]
+            .into_iter()
+            .filter(|k| crate::browser::is_browser_installed(*k))
+            .collect(),
+        headless: crate::browser::headless_enabled(),
+        session_mode: crate::browser::selected_session_mode(),
+    }
+}
+
+// save which browser the agent drives in browser mode
+#[tauri::command]
+pub fn save_browser_settings(browser: String) -> Result<(), String> {
+    let kind = crate::browser::BrowserKind::parse(&browser)
+        .ok_or_else(|| format!("Unknown browser: {}", browser))?;
+    save_env_var(crate::browser::BROWSER_KIND_ENV, kind.as_str())
+}
+
+// run the automation browser without a window - applies the next time it's launched
+#[tauri::command]
+pub fn save_browser_headless(headless: bool) -> Result<(), String> {
+    save_env_var(crate::browser::BROWSER_HEADLESS_ENV, if headless { "true" } else { "false" })
+}
+
+// keep the automation profile between runs, or start each run on a fresh one that's deleted
+// afterwards - applies the next time the browser is launched
+#[tauri::command]
+pub fn save_browser_session_mode(mode: String) -> Result<(), String> {
+    let mode = crate::browser::BrowserSessionMode::parse(&mode)
+        .ok_or_else(|| format!("Unknown browser session mode: {}", mode))?;
+    save_env_var(crate::browser::BROWSER_SESSION_MODE_ENV, mode.as_str())
+}
+
+// get which tool calls need confirmation before they run
+#[tauri::command]
+pub fn get_confirm_settings() -> crate::confirm::ConfirmSettings {
+    crate::confirm::ConfirmSettings::current()
+}
+
+// save which tool calls need confirmation before they run, and the words that make a click risky
+#[tauri::command]
+pub fn save_confirm_settings(bash: bool, computer: bool, risky_clicks: bool, risk_keywords: Vec<String>) -> Result<(), String> {
+    let keywords = crate::confirm::parse_keywords(&risk_keywords.join(","));
+    if risky_clicks && keywords.is_empty() {
+        return Err("Add at least one risk keyword, or turn off risky-click confirmation".to_string());
+    }
+    save_env_var(crate::confirm::CONFIRM_BASH_ENV, if bash { "true" } else { "false" })?;
+    save_env_var(crate::confirm::CONFIRM_COMPUTER_ENV, if computer { "true" } else { "false" })?;
+    save_env_var(crate::confirm::CONFIRM_RISKY_CLICKS_ENV, if risky_clicks { "true" } else { "false" })?;
+    save_env_var(crate::confirm::RISK_KEYWORDS_ENV, &keywords.join(","))
+}
+
+// get where memory embeddings come from
+#[tauri::command]
+pub fn get_embedding_settings() -> crate::cognitive::embeddings::EmbeddingSettings {
+    crate::cognitive::embeddings::EmbeddingSettings::current()
+}
+
+// save the memory embedding backend; a blank model or url uses the backend's default.
+// memories embedded elsewhere are moved over by reembed_memories
+#[tauri::command]
+pub fn save_embedding_settings(backend: String, model: String, url: String) -> Result<(), String> {
+    let backend = crate::cognitive::embeddings::EmbeddingBackend::parse(&backend)
+        .ok_or_else(|| format!("Unknown embedding backend {}", backend))?;
+    if !url.trim().is_empty() && !url.trim().starts_with("http") {
+        return Err(format!("Embedding URL must start with http: {}", url));
+    }
+    save_env_var(crate::cognitive::embeddings::EMBEDDING_BACKEND_ENV, backend.as_str())?;
+    save_env_var(crate::cognitive::embeddings::EMBEDDING_MODEL_ENV, model.trim())?;
+    save_env_var(crate::cognitive::embeddings::EMBEDDING_URL_ENV, url.trim())
+}
+
+// get models to fall back to when the selected one keeps failing
+#[tauri::command]
+pub fn get_fallback_models() -> Vec<String> {
+    crate::api::fallback_models()
+}
+
+// save fallback models, in the order they should be tried
+#[tauri::command]
+pub fn save_fallback_models(models: Vec<String>) -> Result<(), String> {
+    let models: Vec<&str> = models.iter().map(|m| m.trim()).filter(|m| !m.is_empty()).collect();
+    if models.iter().any(|m| m.contains(',')) {
+        return Err("Model names can't contain commas".to_string());
+    }
+    save_env_var(crate::api::FALLBACK_MODELS_ENV, &models.join(","))
+}
+
+// get how long an idle main panel stays expanded before collapsing (0 = never)
+#[tauri::command]
+pub fn get_idle_timeout() -> u32 {
+    crate::panels::idle_timeout_secs()
+}
+
+// save the main panel idle timeout in seconds; 0 disables auto-collapse
+#[tauri::command]
+pub fn save_idle_timeout(seconds: u32) -> Result<(), String> {
+    if seconds > crate::panels::MAX_IDLE_TIMEOUT_SECS {
+        return Err(format!("Idle timeout is limited to {} seconds", crate::panels::MAX_IDLE_TIMEOUT_SECS));
+    }
+    save_env_var(crate::panels::IDLE_TIMEOUT_ENV, &seconds.to_string())
+}
+
+// get the format and area of the help / push-to-talk context screenshot
+#[tauri::command]
+pub fn get_help_screenshot_settings() -> crate::computer::HelpScreenshotSettings {
+    crate::computer::HelpScreenshotSettings::current()
+}
+
+// save the context screenshot format (jpeg, png) and area (full, window, cursor)
+#[tauri::command]
+pub fn save_help_screenshot_settings(format: String, area: String) -> Result<(), String> {
+    let format = crate::computer::ScreenshotFormat::parse(&format)
+        .ok_or_else(|| format!("Unknown screenshot format: {}", format))?;
+    let area = crate::computer::HelpCaptureArea::parse(&area)
+        .ok_or_else(|| format!("Unknown screenshot area: {}", area))?;
+    save_env_var(crate::computer::HELP_SCREENSHOT_FORMAT_ENV, format.as_str())?;
+    save_env_var(crate::computer::HELP_SCREENSHOT_AREA_ENV, area.as_str())
+}
+
+// whether computer actions glide the cursor instead of jumping it
+#[tauri::command]
+pub fn get_humanize_mouse() -> bool {
+    crate::computer::humanize_enabled()
+}
+
+#[tauri::command]
+pub fn save_humanize_mouse(enabled: bool) -> Result<(), String> {
+    save_env_var(crate::computer::HUMANIZE_MOUSE_ENV, if enabled { "true" } else { "false" })
+}
+
+// whether the idle screen is watched for stuck error dialogs
+#[tauri::command]
+pub fn get_ambient_help() -> bool {
+    crate::ambient::enabled()
+}
+
+#[tauri::command]
+pub fn save_ambient_help(enabled: bool) -> Result<(), String> {
+    save_env_var(crate::ambient::AMBIENT_HELP_ENV, if enabled { "true" } else { "false" })
+}
+
+// seconds between ambient looks, so settings can say how often it happens
+#[tauri::command]
+pub fn get_ambient_interval_secs() -> u64 {
+    crate::ambient::interval().as_secs()
+}
+
+// longest an agent run may take in minutes, 0 for no limit
+#[tauri::command]
+pub fn get_max_run_minutes() -> u64 {
+    crate::storage::max_run_minutes()
+}
+
+#[tauri::command]
+pub fn save_max_run_minutes(minutes: u64) -> Result<(), String> {
+    save_env_var(crate::storage::MAX_RUN_MINUTES_ENV, &minutes.to_string())
+}
+
+// whether python calls skip installing missing packages
+#[tauri::command]
+pub fn get_python_skip_autoinstall() -> bool {
+    crate::python_tool::skip_autoinstall()
+}
+
+#[tauri::command]
+pub fn save_python_skip_autoinstall(enabled: bool) -> Result<(), String> {
+    save_env_var(crate::python_tool::SKIP_AUTOINSTALL_ENV, if enabled { "true" } else { "false" })?;
+    // the next python call checks the packages again under the new setting
+    crate::python_tool::reset_python_env();
+    Ok(())
+}
+
+// the folder bash starts in and python saves relative paths to
+#[tauri::command]
+pub fn get_workspace() -> String {
+    crate::files::workspace_dir().to_string_lossy().to_string()
+}
+
+// point the workspace somewhere else, creating the folder if it's missing
+#[tauri::command]
+pub fn set_workspace(path: String) -> Result<String, String> {
+    let dir = crate::files::parse_workspace(&path)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    let dir = dir.to_string_lossy().to_string();
+    save_env_var(crate::files::WORKSPACE_DIR_ENV, &dir)?;
+    Ok(dir)
+}
+
+// the tool toggles the user left on
+#[tauri::command]
+pub fn get_enabled_tools() -> Vec<String> {
+    crate::tool_policy::ToolPolicy::current().enabled()
+}
+
+// save which tools the agent may use; toggles left out of the list are switched off
+#[tauri::command]
+pub fn set_enabled_tools(tools: Vec<String>) -> Result<(), String> {
+    let policy = crate::tool_policy::ToolPolicy::with_enabled(&tools)?;
+    save_env_var(crate::tool_policy::DISABLED_TOOLS_ENV, &policy.to_env_value())
+}
+
+// get how verbose the debug log file is
+#[tauri::command]
+pub fn get_log_level() -> String {
+    crate::logging::current_level()
+}
+
+// save the debug log level and apply it without a restart
+#[tauri::command]
+pub fn save_log_level(level: String) -> Result<(), String> {
+    let level = level.trim().to_lowercase();
+    crate::logging::set_level(&level)?;
+    save_env_var(crate::logging::LOG_LEVEL_ENV, &level)
+}
+
+// standing instructions can span lines, so they live in their own file rather than .env
+const SYSTEM_PREAMBLE_FILE: &str = "system_preamble.txt";
+
+/// longest custom preamble accepted, in characters
+pub const MAX_SYSTEM_PREAMBLE_CHARS: usize = 2000;
+
+/// the user's standing instructions for the agent ("" when unset)
+pub fn user_system_preamble() -> String {
+    std::fs::read_to_string(app_data_dir().join(SYSTEM_PREAMBLE_FILE))
+        .map(|text| text.trim().to_string())
+        .unwrap_or_default()
+}
+
+// get the user's standing instructions that are added to the system prompt
+#[tauri::command]
+pub fn get_system_preamble() -> String {
+    user_system_preamble()
+}
+
+// save the user's standing instructions; an empty string clears them
+#[tauri::command]
+pub fn save_system_preamble(preamble: String) -> Result<(), String> {
+    let preamble = preamble.trim();
+    if preamble.chars().count() > MAX_SYSTEM_PREAMBLE_CHARS {
+        return Err(format!("Custom instructions are limited to {} characters", MAX_SYSTEM_PREAMBLE_CHARS));
+    }
+    let path = app_data_dir().join(SYSTEM_PREAMBLE_FILE);
+    if preamble.is_empty() {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+    std::fs::create_dir_all(app_data_dir()).map_err(|e| e.to_string())?;
+    std::fs::write(&path, preamble).map_err(|e| e.to_string())
+}
+
+// load settings persisted by save_env_var (does not override real env vars)
+pub fn load_saved_settings() {
+    let env_path = app_data_dir().join(".env");
+    if env_path.exists() {
+        if let Err(e) = dotenvy::from_path(&env_path) {
+            println!("[permissions] failed to load saved settings: {}", e);
+        }
+    }
+}
+
 // helper to save env var to .env file (stored in app data dir for portability)
 fn save_env_var(var_name: &str, value: &str) -> Result<(), String> {
     // On Windows, current_dir may be read-only (e.g. C:\Program Files\...).
@@ -603,8 +1349,319 @@ fn save_env_var(var_name: &str, value: &str) -> Result<(), String> {
 #[tauri::command]
 pub fn save_api_key(service: String, key: String) -> Result<(), String> {
     let var_name = api_env_var_for_service(&service).ok_or_else(|| "Unknown service".to_string())?;
-    let entry = keyring::Entry::new(KEYRING_SERVICE, var_name).map_err(|e| e.to_string())?;
+    let entry = keyring::Entry::new(&keyring_service_for(var_name), var_name).map_err(|e| e.to_string())?;
     entry.set_password(&key).map_err(|e| e.to_string())?;
     std::env::set_var(var_name, key);
     Ok(())
 }
+
+// whether sounds, the capture flash and (optionally) spoken replies are muted
+#[tauri::command]
+pub fn get_do_not_disturb() -> crate::quiet::DndStatus {
+    crate::quiet::status()
+}
+
+// the manual switch, also flipped from the tray
+#[tauri::command]
+pub fn set_do_not_disturb(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    save_env_var(crate::quiet::DO_NOT_DISTURB_ENV, if enabled { "true" } else { "false" })?;
+    crate::quiet::notify(&app);
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub fn save_do_not_disturb_options(app: tauri::AppHandle, silence_speech: bool, while_sharing: bool) -> Result<(), String> {
+    save_env_var(crate::quiet::SILENCE_SPEECH_ENV, if silence_speech { "true" } else { "false" })?;
+    save_env_var(crate::quiet::WHILE_SHARING_ENV, if while_sharing { "true" } else { "false" })?;
+    crate::quiet::notify(&app);
+    Ok(())
+}
+
+// --- settings profile export / import ---
+
+/// bumped when the profile layout changes incompatibly
+const SETTINGS_PROFILE_VERSION: u32 = 1;
+
+/// .env-backed preferences that make up a settings profile (never api keys)
+const PROFILE_PREFERENCES: &[&str] = &[
+    "ELEVENLABS_VOICE_ID",
+    crate::browser::BROWSER_KIND_ENV,
+    crate::browser::BROWSER_HEADLESS_ENV,
+    crate::browser::BROWSER_SESSION_MODE_ENV,
+    crate::confirm::CONFIRM_BASH_ENV,
+    crate::confirm::CONFIRM_COMPUTER_ENV,
+    crate::confirm::CONFIRM_RISKY_CLICKS_ENV,
+    crate::confirm::RISK_KEYWORDS_ENV,
+    crate::api::FALLBACK_MODELS_ENV,
+    crate::cognitive::agent_swarm::SWARM_MODELS_ENV,
+    crate::cognitive::embeddings::EMBEDDING_BACKEND_ENV,
+    crate::cognitive::embeddings::EMBEDDING_MODEL_ENV,
+    crate::cognitive::embeddings::EMBEDDING_URL_ENV,
+    crate::context::CONTEXT_BUDGET_ENV,
+    crate::context::SUMMARIZE_THRESHOLD_ENV,
+    crate::context::KEEP_FULL_RESULTS_ENV,
+    crate::logging::LOG_LEVEL_ENV,
+    crate::panels::IDLE_TIMEOUT_ENV,
+    crate::computer::HELP_SCREENSHOT_FORMAT_ENV,
+    crate::computer::HELP_SCREENSHOT_AREA_ENV,
+    crate::computer::HUMANIZE_MOUSE_ENV,
+    crate::computer::PASTE_RESTORE_CLIPBOARD_ENV,
+    crate::ambient::AMBIENT_HELP_ENV,
+    crate::ambient::AMBIENT_INTERVAL_ENV,
+    crate::quiet::DO_NOT_DISTURB_ENV,
+    crate::quiet::SILENCE_SPEECH_ENV,
+    crate::quiet::WHILE_SHARING_ENV,
+    crate::tool_policy::DISABLED_TOOLS_ENV,
+    crate::files::WORKSPACE_DIR_ENV,
+    crate::voice::AUTO_SPEAK_FINAL_ENV,
+    crate::voice::INPUT_DEVICE_ENV,
+    crate::voice::VOICE_LANGUAGE_ENV,
+    crate::python_tool::SPREADSHEET_MAX_ROWS_ENV,
+    crate::python_tool::SPREADSHEET_MAX_CELLS_ENV,
+    crate::python_tool::ANALYZE_SAMPLE_ROWS_ENV,
+    crate::python_tool::SKIP_AUTOINSTALL_ENV,
+    crate::storage::SAVE_EVERY_ROUNDS_ENV,
+    crate::storage::SAVE_INTERVAL_SECS_ENV,
+    crate::storage::MAX_RUN_MINUTES_ENV,
+];
+
+const PROFILE_SECRET_SERVICES: &[&str] = &["anthropic", "deepgram", "elevenlabs"];
+
+/// every user setting in one JSON document, for moving Hey-work to another machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsProfile {
+    pub version: u32,
+    /// env var name -> value for the preferences that are set
+    #[serde(default)]
+    pub preferences: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub system_preamble: Option<String>,
+    #[serde(default)]
+    pub command_policy: Option<crate::bash::CommandPolicy>,
+    /// model id or family -> user-set rate limits
+    #[serde(default)]
+    pub rate_limits: Option<std::collections::BTreeMap<String, crate::rate_limiter::ModelLimits>>,
+    /// service -> api key; only written when the user opts in to exporting secrets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<std::collections::BTreeMap<String, String>>,
+}
+
+/// what an import changed
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportReport {
+    pub preferences: usize,
+    pub system_preamble: bool,
+    pub command_policy: bool,
+    pub rate_limits: usize,
+    /// services whose api keys were imported
+    pub secrets: Vec<String>,
+}
+
+fn validate_preference(name: &str, value: &str) -> Result<(), String> {
+    let is_bool = |v: &str| matches!(v.trim(), "true" | "false" | "1" | "0");
+    let valid = match name {
+        _ if value.contains('\n') => false,
+        crate::browser::BROWSER_KIND_ENV => crate::browser::BrowserKind::parse(value).is_some(),
+        crate::browser::BROWSER_SESSION_MODE_ENV => crate::browser::BrowserSessionMode::parse(value).is_some(),
+        crate::browser::BROWSER_HEADLESS_ENV
+        | crate::confirm::CONFIRM_BASH_ENV
+        | crate::confirm::CONFIRM_COMPUTER_ENV
+        | crate::confirm::CONFIRM_RISKY_CLICKS_ENV
+        | crate::computer::HUMANIZE_MOUSE_ENV
+        | crate::computer::PASTE_RESTORE_CLIPBOARD_ENV
+        | crate::ambient::AMBIENT_HELP_ENV
+        | crate::quiet::DO_NOT_DISTURB_ENV
+        | crate::quiet::SILENCE_SPEECH_ENV
+        | crate::quiet::WHILE_SHARING_ENV
+        | crate::voice::AUTO_SPEAK_FINAL_ENV
+        | crate::python_tool::SKIP_AUTOINSTALL_ENV => is_bool(value),
+        crate::context::CONTEXT_BUDGET_ENV => value.trim().parse::<u32>().is_ok(),
+        crate::context::SUMMARIZE_THRESHOLD_ENV | crate::context::KEEP_FULL_RESULTS_ENV => value.trim().parse::<usize>().is_ok(),
+        crate::python_tool::SPREADSHEET_MAX_ROWS_ENV
+        | crate::python_tool::SPREADSHEET_MAX_CELLS_ENV
+        | crate::python_tool::ANALYZE_SAMPLE_ROWS_ENV
+        | crate::storage::SAVE_EVERY_ROUNDS_ENV => value.trim().parse::<usize>().is_ok_and(|n| n > 0),
+        crate::storage::SAVE_INTERVAL_SECS_ENV
+        | crate::storage::MAX_RUN_MINUTES_ENV
+        | crate::ambient::AMBIENT_INTERVAL_ENV => value.trim().parse::<u64>().is_ok(),
+        crate::logging::LOG_LEVEL_ENV => crate::logging::LOG_LEVELS.contains(&value.trim().to_lowercase().as_str()),
+        crate::panels::IDLE_TIMEOUT_ENV => value
+            .trim()
+            .parse::<u32>()
+            .is_ok_and(|secs| secs <= crate::panels::MAX_IDLE_TIMEOUT_SECS),
+        crate::computer::HELP_SCREENSHOT_FORMAT_ENV => crate::computer::ScreenshotFormat::parse(value).is_some(),
+        crate::computer::HELP_SCREENSHOT_AREA_ENV => crate::computer::HelpCaptureArea::parse(value).is_some(),
+        crate::voice::VOICE_LANGUAGE_ENV => crate::voice::validate_language(value).is_ok(),
+        crate::tool_policy::DISABLED_TOOLS_ENV => crate::tool_policy::ToolPolicy::parse(value).is_ok(),
+        crate::cognitive::embeddings::EMBEDDING_BACKEND_ENV => {
+            crate::cognitive::embeddings::EmbeddingBackend::parse(value).is_some()
+        }
+        crate::files::WORKSPACE_DIR_ENV => crate::files::parse_workspace(value).is_ok(),
+        _ if PROFILE_PREFERENCES.contains(&name) => true,
+        _ => return Err(format!("Unknown setting {}", name)),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid value for {}: {:?}", name, value))
+    }
+}
+
+/// check every section before anything is applied, so a bad profile changes nothing
+fn validate_profile(profile: &SettingsProfile) -> Result<(), String> {
+    if profile.version > SETTINGS_PROFILE_VERSION {
+        return Err(format!(
+            "This profile was exported by a newer Hey-work (version {}), please update first",
+            profile.version
+        ));
+    }
+    for (name, value) in &profile.preferences {
+        validate_preference(name, value).map_err(|e| format!("preferences: {}", e))?;
+    }
+    if let Some(preamble) = &profile.system_preamble {
+        if preamble.trim().chars().count() > MAX_SYSTEM_PREAMBLE_CHARS {
+            return Err(format!("systemPreamble: limited to {} characters", MAX_SYSTEM_PREAMBLE_CHARS));
+        }
+    }
+    if let Some(policy) = &profile.command_policy {
+        if policy.mode == crate::bash::PolicyMode::Allowlist && policy.patterns.iter().all(|p| p.trim().is_empty()) {
+            return Err("commandPolicy: an allowlist needs at least one pattern".to_string());
+        }
+    }
+    for (model, limits) in profile.rate_limits.iter().flatten() {
+        if model.trim().is_empty() {
+            return Err("rateLimits: model is required".to_string());
+        }
+        if limits.requests_per_minute == 0 || limits.tokens_per_minute == 0 {
+            return Err(format!("rateLimits: limits for {} must be greater than zero", model));
+        }
+    }
+    for (service, key) in profile.secrets.iter().flatten() {
+        if !PROFILE_SECRET_SERVICES.contains(&service.as_str()) {
+            return Err(format!("secrets: unknown service {}", service));
+        }
+        if key.trim().is_empty() {
+            return Err(format!("secrets: empty key for {}", service));
+        }
+    }
+    Ok(())
+}
+
+// gather every setting into one JSON profile; api keys only when `include_secrets` is set
+#[tauri::command(rename_all = "camelCase")]
+pub fn export_settings(include_secrets: bool) -> Result<String, String> {
+    let preferences = PROFILE_PREFERENCES
+        .iter()
+        .filter_map(|name| std::env::var(name).ok().map(|value| (name.to_string(), value)))
+        .collect();
+    let preamble = user_system_preamble();
+    let secrets = include_secrets.then(|| {
+        PROFILE_SECRET_SERVICES
+            .iter()
+            .filter_map(|service| {
+                let key = read_api_key_secure(api_env_var_for_service(service)?)?;
+                Some((service.to_string(), key))
+            })
+            .collect()
+    });
+
+    let rate_limits = crate::rate_limiter::rate_limit_overrides();
+
+    let profile = SettingsProfile {
+        version: SETTINGS_PROFILE_VERSION,
+        preferences,
+        system_preamble: (!preamble.is_empty()).then_some(preamble),
+        command_policy: Some(crate::bash::get_command_policy()),
+        rate_limits: (!rate_limits.is_empty()).then(|| rate_limits.into_iter().collect()),
+        secrets,
+    };
+    serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())
+}
+
+/// apply a profile made by `export_settings`; sections missing from it are left alone
+pub fn import_settings_profile(json: &str) -> Result<SettingsImportReport, String> {
+    let profile: SettingsProfile = serde_json::from_str(json).map_err(|e| format!("Not a settings profile: {}", e))?;
+    validate_profile(&profile)?;
+
+    let mut report = SettingsImportReport::default();
+    for (name, value) in &profile.preferences {
+        save_env_var(name, value.trim())?;
+        report.preferences += 1;
+    }
+    if let Some(level) = profile.preferences.get(crate::logging::LOG_LEVEL_ENV) {
+        crate::logging::set_level(level)?;
+    }
+    if let Some(preamble) = profile.system_preamble {
+        save_system_preamble(preamble)?;
+        report.system_preamble = true;
+    }
+    if let Some(policy) = profile.command_policy {
+        crate::bash::set_command_policy(policy)?;
+        report.command_policy = true;
+    }
+    for (model, limits) in profile.rate_limits.into_iter().flatten() {
+        crate::rate_limiter::set_rate_limits(model, limits.requests_per_minute, limits.tokens_per_minute)?;
+        report.rate_limits += 1;
+    }
+    for (service, key) in profile.secrets.into_iter().flatten() {
+        save_api_key(service.clone(), key.trim().to_string())?;
+        report.secrets.push(service);
+    }
+
+    println!(
+        "[permissions] imported settings: {} preferences, preamble={}, policy={}, rate limits={}, secrets={:?}",
+        report.preferences, report.system_preamble, report.command_policy, report.rate_limits, report.secrets
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preferences_are_validated_per_setting() {
+        assert!(validate_preference(crate::browser::BROWSER_KIND_ENV, "firefox").is_ok());
+        assert!(validate_preference(crate::browser::BROWSER_KIND_ENV, "netscape").is_err());
+        assert!(validate_preference(crate::browser::BROWSER_SESSION_MODE_ENV, "ephemeral").is_ok());
+        assert!(validate_preference(crate::browser::BROWSER_SESSION_MODE_ENV, "incognito").is_err());
+        assert!(validate_preference(crate::confirm::CONFIRM_BASH_ENV, "maybe").is_err());
+        assert!(validate_preference(crate::panels::IDLE_TIMEOUT_ENV, "99999").is_err());
+        assert!(validate_preference("ELEVENLABS_VOICE_ID", "abc\nEVIL=1").is_err());
+        // api keys never travel as plain preferences
+        assert!(validate_preference("ANTHROPIC_API_KEY", "sk-ant").is_err());
+    }
+
+    #[test]
+    fn profile_rejects_unknown_secret_services() {
+        let profile: SettingsProfile = serde_json::from_str(
+            r#"{ "version": 1, "secrets": { "openai": "sk-123" } }"#,
+        )
+        .unwrap();
+        assert!(validate_profile(&profile).is_err());
+
+        let profile: SettingsProfile = serde_json::from_str(
+            r#"{ "version": 1, "preferences": { "HEYWORK_LOG_LEVEL": "debug" } }"#,
+        )
+        .unwrap();
+        assert!(validate_profile(&profile).is_ok());
+        assert!(profile.secrets.is_none());
+    }
+
+    #[test]
+    fn profile_rejects_zero_rate_limits() {
+        let profile: SettingsProfile = serde_json::from_str(
+            r#"{ "version": 1, "rateLimits": { "opus": { "requests_per_minute": 0, "tokens_per_minute": 1000 } } }"#,
+        )
+        .unwrap();
+        assert!(validate_profile(&profile).is_err());
+
+        let profile: SettingsProfile = serde_json::from_str(
+            r#"{ "version": 1, "rateLimits": { "opus": { "requests_per_minute": 100, "tokens_per_minute": 90000 } } }"#,
+        )
+        .unwrap();
+        assert!(validate_profile(&profile).is_ok());
+    }
+}
@@ -8,8 +8,10 @@
 //! - Progress streaming for long operations
 //! - PPTX generation with professional themes
 
+use crate::files::CreatedFile;
 use serde::{Deserialize, Serialize};
 use std::io::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
@@ -37,13 +39,175 @@ pub struct PythonExecutionResult {
     pub formatted_output: String,
     pub errors: Vec<String>,
     pub execution_time_ms: u64,
-    pub files_created: Vec<String>,
+    pub files_created: Vec<CreatedFile>,
     pub suggestions: Vec<String>,
+    /// base64 PNGs of charts rendered inline (no save path), shown in the chat
+    #[serde(default)]
+    pub images: Vec<String>,
 }
 
-/// Ensure required Python packages are installed
-pub async fn ensure_python_packages() -> Result<(), String> {
-    // Check which packages are missing
+/// Raw result of running a wrapped script
+struct ScriptOutput {
+    output: String,
+    files: Vec<String>,
+    images: Vec<String>,
+    /// helpers' notes about limited output (truncated sheets, sampled analysis)
+    notices: Vec<String>,
+}
+
+/// most inline charts one python call hands back - the rest are dropped
+const MAX_INLINE_IMAGES: usize = 4;
+
+/// style names create_professional_report accepts, and themes create_presentation accepts.
+/// the python helpers get these same lists and fall back to "modern" with a note otherwise
+pub const REPORT_STYLES: &[&str] = &["modern", "classic", "minimal", "executive", "dark"];
+pub const PRESENTATION_THEMES: &[&str] = &["modern", "dark", "minimal", "corporate", "creative"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentStyles {
+    pub report_styles: Vec<&'static str>,
+    pub presentation_themes: Vec<&'static str>,
+}
+
+// valid style and theme names for the document helpers
+#[tauri::command]
+pub fn list_document_styles() -> DocumentStyles {
+    DocumentStyles {
+        report_styles: REPORT_STYLES.to_vec(),
+        presentation_themes: PRESENTATION_THEMES.to_vec(),
+    }
+}
+
+/// env vars (saved in .env) capping what create_spreadsheet writes and quick_analyze reads.
+/// the python helpers read them straight from the environment they inherit
+pub const SPREADSHEET_MAX_ROWS_ENV: &str = "HEYWORK_SPREADSHEET_MAX_ROWS";
+pub const SPREADSHEET_MAX_CELLS_ENV: &str = "HEYWORK_SPREADSHEET_MAX_CELLS";
+pub const ANALYZE_SAMPLE_ROWS_ENV: &str = "HEYWORK_ANALYZE_SAMPLE_ROWS";
+
+/// env var (saved in .env): never run pip, python calls go ahead with whatever is installed
+pub const SKIP_AUTOINSTALL_ENV: &str = "HEYWORK_PYTHON_SKIP_AUTOINSTALL";
+
+pub fn skip_autoinstall() -> bool {
+    std::env::var(SKIP_AUTOINSTALL_ENV)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+// whether the package check still needs to run this session. once everything is present,
+// or pip has failed (usually offline), later python calls skip the check and go straight to execution.
+// skipped means the user turned installs off or cancelled one - nothing more is installed until a reset
+const PACKAGES_UNCHECKED: u8 = 0;
+const PACKAGES_READY: u8 = 1;
+const PACKAGES_INSTALL_FAILED: u8 = 2;
+const PACKAGES_SKIPPED: u8 = 3;
+
+static PACKAGE_STATE: AtomicU8 = AtomicU8::new(PACKAGES_UNCHECKED);
+// concurrent python calls (swarm subtasks) would otherwise run pip side by side
+static INSTALL_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+// the running pip's stop switch, taken by cancel_python_install
+static PIP_CANCEL: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<()>>> = std::sync::Mutex::new(None);
+// what the results say while packages are skipped, naming the ones that are missing
+static SKIPPED_NOTE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// forget the cached package check, so the next python call checks again and retries pip
+#[tauri::command]
+pub fn reset_python_env() {
+    PACKAGE_STATE.store(PACKAGES_UNCHECKED, Ordering::SeqCst);
+    *SKIPPED_NOTE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+// stop a pip install that's running; the python call goes ahead stdlib-only. false when
+// nothing was installing
+#[tauri::command]
+pub fn cancel_python_install() -> bool {
+    let cancel = PIP_CANCEL.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match cancel {
+        Some(cancel) => {
+            println!("[python_tool] Package install cancelled");
+            cancel.send(()).is_ok()
+        }
+        None => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PipOutcome {
+    Installed,
+    Failed,
+    Cancelled,
+}
+
+// callers hold INSTALL_LOCK, so there's only ever one pip for cancel_python_install to stop
+async fn pip_install(packages: &[String]) -> PipOutcome {
+    let child = Command::new("python3")
+        .arg("-m")
+        .arg("pip")
+        .arg("install")
+        .arg("--quiet")
+        .arg("--disable-pip-version-check")
+        .args(packages)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        // a cancelled install is killed when the child is dropped
+        .kill_on_drop(true)
+        .spawn();
+    let Ok(mut child) = child else {
+        return PipOutcome::Failed;
+    };
+
+    let (cancel, cancelled) = tokio::sync::oneshot::channel();
+    *PIP_CANCEL.lock().unwrap_or_else(|e| e.into_inner()) = Some(cancel);
+    let outcome = tokio::select! {
+        status = child.wait() => {
+            if status.is_ok_and(|status| status.success()) { PipOutcome::Installed } else { PipOutcome::Failed }
+        }
+        _ = cancelled => PipOutcome::Cancelled,
+    };
+    PIP_CANCEL.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if outcome == PipOutcome::Cancelled {
+        PACKAGE_STATE.store(PACKAGES_SKIPPED, Ordering::SeqCst);
+    }
+    outcome
+}
+
+fn skipped_note(missing: &[String], cancelled: bool) -> String {
+    format!(
+        "⚠️ Package install {} - this ran with the standard library only. Missing: {}. Document helpers that need them fall back to plain text, so tell the user the output is simplified.",
+        if cancelled { "was cancelled" } else { "is turned off in Settings" },
+        missing.join(", ")
+    )
+}
+
+fn record_skipped(missing: &[String], cancelled: bool) {
+    PACKAGE_STATE.store(PACKAGES_SKIPPED, Ordering::SeqCst);
+    *SKIPPED_NOTE.lock().unwrap_or_else(|e| e.into_inner()) = Some(skipped_note(missing, cancelled));
+}
+
+// whether an import error may still be fixed by installing the missing module
+fn may_install() -> bool {
+    !skip_autoinstall() && PACKAGE_STATE.load(Ordering::SeqCst) != PACKAGES_SKIPPED
+}
+
+// one missing module an import error named, installed before the retry. false if the
+// install was skipped, so there's nothing to retry
+async fn install_for_retry(pip_name: &str) -> bool {
+    if !may_install() {
+        return false;
+    }
+    let _guard = INSTALL_LOCK.lock().await;
+    match pip_install(&[pip_name.to_string()]).await {
+        PipOutcome::Cancelled => {
+            record_skipped(&[pip_name.to_string()], true);
+            false
+        }
+        _ => true,
+    }
+}
+
+/// pip names of the helper packages python3 can't import, or Err when python3 itself won't run
+pub async fn missing_packages() -> Result<Vec<String>, String> {
     let check_script = r#"
 import importlib
 import json
@@ -68,57 +232,80 @@ for module, pip_name in packages.items():
         missing.append(pip_name)
 print(json.dumps(missing))
 "#;
-    
+
     let output = Command::new("python3")
         .arg("-c")
         .arg(check_script)
         .output()
         .await
         .map_err(|e| format!("Failed to check Python packages: {}", e))?;
-    
+
     let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    
-    if let Ok(missing) = serde_json::from_str::<Vec<String>>(&stdout) {
-        if !missing.is_empty() {
-            println!("[python_tool] Installing missing packages: {:?}", missing);
-            let install_result = Command::new("python3")
-                .arg("-m")
-                .arg("pip")
-                .arg("install")
-                .arg("--quiet")
-                .arg("--disable-pip-version-check")
-                .args(&missing)
-                .output()
-                .await;
-            
-            match install_result {
-                Ok(out) => {
-                    if out.status.success() {
-                        println!("[python_tool] Successfully installed: {:?}", missing);
-                    } else {
-                        let stderr = String::from_utf8_lossy(&out.stderr);
-                        println!("[python_tool] pip install partial failure: {}", stderr);
-                        // Try installing one by one
-                        for pkg in &missing {
-                            let _ = Command::new("python3")
-                                .arg("-m")
-                                .arg("pip")
-                                .arg("install")
-                                .arg("--quiet")
-                                .arg("--disable-pip-version-check")
-                                .arg(pkg)
-                                .output()
-                                .await;
-                        }
+    serde_json::from_str(&stdout).map_err(|_| {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        format!("Failed to check Python packages: {}", if stderr.is_empty() { stdout } else { stderr })
+    })
+}
+
+/// Ensure required Python packages are installed
+pub async fn ensure_python_packages() -> Result<(), String> {
+    let _guard = INSTALL_LOCK.lock().await;
+    match PACKAGE_STATE.load(Ordering::SeqCst) {
+        PACKAGES_READY => return Ok(()),
+        PACKAGES_INSTALL_FAILED => {
+            println!("[python_tool] Skipping package install, pip already failed this session");
+            return Ok(());
+        }
+        PACKAGES_SKIPPED => return Ok(()),
+        _ => {}
+    }
+
+    // Check which packages are missing
+    if let Ok(missing) = missing_packages().await {
+        if missing.is_empty() {
+            PACKAGE_STATE.store(PACKAGES_READY, Ordering::SeqCst);
+            return Ok(());
+        }
+        if skip_autoinstall() {
+            println!("[python_tool] Auto-install is off, running without: {:?}", missing);
+            record_skipped(&missing, false);
+            return Ok(());
+        }
+
+        println!("[python_tool] Installing missing packages: {:?}", missing);
+        let mut outcome = pip_install(&missing).await;
+        if outcome == PipOutcome::Failed {
+            println!("[python_tool] pip install failed, retrying packages one by one");
+            outcome = PipOutcome::Installed;
+            for pkg in &missing {
+                match pip_install(std::slice::from_ref(pkg)).await {
+                    PipOutcome::Installed => {}
+                    PipOutcome::Failed => {
+                        println!("[python_tool] Couldn't install {}", pkg);
+                        outcome = PipOutcome::Failed;
+                    }
+                    PipOutcome::Cancelled => {
+                        outcome = PipOutcome::Cancelled;
+                        break;
                     }
-                }
-                Err(e) => {
-                    println!("[python_tool] pip install failed: {}", e);
                 }
             }
         }
+        if outcome == PipOutcome::Cancelled {
+            // what's still missing after a partial install
+            let still_missing = missing_packages().await.unwrap_or(missing);
+            record_skipped(&still_missing, true);
+            return Ok(());
+        }
+        let installed = outcome == PipOutcome::Installed;
+
+        // one attempt per session - execution still goes ahead, stdlib-only code works without them
+        PACKAGE_STATE.store(if installed { PACKAGES_READY } else { PACKAGES_INSTALL_FAILED }, Ordering::SeqCst);
+        if installed {
+            println!("[python_tool] Successfully installed: {:?}", missing);
+        }
     }
-    
+
     Ok(())
 }
 
@@ -133,6 +320,21 @@ pub async fn execute_python_enhanced(
     // Auto-install missing packages before execution
     let _ = ensure_python_packages().await;
     
+    let mut result = execute_wrapped(code, save_to, task_type, start_time).await?;
+    // say so when packages were left out, the model would otherwise take plain-text fallbacks at face value
+    let skipped = SKIPPED_NOTE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(note) = skipped {
+        result.suggestions.insert(0, note);
+    }
+    Ok(result)
+}
+
+async fn execute_wrapped(
+    code: &str,
+    save_to: Option<&str>,
+    task_type: Option<&str>,
+    start_time: std::time::Instant,
+) -> Result<PythonExecutionResult, String> {
     // Create temporary script
     let temp_dir = std::env::temp_dir();
     let script_path = temp_dir.join(format!("heywork_python_{}.py", uuid::Uuid::new_v4()));
@@ -160,55 +362,49 @@ pub async fn execute_python_enhanced(
     match execution {
         Ok(Ok(result)) => {
             // Check if there were import errors and retry with auto-install
-            if result.contains("ModuleNotFoundError") || result.contains("ImportError") {
+            if result.output.contains("ModuleNotFoundError") || result.output.contains("ImportError") {
                 println!("[python_tool] Import error detected, attempting auto-install and retry");
                 
                 // Extract module name from error
-                let module_name = extract_module_from_error(&result);
+                let module_name = extract_module_from_error(&result.output);
                 if let Some(module) = module_name {
                     let pip_name = module_to_pip_name(&module);
-                    let _ = Command::new("python3")
-                        .arg("-m")
-                        .arg("pip")
-                        .arg("install")
-                        .arg("--quiet")
-                        .arg("--disable-pip-version-check")
-                        .arg(&pip_name)
-                        .output()
-                        .await;
-                    
-                    // Retry execution
-                    let retry_script = temp_dir.join(format!("heywork_python_retry_{}.py", uuid::Uuid::new_v4()));
-                    if let Ok(mut f) = std::fs::File::create(&retry_script) {
-                        let _ = f.write_all(wrapped_code.as_bytes());
-                        if let Ok(Ok(retry_result)) = timeout(
-                            Duration::from_secs(120),
-                            execute_python_script(&retry_script)
-                        ).await {
+                    if install_for_retry(&pip_name).await {
+                        // Retry execution
+                        let retry_script = temp_dir.join(format!("heywork_python_retry_{}.py", uuid::Uuid::new_v4()));
+                        if let Ok(mut f) = std::fs::File::create(&retry_script) {
+                            let _ = f.write_all(wrapped_code.as_bytes());
+                            if let Ok(Ok(retry_result)) = timeout(
+                                Duration::from_secs(120),
+                                execute_python_script(&retry_script)
+                            ).await {
+                                let _ = std::fs::remove_file(&retry_script);
+                                return Ok(PythonExecutionResult {
+                                    success: true,
+                                    formatted_output: format_output(&retry_result.output, task_type),
+                                    errors: vec![],
+                                    execution_time_ms: start_time.elapsed().as_millis() as u64,
+                                    files_created: collect_created_files(&retry_result.files),
+                                    suggestions: generate_suggestions(&retry_result.output, &retry_result.notices, task_type),
+                                    images: retry_result.images,
+                                    output: retry_result.output,
+                                });
+                            }
                             let _ = std::fs::remove_file(&retry_script);
-                            return Ok(PythonExecutionResult {
-                                success: true,
-                                output: retry_result.clone(),
-                                formatted_output: format_output(&retry_result, task_type),
-                                errors: vec![],
-                                execution_time_ms: start_time.elapsed().as_millis() as u64,
-                                files_created: extract_files_created(&retry_result),
-                                suggestions: generate_suggestions(&retry_result, task_type),
-                            });
                         }
-                        let _ = std::fs::remove_file(&retry_script);
                     }
                 }
             }
             
             Ok(PythonExecutionResult {
                 success: true,
-                output: result.clone(),
-                formatted_output: format_output(&result, task_type),
+                formatted_output: format_output(&result.output, task_type),
                 errors: vec![],
                 execution_time_ms,
-                files_created: extract_files_created(&result),
-                suggestions: generate_suggestions(&result, task_type),
+                files_created: collect_created_files(&result.files),
+                suggestions: generate_suggestions(&result.output, &result.notices, task_type),
+                images: result.images,
+                output: result.output,
             })
         }
         Ok(Err(e)) => {
@@ -219,37 +415,30 @@ pub async fn execute_python_enhanced(
                     let pip_name = module_to_pip_name(m);
                     println!("[python_tool] Auto-installing {} and retrying...", pip_name);
                     
-                    let _ = Command::new("python3")
-                        .arg("-m")
-                        .arg("pip")
-                        .arg("install")
-                        .arg("--quiet")
-                        .arg("--disable-pip-version-check")
-                        .arg(&pip_name)
-                        .output()
-                        .await;
-                    
-                    // Retry
-                    let retry_script = temp_dir.join(format!("heywork_python_retry_{}.py", uuid::Uuid::new_v4()));
-                    if let Ok(mut f) = std::fs::File::create(&retry_script) {
-                        let wrapped = generate_enhanced_wrapper(code, save_to, task_type);
-                        let _ = f.write_all(wrapped.as_bytes());
-                        if let Ok(Ok(retry_result)) = timeout(
-                            Duration::from_secs(120),
-                            execute_python_script(&retry_script)
-                        ).await {
+                    if install_for_retry(&pip_name).await {
+                        // Retry
+                        let retry_script = temp_dir.join(format!("heywork_python_retry_{}.py", uuid::Uuid::new_v4()));
+                        if let Ok(mut f) = std::fs::File::create(&retry_script) {
+                            let wrapped = generate_enhanced_wrapper(code, save_to, task_type);
+                            let _ = f.write_all(wrapped.as_bytes());
+                            if let Ok(Ok(retry_result)) = timeout(
+                                Duration::from_secs(120),
+                                execute_python_script(&retry_script)
+                            ).await {
+                                let _ = std::fs::remove_file(&retry_script);
+                                return Ok(PythonExecutionResult {
+                                    success: true,
+                                    formatted_output: format_output(&retry_result.output, task_type),
+                                    errors: vec![],
+                                    execution_time_ms: start_time.elapsed().as_millis() as u64,
+                                    files_created: collect_created_files(&retry_result.files),
+                                    suggestions: generate_suggestions(&retry_result.output, &retry_result.notices, task_type),
+                                    images: retry_result.images,
+                                    output: retry_result.output,
+                                });
+                            }
                             let _ = std::fs::remove_file(&retry_script);
-                            return Ok(PythonExecutionResult {
-                                success: true,
-                                output: retry_result.clone(),
-                                formatted_output: format_output(&retry_result, task_type),
-                                errors: vec![],
-                                execution_time_ms: start_time.elapsed().as_millis() as u64,
-                                files_created: extract_files_created(&retry_result),
-                                suggestions: generate_suggestions(&retry_result, task_type),
-                            });
                         }
-                        let _ = std::fs::remove_file(&retry_script);
                     }
                 }
             }
@@ -263,6 +452,7 @@ pub async fn execute_python_enhanced(
                 execution_time_ms,
                 files_created: vec![],
                 suggestions,
+                images: vec![],
             })
         }
         Err(_) => {
@@ -274,6 +464,7 @@ pub async fn execute_python_enhanced(
                 execution_time_ms,
                 files_created: vec![],
                 suggestions: vec!["Optimize code for better performance".to_string()],
+                images: vec![],
             })
         }
     }
@@ -315,9 +506,14 @@ fn module_to_pip_name(module: &str) -> String {
     }
 }
 
-async fn execute_python_script(script_path: &std::path::Path) -> Result<String, String> {
-    let output = Command::new("python3")
-        .arg(script_path)
+async fn execute_python_script(script_path: &std::path::Path) -> Result<ScriptOutput, String> {
+    let mut command = Command::new("python3");
+    command.arg(script_path);
+    // relative save paths land in the workspace
+    if let Ok(dir) = crate::files::ensure_workspace() {
+        command.current_dir(dir);
+    }
+    let output = command
         .output()
         .await
         .map_err(|e| format!("Failed to execute Python: {}", e))?;
@@ -342,10 +538,32 @@ async fn execute_python_script(script_path: &std::path::Path) -> Result<String,
         if !err.is_empty() {
             return Err(format!("{}", err));
         }
-        return Ok(out.to_string());
+
+        // structured list maintained by OutputCapture - no need to scrape stdout
+        let files = result.get("files")
+            .and_then(|f| f.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+
+        let images = result.get("images")
+            .and_then(|i| i.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .take(MAX_INLINE_IMAGES)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let notices = result.get("notices")
+            .and_then(|n| n.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+
+        return Ok(ScriptOutput { output: out.to_string(), files, images, notices });
     }
     
-    Ok(stdout.to_string())
+    Ok(ScriptOutput { output: stdout.to_string(), files: vec![], images: vec![], notices: vec![] })
 }
 
 fn generate_enhanced_wrapper(code: &str, _save_to: Option<&str>, task_type: Option<&str>) -> String {
@@ -362,6 +580,7 @@ Auto-generated wrapper with professional document helpers
 import sys
 import os
 import json
+import base64
 import traceback
 from io import StringIO, BytesIO
 from datetime import datetime
@@ -373,12 +592,24 @@ class OutputCapture:
         self.stdout = StringIO()
         self.stderr = StringIO()
         self.files_created = []
+        # base64 PNGs of charts rendered without a save path, shown inline in the chat
+        self.images = []
+        # limits a helper hit (truncated rows, sampled data), surfaced as suggestions
+        self.notices = []
         
     def get_output(self):
+        # absolute, de-duplicated paths so the host can stat and open them
+        files = []
+        for f in self.files_created:
+            p = os.path.abspath(os.path.expanduser(str(f)))
+            if p not in files:
+                files.append(p)
         return {
             "output": self.stdout.getvalue(),
             "errors": self.stderr.getvalue(),
-            "files": self.files_created
+            "files": files,
+            "images": self.images,
+            "notices": self.notices
         }
 
 capture = OutputCapture()
@@ -428,10 +659,25 @@ print(json.dumps(result, default=str))
 }
 
 fn generate_template_helpers(_task_type: Option<&str>) -> String {
-    r####"
+    // debug-formatted string slices are valid python list literals
+    let style_lists = format!(
+        "\nREPORT_STYLES = {:?}\nPRESENTATION_THEMES = {:?}\n",
+        REPORT_STYLES, PRESENTATION_THEMES
+    );
+    style_lists + r####"
 # ===== Professional Document Helpers =====
 
-def create_professional_report(title: str, sections: dict, output_path: str, style: str = "modern"):
+def _resolve_style(value, valid, kind):
+    """The style to use - unknown names fall back to 'modern' and say so instead of silently"""
+    name = str(value or 'modern').strip().lower()
+    if name in valid:
+        return name
+    note = f"Unknown {kind} '{value}', used 'modern' instead. Valid {kind}s: {', '.join(valid)}"
+    print(f"Note: {note}")
+    capture.notices.append(note)
+    return 'modern'
+
+def create_professional_report(title: str, sections: dict, output_path: str, style: str = "modern", append: bool = False):
     """Create a professional report with multiple sections
     
     Args:
@@ -439,24 +685,38 @@ def create_professional_report(title: str, sections: dict, output_path: str, sty
         sections: Dict of section_name -> content (str or list of paragraphs)
         output_path: Where to save the report
         style: 'modern', 'classic', 'minimal', 'executive', 'dark'
+        append: Add the sections to the end of an existing .docx/.md/.html/.pptx instead of
+            overwriting it (the file is created when it doesn't exist yet)
     """
     ext = os.path.splitext(output_path)[1].lower()
+    if ext != '.pptx':
+        style = _resolve_style(style, REPORT_STYLES, 'report style')
+    appending = append and os.path.exists(output_path)
+    if appending and ext not in ['.html', '.docx', '.doc', '.md', '.pptx']:
+        return f"Can't append to {ext} files - build the report as .docx, .md or .html, then convert it at the end"
     
     if ext == '.html':
-        return _create_html_report(title, sections, output_path, style)
+        return _create_html_report(title, sections, output_path, style, appending)
     elif ext in ['.docx', '.doc']:
-        return _create_word_report(title, sections, output_path, style)
+        return _create_word_report(title, sections, output_path, style, appending)
     elif ext == '.pdf':
         return _create_pdf_report(title, sections, output_path, style)
     elif ext == '.md':
-        return _create_markdown_report(title, sections, output_path)
+        return _create_markdown_report(title, sections, output_path, appending)
     elif ext == '.pptx':
         slides = [{"title": k, "content": v} for k, v in sections.items()]
-        return create_presentation(title, slides, output_path, style)
+        return create_presentation(title, slides, output_path, style, append=appending)
     else:
         return _create_text_report(title, sections, output_path)
 
-def _create_html_report(title, sections, output_path, style):
+def append_to_report(output_path: str, sections: dict, title: str = None, style: str = "modern"):
+    """Open a .docx/.md/.html/.pptx report, or create it, and add sections to the end - for
+    building a long document a few sections at a time across several calls"""
+    if not title:
+        title = Path(output_path).stem.replace('_', ' ').replace('-', ' ').title()
+    return create_professional_report(title, sections, output_path, style, append=True)
+
+def _create_html_report(title, sections, output_path, style, appending=False):
     """Create modern HTML report with advanced CSS styling"""
     styles = {
         'modern': '''
@@ -540,6 +800,7 @@ def _create_html_report(title, sections, output_path, style):
     </div>
 '''
     
+    sections_html = ''
     for section_name, content in sections.items():
         if isinstance(content, list):
             content_html = ''.join(f'<p>{p}</p>' for p in content)
@@ -554,13 +815,23 @@ def _create_html_report(title, sections, output_path, style):
             paragraphs = str(content).split('\n')
             content_html = ''.join(f'<p>{p}</p>' for p in paragraphs if p.strip())
         
-        html += f'''    <div class="section">
+        sections_html += f'''    <div class="section">
         <h2>{section_name}</h2>
         {content_html}
     </div>
 '''
     
-    html += '''    <div class="footer">
+    if appending:
+        # new sections go just above the footer, or the end of the body for html we didn't write
+        with open(output_path, 'r', encoding='utf-8') as f:
+            existing = f.read()
+        cut = existing.rfind('    <div class="footer">')
+        if cut < 0:
+            cut = existing.rfind('</body>')
+        html = existing + sections_html if cut < 0 else existing[:cut] + sections_html + existing[cut:]
+    else:
+        html += sections_html
+        html += '''    <div class="footer">
         Generated by Hey work
     </div>
 </body>
@@ -570,9 +841,11 @@ def _create_html_report(title, sections, output_path, style):
         f.write(html)
     
     capture.files_created.append(output_path)
+    if appending:
+        return f"Added {len(sections)} sections to HTML report: {output_path}"
     return f"Professional HTML report created: {output_path}"
 
-def _create_word_report(title, sections, output_path, style):
+def _create_word_report(title, sections, output_path, style, appending=False):
     """Create Word document with professional formatting"""
     try:
         from docx import Document
@@ -581,14 +854,15 @@ def _create_word_report(title, sections, output_path, style):
         from docx.enum.style import WD_STYLE_TYPE
         from docx.enum.section import WD_ORIENT
         
-        doc = Document()
+        doc = Document(output_path) if appending else Document()
         
-        # Configure page margins
-        for section in doc.sections:
-            section.top_margin = Cm(2.54)
-            section.bottom_margin = Cm(2.54)
-            section.left_margin = Cm(2.54)
-            section.right_margin = Cm(2.54)
+        # Configure page margins - an existing document keeps its own
+        if not appending:
+            for section in doc.sections:
+                section.top_margin = Cm(2.54)
+                section.bottom_margin = Cm(2.54)
+                section.left_margin = Cm(2.54)
+                section.right_margin = Cm(2.54)
         
         # Style configuration based on theme
         style_config = {
@@ -601,23 +875,24 @@ def _create_word_report(title, sections, output_path, style):
         
         config = style_config.get(style, style_config['modern'])
         
-        # Title
-        title_para = doc.add_heading(title, 0)
-        title_para.alignment = WD_ALIGN_PARAGRAPH.CENTER
-        for run in title_para.runs:
-            run.font.color.rgb = config['title_color']
-            run.font.size = config['title_size']
-        
-        # Subtitle/timestamp
-        subtitle = doc.add_paragraph()
-        subtitle.alignment = WD_ALIGN_PARAGRAPH.CENTER
-        run = subtitle.add_run(f"Generated: {datetime.now().strftime('%B %d, %Y')}")
-        run.font.size = Pt(11)
-        run.font.color.rgb = RGBColor(100, 116, 139)
-        run.font.italic = True
-        
-        # Add a line break
-        doc.add_paragraph()
+        # Title - an existing document already has one
+        if not appending:
+            title_para = doc.add_heading(title, 0)
+            title_para.alignment = WD_ALIGN_PARAGRAPH.CENTER
+            for run in title_para.runs:
+                run.font.color.rgb = config['title_color']
+                run.font.size = config['title_size']
+            
+            # Subtitle/timestamp
+            subtitle = doc.add_paragraph()
+            subtitle.alignment = WD_ALIGN_PARAGRAPH.CENTER
+            run = subtitle.add_run(f"Generated: {datetime.now().strftime('%B %d, %Y')}")
+            run.font.size = Pt(11)
+            run.font.color.rgb = RGBColor(100, 116, 139)
+            run.font.italic = True
+            
+            # Add a line break
+            doc.add_paragraph()
         
         # Sections
         for section_name, content in sections.items():
@@ -649,6 +924,8 @@ def _create_word_report(title, sections, output_path, style):
         
         doc.save(output_path)
         capture.files_created.append(output_path)
+        if appending:
+            return f"Added {len(sections)} sections to Word document: {output_path}"
         return f"Word document created: {output_path}"
     except ImportError:
         return "python-docx not installed. Use: pip install python-docx"
@@ -763,11 +1040,14 @@ def _create_pdf_report(title, sections, output_path, style):
     except ImportError:
         return "reportlab not installed. Use: pip install reportlab"
 
-def _create_markdown_report(title, sections, output_path):
+def _create_markdown_report(title, sections, output_path, appending=False):
     """Create Markdown report"""
-    md = f"# {title}\n\n"
-    md += f"*Generated: {datetime.now().strftime('%B %d, %Y at %I:%M %p')}*\n\n"
-    md += "---\n\n"
+    if appending:
+        md = "\n"
+    else:
+        md = f"# {title}\n\n"
+        md += f"*Generated: {datetime.now().strftime('%B %d, %Y at %I:%M %p')}*\n\n"
+        md += "---\n\n"
     
     for section_name, content in sections.items():
         md += f"## {section_name}\n\n"
@@ -783,10 +1063,12 @@ def _create_markdown_report(title, sections, output_path):
         else:
             md += f"{content}\n\n"
     
-    with open(output_path, 'w', encoding='utf-8') as f:
+    with open(output_path, 'a' if appending else 'w', encoding='utf-8') as f:
         f.write(md)
     
     capture.files_created.append(output_path)
+    if appending:
+        return f"Added {len(sections)} sections to Markdown report: {output_path}"
     return f"Markdown report created: {output_path}"
 
 def _create_text_report(title, sections, output_path):
@@ -820,7 +1102,7 @@ def create_advanced_chart(data, chart_type='auto', title='', save_path=None, **k
         data: Data to visualize (dict, list, or DataFrame)
         chart_type: 'auto', 'bar', 'line', 'scatter', 'heatmap', 'pie', 'donut', 'area', 'histogram'
         title: Chart title
-        save_path: Where to save (supports .png, .html, .svg, .pdf)
+        save_path: Where to save (supports .png, .html, .svg, .pdf); None or 'inline' shows it in the chat
         **kwargs: Additional styling options (figsize, colors, xlabel, ylabel, theme)
     """
     theme = kwargs.get('theme', 'modern')
@@ -964,22 +1246,27 @@ def _create_matplotlib_chart(data, chart_type, title, save_path, **kwargs):
         
         plt.tight_layout()
         
-        if save_path:
+        if save_path and save_path != 'inline':
             plt.savefig(save_path, dpi=kwargs.get('dpi', 150), bbox_inches='tight', 
                        facecolor='white', edgecolor='none')
             plt.close()
             capture.files_created.append(save_path)
             return f"Chart saved: {save_path}"
         else:
+            # no path (or 'inline'): hand the chart back as an image for the chat instead of a file
+            buffer = BytesIO()
+            plt.savefig(buffer, format='png', dpi=min(kwargs.get('dpi', 150), 100), bbox_inches='tight',
+                       facecolor='white', edgecolor='none')
             plt.close()
-            return "Chart created (no save path specified)"
+            capture.images.append(base64.b64encode(buffer.getvalue()).decode('ascii'))
+            return "Chart shown inline in the chat"
             
     except ImportError as e:
         return f"Visualization libraries not installed: {e}"
 
 # ===== Professional Presentation Builder =====
 
-def create_presentation(title: str, slides: list, output_path: str, theme: str = 'modern'):
+def create_presentation(title: str, slides: list, output_path: str, theme: str = 'modern', append: bool = False):
     """Create professional PowerPoint presentation
     
     Args:
@@ -992,6 +1279,7 @@ def create_presentation(title: str, slides: list, output_path: str, theme: str =
             - 'image_path': Optional image to include
         output_path: Where to save (.pptx)
         theme: 'modern', 'dark', 'minimal', 'corporate', 'creative'
+        append: Add the slides to an existing deck (before its end slide) instead of overwriting it
     """
     try:
         from pptx import Presentation
@@ -1000,9 +1288,13 @@ def create_presentation(title: str, slides: list, output_path: str, theme: str =
         from pptx.enum.text import PP_ALIGN, MSO_ANCHOR
         from pptx.enum.shapes import MSO_SHAPE
         
-        prs = Presentation()
-        prs.slide_width = Inches(13.333)
-        prs.slide_height = Inches(7.5)
+        appending = append and os.path.exists(output_path)
+        if appending:
+            prs = Presentation(output_path)
+        else:
+            prs = Presentation()
+            prs.slide_width = Inches(13.333)
+            prs.slide_height = Inches(7.5)
         
         # Theme configurations
         themes = {
@@ -1068,7 +1360,7 @@ def create_presentation(title: str, slides: list, output_path: str, theme: str =
             },
         }
         
-        t = themes.get(theme, themes['modern'])
+        t = themes[_resolve_style(theme, PRESENTATION_THEMES, 'presentation theme')]
         
         def set_slide_bg(slide, color):
             background = slide.background
@@ -1086,32 +1378,40 @@ def create_presentation(title: str, slides: list, output_path: str, theme: str =
                 shape.fill.fore_color.rgb = color
                 shape.line.fill.background()
         
+        # an existing deck keeps its end slide last - remember it so new slides go in front
+        end_slide_id = None
+        if appending and len(prs.slides) > 0:
+            last = prs.slides[len(prs.slides) - 1]
+            if any(shape.has_text_frame and shape.text_frame.text.startswith("Thank You") for shape in last.shapes):
+                end_slide_id = prs.slides._sldIdLst[-1]
+        
         # ===== TITLE SLIDE =====
-        title_slide = prs.slides.add_slide(prs.slide_layouts[6])  # Blank
-        set_slide_bg(title_slide, t['bg_color'])
+        title_slide = None if appending else prs.slides.add_slide(prs.slide_layouts[6])  # Blank
+        if title_slide is not None:
+            set_slide_bg(title_slide, t['bg_color'])
         
-        # Title text
-        txBox = title_slide.shapes.add_textbox(Inches(0.8), Inches(2.0), Inches(11), Inches(2))
-        tf = txBox.text_frame
-        tf.word_wrap = True
-        p = tf.paragraphs[0]
-        p.text = title
-        p.font.size = Pt(44)
-        p.font.bold = True
-        p.font.color.rgb = t['title_color']
-        p.font.name = t['title_font']
+            # Title text
+            txBox = title_slide.shapes.add_textbox(Inches(0.8), Inches(2.0), Inches(11), Inches(2))
+            tf = txBox.text_frame
+            tf.word_wrap = True
+            p = tf.paragraphs[0]
+            p.text = title
+            p.font.size = Pt(44)
+            p.font.bold = True
+            p.font.color.rgb = t['title_color']
+            p.font.name = t['title_font']
         
-        # Subtitle
-        txBox2 = title_slide.shapes.add_textbox(Inches(0.8), Inches(4.2), Inches(11), Inches(1))
-        tf2 = txBox2.text_frame
-        p2 = tf2.paragraphs[0]
-        p2.text = datetime.now().strftime('%B %d, %Y')
-        p2.font.size = Pt(18)
-        p2.font.color.rgb = t['subtitle_color']
-        p2.font.name = t['body_font']
+            # Subtitle
+            txBox2 = title_slide.shapes.add_textbox(Inches(0.8), Inches(4.2), Inches(11), Inches(1))
+            tf2 = txBox2.text_frame
+            p2 = tf2.paragraphs[0]
+            p2.text = datetime.now().strftime('%B %d, %Y')
+            p2.font.size = Pt(18)
+            p2.font.color.rgb = t['subtitle_color']
+            p2.font.name = t['body_font']
         
-        # Accent bar on title slide
-        add_accent_bar(title_slide, t['accent_color'], y=Inches(3.9))
+            # Accent bar on title slide
+            add_accent_bar(title_slide, t['accent_color'], y=Inches(3.9))
         
         # ===== CONTENT SLIDES =====
         for slide_data in slides:
@@ -1135,7 +1435,7 @@ def create_presentation(title: str, slides: list, output_path: str, theme: str =
             p.font.name = t['title_font']
             
             # Accent bar
-            add_accent_bar(title_slide=slide, color=t['accent_color'], y=Inches(1.6))
+            add_accent_bar(slide, t['accent_color'], y=Inches(1.6))
             
             # Content area
             content_top = Inches(2.0)
@@ -1221,35 +1521,55 @@ def create_presentation(title: str, slides: list, output_path: str, theme: str =
                     pass
         
         # ===== THANK YOU / END SLIDE =====
-        end_slide = prs.slides.add_slide(prs.slide_layouts[6])
-        set_slide_bg(end_slide, t['bg_color'])
+        if not appending:
+            end_slide = prs.slides.add_slide(prs.slide_layouts[6])
+            set_slide_bg(end_slide, t['bg_color'])
         
-        txBox = end_slide.shapes.add_textbox(Inches(0.8), Inches(2.5), Inches(11), Inches(2))
-        tf = txBox.text_frame
-        p = tf.paragraphs[0]
-        p.text = "Thank You"
-        p.font.size = Pt(44)
-        p.font.bold = True
-        p.font.color.rgb = t['title_color']
-        p.font.name = t['title_font']
-        p.alignment = PP_ALIGN.CENTER
+            txBox = end_slide.shapes.add_textbox(Inches(0.8), Inches(2.5), Inches(11), Inches(2))
+            tf = txBox.text_frame
+            p = tf.paragraphs[0]
+            p.text = "Thank You"
+            p.font.size = Pt(44)
+            p.font.bold = True
+            p.font.color.rgb = t['title_color']
+            p.font.name = t['title_font']
+            p.alignment = PP_ALIGN.CENTER
         
-        p2 = tf.add_paragraph()
-        p2.text = f"Generated by Hey work • {datetime.now().strftime('%B %Y')}"
-        p2.font.size = Pt(14)
-        p2.font.color.rgb = t['subtitle_color']
-        p2.font.name = t['body_font']
-        p2.alignment = PP_ALIGN.CENTER
+            p2 = tf.add_paragraph()
+            p2.text = f"Generated by Hey work • {datetime.now().strftime('%B %Y')}"
+            p2.font.size = Pt(14)
+            p2.font.color.rgb = t['subtitle_color']
+            p2.font.name = t['body_font']
+            p2.alignment = PP_ALIGN.CENTER
+        elif end_slide_id is not None:
+            prs.slides._sldIdLst.remove(end_slide_id)
+            prs.slides._sldIdLst.append(end_slide_id)
         
         prs.save(output_path)
         capture.files_created.append(output_path)
+        if appending:
+            return f"Added {len(slides)} slides to presentation: {output_path} ({len(prs.slides)} slides now)"
         return f"Presentation created: {output_path} ({len(slides) + 2} slides including title and end)"
         
     except ImportError:
         return "python-pptx not installed. Use: pip install python-pptx"
 
+def add_slides_to(path: str, slides: list, theme: str = 'modern'):
+    """Add slides to an existing .pptx, or create the deck when it doesn't exist yet - for
+    building a presentation a few slides at a time across several calls"""
+    title = Path(path).stem.replace('_', ' ').replace('-', ' ').title()
+    return create_presentation(title, slides, path, theme, append=True)
+
 # ===== Spreadsheet Builder =====
 
+def _env_int(name, default):
+    """Positive integer limit from the environment, falling back to the default"""
+    try:
+        value = int(os.environ.get(name, default))
+        return value if value > 0 else default
+    except ValueError:
+        return default
+
 def create_spreadsheet(data: dict, output_path: str, sheet_names: list = None):
     """Create Excel spreadsheet with professional formatting
     
@@ -1267,20 +1587,38 @@ def create_spreadsheet(data: dict, output_path: str, sheet_names: list = None):
         if not output_path.endswith('.xlsx'):
             output_path += '.xlsx'
         
+        max_rows = _env_int('HEYWORK_SPREADSHEET_MAX_ROWS', 50000)
+        max_cells = _env_int('HEYWORK_SPREADSHEET_MAX_CELLS', 1000000)
+        truncated = []
+        
         with pd.ExcelWriter(output_path, engine='openpyxl') as writer:
             for idx, (name, df_data) in enumerate(data.items()):
                 sheet_name = sheet_names[idx] if sheet_names and idx < len(sheet_names) else name[:31]
                 
                 if isinstance(df_data, list):
-                    df = pd.DataFrame(df_data)
+                    total_rows = len(df_data)
+                    # slice before building the frame so a huge list never becomes a huge frame
+                    df = pd.DataFrame(df_data[:max_rows])
                 elif isinstance(df_data, dict):
+                    total_rows = 1
                     df = pd.DataFrame([df_data])
                 else:
                     df = df_data
+                    total_rows = len(df)
+                
+                row_limit = min(max_rows, max_cells // max(len(df.columns), 1))
+                if total_rows > row_limit:
+                    df = df.head(row_limit)
+                    truncated.append(f"sheet '{sheet_name}' truncated to {row_limit:,} of {total_rows:,} rows")
                 
                 df.to_excel(writer, sheet_name=sheet_name, index=False, startrow=1)
                 
                 worksheet = writer.sheets[sheet_name]
+                if total_rows > row_limit:
+                    note_row = worksheet.max_row + 2
+                    worksheet.cell(row=note_row, column=1,
+                                   value=f"TRUNCATED: showing {row_limit:,} of {total_rows:,} rows")
+                    worksheet.cell(row=note_row, column=1).font = Font(name='Calibri', size=10, bold=True, color="DC2626")
                 
                 # Header styling
                 header_fill = PatternFill(start_color="2563EB", end_color="2563EB", fill_type="solid")
@@ -1330,7 +1668,12 @@ def create_spreadsheet(data: dict, output_path: str, sheet_names: list = None):
                     worksheet.column_dimensions[column_letter].width = adjusted_width
         
         capture.files_created.append(output_path)
-        return f"Excel workbook created: {output_path} ({len(data)} sheets)"
+        result = f"Excel workbook created: {output_path} ({len(data)} sheets)"
+        if truncated:
+            note = "Output limited: " + "; ".join(truncated)
+            capture.notices.append(note)
+            result += f"\n⚠️ {note}"
+        return result
         
     except ImportError:
         return "pandas/openpyxl not installed. Use: pip install pandas openpyxl"
@@ -1338,7 +1681,7 @@ def create_spreadsheet(data: dict, output_path: str, sheet_names: list = None):
 # ===== Data Analysis =====
 
 def quick_analyze(data):
-    """Quick statistical analysis of data"""
+    """Quick statistical analysis of data - large inputs are summarized from a random sample"""
     try:
         import pandas as pd
         import numpy as np
@@ -1348,13 +1691,21 @@ def quick_analyze(data):
         elif isinstance(data, dict):
             data = pd.DataFrame(data)
         
+        total_rows = len(data)
+        sample_rows = _env_int('HEYWORK_ANALYZE_SAMPLE_ROWS', 100000)
+        sampled = data.sample(n=sample_rows, random_state=0) if total_rows > sample_rows else data
+        
         analysis = {
             'shape': str(data.shape),
             'columns': list(data.columns),
             'dtypes': {str(k): str(v) for k, v in data.dtypes.to_dict().items()},
-            'summary': data.describe().to_dict(),
+            'summary': sampled.describe().to_dict(),
             'missing': data.isnull().sum().to_dict()
         }
+        if sampled is not data:
+            note = f"Summary statistics computed from a random sample of {sample_rows:,} of {total_rows:,} rows"
+            analysis['note'] = note
+            capture.notices.append(note)
         
         return json.dumps(analysis, indent=2, default=str)
     except ImportError:
@@ -1434,7 +1785,7 @@ def create_dashboard(title: str, charts: list, output_path: str, layout: str = '
     capture.files_created.append(output_path)
     return f"Dashboard created: {output_path} ({len(charts)} charts)"
 
-"####.to_string()
+"####
 }
 
 fn format_output(output: &str, task_type: Option<&str>) -> String {
@@ -1463,25 +1814,14 @@ fn format_error_output(error: &str) -> String {
     format!("❌ Python Execution Failed\n\n```\n{}\n```\n\n💡 Run in Terminal to debug:\n```\ncd /tmp && python3 script.py\n```", error)
 }
 
-fn extract_files_created(output: &str) -> Vec<String> {
-    let mut files = vec![];
-    
-    for line in output.lines() {
-        if line.contains("created:") || line.contains("saved:") {
-            if let Some(path) = line.split(':').last() {
-                let path = path.trim();
-                if !path.is_empty() {
-                    files.push(path.to_string());
-                }
-            }
-        }
-    }
-    
-    files
+/// Stat the paths reported by the wrapper, dropping any that were never written
+fn collect_created_files(paths: &[String]) -> Vec<CreatedFile> {
+    paths.iter().filter_map(|p| CreatedFile::from_path(p)).collect()
 }
 
-fn generate_suggestions(output: &str, task_type: Option<&str>) -> Vec<String> {
-    let mut suggestions = vec![];
+fn generate_suggestions(output: &str, notices: &[String], task_type: Option<&str>) -> Vec<String> {
+    // limits the helpers hit come first, so the model tells the user the output was cut down
+    let mut suggestions: Vec<String> = notices.iter().map(|notice| format!("⚠️ {}", notice)).collect();
     
     if output.contains("not installed") {
         suggestions.push("💡 Auto-install attempted. If still failing, try: pip3 install python-docx reportlab matplotlib pandas openpyxl python-pptx plotly".to_string());
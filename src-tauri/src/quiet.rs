@@ -0,0 +1,142 @@
+// do not disturb: mutes the push-to-talk and capture sounds, the app's own sound effects and
+// the help-capture flash, and optionally spoken replies. it can also switch itself on while the
+// screen is being shared, so a meeting doesn't hear the agent
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// env vars (saved in .env): the manual switch, whether it also mutes spoken replies, and
+/// whether it turns on by itself during screen sharing
+pub const DO_NOT_DISTURB_ENV: &str = "HEYWORK_DO_NOT_DISTURB";
+pub const SILENCE_SPEECH_ENV: &str = "HEYWORK_DND_SILENCE_SPEECH";
+pub const WHILE_SHARING_ENV: &str = "HEYWORK_DND_WHILE_SHARING";
+
+/// emitted with a DndStatus whenever do not disturb turns on or off
+pub const DND_CHANGED_EVENT: &str = "dnd:changed";
+
+/// sounds played by the push-to-talk and help-capture hotkeys
+pub const START_SOUND: &str = "/System/Library/Sounds/Tink.aiff";
+pub const STOP_SOUND: &str = "/System/Library/Sounds/Pop.aiff";
+pub const CAPTURE_SOUND: &str =
+    "/System/Library/Components/CoreAudio.component/Contents/SharedSupport/SystemSounds/system/Grab.aif";
+
+// how often screen sharing is looked for while the auto switch is on
+const SHARING_POLL_SECS: u64 = 10;
+// processes that only run while the screen is shared: zoom's share helper and the macos
+// screen sharing service. browser-based calls can't be told apart from normal browsing
+#[cfg(target_os = "macos")]
+const SCREEN_SHARE_PROCESSES: &[&str] = &["CptHost", "screensharingd"];
+
+fn flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndStatus {
+    /// the manual switch
+    pub enabled: bool,
+    pub silence_speech: bool,
+    pub while_sharing: bool,
+    /// whether sounds are muted right now - the switch, or a share the auto switch caught
+    pub active: bool,
+}
+
+impl DndStatus {
+    pub fn resolve(enabled: bool, silence_speech: bool, while_sharing: bool, sharing: bool) -> Self {
+        Self { enabled, silence_speech, while_sharing, active: enabled || (while_sharing && sharing) }
+    }
+
+    pub fn speech_muted(&self) -> bool {
+        self.active && self.silence_speech
+    }
+}
+
+/// whether a known screen-share process is running
+pub fn screen_sharing() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        SCREEN_SHARE_PROCESSES.iter().any(|name| {
+            std::process::Command::new("pgrep")
+                .args(["-x", name])
+                .stdout(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        })
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        false
+    }
+}
+
+pub fn status() -> DndStatus {
+    let enabled = flag(DO_NOT_DISTURB_ENV);
+    let while_sharing = flag(WHILE_SHARING_ENV);
+    // only look for a share when it would change the answer
+    let sharing = !enabled && while_sharing && screen_sharing();
+    DndStatus::resolve(enabled, flag(SILENCE_SPEECH_ENV), while_sharing, sharing)
+}
+
+pub fn active() -> bool {
+    status().active
+}
+
+/// play a system sound unless do not disturb is on
+pub fn play_sound(path: &str) {
+    if active() {
+        return;
+    }
+    #[cfg(target_os = "macos")]
+    std::process::Command::new("afplay").arg(path).spawn().ok();
+    #[cfg(not(target_os = "macos"))]
+    let _ = path;
+}
+
+/// the tray's do-not-disturb check item, kept so a change from settings shows in the tray too
+pub struct TrayToggle(pub tauri::menu::CheckMenuItem<tauri::Wry>);
+
+/// tell the tray and the ui about the current state after a setting changed
+pub fn notify(app: &AppHandle) {
+    let status = status();
+    if let Some(toggle) = app.try_state::<TrayToggle>() {
+        let _ = toggle.0.set_checked(status.enabled);
+    }
+    let _ = app.emit(DND_CHANGED_EVENT, status);
+}
+
+/// watch for screen sharing starting or stopping while the auto switch is on
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut was_active = None;
+        loop {
+            // a check that panicked is skipped, but still waits out the interval
+            if let Ok(status) = tokio::task::spawn_blocking(status).await {
+                if was_active.is_some_and(|was| was != status.active) {
+                    println!("[quiet] Do not disturb {}", if status.active { "on" } else { "off" });
+                    let _ = app.emit(DND_CHANGED_EVENT, status);
+                }
+                was_active = Some(status.active);
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(SHARING_POLL_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sharing_only_mutes_when_the_auto_switch_is_on() {
+        assert!(DndStatus::resolve(true, false, false, false).active);
+        assert!(DndStatus::resolve(false, false, true, true).active);
+        assert!(!DndStatus::resolve(false, false, false, true).active);
+        assert!(!DndStatus::resolve(false, true, true, false).active);
+        // spoken replies only go quiet when asked to
+        assert!(!DndStatus::resolve(true, false, false, false).speech_muted());
+        assert!(DndStatus::resolve(false, true, true, true).speech_muted());
+    }
+}
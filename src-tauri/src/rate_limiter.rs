@@ -4,8 +4,10 @@
 //! when rate limits are hit. Ensures context/memory is preserved during retries.
 
 use crate::storage::Usage;
-use std::collections::VecDeque;
-
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::time::sleep;
@@ -30,6 +32,20 @@ const BASE_RETRY_DELAY_MS: u64 = 1000;
 /// Maximum retry delay (ms)
 const MAX_RETRY_DELAY_MS: u64 = 60_000;
 
+/// Jitter applied to backoff delays (±20%) so concurrent agents don't retry in lockstep
+const RETRY_JITTER: f64 = 0.2;
+
+/// Delay before retrying a rate-limited request: the server's `retry_after` when it gave one,
+/// otherwise 2^attempt seconds (capped at 16s) with ±20% jitter
+pub fn retry_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    if let Some(wait) = retry_after {
+        return wait.min(Duration::from_millis(MAX_RETRY_DELAY_MS));
+    }
+    let base = Duration::from_secs(2_u64.pow(attempt.clamp(1, 4)));
+    let factor = 1.0 - RETRY_JITTER + rand::random::<f64>() * RETRY_JITTER * 2.0;
+    base.mul_f64(factor)
+}
+
 /// Token bucket entry
 #[derive(Debug, Clone)]
 struct TokenBucketEntry {
@@ -371,6 +387,236 @@ impl Default for RateLimiter {
     }
 }
 
+// ============================================================================
+// Per-model token buckets
+// ============================================================================
+//
+// Shared across every AnthropicClient in the process (main agent, swarm executors,
+// deep research) so parallel callers draw from one budget instead of each assuming
+// they have the whole org limit to themselves.
+
+/// Per-minute request and input-token budget for a model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelLimits {
+    pub requests_per_minute: u32,
+    pub tokens_per_minute: u32,
+}
+
+/// Defaults by model family, roughly a Build-tier org. Higher tiers raise them with `set_rate_limits`.
+const DEFAULT_MODEL_LIMITS: &[(&str, ModelLimits)] = &[
+    ("haiku", ModelLimits { requests_per_minute: 50, tokens_per_minute: 100_000 }),
+    ("sonnet", ModelLimits { requests_per_minute: 50, tokens_per_minute: BUILD_TIER_INPUT_TPM }),
+    ("opus", ModelLimits { requests_per_minute: 50, tokens_per_minute: BUILD_TIER_INPUT_TPM }),
+];
+
+const FALLBACK_MODEL_LIMITS: ModelLimits = ModelLimits {
+    requests_per_minute: 50,
+    tokens_per_minute: BUILD_TIER_INPUT_TPM,
+};
+
+/// Continuously refilling bucket holding up to one minute of budget
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        Self {
+            capacity: per_minute as f64,
+            available: per_minute as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Time until `amount` is available (zero if it already is)
+    fn wait_for(&self, amount: f64) -> Duration {
+        let amount = amount.min(self.capacity);
+        if self.available >= amount || self.capacity <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((amount - self.available) * 60.0 / self.capacity)
+    }
+
+    fn resize(&mut self, per_minute: u32) {
+        self.capacity = per_minute as f64;
+        self.available = self.available.min(self.capacity);
+    }
+}
+
+/// Request + input-token buckets for one model
+pub struct ModelRateLimiter {
+    model: String,
+    buckets: std::sync::Mutex<(TokenBucket, TokenBucket)>, // (requests, tokens)
+}
+
+impl ModelRateLimiter {
+    fn new(model: &str, limits: ModelLimits) -> Self {
+        let now = Instant::now();
+        Self {
+            model: model.to_string(),
+            buckets: std::sync::Mutex::new((
+                TokenBucket::new(limits.requests_per_minute, now),
+                TokenBucket::new(limits.tokens_per_minute, now),
+            )),
+        }
+    }
+
+    /// Wait until one request and `estimated_tokens` input tokens fit in the budget, then take them.
+    /// Requests bigger than a whole minute of budget wait for a full bucket rather than forever.
+    pub async fn acquire(&self, estimated_tokens: u32) -> Duration {
+        let started = Instant::now();
+        loop {
+            let wait = {
+                let mut guard = self.buckets.lock().unwrap();
+                let (requests, tokens) = &mut *guard;
+                let now = Instant::now();
+                requests.refill(now);
+                tokens.refill(now);
+                let wait = requests.wait_for(1.0).max(tokens.wait_for(estimated_tokens as f64));
+                if wait.is_zero() {
+                    requests.available -= 1.0;
+                    tokens.available -= (estimated_tokens as f64).min(tokens.capacity);
+                }
+                wait
+            };
+            if wait.is_zero() {
+                break;
+            }
+            println!(
+                "[rate_limiter] {} budget exhausted, waiting {:?} for ~{} tokens",
+                self.model, wait, estimated_tokens
+            );
+            sleep(wait.max(Duration::from_millis(50))).await;
+        }
+        started.elapsed()
+    }
+
+    /// Correct the token bucket once the real input usage is known
+    pub fn settle(&self, estimated_tokens: u32, actual_tokens: u32) {
+        let mut guard = self.buckets.lock().unwrap();
+        let tokens = &mut guard.1;
+        tokens.available = (tokens.available + estimated_tokens as f64 - actual_tokens as f64).min(tokens.capacity);
+    }
+
+    fn set_limits(&self, limits: ModelLimits) {
+        let mut guard = self.buckets.lock().unwrap();
+        guard.0.resize(limits.requests_per_minute);
+        guard.1.resize(limits.tokens_per_minute);
+    }
+}
+
+struct LimiterRegistry {
+    limiters: HashMap<String, Arc<ModelRateLimiter>>,
+    /// user overrides keyed by model id or family ("opus", "sonnet", "haiku")
+    overrides: HashMap<String, ModelLimits>,
+}
+
+static REGISTRY: OnceLock<std::sync::Mutex<LimiterRegistry>> = OnceLock::new();
+
+fn overrides_path() -> PathBuf {
+    crate::storage::data_dir().join("rate_limits.json")
+}
+
+fn registry() -> &'static std::sync::Mutex<LimiterRegistry> {
+    REGISTRY.get_or_init(|| {
+        let overrides = std::fs::read_to_string(overrides_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        std::sync::Mutex::new(LimiterRegistry {
+            limiters: HashMap::new(),
+            overrides,
+        })
+    })
+}
+
+fn model_family(model: &str) -> Option<&'static str> {
+    let lower = model.to_lowercase();
+    DEFAULT_MODEL_LIMITS
+        .iter()
+        .map(|(family, _)| *family)
+        .find(|family| lower.contains(family))
+}
+
+fn resolve_limits(model: &str, overrides: &HashMap<String, ModelLimits>) -> ModelLimits {
+    let family = model_family(model);
+    overrides
+        .get(model)
+        .or_else(|| family.and_then(|f| overrides.get(f)))
+        .copied()
+        .or_else(|| {
+            family.and_then(|f| DEFAULT_MODEL_LIMITS.iter().find(|(name, _)| *name == f).map(|(_, l)| *l))
+        })
+        .unwrap_or(FALLBACK_MODEL_LIMITS)
+}
+
+/// Shared limiter for a model (created on first use)
+pub fn limiter_for(model: &str) -> Arc<ModelRateLimiter> {
+    let mut registry = registry().lock().unwrap();
+    if let Some(limiter) = registry.limiters.get(model) {
+        return limiter.clone();
+    }
+    let limits = resolve_limits(model, &registry.overrides);
+    let limiter = Arc::new(ModelRateLimiter::new(model, limits));
+    registry.limiters.insert(model.to_string(), limiter.clone());
+    limiter
+}
+
+/// Effective limits per model family (and any per-model overrides)
+#[tauri::command]
+pub fn get_rate_limits() -> HashMap<String, ModelLimits> {
+    let registry = registry().lock().unwrap();
+    let mut all: HashMap<String, ModelLimits> = DEFAULT_MODEL_LIMITS
+        .iter()
+        .map(|(family, _)| (family.to_string(), resolve_limits(family, &registry.overrides)))
+        .collect();
+    all.extend(registry.overrides.iter().map(|(k, v)| (k.clone(), *v)));
+    all
+}
+
+/// Only the limits the user has set, keyed by model id or family (for settings export)
+pub fn rate_limit_overrides() -> HashMap<String, ModelLimits> {
+    registry().lock().unwrap().overrides.clone()
+}
+
+/// Raise (or lower) the budget for a model id or family; applies to live limiters immediately
+#[tauri::command]
+pub fn set_rate_limits(model: String, requests_per_minute: u32, tokens_per_minute: u32) -> Result<(), String> {
+    if requests_per_minute == 0 || tokens_per_minute == 0 {
+        return Err("Limits must be greater than zero".to_string());
+    }
+    let key = model.trim().to_lowercase();
+    if key.is_empty() {
+        return Err("Model is required".to_string());
+    }
+
+    let mut registry = registry().lock().unwrap();
+    registry.overrides.insert(key, ModelLimits { requests_per_minute, tokens_per_minute });
+
+    let path = overrides_path();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let json = serde_json::to_string_pretty(&registry.overrides).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    let LimiterRegistry { limiters, overrides } = &*registry;
+    for (model_id, limiter) in limiters {
+        limiter.set_limits(resolve_limits(model_id, overrides));
+    }
+    println!("[rate_limiter] Limits for {} set to {} RPM / {} TPM", model, requests_per_minute, tokens_per_minute);
+    Ok(())
+}
+
 /// Statistics for monitoring
 #[derive(Debug, Clone)]
 pub struct RateLimiterStats {
@@ -399,3 +645,62 @@ impl RateLimiterStats {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_waits_for_refill() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, start); // 1 per second
+        bucket.available = 0.0;
+        assert_eq!(bucket.wait_for(2.0), Duration::from_secs(2));
+
+        bucket.refill(start + Duration::from_secs(3));
+        assert!((bucket.available - 3.0).abs() < 1e-6);
+        assert!(bucket.wait_for(2.0).is_zero());
+
+        bucket.refill(start + Duration::from_secs(600));
+        assert_eq!(bucket.available, 60.0);
+    }
+
+    #[test]
+    fn oversized_requests_only_wait_for_a_full_bucket() {
+        let mut bucket = TokenBucket::new(60, Instant::now());
+        bucket.available = 30.0;
+        assert_eq!(bucket.wait_for(1_000.0), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn limits_resolve_by_model_then_family() {
+        let mut overrides = HashMap::new();
+        assert_eq!(resolve_limits("claude-haiku-4-5-20251001", &overrides).tokens_per_minute, 100_000);
+        assert_eq!(resolve_limits("some-other-model", &overrides), FALLBACK_MODEL_LIMITS);
+
+        let family = ModelLimits { requests_per_minute: 1000, tokens_per_minute: 400_000 };
+        let exact = ModelLimits { requests_per_minute: 4000, tokens_per_minute: 2_000_000 };
+        overrides.insert("opus".to_string(), family);
+        overrides.insert("claude-opus-4-6".to_string(), exact);
+        assert_eq!(resolve_limits("claude-opus-4-5", &overrides), family);
+        assert_eq!(resolve_limits("claude-opus-4-6", &overrides), exact);
+    }
+
+    #[test]
+    fn retry_delay_honors_server_hint() {
+        assert_eq!(retry_delay(3, Some(Duration::from_secs(7))), Duration::from_secs(7));
+        assert_eq!(
+            retry_delay(1, Some(Duration::from_secs(600))),
+            Duration::from_millis(MAX_RETRY_DELAY_MS)
+        );
+    }
+
+    #[test]
+    fn retry_delay_jitters_within_bounds() {
+        for attempt in 1..=6 {
+            let base = 2_u64.pow(attempt.min(4)) as f64;
+            let delay = retry_delay(attempt, None).as_secs_f64();
+            assert!(delay >= base * 0.8 - 1e-9 && delay <= base * 1.2 + 1e-9);
+        }
+    }
+}
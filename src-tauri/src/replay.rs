@@ -0,0 +1,157 @@
+// replay_conversation: run a stored conversation's actions again, in order, without the model.
+// only tools that act on the machine are replayed - screenshots and zooms have no model to
+// read them, and anything else (memory, python, search) is listed but skipped
+
+use crate::api::{ContentBlock, Message};
+use serde::Serialize;
+
+// computer actions that only look at the screen
+const LOOK_ONLY_ACTIONS: &[&str] = &["screenshot", "zoom", "zoom_to_text", "cursor_position"];
+// browser tools replay can drive. downloads are left out so a replay doesn't fill the
+// downloads folder with copies
+const REPLAYED_BROWSER_TOOLS: &[&str] = &["see_page", "page_action", "browser_navigate", "autofill_login"];
+// page_action inputs that name elements by snapshot uid. a uid only means something in the
+// snapshot it came from - on replay it's stale, or worse points at a different element - so
+// these page actions are listed but never replayed
+const ELEMENT_UID_FIELDS: &[&str] = &["click", "double_click", "type_into", "hover", "drag_from_to", "scroll_to", "fill_form", "upload_file"];
+// longest summary shown per step
+const MAX_SUMMARY_CHARS: usize = 120;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayStep {
+    /// position among the conversation's tool calls, from 1
+    pub index: usize,
+    pub tool: String,
+    pub summary: String,
+    pub input: serde_json::Value,
+    /// false when the step is only listed - replay passes over it
+    pub replayed: bool,
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn str_field<'a>(input: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    input.get(key).and_then(|v| v.as_str())
+}
+
+/// whether replay performs this call or only lists it
+pub fn is_replayed(tool: &str, input: &serde_json::Value) -> bool {
+    match tool {
+        "computer" => str_field(input, "action").is_some_and(|action| !LOOK_ONLY_ACTIONS.contains(&action)),
+        "bash" => str_field(input, "command").is_some(),
+        "see_page" => !input.get("screenshot").and_then(|v| v.as_bool()).unwrap_or(false),
+        "page_action" => !ELEMENT_UID_FIELDS.iter().any(|field| input.get(field).is_some()),
+        _ => REPLAYED_BROWSER_TOOLS.contains(&tool),
+    }
+}
+
+/// one line describing the call, e.g. `left_click at (512, 300)` or the bash command
+pub fn summarize(tool: &str, input: &serde_json::Value) -> String {
+    let summary = match tool {
+        "computer" => {
+            let action = str_field(input, "action").unwrap_or("?");
+            let coordinate = input.get("coordinate").and_then(|v| v.as_array()).map(|c| {
+                c.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")
+            });
+            match (coordinate, str_field(input, "text")) {
+                (Some(coordinate), _) => format!("{} at ({})", action, coordinate),
+                (None, Some(text)) => format!("{} \"{}\"", action, text),
+                (None, None) => action.to_string(),
+            }
+        }
+        "bash" => str_field(input, "command").unwrap_or("restart").to_string(),
+        "browser_navigate" => format!("navigate {}", str_field(input, "url").unwrap_or("")),
+        _ => format!("{} {}", tool, input),
+    };
+    clip(&summary)
+}
+
+/// every tool call in the conversation, in the order the model made them
+pub fn steps(messages: &[Message]) -> Vec<ReplayStep> {
+    messages
+        .iter()
+        .filter(|message| message.role == "assistant")
+        .flat_map(|message| message.content.iter())
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { name, input, .. } => Some((name, input)),
+            _ => None,
+        })
+        .enumerate()
+        .map(|(i, (name, input))| ReplayStep {
+            index: i + 1,
+            tool: name.clone(),
+            summary: summarize(name, input),
+            input: input.clone(),
+            replayed: is_replayed(name, input),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_use(name: &str, input: serde_json::Value) -> ContentBlock {
+        ContentBlock::ToolUse { id: format!("t_{}", name), name: name.to_string(), input }
+    }
+
+    #[test]
+    fn steps_follow_the_tool_calls_in_order() {
+        let messages = vec![
+            Message { role: "user".to_string(), content: vec![ContentBlock::Text { text: "tidy up".to_string() }] },
+            Message {
+                role: "assistant".to_string(),
+                content: vec![
+                    ContentBlock::Text { text: "Looking first".to_string() },
+                    tool_use("computer", serde_json::json!({ "action": "screenshot" })),
+                    tool_use("computer", serde_json::json!({ "action": "left_click", "coordinate": [512, 300] })),
+                ],
+            },
+            Message {
+                role: "assistant".to_string(),
+                content: vec![
+                    tool_use("bash", serde_json::json!({ "command": "mkdir -p ~/Archive" })),
+                    tool_use("memory", serde_json::json!({ "fact": "likes tidy desktops" })),
+                ],
+            },
+        ];
+        let steps = steps(&messages);
+        let listed: Vec<(usize, &str, &str, bool)> = steps
+            .iter()
+            .map(|s| (s.index, s.tool.as_str(), s.summary.as_str(), s.replayed))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (1, "computer", "screenshot", false),
+                (2, "computer", "left_click at (512, 300)", true),
+                (3, "bash", "mkdir -p ~/Archive", true),
+                (4, "memory", "memory {\"fact\":\"likes tidy desktops\"}", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn page_reads_replay_but_page_screenshots_dont() {
+        assert!(is_replayed("see_page", &serde_json::json!({})));
+        assert!(!is_replayed("see_page", &serde_json::json!({ "screenshot": true })));
+        assert!(is_replayed("browser_navigate", &serde_json::json!({ "url": "https://example.com" })));
+        assert!(!is_replayed("bash", &serde_json::json!({ "restart": true })));
+    }
+
+    #[test]
+    fn page_actions_on_snapshot_uids_are_only_listed() {
+        assert!(!is_replayed("page_action", &serde_json::json!({ "click": "3_42" })));
+        assert!(!is_replayed("page_action", &serde_json::json!({ "type_into": "3_10", "text": "hi" })));
+        assert!(!is_replayed("page_action", &serde_json::json!({ "fill_form": [{ "element": "3_10", "text": "x" }] })));
+        assert!(is_replayed("page_action", &serde_json::json!({ "press_key": "Enter" })));
+        assert!(is_replayed("page_action", &serde_json::json!({ "scroll": "down" })));
+    }
+}
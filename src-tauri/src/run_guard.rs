@@ -0,0 +1,148 @@
+// one top-level agent run at a time. the agent's `running` flag can't serve for this:
+// stop_agent clears it while the run is still winding down and holding the
+// computer/bash/browser locks, and it's only set once the run is well under way
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// what a caller gets back when a run is already active
+pub const AGENT_BUSY_ERROR: &str = "Agent is already running";
+
+#[derive(Debug, Clone, Default)]
+pub struct RunGuard {
+    busy: Arc<AtomicBool>,
+}
+
+/// why `RunGuard::start` didn't start a run
+#[derive(Debug, PartialEq, Eq)]
+pub enum StartError {
+    /// another run holds the guard
+    Busy,
+    /// the readiness check failed, with the message for the caller
+    NotReady(String),
+}
+
+/// held for the lifetime of a run - dropping it (even on panic) frees the guard
+#[derive(Debug)]
+pub struct RunSlot {
+    busy: Arc<AtomicBool>,
+}
+
+impl RunGuard {
+    /// claim the guard, or None if a run already holds it
+    pub fn try_claim(&self) -> Option<RunSlot> {
+        self.busy
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| RunSlot { busy: self.busy.clone() })
+    }
+
+    /// claim the guard, await `ready`, then spawn `run` holding the slot until it finishes.
+    /// `ready` only starts once the claim succeeded, so two callers can't both get past it;
+    /// if it fails the slot is released and `run` never starts
+    pub async fn start<R, F>(&self, ready: R, run: F) -> Result<tokio::task::JoinHandle<()>, StartError>
+    where
+        R: Future<Output = Result<(), String>>,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let slot = self.try_claim().ok_or(StartError::Busy)?;
+        ready.await.map_err(StartError::NotReady)?;
+        Ok(tokio::spawn(async move {
+            run.await;
+            drop(slot);
+        }))
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for RunSlot {
+    fn drop(&mut self) {
+        self.busy.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_is_exclusive_until_dropped() {
+        let guard = RunGuard::default();
+        let slot = guard.try_claim().expect("first claim");
+        assert!(guard.is_busy());
+        assert!(guard.try_claim().is_none());
+        drop(slot);
+        assert!(!guard.is_busy());
+        assert!(guard.try_claim().is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_runs_do_not_both_proceed() {
+        let guard = RunGuard::default();
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let attempts: Vec<_> = (0..2)
+            .map(|_| {
+                let guard = guard.clone();
+                let barrier = barrier.clone();
+                tokio::spawn(async move {
+                    barrier.wait().await;
+                    let slot = guard.try_claim();
+                    // hold the slot the way a run would, so the other attempt overlaps it
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    slot.is_some()
+                })
+            })
+            .collect();
+
+        let mut proceeded = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap() {
+                proceeded += 1;
+            }
+        }
+        assert_eq!(proceeded, 1);
+        assert!(!guard.is_busy());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_starts_spawn_one_run() {
+        let guard = RunGuard::default();
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let start = |guard: RunGuard, runs: Arc<std::sync::atomic::AtomicUsize>| async move {
+            // a slow readiness check, like waiting for the agent lock, so both calls overlap
+            let ready = async {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                Ok(())
+            };
+            let run = async move {
+                runs.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            };
+            guard.start(ready, run).await
+        };
+
+        let (first, second) = tokio::join!(
+            tokio::spawn(start(guard.clone(), runs.clone())),
+            tokio::spawn(start(guard.clone(), runs.clone())),
+        );
+        let outcomes = [first.unwrap(), second.unwrap()];
+        assert_eq!(outcomes.iter().filter(|o| matches!(o, Err(StartError::Busy))).count(), 1);
+        for handle in outcomes.into_iter().flatten() {
+            handle.await.unwrap();
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(!guard.is_busy());
+    }
+
+    #[tokio::test]
+    async fn failed_readiness_frees_the_guard() {
+        let guard = RunGuard::default();
+        let outcome = guard.start(async { Err("no key".to_string()) }, async {}).await;
+        assert_eq!(outcome.err(), Some(StartError::NotReady("no key".to_string())));
+        assert!(!guard.is_busy());
+    }
+}
@@ -2,6 +2,7 @@
 // stores conversations in Anthropic API-compatible format for seamless replay
 
 use crate::api::{ContentBlock, Message};
+use crate::bash::BashState;
 use rusqlite::{params, Connection, Result as SqlResult};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -31,6 +32,53 @@ impl Usage {
     }
 }
 
+/// list price in $ per million (input, output) tokens for a model id
+fn model_pricing(model: &str) -> (f64, f64) {
+    if model.contains("haiku") {
+        (1.0, 5.0)
+    } else if model.contains("opus") {
+        (5.0, 25.0)
+    } else {
+        (3.0, 15.0)
+    }
+}
+
+// prompt caching multipliers on the input price
+const CACHE_WRITE_MULTIPLIER: f64 = 1.25;
+const CACHE_READ_MULTIPLIER: f64 = 0.1;
+
+/// running token / cost totals for the cost meter
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_read_tokens: u32,
+    pub cache_write_tokens: u32,
+    pub cost_usd: f64,
+    /// cost avoided by cache reads, net of the cache write premium (negative until reads pay off)
+    pub saved_usd: f64,
+}
+
+impl UsageSummary {
+    pub fn add(&mut self, usage: &Usage, model: &str) {
+        let (input_price, output_price) = model_pricing(model);
+        let per_token = |price: f64, tokens: u32| price * tokens as f64 / 1_000_000.0;
+
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cache_read_tokens += usage.cache_read_input_tokens;
+        self.cache_write_tokens += usage.cache_creation_input_tokens;
+
+        self.cost_usd += per_token(input_price, usage.input_tokens)
+            + per_token(output_price, usage.output_tokens)
+            + per_token(input_price * CACHE_WRITE_MULTIPLIER, usage.cache_creation_input_tokens)
+            + per_token(input_price * CACHE_READ_MULTIPLIER, usage.cache_read_input_tokens);
+        self.saved_usd += per_token(input_price * (1.0 - CACHE_READ_MULTIPLIER), usage.cache_read_input_tokens)
+            - per_token(input_price * (CACHE_WRITE_MULTIPLIER - 1.0), usage.cache_creation_input_tokens);
+    }
+}
+
 /// per-turn usage tracking - one entry per API call
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurnUsage {
@@ -40,6 +88,98 @@ pub struct TurnUsage {
     pub timestamp: i64,
 }
 
+/// the model in use changed before `message_index` (a mid-thread switch or a fallback)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelChange {
+    pub message_index: usize,
+    pub from: String,
+    pub to: String,
+    pub timestamp: i64,
+}
+
+/// one agent run on a conversation, from the instruction to the final save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub started_at: i64,
+    pub duration_ms: u64,
+}
+
+/// inclusive window of unix seconds for usage stats; an open end is unbounded
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+impl UsageRange {
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.from.is_none_or(|from| timestamp >= from) && self.to.is_none_or(|to| timestamp <= to)
+    }
+}
+
+/// one model's share of the usage in a range
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model: String,
+    /// api calls it served
+    pub turns: u32,
+    pub usage: UsageSummary,
+}
+
+/// aggregate spend and activity across conversations, for the usage dashboard
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    pub totals: UsageSummary,
+    /// biggest spender first
+    pub by_model: Vec<ModelUsage>,
+    /// conversations with any api call in the range
+    pub conversations: u32,
+    pub runs: u32,
+    /// None when no run in the range was timed
+    pub average_run_ms: Option<u64>,
+    pub tool_calls: std::collections::BTreeMap<String, u32>,
+}
+
+/// fold per-turn usage and run records into stats. turns and runs are each filtered by their
+/// own timestamp, so a long-lived conversation only counts what happened inside the range
+pub fn usage_stats(
+    conversations: &[(Vec<TurnUsage>, Vec<RunRecord>)],
+    tool_calls: std::collections::BTreeMap<String, u32>,
+    range: UsageRange,
+) -> UsageStats {
+    let mut stats = UsageStats { tool_calls, ..UsageStats::default() };
+    let mut by_model: std::collections::BTreeMap<&str, ModelUsage> = std::collections::BTreeMap::new();
+    let mut run_ms: u64 = 0;
+
+    for (turns, runs) in conversations {
+        let mut active = false;
+        for turn in turns.iter().filter(|turn| range.contains(turn.timestamp)) {
+            active = true;
+            stats.totals.add(&turn.usage, &turn.model);
+            let entry = by_model.entry(&turn.model).or_insert_with(|| ModelUsage {
+                model: turn.model.clone(),
+                turns: 0,
+                usage: UsageSummary::default(),
+            });
+            entry.turns += 1;
+            entry.usage.add(&turn.usage, &turn.model);
+        }
+        stats.conversations += u32::from(active);
+        for run in runs.iter().filter(|run| range.contains(run.started_at)) {
+            stats.runs += 1;
+            run_ms += run.duration_ms;
+        }
+    }
+
+    stats.average_run_ms = (stats.runs > 0).then(|| run_ms / u64::from(stats.runs));
+    stats.by_model = by_model.into_values().collect();
+    stats.by_model.sort_by(|a, b| b.usage.cost_usd.total_cmp(&a.usage.cost_usd));
+    stats
+}
+
 /// conversation metadata for listing without loading full messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationMeta {
@@ -52,6 +192,8 @@ pub struct ConversationMeta {
     pub message_count: u32,
     pub total_input_tokens: u32,
     pub total_output_tokens: u32,
+    #[serde(default)]
+    pub is_pinned: bool,
 }
 
 /// full conversation with messages
@@ -73,6 +215,31 @@ pub struct Conversation {
     /// voice mode enabled for TTS responses
     #[serde(default)]
     pub voice_mode: bool,
+    /// bash cwd + exported vars, restored when the conversation is resumed
+    #[serde(default)]
+    pub bash_state: BashState,
+    /// where the model changed mid-thread; `model` is always the latest one
+    #[serde(default)]
+    pub model_changes: Vec<ModelChange>,
+    /// pinned conversations list first and are never pruned
+    #[serde(default)]
+    pub is_pinned: bool,
+    /// set while the agent loop runs on this conversation; still set on launch means the app quit mid-run
+    #[serde(default)]
+    pub run_in_progress: bool,
+    /// every finished run, for duration stats
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+    /// wall-clock limit of the latest run in minutes (0 = unlimited), kept so resuming an
+    /// interrupted run honours the bound it started with
+    #[serde(default)]
+    pub max_run_minutes: Option<u64>,
+    /// the user renamed it - titles are never generated over a custom one
+    #[serde(default)]
+    pub title_is_custom: bool,
+    /// the heuristic title was already replaced by a model-written one
+    #[serde(default)]
+    pub title_generated: bool,
 }
 
 impl Conversation {
@@ -90,6 +257,14 @@ impl Conversation {
             total_input_tokens: 0,
             total_output_tokens: 0,
             voice_mode: false,
+            bash_state: BashState::default(),
+            model_changes: Vec::new(),
+            is_pinned: false,
+            run_in_progress: false,
+            runs: Vec::new(),
+            max_run_minutes: None,
+            title_is_custom: false,
+            title_generated: false,
         }
     }
 
@@ -104,6 +279,7 @@ impl Conversation {
             message_count: self.messages.len() as u32,
             total_input_tokens: self.total_input_tokens,
             total_output_tokens: self.total_output_tokens,
+            is_pinned: self.is_pinned,
         }
     }
 
@@ -124,6 +300,179 @@ impl Conversation {
         self.total_output_tokens += usage.output_tokens;
     }
 
+    /// note a run that just ended after `duration`
+    pub fn record_run(&mut self, duration: std::time::Duration) {
+        let duration_ms = duration.as_millis() as u64;
+        self.runs.push(RunRecord { started_at: timestamp() - (duration_ms / 1000) as i64, duration_ms });
+    }
+
+    /// the time limit for the run about to start: resuming keeps the one the run began with,
+    /// a new instruction takes the current setting. None means no limit
+    pub fn run_time_limit(&mut self, resuming: bool, setting_minutes: u64) -> Option<std::time::Duration> {
+        let minutes = match self.max_run_minutes {
+            Some(minutes) if resuming => minutes,
+            _ => setting_minutes,
+        };
+        self.max_run_minutes = Some(minutes);
+        (minutes > 0).then(|| std::time::Duration::from_secs(minutes * 60))
+    }
+
+    /// make `model` the conversation's model from the next message on.
+    /// Returns true when this is a switch mid-thread (recorded as a change point).
+    pub fn switch_model(&mut self, model: &str) -> bool {
+        if self.model == model {
+            return false;
+        }
+        let mid_thread = !self.messages.is_empty();
+        if mid_thread {
+            self.model_changes.push(ModelChange {
+                message_index: self.messages.len(),
+                from: self.model.clone(),
+                to: model.to_string(),
+                timestamp: timestamp(),
+            });
+        }
+        self.model = model.to_string();
+        mid_thread
+    }
+
+    /// model that produced (or was active for) the message at `message_index`
+    pub fn model_at(&self, message_index: usize) -> &str {
+        self.model_changes
+            .iter()
+            .find(|change| message_index < change.message_index)
+            .map(|change| change.from.as_str())
+            .unwrap_or(&self.model)
+    }
+
+    /// totals across every turn, priced per the model that served it
+    pub fn usage_summary(&self) -> UsageSummary {
+        let mut summary = UsageSummary::default();
+        for turn in &self.turn_usage {
+            summary.add(&turn.usage, &turn.model);
+        }
+        summary
+    }
+
+    /// copy of this conversation keeping only its first `up_to_message_index` messages,
+    /// under a new id. A trailing assistant turn whose tool calls lost their results is
+    /// dropped too, so the fork can be sent to the API as-is
+    pub fn fork(&self, id: String, up_to_message_index: usize) -> Result<Self, String> {
+        if up_to_message_index > self.messages.len() {
+            return Err(format!(
+                "message index {} is past the end of the conversation ({} messages)",
+                up_to_message_index,
+                self.messages.len()
+            ));
+        }
+
+        let mut messages = self.messages[..up_to_message_index].to_vec();
+        let dangling_tool_use = messages.last().is_some_and(|msg| {
+            msg.role == "assistant" && msg.content.iter().any(|b| matches!(b, ContentBlock::ToolUse { .. }))
+        });
+        if dangling_tool_use {
+            messages.pop();
+        }
+        let kept = messages.len();
+
+        // one api turn per assistant message, so the kept turns are the first `assistant_turns`
+        let assistant_turns = messages.iter().filter(|msg| msg.role == "assistant").count();
+        let turn_usage: Vec<TurnUsage> = self.turn_usage.iter().take(assistant_turns).cloned().collect();
+
+        let now = timestamp();
+        Ok(Self {
+            id,
+            title: format!("Fork of {}", self.title),
+            created_at: now,
+            updated_at: now,
+            model: self.model_at(kept.saturating_sub(1)).to_string(),
+            mode: self.mode.clone(),
+            messages,
+            total_input_tokens: turn_usage.iter().map(|turn| turn.usage.total_input()).sum(),
+            total_output_tokens: turn_usage.iter().map(|turn| turn.usage.output_tokens).sum(),
+            turn_usage,
+            voice_mode: self.voice_mode,
+            bash_state: self.bash_state.clone(),
+            model_changes: self.model_changes.iter().filter(|change| change.message_index < kept).cloned().collect(),
+            is_pinned: false,
+            run_in_progress: false,
+            // the runs belong to the original - the fork hasn't been run yet
+            runs: Vec::new(),
+            max_run_minutes: self.max_run_minutes,
+            // "Fork of ..." is new, so neither flag carries over
+            title_is_custom: false,
+            title_generated: false,
+        })
+    }
+
+    /// swap the first `split` messages for a user/assistant pair carrying `summary` - the pair
+    /// keeps the roles alternating and the tail (which starts on a user turn) untouched
+    pub fn replace_with_summary(&mut self, split: usize, summary: &str) {
+        let split = split.min(self.messages.len());
+        let note = [
+            Message {
+                role: "user".to_string(),
+                content: vec![ContentBlock::Text {
+                    text: format!("[{} earlier messages were compacted into a summary]", split),
+                }],
+            },
+            Message {
+                role: "assistant".to_string(),
+                content: vec![ContentBlock::Text {
+                    text: format!("Summary of the conversation so far:\n\n{}", summary.trim()),
+                }],
+            },
+        ];
+        self.messages.splice(..split, note);
+        // change points inside the summarized part are gone; later ones move with their messages
+        self.model_changes.retain(|change| change.message_index >= split);
+        for change in &mut self.model_changes {
+            change.message_index = change.message_index - split + 2;
+        }
+        self.updated_at = timestamp();
+    }
+
+    /// the agent loop was cut off with the user's turn (instruction or tool results) still unanswered
+    pub fn is_interrupted(&self) -> bool {
+        self.run_in_progress && self.messages.last().is_some_and(|msg| msg.role == "user")
+    }
+
+    /// whether the conversation is far enough along for a model-written title: a few messages
+    /// in, still on the heuristic title
+    pub fn wants_generated_title(&self) -> bool {
+        !self.title_is_custom && !self.title_generated && self.messages.len() >= GENERATED_TITLE_AFTER_MESSAGES
+    }
+
+    /// the text of the conversation so far, clipped, for the title prompt. tool calls and
+    /// screenshots are left out - the instructions and replies say what the task is
+    pub fn title_excerpt(&self) -> String {
+        let mut excerpt = String::new();
+        for msg in &self.messages {
+            for block in &msg.content {
+                if let ContentBlock::Text { text } = block {
+                    let text = text.trim();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    excerpt.push_str(&format!("{}: {}\n", msg.role, text));
+                }
+            }
+            if excerpt.chars().count() >= TITLE_EXCERPT_CHARS {
+                break;
+            }
+        }
+        excerpt.chars().take(TITLE_EXCERPT_CHARS).collect()
+    }
+
+    /// take a model-written title unless the user has named the conversation themselves
+    pub fn set_generated_title(&mut self, title: String) {
+        if self.title_is_custom {
+            return;
+        }
+        self.title = title;
+        self.title_generated = true;
+    }
+
     /// generate title from first user message if not set
     pub fn auto_title(&mut self) {
         if !self.title.is_empty() && self.title != "New Conversation" {
@@ -148,6 +497,41 @@ impl Conversation {
     }
 }
 
+// messages (user + assistant, tool rounds included) before a title is generated - the
+// first instruction alone often doesn't say what the task turned into
+const GENERATED_TITLE_AFTER_MESSAGES: usize = 4;
+// conversation text handed to the model for a title
+const TITLE_EXCERPT_CHARS: usize = 2000;
+// longest title kept, for renames and generated ones alike
+const MAX_TITLE_CHARS: usize = 80;
+// a generated title is meant to be 4-6 words; anything longer is a sentence, not a title
+const MAX_GENERATED_TITLE_WORDS: usize = 10;
+
+/// trim a title to what the conversation list shows
+pub fn clip_title(title: &str) -> String {
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    title.chars().take(MAX_TITLE_CHARS).collect::<String>().trim().to_string()
+}
+
+/// the title in a model reply: first line, without quotes, a "Title:" label or a final period.
+/// None when the reply isn't a usable title
+pub fn parse_generated_title(reply: &str) -> Option<String> {
+    let line = reply.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line)
+        .trim()
+        .trim_matches(|c| matches!(c, '"' | '\'' | '*' | '`' | '“' | '”'))
+        .trim_end_matches('.')
+        .trim();
+    let words = line.split_whitespace().count();
+    if words == 0 || words > MAX_GENERATED_TITLE_WORDS {
+        return None;
+    }
+    Some(clip_title(line))
+}
+
 fn timestamp() -> i64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -155,15 +539,198 @@ fn timestamp() -> i64 {
         .unwrap_or(0)
 }
 
-fn get_db_path() -> PathBuf {
+/// app data directory holding the conversations db and saved settings
+pub fn data_dir() -> PathBuf {
     #[cfg(target_os = "macos")]
     let base = dirs::data_dir();
     #[cfg(not(target_os = "macos"))]
     let base = dirs::data_local_dir();
 
-    base.unwrap_or_else(|| PathBuf::from("."))
-        .join("hey-work")
-        .join("conversations.db")
+    base.unwrap_or_else(|| PathBuf::from(".")).join("hey-work")
+}
+
+fn get_db_path() -> PathBuf {
+    data_dir().join("conversations.db")
+}
+
+/// where hey-work keeps its data on disk, for backups and bug reports
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePaths {
+    pub data_dir: String,
+    pub db_path: String,
+    /// .env file holding saved api keys and preferences
+    pub settings_path: String,
+    /// active python virtualenv the python tool runs in (None = system python3)
+    pub python_venv_path: Option<String>,
+}
+
+pub fn storage_paths() -> StoragePaths {
+    let dir = data_dir();
+    StoragePaths {
+        data_dir: dir.to_string_lossy().to_string(),
+        db_path: get_db_path().to_string_lossy().to_string(),
+        settings_path: dir.join(".env").to_string_lossy().to_string(),
+        python_venv_path: std::env::var("VIRTUAL_ENV").ok().filter(|v| !v.trim().is_empty()),
+    }
+}
+
+/// one tool invocation in a conversation's audit trail. unlike the message history (which
+/// the model sees and which gets trimmed), this is a record of what was actually done
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallEntry {
+    pub tool_name: String,
+    pub input: serde_json::Value,
+    /// text of the result, truncated to MAX_AUDIT_OUTPUT_CHARS; images show as [image]
+    pub output_summary: String,
+    /// None when only the whole step was timed (swarm subtasks)
+    pub duration_ms: Option<u64>,
+    pub success: bool,
+    pub started_at_ms: i64,
+    /// the swarm subtask that made the call, None for the main agent loop
+    pub subtask_id: Option<String>,
+}
+
+/// how much of a tool's output the audit log keeps
+pub const MAX_AUDIT_OUTPUT_CHARS: usize = 2000;
+
+const TOOL_CALLS_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tool_calls (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        conversation_id TEXT NOT NULL,
+        tool_name TEXT NOT NULL,
+        input_json TEXT NOT NULL,
+        output_summary TEXT NOT NULL,
+        duration_ms INTEGER,
+        success INTEGER NOT NULL,
+        started_at_ms INTEGER NOT NULL,
+        subtask_id TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_tool_calls_conversation ON tool_calls(conversation_id, id);
+";
+
+/// cut tool output down to what the audit log keeps, on a char boundary
+pub fn truncate_audit_output(text: &str) -> String {
+    match text.char_indices().nth(MAX_AUDIT_OUTPUT_CHARS) {
+        Some((end, _)) => format!("{}… ({} chars total)", &text[..end], text.chars().count()),
+        None => text.to_string(),
+    }
+}
+
+/// a file the agent produced in a conversation (python output, browser download), kept so a
+/// later turn can pick it up again instead of regenerating it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactEntry {
+    pub path: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// the tool that made it
+    pub tool_name: String,
+    /// the assistant message whose tool call made it
+    pub message_index: usize,
+    pub created_at: i64,
+}
+
+// most artifacts listed in the system prompt
+const MAX_PROMPT_ARTIFACTS: usize = 10;
+
+const ARTIFACTS_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS artifacts (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        conversation_id TEXT NOT NULL,
+        path TEXT NOT NULL,
+        name TEXT NOT NULL,
+        mime_type TEXT NOT NULL,
+        size_bytes INTEGER NOT NULL,
+        tool_name TEXT NOT NULL,
+        message_index INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        UNIQUE(conversation_id, path)
+    );
+";
+
+/// system prompt note listing the newest artifacts, None when there are none
+pub fn artifacts_prompt(artifacts: &[ArtifactEntry]) -> Option<String> {
+    if artifacts.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = artifacts
+        .iter()
+        .rev()
+        .take(MAX_PROMPT_ARTIFACTS)
+        .map(|artifact| format!("- {} ({}, made by {})", artifact.path, artifact.mime_type, artifact.tool_name))
+        .collect();
+    Some(format!(
+        "Files you created earlier in this conversation, newest first. When the user refers to one (\"the PDF you made\"), reuse it by path instead of generating it again:\n{}",
+        lines.join("\n")
+    ))
+}
+
+/// env vars (saved in .env): save a running conversation every N rounds or T seconds,
+/// whichever comes first - the end of a run always saves
+pub const SAVE_EVERY_ROUNDS_ENV: &str = "HEYWORK_SAVE_EVERY_ROUNDS";
+pub const SAVE_INTERVAL_SECS_ENV: &str = "HEYWORK_SAVE_INTERVAL_SECS";
+const DEFAULT_SAVE_EVERY_ROUNDS: usize = 3;
+const DEFAULT_SAVE_INTERVAL_SECS: u64 = 20;
+
+/// env var (saved in .env): longest one agent run may take, in minutes; 0 means no limit
+pub const MAX_RUN_MINUTES_ENV: &str = "HEYWORK_MAX_RUN_MINUTES";
+const DEFAULT_MAX_RUN_MINUTES: u64 = 15;
+
+/// the run time limit setting in minutes, 0 for unlimited
+pub fn max_run_minutes() -> u64 {
+    std::env::var(MAX_RUN_MINUTES_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MAX_RUN_MINUTES)
+}
+
+/// when the agent loop writes the conversation mid-run. a save rewrites every message
+/// (screenshots included), so long runs don't save on every single round
+#[derive(Debug, Clone)]
+pub struct SaveCadence {
+    every_rounds: usize,
+    interval: std::time::Duration,
+    rounds_since_save: usize,
+    last_save: std::time::Instant,
+}
+
+impl SaveCadence {
+    pub fn new(every_rounds: usize, interval: std::time::Duration) -> Self {
+        Self {
+            every_rounds: every_rounds.max(1),
+            interval,
+            rounds_since_save: 0,
+            last_save: std::time::Instant::now(),
+        }
+    }
+
+    /// defaults, overridden by HEYWORK_SAVE_EVERY_ROUNDS / HEYWORK_SAVE_INTERVAL_SECS
+    pub fn from_env() -> Self {
+        let number = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        Self::new(
+            number(SAVE_EVERY_ROUNDS_ENV).map_or(DEFAULT_SAVE_EVERY_ROUNDS, |n| n as usize),
+            std::time::Duration::from_secs(number(SAVE_INTERVAL_SECS_ENV).unwrap_or(DEFAULT_SAVE_INTERVAL_SECS)),
+        )
+    }
+
+    /// count a finished round; true when it should be saved
+    pub fn round_finished(&mut self) -> bool {
+        self.round_finished_at(std::time::Instant::now())
+    }
+
+    fn round_finished_at(&mut self, now: std::time::Instant) -> bool {
+        self.rounds_since_save += 1;
+        let due = self.rounds_since_save >= self.every_rounds || now.duration_since(self.last_save) >= self.interval;
+        if due {
+            self.rounds_since_save = 0;
+            self.last_save = now;
+        }
+        due
+    }
 }
 
 /// database singleton
@@ -181,6 +748,11 @@ pub fn init_db() -> Result<(), String> {
 
     let conn = Connection::open(&db_path).map_err(|e| format!("failed to open db: {e}"))?;
 
+    // WAL keeps a crash mid-write from corrupting the db, and synchronous=FULL fsyncs each
+    // commit so a saved round survives the app (or the machine) going down right after
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = FULL;")
+        .map_err(|e| format!("failed to configure db: {e}"))?;
+
     // create tables
     conn.execute_batch(
         "
@@ -195,16 +767,44 @@ pub fn init_db() -> Result<(), String> {
             turn_usage_json TEXT NOT NULL,
             total_input_tokens INTEGER NOT NULL DEFAULT 0,
             total_output_tokens INTEGER NOT NULL DEFAULT 0,
-            voice_mode INTEGER NOT NULL DEFAULT 0
+            voice_mode INTEGER NOT NULL DEFAULT 0,
+            bash_state_json TEXT NOT NULL DEFAULT '{}',
+            model_changes_json TEXT NOT NULL DEFAULT '[]',
+            is_pinned INTEGER NOT NULL DEFAULT 0,
+            run_in_progress INTEGER NOT NULL DEFAULT 0,
+            runs_json TEXT NOT NULL DEFAULT '[]',
+            max_run_minutes INTEGER,
+            title_is_custom INTEGER NOT NULL DEFAULT 0,
+            title_generated INTEGER NOT NULL DEFAULT 0
         );
         CREATE INDEX IF NOT EXISTS idx_conversations_updated ON conversations(updated_at DESC);
         ",
     )
     .map_err(|e| format!("failed to create tables: {e}"))?;
+    conn.execute_batch(TOOL_CALLS_SCHEMA)
+        .map_err(|e| format!("failed to create tables: {e}"))?;
+    conn.execute_batch(ARTIFACTS_SCHEMA)
+        .map_err(|e| format!("failed to create tables: {e}"))?;
 
     // migration: add voice_mode column if missing (for existing DBs)
     conn.execute("ALTER TABLE conversations ADD COLUMN voice_mode INTEGER NOT NULL DEFAULT 0", [])
         .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN bash_state_json TEXT NOT NULL DEFAULT '{}'", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN model_changes_json TEXT NOT NULL DEFAULT '[]'", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN is_pinned INTEGER NOT NULL DEFAULT 0", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN run_in_progress INTEGER NOT NULL DEFAULT 0", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN runs_json TEXT NOT NULL DEFAULT '[]'", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN max_run_minutes INTEGER", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN title_is_custom INTEGER NOT NULL DEFAULT 0", [])
+        .ok();
+    conn.execute("ALTER TABLE conversations ADD COLUMN title_generated INTEGER NOT NULL DEFAULT 0", [])
+        .ok();
 
     DB.set(Mutex::new(conn))
         .map_err(|_| "db already initialized")?;
@@ -236,18 +836,37 @@ pub fn create_conversation(title: String, model: String, mode: String) -> Result
     Ok(id)
 }
 
+/// branch a conversation at `up_to_message_index` into a new one, leaving the original untouched
+pub fn fork_conversation(id: &str, up_to_message_index: usize) -> Result<String, String> {
+    let conv = load_conversation(id)?.ok_or_else(|| format!("conversation {} not found", id))?;
+    let fork_id = uuid::Uuid::new_v4().to_string();
+    let fork = conv.fork(fork_id.clone(), up_to_message_index)?;
+    save_conversation(&fork)?;
+    println!("[storage] forked conversation {} into {} at message {}", id, fork_id, up_to_message_index);
+    Ok(fork_id)
+}
+
 /// save/update conversation
 pub fn save_conversation(conv: &Conversation) -> Result<(), String> {
     let messages_json =
         serde_json::to_string(&conv.messages).map_err(|e| format!("serialize error: {e}"))?;
     let turn_usage_json =
         serde_json::to_string(&conv.turn_usage).map_err(|e| format!("serialize error: {e}"))?;
+    let bash_state_json =
+        serde_json::to_string(&conv.bash_state).map_err(|e| format!("serialize error: {e}"))?;
+    let model_changes_json =
+        serde_json::to_string(&conv.model_changes).map_err(|e| format!("serialize error: {e}"))?;
+    let runs_json = serde_json::to_string(&conv.runs).map_err(|e| format!("serialize error: {e}"))?;
 
     with_db(|conn| {
         conn.execute(
             "INSERT OR REPLACE INTO conversations
-             (id, title, created_at, updated_at, model, mode, messages_json, turn_usage_json, total_input_tokens, total_output_tokens, voice_mode)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+             (id, title, created_at, updated_at, model, mode, messages_json, turn_usage_json, total_input_tokens, total_output_tokens, voice_mode, bash_state_json, model_changes_json, is_pinned, run_in_progress, runs_json, max_run_minutes, title_is_custom, title_generated)
+             VALUES (?1,
+                     COALESCE((SELECT title FROM conversations WHERE id = ?1 AND title_is_custom = 1), ?2),
+                     ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                     COALESCE((SELECT is_pinned FROM conversations WHERE id = ?1), ?14), ?15, ?16, ?17,
+                     COALESCE((SELECT title_is_custom FROM conversations WHERE id = ?1 AND title_is_custom = 1), ?18), ?19)",
             params![
                 conv.id,
                 conv.title,
@@ -260,6 +879,14 @@ pub fn save_conversation(conv: &Conversation) -> Result<(), String> {
                 conv.total_input_tokens,
                 conv.total_output_tokens,
                 conv.voice_mode as i32,
+                bash_state_json,
+                model_changes_json,
+                conv.is_pinned as i32,
+                conv.run_in_progress as i32,
+                runs_json,
+                conv.max_run_minutes.map(|minutes| minutes as i64),
+                conv.title_is_custom as i32,
+                conv.title_generated as i32,
             ],
         )?;
         Ok(())
@@ -269,11 +896,17 @@ pub fn save_conversation(conv: &Conversation) -> Result<(), String> {
     Ok(())
 }
 
+/// fold the WAL back into the main db file - sqlite does this on its own every 1000 pages,
+/// this also runs at the end of each agent run so the log doesn't grow across long sessions
+pub fn checkpoint() -> Result<(), String> {
+    with_db(|conn| conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);"))
+}
+
 /// load conversation by id
 pub fn load_conversation(id: &str) -> Result<Option<Conversation>, String> {
     with_db(|conn| {
         let mut stmt = conn.prepare(
-            "SELECT id, title, created_at, updated_at, model, mode, messages_json, turn_usage_json, total_input_tokens, total_output_tokens, voice_mode
+            "SELECT id, title, created_at, updated_at, model, mode, messages_json, turn_usage_json, total_input_tokens, total_output_tokens, voice_mode, bash_state_json, model_changes_json, is_pinned, run_in_progress, runs_json, max_run_minutes, title_is_custom, title_generated
              FROM conversations WHERE id = ?1",
         )?;
 
@@ -281,6 +914,14 @@ pub fn load_conversation(id: &str) -> Result<Option<Conversation>, String> {
             let messages_json: String = row.get(6)?;
             let turn_usage_json: String = row.get(7)?;
             let voice_mode_int: i32 = row.get(10)?;
+            let bash_state_json: String = row.get(11)?;
+            let model_changes_json: String = row.get(12)?;
+            let is_pinned_int: i32 = row.get(13)?;
+            let run_in_progress_int: i32 = row.get(14)?;
+            let runs_json: String = row.get(15)?;
+            let max_run_minutes: Option<i64> = row.get(16)?;
+            let title_is_custom_int: i32 = row.get(17)?;
+            let title_generated_int: i32 = row.get(18)?;
 
             Ok(Conversation {
                 id: row.get(0)?,
@@ -294,6 +935,14 @@ pub fn load_conversation(id: &str) -> Result<Option<Conversation>, String> {
                 total_input_tokens: row.get(8)?,
                 total_output_tokens: row.get(9)?,
                 voice_mode: voice_mode_int != 0,
+                bash_state: serde_json::from_str(&bash_state_json).unwrap_or_default(),
+                model_changes: serde_json::from_str(&model_changes_json).unwrap_or_default(),
+                is_pinned: is_pinned_int != 0,
+                run_in_progress: run_in_progress_int != 0,
+                runs: serde_json::from_str(&runs_json).unwrap_or_default(),
+                max_run_minutes: max_run_minutes.map(|minutes| minutes.max(0) as u64),
+                title_is_custom: title_is_custom_int != 0,
+                title_generated: title_generated_int != 0,
             })
         });
 
@@ -305,12 +954,12 @@ pub fn load_conversation(id: &str) -> Result<Option<Conversation>, String> {
     })
 }
 
-/// list conversations by recency
+/// list conversations by recency, pinned ones first
 pub fn list_conversations(limit: usize, offset: usize) -> Result<Vec<ConversationMeta>, String> {
     with_db(|conn| {
         let mut stmt = conn.prepare(
-            "SELECT id, title, created_at, updated_at, model, mode, messages_json, total_input_tokens, total_output_tokens
-             FROM conversations ORDER BY updated_at DESC LIMIT ?1 OFFSET ?2",
+            "SELECT id, title, created_at, updated_at, model, mode, messages_json, total_input_tokens, total_output_tokens, is_pinned
+             FROM conversations ORDER BY is_pinned DESC, updated_at DESC LIMIT ?1 OFFSET ?2",
         )?;
 
         let rows = stmt.query_map(params![limit as i64, offset as i64], |row| {
@@ -327,6 +976,7 @@ pub fn list_conversations(limit: usize, offset: usize) -> Result<Vec<Conversatio
                 message_count: messages.len() as u32,
                 total_input_tokens: row.get(7)?,
                 total_output_tokens: row.get(8)?,
+                is_pinned: row.get::<_, i32>(9)? != 0,
             })
         })?;
 
@@ -334,23 +984,181 @@ pub fn list_conversations(limit: usize, offset: usize) -> Result<Vec<Conversatio
     })
 }
 
+/// most recent conversation the app quit in the middle of, if any - only meaningful at startup,
+/// before a new run sets the marker again
+pub fn find_interrupted_conversation() -> Result<Option<ConversationMeta>, String> {
+    let ids: Vec<String> = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT id FROM conversations WHERE run_in_progress = 1 ORDER BY updated_at DESC")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<Result<Vec<_>, _>>()
+    })?;
+
+    for id in ids {
+        match load_conversation(&id)? {
+            Some(conv) if conv.is_interrupted() => return Ok(Some(conv.to_meta())),
+            // finished with an answer after all - nothing to resume
+            Some(_) => set_run_in_progress(&id, false)?,
+            None => {}
+        }
+    }
+    Ok(None)
+}
+
+/// set or clear the interrupted-run marker (clearing declines the resume offer)
+pub fn set_run_in_progress(id: &str, in_progress: bool) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE conversations SET run_in_progress = ?1 WHERE id = ?2",
+            params![in_progress as i32, id],
+        )?;
+        Ok(())
+    })
+}
+
 /// delete conversation
 pub fn delete_conversation(id: &str) -> Result<(), String> {
     with_db(|conn| {
         conn.execute("DELETE FROM conversations WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM tool_calls WHERE conversation_id = ?1", params![id])?;
+        conn.execute("DELETE FROM artifacts WHERE conversation_id = ?1", params![id])?;
         Ok(())
     })?;
     println!("[storage] deleted conversation {}", id);
     Ok(())
 }
 
+/// append tool calls to a conversation's audit trail
+pub fn record_tool_calls(conversation_id: &str, entries: &[ToolCallEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    with_db(|conn| insert_tool_calls(conn, conversation_id, entries))
+}
+
+/// every tool call made in a conversation, oldest first
+pub fn get_tool_audit(conversation_id: &str) -> Result<Vec<ToolCallEntry>, String> {
+    with_db(|conn| query_tool_audit(conn, conversation_id))
+}
+
+fn insert_tool_calls(conn: &Connection, conversation_id: &str, entries: &[ToolCallEntry]) -> SqlResult<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO tool_calls
+         (conversation_id, tool_name, input_json, output_summary, duration_ms, success, started_at_ms, subtask_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    for entry in entries {
+        stmt.execute(params![
+            conversation_id,
+            entry.tool_name,
+            entry.input.to_string(),
+            truncate_audit_output(&entry.output_summary),
+            entry.duration_ms.map(|ms| ms as i64),
+            entry.success as i32,
+            entry.started_at_ms,
+            entry.subtask_id,
+        ])?;
+    }
+    Ok(())
+}
+
+fn query_tool_audit(conn: &Connection, conversation_id: &str) -> SqlResult<Vec<ToolCallEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT tool_name, input_json, output_summary, duration_ms, success, started_at_ms, subtask_id
+         FROM tool_calls WHERE conversation_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![conversation_id], |row| {
+        let input_json: String = row.get(1)?;
+        Ok(ToolCallEntry {
+            tool_name: row.get(0)?,
+            input: serde_json::from_str(&input_json).unwrap_or(serde_json::Value::Null),
+            output_summary: row.get(2)?,
+            duration_ms: row.get::<_, Option<i64>>(3)?.map(|ms| ms as u64),
+            success: row.get::<_, i32>(4)? != 0,
+            started_at_ms: row.get(5)?,
+            subtask_id: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// remember a file the agent made; the same path made again moves to the newest entry
+pub fn record_artifact(conversation_id: &str, file: &crate::files::CreatedFile, tool_name: &str, message_index: usize) -> Result<(), String> {
+    let artifact = ArtifactEntry {
+        path: file.path.clone(),
+        name: file.name.clone(),
+        mime_type: file.mime_type.clone(),
+        size_bytes: file.size_bytes,
+        tool_name: tool_name.to_string(),
+        message_index,
+        created_at: timestamp(),
+    };
+    with_db(|conn| insert_artifact(conn, conversation_id, &artifact))
+}
+
+/// the conversation's artifacts, oldest first. entries whose file is gone are dropped from the registry
+pub fn list_artifacts(conversation_id: &str) -> Result<Vec<ArtifactEntry>, String> {
+    let (present, missing): (Vec<_>, Vec<_>) = with_db(|conn| query_artifacts(conn, conversation_id))?
+        .into_iter()
+        .partition(|artifact| std::path::Path::new(&artifact.path).is_file());
+    if !missing.is_empty() {
+        with_db(|conn| delete_artifacts(conn, conversation_id, &missing))?;
+    }
+    Ok(present)
+}
+
+fn insert_artifact(conn: &Connection, conversation_id: &str, artifact: &ArtifactEntry) -> SqlResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO artifacts
+         (conversation_id, path, name, mime_type, size_bytes, tool_name, message_index, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            conversation_id,
+            artifact.path,
+            artifact.name,
+            artifact.mime_type,
+            artifact.size_bytes as i64,
+            artifact.tool_name,
+            artifact.message_index as i64,
+            artifact.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+fn query_artifacts(conn: &Connection, conversation_id: &str) -> SqlResult<Vec<ArtifactEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT path, name, mime_type, size_bytes, tool_name, message_index, created_at
+         FROM artifacts WHERE conversation_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![conversation_id], |row| {
+        Ok(ArtifactEntry {
+            path: row.get(0)?,
+            name: row.get(1)?,
+            mime_type: row.get(2)?,
+            size_bytes: row.get::<_, i64>(3)? as u64,
+            tool_name: row.get(4)?,
+            message_index: row.get::<_, i64>(5)? as usize,
+            created_at: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+fn delete_artifacts(conn: &Connection, conversation_id: &str, artifacts: &[ArtifactEntry]) -> SqlResult<()> {
+    let mut stmt = conn.prepare("DELETE FROM artifacts WHERE conversation_id = ?1 AND path = ?2")?;
+    for artifact in artifacts {
+        stmt.execute(params![conversation_id, artifact.path])?;
+    }
+    Ok(())
+}
+
 /// search conversations by title
 pub fn search_conversations(query: &str, limit: usize) -> Result<Vec<ConversationMeta>, String> {
     let pattern = format!("%{}%", query);
 
     with_db(|conn| {
         let mut stmt = conn.prepare(
-            "SELECT id, title, created_at, updated_at, model, mode, messages_json, total_input_tokens, total_output_tokens
+            "SELECT id, title, created_at, updated_at, model, mode, messages_json, total_input_tokens, total_output_tokens, is_pinned
              FROM conversations WHERE title LIKE ?1 ORDER BY updated_at DESC LIMIT ?2",
         )?;
 
@@ -368,6 +1176,7 @@ pub fn search_conversations(query: &str, limit: usize) -> Result<Vec<Conversatio
                 message_count: messages.len() as u32,
                 total_input_tokens: row.get(7)?,
                 total_output_tokens: row.get(8)?,
+                is_pinned: row.get::<_, i32>(9)? != 0,
             })
         })?;
 
@@ -390,6 +1199,38 @@ pub fn get_total_usage() -> Result<(u32, u32), String> {
     })
 }
 
+/// spend, runs and tool calls across every conversation within `range`
+pub fn get_usage_stats(range: UsageRange) -> Result<UsageStats, String> {
+    let conversations = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT turn_usage_json, runs_json FROM conversations")?;
+        let rows = stmt.query_map([], |row| {
+            let turn_usage_json: String = row.get(0)?;
+            let runs_json: String = row.get(1)?;
+            Ok((
+                serde_json::from_str(&turn_usage_json).unwrap_or_default(),
+                serde_json::from_str(&runs_json).unwrap_or_default(),
+            ))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    })?;
+    let tool_calls = with_db(|conn| count_tool_calls(conn, range))?;
+    Ok(usage_stats(&conversations, tool_calls, range))
+}
+
+fn count_tool_calls(conn: &Connection, range: UsageRange) -> SqlResult<std::collections::BTreeMap<String, u32>> {
+    let mut stmt = conn.prepare(
+        "SELECT tool_name, COUNT(*) FROM tool_calls
+         WHERE started_at_ms >= ?1 AND started_at_ms <= ?2 GROUP BY tool_name",
+    )?;
+    let from_ms = range.from.map_or(i64::MIN, |from| from.saturating_mul(1000));
+    // through the last millisecond of the `to` second
+    let to_ms = range.to.map_or(i64::MAX, |to| to.saturating_mul(1000).saturating_add(999));
+    let rows = stmt.query_map(params![from_ms, to_ms], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u32))
+    })?;
+    rows.collect()
+}
+
 /// count total conversations
 pub fn count_conversations() -> Result<u32, String> {
     with_db(|conn| {
@@ -414,6 +1255,65 @@ pub fn set_conversation_voice_mode(id: &str, voice_mode: bool) -> Result<(), Str
     Ok(())
 }
 
+/// mode of the most recently updated conversation, regardless of pins
+pub fn last_active_mode() -> Result<Option<String>, String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT mode FROM conversations ORDER BY updated_at DESC LIMIT 1")?;
+        match stmt.query_row([], |row| row.get(0)) {
+            Ok(mode) => Ok(Some(mode)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    })
+}
+
+/// give a conversation the user's own title. saves keep a custom title, so a run still in
+/// progress or a later generated title can't undo the rename. returns the title as stored
+pub fn rename_conversation(id: &str, title: &str) -> Result<String, String> {
+    let title = clip_title(title);
+    if title.is_empty() {
+        return Err("Title can't be empty".to_string());
+    }
+    let updated = with_db(|conn| {
+        conn.execute(
+            "UPDATE conversations SET title = ?1, title_is_custom = 1 WHERE id = ?2",
+            params![title, id],
+        )
+    })?;
+    if updated == 0 {
+        return Err(format!("Conversation {} not found", id));
+    }
+    println!("[storage] renamed conversation {} to \"{}\"", id, title);
+    Ok(title)
+}
+
+/// record the call that wrote a title, after the run it titles has been saved. the usage is
+/// kept either way; the title only if the user hasn't renamed the conversation meanwhile.
+/// returns the title as stored when it was taken
+pub fn record_generated_title(id: &str, title: Option<String>, usage: Usage, model: &str) -> Result<Option<String>, String> {
+    let mut conv = load_conversation(id)?.ok_or_else(|| format!("Conversation {} not found", id))?;
+    conv.add_usage(usage, model);
+    let title = title.filter(|_| !conv.title_is_custom);
+    if let Some(title) = &title {
+        conv.set_generated_title(title.clone());
+    }
+    save_conversation(&conv)?;
+    Ok(title)
+}
+
+/// pin or unpin a conversation - the flag is only ever changed here, saves keep it
+pub fn set_conversation_pinned(id: &str, pinned: bool) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE conversations SET is_pinned = ?1 WHERE id = ?2",
+            params![pinned as i32, id],
+        )?;
+        Ok(())
+    })?;
+    println!("[storage] set is_pinned={} for conversation {}", pinned, id);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,6 +1330,26 @@ mod tests {
         assert_eq!(usage.total(), 350);
     }
 
+    #[test]
+    fn test_usage_summary_counts_cache_savings() {
+        let mut conv = Conversation::new(
+            "test".to_string(),
+            "New Conversation".to_string(),
+            "claude-sonnet-4-5".to_string(),
+            "computer".to_string(),
+        );
+        // first turn writes 10k tokens to cache, second reads them back
+        conv.add_usage(Usage { input_tokens: 1000, output_tokens: 0, cache_creation_input_tokens: 10_000, cache_read_input_tokens: 0 }, "claude-sonnet-4-5");
+        conv.add_usage(Usage { input_tokens: 1000, output_tokens: 0, cache_creation_input_tokens: 0, cache_read_input_tokens: 10_000 }, "claude-sonnet-4-5");
+
+        let summary = conv.usage_summary();
+        assert_eq!(summary.cache_read_tokens, 10_000);
+        assert_eq!(summary.cache_write_tokens, 10_000);
+        // uncached would be 22k * $3/M = $0.066; cached costs 2k*3 + 10k*3.75 + 10k*0.3 = $0.0465
+        assert!((summary.cost_usd - 0.0465).abs() < 1e-9);
+        assert!((summary.saved_usd - 0.0195).abs() < 1e-9);
+    }
+
     #[test]
     fn test_conversation_auto_title() {
         let mut conv = Conversation::new(
@@ -449,6 +1369,297 @@ mod tests {
         conv.auto_title();
         assert_eq!(conv.title, "Hello, can you help me with something?");
     }
+
+    #[test]
+    fn test_generated_title_never_replaces_a_rename() {
+        assert_eq!(parse_generated_title("Title: \"Book Lisbon flights for May.\"\n").as_deref(), Some("Book Lisbon flights for May"));
+        assert_eq!(parse_generated_title("  \n**Clean up Downloads folder**"), Some("Clean up Downloads folder".to_string()));
+        assert_eq!(parse_generated_title("Sure! Here is a short title that describes what the user was doing today"), None);
+        assert_eq!(parse_generated_title(""), None);
+
+        let mut conv = Conversation::new(
+            "test".to_string(),
+            "New Conversation".to_string(),
+            "claude-sonnet".to_string(),
+            "computer".to_string(),
+        );
+        let msg = |role: &str| Message { role: role.to_string(), content: vec![ContentBlock::Text { text: "x".to_string() }] };
+        conv.add_message(msg("user"));
+        conv.add_message(msg("assistant"));
+        assert!(!conv.wants_generated_title());
+        conv.add_message(msg("user"));
+        conv.add_message(msg("assistant"));
+        assert!(conv.wants_generated_title());
+        assert_eq!(conv.title_excerpt(), "user: x\nassistant: x\nuser: x\nassistant: x\n");
+
+        conv.title_is_custom = true;
+        conv.title = "Trip".to_string();
+        assert!(!conv.wants_generated_title());
+        conv.set_generated_title("Plan a trip".to_string());
+        assert_eq!(conv.title, "Trip");
+    }
+
+    #[test]
+    fn test_model_switch_points() {
+        let mut conv = Conversation::new(
+            "test".to_string(),
+            "New Conversation".to_string(),
+            "claude-haiku-4-5-20251001".to_string(),
+            "computer".to_string(),
+        );
+        // picking a model before anything was said isn't a switch
+        assert!(!conv.switch_model("claude-sonnet-4-5"));
+        assert!(conv.model_changes.is_empty());
+
+        let text = |t: &str| Message { role: "user".to_string(), content: vec![ContentBlock::Text { text: t.to_string() }] };
+        conv.add_message(text("hi"));
+        conv.add_message(text("hello"));
+        assert!(!conv.switch_model("claude-sonnet-4-5"));
+        assert!(conv.switch_model("claude-opus-4-5"));
+        conv.add_message(text("hard step"));
+
+        assert_eq!(conv.model_changes.len(), 1);
+        assert_eq!(conv.model_at(1), "claude-sonnet-4-5");
+        assert_eq!(conv.model_at(2), "claude-opus-4-5");
+        assert_eq!(conv.model, "claude-opus-4-5");
+    }
+
+    #[test]
+    fn test_fork_truncates_and_recounts_usage() {
+        let mut conv = Conversation::new(
+            "orig".to_string(),
+            "Plan trip".to_string(),
+            "claude-sonnet-4-5".to_string(),
+            "computer".to_string(),
+        );
+        let msg = |role: &str, t: &str| Message { role: role.to_string(), content: vec![ContentBlock::Text { text: t.to_string() }] };
+        let usage = |input: u32, output: u32| Usage { input_tokens: input, output_tokens: output, cache_creation_input_tokens: 0, cache_read_input_tokens: 0 };
+        conv.add_message(msg("user", "plan a trip"));
+        conv.add_message(msg("assistant", "where to?"));
+        conv.add_usage(usage(100, 10), "claude-sonnet-4-5");
+        conv.switch_model("claude-opus-4-5");
+        conv.add_message(msg("user", "lisbon"));
+        conv.add_message(msg("assistant", "booked"));
+        conv.add_usage(usage(300, 30), "claude-opus-4-5");
+
+        let fork = conv.fork("fork".to_string(), 2).unwrap();
+        assert_eq!(fork.id, "fork");
+        assert_eq!(fork.title, "Fork of Plan trip");
+        assert_eq!(fork.messages.len(), 2);
+        assert_eq!(fork.turn_usage.len(), 1);
+        assert_eq!((fork.total_input_tokens, fork.total_output_tokens), (100, 10));
+        assert!(fork.model_changes.is_empty());
+        assert_eq!(fork.model, "claude-sonnet-4-5");
+        // the original is untouched
+        assert_eq!(conv.messages.len(), 4);
+        assert_eq!(conv.total_input_tokens, 400);
+
+        assert!(conv.fork("fork".to_string(), 5).is_err());
+    }
+
+    #[test]
+    fn test_interrupted_needs_marker_and_unanswered_user_turn() {
+        let mut conv = Conversation::new(
+            "test".to_string(),
+            "New Conversation".to_string(),
+            "claude-sonnet-4-5".to_string(),
+            "computer".to_string(),
+        );
+        let msg = |role: &str| Message { role: role.to_string(), content: vec![ContentBlock::Text { text: "x".to_string() }] };
+        conv.add_message(msg("user"));
+        assert!(!conv.is_interrupted());
+
+        conv.run_in_progress = true;
+        assert!(conv.is_interrupted());
+
+        conv.add_message(msg("assistant"));
+        assert!(!conv.is_interrupted());
+    }
+
+    #[test]
+    fn test_resumed_run_keeps_its_time_limit() {
+        let mut conv = Conversation::new(
+            "test".to_string(),
+            "New Conversation".to_string(),
+            "claude-sonnet-4-5".to_string(),
+            "computer".to_string(),
+        );
+        assert_eq!(conv.run_time_limit(false, 15), Some(std::time::Duration::from_secs(900)));
+        assert_eq!(conv.max_run_minutes, Some(15));
+        // the setting changed while the run was interrupted
+        assert_eq!(conv.run_time_limit(true, 5), Some(std::time::Duration::from_secs(900)));
+        assert_eq!(conv.run_time_limit(false, 0), None);
+        assert_eq!(conv.max_run_minutes, Some(0));
+    }
+
+    #[test]
+    fn test_fork_drops_dangling_tool_use() {
+        let mut conv = Conversation::new(
+            "orig".to_string(),
+            "New Conversation".to_string(),
+            "claude-sonnet-4-5".to_string(),
+            "computer".to_string(),
+        );
+        conv.add_message(Message { role: "user".to_string(), content: vec![ContentBlock::Text { text: "ls".to_string() }] });
+        conv.add_message(Message {
+            role: "assistant".to_string(),
+            content: vec![ContentBlock::ToolUse { id: "t1".to_string(), name: "bash".to_string(), input: serde_json::json!({}) }],
+        });
+
+        let fork = conv.fork("fork".to_string(), 2).unwrap();
+        assert_eq!(fork.messages.len(), 1);
+    }
+
+    fn tool_call(name: &str, output: &str) -> ToolCallEntry {
+        ToolCallEntry {
+            tool_name: name.to_string(),
+            input: serde_json::json!({ "command": "ls" }),
+            output_summary: output.to_string(),
+            duration_ms: Some(12),
+            success: true,
+            started_at_ms: 1_700_000_000_000,
+            subtask_id: None,
+        }
+    }
+
+    #[test]
+    fn summary_replaces_the_head_and_shifts_model_changes() {
+        let mut conv = Conversation::new("c".into(), "t".into(), "m1".into(), "computer".into());
+        for i in 0..6 {
+            let role = if i % 2 == 0 { "user" } else { "assistant" };
+            conv.add_message(Message { role: role.into(), content: vec![ContentBlock::Text { text: i.to_string() }] });
+        }
+        conv.model_changes = vec![
+            ModelChange { message_index: 1, from: "m0".into(), to: "m1".into(), timestamp: 0 },
+            ModelChange { message_index: 5, from: "m1".into(), to: "m2".into(), timestamp: 0 },
+        ];
+
+        conv.replace_with_summary(4, "did things");
+        assert_eq!(conv.messages.len(), 4);
+        assert_eq!(conv.messages[0].role, "user");
+        assert!(matches!(&conv.messages[1].content[0], ContentBlock::Text { text } if text.ends_with("did things")));
+        assert!(matches!(&conv.messages[2].content[0], ContentBlock::Text { text } if text == "4"));
+        assert_eq!(conv.model_changes.len(), 1);
+        assert_eq!(conv.model_changes[0].message_index, 3);
+    }
+
+    #[test]
+    fn saves_every_n_rounds_or_after_the_interval() {
+        let start = std::time::Instant::now();
+        let mut cadence = SaveCadence::new(3, std::time::Duration::from_secs(30));
+        cadence.last_save = start;
+        let at = |secs| start + std::time::Duration::from_secs(secs);
+        assert!(!cadence.round_finished_at(at(1)));
+        assert!(!cadence.round_finished_at(at(2)));
+        assert!(cadence.round_finished_at(at(3)));
+        // a slow round is saved on time even before the count comes up
+        assert!(!cadence.round_finished_at(at(10)));
+        assert!(cadence.round_finished_at(at(40)));
+        assert!(!cadence.round_finished_at(at(41)));
+    }
+
+    #[test]
+    fn tool_audit_round_trips_in_order_per_conversation() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(TOOL_CALLS_SCHEMA).unwrap();
+        insert_tool_calls(&conn, "a", &[tool_call("bash", "file.txt"), tool_call("computer", "[image]")]).unwrap();
+        insert_tool_calls(&conn, "b", &[tool_call("bash", "other")]).unwrap();
+
+        let audit = query_tool_audit(&conn, "a").unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0], tool_call("bash", "file.txt"));
+        assert_eq!(audit[1].tool_name, "computer");
+        assert!(query_tool_audit(&conn, "missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn usage_stats_count_only_the_range() {
+        let usage = |input: u32| Usage { input_tokens: input, output_tokens: 0, cache_creation_input_tokens: 0, cache_read_input_tokens: 0 };
+        let turn = |model: &str, input: u32, timestamp: i64| TurnUsage { turn_index: 0, usage: usage(input), model: model.to_string(), timestamp };
+        let run = |started_at: i64, duration_ms: u64| RunRecord { started_at, duration_ms };
+        let conversations = vec![
+            (
+                vec![turn("claude-haiku-4-5", 1_000_000, 100), turn("claude-opus-4-5", 1_000_000, 200)],
+                vec![run(100, 4000), run(200, 2000)],
+            ),
+            // entirely before the range
+            (vec![turn("claude-opus-4-5", 1_000_000, 10)], vec![run(10, 9000)]),
+        ];
+        let tools = std::collections::BTreeMap::from([("bash".to_string(), 3)]);
+
+        let stats = usage_stats(&conversations, tools, UsageRange { from: Some(50), to: None });
+        assert_eq!(stats.conversations, 1);
+        assert_eq!((stats.runs, stats.average_run_ms), (2, Some(3000)));
+        assert_eq!(stats.totals.input_tokens, 2_000_000);
+        assert!((stats.totals.cost_usd - 6.0).abs() < 1e-9);
+        let models: Vec<(&str, u32)> = stats.by_model.iter().map(|m| (m.model.as_str(), m.turns)).collect();
+        assert_eq!(models, vec![("claude-opus-4-5", 1), ("claude-haiku-4-5", 1)]);
+        assert_eq!(stats.tool_calls["bash"], 3);
+
+        let empty = usage_stats(&conversations, Default::default(), UsageRange { from: Some(1000), to: Some(2000) });
+        assert_eq!((empty.conversations, empty.runs, empty.average_run_ms), (0, 0, None));
+    }
+
+    #[test]
+    fn tool_calls_are_counted_by_start_time() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(TOOL_CALLS_SCHEMA).unwrap();
+        insert_tool_calls(&conn, "a", &[tool_call("bash", "x"), tool_call("bash", "y"), tool_call("computer", "z")]).unwrap();
+
+        let all = count_tool_calls(&conn, UsageRange::default()).unwrap();
+        assert_eq!((all["bash"], all["computer"]), (2, 1));
+        // tool_call() starts at 1_700_000_000 seconds
+        let inside = count_tool_calls(&conn, UsageRange { from: None, to: Some(1_700_000_000) }).unwrap();
+        assert_eq!(inside.len(), 2);
+        assert!(count_tool_calls(&conn, UsageRange { from: Some(1_700_000_001), to: None }).unwrap().is_empty());
+    }
+
+    fn artifact(path: &str, message_index: usize) -> ArtifactEntry {
+        ArtifactEntry {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            mime_type: "application/pdf".to_string(),
+            size_bytes: 2048,
+            tool_name: "python".to_string(),
+            message_index,
+            created_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn artifacts_are_kept_per_conversation_and_remade_files_move_last() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(ARTIFACTS_SCHEMA).unwrap();
+        insert_artifact(&conn, "a", &artifact("/out/report.pdf", 3)).unwrap();
+        insert_artifact(&conn, "a", &artifact("/out/chart.png", 5)).unwrap();
+        insert_artifact(&conn, "b", &artifact("/out/other.pdf", 1)).unwrap();
+        // regenerated later in the same conversation
+        insert_artifact(&conn, "a", &artifact("/out/report.pdf", 9)).unwrap();
+
+        let listed = query_artifacts(&conn, "a").unwrap();
+        assert_eq!(listed, vec![artifact("/out/chart.png", 5), artifact("/out/report.pdf", 9)]);
+
+        delete_artifacts(&conn, "a", &listed[..1]).unwrap();
+        assert_eq!(query_artifacts(&conn, "a").unwrap().len(), 1);
+        assert_eq!(query_artifacts(&conn, "b").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn artifacts_prompt_lists_newest_first() {
+        assert_eq!(artifacts_prompt(&[]), None);
+        let prompt = artifacts_prompt(&[artifact("/out/a.pdf", 1), artifact("/out/b.pdf", 2)]).unwrap();
+        let lines: Vec<&str> = prompt.lines().skip(1).collect();
+        assert_eq!(lines, vec!["- /out/b.pdf (application/pdf, made by python)", "- /out/a.pdf (application/pdf, made by python)"]);
+    }
+
+    #[test]
+    fn audit_output_is_truncated_on_char_boundaries() {
+        let long = "é".repeat(MAX_AUDIT_OUTPUT_CHARS + 10);
+        let cut = truncate_audit_output(&long);
+        assert!(cut.starts_with(&"é".repeat(MAX_AUDIT_OUTPUT_CHARS)));
+        assert!(cut.ends_with(&format!("({} chars total)", MAX_AUDIT_OUTPUT_CHARS + 10)));
+        assert_eq!(truncate_audit_output("short"), "short");
+    }
 }
 
 // Rust guideline compliant 2025-12-29
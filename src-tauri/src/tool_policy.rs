@@ -0,0 +1,136 @@
+// which tools the user lets the agent use. a disabled tool isn't offered to the model, isn't
+// described in the system prompt, and is refused if the model calls it anyway
+
+use std::collections::BTreeSet;
+
+/// env var (saved in .env): comma-separated toggles the user switched off. storing the
+/// disabled ones keeps everything on by default, including tools added later
+pub const DISABLED_TOOLS_ENV: &str = "HEYWORK_DISABLED_TOOLS";
+
+/// what the user can switch off, in settings order
+pub const TOOL_TOGGLES: &[&str] = &["computer", "browser", "bash", "python", "web_search", "deep_research"];
+
+/// tool result for a call to a disabled tool
+pub const DISABLED_TOOL_MESSAGE: &str = "This tool is disabled by the user. Don't call it again; do the task another way or tell the user it's turned off in Settings.";
+
+// the screen tools that come with computer control
+const COMPUTER_TOOLS: &[&str] = &[
+    "computer",
+    "zoom_to_text",
+    "click_text",
+    "wait_for_change",
+    "list_windows",
+    "get_active_app",
+    "observe_ui",
+    "focus_window",
+    "set_window_bounds",
+    "snap_window",
+    "launch",
+];
+const BROWSER_TOOLS: &[&str] = &["see_page", "page_action", "browser_navigate", "browser_download", "autofill_login"];
+
+/// the toggle a tool belongs to; None for tools that are always on (speak)
+pub fn toggle_for(tool: &str) -> Option<&'static str> {
+    if COMPUTER_TOOLS.contains(&tool) {
+        Some("computer")
+    } else if BROWSER_TOOLS.contains(&tool) {
+        Some("browser")
+    } else {
+        TOOL_TOGGLES.iter().copied().find(|toggle| *toggle == tool)
+    }
+}
+
+/// refuse a disabled tool on paths that run tools outside the agent loop (skills, the swarm,
+/// self-correction), so a switched-off tool stays off whichever way it's reached
+pub fn ensure_allowed(tool: &str) -> Result<(), String> {
+    if ToolPolicy::current().allows(tool) {
+        Ok(())
+    } else {
+        Err(format!("the {} tool is disabled by the user in Settings", tool))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolPolicy {
+    disabled: BTreeSet<String>,
+}
+
+impl ToolPolicy {
+    /// parse the saved list, rejecting names that aren't toggles
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut disabled = BTreeSet::new();
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            if !TOOL_TOGGLES.contains(&name) {
+                return Err(format!("Unknown tool {}", name));
+            }
+            disabled.insert(name.to_string());
+        }
+        Ok(Self { disabled })
+    }
+
+    /// everything on except the listed toggles
+    pub fn with_enabled(enabled: &[String]) -> Result<Self, String> {
+        if let Some(unknown) = enabled.iter().find(|name| !TOOL_TOGGLES.contains(&name.as_str())) {
+            return Err(format!("Unknown tool {}", unknown));
+        }
+        let disabled = TOOL_TOGGLES
+            .iter()
+            .filter(|toggle| !enabled.iter().any(|name| name == *toggle))
+            .map(|toggle| toggle.to_string())
+            .collect();
+        Ok(Self { disabled })
+    }
+
+    /// the saved policy; an unreadable value leaves everything on
+    pub fn current() -> Self {
+        std::env::var(DISABLED_TOOLS_ENV)
+            .ok()
+            .and_then(|value| Self::parse(&value).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn allows(&self, tool: &str) -> bool {
+        toggle_for(tool).is_none_or(|toggle| !self.disabled.contains(toggle))
+    }
+
+    pub fn enabled(&self) -> Vec<String> {
+        TOOL_TOGGLES
+            .iter()
+            .filter(|toggle| !self.disabled.contains(**toggle))
+            .map(|toggle| toggle.to_string())
+            .collect()
+    }
+
+    /// the value saved in .env
+    pub fn to_env_value(&self) -> String {
+        self.disabled.iter().cloned().collect::<Vec<_>>().join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tools_follow_their_toggle() {
+        let policy = ToolPolicy::parse("bash, computer").unwrap();
+        assert!(!policy.allows("bash"));
+        assert!(!policy.allows("click_text"));
+        assert!(policy.allows("see_page"));
+        assert!(policy.allows("python"));
+        // speak has no toggle
+        assert!(policy.allows("speak"));
+        assert!(ToolPolicy::default().allows("launch"));
+    }
+
+    #[test]
+    fn enabled_list_round_trips_through_the_env_value() {
+        let policy = ToolPolicy::with_enabled(&["computer".to_string(), "bash".to_string()]).unwrap();
+        assert_eq!(policy.enabled(), vec!["computer".to_string(), "bash".to_string()]);
+        assert_eq!(policy.to_env_value(), "browser,deep_research,python,web_search");
+        assert_eq!(ToolPolicy::parse(&policy.to_env_value()).unwrap(), policy);
+        assert!(ToolPolicy::parse("bash,telnet").is_err());
+        assert!(ToolPolicy::with_enabled(&["speak".to_string()]).is_err());
+        assert_eq!(ToolPolicy::parse("").unwrap().enabled().len(), TOOL_TOGGLES.len());
+    }
+}
@@ -83,8 +83,29 @@ impl TtsClient {
     }
 }
 
+/// env var (saved in .env): speak the final answer of a voice-mode run even when the model didn't call speak
+pub const AUTO_SPEAK_FINAL_ENV: &str = "HEYWORK_AUTO_SPEAK_FINAL";
+
+pub fn auto_speak_final_enabled() -> bool {
+    std::env::var(AUTO_SPEAK_FINAL_ENV)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// what an utterance sounds like, for telling whether the final answer was already spoken:
+/// markdown markers and case dropped, whitespace collapsed
+pub fn normalize_utterance(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '#' | '>'))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
 pub fn create_tts_client() -> Option<TtsClient> {
-    let api_key = std::env::var("ELEVENLABS_API_KEY").ok()?;
+    let api_key = crate::permissions::load_api_key_for_service("elevenlabs")?;
     let voice_id = std::env::var("ELEVENLABS_VOICE_ID")
         .unwrap_or_else(|_| "NOpBlnGInO9m6vDvFkFC".to_string());
     Some(TtsClient::new(api_key, voice_id))
@@ -94,23 +115,157 @@ pub fn create_tts_client() -> Option<TtsClient> {
 // Deepgram STT - simple approach
 // ============================================================================
 
+/// env var (saved in .env) holding the BCP-47 tag voice mode listens and answers in
+pub const VOICE_LANGUAGE_ENV: &str = "HEYWORK_VOICE_LANGUAGE";
+
+/// deepgram's auto-detect setting, used when no language is configured
+pub const AUTO_LANGUAGE: &str = "multi";
+
+/// languages deepgram's nova-3 model transcribes: (tag, name the model is told to answer in)
+pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[
+    ("multi", "the user's language"),
+    ("en", "English"),
+    ("en-US", "English"),
+    ("en-GB", "English"),
+    ("en-AU", "English"),
+    ("en-IN", "English"),
+    ("en-NZ", "English"),
+    ("es", "Spanish"),
+    ("es-419", "Spanish"),
+    ("fr", "French"),
+    ("fr-CA", "French"),
+    ("de", "German"),
+    ("de-CH", "German"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+    ("pt-BR", "Portuguese"),
+    ("pt-PT", "Portuguese"),
+    ("nl", "Dutch"),
+    ("nl-BE", "Flemish"),
+    ("sv", "Swedish"),
+    ("da", "Danish"),
+    ("no", "Norwegian"),
+    ("fi", "Finnish"),
+    ("pl", "Polish"),
+    ("cs", "Czech"),
+    ("sk", "Slovak"),
+    ("hu", "Hungarian"),
+    ("ro", "Romanian"),
+    ("bg", "Bulgarian"),
+    ("el", "Greek"),
+    ("uk", "Ukrainian"),
+    ("ru", "Russian"),
+    ("tr", "Turkish"),
+    ("hi", "Hindi"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("vi", "Vietnamese"),
+    ("id", "Indonesian"),
+];
+
+/// canonical form of a supported tag ("EN_us" -> "en-US"), or an error naming the tag
+pub fn validate_language(tag: &str) -> Result<&'static str, String> {
+    let wanted = tag.trim().replace('_', "-");
+    SUPPORTED_LANGUAGES
+        .iter()
+        .find(|(supported, _)| supported.eq_ignore_ascii_case(&wanted))
+        .map(|(supported, _)| *supported)
+        .ok_or_else(|| format!("Deepgram doesn't support the language '{}' for transcription", tag.trim()))
+}
+
+/// the configured voice language, or None to auto-detect
+pub fn voice_language() -> Option<&'static str> {
+    std::env::var(VOICE_LANGUAGE_ENV)
+        .ok()
+        .and_then(|tag| validate_language(&tag).ok())
+        .filter(|tag| *tag != AUTO_LANGUAGE)
+}
+
+/// system prompt hint that keeps the model's replies in the configured language
+pub fn language_hint(tag: &str) -> Option<String> {
+    let name = SUPPORTED_LANGUAGES
+        .iter()
+        .find(|(supported, _)| *supported == tag && *supported != AUTO_LANGUAGE)?
+        .1;
+    Some(format!(
+        "The user speaks {name} ({tag}). Respond in {name}, including everything you say with speak(), \
+         unless they ask for another language."
+    ))
+}
+
+fn deepgram_language(tag: Option<&str>) -> Language {
+    match tag {
+        Some(tag) => Language::Other(tag.to_string()),
+        None => Language::multi,
+    }
+}
+
 #[derive(Clone, serde::Serialize)]
 pub struct TranscriptionEvent {
     pub text: String,
     pub is_final: bool,
 }
 
+/// env var (saved in .env): name of the microphone to capture from; unset uses the system default
+pub const INPUT_DEVICE_ENV: &str = "HEYWORK_INPUT_DEVICE";
+
+/// the saved input device name, if one was picked
+pub fn input_device_setting() -> Option<String> {
+    std::env::var(INPUT_DEVICE_ENV).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// index of the device named `wanted` - exact first, then ignoring case. None falls back to
+/// the default, e.g. when the saved headset is unplugged
+pub fn pick_device(names: &[String], wanted: &str) -> Option<usize> {
+    names
+        .iter()
+        .position(|name| name == wanted)
+        .or_else(|| names.iter().position(|name| name.eq_ignore_ascii_case(wanted)))
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioInputDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// every input device the system offers, the default flagged
+pub fn list_input_devices() -> Result<Vec<AudioInputDevice>, String> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = host.input_devices().map_err(|e| format!("couldn't list input devices: {}", e))?;
+    Ok(devices
+        .filter_map(|d| d.name().ok())
+        .map(|name| AudioInputDevice { is_default: default_name.as_deref() == Some(name.as_str()), name })
+        .collect())
+}
+
+// the named input device, or the default when it's unset or gone
+fn open_input_device(wanted: Option<&str>) -> Result<cpal::Device, String> {
+    let host = cpal::default_host();
+    if let Some(wanted) = wanted {
+        let mut devices: Vec<cpal::Device> = host.input_devices().map(|d| d.collect()).unwrap_or_default();
+        let names: Vec<String> = devices.iter().map(|d| d.name().unwrap_or_default()).collect();
+        match pick_device(&names, wanted) {
+            Some(index) => return Ok(devices.swap_remove(index)),
+            None => println!("[mic] \"{}\" isn't connected, using the default input", wanted),
+        }
+    }
+    host.default_input_device().ok_or_else(|| "no input device".to_string())
+}
+
 // mic -> mpsc channel -> deepgram websocket
 fn start_mic_stream(
     is_running: Arc<AtomicBool>,
+    input_device: Option<&str>,
 ) -> Result<(futures::channel::mpsc::Receiver<Result<bytes::Bytes, std::io::Error>>, u32), String> {
-    let host = cpal::default_host();
-    let device = host.default_input_device().ok_or("no input device")?;
+    let device = open_input_device(input_device)?;
     let config = device.default_input_config().map_err(|e| e.to_string())?;
     let sample_rate = config.sample_rate().0;
     let channels = config.channels();
 
-    println!("[mic] {}Hz, {} ch", sample_rate, channels);
+    println!("[mic] {} - {}Hz, {} ch", device.name().unwrap_or_default(), sample_rate, channels);
 
     let (mut tx, rx) = futures::channel::mpsc::channel::<Result<bytes::Bytes, std::io::Error>>(100);
 
@@ -154,6 +309,144 @@ fn start_mic_stream(
     Ok((rx, sample_rate))
 }
 
+// ============================================================================
+// Microphone test
+// ============================================================================
+
+/// how long test_microphone listens
+const MIC_TEST_MS: u64 = 2000;
+/// how often test_microphone reports the live level
+const MIC_LEVEL_INTERVAL_MS: u64 = 100;
+/// emitted with `{ peak, rms }` while a microphone test runs, for a level meter
+pub const MIC_LEVEL_EVENT: &str = "mic:level";
+// rms below this across the whole test reads as a muted or dead input - room noise on a
+// working mic sits well above it
+const SILENT_RMS: f32 = 0.001;
+
+/// running peak and rms of mono samples in -1.0..=1.0
+#[derive(Debug, Default, Clone)]
+pub struct LevelMeter {
+    peak: f32,
+    sum_squares: f64,
+    samples: u64,
+}
+
+impl LevelMeter {
+    pub fn feed(&mut self, samples: &[f32]) {
+        for s in samples {
+            self.peak = self.peak.max(s.abs());
+            self.sum_squares += f64::from(*s) * f64::from(*s);
+        }
+        self.samples += samples.len() as u64;
+    }
+
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+
+    pub fn rms(&self) -> f32 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        (self.sum_squares / self.samples as f64).sqrt() as f32
+    }
+
+    /// whether anything was heard at all
+    pub fn is_silent(&self) -> bool {
+        self.rms() < SILENT_RMS
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicTestReport {
+    pub permission: crate::permissions::PermissionStatus,
+    /// the input device that was opened; None when there isn't one
+    pub device: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub peak: f32,
+    pub rms: f32,
+    /// the stream opened but only delivered silence - muted, or the wrong device selected
+    pub silent: bool,
+    /// why the test couldn't listen, in words for the user
+    pub error: Option<String>,
+}
+
+/// open the selected input for a couple of seconds and measure it, emitting MIC_LEVEL_EVENT as
+/// it goes. blocking; no transcription session is started
+pub fn test_microphone(app_handle: &AppHandle) -> MicTestReport {
+    let mut report = MicTestReport {
+        permission: crate::permissions::microphone_permission(),
+        device: None,
+        sample_rate: None,
+        peak: 0.0,
+        rms: 0.0,
+        silent: true,
+        error: None,
+    };
+    if report.permission == crate::permissions::PermissionStatus::Denied {
+        report.error = Some("Microphone access is denied - allow Hey-work in System Settings > Privacy & Security > Microphone".to_string());
+        return report;
+    }
+
+    let Ok(device) = open_input_device(input_device_setting().as_deref()) else {
+        report.error = Some("No input device found - connect a microphone or pick one in your sound settings".to_string());
+        return report;
+    };
+    report.device = device.name().ok();
+    let config = match device.default_input_config() {
+        Ok(config) => config,
+        Err(e) => {
+            report.error = Some(format!("Couldn't read the input's settings: {}", e));
+            return report;
+        }
+    };
+    report.sample_rate = Some(config.sample_rate().0);
+    let channels = config.channels().max(1) as usize;
+
+    // whole-test totals, and the window since the last level event
+    let meters = Arc::new(Mutex::new((LevelMeter::default(), LevelMeter::default())));
+    let meters_cb = meters.clone();
+    let stream = device.build_input_stream(
+        &config.into(),
+        move |data: &[f32], _: &_| {
+            let mono: Vec<f32> = data.chunks(channels).map(|c| c.iter().sum::<f32>() / c.len() as f32).collect();
+            if let Ok(mut meters) = meters_cb.lock() {
+                meters.0.feed(&mono);
+                meters.1.feed(&mono);
+            }
+        },
+        |e| println!("[mic] test error: {}", e),
+        None,
+    );
+    let stream = match stream {
+        Ok(stream) => stream,
+        Err(e) => {
+            report.error = Some(format!("Couldn't open the microphone: {}", e));
+            return report;
+        }
+    };
+    if let Err(e) = stream.play() {
+        report.error = Some(format!("Couldn't start the microphone: {}", e));
+        return report;
+    }
+
+    let started = std::time::Instant::now();
+    while started.elapsed() < std::time::Duration::from_millis(MIC_TEST_MS) {
+        std::thread::sleep(std::time::Duration::from_millis(MIC_LEVEL_INTERVAL_MS));
+        let window = meters.lock().map(|mut m| std::mem::take(&mut m.1)).unwrap_or_default();
+        let _ = app_handle.emit(MIC_LEVEL_EVENT, serde_json::json!({ "peak": window.peak(), "rms": window.rms() }));
+    }
+    drop(stream);
+
+    let total = meters.lock().map(|m| m.0.clone()).unwrap_or_default();
+    report.peak = total.peak();
+    report.rms = total.rms();
+    report.silent = total.is_silent();
+    println!("[mic] test on {:?}: peak {:.3}, rms {:.4}", report.device, report.peak, report.rms);
+    report
+}
+
 // ============================================================================
 // PushToTalkSession - simple version
 // ============================================================================
@@ -189,7 +482,13 @@ impl PushToTalkSession {
         (text, session_id)
     }
 
-    pub async fn start(&self, api_key: String, app_handle: AppHandle) -> Result<u64, String> {
+    pub async fn start(
+        &self,
+        api_key: String,
+        language: Option<&str>,
+        input_device: Option<&str>,
+        app_handle: AppHandle,
+    ) -> Result<u64, String> {
         if self.is_running.load(Ordering::SeqCst) {
             return Err("already running".to_string());
         }
@@ -202,7 +501,8 @@ impl PushToTalkSession {
         let accumulated = self.accumulated_text.clone();
         let app = app_handle.clone();
 
-        let (audio_rx, sample_rate) = start_mic_stream(is_running.clone())?;
+        let (audio_rx, sample_rate) = start_mic_stream(is_running.clone(), input_device)?;
+        let language = deepgram_language(language);
 
         tokio::spawn(async move {
             let dg = match Deepgram::new(&api_key) {
@@ -215,7 +515,7 @@ impl PushToTalkSession {
 
             let options = Options::builder()
                 .model(Model::Nova3)
-                .language(Language::multi)
+                .language(language)
                 .smart_format(true)
                 .build();
 
@@ -301,7 +601,13 @@ impl VoiceSession {
         self.is_running.store(false, Ordering::SeqCst);
     }
 
-    pub async fn start(&self, api_key: String, app_handle: AppHandle) -> Result<(), String> {
+    pub async fn start(
+        &self,
+        api_key: String,
+        language: Option<&str>,
+        input_device: Option<&str>,
+        app_handle: AppHandle,
+    ) -> Result<(), String> {
         if self.is_running.load(Ordering::SeqCst) {
             return Err("already running".to_string());
         }
@@ -310,7 +616,8 @@ impl VoiceSession {
         let is_running = self.is_running.clone();
         let app = app_handle.clone();
 
-        let (audio_rx, sample_rate) = start_mic_stream(is_running.clone())?;
+        let (audio_rx, sample_rate) = start_mic_stream(is_running.clone(), input_device)?;
+        let language = deepgram_language(language);
 
         tokio::spawn(async move {
             let dg = match Deepgram::new(&api_key) {
@@ -324,7 +631,7 @@ impl VoiceSession {
 
             let options = Options::builder()
                 .model(Model::Nova3)
-                .language(Language::multi)
+                .language(language)
                 .smart_format(true)
                 .build();
 
@@ -367,3 +674,51 @@ impl VoiceSession {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tags_are_canonicalized_or_rejected() {
+        assert_eq!(validate_language("EN_us"), Ok("en-US"));
+        assert_eq!(validate_language(" es "), Ok("es"));
+        assert!(validate_language("tlh").unwrap_err().contains("tlh"));
+    }
+
+    #[test]
+    fn auto_detect_has_no_language_hint() {
+        assert_eq!(language_hint(AUTO_LANGUAGE), None);
+        assert!(language_hint("fr").unwrap().contains("Respond in French"));
+    }
+
+    #[test]
+    fn saved_device_is_found_by_name_or_falls_back() {
+        let names = ["MacBook Pro Microphone", "AirPods Pro", "airpods pro"].map(String::from);
+        assert_eq!(pick_device(&names, "airpods pro"), Some(2));
+        assert_eq!(pick_device(&names, "AIRPODS PRO"), Some(1));
+        assert_eq!(pick_device(&names, "MacBook Pro Microphone"), Some(0));
+        assert_eq!(pick_device(&names, "Yeti"), None);
+    }
+
+    #[test]
+    fn level_meter_tracks_peak_and_rms() {
+        let mut meter = LevelMeter::default();
+        assert_eq!(meter.rms(), 0.0);
+        assert!(meter.is_silent());
+        meter.feed(&[0.5, -0.5, 0.5, -0.5]);
+        meter.feed(&[-0.8]);
+        assert_eq!(meter.peak(), 0.8);
+        assert!((meter.rms() - ((4.0 * 0.25 + 0.64) / 5.0f32).sqrt()).abs() < 1e-6);
+        assert!(!meter.is_silent());
+    }
+
+    #[test]
+    fn utterances_compare_without_markdown_or_spacing() {
+        assert_eq!(
+            normalize_utterance("**Done!**  The file is\nsaved."),
+            normalize_utterance("done! the file is saved.")
+        );
+        assert_ne!(normalize_utterance("Done."), normalize_utterance("Not done."));
+    }
+}
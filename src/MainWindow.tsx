@@ -16,7 +16,8 @@ type State =
   | { mode: "revealing" }       // orb expands to show brand name
   | { mode: "expanded" }
   | { mode: "running" }
-  | { mode: "help"; screenshot: string }
+  | { mode: "help"; screenshot: string; snapshot?: string; taskMode?: string }
+  | { mode: "ambientOffer"; text: string }  // idle screen stuck on an error
   | { mode: "voiceResponse" }
   | { mode: "spotlight" };
 
@@ -24,8 +25,9 @@ type Action =
   | { type: "EXPAND" }          // idle → revealing
   | { type: "REVEAL_DONE" }     // revealing → expanded
   | { type: "COLLAPSE" }
-  | { type: "HELP"; screenshot: string }
+  | { type: "HELP"; screenshot: string; snapshot?: string; taskMode?: string }
   | { type: "HELP_CANCEL" }
+  | { type: "AMBIENT_OFFER"; text: string }
   | { type: "HELP_SUBMIT" }
   | { type: "AGENT_START" }
   | { type: "AGENT_STOP" }
@@ -45,9 +47,12 @@ function reducer(state: State, action: Action): State {
     case "COLLAPSE":
       return { mode: "idle" };
     case "HELP":
-      return { mode: "help", screenshot: action.screenshot };
+      return { mode: "help", screenshot: action.screenshot, snapshot: action.snapshot, taskMode: action.taskMode };
     case "HELP_CANCEL":
       return { mode: "idle" };
+    case "AMBIENT_OFFER":
+      // only ever replaces the resting orb, never something the user is doing
+      return state.mode === "idle" ? { mode: "ambientOffer", text: action.text } : state;
     case "HELP_SUBMIT":
       return { mode: "expanded" };
     case "AGENT_START":
@@ -79,6 +84,7 @@ const SIZES: Record<string, { w: number; h: number; centered?: boolean }> = {
   expanded: { w: 420, h: 540 },
   running: { w: 420, h: 540 },
   help: { w: 520, h: 420, centered: true },
+  ambientOffer: { w: 300, h: 52 },
   voiceResponse: { w: 340, h: 420 },
   spotlight: { w: 600, h: 72, centered: true },
 };
@@ -90,6 +96,7 @@ export default function MainWindow() {
 
   const helpPromptRef = useRef("");
   const spotlightPromptRef = useRef("");
+  const spotlightInputRef = useRef<HTMLInputElement>(null);
   const submitRef = useRef(submit);
   const stateRef = useRef(state);
 
@@ -158,7 +165,7 @@ export default function MainWindow() {
     // Toggle #root clipping — orb/reveal modes need no box, others get rounded card
     const root = document.getElementById("root");
     if (root) {
-      const noBox = state.mode === "idle" || state.mode === "revealing";
+      const noBox = state.mode === "idle" || state.mode === "revealing" || state.mode === "ambientOffer";
       root.style.borderRadius = noBox ? "0" : "12px";
       root.style.overflow = noBox ? "visible" : "hidden";
       root.style.background = "transparent";
@@ -180,6 +187,21 @@ export default function MainWindow() {
   const [isPttActive, setIsPttActive] = useState(false);
   const [showSwarmPanel, setShowSwarmPanel] = useState(false);
   const [showSkillsPanel, setShowSkillsPanel] = useState(false);
+  const [ambientOn, setAmbientOn] = useState(false);
+
+  // ambient help: a dot on the orb while it's watching, and a quiet offer when the screen looks stuck
+  useEffect(() => {
+    invoke<boolean>("get_ambient_help")
+      .then(setAmbientOn)
+      .catch(() => {});
+    const listeners = [
+      listen<boolean>("settings:ambient-help", (e) => setAmbientOn(e.payload)),
+      listen<{ text: string }>("ambient-help", (e) => dispatch({ type: "AMBIENT_OFFER", text: e.payload.text })),
+    ];
+    return () => {
+      listeners.forEach((p) => p.then((fn) => fn()));
+    };
+  }, []);
 
   // startDrag kept for backwards compat but real drag is the global mousedown/move above
   const startDrag = () => {
@@ -211,9 +233,10 @@ export default function MainWindow() {
         setIsRunning(false);
       }),
 
-      listen<{ screenshot: string | null }>("hotkey-help", (e) => {
+      // browser tasks also carry the page's a11y snapshot and mode "browser"
+      listen<{ screenshot: string | null; snapshot?: string; mode?: string }>("hotkey-help", (e) => {
         if (e.payload.screenshot) {
-          dispatch({ type: "HELP", screenshot: e.payload.screenshot });
+          dispatch({ type: "HELP", screenshot: e.payload.screenshot, snapshot: e.payload.snapshot, taskMode: e.payload.mode });
         }
       }),
 
@@ -235,6 +258,12 @@ export default function MainWindow() {
         dispatch({ type: "SPOTLIGHT" });
       }),
 
+      // the backend made the panel key; autoFocus can run before that, so focus again
+      listen<boolean>("spotlight:focus-input", (e) => {
+        if (!e.payload) console.warn("Spotlight panel didn't get keyboard focus");
+        spotlightInputRef.current?.focus();
+      }),
+
       // FIX: Only hide panel if in spotlight mode; otherwise leave visible
       listen("window:blur", () => {
         if (stateRef.current.mode === "spotlight") {
@@ -258,6 +287,58 @@ export default function MainWindow() {
     };
   }, []);
 
+  // idle auto-collapse: an expanded, non-running panel left untouched for the configured
+  // number of seconds folds back to the orb (0 = disabled)
+  const idleTimeoutRef = useRef(0);
+  const idleTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+  const resetIdleTimer = useRef(() => {
+    if (idleTimerRef.current) clearTimeout(idleTimerRef.current);
+    idleTimerRef.current = null;
+    if (idleTimeoutRef.current <= 0 || stateRef.current.mode !== "expanded") return;
+    idleTimerRef.current = setTimeout(async () => {
+      idleTimerRef.current = null;
+      const running = await invoke<boolean>("is_agent_running").catch(() => true);
+      if (!running && stateRef.current.mode === "expanded") {
+        dispatch({ type: "COLLAPSE" });
+      }
+    }, idleTimeoutRef.current * 1000);
+  }).current;
+
+  useEffect(() => {
+    invoke<number>("get_idle_timeout")
+      .then((secs) => {
+        idleTimeoutRef.current = secs;
+        resetIdleTimer();
+      })
+      .catch((e) => console.error("Idle timeout load failed:", e));
+
+    const onInteraction = () => resetIdleTimer();
+    const interactionEvents = ["mousemove", "mousedown", "keydown", "wheel"] as const;
+    interactionEvents.forEach((name) => window.addEventListener(name, onInteraction));
+
+    const listeners = [
+      listen("window:blur", onInteraction),
+      listen("window:focus", onInteraction),
+      listen("agent:stopped", onInteraction),
+      listen<number>("settings:idle-timeout", (e) => {
+        idleTimeoutRef.current = e.payload;
+        resetIdleTimer();
+      }),
+    ];
+
+    return () => {
+      interactionEvents.forEach((name) => window.removeEventListener(name, onInteraction));
+      listeners.forEach((p) => p.then((fn) => fn()));
+      if (idleTimerRef.current) clearTimeout(idleTimerRef.current);
+    };
+  }, [resetIdleTimer]);
+
+  // (re)arm on entering expanded, cancel on anything else (running, help, idle...)
+  useEffect(() => {
+    resetIdleTimer();
+  }, [state.mode, resetIdleTimer]);
+
   // Auto-advance from revealing → expanded after showing brand
   useEffect(() => {
     if (state.mode === "revealing") {
@@ -288,6 +369,61 @@ export default function MainWindow() {
           className="idle-icon-img"
           onMouseDown={startDrag}
         />
+        {ambientOn && (
+          <span
+            className="absolute bottom-1 right-1 w-2 h-2 rounded-full bg-emerald-400/80 ring-2 ring-black/40"
+            title="Watching for stuck screens"
+          />
+        )}
+      </motion.div>
+    );
+  }
+
+  // ═══════════════════════════════════════════════════════════════
+  // AMBIENT OFFER — the idle screen has sat on an error; ask before capturing anything
+  // ═══════════════════════════════════════════════════════════════
+  if (state.mode === "ambientOffer") {
+    const handleAccept = async () => {
+      // the same capture the help hotkey makes - only now does a screenshot leave the machine
+      const screenshot = await invoke<string>("capture_screen_for_help").catch(() => null);
+      if (screenshot) {
+        helpPromptRef.current = `Help me with this: "${state.text}"`;
+        dispatch({ type: "HELP", screenshot });
+      } else {
+        dispatch({ type: "COLLAPSE" });
+      }
+    };
+
+    return (
+      <motion.div
+        data-tauri-drag-region
+        initial={{ opacity: 0, x: -8 }}
+        animate={{ opacity: 1, x: 0 }}
+        className="reveal-row"
+        onMouseDown={startDrag}
+      >
+        <img
+          data-tauri-drag-region
+          src="/windows-computer-icon.png"
+          alt="Hey work"
+          draggable={false}
+          className="reveal-icon-img"
+          onMouseDown={startDrag}
+        />
+        <button
+          onClick={handleAccept}
+          className="flex-1 min-w-0 text-left text-[12px] text-white/80 hover:text-white truncate"
+          title={state.text}
+        >
+          Need help with this?
+        </button>
+        <button
+          onClick={() => dispatch({ type: "COLLAPSE" })}
+          className="w-6 h-6 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10"
+          title="Dismiss"
+        >
+          <X size={12} />
+        </button>
       </motion.div>
     );
   }
@@ -336,7 +472,7 @@ export default function MainWindow() {
       const prompt = helpPromptRef.current;
       if (!prompt.trim()) return;
       dispatch({ type: "HELP_SUBMIT" });
-      await submitRef.current(prompt, state.screenshot);
+      await submitRef.current(prompt, state.screenshot, state.taskMode, false, false, state.snapshot);
       helpPromptRef.current = "";
     };
 
@@ -364,6 +500,7 @@ export default function MainWindow() {
             <input
               type="text"
               autoFocus
+              defaultValue={helpPromptRef.current}
               onChange={(e) => (helpPromptRef.current = e.target.value)}
               onKeyDown={(e) => {
                 if (e.key === "Enter") handleSubmit();
@@ -418,6 +555,7 @@ export default function MainWindow() {
       >
         <Zap size={16} className="text-blue-400/70 mr-3 flex-shrink-0" />
         <input
+          ref={spotlightInputRef}
           type="text"
           autoFocus
           onChange={(e) => (spotlightPromptRef.current = e.target.value)}
@@ -1,6 +1,12 @@
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 
+interface ApiKeyValidation {
+  valid: boolean;
+  message: string;
+  tier?: string | null;
+}
+
 interface ApiKeyOnboardingProps {
   onComplete: () => void;
 }
@@ -21,6 +27,12 @@ export default function ApiKeyOnboarding({ onComplete }: ApiKeyOnboardingProps)
     setError(null);
     try {
       await invoke("save_api_key", { service: "anthropic", key: trimmed });
+      // catch a bad key now rather than mid-run; if the check itself can't run (offline), carry on
+      const check = await invoke<ApiKeyValidation>("validate_api_key", { service: "anthropic" }).catch(() => null);
+      if (check && !check.valid) {
+        setError(check.message);
+        return;
+      }
       await invoke("set_api_key", { apiKey: trimmed });
       localStorage.setItem("heywork_onboarding_complete", "true");
       onComplete();
@@ -52,7 +64,7 @@ export default function ApiKeyOnboarding({ onComplete }: ApiKeyOnboardingProps)
           disabled={saving}
           className="w-full rounded-lg bg-white text-black font-medium py-2 disabled:opacity-60"
         >
-          {saving ? "Saving..." : "Continue"}
+          {saving ? "Checking key..." : "Continue"}
         </button>
       </div>
     </div>
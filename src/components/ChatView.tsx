@@ -3,7 +3,7 @@ import { motion, AnimatePresence } from "framer-motion";
 import { Streamdown } from "streamdown";
 import { useAgentStore } from "../stores/agentStore";
 import { useAgent } from "../hooks/useAgent";
-import { ChatMessage, ConversationMeta, Conversation, ModelId, AgentMode } from "../types";
+import { ChatMessage, ConversationMeta, Conversation, ModelId, AgentMode, UsageSummary, LastResponse, CompactionReport, ReplayStep } from "../types";
 import {
   Send,
   Square,
@@ -42,11 +42,24 @@ import {
   Pause,
   Search,
   FileText,
+  AlertTriangle,
+  Pin,
+  RotateCcw,
+  Sparkles,
+  ThumbsUp,
+  ThumbsDown,
+  Copy,
+  Code2,
+  Shrink,
+  Repeat,
+  Pencil,
 } from "lucide-react";
 import SettingsContent from "./SettingsContent";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { createAudioElement } from "../utils/audio";
+import { imageDataUrl } from "../utils/image";
+import { modelLabel } from "../utils/models";
 import { formatToolMessage, ToolInput, stripVoiceInputTags } from "../utils/toolFormat";
 
 // url pill component showing domain and path
@@ -181,7 +194,7 @@ function ScreenshotBlock({ msg }: { msg: ChatMessage }) {
             >
               <div className="rounded-lg overflow-hidden bg-black/40">
                 <img
-                  src={`data:image/jpeg;base64,${msg.screenshot}`}
+                  src={imageDataUrl(msg.screenshot)}
                   alt="Screenshot"
                   className="w-full h-auto"
                 />
@@ -350,6 +363,62 @@ function formatActionContent(msg: ChatMessage): React.ReactNode {
   }
 }
 
+// a skill handled the request - thumbs up/down feed its success rate (rate_skill_execution)
+function SkillRunBlock({ msg }: { msg: ChatMessage }) {
+  const run = msg.skillRun;
+  const [rated, setRated] = useState<boolean | null>(null);
+  if (!run) return null;
+
+  const rate = async (succeeded: boolean) => {
+    setRated(succeeded);
+    try {
+      await invoke("rate_skill_execution", { skillId: run.skillId, succeeded });
+    } catch (e) {
+      console.error("Skill rating failed:", e);
+      setRated(null);
+    }
+  };
+
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      className="flex justify-start"
+    >
+      <div className="px-2.5 py-2 rounded-lg bg-white/5 border border-white/10 max-w-full">
+        <div className="flex items-center gap-2">
+          <Sparkles size={14} className={run.success ? "text-purple-300 shrink-0" : "text-red-400 shrink-0"} />
+          <p className="text-[12px] text-white/70 truncate">Used skill: {run.name}</p>
+        </div>
+        {msg.content && (
+          <p className="mt-1 text-[12px] text-white/85 leading-relaxed whitespace-pre-wrap break-words select-text">{msg.content}</p>
+        )}
+        <div className="flex items-center gap-1 mt-1.5">
+          <span className="text-[11px] text-white/40 mr-1">
+            {rated === null ? "Did it do the right thing?" : "Thanks, noted"}
+          </span>
+          <button
+            onClick={() => rate(true)}
+            disabled={rated !== null}
+            aria-label="Skill did the right thing"
+            className={`p-1 rounded transition-colors disabled:cursor-default ${rated === true ? "text-green-400 bg-green-500/20" : "text-white/40 hover:text-white/80 hover:bg-white/10"}`}
+          >
+            <ThumbsUp size={12} />
+          </button>
+          <button
+            onClick={() => rate(false)}
+            disabled={rated !== null}
+            aria-label="Skill did the wrong thing"
+            className={`p-1 rounded transition-colors disabled:cursor-default ${rated === false ? "text-red-400 bg-red-500/20" : "text-white/40 hover:text-white/80 hover:bg-white/10"}`}
+          >
+            <ThumbsDown size={12} />
+          </button>
+        </div>
+      </div>
+    </motion.div>
+  );
+}
+
 function MessageBubble({ msg }: { msg: ChatMessage }) {
   const isUser = msg.role === "user";
 
@@ -365,6 +434,10 @@ function MessageBubble({ msg }: { msg: ChatMessage }) {
     return <SpeakBubble msg={msg} />;
   }
 
+  if (msg.type === "skill") {
+    return <SkillRunBlock msg={msg} />;
+  }
+
   const getIcon = () => {
     if (isUser) return null;
     switch (msg.type) {
@@ -431,7 +504,7 @@ function MessageBubble({ msg }: { msg: ChatMessage }) {
         {isUser && msg.screenshot && (
           <div className="mb-2 rounded-lg overflow-hidden">
             <img
-              src={`data:image/jpeg;base64,${msg.screenshot}`}
+              src={imageDataUrl(msg.screenshot)}
               alt="Context"
               className="w-full max-w-[300px] h-auto rounded-lg"
             />
@@ -455,6 +528,9 @@ function MessageBubble({ msg }: { msg: ChatMessage }) {
             </p>
           )}
         </div>
+        {!isUser && msg.model && (
+          <p className="mt-0.5 text-[10px] text-white/30">{modelLabel(msg.model)}</p>
+        )}
       </div>
     </motion.div>
   );
@@ -467,6 +543,29 @@ const MODELS: { id: ModelId; label: string }[] = [
   { id: "claude-opus-4-6", label: "Opus 4.6 🆕" },
 ];
 
+function formatTokens(tokens: number): string {
+  return tokens >= 1000 ? `${(tokens / 1000).toFixed(1)}k` : `${tokens}`;
+}
+
+// conversation cost so far, with how much prompt caching saved
+function CostMeter({ usage }: { usage: UsageSummary }) {
+  const title = [
+    `Input: ${formatTokens(usage.inputTokens)} tokens`,
+    `Output: ${formatTokens(usage.outputTokens)} tokens`,
+    `Cache read: ${formatTokens(usage.cacheReadTokens)} tokens`,
+    `Cache write: ${formatTokens(usage.cacheWriteTokens)} tokens`,
+    `Saved by caching: $${Math.max(usage.savedUsd, 0).toFixed(4)}`,
+  ].join("\n");
+  return (
+    <span className="text-[10px] text-white/40 tabular-nums px-1" title={title}>
+      ${usage.costUsd.toFixed(usage.costUsd < 1 ? 3 : 2)}
+      {usage.savedUsd > 0.0005 && (
+        <span className="text-emerald-400/60"> (−${usage.savedUsd.toFixed(3)})</span>
+      )}
+    </span>
+  );
+}
+
 function formatRelativeTime(timestamp: number): string {
   const now = Date.now() / 1000;
   const diff = now - timestamp;
@@ -483,7 +582,12 @@ function convertApiToChat(conversation: Conversation): ChatMessage[] {
   // map tool_use_id -> index in chatMessages for attaching results
   const toolUseIdToIndex: Map<string, number> = new Map();
 
-  for (const msg of conversation.messages) {
+  // model that was active for each message, walking the recorded switch points
+  const changes = conversation.model_changes ?? [];
+  const modelAt = (index: number) =>
+    changes.find(c => index < c.message_index)?.from ?? conversation.model;
+
+  for (const [msgIndex, msg] of conversation.messages.entries()) {
     if (msg.role === "user") {
       // check if this is a tool result message
       const toolResults = msg.content.filter(b => b.type === "tool_result");
@@ -554,6 +658,7 @@ function convertApiToChat(conversation: Conversation): ChatMessage[] {
             role: "assistant",
             content: block.text,
             timestamp: new Date(conversation.updated_at * 1000),
+            model: modelAt(msgIndex),
           });
         } else if (block.type === "tool_use" && block.name) {
           const input = (block.input as ToolInput) || {};
@@ -580,6 +685,74 @@ function convertApiToChat(conversation: Conversation): ChatMessage[] {
   return chatMessages;
 }
 
+// offer to pick up a run the last session quit in the middle of
+function ResumeBanner() {
+  const [resumable, setResumable] = useState<ConversationMeta | null>(null);
+  const { isRunning, setMessages, setSelectedModel, setSelectedMode, setConversationId, setVoiceMode, setUsage } = useAgentStore();
+
+  useEffect(() => {
+    invoke<ConversationMeta | null>("get_resumable_conversation")
+      .then(setResumable)
+      .catch(console.error);
+    const unlisten = listen<ConversationMeta>("resumable_conversation", (e) => setResumable(e.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (!resumable || isRunning) return null;
+
+  const handleResume = async () => {
+    setResumable(null);
+    try {
+      const conv = await invoke<Conversation | null>("load_conversation", { id: resumable.id });
+      if (!conv) return;
+      setMessages(convertApiToChat(conv));
+      setSelectedModel(conv.model as ModelId);
+      setSelectedMode(conv.mode as AgentMode);
+      setConversationId(conv.id);
+      setVoiceMode(conv.voice_mode ?? false);
+      setUsage(null);
+      // no instruction: the agent answers the turn that was left pending
+      await invoke("run_agent", {
+        instructions: "",
+        model: conv.model,
+        mode: conv.mode,
+        voiceMode: conv.voice_mode ?? false,
+        history: [],
+        conversationId: conv.id,
+      });
+    } catch (e) {
+      console.error("Resume failed:", e);
+    }
+  };
+
+  const handleDismiss = () => {
+    invoke("dismiss_resumable_conversation", { id: resumable.id }).catch(console.error);
+    setResumable(null);
+  };
+
+  return (
+    <div className="mx-3 mt-2 px-3 py-2 rounded-lg bg-blue-500/10 border border-blue-400/20 flex items-center gap-2">
+      <p className="flex-1 min-w-0 text-[12px] text-white/80 truncate">
+        Interrupted: <span className="text-white/60">{resumable.title}</span>
+      </p>
+      <button
+        onClick={handleResume}
+        className="px-2.5 py-1 rounded-md bg-blue-500/20 hover:bg-blue-500/30 text-blue-200 text-[11px] transition-colors"
+      >
+        Resume
+      </button>
+      <button
+        onClick={handleDismiss}
+        className="px-2.5 py-1 rounded-md bg-white/5 hover:bg-white/10 text-white/50 text-[11px] transition-colors"
+      >
+        Dismiss
+      </button>
+    </div>
+  );
+}
+
 interface HistoryDropdownProps {
   onNewChat: () => void;
   onLoad: (messages: ChatMessage[], model: ModelId, mode: AgentMode, conversationId: string, voiceMode: boolean) => void;
@@ -590,6 +763,8 @@ function HistoryDropdown({ onNewChat, onLoad, disabled }: HistoryDropdownProps)
   const [open, setOpen] = useState(false);
   const [conversations, setConversations] = useState<ConversationMeta[]>([]);
   const [loading, setLoading] = useState(false);
+  const [renamingId, setRenamingId] = useState<string | null>(null);
+  const [renameText, setRenameText] = useState("");
   const dropdownRef = useRef<HTMLDivElement>(null);
 
   useEffect(() => {
@@ -602,6 +777,16 @@ function HistoryDropdown({ onNewChat, onLoad, disabled }: HistoryDropdownProps)
     }
   }, [open]);
 
+  // titles are written in the background after a run ends
+  useEffect(() => {
+    const unlisten = listen<{ id: string; title: string }>("conversation:titled", (e) => {
+      setConversations(prev => prev.map(c => (c.id === e.payload.id ? { ...c, title: e.payload.title } : c)));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     const handleClickOutside = (e: MouseEvent) => {
       if (dropdownRef.current && !dropdownRef.current.contains(e.target as Node)) {
@@ -618,6 +803,36 @@ function HistoryDropdown({ onNewChat, onLoad, disabled }: HistoryDropdownProps)
     setConversations(prev => prev.filter(c => c.id !== id));
   };
 
+  const handleTogglePin = async (e: React.MouseEvent, conv: ConversationMeta) => {
+    e.stopPropagation();
+    const pinned = !conv.is_pinned;
+    await invoke("set_conversation_pinned", { id: conv.id, pinned });
+    // keep the backend order: pinned first, then most recent
+    setConversations(prev =>
+      prev
+        .map(c => (c.id === conv.id ? { ...c, is_pinned: pinned } : c))
+        .sort((a, b) => Number(!!b.is_pinned) - Number(!!a.is_pinned) || b.updated_at - a.updated_at)
+    );
+  };
+
+  const startRename = (e: React.MouseEvent, conv: ConversationMeta) => {
+    e.stopPropagation();
+    setRenamingId(conv.id);
+    setRenameText(conv.title);
+  };
+
+  const handleRename = async (id: string) => {
+    setRenamingId(null);
+    const current = conversations.find(c => c.id === id);
+    if (!renameText.trim() || renameText.trim() === current?.title) return;
+    try {
+      const title = await invoke<string>("rename_conversation", { id, title: renameText });
+      setConversations(prev => prev.map(c => (c.id === id ? { ...c, title } : c)));
+    } catch (e) {
+      console.error("Failed to rename conversation:", e);
+    }
+  };
+
   const handleLoad = async (id: string) => {
     try {
       const conv = await invoke<Conversation | null>("load_conversation", { id });
@@ -696,13 +911,48 @@ function HistoryDropdown({ onNewChat, onLoad, disabled }: HistoryDropdownProps)
                   <div
                     key={conv.id}
                     className="group flex items-center gap-2.5 px-3 py-2 hover:bg-white/8 transition-colors cursor-pointer"
-                    onClick={() => handleLoad(conv.id)}
+                    onClick={() => renamingId !== conv.id && handleLoad(conv.id)}
                   >
                     <MessageSquare size={13} className="text-white/30 shrink-0" />
                     <div className="flex-1 min-w-0">
-                      <p className="text-[12px] text-white/80 truncate">{conv.title || "Untitled"}</p>
+                      {renamingId === conv.id ? (
+                        <input
+                          autoFocus
+                          value={renameText}
+                          onChange={(e) => setRenameText(e.target.value)}
+                          onClick={(e) => e.stopPropagation()}
+                          onKeyDown={(e) => {
+                            // saving happens on blur, so enter and clicking away don't both save
+                            if (e.key === "Enter") e.currentTarget.blur();
+                            if (e.key === "Escape") setRenamingId(null);
+                          }}
+                          onBlur={() => handleRename(conv.id)}
+                          aria-label="Conversation title"
+                          className="w-full px-1 py-0.5 -mx-1 rounded bg-black/30 border border-white/10 text-[12px] text-white/90 outline-none focus:border-white/20"
+                        />
+                      ) : (
+                        <p className="text-[12px] text-white/80 truncate">{conv.title || "Untitled"}</p>
+                      )}
                       <p className="text-[10px] text-white/40">{formatRelativeTime(conv.updated_at)} · {conv.message_count} msgs</p>
                     </div>
+                    <button
+                      onClick={(e) => startRename(e, conv)}
+                      title="Rename"
+                      aria-label="Rename conversation"
+                      className="opacity-0 group-hover:opacity-100 p-1.5 rounded-md hover:bg-white/10 transition-all"
+                    >
+                      <Pencil size={12} className="text-white/40" />
+                    </button>
+                    <button
+                      onClick={(e) => handleTogglePin(e, conv)}
+                      title={conv.is_pinned ? "Unpin" : "Pin"}
+                      aria-label={conv.is_pinned ? "Unpin conversation" : "Pin conversation"}
+                      className={`p-1.5 rounded-md hover:bg-white/10 transition-all ${
+                        conv.is_pinned ? "opacity-100" : "opacity-0 group-hover:opacity-100"
+                      }`}
+                    >
+                      <Pin size={12} className={conv.is_pinned ? "text-amber-400/90" : "text-white/40"} />
+                    </button>
                     <button
                       onClick={(e) => handleDelete(e, conv.id)}
                       className="opacity-0 group-hover:opacity-100 p-1.5 rounded-md hover:bg-red-500/20 transition-all"
@@ -787,9 +1037,27 @@ function StreamingBubble() {
   );
 }
 
+// tool input as it streams in, e.g. the bash command being typed out
+function ToolInputBubble() {
+  const streamingToolInput = useAgentStore((s) => s.streamingToolInput);
+
+  if (!streamingToolInput?.json) return null;
+
+  const { name, json } = streamingToolInput;
+  const tail = json.length > 160 ? `…${json.slice(-160)}` : json;
+
+  return (
+    <div className="flex justify-start">
+      <div className="max-w-full px-3 py-1.5 rounded-lg bg-white/[0.03] border border-white/5 text-[11px] font-mono text-white/50 break-all">
+        <span className="text-white/70">{name}</span> {tail}
+      </div>
+    </div>
+  );
+}
+
 export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSettingsClose, onCollapse, headerRight }: ChatViewProps) {
-  const { messages, isRunning, inputText, setInputText, selectedModel, setSelectedModel, selectedMode, setSelectedMode, streamingText, streamingThinking, clearMessages, setMessages, setVoiceMode, setConversationId } = useAgentStore();
-  const { submit, stop } = useAgent();
+  const { messages, isRunning, inputText, setInputText, selectedModel, setSelectedModel, selectedMode, setSelectedMode, streamingText, streamingThinking, clearMessages, setMessages, setVoiceMode, setConversationId, conversationId, usage, setUsage, addMessage } = useAgentStore();
+  const { submit, stop, softStop } = useAgent();
   const scrollRef = useRef<HTMLDivElement>(null);
   const bottomRef = useRef<HTMLDivElement>(null);
   const inputRef = useRef<HTMLTextAreaElement>(null);
@@ -800,6 +1068,9 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
   const [showVoiceConfirm, setShowVoiceConfirm] = useState(false);
   const [isPttActive, setIsPttActive] = useState(false);
 
+  // destructive action waiting for the user's ok (await_confirmation)
+  const [pendingConfirm, setPendingConfirm] = useState<{ tool: string; summary: string; reason: string; screenshot?: string } | null>(null);
+
   // scroll tracking — detect if user has scrolled up manually
   const [userScrolledUp, setUserScrolledUp] = useState(false);
   const isAutoScrolling = useRef(false);
@@ -885,6 +1156,30 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
     };
   }, [setInputText]);
 
+  // confirmation listeners - the panel is click-through during computer runs, so lift that while asking
+  useEffect(() => {
+    const unlistenAwait = listen<{ tool: string; summary: string; reason: string; screenshot?: string }>("await_confirmation", (event) => {
+      setPendingConfirm(event.payload);
+      invoke("set_main_click_through", { ignore: false }).catch(() => {});
+    });
+    const unlistenResolved = listen<boolean>("confirmation_resolved", () => {
+      setPendingConfirm(null);
+      const s = useAgentStore.getState();
+      if (s.isRunning && s.selectedMode === "computer") {
+        invoke("set_main_click_through", { ignore: true }).catch(() => {});
+      }
+    });
+    return () => {
+      unlistenAwait.then((f) => f());
+      unlistenResolved.then((f) => f());
+    };
+  }, []);
+
+  const answerConfirm = (approved: boolean) => {
+    invoke("confirm_action", { approved }).catch(console.error);
+    setPendingConfirm(null);
+  };
+
   // ─── Auto-scroll: new messages added ──────────────────────────
   useEffect(() => {
     if (messages.length > prevMessagesLen.current) {
@@ -946,26 +1241,93 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
     });
   }, [voiceText, inputText]);
 
-  const handleSubmit = () => {
+  const handleSubmit = (dryRun = false) => {
     if (!inputText.trim()) return;
     // enable TTS response if voice input was used
     if (usedVoiceInput) {
       setVoiceMode(true);
       setUsedVoiceInput(false);
     }
-    submit();
+    // spotlight questions try a tool-less quick answer first
+    submit(undefined, undefined, undefined, dryRun, isSpotlight && !dryRun);
   };
 
   const handleKeyDown = (e: KeyboardEvent<HTMLTextAreaElement>) => {
+    // alt+enter previews the plan without running anything
     if (e.key === "Enter" && !e.shiftKey) {
       e.preventDefault();
-      handleSubmit();
+      handleSubmit(e.altKey);
     }
     if (e.key === "Escape" && isSpotlight) {
       invoke("hide_spotlight_window").catch(() => {});
     }
   };
 
+  // reverse the last simple computer action; the backend explains when it can't
+  const handleUndo = () => {
+    invoke("undo_last_action").catch((e) => {
+      addMessage({ role: "assistant", content: String(e), type: "info" });
+    });
+  };
+
+  // copy the final answer, or just its code blocks, straight to the system clipboard
+  const handleCopyResponse = async (codeOnly: boolean) => {
+    if (!conversationId) return;
+    try {
+      const last = await invoke<LastResponse>("get_last_response", { conversationId });
+      const text = codeOnly ? last.codeBlocks.map((block) => block.code).join("\n\n") : last.text;
+      if (!text) {
+        addMessage({
+          role: "assistant",
+          content: codeOnly ? "No code blocks in the last response" : "Nothing to copy yet",
+          type: "info",
+        });
+        return;
+      }
+      await invoke("copy_to_clipboard", { text });
+    } catch (e) {
+      addMessage({ role: "assistant", content: String(e), type: "info" });
+    }
+  };
+
+  // summarize the oldest messages so a long conversation fits the context window again
+  const handleCompact = async () => {
+    if (!conversationId) return;
+    try {
+      const report = await invoke<CompactionReport>("compact_conversation", { id: conversationId });
+      const conv = await invoke<Conversation | null>("load_conversation", { id: conversationId });
+      if (conv) setMessages(convertApiToChat(conv));
+      addMessage({
+        role: "assistant",
+        content: `Compacted ${report.compactedMessages} messages (~${report.tokensBefore.toLocaleString()} → ~${report.tokensAfter.toLocaleString()} tokens)`,
+        type: "info",
+      });
+    } catch (e) {
+      addMessage({ role: "assistant", content: String(e), type: "info" });
+    }
+  };
+
+  // preview the recorded actions, then run them again - the backend asks for confirmation first
+  const handleReplay = async () => {
+    if (!conversationId) return;
+    try {
+      const steps = await invoke<ReplayStep[]>("replay_conversation", { id: conversationId, dryRun: true });
+      const replayed = steps.filter((step) => step.replayed);
+      if (replayed.length === 0) {
+        addMessage({ role: "assistant", content: "Nothing to replay - this conversation has no actions", type: "info" });
+        return;
+      }
+      addMessage({
+        role: "assistant",
+        content: `Replay ${replayed.length} of ${steps.length} steps:\n${replayed.map((step) => `${step.index}. ${step.summary}`).join("\n")}`,
+        type: "info",
+      });
+      await invoke("replay_conversation", { id: conversationId });
+    } catch (e) {
+      addMessage({ role: "assistant", content: String(e), type: "info" });
+    }
+  };
+
   const handleToggleView = () => {
     // toggle view not applicable for compact mode
     if (isCompact) return;
@@ -1037,10 +1399,12 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
                   setSelectedMode(mode);
                   setConversationId(conversationId);
                   setVoiceMode(voiceMode);
+                  setUsage(null);
                 }}
                 disabled={isRunning}
               />
               <div className="flex items-center gap-1">
+                {usage && <CostMeter usage={usage} />}
                 <select
                   value={selectedModel}
                   onChange={(e) => setSelectedModel(e.target.value as ModelId)}
@@ -1054,6 +1418,46 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
                   ))}
                 </select>
                 {headerRight}
+                <button
+                  onClick={handleUndo}
+                  disabled={isRunning}
+                  className="w-7 h-7 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10 transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                  title="Undo last action"
+                >
+                  <RotateCcw size={14} />
+                </button>
+                <button
+                  onClick={() => handleCopyResponse(false)}
+                  disabled={!conversationId}
+                  className="w-7 h-7 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10 transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                  title="Copy last response"
+                >
+                  <Copy size={14} />
+                </button>
+                <button
+                  onClick={() => handleCopyResponse(true)}
+                  disabled={!conversationId}
+                  className="w-7 h-7 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10 transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                  title="Copy code blocks"
+                >
+                  <Code2 size={14} />
+                </button>
+                <button
+                  onClick={handleCompact}
+                  disabled={!conversationId || isRunning}
+                  className="w-7 h-7 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10 transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                  title="Compact conversation"
+                >
+                  <Shrink size={14} />
+                </button>
+                <button
+                  onClick={handleReplay}
+                  disabled={!conversationId || isRunning}
+                  className="w-7 h-7 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10 transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                  title="Replay actions"
+                >
+                  <Repeat size={14} />
+                </button>
                 <button
                   onClick={() => setSettingsOpen(true)}
                   className="w-7 h-7 flex items-center justify-center rounded-md text-white/40 hover:text-white/70 hover:bg-white/10 transition-colors"
@@ -1090,6 +1494,7 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
         </div>
       ) : (
         <>
+          <ResumeBanner />
           {/* messages */}
           <div ref={scrollRef} className={`flex-1 overflow-y-auto scroll-smooth ${padding} relative`}>
             <div className={messages.length === 0 && !streamingText && !streamingThinking ? "h-full" : "space-y-3"}>
@@ -1108,6 +1513,7 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
                     {messages.map((msg) => <MessageBubble key={msg.id} msg={msg} />)}
                     <ThinkingBubble />
                     <StreamingBubble />
+                    <ToolInputBubble />
                     <div ref={bottomRef} className="h-2" />
                   </>
                 )}
@@ -1174,6 +1580,51 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
               )}
             </AnimatePresence>
 
+            {/* destructive action confirmation */}
+            <AnimatePresence>
+              {pendingConfirm && (
+                <motion.div
+                  initial={{ opacity: 0, y: 10 }}
+                  animate={{ opacity: 1, y: 0 }}
+                  exit={{ opacity: 0, y: 10 }}
+                  className="glass-card mb-2 p-2"
+                >
+                  <div className="flex items-start gap-2 mb-2">
+                    <AlertTriangle size={14} className="text-amber-300 shrink-0 mt-0.5" />
+                    <div className="min-w-0">
+                      <p className="text-[12px] text-white/80 font-mono break-words whitespace-pre-wrap">{pendingConfirm.summary}</p>
+                      <p className="text-[11px] text-amber-200/60 mt-0.5">This {pendingConfirm.reason}. Allow it?</p>
+                    </div>
+                  </div>
+                  {pendingConfirm.screenshot && (
+                    <img
+                      src={`data:image/jpeg;base64,${pendingConfirm.screenshot}`}
+                      alt="What's about to be clicked"
+                      className="mb-2 max-h-24 rounded-lg border border-white/10"
+                    />
+                  )}
+                  <div className="flex justify-end gap-2">
+                    <motion.button
+                      onClick={() => answerConfirm(false)}
+                      whileHover={{ scale: 1.05 }}
+                      whileTap={{ scale: 0.95 }}
+                      className="px-3 py-1 rounded-lg bg-white/10 border border-white/10 text-[11px] text-white/70 hover:text-white/90 hover:bg-white/15 transition-colors"
+                    >
+                      Decline
+                    </motion.button>
+                    <motion.button
+                      onClick={() => answerConfirm(true)}
+                      whileHover={{ scale: 1.05 }}
+                      whileTap={{ scale: 0.95 }}
+                      className="px-3 py-1 rounded-lg bg-amber-500/30 border border-amber-400/30 text-[11px] text-amber-200 hover:bg-amber-500/40 transition-colors"
+                    >
+                      Allow
+                    </motion.button>
+                  </div>
+                </motion.div>
+              )}
+            </AnimatePresence>
+
             {isRunning ? (
               <div className="glass-card p-2">
                 {/* Live activity indicator */}
@@ -1200,15 +1651,24 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
                     transition={{ duration: 1.5, repeat: Infinity, ease: "linear" }}
                   />
                 </div>
-                {/* Stop button */}
-                <button
-                  onClick={() => stop()}
-                  className="w-full flex items-center justify-center gap-2 py-1.5 rounded-lg bg-red-500/10 hover:bg-red-500/20 border border-red-500/10 hover:border-red-500/20 text-red-300/60 hover:text-red-300 transition-all text-[11px]"
-                >
-                  <Square size={11} />
-                  <span>Stop</span>
-                  <kbd className="text-[9px] text-red-300/30 bg-red-500/10 px-1 py-0.5 rounded ml-1">⌘⇧S</kbd>
-                </button>
+                {/* Stop buttons: finish the current step, or stop right away */}
+                <div className="flex gap-1.5">
+                  <button
+                    onClick={() => softStop()}
+                    className="flex-1 flex items-center justify-center gap-2 py-1.5 rounded-lg bg-white/5 hover:bg-white/10 border border-white/5 hover:border-white/10 text-white/50 hover:text-white/80 transition-all text-[11px]"
+                    title="Let the current tool finish, then stop"
+                  >
+                    <span>Finish step</span>
+                  </button>
+                  <button
+                    onClick={() => stop()}
+                    className="flex-1 flex items-center justify-center gap-2 py-1.5 rounded-lg bg-red-500/10 hover:bg-red-500/20 border border-red-500/10 hover:border-red-500/20 text-red-300/60 hover:text-red-300 transition-all text-[11px]"
+                  >
+                    <Square size={11} />
+                    <span>Stop</span>
+                    <kbd className="text-[9px] text-red-300/30 bg-red-500/10 px-1 py-0.5 rounded ml-1">⌘⇧S</kbd>
+                  </button>
+                </div>
               </div>
             ) : showVoiceConfirm ? null : (
               <div className="glass-card flex items-center gap-2 p-2">
@@ -1266,7 +1726,7 @@ export default function ChatView({ variant, settingsOpen: propSettingsOpen, onSe
                   <span className="text-[9px]">Computer</span>
                 </motion.button>
                 <motion.button
-                  onClick={handleSubmit}
+                  onClick={() => handleSubmit()}
                   disabled={!inputText.trim()}
                   whileHover={{ scale: 1.05 }}
                   whileTap={{ scale: 0.95 }}
@@ -1,4 +1,5 @@
 import { useRef, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { motion, AnimatePresence } from "framer-motion";
 import { Streamdown } from "streamdown";
 import { useAgentStore } from "../stores/agentStore";
@@ -30,8 +31,14 @@ import {
   Pause,
   Search,
   FileText,
+  FolderOpen,
+  ExternalLink,
+  ListChecks,
+  Check,
 } from "lucide-react";
 import { createAudioElement } from "../utils/audio";
+import { modelLabel } from "../utils/models";
+import { useAgent } from "../hooks/useAgent";
 
 function UrlLink({ url }: { url: string }) {
   let domain = "";
@@ -187,6 +194,11 @@ function BashBlock({ msg }: { msg: ChatMessage }) {
               >
                 {expanded ? <ChevronUp size={8} /> : <ChevronDown size={8} />}
                 output
+                {msg.bashCwd && (
+                  <span className="ml-auto font-mono truncate max-w-[70%]" title={msg.bashCwd}>
+                    {msg.bashCwd}
+                  </span>
+                )}
               </button>
             </div>
             <AnimatePresence>
@@ -323,6 +335,102 @@ function SpeakBubble({ msg }: { msg: ChatMessage }) {
   );
 }
 
+function formatFileSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+function FileBlock({ msg }: { msg: ChatMessage }) {
+  const file = msg.file;
+  const [error, setError] = useState<string | null>(null);
+  if (!file) return null;
+
+  const run = (command: "open_file" | "open_file_location") => {
+    setError(null);
+    invoke(command, { path: file.path }).catch((e) => setError(String(e)));
+  };
+
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      className="flex justify-start"
+    >
+      <div className="flex items-center gap-2 px-2.5 py-1.5 rounded-lg bg-white/5 border border-white/10 max-w-full">
+        <FileText size={14} className="text-white/50 shrink-0" />
+        <div className="min-w-0">
+          <p className="text-[13px] text-white/90 truncate" title={file.path}>{file.name}</p>
+          <p className="text-[10px] text-white/40">{formatFileSize(file.size_bytes)}</p>
+          {error && <p className="text-[10px] text-red-400">{error}</p>}
+        </div>
+        <button
+          onClick={() => run("open_file")}
+          className="flex items-center gap-1 px-2 py-0.5 rounded text-[11px] text-white/70 bg-white/10 hover:bg-white/20 transition-colors"
+        >
+          <ExternalLink size={10} />
+          Open
+        </button>
+        <button
+          onClick={() => run("open_file_location")}
+          className="text-white/40 hover:text-white/70 transition-colors"
+          title="Show in folder"
+        >
+          <FolderOpen size={14} />
+        </button>
+      </div>
+    </motion.div>
+  );
+}
+
+// dry-run plan: approving re-runs the same instructions for real
+function PlanBlock({ msg }: { msg: ChatMessage }) {
+  const plan = msg.plan;
+  const { submit } = useAgent();
+  const { isRunning } = useAgentStore();
+  const [approved, setApproved] = useState(false);
+  if (!plan) return null;
+
+  const approve = () => {
+    setApproved(true);
+    submit(plan.instructions, undefined, plan.mode, false);
+  };
+
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      className="flex justify-start"
+    >
+      <div className="px-2.5 py-2 rounded-lg bg-white/5 border border-white/10 max-w-full">
+        <div className="flex items-center gap-2 mb-1.5">
+          <ListChecks size={14} className="text-white/50 shrink-0" />
+          <p className="text-[12px] text-white/70 truncate" title={plan.instructions}>Plan: {plan.instructions}</p>
+        </div>
+        <ol className="list-decimal pl-5 space-y-0.5 mb-2">
+          {plan.steps.map((step, i) => (
+            <li key={i} className="text-[12px] text-white/85 leading-relaxed">{step}</li>
+          ))}
+        </ol>
+        {plan.preamble && (
+          <details className="mb-2">
+            <summary className="text-[11px] text-white/50 cursor-pointer select-none">Custom instructions in effect</summary>
+            <pre className="mt-1 text-[11px] text-white/60 whitespace-pre-wrap font-mono">{plan.preamble}</pre>
+          </details>
+        )}
+        <button
+          onClick={approve}
+          disabled={approved || isRunning}
+          className="flex items-center gap-1 px-2 py-0.5 rounded text-[11px] text-blue-300 bg-blue-500/20 hover:bg-blue-500/30 disabled:opacity-40 transition-colors"
+        >
+          <Check size={10} />
+          {approved ? "Approved" : "Approve plan"}
+        </button>
+      </div>
+    </motion.div>
+  );
+}
+
 function MessageBubble({ msg }: { msg: ChatMessage }) {
   const isUser = msg.role === "user";
 
@@ -338,6 +446,14 @@ function MessageBubble({ msg }: { msg: ChatMessage }) {
     return <SpeakBubble msg={msg} />;
   }
 
+  if (msg.type === "file") {
+    return <FileBlock msg={msg} />;
+  }
+
+  if (msg.type === "plan") {
+    return <PlanBlock msg={msg} />;
+  }
+
   const getIcon = () => {
     if (isUser) return null;
     switch (msg.type) {
@@ -425,6 +541,9 @@ function MessageBubble({ msg }: { msg: ChatMessage }) {
             </p>
           )}
         </div>
+        {!isUser && msg.model && (
+          <p className="mt-0.5 text-[10px] text-white/30">{modelLabel(msg.model)}</p>
+        )}
       </div>
     </motion.div>
   );
@@ -1,4 +1,4 @@
-import { useEffect, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 import {
   Check,
   ExternalLink,
@@ -14,9 +14,22 @@ import {
   Loader2,
   Mic,
   RotateCcw,
+  Download,
+  ShieldAlert,
+  Shuffle,
+  MessageSquareText,
+  AlertCircle,
+  FolderOpen,
+  Activity,
+  Wrench,
+  BarChart3,
+  BellOff,
+  Brain,
 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
+import { emit, listen } from "@tauri-apps/api/event";
 import { useAgentStore } from "../stores/agentStore";
+import type { DndStatus, MicTestReport, ModelId, UsageStats } from "../types";
 
 interface PermissionsCheck {
   accessibility: "granted" | "denied" | "notAsked" | "notNeeded";
@@ -24,10 +37,30 @@ interface PermissionsCheck {
   microphone: "granted" | "denied" | "notAsked" | "notNeeded";
 }
 
+type HealthStatus = "ok" | "warning" | "error";
+
+interface HealthReport {
+  status: HealthStatus;
+  checks: { name: string; status: HealthStatus; detail: string }[];
+}
+
+const HEALTH_COLORS: Record<HealthStatus, string> = {
+  ok: "bg-emerald-400",
+  warning: "bg-amber-400",
+  error: "bg-red-400",
+};
+
 interface BrowserProfileStatus {
   exists: boolean;
   path: string;
   sessions: string[];
+  headless: boolean;
+}
+
+interface CookieImportReport {
+  imported: string[];
+  skipped: string[];
+  cookieCount: number;
 }
 
 interface ApiKeyStatus {
@@ -36,10 +69,104 @@ interface ApiKeyStatus {
   elevenlabs: boolean;
 }
 
+interface ApiKeyValidation {
+  valid: boolean;
+  message: string;
+  tier?: string | null;
+}
+
+type ApiKeyService = keyof ApiKeyStatus;
+
+interface SettingsImportReport {
+  preferences: number;
+  systemPreamble: boolean;
+  commandPolicy: boolean;
+  rateLimits: number;
+  secrets: string[];
+}
+
+interface StoragePaths {
+  dataDir: string;
+  dbPath: string;
+  settingsPath: string;
+  pythonVenvPath: string | null;
+}
+
 interface VoiceSettings {
   elevenlabsVoiceId: string | null;
+  autoSpeakFinalResponse: boolean;
+  language: string;
+  inputDevice: string | null;
+}
+
+interface AudioInputDevice {
+  name: string;
+  isDefault: boolean;
 }
 
+type BrowserKind = "chrome" | "edge" | "firefox";
+
+interface BrowserSettings {
+  browser: BrowserKind;
+  installed: BrowserKind[];
+  headless: boolean;
+  sessionMode: "persistent" | "ephemeral";
+}
+
+interface ConfirmSettings {
+  bash: boolean;
+  computer: boolean;
+  riskyClicks: boolean;
+  riskKeywords: string[];
+}
+
+type EmbeddingBackend = "hashed" | "openai" | "voyage" | "local";
+
+interface EmbeddingSettings {
+  backend: EmbeddingBackend;
+  model: string;
+  url: string;
+  hasKey: boolean;
+}
+
+const EMBEDDING_BACKENDS: { id: EmbeddingBackend; name: string; hint: string }[] = [
+  { id: "hashed", name: "Built-in (keywords only)", hint: "Offline, no key needed" },
+  { id: "openai", name: "OpenAI", hint: "Needs OPENAI_API_KEY in .env" },
+  { id: "voyage", name: "Voyage (Anthropic's pick)", hint: "Needs VOYAGE_API_KEY in .env" },
+  { id: "local", name: "Local server (gguf)", hint: "llama-server --embedding or Ollama on this machine" },
+];
+
+interface HelpScreenshotSettings {
+  format: "jpeg" | "png";
+  area: "full" | "window" | "cursor";
+}
+
+// tried top to bottom when the selected model keeps failing
+const FALLBACK_OPTIONS: { id: ModelId; name: string }[] = [
+  { id: "claude-sonnet-4-5", name: "Sonnet 4.5" },
+  { id: "claude-opus-4-5", name: "Opus 4.5" },
+  { id: "claude-haiku-4-5-20251001", name: "Haiku 4.5" },
+];
+
+// matches TOOL_TOGGLES in tool_policy.rs
+const TOOL_OPTIONS: { id: string; name: string; description: string }[] = [
+  { id: "computer", name: "Computer control", description: "Screenshots, mouse, keyboard, windows and app launching" },
+  { id: "browser", name: "Browser", description: "Drive Chrome page by page in browser mode" },
+  { id: "bash", name: "Terminal", description: "Run shell commands" },
+  { id: "python", name: "Python", description: "Documents, charts, spreadsheets and data work" },
+  { id: "web_search", name: "Web search", description: "Quick lookups on the web" },
+  { id: "deep_research", name: "Deep research", description: "Multi-page research reports" },
+];
+
+// matches MAX_SYSTEM_PREAMBLE_CHARS in permissions.rs
+const MAX_PREAMBLE_CHARS = 2000;
+
+const BROWSER_OPTIONS: { id: BrowserKind; name: string }[] = [
+  { id: "chrome", name: "Chrome" },
+  { id: "edge", name: "Edge" },
+  { id: "firefox", name: "Firefox" },
+];
+
 function PermissionRow({
   label,
   status,
@@ -101,10 +228,12 @@ function PermissionRow({
 function ApiKeyRow({
   label,
   isSet,
+  validation,
   onSave,
 }: {
   label: string;
   isSet: boolean;
+  validation?: ApiKeyValidation;
   onSave: (key: string) => void;
 }) {
   const [editing, setEditing] = useState(false);
@@ -177,7 +306,15 @@ function ApiKeyRow({
               <span className="text-[11px] text-white/40 font-mono">
                 ••••••••••••
               </span>
-              <Check size={12} className="text-emerald-400" />
+              {validation && !validation.valid ? (
+                <span title={validation.message} className="flex">
+                  <AlertCircle size={12} className="text-red-400" />
+                </span>
+              ) : (
+                <span title={validation?.tier ? `${validation.message} (${validation.tier})` : validation?.message} className="flex">
+                  <Check size={12} className="text-emerald-400" />
+                </span>
+              )}
             </>
           ) : (
             <span className="text-[11px] text-white/30">Not set</span>
@@ -212,6 +349,42 @@ function LoadingSkeleton({ error }: { error?: string }) {
   );
 }
 
+function ToggleRow({
+  label,
+  description,
+  checked,
+  onChange,
+}: {
+  label: string;
+  description: string;
+  checked: boolean;
+  onChange: (checked: boolean) => void;
+}) {
+  return (
+    <div className="flex items-center justify-between py-2.5">
+      <div>
+        <p className="text-[13px] text-white/90">{label}</p>
+        <p className="text-[11px] text-white/40 mt-0.5">{description}</p>
+      </div>
+      <button
+        role="switch"
+        aria-checked={checked}
+        aria-label={label}
+        onClick={() => onChange(!checked)}
+        className={`relative w-8 h-[18px] rounded-full transition-colors shrink-0 ${
+          checked ? "bg-blue-500/70" : "bg-white/15"
+        }`}
+      >
+        <span
+          className={`absolute top-[2px] w-[14px] h-[14px] rounded-full bg-white transition-all ${
+            checked ? "left-[16px]" : "left-[2px]"
+          }`}
+        />
+      </button>
+    </div>
+  );
+}
+
 const VOICE_PRESETS = [
   { id: "NOpBlnGInO9m6vDvFkFC", name: "Southern Grandpa" },
   { id: "PB6BdkFkZLbI39GHdnbQ", name: "$eX¥" },
@@ -220,17 +393,478 @@ const VOICE_PRESETS = [
 
 const DEFAULT_VOICE_ID = "NOpBlnGInO9m6vDvFkFC";
 
+// tags the backend accepts for deepgram transcription
+const VOICE_LANGUAGES = [
+  { tag: "multi", name: "Auto-detect" },
+  { tag: "en-US", name: "English (US)" },
+  { tag: "en-GB", name: "English (UK)" },
+  { tag: "es", name: "Spanish" },
+  { tag: "fr", name: "French" },
+  { tag: "de", name: "German" },
+  { tag: "it", name: "Italian" },
+  { tag: "pt-BR", name: "Portuguese (Brazil)" },
+  { tag: "nl", name: "Dutch" },
+  { tag: "pl", name: "Polish" },
+  { tag: "ru", name: "Russian" },
+  { tag: "uk", name: "Ukrainian" },
+  { tag: "tr", name: "Turkish" },
+  { tag: "hi", name: "Hindi" },
+  { tag: "ja", name: "Japanese" },
+  { tag: "ko", name: "Korean" },
+];
+
 export default function SettingsContent() {
   const setApiKeySet = useAgentStore((s) => s.setApiKeySet);
   const [permissions, setPermissions] = useState<PermissionsCheck | null>(null);
   const [profile, setProfile] = useState<BrowserProfileStatus | null>(null);
   const [apiKeys, setApiKeys] = useState<ApiKeyStatus | null>(null);
+  const [keyChecks, setKeyChecks] = useState<Partial<Record<ApiKeyService, ApiKeyValidation>>>({});
   const [voiceSettings, setVoiceSettings] = useState<VoiceSettings | null>(null);
   const [resetting, setResetting] = useState(false);
+  const [debuggable, setDebuggable] = useState<boolean | null>(null);
+  const [restartingBrowser, setRestartingBrowser] = useState(false);
+  const [restartError, setRestartError] = useState<string | null>(null);
+  const [health, setHealth] = useState<HealthReport | null>(null);
+  const [checkingHealth, setCheckingHealth] = useState(false);
   const [loading, setLoading] = useState(true);
   const [loadError, setLoadError] = useState<string | null>(null);
   const [editingVoiceId, setEditingVoiceId] = useState(false);
+  const [languageError, setLanguageError] = useState<string | null>(null);
   const [voiceIdInput, setVoiceIdInput] = useState("");
+  const [browserSettings, setBrowserSettings] = useState<BrowserSettings | null>(null);
+  const [importDomain, setImportDomain] = useState("");
+  const [importing, setImporting] = useState(false);
+  const [credentialDomains, setCredentialDomains] = useState<string[]>([]);
+  const [loginDomain, setLoginDomain] = useState("");
+  const [loginUsername, setLoginUsername] = useState("");
+  const [loginPassword, setLoginPassword] = useState("");
+  const [loginSubdomains, setLoginSubdomains] = useState(false);
+  const [loginError, setLoginError] = useState<string | null>(null);
+  const [importResult, setImportResult] = useState<string | null>(null);
+  const [confirmSettings, setConfirmSettings] = useState<ConfirmSettings | null>(null);
+  const [riskKeywordsText, setRiskKeywordsText] = useState("");
+  const [humanizeMouse, setHumanizeMouse] = useState<boolean | null>(null);
+  const [ambientHelp, setAmbientHelp] = useState<boolean | null>(null);
+  const [ambientIntervalSecs, setAmbientIntervalSecs] = useState(30);
+  const [maxRunMinutes, setMaxRunMinutes] = useState<number | null>(null);
+  const [skipPythonInstall, setSkipPythonInstall] = useState<boolean | null>(null);
+  const [installCancelNote, setInstallCancelNote] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ConfirmSettings>("get_confirm_settings")
+      .then((settings) => {
+        setConfirmSettings(settings);
+        setRiskKeywordsText(settings.riskKeywords.join(", "));
+      })
+      .catch((e) => console.error("Confirm settings load failed:", e));
+    invoke<boolean>("get_humanize_mouse")
+      .then(setHumanizeMouse)
+      .catch((e) => console.error("Mouse settings load failed:", e));
+    invoke<boolean>("get_ambient_help")
+      .then(setAmbientHelp)
+      .catch((e) => console.error("Ambient help load failed:", e));
+    invoke<number>("get_ambient_interval_secs")
+      .then(setAmbientIntervalSecs)
+      .catch((e) => console.error("Ambient interval load failed:", e));
+    invoke<number>("get_max_run_minutes")
+      .then(setMaxRunMinutes)
+      .catch((e) => console.error("Run time limit load failed:", e));
+    invoke<boolean>("get_python_skip_autoinstall")
+      .then(setSkipPythonInstall)
+      .catch((e) => console.error("Python install setting load failed:", e));
+  }, []);
+
+  // stop a package install that's holding up a python step; the step runs stdlib-only
+  const handleCancelPythonInstall = async () => {
+    try {
+      const cancelled = await invoke<boolean>("cancel_python_install");
+      setInstallCancelNote(cancelled ? "Install cancelled" : "Nothing is installing");
+    } catch (e) {
+      setInstallCancelNote(`Couldn't cancel: ${e}`);
+    }
+  };
+
+  const [dnd, setDnd] = useState<DndStatus | null>(null);
+
+  useEffect(() => {
+    invoke<DndStatus>("get_do_not_disturb")
+      .then(setDnd)
+      .catch((e) => console.error("Do not disturb load failed:", e));
+    // the tray toggle and screen sharing change it too
+    const unlisten = listen<DndStatus>("dnd:changed", (event) => setDnd(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleDndOptions = async (silenceSpeech: boolean, whileSharing: boolean) => {
+    await invoke("save_do_not_disturb_options", { silenceSpeech, whileSharing }).catch((e) =>
+      console.error("Do not disturb save failed:", e),
+    );
+  };
+
+  const handleConfirmSettings = async (next: ConfirmSettings) => {
+    setConfirmSettings(next);
+    await invoke("save_confirm_settings", { ...next }).catch((e) => console.error("Confirm settings save failed:", e));
+  };
+
+  const [fallbackModels, setFallbackModels] = useState<string[] | null>(null);
+
+  useEffect(() => {
+    invoke<string[]>("get_fallback_models")
+      .then(setFallbackModels)
+      .catch((e) => console.error("Fallback models load failed:", e));
+  }, []);
+
+  // memory embeddings come from their own backend, independent of the chat model
+  const [embeddingSettings, setEmbeddingSettings] = useState<EmbeddingSettings | null>(null);
+  const [reembedStatus, setReembedStatus] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<EmbeddingSettings>("get_embedding_settings")
+      .then(setEmbeddingSettings)
+      .catch((e) => console.error("Embedding settings load failed:", e));
+  }, []);
+
+  // saving moves memories embedded by the old backend over, so vector spaces never mix
+  const handleSaveEmbeddings = async (backend: EmbeddingBackend, model: string, url: string) => {
+    try {
+      await invoke("save_embedding_settings", { backend, model, url });
+      setEmbeddingSettings(await invoke<EmbeddingSettings>("get_embedding_settings"));
+      setReembedStatus("Re-embedding memories…");
+      const moved = await invoke<number>("reembed_memories");
+      setReembedStatus(moved > 0 ? `Re-embedded ${moved} memories` : null);
+    } catch (e) {
+      setReembedStatus(String(e));
+    }
+  };
+
+  const [enabledTools, setEnabledTools] = useState<string[] | null>(null);
+
+  useEffect(() => {
+    invoke<string[]>("get_enabled_tools")
+      .then(setEnabledTools)
+      .catch((e) => console.error("Tool settings load failed:", e));
+  }, []);
+
+  // takes effect on the agent's next request
+  const handleToggleTool = async (tool: string, enabled: boolean) => {
+    const current = enabledTools ?? [];
+    const next = TOOL_OPTIONS.map((o) => o.id).filter((id) => (id === tool ? enabled : current.includes(id)));
+    setEnabledTools(next);
+    await invoke("set_enabled_tools", { tools: next }).catch((e) => console.error("Tool settings save failed:", e));
+  };
+
+  const handleToggleFallback = async (model: ModelId, enabled: boolean) => {
+    const current = fallbackModels ?? [];
+    const next = FALLBACK_OPTIONS.map((o) => o.id).filter((id) =>
+      id === model ? enabled : current.includes(id)
+    );
+    setFallbackModels(next);
+    await invoke("save_fallback_models", { models: next }).catch((e) => console.error("Fallback models save failed:", e));
+  };
+
+  // standing instructions prepended to the agent's system prompt
+  const [preamble, setPreamble] = useState("");
+  const [savedPreamble, setSavedPreamble] = useState("");
+  const [preambleError, setPreambleError] = useState<string | null>(null);
+
+  // where conversations and settings live on disk
+  const [storagePaths, setStoragePaths] = useState<StoragePaths | null>(null);
+  // debug log file verbosity
+  const [logLevel, setLogLevel] = useState("info");
+  const [logsCopied, setLogsCopied] = useState(false);
+  const [cacheCleared, setCacheCleared] = useState<number | null>(null);
+
+  useEffect(() => {
+    invoke<StoragePaths>("get_storage_paths")
+      .then(setStoragePaths)
+      .catch((e) => console.error("Storage paths load failed:", e));
+    invoke<string>("get_log_level")
+      .then(setLogLevel)
+      .catch((e) => console.error("Log level load failed:", e));
+  }, []);
+
+  // folder bash starts in and python saves relative paths to
+  const [workspace, setWorkspace] = useState("");
+  const [savedWorkspace, setSavedWorkspace] = useState("");
+  const [workspaceError, setWorkspaceError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<string>("get_workspace")
+      .then((dir) => {
+        setWorkspace(dir);
+        setSavedWorkspace(dir);
+      })
+      .catch((e) => console.error("Workspace load failed:", e));
+  }, []);
+
+  const handleSaveWorkspace = async () => {
+    try {
+      const dir = await invoke<string>("set_workspace", { path: workspace });
+      setWorkspace(dir);
+      setSavedWorkspace(dir);
+      setWorkspaceError(null);
+    } catch (e) {
+      setWorkspaceError(String(e));
+    }
+  };
+
+  // seconds before an idle expanded panel collapses to the orb (0 = never)
+  const [idleTimeout, setIdleTimeout] = useState(0);
+
+  useEffect(() => {
+    invoke<number>("get_idle_timeout")
+      .then(setIdleTimeout)
+      .catch((e) => console.error("Idle timeout load failed:", e));
+  }, []);
+
+  const handleIdleTimeoutSave = async (seconds: number) => {
+    const secs = Number.isFinite(seconds) ? Math.min(Math.max(Math.round(seconds), 0), 3600) : 0;
+    setIdleTimeout(secs);
+    try {
+      await invoke("save_idle_timeout", { seconds: secs });
+      await emit("settings:idle-timeout", secs);
+    } catch (e) {
+      console.error("Idle timeout save failed:", e);
+    }
+  };
+
+  // context screenshot taken by the help and push-to-talk hotkeys
+  const [helpScreenshot, setHelpScreenshot] = useState<HelpScreenshotSettings>({ format: "jpeg", area: "full" });
+
+  useEffect(() => {
+    invoke<HelpScreenshotSettings>("get_help_screenshot_settings")
+      .then(setHelpScreenshot)
+      .catch((e) => console.error("Help screenshot settings load failed:", e));
+  }, []);
+
+  const handleHelpScreenshotChange = async (next: HelpScreenshotSettings) => {
+    setHelpScreenshot(next);
+    try {
+      await invoke("save_help_screenshot_settings", { format: next.format, area: next.area });
+    } catch (e) {
+      console.error("Help screenshot settings save failed:", e);
+    }
+  };
+
+  // whole-settings profile, moved between machines via the clipboard
+  const [includeKeys, setIncludeKeys] = useState(false);
+  const [profileStatus, setProfileStatus] = useState<string | null>(null);
+
+  const handleExportSettings = async () => {
+    try {
+      const json = await invoke<string>("export_settings", { includeSecrets: includeKeys });
+      await navigator.clipboard.writeText(json);
+      setProfileStatus(includeKeys ? "Copied, including API keys - keep it private" : "Copied to clipboard");
+    } catch (e) {
+      setProfileStatus(String(e));
+    }
+  };
+
+  const handleImportSettings = async () => {
+    try {
+      const json = await navigator.clipboard.readText();
+      const report = await invoke<SettingsImportReport>("import_settings", { json });
+      const keys = report.secrets.length ? `, keys: ${report.secrets.join(", ")}` : "";
+      setProfileStatus(`Imported ${report.preferences} settings${keys}`);
+    } catch (e) {
+      setProfileStatus(String(e));
+    }
+  };
+
+  const handleLogLevelChange = async (level: string) => {
+    setLogLevel(level);
+    await invoke("save_log_level", { level }).catch((e) => console.error("Log level save failed:", e));
+  };
+
+  const handleCopyLogs = async () => {
+    try {
+      const logs = await invoke<string>("get_recent_logs", { lines: 500 });
+      await navigator.clipboard.writeText(logs);
+      setLogsCopied(true);
+      setTimeout(() => setLogsCopied(false), 2000);
+    } catch (e) {
+      console.error("Copy logs failed:", e);
+    }
+  };
+
+  // usage dashboard window in days, null = all time
+  const [usageDays, setUsageDays] = useState<number | null>(30);
+  const [usageStats, setUsageStats] = useState<UsageStats | null>(null);
+
+  useEffect(() => {
+    const from = usageDays === null ? null : Math.floor(Date.now() / 1000) - usageDays * 86400;
+    invoke<UsageStats>("get_usage_stats", { range: { from, to: null } })
+      .then(setUsageStats)
+      .catch((e) => console.error("Usage stats load failed:", e));
+  }, [usageDays]);
+
+  const handleClearApiCache = async () => {
+    try {
+      const removed = await invoke<number>("clear_api_cache");
+      setCacheCleared(removed);
+      setTimeout(() => setCacheCleared(null), 2000);
+    } catch (e) {
+      console.error("Clear API cache failed:", e);
+    }
+  };
+
+  useEffect(() => {
+    invoke<string>("get_system_preamble")
+      .then((text) => {
+        setPreamble(text);
+        setSavedPreamble(text);
+      })
+      .catch((e) => console.error("Custom instructions load failed:", e));
+  }, []);
+
+  const handleSavePreamble = async () => {
+    try {
+      await invoke("save_system_preamble", { preamble });
+      setSavedPreamble(preamble.trim());
+      setPreambleError(null);
+    } catch (e) {
+      setPreambleError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    invoke<BrowserSettings>("get_browser_settings")
+      .then(setBrowserSettings)
+      .catch((e) => console.error("Browser settings load failed:", e));
+  }, []);
+
+  useEffect(() => {
+    invoke<boolean>("is_browser_debuggable")
+      .then(setDebuggable)
+      .catch((e) => console.error("Browser debug check failed:", e));
+  }, []);
+
+  // on demand only - the key checks make network calls
+  const handleHealthCheck = async () => {
+    setCheckingHealth(true);
+    try {
+      setHealth(await invoke<HealthReport>("system_healthcheck"));
+    } catch (e) {
+      console.error("Health check failed:", e);
+    } finally {
+      setCheckingHealth(false);
+    }
+  };
+
+  const handleRestartBrowser = async () => {
+    setRestartingBrowser(true);
+    setRestartError(null);
+    try {
+      await invoke("restart_browser_debugging");
+    } catch (e) {
+      setRestartError(String(e));
+    } finally {
+      setDebuggable(await invoke<boolean>("is_browser_debuggable").catch(() => false));
+      setRestartingBrowser(false);
+    }
+  };
+
+  useEffect(() => {
+    invoke<string[]>("list_credential_domains")
+      .then(setCredentialDomains)
+      .catch((e) => console.error("Saved logins load failed:", e));
+  }, []);
+
+  const handleSaveLogin = async () => {
+    try {
+      await invoke("save_credential", {
+        domain: loginDomain,
+        username: loginUsername,
+        password: loginPassword,
+        includeSubdomains: loginSubdomains,
+      });
+      setCredentialDomains(await invoke<string[]>("list_credential_domains"));
+      setLoginDomain("");
+      setLoginUsername("");
+      setLoginPassword("");
+      setLoginSubdomains(false);
+      setLoginError(null);
+    } catch (e) {
+      setLoginError(String(e));
+    }
+  };
+
+  const handleDeleteLogin = async (domain: string) => {
+    await invoke("delete_credential", { domain });
+    setCredentialDomains((prev) => prev.filter((d) => d !== domain));
+  };
+
+  const handleSelectBrowser = async (browser: BrowserKind) => {
+    await invoke("save_browser_settings", { browser });
+    setBrowserSettings((prev) => (prev ? { ...prev, browser } : prev));
+  };
+
+  const handleSelectLanguage = async (language: string) => {
+    try {
+      await invoke("save_voice_language", { language });
+      setVoiceSettings((prev) => (prev ? { ...prev, language } : prev));
+      setLanguageError(null);
+    } catch (e) {
+      setLanguageError(String(e));
+    }
+  };
+
+  // mic check: a live meter from mic:level while it listens, then the report
+  const [micTesting, setMicTesting] = useState(false);
+  const [micLevel, setMicLevel] = useState(0);
+  const [micReport, setMicReport] = useState<MicTestReport | null>(null);
+  const [micError, setMicError] = useState<string | null>(null);
+
+  const [inputDevices, setInputDevices] = useState<AudioInputDevice[]>([]);
+
+  useEffect(() => {
+    invoke<AudioInputDevice[]>("list_audio_input_devices")
+      .then(setInputDevices)
+      .catch((e) => console.error("Input devices load failed:", e));
+  }, []);
+
+  // empty name = follow the system default
+  const handleSelectInputDevice = async (device: string) => {
+    const inputDevice = device || null;
+    await invoke("save_input_device", { device: inputDevice }).catch((e) => console.error("Input device save failed:", e));
+    setVoiceSettings((prev) => (prev ? { ...prev, inputDevice } : prev));
+    setMicReport(null);
+  };
+
+  const handleTestMicrophone = async () => {
+    setMicTesting(true);
+    setMicReport(null);
+    setMicError(null);
+    const unlisten = await listen<{ peak: number; rms: number }>("mic:level", (event) => setMicLevel(event.payload.peak));
+    try {
+      setMicReport(await invoke<MicTestReport>("test_microphone"));
+    } catch (e) {
+      setMicError(String(e));
+    } finally {
+      unlisten();
+      setMicLevel(0);
+      setMicTesting(false);
+    }
+  };
+
+  const handleToggleAutoSpeak = async (enabled: boolean) => {
+    await invoke("save_auto_speak_final", { enabled });
+    setVoiceSettings((prev) => (prev ? { ...prev, autoSpeakFinalResponse: enabled } : prev));
+  };
+
+  const handleToggleHeadless = async (headless: boolean) => {
+    await invoke("save_browser_headless", { headless });
+    setBrowserSettings((prev) => (prev ? { ...prev, headless } : prev));
+    setProfile((prev) => (prev ? { ...prev, headless } : prev));
+  };
+
+  const handleToggleEphemeral = async (ephemeral: boolean) => {
+    const sessionMode = ephemeral ? "ephemeral" : "persistent";
+    await invoke("save_browser_session_mode", { mode: sessionMode });
+    setBrowserSettings((prev) => (prev ? { ...prev, sessionMode } : prev));
+  };
 
   useEffect(() => {
     let cancelled = false;
@@ -329,6 +963,28 @@ export default function SettingsContent() {
     setProfile(prof);
   };
 
+  const handleImportCookies = async () => {
+    const domain = importDomain.trim();
+    if (!domain) return;
+    setImporting(true);
+    setImportResult(null);
+    try {
+      const report = await invoke<CookieImportReport>("import_cookies", { domain });
+      const parts = [`Imported ${report.cookieCount} cookies for ${report.imported.join(", ") || "no domains"}`];
+      if (report.skipped.length > 0) {
+        parts.push(`skipped (couldn't decrypt): ${report.skipped.join(", ")}`);
+      }
+      setImportResult(parts.join(" · "));
+      setImportDomain("");
+      const prof = await invoke<BrowserProfileStatus>("get_browser_profile_status");
+      setProfile(prof);
+    } catch (e) {
+      setImportResult(String(e));
+    } finally {
+      setImporting(false);
+    }
+  };
+
   const handleResetProfile = async () => {
     setResetting(true);
     try {
@@ -342,7 +998,22 @@ export default function SettingsContent() {
     }
   };
 
-  const handleSaveApiKey = async (service: string, key: string) => {
+  // re-checked whenever key status reloads; positive results are cached in the backend
+  // so re-opening settings doesn't re-hit the APIs
+  const checkApiKey = useCallback((service: ApiKeyService) => {
+    invoke<ApiKeyValidation>("validate_api_key", { service })
+      .then((result) => setKeyChecks((prev) => ({ ...prev, [service]: result })))
+      .catch((e) => console.warn(`[settings] ${service} key check failed:`, e));
+  }, []);
+
+  useEffect(() => {
+    if (!apiKeys) return;
+    (Object.keys(apiKeys) as ApiKeyService[])
+      .filter((service) => apiKeys[service])
+      .forEach(checkApiKey);
+  }, [apiKeys, checkApiKey]);
+
+  const handleSaveApiKey = async (service: ApiKeyService, key: string) => {
     await invoke("save_api_key", { service, key });
     if (service === "anthropic") {
       await invoke("set_api_key", { apiKey: key });
@@ -413,6 +1084,329 @@ export default function SettingsContent() {
         </div>
       </section>
 
+      {/* system check */}
+      <section>
+        <div className="flex items-center gap-2 mb-2">
+          <Activity size={14} className="text-white/50" />
+          <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+            System Check
+          </h3>
+          <button
+            onClick={handleHealthCheck}
+            disabled={checkingHealth}
+            className="ml-auto flex items-center gap-1 text-[11px] text-white/50 hover:text-white/80 disabled:opacity-40 transition-colors"
+          >
+            {checkingHealth ? <Loader2 size={11} className="animate-spin" /> : <RefreshCw size={11} />}
+            {health ? "Check again" : "Run check"}
+          </button>
+        </div>
+        {health && (
+          <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 divide-y divide-white/5">
+            {health.checks.map((check) => (
+              <div key={check.name} className="flex items-start gap-2.5 py-2.5">
+                <span className={`mt-1.5 w-1.5 h-1.5 rounded-full shrink-0 ${HEALTH_COLORS[check.status]}`} />
+                <div className="min-w-0">
+                  <div className="text-[12px] text-white/80">{check.name}</div>
+                  <div className="text-[11px] text-white/40 break-words">{check.detail}</div>
+                </div>
+              </div>
+            ))}
+          </div>
+        )}
+      </section>
+
+      {/* safety */}
+      <section>
+        <div className="flex items-center gap-2 mb-2">
+          <ShieldAlert size={14} className="text-white/50" />
+          <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+            Safety
+          </h3>
+        </div>
+        <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 divide-y divide-white/5">
+          {confirmSettings && (
+            <>
+              <ToggleRow
+                label="Confirm destructive commands"
+                description="Ask before rm, mv, dd, mkfs, killall and similar"
+                checked={confirmSettings.bash}
+                onChange={(bash) => handleConfirmSettings({ ...confirmSettings, bash })}
+              />
+              <ToggleRow
+                label="Confirm clicks"
+                description="Ask before every click and app-quitting shortcut"
+                checked={confirmSettings.computer}
+                onChange={(computer) => handleConfirmSettings({ ...confirmSettings, computer })}
+              />
+              {!confirmSettings.computer && (
+                <ToggleRow
+                  label="Confirm risky clicks"
+                  description="Ask, with a crop of the button, before clicking text that matches a keyword below"
+                  checked={confirmSettings.riskyClicks}
+                  onChange={(riskyClicks) => handleConfirmSettings({ ...confirmSettings, riskyClicks })}
+                />
+              )}
+              {!confirmSettings.computer && confirmSettings.riskyClicks && (
+                <div className="py-2.5">
+                  <input
+                    value={riskKeywordsText}
+                    onChange={(e) => setRiskKeywordsText(e.target.value)}
+                    onBlur={() => {
+                      const riskKeywords = riskKeywordsText.split(",").map((k) => k.trim()).filter(Boolean);
+                      if (riskKeywords.length > 0) handleConfirmSettings({ ...confirmSettings, riskKeywords });
+                    }}
+                    placeholder="delete, remove, confirm, purchase, pay"
+                    aria-label="Risk keywords"
+                    className="w-full px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 placeholder:text-white/30 outline-none focus:border-white/20"
+                  />
+                </div>
+              )}
+            </>
+          )}
+          {humanizeMouse !== null && (
+            <ToggleRow
+              label="Human-like mouse movement"
+              description="Glide the cursor to each click instead of jumping - slower, but triggers hover menus"
+              checked={humanizeMouse}
+              onChange={async (enabled) => {
+                await invoke("save_humanize_mouse", { enabled });
+                setHumanizeMouse(enabled);
+              }}
+            />
+          )}
+          {ambientHelp !== null && (
+            <ToggleRow
+              label="Offer help on stuck screens"
+              description={`While idle, glance at the screen every ${ambientIntervalSecs}s and offer help if an error stays up. Checked on this Mac only - nothing is sent unless you ask`}
+              checked={ambientHelp}
+              onChange={async (enabled) => {
+                await invoke("save_ambient_help", { enabled });
+                setAmbientHelp(enabled);
+                await emit("settings:ambient-help", enabled);
+              }}
+            />
+          )}
+          {maxRunMinutes !== null && (
+            <div className="flex items-center justify-between gap-3 py-2.5">
+              <div>
+                <p className="text-[13px] text-white/90">Run time limit</p>
+                <p className="text-[11px] text-white/40 mt-0.5">
+                  Minutes a task may run before it stops after the current step (0 = no limit)
+                </p>
+              </div>
+              <input
+                type="number"
+                aria-label="Run time limit in minutes"
+                min={0}
+                value={maxRunMinutes}
+                onChange={(e) => setMaxRunMinutes(Math.max(0, Math.floor(Number(e.target.value))))}
+                onBlur={() =>
+                  invoke("save_max_run_minutes", { minutes: maxRunMinutes }).catch((e) =>
+                    console.error("Run time limit save failed:", e)
+                  )
+                }
+                className="w-20 px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30"
+              />
+            </div>
+          )}
+          {skipPythonInstall !== null && (
+            <ToggleRow
+              label="Skip Python package installs"
+              description="Run Python with what's already installed instead of fetching missing document and chart libraries"
+              checked={skipPythonInstall}
+              onChange={async (enabled) => {
+                await invoke("save_python_skip_autoinstall", { enabled });
+                setSkipPythonInstall(enabled);
+              }}
+            />
+          )}
+          <div className="flex items-center justify-between gap-3 py-2.5">
+            <div>
+              <p className="text-[13px] text-white/90">Cancel package install</p>
+              <p className="text-[11px] text-white/40 mt-0.5">
+                {installCancelNote ?? "Stop a running install; the Python step goes ahead without the packages"}
+              </p>
+            </div>
+            <button
+              onClick={handleCancelPythonInstall}
+              className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors"
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </section>
+
+      {/* do not disturb */}
+      {dnd && (
+        <section>
+          <div className="flex items-center gap-2 mb-2">
+            <BellOff size={14} className="text-white/50" />
+            <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+              Do Not Disturb
+            </h3>
+          </div>
+          <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 divide-y divide-white/5">
+            <ToggleRow
+              label="Do not disturb"
+              description="Mute push-to-talk, capture and activity sounds. Also in the tray menu"
+              checked={dnd.enabled}
+              onChange={(enabled) =>
+                invoke("set_do_not_disturb", { enabled }).catch((e) => console.error("Do not disturb save failed:", e))
+              }
+            />
+            <ToggleRow
+              label="Mute spoken replies"
+              description="Voice mode answers in text only while do not disturb is on"
+              checked={dnd.silenceSpeech}
+              onChange={(silenceSpeech) => handleDndOptions(silenceSpeech, dnd.whileSharing)}
+            />
+            <ToggleRow
+              label="Turn on while screen sharing"
+              description="Zoom and macOS Screen Sharing are detected; calls in a browser tab aren't"
+              checked={dnd.whileSharing}
+              onChange={(whileSharing) => handleDndOptions(dnd.silenceSpeech, whileSharing)}
+            />
+          </div>
+        </section>
+      )}
+
+      {/* tools */}
+      <section>
+        <div className="flex items-center gap-2 mb-2">
+          <Wrench size={14} className="text-white/50" />
+          <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+            Tools
+          </h3>
+        </div>
+        <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 divide-y divide-white/5">
+          {enabledTools &&
+            TOOL_OPTIONS.map((option) => (
+              <ToggleRow
+                key={option.id}
+                label={option.name}
+                description={option.description}
+                checked={enabledTools.includes(option.id)}
+                onChange={(enabled) => handleToggleTool(option.id, enabled)}
+              />
+            ))}
+        </div>
+      </section>
+
+      {/* model fallback */}
+      <section>
+        <div className="flex items-center gap-2 mb-2">
+          <Shuffle size={14} className="text-white/50" />
+          <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+            Model Fallback
+          </h3>
+        </div>
+        <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 divide-y divide-white/5">
+          {fallbackModels &&
+            FALLBACK_OPTIONS.map((option) => (
+              <ToggleRow
+                key={option.id}
+                label={option.name}
+                description="Switch to this model if the selected one stays overloaded"
+                checked={fallbackModels.includes(option.id)}
+                onChange={(enabled) => handleToggleFallback(option.id, enabled)}
+              />
+            ))}
+        </div>
+      </section>
+
+      {/* memory embeddings */}
+      <section>
+        <div className="flex items-center gap-2 mb-2">
+          <Brain size={14} className="text-white/50" />
+          <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+            Memory Embeddings
+          </h3>
+        </div>
+        {embeddingSettings && (
+          <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 py-3 space-y-2">
+            <div className="flex items-center justify-between gap-2">
+              <div>
+                <p className="text-[13px] text-white/90">Backend</p>
+                <p className="text-[11px] text-white/40 mt-0.5">
+                  {EMBEDDING_BACKENDS.find((b) => b.id === embeddingSettings.backend)?.hint}
+                  {!embeddingSettings.hasKey && " - key missing, using built-in"}
+                </p>
+              </div>
+              <select
+                aria-label="Embedding backend"
+                value={embeddingSettings.backend}
+                // a new backend starts from its own default model and endpoint
+                onChange={(e) => handleSaveEmbeddings(e.target.value as EmbeddingBackend, "", "")}
+                className="px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30 cursor-pointer"
+              >
+                {EMBEDDING_BACKENDS.map((backend) => (
+                  <option key={backend.id} value={backend.id} className="bg-zinc-900">
+                    {backend.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+            {embeddingSettings.backend !== "hashed" && (
+              <div className="flex gap-2">
+                <input
+                  value={embeddingSettings.model}
+                  onChange={(e) => setEmbeddingSettings({ ...embeddingSettings, model: e.target.value })}
+                  onBlur={() => handleSaveEmbeddings(embeddingSettings.backend, embeddingSettings.model, embeddingSettings.url)}
+                  aria-label="Embedding model"
+                  className="w-1/3 px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 outline-none focus:border-white/20"
+                />
+                <input
+                  value={embeddingSettings.url}
+                  onChange={(e) => setEmbeddingSettings({ ...embeddingSettings, url: e.target.value })}
+                  onBlur={() => handleSaveEmbeddings(embeddingSettings.backend, embeddingSettings.model, embeddingSettings.url)}
+                  aria-label="Embedding endpoint"
+                  className="flex-1 px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 outline-none focus:border-white/20"
+                />
+              </div>
+            )}
+            {reembedStatus && <p className="text-[10px] text-white/50">{reembedStatus}</p>}
+          </div>
+        )}
+      </section>
+
+      {/* custom instructions */}
+      <section>
+        <div className="flex items-center gap-2 mb-2">
+          <MessageSquareText size={14} className="text-white/50" />
+          <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+            Custom Instructions
+          </h3>
+        </div>
+        <div className="rounded-xl bg-white/[0.03] border border-white/5 px-4 py-3 space-y-2">
+          <textarea
+            value={preamble}
+            onChange={(e) => setPreamble(e.target.value)}
+            maxLength={MAX_PREAMBLE_CHARS}
+            rows={4}
+            placeholder={"e.g. Always use metric units.\nPrefer Firefox for browsing."}
+            aria-label="Custom instructions"
+            className="w-full px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 placeholder-white/30 focus:outline-none focus:border-white/30 resize-y"
+          />
+          <div className="flex items-center justify-between">
+            <span className="text-[10px] text-white/40">
+              {preamble.length}/{MAX_PREAMBLE_CHARS}
+            </span>
+            <button
+              onClick={handleSavePreamble}
+              disabled={preamble.trim() === savedPreamble}
+              className="px-2 py-1 text-[10px] rounded-md bg-emerald-500/20 hover:bg-emerald-500/30 text-emerald-400 transition-colors disabled:opacity-50"
+            >
+              Save
+            </button>
+          </div>
+          {preambleError && <p className="text-[10px] text-red-400">{preambleError}</p>}
+        </div>
+        <p className="text-[10px] text-white/40 mt-2 px-1">
+          Added to every task as standing preferences; they can't override how the agent uses its tools
+        </p>
+      </section>
+
       {/* api keys */}
       <section>
         <div className="flex items-center gap-2 mb-2">
@@ -427,16 +1421,19 @@ export default function SettingsContent() {
               <ApiKeyRow
                 label="Anthropic"
                 isSet={apiKeys.anthropic}
+                validation={keyChecks.anthropic}
                 onSave={(key) => handleSaveApiKey("anthropic", key)}
               />
               <ApiKeyRow
                 label="Deepgram"
                 isSet={apiKeys.deepgram}
+                validation={keyChecks.deepgram}
                 onSave={(key) => handleSaveApiKey("deepgram", key)}
               />
               <ApiKeyRow
                 label="ElevenLabs"
                 isSet={apiKeys.elevenlabs}
+                validation={keyChecks.elevenlabs}
                 onSave={(key) => handleSaveApiKey("elevenlabs", key)}
               />
             </>
@@ -525,6 +1522,87 @@ export default function SettingsContent() {
               </button>
             </div>
           )}
+
+          <div className="flex items-center justify-between">
+            <span className="text-[13px] text-white/90">Language</span>
+            <select
+              aria-label="Voice language"
+              value={voiceSettings?.language ?? "multi"}
+              onChange={(e) => handleSelectLanguage(e.target.value)}
+              className="px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30 cursor-pointer"
+            >
+              {VOICE_LANGUAGES.map((language) => (
+                <option key={language.tag} value={language.tag} className="bg-zinc-900">
+                  {language.name}
+                </option>
+              ))}
+              {voiceSettings && !VOICE_LANGUAGES.some((l) => l.tag === voiceSettings.language) && (
+                <option value={voiceSettings.language} className="bg-zinc-900">{voiceSettings.language}</option>
+              )}
+            </select>
+          </div>
+          {languageError && <p className="text-[10px] text-red-400">{languageError}</p>}
+
+          {voiceSettings && (
+            <ToggleRow
+              label="Speak final summary"
+              description="Read the final answer aloud in voice mode, even if the agent didn't speak it"
+              checked={voiceSettings.autoSpeakFinalResponse}
+              onChange={handleToggleAutoSpeak}
+            />
+          )}
+
+          <div className="flex items-center justify-between">
+            <span className="text-[13px] text-white/90">Input device</span>
+            <select
+              aria-label="Input device"
+              value={voiceSettings?.inputDevice ?? ""}
+              onChange={(e) => handleSelectInputDevice(e.target.value)}
+              className="max-w-[60%] px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30 cursor-pointer"
+            >
+              <option value="" className="bg-zinc-900">System default</option>
+              {inputDevices.map((device) => (
+                <option key={device.name} value={device.name} className="bg-zinc-900">
+                  {device.name}
+                  {device.isDefault ? " (default)" : ""}
+                </option>
+              ))}
+              {voiceSettings?.inputDevice && !inputDevices.some((d) => d.name === voiceSettings.inputDevice) && (
+                <option value={voiceSettings.inputDevice} className="bg-zinc-900">
+                  {voiceSettings.inputDevice} (not connected)
+                </option>
+              )}
+            </select>
+          </div>
+
+          <div className="space-y-1.5">
+            <div className="flex items-center justify-between gap-3">
+              <span className="text-[13px] text-white/90">Microphone</span>
+              <div className="flex-1 h-1.5 rounded-full bg-white/10 overflow-hidden">
+                <div
+                  className="h-full bg-emerald-400/70 transition-[width] duration-100"
+                  style={{ width: `${Math.min(100, Math.round(micLevel * 100))}%` }}
+                />
+              </div>
+              <button
+                onClick={handleTestMicrophone}
+                disabled={micTesting}
+                className="flex items-center gap-1.5 px-2 py-1 text-[11px] rounded-md bg-white/10 hover:bg-white/15 text-white/80 transition-colors disabled:opacity-50"
+              >
+                {micTesting ? <Loader2 size={11} className="animate-spin" /> : <Mic size={11} />}
+                {micTesting ? "Listening…" : "Test"}
+              </button>
+            </div>
+            {micReport && (
+              <p className={`text-[10px] ${micReport.error || micReport.silent ? "text-amber-300/80" : "text-white/50"}`}>
+                {micReport.error ??
+                  (micReport.silent
+                    ? `Nothing heard from ${micReport.device ?? "the input"} - is it muted, or the wrong device?`
+                    : `${micReport.device ?? "Input"} works - peak ${Math.round(micReport.peak * 100)}%`)}
+              </p>
+            )}
+            {micError && <p className="text-[10px] text-red-400">{micError}</p>}
+          </div>
         </div>
       </section>
 
@@ -542,6 +1620,71 @@ export default function SettingsContent() {
             the agent will use those sessions.
           </p>
 
+          {browserSettings && (
+            <div className="flex items-center gap-1.5 mb-3">
+              <span className="text-[10px] text-white/50 uppercase tracking-wider mr-1">Browser</span>
+              {BROWSER_OPTIONS.map((option) => {
+                const installed = browserSettings.installed.includes(option.id);
+                const selected = browserSettings.browser === option.id;
+                return (
+                  <button
+                    key={option.id}
+                    onClick={() => handleSelectBrowser(option.id)}
+                    disabled={!installed && !selected}
+                    title={installed ? undefined : "Not installed"}
+                    className={`px-2 py-1 text-[11px] rounded-md transition-colors disabled:opacity-30 ${
+                      selected
+                        ? "bg-white/20 text-white"
+                        : "bg-white/5 text-white/60 hover:bg-white/10 hover:text-white/80"
+                    }`}
+                  >
+                    {option.name}
+                  </button>
+                );
+              })}
+            </div>
+          )}
+
+          {browserSettings && (
+            <div className="mb-3">
+              <ToggleRow
+                label="Run headless"
+                description="No browser window - for long or overnight jobs. Applies on the next launch"
+                checked={browserSettings.headless}
+                onChange={handleToggleHeadless}
+              />
+              <ToggleRow
+                label="Fresh profile each run"
+                description="Start every task signed out and delete its cookies and logins afterwards. Applies on the next launch"
+                checked={browserSettings.sessionMode === "ephemeral"}
+                onChange={handleToggleEphemeral}
+              />
+            </div>
+          )}
+
+          <div className="mb-3">
+            <div className="flex items-center justify-between gap-2">
+              <span className="text-[11px] text-white/60">
+                {debuggable === null
+                  ? "Checking debugging connection..."
+                  : debuggable
+                    ? "Debugging connection ready"
+                    : "Browser isn't running with debugging - tasks will restart it"}
+              </span>
+              <button
+                onClick={handleRestartBrowser}
+                disabled={restartingBrowser}
+                className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/80 hover:text-white text-[11px] transition-colors disabled:opacity-50"
+              >
+                <RefreshCw size={11} className={restartingBrowser ? "animate-spin" : undefined} />
+                Restart with debugging
+              </button>
+            </div>
+            {restartError && (
+              <p className="mt-1.5 text-[10px] text-red-400/80 leading-relaxed">{restartError}</p>
+            )}
+          </div>
+
           {profile?.exists && profile.sessions.length > 0 && (
             <div className="mb-3">
               <div className="flex items-center justify-between mb-1.5">
@@ -578,6 +1721,92 @@ export default function SettingsContent() {
             </div>
           )}
 
+          <div className="mb-3">
+            <div className="flex gap-2">
+              <input
+                value={importDomain}
+                onChange={(e) => setImportDomain(e.target.value)}
+                onKeyDown={(e) => e.key === "Enter" && handleImportCookies()}
+                placeholder="Import login from Chrome, e.g. github.com"
+                className="flex-1 px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 placeholder:text-white/30 outline-none focus:border-white/20"
+              />
+              <button
+                onClick={handleImportCookies}
+                disabled={importing || !importDomain.trim()}
+                className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/80 hover:text-white text-[11px] transition-colors disabled:opacity-50"
+              >
+                {importing ? <RefreshCw size={11} className="animate-spin" /> : <Download size={11} />}
+                Import
+              </button>
+            </div>
+            {importResult && (
+              <p className="mt-1.5 text-[10px] text-white/50 leading-relaxed">{importResult}</p>
+            )}
+          </div>
+
+          <div className="mb-3">
+            <span className="text-[10px] text-white/50 uppercase tracking-wider">
+              Saved logins ({credentialDomains.length})
+            </span>
+            <p className="mt-1 mb-1.5 text-[10px] text-white/40 leading-relaxed">
+              Stored in your system keychain. The agent fills these in on https login pages for the exact domain without ever seeing them.
+            </p>
+            {credentialDomains.length > 0 && (
+              <div className="mb-1.5 max-h-[100px] overflow-y-auto rounded-lg bg-black/30 divide-y divide-white/10">
+                {credentialDomains.map((domain) => (
+                  <div key={domain} className="flex items-center justify-between px-2 py-1.5 group">
+                    <span className="text-[11px] text-white/80">{domain}</span>
+                    <button
+                      onClick={() => handleDeleteLogin(domain)}
+                      className="p-1 rounded text-white/40 hover:text-red-400 hover:bg-red-500/20 transition-colors opacity-0 group-hover:opacity-100"
+                      title="Remove login"
+                    >
+                      <X size={12} />
+                    </button>
+                  </div>
+                ))}
+              </div>
+            )}
+            <div className="flex gap-2">
+              <input
+                value={loginDomain}
+                onChange={(e) => setLoginDomain(e.target.value)}
+                placeholder="github.com"
+                className="w-[30%] px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 placeholder:text-white/30 outline-none focus:border-white/20"
+              />
+              <input
+                value={loginUsername}
+                onChange={(e) => setLoginUsername(e.target.value)}
+                placeholder="Username"
+                autoComplete="off"
+                className="flex-1 min-w-0 px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 placeholder:text-white/30 outline-none focus:border-white/20"
+              />
+              <input
+                type="password"
+                value={loginPassword}
+                onChange={(e) => setLoginPassword(e.target.value)}
+                onKeyDown={(e) => e.key === "Enter" && handleSaveLogin()}
+                placeholder="Password"
+                autoComplete="new-password"
+                className="flex-1 min-w-0 px-2 py-1.5 rounded-lg bg-black/30 border border-white/10 text-[11px] text-white/80 placeholder:text-white/30 outline-none focus:border-white/20"
+              />
+              <button
+                onClick={handleSaveLogin}
+                disabled={!loginDomain.trim() || !loginPassword}
+                className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/80 hover:text-white text-[11px] transition-colors disabled:opacity-50"
+              >
+                Save
+              </button>
+            </div>
+            <label className="mt-1.5 flex items-center gap-1.5 text-[11px] text-white/50 cursor-pointer">
+              <input type="checkbox" checked={loginSubdomains} onChange={(e) => setLoginSubdomains(e.target.checked)} />
+              Also fill on subdomains
+            </label>
+            {loginError && (
+              <p className="mt-1.5 text-[10px] text-red-400/80 leading-relaxed">{loginError}</p>
+            )}
+          </div>
+
           <div className="flex gap-2">
             <button
               onClick={handleOpenProfile}
@@ -620,9 +1849,212 @@ export default function SettingsContent() {
               </kbd>
             </div>
           ))}
+          <div className="flex items-center justify-between pt-2 border-t border-white/5">
+            <div>
+              <p className="text-[13px] text-white/90">Context Screenshot</p>
+              <p className="text-[11px] text-white/40 mt-0.5">
+                PNG keeps small text sharp; a window or cursor crop sends less
+              </p>
+            </div>
+            <div className="flex gap-2">
+              <select
+                aria-label="Context screenshot area"
+                value={helpScreenshot.area}
+                onChange={(e) =>
+                  handleHelpScreenshotChange({ ...helpScreenshot, area: e.target.value as HelpScreenshotSettings["area"] })
+                }
+                className="px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30 cursor-pointer"
+              >
+                <option value="full" className="bg-zinc-900">Full screen</option>
+                <option value="window" className="bg-zinc-900">Active window</option>
+                <option value="cursor" className="bg-zinc-900">Around cursor</option>
+              </select>
+              <select
+                aria-label="Context screenshot format"
+                value={helpScreenshot.format}
+                onChange={(e) =>
+                  handleHelpScreenshotChange({ ...helpScreenshot, format: e.target.value as HelpScreenshotSettings["format"] })
+                }
+                className="px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30 cursor-pointer"
+              >
+                <option value="jpeg" className="bg-zinc-900">JPEG</option>
+                <option value="png" className="bg-zinc-900">PNG</option>
+              </select>
+            </div>
+          </div>
         </div>
       </section>
 
+      {/* usage */}
+      {usageStats && (
+        <section>
+          <div className="flex items-center justify-between mb-2">
+            <div className="flex items-center gap-2">
+              <BarChart3 size={14} className="text-white/50" />
+              <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+                Usage
+              </h3>
+            </div>
+            <select
+              aria-label="Usage range"
+              value={usageDays ?? "all"}
+              onChange={(e) => setUsageDays(e.target.value === "all" ? null : Number(e.target.value))}
+              className="px-2 py-1 text-[11px] bg-white/5 border border-white/10 rounded-md text-white/70 focus:outline-none focus:border-white/30 cursor-pointer"
+            >
+              <option value={7} className="bg-zinc-900">Last 7 days</option>
+              <option value={30} className="bg-zinc-900">Last 30 days</option>
+              <option value="all" className="bg-zinc-900">All time</option>
+            </select>
+          </div>
+          <div className="rounded-xl bg-white/[0.03] border border-white/5 p-4 space-y-3">
+            <div className="grid grid-cols-3 gap-3">
+              {[
+                { label: "Spent", value: `$${usageStats.totals.costUsd.toFixed(2)}` },
+                { label: "Runs", value: String(usageStats.runs) },
+                {
+                  label: "Avg run",
+                  value: usageStats.averageRunMs === null ? "-" : `${(usageStats.averageRunMs / 1000).toFixed(1)}s`,
+                },
+              ].map(({ label, value }) => (
+                <div key={label}>
+                  <p className="text-[11px] text-white/40">{label}</p>
+                  <p className="text-[15px] text-white/90 tabular-nums">{value}</p>
+                </div>
+              ))}
+            </div>
+            {usageStats.byModel.map(({ model, turns, usage }) => (
+              <div key={model} className="flex items-center justify-between text-[11px]">
+                <span className="text-white/70 font-mono truncate">{model}</span>
+                <span className="text-white/40 tabular-nums shrink-0 ml-2">
+                  {turns} calls · {((usage.inputTokens + usage.outputTokens + usage.cacheReadTokens + usage.cacheWriteTokens) / 1000).toFixed(0)}k tokens · ${usage.costUsd.toFixed(2)}
+                </span>
+              </div>
+            ))}
+            {Object.keys(usageStats.toolCalls).length > 0 && (
+              <p className="text-[11px] text-white/40">
+                {Object.entries(usageStats.toolCalls)
+                  .sort(([, a], [, b]) => b - a)
+                  .map(([tool, count]) => `${tool} ${count}`)
+                  .join(" · ")}
+              </p>
+            )}
+          </div>
+        </section>
+      )}
+
+      {/* storage */}
+      {storagePaths && (
+        <section>
+          <div className="flex items-center gap-2 mb-2">
+            <FolderOpen size={14} className="text-white/50" />
+            <h3 className="text-[11px] font-medium uppercase tracking-wider text-white/50">
+              Storage
+            </h3>
+          </div>
+          <div className="rounded-xl bg-white/[0.03] border border-white/5 p-4 space-y-3">
+            {[
+              { label: "Conversations", path: storagePaths.dbPath },
+              { label: "Settings", path: storagePaths.settingsPath },
+              { label: "Python venv", path: storagePaths.pythonVenvPath ?? "System python3" },
+            ].map(({ label, path }) => (
+              <div key={label}>
+                <p className="text-[13px] text-white/90">{label}</p>
+                <p className="text-[11px] text-white/40 mt-0.5 font-mono break-all select-text">{path}</p>
+              </div>
+            ))}
+            <div>
+              <p className="text-[13px] text-white/90">Workspace</p>
+              <p className="text-[11px] text-white/40 mt-0.5">
+                Where terminal commands start and Python saves files given without a folder
+              </p>
+              <div className="flex items-center gap-2 mt-2">
+                <input
+                  value={workspace}
+                  onChange={(e) => setWorkspace(e.target.value)}
+                  aria-label="Workspace folder"
+                  className="flex-1 min-w-0 px-2 py-1.5 text-[12px] font-mono bg-white/5 border border-white/10 rounded-md text-white/90 placeholder-white/30 focus:outline-none focus:border-white/30"
+                />
+                <button
+                  onClick={handleSaveWorkspace}
+                  disabled={workspace.trim() === "" || workspace === savedWorkspace}
+                  className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                >
+                  Save
+                </button>
+              </div>
+              {workspaceError && <p className="text-[10px] text-red-400 mt-1.5">{workspaceError}</p>}
+            </div>
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="text-[13px] text-white/90">Log Level</p>
+                <p className="text-[11px] text-white/40 mt-0.5">
+                  How much goes into the debug log file
+                </p>
+              </div>
+              <select
+                aria-label="Log level"
+                value={logLevel}
+                onChange={(e) => handleLogLevelChange(e.target.value)}
+                className="px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30 cursor-pointer"
+              >
+                {["error", "warn", "info", "debug"].map((level) => (
+                  <option key={level} value={level} className="bg-zinc-900">
+                    {level}
+                  </option>
+                ))}
+              </select>
+            </div>
+            <div>
+              <p className="text-[13px] text-white/90">Settings Profile</p>
+              <p className="text-[11px] text-white/40 mt-0.5">
+                Copy all settings as JSON, or import a profile from the clipboard
+              </p>
+              <div className="flex items-center gap-2 mt-2">
+                <button
+                  onClick={handleExportSettings}
+                  className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors"
+                >
+                  Export
+                </button>
+                <button
+                  onClick={handleImportSettings}
+                  className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors"
+                >
+                  Import
+                </button>
+                <label className="flex items-center gap-1.5 text-[11px] text-white/50 cursor-pointer">
+                  <input type="checkbox" checked={includeKeys} onChange={(e) => setIncludeKeys(e.target.checked)} />
+                  Include API keys
+                </label>
+              </div>
+              {profileStatus && <p className="text-[11px] text-white/40 mt-1.5">{profileStatus}</p>}
+            </div>
+            <div className="flex items-center gap-2">
+              <button
+                onClick={() =>
+                  invoke("open_data_folder").catch((e) => console.error("Open data folder failed:", e))
+                }
+                className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors"
+              >
+                Open Data Folder
+              </button>
+              <button
+                onClick={handleCopyLogs}
+                className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors"
+              >
+                {logsCopied ? "Copied" : "Copy Recent Logs"}
+              </button>
+              <button
+                onClick={handleClearApiCache}
+                className="px-3 py-1.5 rounded-lg bg-white/10 hover:bg-white/15 text-white/70 hover:text-white text-[11px] transition-colors"
+              >
+                {cacheCleared === null ? "Clear API Cache" : `Cleared ${cacheCleared}`}
+              </button>
+            </div>
+          </div>
+        </section>
+      )}
+
       {/* reset onboarding */}
       <section>
         <div className="flex items-center gap-2 mb-2">
@@ -631,7 +2063,25 @@ export default function SettingsContent() {
             Setup
           </h3>
         </div>
-        <div className="rounded-xl bg-white/[0.03] border border-white/5 p-4">
+        <div className="rounded-xl bg-white/[0.03] border border-white/5 p-4 space-y-3">
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-[13px] text-white/90">Auto-collapse Panel</p>
+              <p className="text-[11px] text-white/40 mt-0.5">
+                Seconds of inactivity before the panel shrinks back (0 = never)
+              </p>
+            </div>
+            <input
+              type="number"
+              aria-label="Idle timeout in seconds"
+              min={0}
+              max={3600}
+              value={idleTimeout}
+              onChange={(e) => setIdleTimeout(Number(e.target.value))}
+              onBlur={(e) => handleIdleTimeoutSave(Number(e.target.value))}
+              className="w-20 px-2 py-1.5 text-[12px] bg-white/5 border border-white/10 rounded-md text-white/90 focus:outline-none focus:border-white/30"
+            />
+          </div>
           <div className="flex items-center justify-between">
             <div>
               <p className="text-[13px] text-white/90">Show Onboarding Again</p>
@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
 import { motion, AnimatePresence } from "framer-motion";
 import { 
   Bot, 
@@ -10,10 +11,11 @@ import {
   ChevronDown,
   ChevronUp,
   Trash2,
+  Square,
   X
 } from "lucide-react";
 import { useSwarmStore } from "../stores/swarmStore";
-import { SwarmTask, AgentType } from "../types";
+import { SwarmTask, AgentType, SwarmProgress, SwarmSubtaskStatus, SwarmVerification } from "../types";
 
 const agentColors: Record<AgentType, string> = {
   Planner: "bg-blue-500/20 text-blue-300 border-blue-500/30",
@@ -73,14 +75,23 @@ export default function SwarmPanel({ isOpen, onClose }: SwarmPanelProps) {
         const { task_id, subtask_id, error } = e.payload;
         handleSwarmEvent({ type: "subtask_failed", task_id, subtask_id, error });
       }),
-      listen<{ task_id: string; subtask_id: string; passed: boolean; score: number }>("swarm:verification", (e) => {
-        const { task_id, subtask_id, passed, score } = e.payload;
-        handleSwarmEvent({ type: "verification", task_id, subtask_id, passed, score });
+      listen<{ task_id: string; subtask_id: string } & SwarmVerification>("swarm:verification", (e) => {
+        const { task_id, subtask_id, ...verification } = e.payload;
+        const { passed, score } = verification;
+        handleSwarmEvent({ type: "verification", task_id, subtask_id, passed, score, verification });
       }),
       listen<{ task_id: string; subtask_id: string; strategy: string }>("swarm:recovery", (e) => {
         const { task_id, subtask_id, strategy } = e.payload;
         handleSwarmEvent({ type: "recovery", task_id, subtask_id, strategy });
       }),
+      listen<{ task_id: string } & SwarmProgress>("swarm:progress", (e) => {
+        const { task_id, ...progress } = e.payload;
+        handleSwarmEvent({ type: "progress", task_id, progress });
+      }),
+      listen<{ task_id: string; subtask_id: string; description: string; status: SwarmSubtaskStatus }>("swarm:subtask_status", (e) => {
+        const { task_id, subtask_id, description, status } = e.payload;
+        handleSwarmEvent({ type: "subtask_status", task_id, subtask_id, description, status });
+      }),
       listen<{ task_id: string; success: boolean }>("swarm:task_completed", (e) => {
         const { task_id, success } = e.payload;
         handleSwarmEvent({ type: "task_completed", task_id, success });
@@ -236,11 +247,48 @@ interface TaskCardProps {
   isCompleted?: boolean;
 }
 
+// before/after thumbnails the verifier compared, with the measured pixel change
+function VerificationDiff({ verification }: { verification: SwarmVerification }) {
+  return (
+    <div className="px-2 pb-2">
+      <div className="grid grid-cols-2 gap-1">
+        {[
+          ["Before", verification.before_screenshot],
+          ["After", verification.after_screenshot],
+        ].map(([label, src]) => (
+          <div key={label}>
+            <img
+              src={`data:image/jpeg;base64,${src}`}
+              alt={`${label} screenshot`}
+              className="w-full rounded border border-white/10"
+            />
+            <span className="text-[9px] text-white/30">{label}</span>
+          </div>
+        ))}
+      </div>
+      {verification.pixel_diff != null && (
+        <p className="text-[9px] text-white/40 mt-0.5">
+          {(verification.pixel_diff * 100).toFixed(1)}% of the screen changed · score {verification.score.toFixed(2)}
+        </p>
+      )}
+    </div>
+  );
+}
+
+function formatEta(ms: number): string {
+  const seconds = Math.round(ms / 1000);
+  if (seconds < 60) return `~${seconds}s left`;
+  return `~${Math.round(seconds / 60)}m left`;
+}
+
 function TaskCard({ task, isExpanded, onToggle, isCompleted }: TaskCardProps) {
-  const completedCount = task.subtasks.filter((st) => st.status === "Completed").length;
-  const progress = task.subtasks.length > 0 
-    ? (completedCount / task.subtasks.length) * 100 
+  // prefer the backend's counts - the subtask list only fills in as events arrive
+  const completedCount = task.progress?.completed ?? task.subtasks.filter((st) => st.status === "Completed").length;
+  const totalCount = task.progress?.total ?? task.subtasks.length;
+  const progress = totalCount > 0
+    ? (completedCount / totalCount) * 100
     : 0;
+  const eta = !isCompleted && task.progress?.eta_ms != null ? formatEta(task.progress.eta_ms) : null;
 
   return (
     <motion.div
@@ -280,8 +328,9 @@ function TaskCard({ task, isExpanded, onToggle, isCompleted }: TaskCardProps) {
               {task.status}
             </span>
             <span className="text-[10px] text-white/40">
-              {completedCount}/{task.subtasks.length} steps
+              {completedCount}/{totalCount} steps
             </span>
+            {eta && <span className="text-[10px] text-white/30">{eta}</span>}
           </div>
         </div>
 
@@ -294,6 +343,21 @@ function TaskCard({ task, isExpanded, onToggle, isCompleted }: TaskCardProps) {
             />
           </div>
         )}
+
+        {!isCompleted && (
+          <span
+            role="button"
+            onClick={(e) => {
+              // stops only this task - other swarm tasks keep running
+              e.stopPropagation();
+              invoke("cancel_swarm_task", { taskId: task.id }).catch(console.error);
+            }}
+            className="p-1 rounded-md text-white/40 hover:text-red-400 hover:bg-red-500/10 transition-colors"
+            title="Cancel task"
+          >
+            <Square size={12} />
+          </span>
+        )}
       </button>
 
       {/* Expanded Subtasks */}
@@ -307,22 +371,24 @@ function TaskCard({ task, isExpanded, onToggle, isCompleted }: TaskCardProps) {
           >
             <div className="p-2 space-y-1">
               {task.subtasks.map((subtask, idx) => (
-                <div
-                  key={subtask.id}
-                  className="flex items-center gap-2 px-2 py-1.5 rounded-lg bg-white/5"
-                >
-                  <span className="text-[10px] text-white/30 w-4">{idx + 1}</span>
-                  {statusIcons[subtask.status]}
-                  <span className="flex-1 text-xs text-white/60 truncate">
-                    {subtask.description.slice(0, 40)}
-                  </span>
-                  <span
-                    className={`text-[9px] px-1.5 py-0.5 rounded border ${
-                      agentColors[subtask.agent_type]
-                    }`}
-                  >
-                    {subtask.agent_type}
-                  </span>
+                <div key={subtask.id} className="rounded-lg bg-white/5">
+                  <div className="flex items-center gap-2 px-2 py-1.5">
+                    <span className="text-[10px] text-white/30 w-4">{idx + 1}</span>
+                    {statusIcons[subtask.status]}
+                    <span className="flex-1 text-xs text-white/60 truncate">
+                      {subtask.description.slice(0, 40)}
+                    </span>
+                    <span
+                      className={`text-[9px] px-1.5 py-0.5 rounded border ${
+                        agentColors[subtask.agent_type]
+                      }`}
+                    >
+                      {subtask.agent_type}
+                    </span>
+                  </div>
+                  {subtask.verification?.before_screenshot && subtask.verification.after_screenshot && (
+                    <VerificationDiff verification={subtask.verification} />
+                  )}
                 </div>
               ))}
             </div>
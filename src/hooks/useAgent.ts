@@ -2,8 +2,8 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useEffect, useCallback } from "react";
 import { useAgentStore } from "../stores/agentStore";
-import { AgentUpdate } from "../types";
-import { queueAudio, playClickSound, playTypeSound, playDoneSound, playScreenshotSound, startAmbientSound, stopAmbientSound, pauseAmbientSound, resumeAmbientSound, setAudioEndCallback } from "../utils/audio";
+import { AgentUpdate, CreatedFile, DndStatus, PlanPreview, UsageSummary } from "../types";
+import { queueAudio, playClickSound, playTypeSound, playDoneSound, playScreenshotSound, startAmbientSound, stopAmbientSound, pauseAmbientSound, resumeAmbientSound, setAudioEndCallback, setSoundEffectsMuted } from "../utils/audio";
 import { formatToolMessage, ToolInput } from "../utils/toolFormat";
 
 type UnlistenFn = () => void;
@@ -15,6 +15,9 @@ const shouldAutoplayAudio = (() => {
   return !params.has("voice") && !params.has("border");
 })();
 
+// matches AGENT_BUSY_ERROR in run_guard.rs
+const AGENT_BUSY_ERROR = "Agent is already running";
+
 let listenersAttached = false;
 let listenersRefCount = 0;
 let unlistenPromises: Array<Promise<UnlistenFn>> = [];
@@ -57,12 +60,16 @@ function attachListeners() {
         s.addMessage({ role: "assistant", content: message, type: "thinking" });
         break;
 
-      case "response":
+      case "response": {
         s.clearStreamingText();
-        s.addMessage({ role: "assistant", content: message, type: "info" });
+        s.clearStreamingToolInput();
+        const model = (event.payload.action as unknown as { model?: string } | undefined)?.model;
+        s.addMessage({ role: "assistant", content: message, type: "info", model });
         break;
+      }
 
       case "tool":
+        s.clearStreamingToolInput();
         // centralized tool formatting
         if (tool_name && tool_input) {
           const formatted = formatToolMessage(tool_name, tool_input as ToolInput, { pending: true });
@@ -107,6 +114,7 @@ function attachListeners() {
 
       case "finished":
         s.setIsRunning(false);
+        s.clearStreamingToolInput();
         invoke("set_main_click_through", { ignore: false }).catch(() => {});
         invoke("hide_border_overlay").catch(() => {});
         // clear callback first, then stop ambient
@@ -118,8 +126,20 @@ function attachListeners() {
         }
         break;
 
+      case "time_limit":
+        // stopped like a soft stop, once the running tool finished
+        s.setIsRunning(false);
+        s.clearStreamingToolInput();
+        invoke("set_main_click_through", { ignore: false }).catch(() => {});
+        invoke("hide_border_overlay").catch(() => {});
+        setAudioEndCallback(null);
+        stopAmbientSound();
+        s.addMessage({ role: "assistant", content: message, type: "info" });
+        break;
+
       case "error":
         s.setIsRunning(false);
+        s.clearStreamingToolInput();
         invoke("set_main_click_through", { ignore: false }).catch(() => {});
         invoke("hide_border_overlay").catch(() => {});
         setAudioEndCallback(null);
@@ -127,9 +147,22 @@ function attachListeners() {
         s.addMessage({ role: "assistant", content: message, type: "error" });
         break;
 
-      case "bash_result":
-        s.updateLastBashWithResult(message, exit_code);
+      case "permission_revoked": {
+        // the run is paused, not stopped - it resumes on its own once access is back
+        const permission = (event.payload.action as unknown as { permission?: string } | undefined)?.permission;
+        s.addMessage({ role: "assistant", content: `🔒 ${message}`, type: "error" });
+        if (permission) {
+          invoke("open_permission_settings", { permission }).catch(() => {});
+        }
+        break;
+      }
+
+      case "bash_result": {
+        // action carries the shell's cwd after the command (persists across turns)
+        const cwd = (event.payload.action as unknown as { cwd?: string | null } | undefined)?.cwd ?? undefined;
+        s.updateLastBashWithResult(message, exit_code, cwd);
         break;
+      }
 
       case "browser_result":
         s.markLastActionComplete();
@@ -141,24 +174,71 @@ function attachListeners() {
         break;
 
       case "status":
-        // status updates from agent (swarm activation, progress, etc.)
+      case "research_cancelled":
+        // status updates from agent (swarm activation, progress, stopped research, etc.)
         s.addMessage({ role: "assistant", content: message, type: "info" });
         break;
 
-      case "skill":
-        // skill execution update (e.g., "Using skill: Open Chrome")
-        s.addMessage({ role: "assistant", content: `🎯 ${message}`, type: "info" });
+      case "usage":
+        // per-turn cost meter totals (includes prompt cache savings)
+        s.setUsage((event.payload.action as unknown as UsageSummary | undefined) ?? null);
+        break;
+
+      case "undo":
+        // the last computer action was reversed from the header button
+        s.addMessage({ role: "assistant", content: `↩️ ${message}`, type: "info" });
+        break;
+
+      case "model_switched":
+        // primary model kept failing, the run continues on a fallback
+        s.addMessage({ role: "assistant", content: `🔀 ${message}`, type: "info" });
+        break;
+
+      case "plan_preview": {
+        // dry run finished: show the proposed steps with an approve button
+        const plan = event.payload.action as unknown as Omit<PlanPreview, "mode"> | undefined;
+        if (plan) {
+          s.addMessage({ role: "assistant", content: message, type: "plan", plan: { ...plan, mode } });
+        }
+        break;
+      }
+
+      case "busy":
+        // a run is already active - this request was refused rather than raced against it
+        s.setIsRunning(true);
+        s.addMessage({ role: "assistant", content: `⏳ ${message}, stop it or wait for it to finish`, type: "info" });
+        break;
+
+      case "skill": {
+        // skill execution result - rated from the chat so the skill library learns from it
+        const run = event.payload.action as unknown as { skill_id?: string; skill?: string; success?: boolean } | undefined;
+        if (run?.skill_id) {
+          s.addMessage({
+            role: "assistant",
+            content: message,
+            type: "skill",
+            skillRun: { skillId: run.skill_id, name: run.skill ?? tool_name ?? "", success: run.success ?? true },
+            screenshot,
+          });
+        } else {
+          s.addMessage({ role: "assistant", content: `🎯 ${message}`, type: "info" });
+        }
         break;
+      }
     }
   });
 
-  const unlistenStreamPromise = listen<{ type: string; text?: string }>("agent-stream", (event) => {
-    const { type, text } = event.payload;
+  const unlistenStreamPromise = listen<{ type: string; text?: string; name?: string }>("agent-stream", (event) => {
+    const { type, text, name } = event.payload;
     const s = store();
     if (type === "thinking_delta" && text) {
       s.appendStreamingThinking(text);
     } else if (type === "text_delta" && text) {
       s.appendStreamingText(text);
+    } else if (type === "tool_start" && name) {
+      s.startStreamingToolInput(name);
+    } else if (type === "tool_input_delta" && text) {
+      s.appendStreamingToolInput(text);
     }
   });
 
@@ -182,12 +262,25 @@ function attachListeners() {
     store().setVoiceMode(event.payload);
   });
 
+  const unlistenFileCreatedPromise = listen<CreatedFile>("agent:file_created", (event) => {
+    store().addMessage({ role: "assistant", content: event.payload.name, type: "file", file: event.payload });
+  });
+
+  invoke<DndStatus>("get_do_not_disturb")
+    .then((status) => setSoundEffectsMuted(status.active))
+    .catch((e) => console.error("Do not disturb load failed:", e));
+  const unlistenDndPromise = listen<DndStatus>("dnd:changed", (event) => {
+    setSoundEffectsMuted(event.payload.active);
+  });
+
   unlistenPromises = [
     unlistenPromise,
     unlistenStreamPromise,
     unlistenConvIdPromise,
     unlistenSpeakPromise,
     unlistenVoiceModePromise,
+    unlistenFileCreatedPromise,
+    unlistenDndPromise,
   ];
 }
 
@@ -230,7 +323,10 @@ export function useAgent() {
     };
   }, []);
 
-  const submit = useCallback(async (overrideText?: string, contextScreenshot?: string, overrideMode?: string) => {
+  // dryRun: only plan the request (plan_preview) - nothing is executed until the plan is approved
+  // chatOnly: answer without tools (spotlight); the backend still hands off to the agent if needed
+  // contextSnapshot: browser help hotkey's a11y snapshot, sent alongside the page screenshot
+  const submit = useCallback(async (overrideText?: string, contextScreenshot?: string, overrideMode?: string, dryRun = false, chatOnly = false, contextSnapshot?: string) => {
     const text = (overrideText ?? inputText).trim();
     // use fresh isRunning to avoid stale closure
     const currentIsRunning = useAgentStore.getState().isRunning;
@@ -254,9 +350,14 @@ export function useAgent() {
     const currentVoiceMode = useAgentStore.getState().voiceMode;
 
     try {
-      console.log("[useAgent] invoking run_agent:", { text: text.slice(0, 50), model: selectedModel, mode, voiceMode: currentVoiceMode, conversationId });
-      await invoke("run_agent", { instructions: text, model: selectedModel, mode, voiceMode: currentVoiceMode, history, contextScreenshot: contextScreenshot ?? null, conversationId });
+      console.log("[useAgent] invoking run_agent:", { text: text.slice(0, 50), model: selectedModel, mode, voiceMode: currentVoiceMode, conversationId, dryRun, chatOnly });
+      await invoke("run_agent", { instructions: text, model: selectedModel, mode, voiceMode: currentVoiceMode, history, contextScreenshot: contextScreenshot ?? null, contextSnapshot: contextSnapshot ?? null, conversationId, dryRun, chatOnly });
     } catch (error) {
+      // refused because a run is active: the "busy" update already said so, and it's still running
+      if (String(error) === AGENT_BUSY_ERROR) {
+        if (!overrideText) setInputText(text);
+        return;
+      }
       // on early failure, show the user message so they know what failed
       addMessage({ role: "user", content: text });
       addMessage({ role: "assistant", content: String(error), type: "error" });
@@ -275,6 +376,16 @@ export function useAgent() {
     }
   }, [setIsRunning, addMessage]);
 
+  // let the running tool finish; "finished" ends the run once its result is saved
+  const softStop = useCallback(async () => {
+    try {
+      await invoke("stop_after_tool");
+      addMessage({ role: "assistant", content: "Stopping after the current step…", type: "info" });
+    } catch (e) {
+      console.error(e);
+    }
+  }, [addMessage]);
+
   const toggle = useCallback(() => {
     if (isRunning) {
       stop();
@@ -283,5 +394,5 @@ export function useAgent() {
     }
   }, [isRunning, stop, submit]);
 
-  return { submit, stop, toggle };
+  return { submit, stop, softStop, toggle };
 }
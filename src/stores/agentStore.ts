@@ -14,6 +14,8 @@ function toPastTense(text: string): string {
     [/^Pressing key:/, "Pressed key:"],
     [/^Scrolling/, "Scrolled"],
     [/^Waiting$/, "Waited"],
+    [/^Zooming to/, "Zoomed to"],
+    [/^Waiting for the screen to settle$/, "Waited for the screen to settle"],
     [/^\$ /, "$ "],  // bash commands stay the same
     // browser mode actions
     [/^Taking snapshot$/, "Took snapshot"],
@@ -59,7 +61,9 @@ export const useAgentStore = create<AgentState>((set) => ({
   voiceMode: false,
   streamingText: "",
   streamingThinking: "",
+  streamingToolInput: null,
   conversationId: null,
+  usage: null,
 
   setIsRunning: (running) => set({ isRunning: running }),
 
@@ -124,12 +128,12 @@ export const useAgentStore = create<AgentState>((set) => ({
       return { messages };
     }),
 
-  updateLastBashWithResult: (output, exitCode) =>
+  updateLastBashWithResult: (output, exitCode, cwd) =>
     set((state) => {
       const messages = [...state.messages];
       for (let i = messages.length - 1; i >= 0; i--) {
         if (messages[i].type === "bash" && messages[i].pending) {
-          messages[i] = { ...messages[i], pending: false, bashOutput: output, exitCode };
+          messages[i] = { ...messages[i], pending: false, bashOutput: output, exitCode, bashCwd: cwd };
           break;
         }
       }
@@ -146,7 +150,7 @@ export const useAgentStore = create<AgentState>((set) => ({
 
   setVoiceMode: (voiceMode) => set({ voiceMode }),
 
-  clearMessages: () => set({ messages: [], conversationId: null, streamingText: "", streamingThinking: "", voiceMode: false }),
+  clearMessages: () => set({ messages: [], conversationId: null, streamingText: "", streamingThinking: "", streamingToolInput: null, voiceMode: false, usage: null }),
 
   setMessages: (messages) => set({ messages }),
 
@@ -162,5 +166,17 @@ export const useAgentStore = create<AgentState>((set) => ({
 
   clearStreamingThinking: () => set({ streamingThinking: "" }),
 
+  startStreamingToolInput: (name) => set({ streamingToolInput: { name, json: "" } }),
+
+  appendStreamingToolInput: (json) => set((state) => ({
+    streamingToolInput: state.streamingToolInput
+      ? { ...state.streamingToolInput, json: state.streamingToolInput.json + json }
+      : null,
+  })),
+
+  clearStreamingToolInput: () => set({ streamingToolInput: null }),
+
   setConversationId: (id) => set({ conversationId: id }),
+
+  setUsage: (usage) => set({ usage }),
 }));
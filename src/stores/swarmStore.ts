@@ -136,6 +136,7 @@ export const useSwarmStore = create<SwarmState>((set, get) => ({
         if (event.subtask_id) {
           updateSubtask(event.task_id, event.subtask_id, {
             status: event.passed ? "Completed" : "NeedsRetry",
+            verification: event.verification,
           });
         }
         break;
@@ -150,6 +151,38 @@ export const useSwarmStore = create<SwarmState>((set, get) => ({
         break;
       }
       
+      case "progress": {
+        if (event.progress) {
+          updateTask(event.task_id, { progress: event.progress });
+        }
+        break;
+      }
+
+      case "subtask_status": {
+        // retry/verification transitions - may arrive before subtask_started
+        const task = tasks.find((t) => t.id === event.task_id);
+        if (!task || !event.subtask_id || !event.status) break;
+        if (task.subtasks.some((st) => st.id === event.subtask_id)) {
+          updateSubtask(event.task_id, event.subtask_id, { status: event.status });
+        } else {
+          updateTask(event.task_id, {
+            subtasks: [
+              ...task.subtasks,
+              {
+                id: event.subtask_id,
+                description: event.description || "Subtask",
+                agent_type: "Executor",
+                status: event.status,
+                dependencies: [],
+                retry_count: 0,
+                max_retries: 3,
+              },
+            ],
+          });
+        }
+        break;
+      }
+
       case "task_completed": {
         updateTask(event.task_id, {
           status: event.success ? "Completed" : "Failed",
@@ -1,5 +1,5 @@
 export interface AgentUpdate {
-  update_type: "started" | "thinking" | "response" | "action" | "screenshot" | "finished" | "error" | "bash_result" | "user_message" | "browser_result" | "web_result" | "tool" | "skill" | "status";
+  update_type: "started" | "thinking" | "response" | "action" | "screenshot" | "finished" | "error" | "bash_result" | "user_message" | "browser_result" | "web_result" | "tool" | "skill" | "status" | "plan_preview" | "model_switched" | "usage" | "research_cancelled" | "undo" | "busy" | "permission_revoked" | "time_limit";
   message: string;
   tool_name?: string;
   tool_input?: Record<string, unknown>;
@@ -10,6 +10,95 @@ export interface AgentUpdate {
   mode?: "computer" | "browser";
 }
 
+// file written by the python tool (agent:file_created)
+export interface CreatedFile {
+  path: string;
+  name: string;
+  size_bytes: number;
+  mime_type: string;
+}
+
+// steps proposed by a dry run (plan_preview), approved by re-running the same instructions
+export interface PlanPreview {
+  instructions: string;
+  steps: string[];
+  preamble?: string | null; // custom instructions block added to the system prompt
+  mode?: AgentMode;
+}
+
+// a matched skill ran instead of the agent loop ("skill" updates carrying skill_id)
+export interface SkillRun {
+  skillId: string;
+  name: string;
+  success: boolean;
+}
+
+// final assistant text of a conversation (get_last_response), empty before the first reply
+export interface LastResponse {
+  text: string;
+  codeBlocks: { language: string | null; code: string }[];
+}
+
+// result of compact_conversation
+export interface CompactionReport {
+  compactedMessages: number;
+  keptMessages: number;
+  tokensBefore: number;
+  tokensAfter: number;
+  archivePath: string;
+}
+
+// one recorded tool call, as listed by replay_conversation
+export interface ReplayStep {
+  index: number;
+  tool: string;
+  summary: string;
+  input: Record<string, unknown>;
+  // false for looks (screenshots, zooms) and tools replay doesn't drive
+  replayed: boolean;
+}
+
+// running token / cost totals for the current conversation ("usage" updates)
+export interface UsageSummary {
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheWriteTokens: number;
+  costUsd: number;
+  savedUsd: number;
+}
+
+// do not disturb state (get_do_not_disturb, "dnd:changed")
+export interface DndStatus {
+  enabled: boolean;
+  silenceSpeech: boolean;
+  whileSharing: boolean;
+  // muted right now - the switch, or screen sharing with whileSharing on
+  active: boolean;
+}
+
+// test_microphone: what a couple of seconds of listening to the default input found
+export interface MicTestReport {
+  permission: "granted" | "denied" | "notAsked" | "notNeeded";
+  device: string | null;
+  sampleRate: number | null;
+  peak: number;
+  rms: number;
+  silent: boolean;
+  error: string | null;
+}
+
+// get_usage_stats: spend and activity across conversations in a date range
+export interface UsageStats {
+  totals: UsageSummary;
+  // biggest spender first
+  byModel: { model: string; turns: number; usage: UsageSummary }[];
+  conversations: number;
+  runs: number;
+  averageRunMs: number | null;
+  toolCalls: Record<string, number>;
+}
+
 export interface ComputerAction {
   action: string;
   coordinate?: [number, number];
@@ -24,13 +113,18 @@ export interface ChatMessage {
   role: "user" | "assistant";
   content: string;
   timestamp: Date;
-  type?: "thinking" | "action" | "error" | "info" | "bash" | "speak";
+  type?: "thinking" | "action" | "error" | "info" | "bash" | "speak" | "file" | "plan" | "skill";
   audioData?: string; // base64 audio for speak messages
+  file?: CreatedFile; // generated artifact for file messages
+  plan?: PlanPreview; // proposed steps for plan messages (dry run)
+  skillRun?: SkillRun; // skill that handled the request, for thumbs up/down
   action?: ComputerAction;
   screenshot?: string;
   pending?: boolean;
   bashOutput?: string;
   exitCode?: number;
+  bashCwd?: string;
+  model?: string; // model that wrote this response
 }
 
 export type ModelId = "claude-haiku-4-5-20251001" | "claude-sonnet-4-5" | "claude-opus-4-5" | "claude-opus-4-6";
@@ -47,6 +141,7 @@ export interface ConversationMeta {
   message_count: number;
   total_input_tokens: number;
   total_output_tokens: number;
+  is_pinned?: boolean;
 }
 
 // anthropic api content block format
@@ -81,6 +176,17 @@ export interface Conversation {
   total_input_tokens: number;
   total_output_tokens: number;
   voice_mode: boolean;
+  model_changes?: ModelChange[];
+  is_pinned?: boolean;
+  run_in_progress?: boolean;
+}
+
+// the conversation's model changed before message_index (mid-thread switch or fallback)
+export interface ModelChange {
+  message_index: number;
+  from: string;
+  to: string;
+  timestamp: number;
 }
 
 export interface AgentState {
@@ -93,12 +199,15 @@ export interface AgentState {
   voiceMode: boolean;
   streamingText: string;
   streamingThinking: string;
+  // tool call whose input JSON is still streaming in (raw, usually not yet valid JSON)
+  streamingToolInput: { name: string; json: string } | null;
   conversationId: string | null;
+  usage: UsageSummary | null;
 
   setIsRunning: (running: boolean) => void;
   addMessage: (msg: Omit<ChatMessage, "id" | "timestamp">) => void;
   markLastActionComplete: (screenshot?: string) => void;
-  updateLastBashWithResult: (output: string, exitCode?: number) => void;
+  updateLastBashWithResult: (output: string, exitCode?: number, cwd?: string) => void;
   setApiKeySet: (set: boolean) => void;
   setInputText: (text: string) => void;
   setSelectedModel: (model: ModelId) => void;
@@ -110,7 +219,11 @@ export interface AgentState {
   clearStreamingText: () => void;
   appendStreamingThinking: (text: string) => void;
   clearStreamingThinking: () => void;
+  startStreamingToolInput: (name: string) => void;
+  appendStreamingToolInput: (json: string) => void;
+  clearStreamingToolInput: () => void;
   setConversationId: (id: string | null) => void;
+  setUsage: (usage: UsageSummary | null) => void;
 }
 
 // Agent Swarm Types
@@ -128,6 +241,16 @@ export interface SwarmSubtask {
   max_retries: number;
   output?: string;
   error?: string;
+  verification?: SwarmVerification;
+}
+
+// verifier verdict; screenshots are base64 JPEGs from before/after the subtask
+export interface SwarmVerification {
+  passed: boolean;
+  score: number;
+  pixel_diff?: number | null;
+  before_screenshot?: string | null;
+  after_screenshot?: string | null;
 }
 
 export interface SwarmTask {
@@ -137,10 +260,19 @@ export interface SwarmTask {
   subtasks: SwarmSubtask[];
   created_at: string;
   completed_at?: string;
+  progress?: SwarmProgress;
+}
+
+// periodic counts from the backend poller; eta_ms is null without planner estimates
+export interface SwarmProgress {
+  completed: number;
+  failed: number;
+  total: number;
+  eta_ms: number | null;
 }
 
 export interface SwarmEvent {
-  type: "task_started" | "subtask_started" | "subtask_completed" | "subtask_failed" | "verification" | "recovery" | "task_completed";
+  type: "task_started" | "subtask_started" | "subtask_completed" | "subtask_failed" | "verification" | "recovery" | "task_completed" | "progress" | "subtask_status";
   task_id: string;
   subtask_id?: string;
   agent?: AgentType;
@@ -151,4 +283,7 @@ export interface SwarmEvent {
   score?: number;
   strategy?: string;
   success?: boolean;
+  status?: SwarmSubtaskStatus;
+  progress?: SwarmProgress;
+  verification?: SwarmVerification;
 }
@@ -3,6 +3,13 @@
 // Web Audio context for generated sounds
 let audioContext: AudioContext | null = null;
 
+// do not disturb - generated sound effects stay silent while it's on
+let effectsMuted = false;
+
+export function setSoundEffectsMuted(muted: boolean) {
+  effectsMuted = muted;
+}
+
 function getAudioContext(): AudioContext {
   if (!audioContext) {
     audioContext = new AudioContext();
@@ -16,6 +23,7 @@ function getAudioContext(): AudioContext {
 
 // iOS-style subtle pop/click - two layered tones for depth
 export function playClickSound() {
+  if (effectsMuted) return;
   try {
     const ctx = getAudioContext();
 
@@ -51,6 +59,7 @@ export function playClickSound() {
 
 // iOS keyboard-style soft tick
 export function playTypeSound() {
+  if (effectsMuted) return;
   try {
     const ctx = getAudioContext();
 
@@ -83,6 +92,7 @@ export function playTypeSound() {
 
 // soft completion chime
 export function playDoneSound() {
+  if (effectsMuted) return;
   const ctx = getAudioContext();
 
   [523.25, 659.25, 783.99].forEach((freq, i) => {
@@ -110,6 +120,7 @@ let ambientInterval: ReturnType<typeof setInterval> | null = null;
 let ambientPaused = false;
 
 function playBloop() {
+  if (effectsMuted) return;
   if (ambientPaused) return;
 
   const ctx = getAudioContext();
@@ -164,6 +175,7 @@ export function resumeAmbientSound() {
 
 // iOS-style camera shutter - crisp snap with tonal body
 export function playScreenshotSound() {
+  if (effectsMuted) return;
   try {
     const ctx = getAudioContext();
 
@@ -0,0 +1,5 @@
+// screenshots arrive as base64 jpeg, inline charts and png help captures as base64 png
+export function imageDataUrl(base64: string): string {
+  const mediaType = base64.startsWith("iVBORw0KGgo") ? "image/png" : "image/jpeg";
+  return `data:${mediaType};base64,${base64}`;
+}
@@ -0,0 +1,12 @@
+// display names for model ids, used for the per-message model caption
+
+const MODEL_LABELS: Record<string, string> = {
+  "claude-haiku-4-5-20251001": "Haiku 4.5",
+  "claude-sonnet-4-5": "Sonnet 4.5",
+  "claude-opus-4-5": "Opus 4.5",
+  "claude-opus-4-6": "Opus 4.6",
+};
+
+export function modelLabel(model: string): string {
+  return MODEL_LABELS[model] ?? model;
+}
@@ -14,10 +14,12 @@ export interface ToolInput {
   // bash tool
   command?: string;
   // speak tool (text reused)
-  // consolidated browser tools (see_page, page_action, browser_navigate)
+  // consolidated browser tools (see_page, page_action, browser_navigate, browser_download, autofill_login)
   // see_page
   screenshot?: boolean;
   list_tabs?: boolean;
+  read_page?: boolean;
+  max_chars?: number;
   // page_action
   click?: string;
   double_click?: string;
@@ -27,7 +29,9 @@ export interface ToolInput {
   press_key?: string;
   scroll?: string;
   scroll_pixels?: number;
+  scroll_to?: string;
   fill_form?: Array<{ element: string; text: string }>;
+  upload_file?: { element: string; path: string };
   dialog?: string;
   dialog_text?: string;
   // browser_navigate
@@ -41,6 +45,8 @@ export interface ToolInput {
   close_tab?: number;
   wait_for_text?: string;
   wait_timeout_ms?: number;
+  // browser_download (click and url reused)
+  timeout_ms?: number;
   // legacy browser tools (for backwards compat with old conversations)
   uid?: string;
   value?: string;
@@ -53,6 +59,15 @@ export interface ToolInput {
   direction?: string; // legacy scroll direction
   // web search/fetch tools (server-side)
   query?: string;
+  // focus_window, set_window_bounds, snap_window
+  title_or_id?: string;
+  x?: number;
+  y?: number;
+  width?: number;
+  height?: number;
+  position?: string;
+  // launch
+  targets?: string[];
 }
 
 interface FormatOptions {
@@ -94,6 +109,59 @@ export function formatToolMessage(
         type: "action",
       };
     }
+    case "zoom_to_text":
+      return {
+        content: `${pending ? "Zooming to" : "Zoomed to"} "${input.query || ""}"`,
+        type: "action",
+      };
+    case "click_text":
+      return {
+        content: `${pending ? "Clicking" : "Clicked"} "${input.query || ""}"`,
+        type: "action",
+      };
+    case "wait_for_change":
+      return {
+        content: pending ? "Waiting for the screen to settle" : "Waited for the screen to settle",
+        type: "action",
+      };
+    case "list_windows":
+      return {
+        content: pending ? "Listing open windows" : "Listed open windows",
+        type: "action",
+      };
+    case "launch":
+      return {
+        content: `${pending ? "Opening" : "Opened"} ${(input.targets || []).join(", ")}`,
+        type: "action",
+      };
+    case "get_active_app":
+      return {
+        content: pending ? "Checking the active app" : "Checked the active app",
+        type: "action",
+      };
+    case "observe_ui":
+      return {
+        content: pending ? "Reading the window's controls" : "Read the window's controls",
+        type: "action",
+      };
+    case "focus_window":
+      return {
+        content: `${pending ? "Focusing" : "Focused"} "${input.title_or_id || ""}"`,
+        type: "action",
+      };
+    case "set_window_bounds":
+      return {
+        content: `${pending ? "Moving" : "Moved"} "${input.title_or_id || ""}" to ${input.width}×${input.height} at (${input.x}, ${input.y})`,
+        type: "action",
+      };
+    case "snap_window":
+      return {
+        content:
+          input.position === "maximize"
+            ? `${pending ? "Maximizing" : "Maximized"} "${input.title_or_id || ""}"`
+            : `${pending ? "Snapping" : "Snapped"} "${input.title_or_id || ""}" to the ${input.position}`,
+        type: "action",
+      };
     case "web_fetch": {
       const url = input.url;
       if (url) {
@@ -207,6 +275,9 @@ function formatBrowserTool(name: string, input: ToolInput, pending: boolean): st
       if (input.list_tabs) {
         return pending ? "Listing tabs" : "Listed tabs";
       }
+      if (input.read_page) {
+        return pending ? "Reading page" : "Read page";
+      }
       return pending ? "Getting page elements" : "Got page elements";
     }
 
@@ -237,10 +308,17 @@ function formatBrowserTool(name: string, input: ToolInput, pending: boolean): st
       if (input.scroll) {
         return `${pending ? "Scrolling" : "Scrolled"} ${input.scroll}`;
       }
+      if (input.scroll_to) {
+        return pending ? "Scrolling to element" : "Scrolled to element";
+      }
       if (input.fill_form) {
         const count = input.fill_form.length;
         return `${pending ? "Filling" : "Filled"} ${count} field${count !== 1 ? "s" : ""}`;
       }
+      if (input.upload_file) {
+        const name = input.upload_file.path.split(/[\\/]/).pop() || input.upload_file.path;
+        return `${pending ? "Uploading" : "Uploaded"} ${name}`;
+      }
       if (input.dialog) {
         return input.dialog === "accept"
           ? (pending ? "Accepting dialog" : "Accepted dialog")
@@ -280,6 +358,16 @@ function formatBrowserTool(name: string, input: ToolInput, pending: boolean): st
       return pending ? "Navigating" : "Navigated";
     }
 
+    case "browser_download": {
+      if (input.url) {
+        return `${pending ? "Downloading" : "Downloaded"} ||${input.url}||`;
+      }
+      return pending ? "Downloading file" : "Downloaded file";
+    }
+
+    case "autofill_login":
+      return pending ? "Filling saved login" : "Filled saved login";
+
     // legacy browser tools (for old conversations)
     case "take_snapshot":
       return pending ? "Getting page elements" : "Got page elements";